@@ -0,0 +1,32 @@
+#![allow(unused_imports)]
+
+use pax_kit::*;
+
+mod signup_form;
+use crate::signup_form::{SignupForm, SignupFormHandle};
+
+#[pax]
+#[main]
+#[file("lib.pax")]
+pub struct Example {
+    pub status: Property<String>,
+}
+
+impl Example {
+    pub fn reset_form(&mut self, ctx: &NodeContext, _args: Event<ButtonClick>) {
+        match ctx.get_component_handle::<SignupForm>("signup_form") {
+            Ok(form) => {
+                form.reset();
+                self.status.set("Form reset".to_string());
+            }
+            Err(e) => self.status.set(e),
+        }
+    }
+
+    pub fn fill_form(&mut self, ctx: &NodeContext, _args: Event<ButtonClick>) {
+        if let Ok(form) = ctx.get_component_handle::<SignupForm>("signup_form") {
+            form.fill("Ada Lovelace".to_string(), "ada@example.com".to_string());
+            self.status.set("Form filled in".to_string());
+        }
+    }
+}
@@ -0,0 +1,26 @@
+#![allow(unused_imports)]
+
+use pax_kit::*;
+
+#[pax]
+#[file("signup_form.pax")]
+pub struct SignupForm {
+    pub name: Property<String>,
+    pub email: Property<String>,
+}
+
+#[pax]
+impl SignupForm {
+    /// Clears the form, called by the parent through a `ComponentHandle<SignupForm>`
+    #[pax(public)]
+    pub fn reset(&mut self, _ctx: &NodeContext) {
+        self.name.set(String::new());
+        self.email.set(String::new());
+    }
+
+    #[pax(public)]
+    pub fn fill(&mut self, name: String, email: String) {
+        self.name.set(name);
+        self.email.set(email);
+    }
+}
@@ -0,0 +1,23 @@
+#![allow(unused_imports)]
+
+use pax_kit::*;
+
+mod particle_field;
+use crate::particle_field::ParticleField;
+
+#[pax]
+#[main]
+#[file("lib.pax")]
+pub struct Example {
+    pub density: Property<u32>,
+}
+
+impl Example {
+    pub fn handle_mount(&mut self, _ctx: &NodeContext) {
+        self.density.set(200);
+    }
+
+    pub fn add_particles(&mut self, _ctx: &NodeContext, _args: Event<Click>) {
+        self.density.set(self.density.get() + 100);
+    }
+}
@@ -0,0 +1,111 @@
+use kurbo::{Circle, Shape};
+use pax_kit::pax_engine::api::{Layer, RenderContext};
+use pax_kit::pax_engine::math::Point2;
+use pax_kit::pax_engine::NodeLocal;
+use pax_kit::pax_engine::pax_runtime::{
+    BaseInstance, ExpandedNode, InstanceFlags, InstanceNode, InstantiationArgs, RuntimeContext,
+};
+use pax_kit::*;
+use std::rc::Rc;
+
+/// A round field of `density` particles, drawn straight onto the canvas.  Declared outside of
+/// pax-std: `#[primitive]` points at `ParticleFieldInstance`, relative to this module
+#[pax]
+#[primitive("self::ParticleFieldInstance")]
+pub struct ParticleField {
+    pub density: Property<u32>,
+    pub color: Property<Color>,
+}
+
+pub struct ParticleFieldInstance {
+    base: BaseInstance,
+}
+
+impl InstanceNode for ParticleFieldInstance {
+    fn instantiate(args: InstantiationArgs) -> Rc<Self>
+    where
+        Self: Sized,
+    {
+        Rc::new(ParticleFieldInstance {
+            base: BaseInstance::new(
+                args,
+                InstanceFlags {
+                    invisible_to_slot: false,
+                    invisible_to_raycasting: false,
+                    layer: Layer::Canvas,
+                    is_component: false,
+                },
+            ),
+        })
+    }
+
+    fn render(
+        &self,
+        expanded_node: &ExpandedNode,
+        _context: &Rc<RuntimeContext>,
+        rc: &mut dyn RenderContext,
+    ) {
+        let tab = expanded_node.transform_and_bounds.get();
+        let (width, height) = tab.bounds;
+        let layer_id = format!("{}", expanded_node.occlusion.get().occlusion_layer_id);
+        expanded_node.with_properties_unwrapped(|properties: &mut ParticleField| {
+            let color = properties.color.get().to_piet_color();
+            let mut rng = Lcg(u64::from(properties.density.get()) + 1);
+            for _ in 0..properties.density.get() {
+                // uniformly distributed within the ellipse inscribed in the bounds
+                let radius = rng.next().sqrt() / 2.0;
+                let angle = rng.next() * std::f64::consts::TAU;
+                let center = (
+                    width * (0.5 + radius * angle.cos()),
+                    height * (0.5 + radius * angle.sin()),
+                );
+                let particle = Circle::new(center, 1.0 + 2.0 * rng.next()).to_path(0.1);
+                let transformed = Into::<kurbo::Affine>::into(tab.transform) * particle;
+                rc.fill(&layer_id, transformed, &color.clone().into());
+            }
+        });
+    }
+
+    fn hit_test(&self, expanded_node: &ExpandedNode, point: Point2<NodeLocal>) -> bool {
+        // only the round field is clickable, not the corners of its bounds
+        let (width, height) = expanded_node.transform_and_bounds.get().bounds;
+        let dx = point.x / width - 0.5;
+        let dy = point.y / height - 0.5;
+        dx * dx + dy * dy <= 0.25
+    }
+
+    fn resolve_debug(
+        &self,
+        f: &mut std::fmt::Formatter,
+        expanded_node: Option<&ExpandedNode>,
+    ) -> std::fmt::Result {
+        match expanded_node {
+            Some(expanded_node) => {
+                expanded_node.with_properties_unwrapped(|p: &mut ParticleField| {
+                    f.debug_struct("ParticleField")
+                        .field("density", &p.density.get())
+                        .finish()
+                })
+            }
+            None => f.debug_struct("ParticleField").finish_non_exhaustive(),
+        }
+    }
+
+    fn base(&self) -> &BaseInstance {
+        &self.base
+    }
+}
+
+/// Deterministic pseudo-random numbers in `0..1`, so that particles keep their places from one
+/// frame to the next
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> f64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
@@ -14,13 +14,15 @@ use flexbuffers;
 use flexbuffers::DeserializationError;
 use serde::Serialize;
 
-use pax_runtime::{PaxEngine, Renderer};
+use pax_runtime::{ExpandedNodeIdentifier, PaxEngine, Renderer};
 
 //Re-export all native message types; used by Swift via FFI.
 //Note that any types exposed by pax_message must ALSO be added to `PaxCartridge.h`
 //in order to be visible to Swift
 pub use pax_message::*;
-use pax_runtime::api::{Click, Event, ModifierKey, MouseButton, MouseEventArgs, RenderContext};
+use pax_runtime::api::{
+    borrow, Click, Event, ModifierKey, MouseButton, MouseEventArgs, RenderContext, VideoEnded,
+};
 
 /// Container data structure for PaxEngine, aggregated to support passing across C bridge
 #[repr(C)] //Exposed to Swift via PaxCartridge.h
@@ -87,6 +89,23 @@ pub extern "C" fn pax_interrupt(
                 &engine.runtime_context,
             );
         }
+        NativeInterrupt::VideoTimeUpdate(VideoTimeUpdateArgs { id, .. })
+        | NativeInterrupt::VideoPlayStateChange(VideoPlayStateChangeArgs { id, .. })
+        | NativeInterrupt::VideoMetadata(VideoMetadataArgs { id, .. })
+        | NativeInterrupt::VideoUnsupported(VideoUnsupportedArgs { id }) => {
+            if let Some(node) = engine.get_expanded_node(ExpandedNodeIdentifier(id)) {
+                borrow!(node.instance_node).handle_native_interrupt(&node, &interrupt);
+            }
+        }
+        NativeInterrupt::VideoEnded(args) => {
+            if let Some(node) = engine.get_expanded_node(ExpandedNodeIdentifier(args.id)) {
+                node.dispatch_video_ended(
+                    Event::new(VideoEnded {}),
+                    &engine.runtime_context.globals(),
+                    &engine.runtime_context,
+                );
+            }
+        }
         NativeInterrupt::Scrollbar(_args) => {}
         NativeInterrupt::Scroll(_args) => {}
         NativeInterrupt::Image(args) => match args {
@@ -101,6 +120,8 @@ pub extern "C" fn pax_interrupt(
             }
             ImageLoadInterruptArgs::Data(_) => {}
         },
+        NativeInterrupt::ViewportDprChange(args) => engine.set_device_pixel_ratio(args.dpr),
+        NativeInterrupt::ClipboardRead(text) => engine.runtime_context.resolve_clipboard_read(text),
         _ => {}
     }
 
@@ -2,7 +2,10 @@
 #![allow(non_snake_case)]
 
 use js_sys::Uint8Array;
-use pax_message::ImageLoadInterruptArgs;
+use pax_message::{
+    ChassisCapabilities, ImageLoadInterruptArgs, VideoMetadataArgs, VideoPlayStateChangeArgs,
+    VideoTimeUpdateArgs, VideoUnsupportedArgs,
+};
 use pax_runtime::api::borrow;
 use pax_runtime::api::math::Point2;
 use pax_runtime::api::use_RefCell;
@@ -10,6 +13,7 @@ use pax_runtime::api::ButtonClick;
 use pax_runtime::api::Platform;
 use pax_runtime::api::RenderContext;
 use pax_runtime::api::TextboxChange;
+use pax_runtime::api::VideoEnded;
 use pax_runtime::api::OS;
 use pax_runtime::DefinitionToInstanceTraverser;
 use pax_runtime_api::borrow_mut;
@@ -26,24 +30,32 @@ use web_sys::{window, HtmlCanvasElement};
 
 use piet_web::WebRenderContext;
 
+use pax_runtime::message_chunks::MessageChunks;
 use pax_runtime::{PaxEngine, Renderer};
 
 pub use {console_error_panic_hook, console_log};
 
 use pax_message::NativeInterrupt;
 use pax_runtime::api::{
-    Clap, Click, ContextMenu, DoubleClick, Drop, KeyDown, KeyPress, KeyUp, KeyboardEventArgs,
-    ModifierKey, MouseButton, MouseDown, MouseEventArgs, MouseMove, MouseUp, Touch, TouchEnd,
-    TouchMove, TouchStart, Wheel,
+    Clap, Click, ContextMenu, DoubleClick, Drop, KeyDown, KeyLocation, KeyPress, KeyUp,
+    KeyboardEventArgs, ModifierKey, MouseButton, MouseDown, MouseEventArgs, MouseMove, MouseUp,
+    Touch, TouchEnd, TouchMove, TouchStart, Wheel,
 };
 use serde_json;
 
 #[cfg(any(feature = "designtime", feature = "designer"))]
-use {pax_designtime::orm::ReloadType, pax_designtime::DesigntimeManager};
+use {
+    pax_designtime::messages::NodeBounds,
+    pax_designtime::orm::ReloadType,
+    pax_designtime::thumbnails::{ComponentThumbnail, ThumbnailRequest, THUMBNAIL_SIZE},
+    pax_designtime::DesigntimeManager,
+};
 
 const USERLAND_COMPONENT_ROOT: &str = "USERLAND_COMPONENT_ROOT";
 #[cfg(any(feature = "designtime", feature = "designer"))]
 const DESIGNER_COMPONENT_ROOT: &str = "DESIGNER_COMPONENT_ROOT";
+#[cfg(any(feature = "designtime", feature = "designer"))]
+const THUMBNAIL_COMPONENT_ROOT: &str = "THUMBNAIL_COMPONENT_ROOT";
 
 #[wasm_bindgen]
 pub fn wasm_memory() -> JsValue {
@@ -54,6 +66,7 @@ pub fn wasm_memory() -> JsValue {
 pub struct PaxChassisWeb {
     drawing_contexts: Renderer<WebRenderContext<'static>>,
     engine: Rc<RefCell<PaxEngine>>,
+    message_chunks: MessageChunks,
     #[cfg(any(feature = "designtime", feature = "designer"))]
     userland_definition_to_instance_traverser:
         Box<dyn pax_runtime::cartridge::DefinitionToInstanceTraverser>,
@@ -101,10 +114,12 @@ impl PaxChassisWeb {
             os_info,
             get_elapsed_millis,
         );
+        engine.set_device_pixel_ratio(window().unwrap().device_pixel_ratio());
         let engine_container: Rc<RefCell<PaxEngine>> = Rc::new(RefCell::new(engine));
         Self {
             engine: engine_container,
             drawing_contexts: Renderer::new(),
+            message_chunks: MessageChunks::default(),
             userland_definition_to_instance_traverser,
             designtime_manager,
             last_manifest_version_rendered: 0,
@@ -126,12 +141,14 @@ impl PaxChassisWeb {
             os_info,
             get_time,
         );
+        engine.set_device_pixel_ratio(window().unwrap().device_pixel_ratio());
 
         let engine_container: Rc<RefCell<PaxEngine>> = Rc::new(RefCell::new(engine));
 
         Self {
             engine: engine_container,
             drawing_contexts: Renderer::new(),
+            message_chunks: MessageChunks::default(),
         }
     }
 
@@ -186,6 +203,25 @@ impl PaxChassisWeb {
         self.drawing_contexts.remove_context(&id);
     }
 
+    /// Called once by the web interface on startup, with a serialized
+    /// [`pax_message::ChassisCapabilities`] declaring the optional features it supports
+    pub fn declare_capabilities(&mut self, capabilities: String) {
+        let capabilities: ChassisCapabilities = match serde_json::from_str(&capabilities) {
+            Ok(capabilities) => capabilities,
+            Err(e) => {
+                log::warn!("failed to parse chassis capabilities: {}", e);
+                return;
+            }
+        };
+        let engine = borrow!(self.engine);
+        engine
+            .runtime_context
+            .set_native_element_recycling(capabilities.native_element_recycling);
+        engine
+            .runtime_context
+            .set_native_element_ordering(capabilities.native_element_ordering);
+    }
+
     pub fn interrupt(
         &mut self,
         native_interrupt: String,
@@ -272,6 +308,33 @@ impl PaxChassisWeb {
                     false
                 }
             }
+            NativeInterrupt::VideoTimeUpdate(VideoTimeUpdateArgs { id, .. })
+            | NativeInterrupt::VideoPlayStateChange(VideoPlayStateChangeArgs { id, .. })
+            | NativeInterrupt::VideoMetadata(VideoMetadataArgs { id, .. })
+            | NativeInterrupt::VideoUnsupported(VideoUnsupportedArgs { id }) => {
+                if let Some(node) =
+                    engine.get_expanded_node(pax_runtime::ExpandedNodeIdentifier(*id))
+                {
+                    borrow!(node.instance_node).handle_native_interrupt(&node, &x);
+                }
+                false
+            }
+            NativeInterrupt::VideoEnded(args) => {
+                if let Some(node) =
+                    engine.get_expanded_node(pax_runtime::ExpandedNodeIdentifier(args.id))
+                {
+                    node.dispatch_video_ended(
+                        Event::new(VideoEnded {}),
+                        &globals,
+                        &engine.runtime_context,
+                    )
+                } else {
+                    log::warn!(
+                        "tried to dispatch event for video ended after node already removed"
+                    );
+                    false
+                }
+            }
             NativeInterrupt::FormTextboxInput(args) => {
                 if let Some(node) =
                     engine.get_expanded_node(pax_runtime::ExpandedNodeIdentifier(args.id))
@@ -326,6 +389,18 @@ impl PaxChassisWeb {
             }
 
             NativeInterrupt::AddedLayer(_args) => false,
+            NativeInterrupt::ViewportDprChange(args) => {
+                engine.set_device_pixel_ratio(args.dpr);
+                false
+            }
+            NativeInterrupt::ClipboardRead(text) => {
+                engine.runtime_context.resolve_clipboard_read(text.clone());
+                false
+            }
+            NativeInterrupt::MessageChunksConsumed(args) => {
+                self.message_chunks.acknowledge(args.frame_id);
+                false
+            }
             NativeInterrupt::Click(args) => {
                 let topmost_node = engine
                     .runtime_context
@@ -416,6 +491,7 @@ impl PaxChassisWeb {
                         key: args.key.clone(),
                         modifiers,
                         is_repeat: args.is_repeat,
+                        location: KeyLocation::from(&args.location),
                     },
                 };
                 engine.global_dispatch_key_down(args_key_down)
@@ -431,6 +507,7 @@ impl PaxChassisWeb {
                         key: args.key.clone(),
                         modifiers,
                         is_repeat: args.is_repeat,
+                        location: KeyLocation::from(&args.location),
                     },
                 };
                 engine.global_dispatch_key_up(args_key_up)
@@ -446,6 +523,7 @@ impl PaxChassisWeb {
                         key: args.key.clone(),
                         modifiers,
                         is_repeat: args.is_repeat,
+                        location: KeyLocation::from(&args.location),
                     },
                 };
                 engine.global_dispatch_key_press(args_key_press)
@@ -667,23 +745,109 @@ impl PaxChassisWeb {
             .expect("couldn't handle recv");
     }
 
+    /// Reads back layout from the engine for every node requested through the designtime
+    #[cfg(any(feature = "designtime", feature = "designer"))]
+    pub fn serve_node_bounds_requests(&mut self) {
+        let requests = borrow_mut!(self.designtime_manager).take_pending_bounds_requests();
+        if requests.is_empty() {
+            return;
+        }
+        let engine = borrow!(self.engine);
+        for uni in requests {
+            let bounds = engine
+                .get_transform_and_bounds_by_global_id(&uni)
+                .into_iter()
+                .map(|tab| NodeBounds {
+                    transform: tab.transform.coeffs(),
+                    bounds: tab.bounds,
+                })
+                .collect();
+            if let Err(e) = borrow_mut!(self.designtime_manager).set_node_bounds(uni, bounds) {
+                log::warn!("couldn't send node bounds: {e}");
+            }
+        }
+    }
+
+    /// Renders the component thumbnails requested through the designtime
+    #[cfg(any(feature = "designtime", feature = "designer"))]
+    pub fn serve_thumbnail_requests(&mut self) {
+        let requests = borrow_mut!(self.designtime_manager).take_pending_thumbnail_requests();
+        for request in requests {
+            match self.render_thumbnail(&request) {
+                Ok(thumbnail) => borrow_mut!(self.designtime_manager)
+                    .set_component_thumbnail(request.type_id, thumbnail),
+                Err(e) => log::warn!("couldn't render thumbnail of {}: {e}", request.type_id),
+            }
+        }
+    }
+
+    #[cfg(any(feature = "designtime", feature = "designer"))]
+    fn render_thumbnail(&self, request: &ThumbnailRequest) -> Result<ComponentThumbnail, String> {
+        let window = window().unwrap();
+        let (width, height) = THUMBNAIL_SIZE;
+        let canvas = window
+            .document()
+            .unwrap()
+            .create_element("canvas")
+            .map_err(|e| format!("{e:?}"))?
+            .dyn_into::<HtmlCanvasElement>()
+            .map_err(|e| format!("{e:?}"))?;
+        canvas.set_width(width as u32);
+        canvas.set_height(height as u32);
+        let context = canvas
+            .get_context("2d")
+            .map_err(|e| format!("{e:?}"))?
+            .ok_or("no 2d context")?
+            .dyn_into::<web_sys::CanvasRenderingContext2d>()
+            .map_err(|e| format!("{e:?}"))?;
+
+        let component = self
+            .userland_definition_to_instance_traverser
+            .get_component_root(&request.type_id, THUMBNAIL_COMPONENT_ROOT);
+        let mut renderer = Renderer::flattened(WebRenderContext::new(context.clone(), window));
+        borrow_mut!(self.engine).render_detached(
+            component,
+            (width as f64, height as f64),
+            &mut renderer as &mut dyn RenderContext,
+        );
+
+        let rgba = context
+            .get_image_data(0.0, 0.0, width as f64, height as f64)
+            .map_err(|e| format!("{e:?}"))?
+            .data()
+            .0;
+        Ok(ComponentThumbnail {
+            content_hash: request.content_hash,
+            width,
+            height,
+            rgba,
+        })
+    }
+
     #[cfg(any(feature = "designtime", feature = "designer"))]
     pub fn designtime_tick(&mut self) {
         self.handle_recv_designtime();
         self.update_userland_component();
+        self.serve_node_bounds_requests();
+        self.serve_thumbnail_requests();
     }
 
-    pub fn tick(&mut self) -> MemorySlice {
+    /// Queues the native messages of this tick, to be pulled with [`Self::next_message_chunk`]
+    pub fn tick(&mut self) {
         #[cfg(any(feature = "designtime", feature = "designer"))]
         self.designtime_tick();
 
-        let message_queue = borrow_mut!(self.engine).tick();
-
-        // Serialize data to a JSON string
-        let json_string = serde_json::to_string(&message_queue).unwrap();
+        let messages = borrow_mut!(self.engine).tick();
+        self.message_chunks.push_tick(messages);
+    }
 
-        // Convert the string into bytes
-        let bytes = json_string.as_bytes();
+    /// The next chunk of native messages, a JSON-serialized
+    /// [`pax_message::NativeMessageChunk`], or `undefined` if all chunks of the current frame
+    /// were pulled. The chassis acknowledges the frame with a `MessageChunksConsumed`
+    /// interrupt once it has applied its last chunk; until then, the messages of following
+    /// ticks are held back and coalesced.
+    pub fn next_message_chunk(&mut self) -> Option<MemorySlice> {
+        let bytes = self.message_chunks.next_chunk()?;
 
         // Allocate space in the WebAssembly memory
         let layout = std::alloc::Layout::from_size_align(bytes.len(), 1).unwrap();
@@ -694,10 +858,21 @@ impl PaxChassisWeb {
             std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, bytes.len());
         }
 
-        MemorySlice {
+        Some(MemorySlice {
             ptr: ptr as *const u8,
             len: bytes.len(),
-        }
+        })
+    }
+
+    /// Sets the upper bound of the serialized size of message chunks, from the next frame on
+    pub fn set_message_chunk_bytes(&mut self, bytes: usize) {
+        self.message_chunks.set_chunk_bytes(bytes);
+    }
+
+    /// Counters of the chunked message transfer, as a JSON-serialized
+    /// [`pax_runtime::message_chunks::MessageChunkStats`]
+    pub fn message_chunk_stats(&self) -> String {
+        serde_json::to_string(self.message_chunks.stats()).unwrap()
     }
 
     pub fn render(&mut self) {
@@ -68,3 +68,32 @@ fn get_user_agent() -> String {
 
     format!("{}/{} ({}; {})", TOOL_NAME, os, locale, arch)
 }
+
+/// Sends a snippet archive to the design server listening on `port`, which forwards it to the
+/// connected designer to be imported.  Returns the message of the server.
+pub fn import_snippet(port: u16, archive: Vec<u8>) -> Result<String, String> {
+    let url = format!("http://127.0.0.1:{}/import_snippet", port);
+    let client = reqwest::blocking::Client::new();
+    let response = client
+        .post(&url)
+        .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+        .body(archive)
+        .send()
+        .map_err(|_| {
+            format!(
+                "couldn't reach the design server at {}, is the project running with `pax-cli run`?",
+                url
+            )
+        })?;
+    let is_success = response.status().is_success();
+    let body = response.text().unwrap_or_default();
+    let message = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|json| json["message"].as_str().map(String::from))
+        .unwrap_or(body);
+    if is_success {
+        Ok(message)
+    } else {
+        Err(message)
+    }
+}
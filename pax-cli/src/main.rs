@@ -1,13 +1,18 @@
 use clap::{crate_version, App, AppSettings, Arg, ArgMatches};
 use color_eyre::config::HookBuilder;
 use colored::{ColoredString, Colorize};
-use std::io::Write;
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use std::{process, thread};
 
-use pax_compiler::{CreateContext, RunContext, RunTarget};
+use pax_compiler::{
+    CancellationToken, CreateContext, ProgressMode, ProgressReporter, ProjectTemplate, RunContext,
+    RunTarget, Verbosity,
+};
+use pax_runtime_api::PaxValue;
 extern crate pax_language_server;
 
 mod http;
@@ -24,6 +29,8 @@ fn main() -> Result<(), Report> {
 
     //Shared state to store child processes keyed by static unique string IDs, for cleanup tracking
     let process_child_ids: Arc<Mutex<Vec<u64>>> = Arc::new(Mutex::new(vec![]));
+    // Cancels the build in progress on interrupt, so that it stops spawning build steps
+    let cancellation = CancellationToken::new();
     // Shared state to store the new version info if available.
     let new_version_info = Arc::new(Mutex::new(None));
 
@@ -44,7 +51,23 @@ fn main() -> Result<(), Report> {
     let ARG_VERBOSE = Arg::with_name("verbose")
         .short("v")
         .long("verbose")
-        .takes_value(false);
+        .takes_value(false)
+        .conflicts_with("verbosity")
+        .help("Shorthand for `--verbosity verbose`");
+
+    #[allow(non_snake_case)]
+    let ARG_VERBOSITY = Arg::with_name("verbosity")
+        .long("verbosity")
+        .takes_value(true)
+        .possible_values(&["quiet", "normal", "verbose", "trace"])
+        .help("How much to print: `quiet` prints only errors and warnings, `verbose` adds cargo's full output, and `trace` a summary of the manifest and generated code");
+
+    #[allow(non_snake_case)]
+    let ARG_PROGRESS = Arg::with_name("progress")
+        .long("progress")
+        .takes_value(true)
+        .possible_values(&["interactive", "plain", "quiet"])
+        .help("How to show build progress: `interactive` with spinners and collapsed cargo output, `plain` as timestamped lines without colors (the default when stdout isn't a terminal, e.g. in CI), or `quiet` with only errors and the final timings");
 
     const DEFAULT_TARGET: &str = "web";
     #[allow(non_snake_case)]
@@ -80,6 +103,50 @@ fn main() -> Result<(), Report> {
         .help("Signal to the compiler to run certain operations in libdev mode, offering certain ergonomic affordances for Pax library developers.")
         .hidden(true); //hidden because this is of negative value to end-users; things are expected to break when invoked outside of the pax monorepo
 
+    #[allow(non_snake_case)]
+    let ARG_CARGO = Arg::with_name("cargo")
+        .long("cargo")
+        .takes_value(true)
+        .help(
+            "Path to the cargo binary used to build the project, instead of the `cargo` on PATH.",
+        );
+
+    #[allow(non_snake_case)]
+    let ARG_TOOLCHAIN = Arg::with_name("toolchain")
+        .long("toolchain")
+        .takes_value(true)
+        .help("Rust toolchain used to build the project, passed as `cargo +<toolchain>`, e.g. `nightly-2024-05-01`.");
+
+    #[allow(non_snake_case)]
+    let ARG_DEFINE = Arg::with_name("define")
+        .long("define")
+        .takes_value(true)
+        .multiple(true)
+        .number_of_values(1)
+        .help("Defines `key=value`, readable in expressions as `$defines.key`, e.g. `--define new_nav=true`.  Repeatable; overrides `[package.metadata.pax.defines]`.");
+
+    #[allow(non_snake_case)]
+    let ARG_FEATURES = Arg::with_name("features")
+        .long("features")
+        .takes_value(true)
+        .multiple(true)
+        .number_of_values(1)
+        .use_delimiter(true)
+        .help("Cargo features of the project to enable when building it, comma-separated or repeated, e.g. `--features analytics,premium`.");
+
+    #[allow(non_snake_case)]
+    let ARG_EMIT_MANIFEST = Arg::with_name("emit-manifest")
+        .long("emit-manifest")
+        .takes_value(true)
+        .value_name("path")
+        .help("Writes the manifest as compiled into the app to `path` as canonical JSON, and its userland variant without the designer's components next to it, e.g. `--emit-manifest target/manifest.json` also writes `target/manifest.userland.json`.");
+
+    #[allow(non_snake_case)]
+    let ARG_SPLIT_CARTRIDGE = Arg::with_name("split-cartridge")
+        .long("split-cartridge")
+        .takes_value(false)
+        .help("Generates the cartridge as one module per component under `.pax/cartridge/` instead of a single file, so that rustc compiles components in parallel and incremental builds only recompile the components that changed.  Useful for large projects.");
+
     let matches = App::new("pax")
         .name("pax")
         .bin_name("pax-cli")
@@ -94,7 +161,15 @@ fn main() -> Result<(), Report> {
                 .arg( ARG_NO_DESIGNER.clone() )
                 .arg( ARG_TARGET.clone() )
                 .arg( ARG_VERBOSE.clone() )
+                .arg( ARG_VERBOSITY.clone() )
+                .arg( ARG_PROGRESS.clone() )
                 .arg( ARG_LIBDEV.clone() )
+                .arg( ARG_CARGO.clone() )
+                .arg( ARG_TOOLCHAIN.clone() )
+                .arg( ARG_DEFINE.clone() )
+                .arg( ARG_FEATURES.clone() )
+                .arg( ARG_EMIT_MANIFEST.clone() )
+                .arg( ARG_SPLIT_CARTRIDGE.clone() )
         )
         .subcommand(
             App::new("build")
@@ -103,8 +178,37 @@ fn main() -> Result<(), Report> {
                 .arg( ARG_TARGET.clone() )
                 .arg( ARG_DESIGNER.clone() )
                 .arg( ARG_VERBOSE.clone() )
+                .arg( ARG_VERBOSITY.clone() )
+                .arg( ARG_PROGRESS.clone() )
+                .arg( ARG_LIBDEV.clone() )
+                .arg( ARG_RELEASE.clone() )
+                .arg( ARG_CARGO.clone() )
+                .arg( ARG_TOOLCHAIN.clone() )
+                .arg( ARG_DEFINE.clone() )
+                .arg( ARG_FEATURES.clone() )
+                .arg( ARG_EMIT_MANIFEST.clone() )
+                .arg( ARG_SPLIT_CARTRIDGE.clone() )
+        )
+        .subcommand(
+            App::new("manifest")
+                .about("Parses the Pax project from the current working directory and writes its manifest as `build` would compile it into the app, with lint and validation results, without building the app.  See `--emit-manifest` of `build`.")
+                .arg( ARG_PATH.clone() )
+                .arg(Arg::with_name("output")
+                    .short("o")
+                    .long("output")
+                    .takes_value(true)
+                    .default_value("manifest.json")
+                    .help("Where to write the manifest; its userland variant is written next to it"))
+                .arg( ARG_DESIGNER.clone() )
+                .arg( ARG_VERBOSE.clone() )
+                .arg( ARG_VERBOSITY.clone() )
+                .arg( ARG_PROGRESS.clone() )
                 .arg( ARG_LIBDEV.clone() )
                 .arg( ARG_RELEASE.clone() )
+                .arg( ARG_CARGO.clone() )
+                .arg( ARG_TOOLCHAIN.clone() )
+                .arg( ARG_DEFINE.clone() )
+                .arg( ARG_FEATURES.clone() )
         )
         .subcommand(
             App::new("clean")
@@ -120,6 +224,12 @@ fn main() -> Result<(), Report> {
                     .help("File system path where the new project should be created. If not provided with --path, it should directly follow 'create'")
                     .takes_value(true)
                     .index(1))  // Positional arg, `pax create positional_arg_here`
+                .arg(Arg::with_name("template")
+                    .long("template")
+                    .help("Starting point to scaffold the new project from")
+                    .possible_values(&ProjectTemplate::NAMES)
+                    .default_value("designer-playground")
+                    .takes_value(true))
                 .arg( ARG_LIBDEV.clone())
         )
         .subcommand(
@@ -131,15 +241,64 @@ fn main() -> Result<(), Report> {
                 )
                 .about("Collection of tools for internal library development")
         )
+        .subcommand(
+            App::new("lint")
+                .about("Reports likely mistakes in the Pax project from the current working directory, like unused components or undefined identifiers in expressions.  Lints are advisory and don't fail the build.  Extra rules are selected under `[lint]` in `pax.workspace.toml`; rules reporting errors make this command fail.")
+                .arg( ARG_PATH.clone() )
+        )
+        .subcommand(
+            App::new("publish")
+                .about("Publishes the Pax component library from the current working directory to crates.io, after checking it has no lint errors, has a `[package.metadata.pax]` section in Cargo.toml and packages all of its templates.  Bumps the patch version in Cargo.toml once published.")
+                .arg( ARG_PATH.clone() )
+                .arg(Arg::with_name("dry-run")
+                    .long("dry-run")
+                    .help("Runs the checks and `cargo publish --dry-run` without uploading"))
+        )
         .subcommand(App::new("lsp").about("Start the Pax LSP server"))
+        .subcommand(
+            App::new("manifest-rpc")
+                .about("Parses the Pax project from the current working directory and serves its manifest over JSON-RPC on stdin/stdout, one message per line, for editor plugins and other tooling.  Methods: get_manifest, build_node, get_node, remove_node, move_node, undo and redo.")
+                .arg( ARG_PATH.clone() )
+        )
+        .subcommand(
+            App::new("replay")
+                .about("Replays a designer session from a bug report (or the .pax-designer-session.jsonl log of a project) from its starting manifest, checking the manifest after each command, and reports the first command whose result diverges from the recording")
+                .arg(Arg::with_name("file")
+                    .help("Bug report or session log to replay")
+                    .required(true)
+                    .takes_value(true)
+                    .index(1))
+        )
         .subcommand(
             App::new("format")
-                .about("Format a Pax File")
+                .about("Formats a Pax file, or else every .pax file of the project at `--path` not ignored by a .gitignore")
                 .alias("fmt")
                 .arg(Arg::with_name("file")
                     .help("File to format. If not provided with --file, it should directly follow 'format'")
                     .takes_value(true)
                     .index(1))
+                .arg( ARG_PATH.clone() )
+                .arg(Arg::with_name("check")
+                    .long("check")
+                    .help("Prints the changes formatting would make as diffs instead of writing them, and fails if there are any"))
+                .arg(Arg::with_name("stdin")
+                    .long("stdin")
+                    .conflicts_with_all(&["file", "check"])
+                    .help("Formats a .pax document read from stdin to stdout, e.g. for editor integration"))
+        )
+        .subcommand(
+            App::new("import-snippet")
+                .about("Imports a .paxsnip snippet into the component open in the designer of a running `pax-cli run`")
+                .arg(Arg::with_name("file")
+                    .help("Snippet file to import")
+                    .takes_value(true)
+                    .required(true)
+                    .index(1))
+                .arg(Arg::with_name("port")
+                    .long("port")
+                    .takes_value(true)
+                    .default_value("8080")
+                    .help("Port of the running design server"))
         )
         .subcommand(
             App::new("eject")
@@ -158,8 +317,10 @@ fn main() -> Result<(), Report> {
 
     let cloned_version_info = Arc::clone(&new_version_info);
     let cloned_process_child_ids = Arc::clone(&process_child_ids);
+    let cloned_cancellation = cancellation.clone();
     ctrlc::set_handler(move || {
         println!("\nInterrupt received. Cleaning up child processes...");
+        cloned_cancellation.cancel();
         perform_cleanup(
             Arc::clone(&cloned_version_info),
             Arc::clone(&cloned_process_child_ids),
@@ -169,57 +330,157 @@ fn main() -> Result<(), Report> {
     })
     .expect("ctrl-c hook should have been set up successfully");
 
-    let res = perform_nominal_action(matches, Arc::clone(&process_child_ids));
+    let res = perform_nominal_action(matches, Arc::clone(&process_child_ids), cancellation);
     perform_cleanup(new_version_info, process_child_ids, is_libdev_mode, false);
     res
 }
 
+/// The `--define key=value`s of `args`, later ones overriding earlier ones
+fn parse_defines(args: &ArgMatches<'_>) -> Result<BTreeMap<String, PaxValue>, Report> {
+    args.values_of("define")
+        .into_iter()
+        .flatten()
+        .map(|define| pax_compiler::parse_define(define).map_err(|e| color_eyre::eyre::eyre!(e)))
+        .collect()
+}
+
+fn parse_verbosity(args: &ArgMatches<'_>) -> Result<Verbosity, Report> {
+    match args.value_of("verbosity") {
+        Some(verbosity) => verbosity.parse(),
+        None if args.is_present("verbose") => Ok(Verbosity::Verbose),
+        None => Ok(Verbosity::Normal),
+    }
+}
+
+/// The reporter for `--progress`, quiet with `--verbosity quiet`, or else detected from stdout
+fn parse_progress(args: &ArgMatches<'_>, verbosity: Verbosity) -> Result<ProgressReporter, Report> {
+    let mode = match args.value_of("progress") {
+        Some(mode) => mode.parse()?,
+        None if verbosity == Verbosity::Quiet => ProgressMode::Quiet,
+        None => ProgressMode::detect(),
+    };
+    if mode == ProgressMode::Plain {
+        // plain logs carry no ANSI escapes, our badges included
+        colored::control::set_override(false);
+    }
+    Ok(ProgressReporter::new(mode))
+}
+
 fn perform_nominal_action(
     matches: ArgMatches<'_>,
     process_child_ids: Arc<Mutex<Vec<u64>>>,
+    cancellation: CancellationToken,
 ) -> Result<(), Report> {
     match matches.subcommand() {
         ("run", Some(args)) => {
             let target = args.value_of("target").unwrap().to_lowercase();
             let path = args.value_of("path").unwrap().to_string(); //default value "."
-            let verbose = args.is_present("verbose");
+            let verbosity = parse_verbosity(args)?;
             let is_libdev_mode = args.is_present("libdev");
             let should_run_designer = !args.is_present("no-designer");
 
-            let _ = pax_compiler::perform_build(&RunContext {
+            let ctx = RunContext {
                 target: RunTarget::from(target.as_str()),
                 project_path: PathBuf::from(path),
-                verbose,
-                should_also_run: true,
+                verbosity,
                 is_libdev_mode,
                 process_child_ids,
                 should_run_designer,
                 is_release: false,
-            })?;
+                timeout: pax_compiler::helpers::default_build_timeout(),
+                cargo_bin: args.value_of("cargo").map(PathBuf::from),
+                toolchain: args.value_of("toolchain").map(str::to_string),
+                defines: parse_defines(args)?,
+                extra_features: args
+                    .values_of("features")
+                    .into_iter()
+                    .flatten()
+                    .map(str::to_string)
+                    .collect(),
+                emit_manifest: args.value_of("emit-manifest").map(PathBuf::from),
+                split_cartridge: args.is_present("split-cartridge"),
+                cancellation: cancellation.clone(),
+                progress: parse_progress(args, verbosity)?,
+            };
+            let app = pax_compiler::perform_build(&ctx)
+                .and_then(|artifacts| pax_compiler::perform_run(&ctx, &artifacts));
+            ctx.progress.finish();
+            let mut app = app?;
+            if ctx.target == RunTarget::iOS {
+                // the app keeps running on the simulator after the CLI exits
+                app.detach();
+            } else {
+                app.wait()?;
+            }
 
             Ok(())
         }
         ("build", Some(args)) => {
             let target = args.value_of("target").unwrap().to_lowercase();
             let path = args.value_of("path").unwrap().to_string(); //default value "."
-            let verbose = args.is_present("verbose");
+            let verbosity = parse_verbosity(args)?;
             let should_run_designer = args.is_present("designer");
             let is_libdev_mode = args.is_present("libdev");
             let is_release = args.is_present("release");
 
-            let _ = pax_compiler::perform_build(&RunContext {
+            let ctx = RunContext {
                 target: RunTarget::from(target.as_str()),
                 project_path: PathBuf::from(path),
-                should_also_run: false,
                 should_run_designer,
-                verbose,
+                verbosity,
                 is_libdev_mode,
                 process_child_ids,
                 is_release,
-            })?;
+                timeout: pax_compiler::helpers::default_build_timeout(),
+                cargo_bin: args.value_of("cargo").map(PathBuf::from),
+                toolchain: args.value_of("toolchain").map(str::to_string),
+                defines: parse_defines(args)?,
+                extra_features: args
+                    .values_of("features")
+                    .into_iter()
+                    .flatten()
+                    .map(str::to_string)
+                    .collect(),
+                emit_manifest: args.value_of("emit-manifest").map(PathBuf::from),
+                split_cartridge: args.is_present("split-cartridge"),
+                cancellation: cancellation.clone(),
+                progress: parse_progress(args, verbosity)?,
+            };
+            let result = pax_compiler::perform_build(&ctx);
+            ctx.progress.finish();
+            result?;
 
             Ok(())
         }
+        ("manifest", Some(args)) => {
+            let path = args.value_of("path").unwrap().to_string(); //default value "."
+            let verbosity = parse_verbosity(args)?;
+            let ctx = RunContext {
+                target: RunTarget::Web,
+                project_path: PathBuf::from(path),
+                verbosity,
+                is_libdev_mode: args.is_present("libdev"),
+                process_child_ids,
+                should_run_designer: args.is_present("designer"),
+                is_release: args.is_present("release"),
+                timeout: pax_compiler::helpers::default_build_timeout(),
+                cargo_bin: args.value_of("cargo").map(PathBuf::from),
+                toolchain: args.value_of("toolchain").map(str::to_string),
+                defines: parse_defines(args)?,
+                extra_features: args
+                    .values_of("features")
+                    .into_iter()
+                    .flatten()
+                    .map(str::to_string)
+                    .collect(),
+                emit_manifest: None,
+                split_cartridge: false,
+                cancellation: cancellation.clone(),
+                progress: parse_progress(args, verbosity)?,
+            };
+            let output = PathBuf::from(args.value_of("output").unwrap()); //default value "manifest.json"
+            pax_compiler::perform_emit_manifest(&ctx, &output)
+        }
         ("clean", Some(args)) => {
             println!("🧹 Cleaning cached & temporary files...");
             let path = args.value_of("path").unwrap().to_string(); //default value "."
@@ -234,11 +495,13 @@ fn perform_nominal_action(
             let path = args.value_of("path").unwrap().to_string(); //default value "."
             let is_libdev_mode = args.is_present("libdev");
             let version = crate_version!().to_string(); // Note: this could also be parameterized, but an easy default is to clamp to the CLI version
+            let template = ProjectTemplate::from(args.value_of("template").unwrap()); //default value "designer-playground"
 
             pax_compiler::perform_create(&CreateContext {
                 path,
                 is_libdev_mode,
                 version,
+                template,
             });
             Ok(())
         }
@@ -249,12 +512,20 @@ fn perform_nominal_action(
             let _ = pax_compiler::perform_eject(&RunContext {
                 target: RunTarget::from(target.as_str()),
                 project_path: PathBuf::from("."),
-                should_also_run: false,
                 should_run_designer: false,
-                verbose: false,
+                verbosity: Verbosity::Normal,
                 is_libdev_mode,
                 process_child_ids,
                 is_release: false,
+                timeout: pax_compiler::helpers::default_build_timeout(),
+                cargo_bin: None,
+                toolchain: None,
+                defines: BTreeMap::new(),
+                extra_features: vec![],
+                emit_manifest: None,
+                split_cartridge: false,
+                cancellation: cancellation.clone(),
+                progress: ProgressReporter::new(ProgressMode::detect()),
             })?;
 
             Ok(())
@@ -284,19 +555,94 @@ fn perform_nominal_action(
                 }
             }
         }
+        ("lint", Some(args)) => {
+            let path = args.value_of("path").unwrap().to_string(); //default value "."
+            let diagnostics = pax_compiler::perform_lint(&PathBuf::from(path), process_child_ids)?;
+            for diagnostic in &diagnostics {
+                println!("{}\n", diagnostic);
+            }
+            let errors = diagnostics
+                .iter()
+                .filter(|d| d.severity == pax_compiler::LintSeverity::Error)
+                .count();
+            println!(
+                "{} warning(s), {} error(s)",
+                diagnostics.len() - errors,
+                errors
+            );
+            if errors > 0 {
+                return Err(Report::msg("lint failed"));
+            }
+            Ok(())
+        }
+        ("publish", Some(args)) => {
+            let path = args.value_of("path").unwrap(); //default value "."
+            pax_compiler::perform_publish(&PathBuf::from(path), args.is_present("dry-run"))
+        }
+        ("manifest-rpc", Some(args)) => {
+            let path = args.value_of("path").unwrap().to_string(); //default value "."
+            pax_compiler::perform_manifest_rpc(&PathBuf::from(path), process_child_ids)
+        }
+        ("replay", Some(args)) => {
+            let file = args.value_of("file").unwrap();
+            pax_compiler::perform_replay(&PathBuf::from(file))
+        }
         ("lsp", Some(_)) => {
             tokio::runtime::Runtime::new()
                 .unwrap()
                 .block_on(pax_language_server::start_server());
             Ok(())
         }
+        ("import-snippet", Some(args)) => {
+            let file = args.value_of("file").unwrap();
+            let port = args.value_of("port").unwrap(); //default value "8080"
+            let port: u16 = port
+                .parse()
+                .map_err(|_| Report::msg(format!("invalid port: {}", port)))?;
+            let archive = std::fs::read(file)?;
+
+            let message = http::import_snippet(port, archive).map_err(Report::msg)?;
+            println!("{}", message);
+            Ok(())
+        }
         ("format", Some(args)) => {
-            let file = args.value_of("file").unwrap().to_string();
-            // current directory
-            let path = std::env::current_dir().unwrap();
-            let file_path = path.join(file);
+            if args.is_present("stdin") {
+                let mut code = String::new();
+                std::io::stdin().read_to_string(&mut code)?;
+                print!("{}", pax_compiler::formatting::format_pax_document(&code)?);
+                return Ok(());
+            }
+            if let Some(file) = args.value_of("file") {
+                // current directory
+                let path = std::env::current_dir().unwrap();
+                let file_path = path.join(file);
 
-            pax_compiler::formatting::format_file(file_path.to_str().unwrap())?;
+                pax_compiler::formatting::format_file(file_path.to_str().unwrap())?;
+                return Ok(());
+            }
+
+            let path = args.value_of("path").unwrap(); //default value "."
+            let check = args.is_present("check");
+            let report = pax_compiler::perform_format(
+                &PathBuf::from(path),
+                &pax_compiler::FormatOptions { check },
+            )?;
+            for file in &report.files {
+                match &file.status {
+                    pax_compiler::FileFormatStatus::Changed(diff) if check => print!("{}", diff),
+                    pax_compiler::FileFormatStatus::Error(e) => {
+                        eprintln!("{}: {}", file.path.display(), e)
+                    }
+                    _ => {}
+                }
+            }
+            println!("{}", report.summary(check));
+            if report.errors().count() > 0 {
+                return Err(Report::msg("some files couldn't be formatted"));
+            }
+            if check && report.changed().count() > 0 {
+                return Err(Report::msg("some files aren't formatted"));
+            }
             Ok(())
         }
         _ => unreachable!(), // If all subcommands are defined above, anything else is unreachable
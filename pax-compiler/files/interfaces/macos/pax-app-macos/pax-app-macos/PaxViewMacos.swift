@@ -153,6 +153,7 @@ struct PaxViewMacos: View {
 
             if PaxEngineContainer.paxEngineContainer == nil {
                 PaxEngineContainer.paxEngineContainer = pax_init()
+                sendViewportDprChange()
             } else {
 
                 let nativeMessageQueue = pax_tick(PaxEngineContainer.paxEngineContainer!, &cgContext, CFloat(dirtyRect.width), CFloat(dirtyRect.height))
@@ -304,6 +305,81 @@ struct PaxViewMacos: View {
 
 
 
+        func handlePrintRequest(patch: PrintPatch) {
+            // native elements aren't addressable by id here, so a target_id
+            // falls back to printing the whole view
+            if patch.target_id != nil {
+                print("PrintRequest target_id is not supported on macOS, printing the whole view")
+            }
+            DispatchQueue.main.async {
+                let printOperation = NSPrintOperation(view: self)
+                printOperation.showsPrintPanel = true
+                printOperation.run()
+            }
+        }
+
+        override func viewDidChangeBackingProperties() {
+            super.viewDidChangeBackingProperties()
+            // e.g. the window moved to a display with another backing scale factor
+            if PaxEngineContainer.paxEngineContainer != nil {
+                sendViewportDprChange()
+            }
+        }
+
+        func handleClipboardWrite(text: String) {
+            let pasteboard = NSPasteboard.general
+            pasteboard.clearContents()
+            pasteboard.setString(text, forType: .string)
+        }
+
+        func handleClipboardReadRequest() {
+            let text = NSPasteboard.general.string(forType: .string) ?? ""
+            let buffer = try! FlexBufferBuilder.encode(["ClipboardRead": text] as FlxbValueMap)
+
+            buffer.data.withUnsafeBytes({ptr in
+                var ffi_container = InterruptBuffer( data_ptr: ptr.baseAddress!, length: UInt64(ptr.count) )
+                withUnsafePointer(to: &ffi_container) {ffi_container_ptr in
+                    pax_interrupt(PaxEngineContainer.paxEngineContainer!, ffi_container_ptr)
+                }
+            })
+        }
+
+        func sendViewportDprChange() {
+            let dpr = window?.backingScaleFactor ?? NSScreen.main?.backingScaleFactor ?? 1.0
+            let json = String(format: "{\"ViewportDprChange\": {\"dpr\": %f} }", Double(dpr))
+            let buffer = try! FlexBufferBuilder.fromJSON(json)
+
+            buffer.data.withUnsafeBytes({ptr in
+                var ffi_container = InterruptBuffer( data_ptr: ptr.baseAddress!, length: UInt64(ptr.count) )
+                withUnsafePointer(to: &ffi_container) {ffi_container_ptr in
+                    pax_interrupt(PaxEngineContainer.paxEngineContainer!, ffi_container_ptr)
+                }
+            })
+        }
+
+        func handleVideoCreate(fb: FlxbReference) {
+            // there's no native video player on macOS yet: report the video as
+            // unsupported so that the engine renders its poster instead
+            let id = fb["id"]!.asUInt32!
+            let json = String(format: "{\"VideoUnsupported\": {\"id\": %u} }", id)
+            let buffer = try! FlexBufferBuilder.fromJSON(json)
+
+            buffer.data.withUnsafeBytes({ptr in
+                var ffi_container = InterruptBuffer( data_ptr: ptr.baseAddress!, length: UInt64(ptr.count) )
+                withUnsafePointer(to: &ffi_container) {ffi_container_ptr in
+                    pax_interrupt(PaxEngineContainer.paxEngineContainer!, ffi_container_ptr)
+                }
+            })
+        }
+
+        func handleVideoUpdate(patch: VideoUpdatePatch) {
+            //no-op until a native video player is implemented
+        }
+
+        func handleVideoDelete(id: UInt32) {
+            //no-op until a native video player is implemented
+        }
+
         func processNativeMessageQueue(queue: NativeMessageQueue) {
 
             let buffer = UnsafeBufferPointer<UInt8>(start: queue.data_ptr!, count: Int(queue.length))
@@ -346,6 +422,36 @@ struct PaxViewMacos: View {
                     handleImageLoad(patch: ImageLoadPatch(fb: imageLoadMessage!))
                 }
 
+                let printRequestMessage = message["PrintRequest"]
+                if printRequestMessage != nil {
+                    handlePrintRequest(patch: PrintPatch(fb: printRequestMessage!))
+                }
+
+                let clipboardWriteMessage = message["ClipboardWrite"]
+                if clipboardWriteMessage != nil {
+                    handleClipboardWrite(text: clipboardWriteMessage!.asString ?? "")
+                }
+
+                let clipboardReadRequestMessage = message["ClipboardReadRequest"]
+                if clipboardReadRequestMessage != nil {
+                    handleClipboardReadRequest()
+                }
+
+                let videoCreateMessage = message["VideoCreate"]
+                if videoCreateMessage != nil {
+                    handleVideoCreate(fb: videoCreateMessage!)
+                }
+
+                let videoUpdateMessage = message["VideoUpdate"]
+                if videoUpdateMessage != nil {
+                    handleVideoUpdate(patch: VideoUpdatePatch(fb: videoUpdateMessage!))
+                }
+
+                let videoDeleteMessage = message["VideoDelete"]
+                if videoDeleteMessage != nil {
+                    handleVideoDelete(id: videoDeleteMessage!.asUInt32!)
+                }
+
                 //^ Add new message-receive handlers here ^
             })
 
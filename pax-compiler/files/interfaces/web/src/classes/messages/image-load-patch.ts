@@ -1,14 +1,17 @@
 export class ImageLoadPatch {
     public id?: number;
     public path?: string;
+    public scale?: number;
 
     fromPatch(jsonMessage: any) {
         this.id = jsonMessage["id"];
         this.path = jsonMessage["path"];
+        this.scale = jsonMessage["scale"];
     }
 
     cleanUp(){
         this.id = undefined;
         this.path = '';
+        this.scale = undefined;
     }
 }
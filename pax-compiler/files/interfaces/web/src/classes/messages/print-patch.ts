@@ -0,0 +1,11 @@
+export class PrintPatch {
+    public targetId?: string;
+
+    fromPatch(jsonMessage: any) {
+        this.targetId = jsonMessage["target_id"];
+    }
+
+    cleanUp(){
+        this.targetId = undefined;
+    }
+}
@@ -13,6 +13,7 @@ export class SliderUpdatePatch {
     public max?: number;
     public background?: ColorGroup;
     public borderRadius?: number;
+    public orientation?: string;
     objectManager: ObjectManager;
 
     constructor(objectManager: ObjectManager) {
@@ -31,6 +32,7 @@ export class SliderUpdatePatch {
         this.max = jsonMessage["max"];
         this.borderRadius = jsonMessage["border_radius"];
         this.background = jsonMessage["background"];
+        this.orientation = jsonMessage["orientation"];
     }
 
     cleanUp(){
@@ -45,5 +47,6 @@ export class SliderUpdatePatch {
         this.background = undefined;
         this.accent = undefined;
         this.transform = [];
+        this.orientation = undefined;
     }
 }
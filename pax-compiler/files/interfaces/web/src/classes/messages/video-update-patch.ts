@@ -0,0 +1,41 @@
+export class VideoUpdatePatch {
+    public id?: number;
+    public size_x?: number;
+    public size_y?: number;
+    public transform?: number[];
+    public source?: string;
+    public playing?: boolean;
+    public looping?: boolean;
+    public muted?: boolean;
+    public volume?: number;
+    public currentTime?: number;
+    public fit?: string;
+
+    fromPatch(jsonMessage: any) {
+        this.id = jsonMessage["id"];
+        this.size_x = jsonMessage["size_x"];
+        this.size_y = jsonMessage["size_y"];
+        this.transform = jsonMessage["transform"];
+        this.source = jsonMessage["source"];
+        this.playing = jsonMessage["playing"];
+        this.looping = jsonMessage["looping"];
+        this.muted = jsonMessage["muted"];
+        this.volume = jsonMessage["volume"];
+        this.currentTime = jsonMessage["current_time"];
+        this.fit = jsonMessage["fit"];
+    }
+
+    cleanUp(){
+        this.id = undefined;
+        this.size_x = 0;
+        this.size_y = 0;
+        this.transform = [];
+        this.source = undefined;
+        this.playing = undefined;
+        this.looping = undefined;
+        this.muted = undefined;
+        this.volume = undefined;
+        this.currentTime = undefined;
+        this.fit = undefined;
+    }
+}
@@ -1,5 +1,6 @@
 import {BUTTON_CLASS, BUTTON_TEXT_CONTAINER_CLASS,
-    NATIVE_LEAF_CLASS, CHECKBOX_CLASS, RADIO_SET_CLASS,SCROLLER_CONTAINER} from "../utils/constants";
+    NATIVE_LEAF_CLASS, CHECKBOX_CLASS, RADIO_SET_CLASS,SCROLLER_CONTAINER,
+    VIDEO_TIME_UPDATE_INTERVAL_MS, VIDEO_SEEK_TOLERANCE_SECS} from "../utils/constants";
 import {AnyCreatePatch} from "./messages/any-create-patch";
 import {OcclusionUpdatePatch} from "./messages/occlusion-update-patch";
 import snarkdown from 'snarkdown';
@@ -21,13 +22,19 @@ import { DropdownUpdatePatch } from "./messages/dropdown-update-patch";
 import { SliderUpdatePatch } from "./messages/slider-update-patch";
 import { EventBlockerUpdatePatch } from "./messages/event-blocker-update-patch";
 import { NavigationPatch } from "./messages/navigation-patch";
+import { PrintPatch } from "./messages/print-patch";
+import { VideoUpdatePatch } from "./messages/video-update-patch";
 import { NativeImageUpdatePatch } from "./messages/native-image-update-patch";
 
 export class NativeElementPool {
     private canvases: Map<string, HTMLCanvasElement>;
     layers: OcclusionLayerManager;
     private nodesLookup = new Map<number, HTMLElement>();
+    // scale of the asset variant loaded for each image path, see imageLoad
+    private imageScales = new Map<string, number>();
     private chassis?: PaxChassisWeb;
+    // clipboard reads are answered in the order they were requested
+    private clipboardReads: Promise<void> = Promise.resolve();
     private objectManager: ObjectManager;
     private resizeObserver: ResizeObserver;
     registeredFontFaces: Set<string>;
@@ -206,6 +213,126 @@ export class NativeElementPool {
         }
     }
 
+    videoCreate(patch: AnyCreatePatch) {
+        console.assert(patch.id != null);
+        console.assert(patch.occlusionLayerId != null);
+        let id = patch.id!;
+
+        // not pooled, since the element carries event listeners bound to this id
+        const video = document.createElement("video");
+        video.style.margin = "0";
+        video.style.display = "block";
+        video.playsInline = true;
+        video.preload = "metadata";
+
+        let lastTimeUpdate = 0;
+        video.addEventListener("timeupdate", (_event) => {
+            // throttled, since property updates from playback re-run expressions bound to current_time
+            let now = performance.now();
+            if (now - lastTimeUpdate < VIDEO_TIME_UPDATE_INTERVAL_MS) {
+                return;
+            }
+            lastTimeUpdate = now;
+            this.sendVideoInterrupt("VideoTimeUpdate", { "id": id, "current_time": video.currentTime });
+        });
+        video.addEventListener("play", (_event) => {
+            this.sendVideoInterrupt("VideoPlayStateChange", { "id": id, "playing": true });
+        });
+        video.addEventListener("pause", (_event) => {
+            this.sendVideoInterrupt("VideoTimeUpdate", { "id": id, "current_time": video.currentTime });
+            this.sendVideoInterrupt("VideoPlayStateChange", { "id": id, "playing": false });
+        });
+        video.addEventListener("loadedmetadata", (_event) => {
+            this.sendVideoInterrupt("VideoMetadata", {
+                "id": id,
+                "natural_width": video.videoWidth,
+                "natural_height": video.videoHeight,
+                "duration": isFinite(video.duration) ? video.duration : 0,
+            });
+        });
+        video.addEventListener("ended", (_event) => {
+            this.sendVideoInterrupt("VideoPlayStateChange", { "id": id, "playing": false });
+            this.sendVideoInterrupt("VideoEnded", { "id": id });
+        });
+        video.addEventListener("error", (_event) => {
+            // let the engine draw its canvas fallback in place of the video
+            video.style.visibility = "hidden";
+            this.sendVideoInterrupt("VideoUnsupported", { "id": id });
+        });
+
+        let videoDiv: HTMLDivElement = this.objectManager.getFromPool(DIV);
+        videoDiv.appendChild(video);
+        videoDiv.setAttribute("class", NATIVE_LEAF_CLASS)
+        videoDiv.setAttribute("pax_id", String(id));
+        if(patch.id != undefined && patch.occlusionLayerId != undefined){
+            this.layers.addElement(videoDiv, patch.parentFrame, patch.occlusionLayerId);
+        }
+        this.nodesLookup.set(id, videoDiv);
+
+        if (typeof video.canPlayType !== "function") {
+            video.style.visibility = "hidden";
+            this.sendVideoInterrupt("VideoUnsupported", { "id": id });
+        }
+    }
+
+    videoUpdate(patch: VideoUpdatePatch) {
+        let leaf = this.nodesLookup.get(patch.id!);
+        let video = leaf!.firstChild as HTMLVideoElement;
+        updateCommonProps(leaf!, patch);
+        if (patch.source != null) {
+            video.style.visibility = "visible";
+            video.src = patch.source;
+        }
+        if (patch.fit != null) {
+            video.style.objectFit = patch.fit;
+        }
+        if (patch.looping != null) {
+            video.loop = patch.looping;
+        }
+        if (patch.muted != null) {
+            video.muted = patch.muted;
+        }
+        if (patch.volume != null) {
+            video.volume = Math.min(Math.max(patch.volume, 0), 1);
+        }
+        // only sent when set from userland (a seek), not when echoing playback progress
+        if (patch.currentTime != null && Math.abs(video.currentTime - patch.currentTime) > VIDEO_SEEK_TOLERANCE_SECS) {
+            video.currentTime = patch.currentTime;
+        }
+        if (patch.playing != null) {
+            if (patch.playing && video.paused) {
+                video.play().catch((err) => {
+                    // e.g. autoplay policies: report the state back so `playing` stays truthful
+                    console.warn("failed to play video:", err);
+                    this.sendVideoInterrupt("VideoPlayStateChange", { "id": patch.id!, "playing": false });
+                });
+            } else if (!patch.playing && !video.paused) {
+                video.pause();
+            }
+        }
+    }
+
+    videoDelete(id: number) {
+        let oldNode = this.nodesLookup.get(id);
+        if (oldNode){
+            let video = oldNode.firstChild as HTMLVideoElement;
+            video.pause();
+            video.removeAttribute("src");
+            video.load();
+            let parent = oldNode.parentElement;
+            parent!.removeChild(oldNode);
+            this.nodesLookup.delete(id);
+        }
+    }
+
+    sendVideoInterrupt(name: string, args: any) {
+        // the element may outlive its node briefly (e.g. a pause event while being removed)
+        if (!this.nodesLookup.has(args["id"])) {
+            return;
+        }
+        this.chassis!.interrupt(JSON.stringify({ [name]: args }), undefined);
+    }
+
     textboxCreate(patch: AnyCreatePatch) {
         const textbox = this.objectManager.getFromPool(INPUT) as HTMLInputElement;
         textbox.type = "text";
@@ -482,6 +609,14 @@ export class NativeElementPool {
         if (patch.borderRadius != null) {
             slider.style.borderRadius = patch.borderRadius + "px";
         }
+
+        if (patch.orientation != null) {
+            // vertical writing mode with rtl direction puts min at the bottom, so dragging
+            // up increases the value
+            let vertical = patch.orientation == "Vertical";
+            slider.style.writingMode = vertical ? "vertical-lr" : "";
+            slider.style.direction = vertical ? "rtl" : "";
+        }
     }
 
     sliderDelete(id: number) {
@@ -691,6 +826,12 @@ export class NativeElementPool {
         // send interrupts to the engine, or not?
         let start_listening = false;
 
+        // this element was used by a node that has since been unmounted,
+        // the patch carries the full state of the new node
+        if (patch.recycled) {
+            this.resizeObserver.unobserve(leaf);
+        }
+
         // Handle size_x and size_y
         if (patch.size_x != null) {
 
@@ -967,7 +1108,9 @@ export class NativeElementPool {
 
     async imageLoad(patch: ImageLoadPatch, chassis: PaxChassisWeb) {
 
-        if (chassis.image_loaded(patch.path ?? "")) {
+        const scale = patch.scale ?? 1;
+        // reload only if a higher resolution variant is wanted than the one loaded
+        if (chassis.image_loaded(patch.path ?? "") && (this.imageScales.get(patch.path!) ?? 1) >= scale) {
             return
         }
         //Check the full path of our index.js; use the prefix of this path also for our image assets
@@ -980,7 +1123,20 @@ export class NativeElementPool {
         const BASE_PATH = getBasePath();
 
         let path = (BASE_PATH + patch.path!).replace("//", "/");
-        let image_data = await readImageToByteBuffer(path!)
+        let image_data;
+        let loaded_scale = 1;
+        // prefer e.g. logo@2x.png over logo.png on high-DPI screens, if it exists
+        for (let variant_scale = scale; variant_scale > 1 && image_data == undefined; variant_scale--) {
+            let variant_path = path.replace(/(\.[^./]*)?$/, `@${variant_scale}x$1`);
+            image_data = await readImageToByteBuffer(variant_path, 1).catch(() => undefined);
+            loaded_scale = variant_scale;
+        }
+        if (image_data == undefined) {
+            image_data = await readImageToByteBuffer(path!);
+            loaded_scale = 1;
+        }
+        this.imageScales.set(patch.path!, loaded_scale);
+        // sent with the logical path, which is what the image is drawn by
         let message = {
             "Image": {
                 "Data": {
@@ -1009,6 +1165,51 @@ export class NativeElementPool {
         }
         window.open(patch.url, name);
     }
+
+    print(patch: PrintPatch) {
+        if (patch.targetId == null) {
+            window.print();
+            return;
+        }
+        let target = document.getElementById(patch.targetId);
+        if (target == null) {
+            console.error(`no element with id ${patch.targetId} to print`);
+            return;
+        }
+        // print a copy of only the target (with the page styles) from a hidden iframe
+        let frame = document.createElement("iframe");
+        frame.style.position = "fixed";
+        frame.style.width = "0";
+        frame.style.height = "0";
+        frame.style.border = "0";
+        document.body.appendChild(frame);
+        let frameDoc = frame.contentDocument!;
+        document.querySelectorAll("style, link[rel=stylesheet]").forEach((style) => {
+            frameDoc.head.appendChild(style.cloneNode(true));
+        });
+        frameDoc.body.appendChild(target.cloneNode(true));
+        frame.contentWindow!.onafterprint = () => frame.remove();
+        frame.contentWindow!.focus();
+        frame.contentWindow!.print();
+    }
+
+    clipboardWrite(text: string) {
+        navigator.clipboard.writeText(text).catch((err) => {
+            console.error("failed to write to the clipboard", err);
+        });
+    }
+
+    clipboardRead() {
+        this.clipboardReads = this.clipboardReads
+            .then(() => navigator.clipboard.readText())
+            .catch((err) => {
+                console.error("failed to read the clipboard", err);
+                return "";
+            })
+            .then((text) => {
+                this.chassis!.interrupt(JSON.stringify({"ClipboardRead": text}), undefined);
+            });
+    }
 }
 
 function toCssColor(color: ColorGroup): string {
@@ -9,6 +9,15 @@ function convertModifiers(event: MouseEvent | KeyboardEvent) {
     return modifiers;
 }
 
+function getKeyLocation(event: KeyboardEvent) {
+    switch (event.location) {
+        case 1: return 'Left';
+        case 2: return 'Right';
+        case 3: return 'Numpad';
+        default: return 'Standard';
+    }
+}
+
 function getMouseButton(event: MouseEvent) {
     switch (event.button) {
         case 0: return 'Left';
@@ -213,7 +222,8 @@ export function setupEventListeners(chassis: PaxChassisWeb) {
             "KeyDown": {
                 "key": evt.key,
                 "modifiers": convertModifiers(evt),
-                "is_repeat": evt.repeat
+                "is_repeat": evt.repeat,
+                "location": getKeyLocation(evt)
             }
         };
         let res = chassis.interrupt(JSON.stringify(event), []);
@@ -229,7 +239,8 @@ export function setupEventListeners(chassis: PaxChassisWeb) {
             "KeyUp": {
                 "key": evt.key,
                 "modifiers": convertModifiers(evt),
-                "is_repeat": evt.repeat
+                "is_repeat": evt.repeat,
+                "location": getKeyLocation(evt)
             }
         };
         let res = chassis.interrupt(JSON.stringify(event), []);
@@ -245,7 +256,8 @@ export function setupEventListeners(chassis: PaxChassisWeb) {
             "KeyPress": {
                 "key": evt.key,
                 "modifiers": convertModifiers(evt),
-                "is_repeat": evt.repeat
+                "is_repeat": evt.repeat,
+                "location": getKeyLocation(evt)
             }
         };
         let res = chassis.interrupt(JSON.stringify(event), []);
@@ -14,7 +14,9 @@ import {
     RADIOSET_UPDATE_PATCH,
     EVENT_BLOCKER_UPDATE_PATCH,
     NAVIGATION_PATCH,
+    PRINT_PATCH,
     NATIVE_IMAGE_UPDATE_PATCH,
+    VIDEO_UPDATE_PATCH,
 } from "./pools/supported-objects";
 import {NativeElementPool} from "./classes/native-element-pool";
 import {AnyCreatePatch} from "./classes/messages/any-create-patch";
@@ -33,10 +35,15 @@ import { TextboxUpdatePatch } from "./classes/messages/textbox-update-patch";
 import { DropdownUpdatePatch } from "./classes/messages/dropdown-update-patch";
 import { SliderUpdatePatch } from "./classes/messages/slider-update-patch";
 import { NavigationPatch } from "./classes/messages/navigation-patch";
+import { PrintPatch } from "./classes/messages/print-patch";
 import { NativeImageUpdatePatch } from "./classes/messages/native-image-update-patch";
+import { VideoUpdatePatch } from "./classes/messages/video-update-patch";
+
+// Time per animation frame spent applying message chunks, the chunks left over are applied on
+// the following frames while the engine holds back and coalesces new messages
+const MESSAGE_CHUNK_BUDGET_MS = 8;
 
 let objectManager = new ObjectManager(SUPPORTED_OBJECTS);
-let messages : any[];
 let nativePool = new NativeElementPool(objectManager);
 let textDecoder = new TextDecoder();
 let initializedChassis = false;
@@ -87,6 +94,9 @@ async function startRenderLoop(extensionlessUrl: string, mount: Element) {
     try {
         let {chassis, get_latest_memory} = await loadWasmModule(extensionlessUrl);
         nativePool.attach(chassis, mount);
+        chassis.declare_capabilities(JSON.stringify({
+            native_element_recycling: true,
+        }));
         requestAnimationFrame(renderLoop.bind(renderLoop, chassis, mount, get_latest_memory));
     } catch (error) {
         console.error("Failed to load or instantiate Wasm module:", error);
@@ -96,13 +106,7 @@ async function startRenderLoop(extensionlessUrl: string, mount: Element) {
 function renderLoop (chassis: PaxChassisWeb, mount: Element, get_latest_memory: ()=>any) {
     nativePool.clearCanvases();
 
-    const memorySliceSpec = chassis.tick();
-    const latestMemory : WebAssembly.Memory = get_latest_memory();
-    const memoryBuffer = new Uint8Array(latestMemory.buffer);
-
-    // Extract the serialized data directly from memory
-    const jsonString = textDecoder.decode(memoryBuffer.subarray(memorySliceSpec.ptr(), memorySliceSpec.ptr() + memorySliceSpec.len()));
-    messages = JSON.parse(jsonString);
+    chassis.tick();
 
     if(!initializedChassis){
         let resizeHandler = () => {
@@ -112,22 +116,55 @@ function renderLoop (chassis: PaxChassisWeb, mount: Element, get_latest_memory:
         };
         window.addEventListener('resize', resizeHandler);
         resizeHandler();//Fire once manually to init viewport size & occlusion context
+        // `resolution` media queries match a single ratio, so re-register after every change,
+        // e.g. when zooming or moving the window to a monitor with another pixel density
+        let dprHandler = () => {
+            let dpr = window.devicePixelRatio;
+            chassis.interrupt(JSON.stringify({"ViewportDprChange": {"dpr": dpr}}), []);
+            window.matchMedia(`(resolution: ${dpr}dppx)`)
+                .addEventListener('change', dprHandler, { once: true });
+        };
+        dprHandler();
         setupEventListeners(chassis);
         initializedChassis = true;
     }
 
-    processMessages(messages, chassis, objectManager);
+    applyMessageChunks(chassis, get_latest_memory);
 
     //draw canvas elements
     chassis.render();
 
-    //necessary manual cleanup
-    chassis.deallocate(memorySliceSpec);
-
     requestAnimationFrame(renderLoop.bind(renderLoop, chassis, mount, get_latest_memory))
 }
 
 
+// Pulls and applies the chunks of the current frame's messages until they're all applied or the
+// budget is spent, acknowledging the frame after its last chunk
+function applyMessageChunks(chassis: PaxChassisWeb, get_latest_memory: ()=>any) {
+    const deadline = performance.now() + MESSAGE_CHUNK_BUDGET_MS;
+    let memorySliceSpec;
+    while ((memorySliceSpec = chassis.next_message_chunk()) !== undefined) {
+        const latestMemory : WebAssembly.Memory = get_latest_memory();
+        const memoryBuffer = new Uint8Array(latestMemory.buffer);
+
+        // Extract the serialized data directly from memory
+        const jsonString = textDecoder.decode(memoryBuffer.subarray(memorySliceSpec.ptr(), memorySliceSpec.ptr() + memorySliceSpec.len()));
+        //necessary manual cleanup
+        chassis.deallocate(memorySliceSpec);
+        const chunk = JSON.parse(jsonString);
+
+        processMessages(chunk.messages, chassis, objectManager);
+
+        if (chunk.chunk_index + 1 === chunk.chunk_count) {
+            chassis.interrupt(JSON.stringify({"MessageChunksConsumed": {"frame_id": chunk.frame_id}}), []);
+            return;
+        }
+        if (performance.now() > deadline) {
+            return;
+        }
+    }
+}
+
 export function processMessages(messages: any[], chassis: PaxChassisWeb, objectManager: ObjectManager) {
     if (messages.length === 0) {
         return;
@@ -245,6 +282,19 @@ export function processMessages(messages: any[], chassis: PaxChassisWeb, objectM
         }else if (unwrapped_msg["NativeImageDelete"]) {
             let msg = unwrapped_msg["NativeImageDelete"];
             nativePool.nativeImageDelete(msg)
+        } else if(unwrapped_msg["VideoCreate"]) {
+            let msg = unwrapped_msg["VideoCreate"]
+            let patch: AnyCreatePatch = objectManager.getFromPool(ANY_CREATE_PATCH);
+            patch.fromPatch(msg);
+            nativePool.videoCreate(patch);
+        } else if (unwrapped_msg["VideoUpdate"]){
+            let msg = unwrapped_msg["VideoUpdate"]
+            let patch: VideoUpdatePatch = objectManager.getFromPool(VIDEO_UPDATE_PATCH);
+            patch.fromPatch(msg);
+            nativePool.videoUpdate(patch);
+        } else if (unwrapped_msg["VideoDelete"]) {
+            let msg = unwrapped_msg["VideoDelete"];
+            nativePool.videoDelete(msg)
         } else if(unwrapped_msg["FrameCreate"]) {
             let msg = unwrapped_msg["FrameCreate"]
             let patch: AnyCreatePatch = objectManager.getFromPool(ANY_CREATE_PATCH);
@@ -289,6 +339,15 @@ export function processMessages(messages: any[], chassis: PaxChassisWeb, objectM
             let patch : NavigationPatch = objectManager.getFromPool(NAVIGATION_PATCH);
             patch.fromPatch(msg);
             nativePool.navigate(patch)
+        } else if (unwrapped_msg["PrintRequest"]) {
+            let msg = unwrapped_msg["PrintRequest"];
+            let patch : PrintPatch = objectManager.getFromPool(PRINT_PATCH);
+            patch.fromPatch(msg);
+            nativePool.print(patch);
+        } else if (unwrapped_msg["ClipboardWrite"] != null) {
+            nativePool.clipboardWrite(unwrapped_msg["ClipboardWrite"]);
+        } else if (unwrapped_msg["ClipboardReadRequest"]) {
+            nativePool.clipboardRead();
         }
     });
 
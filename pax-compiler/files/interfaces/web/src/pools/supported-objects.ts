@@ -18,6 +18,8 @@ import { SliderUpdatePatch } from "../classes/messages/slider-update-patch";
 import { RadioSetUpdatePatch } from "../classes/messages/radio-set-update-patch";
 import { EventBlockerUpdatePatch } from "../classes/messages/event-blocker-update-patch";
 import { NavigationPatch } from "../classes/messages/navigation-patch";
+import { PrintPatch } from "../classes/messages/print-patch";
+import { VideoUpdatePatch } from "../classes/messages/video-update-patch";
 
 export const OBJECT = "Object";
 export const ARRAY = "Array";
@@ -34,8 +36,10 @@ export const EVENT_BLOCKER_UPDATE_PATCH = "Event Blocker Update Patch";
 export const IMAGE_LOAD_PATCH = "IMAGE LOAD PATCH";
 export const SCROLLER_UPDATE_PATCH = "Scroller Update Patch";
 export const NAVIGATION_PATCH = "Navigation Patch";
+export const PRINT_PATCH = "Print Patch";
 export const TEXT_UPDATE_PATCH = "Text Update Patch";
 export const NATIVE_IMAGE_UPDATE_PATCH = "Native Image Update Patch";
+export const VIDEO_UPDATE_PATCH = "Video Update Patch";
 export const CHECKBOX_UPDATE_PATCH = "Checkbox Update Patch";
 export const TEXTBOX_UPDATE_PATCH = "Textbox Update Patch";
 export const DROPDOWN_UPDATE_PATCH = "Dropdown Update Patch";
@@ -160,6 +164,11 @@ export let SUPPORTED_OBJECTS = [{
         factory: () => new NativeImageUpdatePatch(),
         cleanUp: (patch: NativeImageUpdatePatch) => {patch.cleanUp()},
     },
+    {
+        name: VIDEO_UPDATE_PATCH,
+        factory: () => new VideoUpdatePatch(),
+        cleanUp: (patch: VideoUpdatePatch) => {patch.cleanUp()},
+    },
     {
         name: CHECKBOX_UPDATE_PATCH,
         factory: (objectManager: ObjectManager) => new CheckboxUpdatePatch(objectManager),
@@ -205,6 +214,11 @@ export let SUPPORTED_OBJECTS = [{
         factory: () => new NavigationPatch(),
         cleanUp: (patch: NavigationPatch) => {patch.cleanUp()},
     },
+    {
+        name: PRINT_PATCH,
+        factory: () => new PrintPatch(),
+        cleanUp: (patch: PrintPatch) => {patch.cleanUp()},
+    },
     {
         name: LAYER,
         factory: (objectManager: ObjectManager) => new Layer(objectManager),
@@ -39,6 +39,10 @@ export class PaxChassisWeb {
 */
   remove_context(id: string): void;
 /**
+* @param {string} capabilities
+*/
+  declare_capabilities(capabilities: string): void;
+/**
 * @param {string} native_interrupt
 * @param {any} additional_payload
 */
@@ -52,9 +56,20 @@ export class PaxChassisWeb {
 */
   deallocate(slice: MemorySlice): void;
 /**
-* @returns {MemorySlice}
 */
-  tick(): MemorySlice;
+  tick(): void;
+/**
+* @returns {MemorySlice | undefined}
+*/
+  next_message_chunk(): MemorySlice | undefined;
+/**
+* @param {number} bytes
+*/
+  set_message_chunk_bytes(bytes: number): void;
+/**
+* @returns {string}
+*/
+  message_chunk_stats(): string;
 /**
 */
   render(): void;
@@ -74,9 +89,13 @@ export interface InitOutput {
   readonly paxchassisweb_add_context: (a: number, b: number, c: number) => void;
   readonly paxchassisweb_send_viewport_update: (a: number, b: number, c: number) => void;
   readonly paxchassisweb_remove_context: (a: number, b: number, c: number) => void;
+  readonly paxchassisweb_declare_capabilities: (a: number, b: number, c: number) => void;
   readonly paxchassisweb_interrupt: (a: number, b: number, c: number, d: number) => void;
   readonly paxchassisweb_deallocate: (a: number, b: number) => void;
-  readonly paxchassisweb_tick: (a: number) => number;
+  readonly paxchassisweb_tick: (a: number) => void;
+  readonly paxchassisweb_next_message_chunk: (a: number) => number;
+  readonly paxchassisweb_set_message_chunk_bytes: (a: number, b: number) => void;
+  readonly paxchassisweb_message_chunk_stats: (a: number, b: number) => void;
   readonly __wbg_memoryslice_free: (a: number) => void;
   readonly memoryslice_ptr: (a: number) => number;
   readonly memoryslice_len: (a: number) => number;
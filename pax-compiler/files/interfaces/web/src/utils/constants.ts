@@ -9,3 +9,7 @@ export const CHECKBOX_CLASS = "checkbox-styles";
 export const RADIO_SET_CLASS = "radio-set-style";
 export const CLIPPING_CONTAINER = "clipping-container";
 export const BUTTON_TEXT_CONTAINER_CLASS = "button-text-container";
+// minimum time between playback position updates sent to the engine for a video
+export const VIDEO_TIME_UPDATE_INTERVAL_MS = 250;
+// seeks closer than this to the current playback position are ignored
+export const VIDEO_SEEK_TOLERANCE_SECS = 0.05;
@@ -1,5 +1,4 @@
-export async function readImageToByteBuffer(imagePath: string): Promise<{ pixels: Uint8ClampedArray, width: number, height: number }> {
-    let attempts = 8;
+export async function readImageToByteBuffer(imagePath: string, attempts: number = 8): Promise<{ pixels: Uint8ClampedArray, width: number, height: number }> {
     let delay = 100;
     while (attempts > 0) {
         const response = await fetch(imagePath);
@@ -0,0 +1,17 @@
+#![allow(unused_imports)]
+
+use pax_kit::*;
+
+#[pax]
+#[main]
+#[file("lib.pax")]
+pub struct Example {
+    pub num_clicks: Property<usize>,
+}
+
+impl Example {
+    pub fn increment(&mut self, _ctx: &NodeContext, _args: Event<Click>) {
+        let old_num_clicks = self.num_clicks.get();
+        self.num_clicks.set(old_num_clicks + 1);
+    }
+}
@@ -0,0 +1,26 @@
+#![allow(unused_imports)]
+
+use pax_kit::*;
+
+#[pax]
+#[main]
+#[file("lib.pax")]
+pub struct Example {
+    pub items: Property<Vec<String>>,
+}
+
+impl Example {
+    pub fn handle_mount(&mut self, _ctx: &NodeContext) {
+        self.items.set(vec![
+            "Header".to_string(),
+            "Content".to_string(),
+            "Footer".to_string(),
+        ]);
+    }
+
+    pub fn add_item(&mut self, _ctx: &NodeContext, _args: Event<Click>) {
+        let mut items = self.items.get();
+        items.push(format!("Item {}", items.len() + 1));
+        self.items.set(items);
+    }
+}
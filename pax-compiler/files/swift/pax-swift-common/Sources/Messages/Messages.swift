@@ -208,6 +208,44 @@ public class ImageLoadPatch {
     }
 }
 
+public class VideoUpdatePatch {
+    public var id: UInt32
+    public var source: String?
+    public var playing: Bool?
+    public var looping: Bool?
+    public var muted: Bool?
+    public var volume: Float?
+    public var current_time: Float?
+    public var fit: String?
+    public var size_x: Float?
+    public var size_y: Float?
+    public var transform: [Float]?
+
+    public init(fb:FlxbReference) {
+        self.id = fb["id"]!.asUInt32!
+        self.source = fb["source"]?.asString
+        self.playing = fb["playing"]?.asBool
+        self.looping = fb["looping"]?.asBool
+        self.muted = fb["muted"]?.asBool
+        self.volume = fb["volume"]?.asFloat
+        self.current_time = fb["current_time"]?.asFloat
+        self.fit = fb["fit"]?.asString
+        self.size_x = fb["size_x"]?.asFloat
+        self.size_y = fb["size_y"]?.asFloat
+        self.transform = fb["transform"]?.asVector?.makeIterator().map({ fb in
+            fb.asFloat!
+        })
+    }
+}
+
+public class PrintPatch {
+    public var target_id: String?
+
+    public init(fb:FlxbReference) {
+        self.target_id = fb["target_id"]?.asString
+    }
+}
+
 
 public class TextStyleMessage {
     public var font: FlxbReference
@@ -1,10 +1,14 @@
 use colored::Colorize;
 use serde_json::Value;
 
+use crate::errors::source_map::SourceMap;
 use crate::helpers::{
-    BUILD_DIR_NAME, DIR_IGNORE_LIST_MACOS, ERR_SPAWN, INTERFACE_DIR_NAME, PAX_BADGE,
+    add_feature_args, wait_with_output, wait_with_timeout, BUILD_DIR_NAME, DIR_IGNORE_LIST_MACOS,
+    ERR_SPAWN, INTERFACE_DIR_NAME,
 };
-use crate::{copy_dir_recursively, wait_with_output, RunContext, RunTarget};
+use crate::progress::Phase;
+use crate::workspace::CargoProject;
+use crate::{copy_dir_recursively, BuildArtifacts, RunContext, RunTarget, RunningApp, Verbosity};
 
 use color_eyre::eyre;
 use eyre::eyre;
@@ -32,16 +36,21 @@ const MACOS_MULTIARCH_PACKAGE_ID: &str = "macos-arm64_x86_64";
 const IOS_SIMULATOR_MULTIARCH_PACKAGE_ID: &str = "ios-arm64_x86_64-simulator";
 const IOS_PACKAGE_ID: &str = "ios-arm64";
 
+const IOS_BUNDLE_ID: &str = "dev.pax.pax-app-ios";
+
+/// Builds the app with `cargo` and `xcodebuild`, returning the built `.app` bundle
 pub fn build_apple_project_with_cartridge(
     ctx: &RunContext,
+    project: &CargoProject,
     pax_dir: &PathBuf,
     process_child_ids: Arc<Mutex<Vec<u64>>>,
-) -> Result<(), eyre::Report> {
+    source_map: &SourceMap,
+    features: &[String],
+) -> Result<PathBuf, eyre::Report> {
     let target: &RunTarget = &ctx.target;
     let target_str: &str = target.into();
     let target_str_lower = &target_str.to_lowercase();
     let pax_dir = PathBuf::from(pax_dir.to_str().unwrap());
-    let project_path = ctx.project_path.clone();
 
     let is_release: bool = ctx.is_release;
     let is_ios = if let RunTarget::iOS = target {
@@ -84,6 +93,7 @@ pub fn build_apple_project_with_cartridge(
         RUST_IOS_DYLIB_FILE_NAME
     };
 
+    let phase = ctx.progress.start(Phase::ChassisBuild);
     let mut handles = Vec::new();
 
     //(arch id, single-platform .dylib path, stdout/stderr from build)
@@ -96,17 +106,19 @@ pub fn build_apple_project_with_cartridge(
         .collect::<Vec<String>>()
         .join(", ")
         .bold();
-    println!(
-        "{} 🧶 Compiling targets {{{}}} in {} mode using {} threads...\n",
-        *PAX_BADGE,
-        &targets_single_string,
-        &build_mode_name.to_string().bold(),
-        target_mappings.len()
+    ctx.status(
+        Verbosity::Normal,
+        &format!(
+            "🧶 Compiling targets {{{}}} in {} mode using {} threads...",
+            &targets_single_string,
+            &build_mode_name.to_string().bold(),
+            target_mappings.len()
+        ),
     );
 
     let mut index = 0;
     for target_mapping in target_mappings {
-        let project_path = project_path.clone();
+        let project = project.clone();
         let pax_dir = pax_dir.clone();
 
         let process_child_ids_threadsafe = process_child_ids.clone();
@@ -118,13 +130,18 @@ pub fn build_apple_project_with_cartridge(
             "--features=ios"
         };
 
-        let handle = thread::spawn(move || {
-            let mut cmd = Command::new("cargo");
-
-            cmd.current_dir(&project_path)
-                .arg("build")
-                .arg("--color")
-                .arg("always")
+        let timeout = ctx.timeout;
+        let cancellation = ctx.cancellation.clone();
+        let extra_features = features.to_vec();
+        let mut cmd = ctx.cargo_command();
+        let color = ctx.cargo_color();
+        let message_format = ctx.cargo_message_format();
+        let handle = thread::spawn(move || -> Result<(), eyre::Report> {
+            cmd.current_dir(project.cargo_cwd()).arg("build");
+            project.add_package_args(&mut cmd);
+            cmd.arg("--color")
+                .arg(color)
+                .arg(message_format)
                 .arg("--target")
                 .arg(target_mapping.0)
                 .arg(arg_features)
@@ -135,6 +152,7 @@ pub fn build_apple_project_with_cartridge(
             if is_release {
                 cmd.arg("--release");
             }
+            add_feature_args(&mut cmd, &extra_features);
 
             #[cfg(unix)]
             unsafe {
@@ -144,10 +162,16 @@ pub fn build_apple_project_with_cartridge(
             let child = cmd.spawn().expect(ERR_SPAWN);
 
             //Execute `cargo build`, which generates our dylibs
-            let output = wait_with_output(&process_child_ids_threadsafe, child);
-
-            let dylib_src = project_path
-                .join("target")
+            let output = wait_with_timeout(
+                &process_child_ids_threadsafe,
+                child,
+                timeout,
+                &cancellation,
+                &format!("cargo build ({})", target_mapping.1),
+            )?;
+
+            let dylib_src = project
+                .target_dir()
                 .join(target_mapping.0)
                 .join(build_mode_name)
                 .join(dylib_file_name);
@@ -161,6 +185,7 @@ pub fn build_apple_project_with_cartridge(
                 .lock()
                 .unwrap()
                 .insert(index, new_val);
+            Ok(())
         });
         index = index + 1;
         handles.push(handle);
@@ -168,9 +193,9 @@ pub fn build_apple_project_with_cartridge(
 
     let mut index = 0;
     // Wait for all threads to complete and print their outputs
-    for handle in handles {
-        handle.join().unwrap();
-    }
+    let thread_results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+    // e.g. a timed out build step
+    thread_results.into_iter().collect::<Result<(), _>>()?;
 
     let results = build_results.lock().unwrap();
 
@@ -181,18 +206,18 @@ pub fn build_apple_project_with_cartridge(
         let target = &result.0;
         let output = &result.2;
 
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-
-        if stdout != "" || stderr != "" {
-            println!("{} build finished with output:", &target);
-        }
-        if stdout != "" {
-            println!("{}", &stdout);
-        }
-        if stderr != "" {
-            eprintln!("{}", &stderr);
-        }
+        // stdout carries cargo's JSON messages, rendered with errors in generated code remapped
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stdout = if ctx.prints(Verbosity::Verbose) {
+            source_map.remap_all_cargo_messages(&stdout)
+        } else {
+            source_map.remap_cargo_messages(&stdout)
+        };
+        ctx.child_output(
+            &format!("cargo build --target {}", target),
+            &format!("{}{}", String::from_utf8_lossy(&output.stderr), stdout),
+            !output.status.success(),
+        );
 
         if !output.status.success() {
             should_abort = true;
@@ -207,6 +232,7 @@ pub fn build_apple_project_with_cartridge(
         ));
     }
 
+    ctx.cancellation.check()?;
     // Update the `install name` of each Rust-built .dylib, instead of the default-output absolute file paths
     // embedded in each .dylib.  This allows our .dylibs to be portably embedded into an SPM module.
     let result = results.iter().try_for_each(|res: (&u32, &(String, String, Output))| {
@@ -222,7 +248,13 @@ pub fn build_apple_project_with_cartridge(
             cmd.pre_exec(crate::pre_exec_hook);
         }
         let child = cmd.spawn().unwrap();
-        let output = wait_with_output(&process_child_ids, child);
+        let output = wait_with_timeout(
+            &process_child_ids,
+            child,
+            ctx.timeout,
+            &ctx.cancellation,
+            "install_name_tool",
+        )?;
         if !output.status.success() {
             return Err(eyre!("Failed to rewrite dynamic library (path:{}) install name with install_name_tool.  Aborting.", dylib_path));
         }
@@ -269,9 +301,9 @@ pub fn build_apple_project_with_cartridge(
         // of multi-arch builds + xcframeworks for the Apple toolchain; we cannot bundle two
         // macos arch .frameworks in an xcframework; they must lipo'd into a single .framework + dylib.
         // Similarly, iOS binaries require a particular bundling for simulator & device builds.)
-        println!(
-            "{} 🖇️  Combining architecture-specific binaries with `lipo`...",
-            *PAX_BADGE
+        ctx.status(
+            Verbosity::Normal,
+            "🖇️  Combining architecture-specific binaries with `lipo`...",
         );
 
         if let RunTarget::macOS = target {
@@ -303,7 +335,13 @@ pub fn build_apple_project_with_cartridge(
                 lipo_command.pre_exec(crate::pre_exec_hook);
             }
             let child = lipo_command.spawn().expect(ERR_SPAWN);
-            let output = wait_with_output(&process_child_ids, child);
+            let output = wait_with_timeout(
+                &process_child_ids,
+                child,
+                ctx.timeout,
+                &ctx.cancellation,
+                "lipo",
+            )?;
 
             if !output.status.success() {
                 return Err(eyre!("Failed to combine packages with lipo. Aborting."));
@@ -346,7 +384,13 @@ pub fn build_apple_project_with_cartridge(
                 lipo_command.pre_exec(crate::pre_exec_hook);
             }
             let child = lipo_command.spawn().expect(ERR_SPAWN);
-            let output = wait_with_output(&process_child_ids, child);
+            let output = wait_with_timeout(
+                &process_child_ids,
+                child,
+                ctx.timeout,
+                &ctx.cancellation,
+                "lipo",
+            )?;
             if !output.status.success() {
                 return Err(eyre!("Failed to combine dylibs with lipo. Aborting."));
             }
@@ -422,7 +466,8 @@ Note that the temporary directories mentioned above are subject to overwriting.\
         "macosx"
     };
 
-    println!("{} 💻 Building xcodeproject...", *PAX_BADGE);
+    ctx.cancellation.check()?;
+    ctx.status(Verbosity::Normal, "💻 Building xcodeproject...");
     let mut cmd = Command::new("xcodebuild");
     cmd.arg("-configuration")
         .arg(configuration)
@@ -444,7 +489,7 @@ Note that the temporary directories mentioned above are subject to overwriting.\
             .arg("CODE_SIGN_IDENTITY=");
     }
 
-    if !ctx.verbose {
+    if !ctx.prints(Verbosity::Verbose) {
         cmd.arg("-quiet");
         cmd.arg("GCC_WARN_INHIBIT_ALL_WARNINGS=YES");
     }
@@ -454,7 +499,13 @@ Note that the temporary directories mentioned above are subject to overwriting.\
         cmd.pre_exec(crate::pre_exec_hook);
     }
     let child = cmd.spawn().expect(ERR_SPAWN);
-    let output = wait_with_output(&process_child_ids, child);
+    let output = wait_with_timeout(
+        &process_child_ids,
+        child,
+        ctx.timeout,
+        &ctx.cancellation,
+        "xcodebuild",
+    )?;
 
     // Crudely prune out noisy xcodebuild warnings due to an apparent xcode-internal bug at time of authoring, spitting out:
     //   Details:  createItemModels creation requirements should not create capability item model for a capability item model that already exists.
@@ -464,8 +515,9 @@ Note that the temporary directories mentioned above are subject to overwriting.\
     // If we get to a point where xcodebuild isn't spitting these errors, we can drop this block of code and just `.inherit` stderr in
     // the command above.
     let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-    if ctx.verbose {
-        println!("{}", stderr);
+    let mut shown = String::new();
+    if ctx.prints(Verbosity::Verbose) {
+        shown = stderr;
     } else {
         let mut skip_lines = 0;
         for line in stderr.lines() {
@@ -480,15 +532,19 @@ Note that the temporary directories mentioned above are subject to overwriting.\
                 continue;
             }
 
-            println!("{}", line);
+            shown.push_str(line);
+            shown.push('\n');
         }
     }
+    ctx.child_output("xcodebuild", &shown, !output.status.success());
 
     if !output.status.success() {
         return Err(eyre!("Failed to build project with xcodebuild. Aborting."));
     }
+    phase.done();
 
     //Copy build artifacts & packages into `build`
+    let phase = ctx.progress.start(Phase::Assets);
     let swift_cart_src = pax_dir
         .join(INTERFACE_DIR_NAME)
         .join("common")
@@ -539,70 +595,92 @@ Note that the temporary directories mentioned above are subject to overwriting.\
         &DIR_IGNORE_LIST_MACOS,
     );
 
-    // Start  `run` rather than a `build`
-    let target_str: &str = target.into();
-    if ctx.should_also_run {
-        println!("{} 🐇 Running Pax {}...", *PAX_BADGE, target_str);
-
-        if let RunTarget::macOS = target {
-            //
-            // Handle macOS `run`
-            //
-
-            let system_binary_path =
-                executable_dot_app_path.join(&format!("Contents/MacOS/{}", scheme));
+    let build_path = executable_output_dir_path.to_str().unwrap().bold();
+    phase.done();
+    ctx.status(
+        Verbosity::Normal,
+        &format!(
+            "🗂️  Done: {} {} build available at {}",
+            target_str, build_mode_name, build_path
+        ),
+    );
+    Ok(executable_dot_app_path)
+}
 
-            let status = Command::new(system_binary_path)
-                .status() // This will wait for the process to complete
-                .expect("failed to execute the app");
+/// Launches an app built by `build_apple_project_with_cartridge`: macOS apps are run directly, iOS
+/// apps are installed and launched on the newest iPhone simulator, which is booted first
+pub fn launch_apple_app(
+    ctx: &RunContext,
+    artifacts: &BuildArtifacts,
+) -> Result<RunningApp, eyre::Report> {
+    let process_child_ids = Arc::clone(&ctx.process_child_ids);
+    let target_str: &str = (&artifacts.target).into();
+    let executable_dot_app_path = &artifacts.app_path;
+    ctx.status(
+        Verbosity::Normal,
+        &format!("🐇 Running Pax {}...", target_str),
+    );
 
-            println!("App exited with: {:?}", status);
-        } else {
-            //
-            // Handle iOS `run`
-            //
-
-            // Get list of devices
-            let mut cmd = Command::new("xcrun");
-            cmd.arg("simctl")
-                .arg("list")
-                .arg("-j")
-                .arg("devices")
-                .arg("available")
-                .stdout(std::process::Stdio::piped());
+    if let RunTarget::macOS = artifacts.target {
+        //
+        // Handle macOS `run`
+        //
+
+        let scheme = executable_dot_app_path
+            .file_stem()
+            .ok_or_else(|| eyre!("Invalid app path {:?}", executable_dot_app_path))?;
+        let system_binary_path = executable_dot_app_path
+            .join("Contents")
+            .join("MacOS")
+            .join(scheme);
+        let child = Command::new(system_binary_path)
+            .spawn()
+            .map_err(|e| eyre!("Failed to execute the app: {}", e))?;
+        Ok(RunningApp::from_child(child, process_child_ids))
+    } else {
+        //
+        // Handle iOS `run`
+        //
+
+        // Get list of devices
+        let mut cmd = Command::new("xcrun");
+        cmd.arg("simctl")
+            .arg("list")
+            .arg("-j")
+            .arg("devices")
+            .arg("available")
+            .stdout(std::process::Stdio::piped());
 
-            #[cfg(unix)]
-            unsafe {
-                cmd.pre_exec(crate::pre_exec_hook);
-            }
-            let child = cmd.spawn().expect(ERR_SPAWN);
-            let output = wait_with_output(&process_child_ids, child);
-            let output_str = std::str::from_utf8(&output.stdout)
-                .map_err(|_| eyre!("Failed to parse stdout for xcrun"))?;
-            let parsed: Value = serde_json::from_str(&output_str)
-                .map_err(|_| eyre!("Failed to deserialize xcrun."))?;
-
-            // Extract devices
-            let devices = parsed["devices"].as_object().ok_or_else(|| {
-                return eyre!("Invalid JSON format for devices.");
-            })?;
-
-            let mut max_iphone_number = 0;
-            let mut desired_udid = None;
-
-            for (_, device_list) in devices {
-                if let Some(device_array) = device_list.as_array() {
-                    for device in device_array {
-                        if let Some(device_type) = device["deviceTypeIdentifier"].as_str() {
-                            if device_type
-                                .starts_with("com.apple.CoreSimulator.SimDeviceType.iPhone-")
-                            {
-                                if let Some(number) = device_type.split('-').last() {
-                                    if let Ok(number) = number.parse::<i32>() {
-                                        if number > max_iphone_number {
-                                            max_iphone_number = number;
-                                            desired_udid = device["udid"].as_str();
-                                        }
+        #[cfg(unix)]
+        unsafe {
+            cmd.pre_exec(crate::pre_exec_hook);
+        }
+        let child = cmd.spawn().expect(ERR_SPAWN);
+        let output = wait_with_output(&process_child_ids, child);
+        let output_str = std::str::from_utf8(&output.stdout)
+            .map_err(|_| eyre!("Failed to parse stdout for xcrun"))?;
+        let parsed: Value =
+            serde_json::from_str(&output_str).map_err(|_| eyre!("Failed to deserialize xcrun."))?;
+
+        // Extract devices
+        let devices = parsed["devices"].as_object().ok_or_else(|| {
+            return eyre!("Invalid JSON format for devices.");
+        })?;
+
+        let mut max_iphone_number = 0;
+        let mut desired_udid = None;
+
+        for (_, device_list) in devices {
+            if let Some(device_array) = device_list.as_array() {
+                for device in device_array {
+                    if let Some(device_type) = device["deviceTypeIdentifier"].as_str() {
+                        if device_type.starts_with("com.apple.CoreSimulator.SimDeviceType.iPhone-")
+                        {
+                            if let Some(number) = device_type.split('-').last() {
+                                if let Ok(number) = number.parse::<i32>() {
+                                    if number > max_iphone_number {
+                                        max_iphone_number = number;
+                                        desired_udid = device["udid"].as_str();
                                     }
                                 }
                             }
@@ -610,144 +688,147 @@ Note that the temporary directories mentioned above are subject to overwriting.\
                     }
                 }
             }
+        }
 
-            let device_udid = match desired_udid {
-                Some(udid) => udid,
-                None => {
-                    return Err(eyre!("No installed iOS simulators found on this system. Install at least one iPhone simulator through xcode and try again."));
-                }
-            };
-
-            // Open the Simulator app
-            let mut cmd = Command::new("open");
-            cmd.arg("-a")
-                .arg("Simulator")
-                .stdout(std::process::Stdio::piped())
-                .stderr(std::process::Stdio::piped());
-
-            #[cfg(unix)]
-            unsafe {
-                cmd.pre_exec(crate::pre_exec_hook);
-            }
-            let child = cmd.spawn().expect(ERR_SPAWN);
-            let output = wait_with_output(&process_child_ids, child);
-            if !output.status.success() {
-                return Err(eyre!("Error opening iOS simulator. Aborting."));
+        let device_udid = match desired_udid {
+            Some(udid) => udid,
+            None => {
+                return Err(eyre!("No installed iOS simulators found on this system. Install at least one iPhone simulator through xcode and try again."));
             }
+        };
 
-            // Boot current device
-            let mut cmd = Command::new("xcrun");
-            cmd.arg("simctl")
-                .arg("boot")
-                .arg(device_udid)
-                .stdout(std::process::Stdio::piped())
-                .stderr(std::process::Stdio::piped());
+        // Open the Simulator app
+        let mut cmd = Command::new("open");
+        cmd.arg("-a")
+            .arg("Simulator")
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped());
 
-            #[cfg(unix)]
-            unsafe {
-                cmd.pre_exec(crate::pre_exec_hook);
-            }
-            let child = cmd.spawn().expect(ERR_SPAWN);
-            let _output = wait_with_output(&process_child_ids, child);
-
-            // Boot the relevant simulator
-            let mut cmd = Command::new("xcrun");
-            cmd.arg("simctl")
-                .arg("spawn")
-                .arg(device_udid)
-                .arg("launchctl")
-                .arg("print")
-                .arg("system")
-                .stdout(std::process::Stdio::piped())
-                .stderr(std::process::Stdio::inherit());
+        #[cfg(unix)]
+        unsafe {
+            cmd.pre_exec(crate::pre_exec_hook);
+        }
+        let child = cmd.spawn().expect(ERR_SPAWN);
+        let output = wait_with_output(&process_child_ids, child);
+        if !output.status.success() {
+            return Err(eyre!("Error opening iOS simulator. Aborting."));
+        }
 
-            #[cfg(unix)]
-            unsafe {
-                cmd.pre_exec(crate::pre_exec_hook);
-            }
-            let child = cmd.spawn().expect(ERR_SPAWN);
-            let output = wait_with_output(&process_child_ids, child);
-            if !output.status.success() {
-                return Err(eyre!("Error spawning iOS simulator. Aborting."));
-            }
-            // ^ Note that we don't handle errors on this particular command; it will return an error by default
-            // if the simulator isn't running, which isn't an "error" for us.  Instead, defer to the following
-            // polling logic to decide whether the simulator failed to start, which would indeed be an error.
-
-            // After opening the simulator, wait for the simulator to be booted
-            let max_retries = 5;
-            let retry_period_secs = 5;
-            let mut retries = 0;
-
-            while !is_simulator_booted(device_udid, &process_child_ids) && retries < max_retries {
-                println!("{} 💤 Waiting for simulator to boot...", *PAX_BADGE);
-                std::thread::sleep(std::time::Duration::from_secs(retry_period_secs));
-                retries = retries + 1;
-            }
+        // Boot current device
+        let mut cmd = Command::new("xcrun");
+        cmd.arg("simctl")
+            .arg("boot")
+            .arg(device_udid)
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped());
 
-            if retries == max_retries {
-                return Err(eyre!(
-                    "Failed to boot the simulator within the expected time. Aborting."
-                ));
-            }
+        #[cfg(unix)]
+        unsafe {
+            cmd.pre_exec(crate::pre_exec_hook);
+        }
+        let child = cmd.spawn().expect(ERR_SPAWN);
+        let _output = wait_with_output(&process_child_ids, child);
+
+        // Boot the relevant simulator
+        let mut cmd = Command::new("xcrun");
+        cmd.arg("simctl")
+            .arg("spawn")
+            .arg(device_udid)
+            .arg("launchctl")
+            .arg("print")
+            .arg("system")
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::inherit());
 
-            // Install and run app on simulator
-            println!(
-                "{} 📤 Installing and running app from {} on simulator...",
-                *PAX_BADGE,
-                executable_output_dir_path.to_str().unwrap()
-            );
+        #[cfg(unix)]
+        unsafe {
+            cmd.pre_exec(crate::pre_exec_hook);
+        }
+        let child = cmd.spawn().expect(ERR_SPAWN);
+        let output = wait_with_output(&process_child_ids, child);
+        if !output.status.success() {
+            return Err(eyre!("Error spawning iOS simulator. Aborting."));
+        }
+        // ^ Note that we don't handle errors on this particular command; it will return an error by default
+        // if the simulator isn't running, which isn't an "error" for us.  Instead, defer to the following
+        // polling logic to decide whether the simulator failed to start, which would indeed be an error.
+
+        // After opening the simulator, wait for the simulator to be booted
+        let max_retries = 5;
+        let retry_period_secs = 5;
+        let mut retries = 0;
+
+        while !is_simulator_booted(device_udid, &process_child_ids) && retries < max_retries {
+            ctx.status(Verbosity::Normal, "💤 Waiting for simulator to boot...");
+            std::thread::sleep(std::time::Duration::from_secs(retry_period_secs));
+            retries = retries + 1;
+        }
 
-            let mut cmd = Command::new("xcrun");
-            cmd.arg("simctl")
-                .arg("install")
-                .arg(device_udid)
-                .arg(executable_dot_app_path)
-                .stdout(std::process::Stdio::piped())
-                .stderr(std::process::Stdio::piped());
+        if retries == max_retries {
+            return Err(eyre!(
+                "Failed to boot the simulator within the expected time. Aborting."
+            ));
+        }
 
-            #[cfg(unix)]
-            unsafe {
-                cmd.pre_exec(crate::pre_exec_hook);
-            }
-            let child = cmd.spawn().expect(ERR_SPAWN);
-            let output = wait_with_output(&process_child_ids, child);
-            if !output.status.success() {
-                return Err(eyre!("Error installing app on iOS simulator. Aborting."));
-            }
+        // Install and run app on simulator
+        ctx.status(
+            Verbosity::Normal,
+            &format!(
+                "📤 Installing and running app from {} on simulator...",
+                executable_dot_app_path.to_str().unwrap()
+            ),
+        );
 
-            let mut cmd = Command::new("xcrun");
-            cmd.arg("simctl")
-                .arg("launch")
-                .arg(device_udid)
-                .arg("dev.pax.pax-app-ios")
-                .stdout(std::process::Stdio::inherit())
-                .stderr(std::process::Stdio::inherit());
+        let mut cmd = Command::new("xcrun");
+        cmd.arg("simctl")
+            .arg("install")
+            .arg(device_udid)
+            .arg(executable_dot_app_path)
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped());
 
-            #[cfg(unix)]
-            unsafe {
-                cmd.pre_exec(crate::pre_exec_hook);
-            }
-            let child = cmd.spawn().expect(ERR_SPAWN);
-            let output = wait_with_output(&process_child_ids, child);
-            if !output.status.success() {
-                return Err(eyre!("Error launching app on iOS simulator. Aborting."));
-            }
-            let status = output.status.code().unwrap();
+        #[cfg(unix)]
+        unsafe {
+            cmd.pre_exec(crate::pre_exec_hook);
+        }
+        let child = cmd.spawn().expect(ERR_SPAWN);
+        let output = wait_with_output(&process_child_ids, child);
+        if !output.status.success() {
+            return Err(eyre!("Error installing app on iOS simulator. Aborting."));
+        }
 
-            println!(
-                "{} 🚀 App launched on simulator. Launch command exited with code: {:?}",
-                *PAX_BADGE, status
-            );
+        let mut cmd = Command::new("xcrun");
+        cmd.arg("simctl")
+            .arg("launch")
+            .arg(device_udid)
+            .arg(IOS_BUNDLE_ID)
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::inherit());
+
+        #[cfg(unix)]
+        unsafe {
+            cmd.pre_exec(crate::pre_exec_hook);
         }
-    } else {
-        let build_path = executable_output_dir_path.to_str().unwrap().bold();
-        println!(
-            "{} 🗂️  Done: {} {} build available at {}",
-            *PAX_BADGE, target_str, build_mode_name, build_path
-        );
+        let child = cmd.spawn().expect(ERR_SPAWN);
+        let output = wait_with_output(&process_child_ids, child);
+        if !output.status.success() {
+            return Err(eyre!("Error launching app on iOS simulator. Aborting."));
+        }
+        // `simctl launch` reports the launched app as `<bundle id>: <pid>`
+        let pid = String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .rsplit(": ")
+            .next()
+            .and_then(|pid| pid.parse().ok());
+
+        ctx.status(Verbosity::Normal, "🚀 App launched on simulator");
+        Ok(RunningApp::from_simulator(
+            device_udid,
+            IOS_BUNDLE_ID,
+            pid,
+            process_child_ids,
+        ))
     }
-    Ok(())
 }
 
 // This function checks if the simulator with the given UDID is booted
@@ -4,48 +4,66 @@
 //! The `build_chassis_with_cartridge` function is the main entrypoint
 
 use std::{
+    collections::HashSet,
     path::PathBuf,
     sync::{Arc, Mutex},
 };
 
 use color_eyre::eyre;
-use pax_manifest::PaxManifest;
 
-use crate::{RunContext, RunTarget};
+use crate::{errors::source_map::SourceMap, workspace::CargoProject, RunContext, RunTarget};
 
 use self::{apple::build_apple_project_with_cartridge, web::build_web_project_with_cartridge};
 
 pub mod apple;
+pub mod std_features;
 pub mod web;
 
 /// Runs `cargo build` (or `wasm-pack build`) with appropriate env in the directory
 /// of the generated chassis project inside the specified .pax dir
-/// Returns an output object containing bytestreams of stdout/stderr as well as an exit code
+/// Compiler diagnostics are printed with `source_map` applied, so that errors in generated
+/// code point at the `.pax` source that produced it.  `features` are the features of the app crate to
+/// enable, see `RunContext::extra_features` and [`std_features`].
+/// Returns the built app, see `BuildArtifacts::app_path`, and for web builds the interface
+/// directory it was assembled in
 pub fn build_project_with_cartridge(
     pax_dir: &PathBuf,
     ctx: &RunContext,
+    project: &CargoProject,
     process_child_ids: Arc<Mutex<Vec<u64>>>,
     assets_dirs: Vec<String>,
-    manifest: PaxManifest,
-) -> Result<Option<PathBuf>, eyre::Report> {
+    excluded_assets: &HashSet<PathBuf>,
+    source_map: &SourceMap,
+    features: &[String],
+) -> Result<(PathBuf, Option<PathBuf>), eyre::Report> {
     let target: &RunTarget = &ctx.target;
     let pax_dir = PathBuf::from(pax_dir.to_str().unwrap());
 
     //string together a shell call to build the userland project, with cartridge injected via macro
     match target {
         RunTarget::macOS | RunTarget::iOS => {
-            build_apple_project_with_cartridge(ctx, &pax_dir, process_child_ids)?;
-            Ok(None)
+            let app_path = build_apple_project_with_cartridge(
+                ctx,
+                project,
+                &pax_dir,
+                process_child_ids,
+                source_map,
+                features,
+            )?;
+            Ok((app_path, None))
         }
         RunTarget::Web => {
-            let fs = build_web_project_with_cartridge(
+            let (app_path, interface_dir) = build_web_project_with_cartridge(
                 ctx,
+                project,
                 &pax_dir,
                 process_child_ids,
                 assets_dirs,
-                manifest,
+                excluded_assets,
+                source_map,
+                features,
             )?;
-            Ok(Some(fs))
+            Ok((app_path, Some(interface_dir)))
         }
     }
 }
@@ -1,28 +1,35 @@
+use crate::errors::source_map::SourceMap;
 use crate::helpers::{
-    wait_with_output, ASSETS_DIR_NAME, BUILD_DIR_NAME, DIR_IGNORE_LIST_WEB, INTERFACE_DIR_NAME,
-    PAX_BADGE,
+    add_feature_args, set_cargo_env, wait_with_timeout, ASSETS_DIR_NAME, BUILD_DIR_NAME,
+    DIR_IGNORE_LIST_WEB, INTERFACE_DIR_NAME,
 };
-use crate::{copy_dir_recursively, RunContext, RunTarget};
+use crate::progress::{Phase, ProgressMode};
+use crate::workspace::CargoProject;
+use crate::{copy_dir_recursively, RunContext, RunTarget, Verbosity};
 
 use color_eyre::eyre;
+use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::sync::{Arc, Mutex};
 
-use dotenv::dotenv;
 use eyre::eyre;
-use pax_manifest::PaxManifest;
 #[cfg(unix)]
 use std::os::unix::process::CommandExt;
 
+/// Builds the app with `wasm-pack`, returning the directory of the built app under `.pax/build`,
+/// and the interface directory it was assembled in
 pub fn build_web_project_with_cartridge(
     ctx: &RunContext,
+    project: &CargoProject,
     pax_dir: &PathBuf,
     process_child_ids: Arc<Mutex<Vec<u64>>>,
     assets_dirs: Vec<String>,
-    manifest: PaxManifest, //used by designtime
-) -> Result<PathBuf, eyre::Report> {
+    excluded_assets: &HashSet<PathBuf>,
+    source_map: &SourceMap,
+    features: &[String],
+) -> Result<(PathBuf, PathBuf), eyre::Report> {
     let target: &RunTarget = &ctx.target;
     let target_str: &str = target.into();
     let target_str_lower = &target_str.to_lowercase();
@@ -33,10 +40,12 @@ pub fn build_web_project_with_cartridge(
 
     let interface_path = pax_dir.join(INTERFACE_DIR_NAME).join("web");
 
-    // wasm-pack build
+    // wasm-pack build.  wasm-pack has no `-p`; it selects the app crate by path instead
+    let phase = ctx.progress.start(Phase::ChassisBuild);
     let mut cmd = Command::new("wasm-pack");
-    cmd.current_dir(&ctx.project_path)
+    cmd.current_dir(project.cargo_cwd())
         .arg("build")
+        .arg(&project.crate_root)
         .arg("--target")
         .arg("web")
         .arg("--out-name")
@@ -51,8 +60,15 @@ pub fn build_web_project_with_cartridge(
         )
         .arg("--features=web")
         .env("PAX_DIR", &pax_dir)
-        .stdout(std::process::Stdio::inherit())
-        .stderr(std::process::Stdio::inherit());
+        .stdout(std::process::Stdio::piped());
+    // streamed as is into plain logs, captured otherwise
+    if ctx.progress.mode() == ProgressMode::Plain {
+        cmd.stderr(std::process::Stdio::inherit());
+    } else {
+        cmd.stderr(std::process::Stdio::piped());
+    }
+    set_cargo_env(&mut cmd, ctx.cargo_bin.as_deref(), ctx.toolchain.as_deref());
+    ctx.set_cargo_verbosity(&mut cmd);
 
     if is_release {
         cmd.arg("--release");
@@ -62,6 +78,9 @@ pub fn build_web_project_with_cartridge(
     if ctx.should_run_designer {
         cmd.arg("--features").arg("designer");
     }
+    add_feature_args(&mut cmd, features);
+    // passed through to cargo, so that diagnostics can be remapped through the source map
+    cmd.arg(ctx.cargo_message_format());
 
     #[cfg(unix)]
     unsafe {
@@ -75,12 +94,32 @@ pub fn build_web_project_with_cartridge(
     );
 
     // Execute wasm-pack build
-    let output = wait_with_output(&process_child_ids, child);
+    let output = wait_with_timeout(
+        &process_child_ids,
+        child,
+        ctx.timeout,
+        &ctx.cancellation,
+        "wasm-pack build",
+    )?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stdout = if ctx.prints(Verbosity::Verbose) {
+        source_map.remap_all_cargo_messages(&stdout)
+    } else {
+        source_map.remap_cargo_messages(&stdout)
+    };
+    ctx.child_output(
+        "wasm-pack build",
+        &format!("{}{}", String::from_utf8_lossy(&output.stderr), stdout),
+        !output.status.success(),
+    );
     if !output.status.success() {
         return Err(eyre!("failed to compile project with wasm-pack"));
     }
+    phase.done();
 
     // Copy assets
+    ctx.cancellation.check()?;
+    let phase = ctx.progress.start(Phase::Assets);
     let asset_dest = interface_path.join(ASSETS_DIR_NAME);
 
     // Create target assets directory
@@ -94,7 +133,7 @@ pub fn build_web_project_with_cartridge(
         // Check if the asset_src directory exists before attempting the copy
         if asset_src.exists() {
             // Perform recursive copy from userland `assets/` to built `assets/`
-            if let Err(e) = copy_dir_recursively(&asset_src, &asset_dest, &vec![]) {
+            if let Err(e) = copy_assets(&asset_src, &asset_dest, excluded_assets) {
                 return Err(eyre!("Error copying assets: {}", e));
             }
         }
@@ -108,6 +147,7 @@ pub fn build_web_project_with_cartridge(
         .join(target_str_lower);
 
     // Clean build dir
+    ctx.cancellation.check()?;
     let _ = fs::remove_dir_all(&build_dest);
 
     // Copy files to build dir
@@ -121,27 +161,33 @@ pub fn build_web_project_with_cartridge(
         );
     }
 
-    // Start local server if this is a `run` rather than a `build`
-    if ctx.should_also_run {
-        if ctx.should_run_designer {
-            println!("{} 🐇🎨 Running Pax Web with Pax Designer...", *PAX_BADGE);
-            dotenv().ok();
-            let _ = crate::design_server::start_server(
-                build_dest.to_str().unwrap(),
-                pax_dir.parent().unwrap().to_str().unwrap(),
-                manifest,
-            );
-        } else {
-            println!("{} 🐇 Running Pax Web...", *PAX_BADGE);
-            let _ = crate::design_server::static_server::start_server(build_dest);
-        }
-    } else {
-        println!(
-            "{} 🗂️ Done: {} build available at {}",
-            *PAX_BADGE,
+    phase.done();
+    ctx.status(
+        Verbosity::Normal,
+        &format!(
+            "🗂️ Done: {} build available at {}",
             build_mode_name,
             build_dest.to_str().unwrap()
-        );
+        ),
+    );
+    Ok((build_dest, build_src))
+}
+
+/// Like `copy_dir_recursively`, leaving out the files in `excluded` (assets tree shaking found unused)
+fn copy_assets(
+    src: &Path,
+    dest: &Path,
+    excluded: &HashSet<PathBuf>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if src.is_dir() {
+        fs::create_dir_all(dest)?;
+        for entry in fs::read_dir(src)? {
+            let path = entry?.path();
+            let dest_child = dest.join(path.file_name().ok_or("Invalid file name")?);
+            copy_assets(&path, &dest_child, excluded)?;
+        }
+    } else if !excluded.contains(src) {
+        fs::copy(src, dest)?;
     }
-    Ok(build_src)
+    Ok(())
 }
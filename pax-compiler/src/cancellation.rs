@@ -0,0 +1,78 @@
+//! # Cancellation
+//!
+//! Builds are cancelled cooperatively: [`crate::perform_build`] checks the
+//! [`CancellationToken`] of its `RunContext` between phases, and the build subprocesses it waits
+//! on are killed as soon as the token is cancelled.  A cancelled build fails with
+//! [`BuildError::BuildCancelled`], which callers superseding a build with a newer one can ignore.
+//! [`LatestBuild`] does the superseding for tooling that rebuilds on changes.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use color_eyre::eyre;
+
+use crate::errors::build_error::BuildError;
+use crate::helpers::ERR_LOCK;
+
+/// Shared flag cancelling the build it's passed to.  Clones share the flag, so a build can be
+/// cancelled from any thread through a clone of its token
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Fails with [`BuildError::BuildCancelled`] once cancelled, for checking between build phases
+    pub fn check(&self) -> eyre::Result<()> {
+        if self.is_cancelled() {
+            return Err(BuildError::BuildCancelled.into());
+        }
+        Ok(())
+    }
+}
+
+/// Runs builds one at a time, each superseding the one before: starting a build cancels the build
+/// in flight and waits for it to return, so that rapid successive changes restart the build
+/// instead of queueing builds or running them concurrently on the same target dir
+#[derive(Default)]
+pub struct LatestBuild {
+    latest: Mutex<CancellationToken>,
+    running: Mutex<()>,
+}
+
+impl LatestBuild {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the build in flight, if any, and runs `build` with the token cancelling it in turn
+    /// once it's returned.  Builds superseded while waiting return [`BuildError::BuildCancelled`]
+    /// as soon as they check their token
+    pub fn run<T>(&self, build: impl FnOnce(CancellationToken) -> T) -> T {
+        let cancellation = {
+            let mut latest = self.latest.lock().expect(ERR_LOCK);
+            latest.cancel();
+            *latest = CancellationToken::new();
+            latest.clone()
+        };
+        let _running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        build(cancellation)
+    }
+
+    /// Cancels the build in flight, if any, without starting another
+    pub fn cancel(&self) {
+        self.latest.lock().expect(ERR_LOCK).cancel();
+    }
+}
@@ -0,0 +1,104 @@
+//! # Cfg
+//!
+//! Template nodes with a `cfg` attribute are only part of builds whose target and features satisfy
+//! the condition, see [`pax_manifest::cfg`].  `CfgPass` checks that every condition is well-formed,
+//! then removes the nodes whose condition the build doesn't satisfy, along with their children, so
+//! that they're left out of the cartridge entirely.
+//!
+//! Designer builds keep every node, since the designer writes the manifest back to `.pax` source.
+//! The build's target and features are recorded in the manifest instead, and the runtime skips the
+//! nodes whose condition they don't satisfy when instantiating templates.
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use pax_manifest::cfg::{BuildCfg, CfgCondition};
+use pax_manifest::PaxManifest;
+
+/// `cfg` conditions of a manifest, and the nodes pruned because of them
+#[derive(Debug, Default)]
+pub struct CfgReport {
+    pub conditions: usize,
+    /// Nodes whose condition was false, removed along with their children
+    pub pruned_nodes: usize,
+    /// Features tested by conditions that weren't enabled
+    pub disabled_features: BTreeSet<String>,
+}
+
+impl CfgReport {
+    pub fn to_report_string(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "cfg conditions: {}, nodes pruned: {}",
+            self.conditions, self.pruned_nodes
+        );
+        if !self.disabled_features.is_empty() {
+            let disabled: Vec<_> = self.disabled_features.iter().map(String::as_str).collect();
+            let _ = writeln!(out, "cfg features not enabled: {}", disabled.join(", "));
+        }
+        out
+    }
+}
+
+pub struct CfgPass {
+    build_cfg: BuildCfg,
+}
+
+impl CfgPass {
+    pub fn new(build_cfg: BuildCfg) -> Self {
+        Self { build_cfg }
+    }
+
+    /// Checks the `cfg` conditions of the nodes of `manifest`, and records the build's target and
+    /// features in `manifest.build_cfg`.  If `prune`, also removes the nodes whose condition is false.
+    pub fn run(self, manifest: &mut PaxManifest, prune: bool) -> Result<CfgReport, String> {
+        let mut report = CfgReport::default();
+        let mut errors = BTreeSet::new();
+        for component in manifest.components.values_mut() {
+            let Some(template) = &mut component.template else {
+                continue;
+            };
+            let mut disabled = vec![];
+            for id in template.get_ids().into_iter().cloned().collect::<Vec<_>>() {
+                let Some(condition) = template.get_node(&id).and_then(|tnd| tnd.get_cfg()) else {
+                    continue;
+                };
+                report.conditions += 1;
+                match CfgCondition::parse(&condition) {
+                    Ok(parsed) => {
+                        report.disabled_features.extend(
+                            parsed
+                                .features()
+                                .into_iter()
+                                .filter(|f| !self.build_cfg.features.contains(*f))
+                                .map(str::to_string),
+                        );
+                        if !parsed.evaluate(&self.build_cfg) {
+                            disabled.push(id);
+                        }
+                    }
+                    Err(error) => {
+                        errors.insert(format!("In `{}`: {}", component.type_id, error));
+                    }
+                }
+            }
+            if prune {
+                for id in disabled {
+                    if template.get_node(&id).is_none() {
+                        // inside a node pruned before
+                        continue;
+                    }
+                    template.remove_node(id);
+                    report.pruned_nodes += 1;
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors.into_iter().collect::<Vec<_>>().join("\n"));
+        }
+        manifest.build_cfg = Some(self.build_cfg);
+        Ok(report)
+    }
+}
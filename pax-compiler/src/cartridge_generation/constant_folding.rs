@@ -0,0 +1,83 @@
+//! # Constant Folding
+//!
+//! PAXEL expressions like `{1 + 2}` or `{100.0 * 0.5}` reference no symbols, yet are wrapped in a
+//! computed `Property` and re-evaluated at runtime.  `ConstantFoldingPass` evaluates these
+//! expressions once at compiletime and replaces them with `ValueDefinition::LiteralValue`s.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use pax_lang::Computable;
+use pax_manifest::{
+    ExpressionInfo, LiteralBlockDefinition, PaxManifest, SettingElement, SettingsBlockElement,
+    ValueDefinition,
+};
+use pax_runtime_api::{Functions, PaxValue};
+
+#[derive(Default)]
+pub struct ConstantFoldingPass {
+    folded_count: usize,
+}
+
+impl ConstantFoldingPass {
+    /// Folds every constant expression found in template node settings and settings blocks
+    /// of `manifest`, in place.  Returns the number of expressions that were folded.
+    pub fn run(&mut self, manifest: &mut PaxManifest) -> usize {
+        // Operators are dispatched through the `Math` function registry
+        Functions::register_all_functions();
+
+        for component in manifest.components.values_mut() {
+            if let Some(template) = &mut component.template {
+                for tnd in template.get_nodes_mut() {
+                    if let Some(settings) = &mut tnd.settings {
+                        self.fold_setting_elements(settings);
+                    }
+                }
+            }
+            if let Some(settings) = &mut component.settings {
+                for element in settings.iter_mut() {
+                    if let SettingsBlockElement::SelectorBlock(_, block) = element {
+                        self.fold_block(block);
+                    }
+                }
+            }
+        }
+        self.folded_count
+    }
+
+    fn fold_setting_elements(&mut self, elements: &mut [SettingElement]) {
+        for element in elements.iter_mut() {
+            if let SettingElement::Setting(_, value) = element {
+                self.fold_value(value);
+            }
+        }
+    }
+
+    fn fold_block(&mut self, block: &mut LiteralBlockDefinition) {
+        self.fold_setting_elements(&mut block.elements);
+    }
+
+    fn fold_value(&mut self, value: &mut ValueDefinition) {
+        match value {
+            ValueDefinition::Expression(info) => {
+                if let Some(folded) = try_fold(info) {
+                    *value = ValueDefinition::LiteralValue(folded);
+                    self.folded_count += 1;
+                }
+            }
+            ValueDefinition::Block(block) => self.fold_block(block),
+            _ => {}
+        }
+    }
+}
+
+/// Evaluates `info` if it is built purely from literals, returning `None` if it isn't,
+/// or if evaluation fails (in which case the error is left to surface at runtime).  Operators
+/// on literals don't panic: integer overflow and division by zero fall back to floats
+pub(crate) fn try_fold(info: &ExpressionInfo) -> Option<PaxValue> {
+    if !info.dependencies.is_empty() || !info.expression.is_constant() {
+        return None;
+    }
+    let empty_resolver: Rc<HashMap<String, PaxValue>> = Rc::new(HashMap::new());
+    info.expression.compute(empty_resolver).ok()
+}
@@ -0,0 +1,299 @@
+//! # Defines
+//!
+//! Build-time defines, passed as `--define key=value` or listed under
+//! `[package.metadata.pax.defines]`, are visible to expressions as `$defines.key`, e.g. to ship
+//! one of two variants of a layout:
+//!
+//! ```pax
+//! if $defines.new_nav {
+//!     <NewNav/>
+//! }
+//! if !$defines.new_nav {
+//!     <OldNav/>
+//! }
+//! ```
+//!
+//! `DefinesPass` replaces `$defines.key` by the value of the define, then prunes the branches of
+//! the `if`s whose condition became constant, so that the losing variant is left out of the
+//! cartridge entirely.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use pax_lang::interpreter::PaxAccessor;
+use pax_manifest::{
+    ComponentTemplate, ExpressionInfo, LiteralBlockDefinition, PaxManifest, SettingElement,
+    SettingsBlockElement, TemplateNodeId, TreeIndexPosition, ValueDefinition,
+};
+use pax_runtime_api::{Functions, Numeric, PaxValue};
+
+use super::constant_folding::try_fold;
+
+/// Symbol under which defines are visible to expressions
+pub const DEFINES_SYMBOL: &str = "$defines";
+
+/// Parses a `key=value` define.  `true` and `false` are bools, integers and decimals are numbers
+/// and anything else is a string, optionally quoted to keep e.g. `"true"` a string
+pub fn parse_define(define: &str) -> Result<(String, PaxValue), String> {
+    let (key, value) = define
+        .split_once('=')
+        .ok_or_else(|| format!("Invalid define `{}`, expected `key=value`", define))?;
+    let key = key.trim();
+    let is_identifier = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !is_identifier {
+        return Err(format!(
+            "Invalid define name `{}`, expected letters, digits and underscores",
+            key
+        ));
+    }
+    Ok((key.to_string(), parse_define_value(value.trim())))
+}
+
+pub fn parse_define_value(value: &str) -> PaxValue {
+    let quoted = ['"', '\'']
+        .iter()
+        .any(|q| value.len() >= 2 && value.starts_with(*q) && value.ends_with(*q));
+    if quoted {
+        return PaxValue::String(value[1..value.len() - 1].to_string());
+    }
+    match value {
+        "true" => PaxValue::Bool(true),
+        "false" => PaxValue::Bool(false),
+        _ => {
+            if let Ok(i) = value.parse::<i64>() {
+                PaxValue::Numeric(Numeric::I64(i))
+            } else if let Ok(f) = value.parse::<f64>() {
+                PaxValue::Numeric(Numeric::F64(f))
+            } else {
+                PaxValue::String(value.to_string())
+            }
+        }
+    }
+}
+
+/// Defines used by a manifest, and the `if` branches pruned because of them
+#[derive(Default)]
+pub struct DefinesReport {
+    pub used: BTreeMap<String, PaxValue>,
+    pub unused: BTreeSet<String>,
+    /// `if`s whose condition was false, removed along with their content
+    pub pruned_branches: usize,
+    /// `if`s whose condition was true, replaced by their content
+    pub inlined_branches: usize,
+}
+
+impl DefinesReport {
+    pub fn to_report_string(&self) -> String {
+        let mut out = String::new();
+        if self.used.is_empty() {
+            let _ = writeln!(out, "defines used: none");
+        } else {
+            let _ = writeln!(out, "defines used:");
+            for (key, value) in &self.used {
+                let _ = writeln!(out, "  {} = {}", key, value);
+            }
+        }
+        if !self.unused.is_empty() {
+            let unused: Vec<_> = self.unused.iter().map(String::as_str).collect();
+            let _ = writeln!(out, "defines never referenced: {}", unused.join(", "));
+        }
+        let _ = writeln!(
+            out,
+            "`if` branches pruned: {}, inlined: {}",
+            self.pruned_branches, self.inlined_branches
+        );
+        out
+    }
+}
+
+pub struct DefinesPass<'a> {
+    defines: &'a BTreeMap<String, PaxValue>,
+    used: BTreeSet<String>,
+    errors: BTreeSet<String>,
+}
+
+impl<'a> DefinesPass<'a> {
+    pub fn new(defines: &'a BTreeMap<String, PaxValue>) -> Self {
+        Self {
+            defines,
+            used: BTreeSet::new(),
+            errors: BTreeSet::new(),
+        }
+    }
+
+    /// Checks that every `$defines.key` in `manifest` is defined, and records the defines used in
+    /// `manifest.defines`.  If `substitute`, also replaces them by their values and prunes the
+    /// `if` branches they decide.  Designer builds don't substitute, since the designer writes
+    /// the manifest back to `.pax` source: `$defines` is resolved at runtime instead.
+    pub fn run(
+        mut self,
+        manifest: &mut PaxManifest,
+        substitute: bool,
+    ) -> Result<DefinesReport, String> {
+        // Conditions are folded through the `Math` function registry, and this pass runs
+        // before `ConstantFoldingPass` has registered it
+        Functions::register_all_functions();
+
+        let mut report = DefinesReport::default();
+        for component in manifest.components.values_mut() {
+            if let Some(settings) = &mut component.settings {
+                for element in settings.iter_mut() {
+                    if let SettingsBlockElement::SelectorBlock(_, block) = element {
+                        self.visit_block(block, substitute);
+                    }
+                }
+            }
+            let Some(template) = &mut component.template else {
+                continue;
+            };
+            let mut decided = vec![];
+            for id in template.get_ids().into_iter().cloned().collect::<Vec<_>>() {
+                let Some(mut tnd) = template.get_node(&id).cloned() else {
+                    continue;
+                };
+                if let Some(settings) = &mut tnd.settings {
+                    self.visit_settings(settings, substitute);
+                }
+                if let Some(cfsd) = &mut tnd.control_flow_settings {
+                    for info in [
+                        &mut cfsd.slot_index_expression,
+                        &mut cfsd.repeat_source_expression,
+                    ]
+                    .into_iter()
+                    .flatten()
+                    {
+                        self.visit_expression(info, substitute);
+                    }
+                    if let Some(condition) = &mut cfsd.condition_expression {
+                        if self.visit_expression(condition, substitute) {
+                            if let Some(PaxValue::Bool(value)) = try_fold(condition) {
+                                decided.push((id.clone(), value));
+                            }
+                        }
+                    }
+                }
+                if substitute {
+                    template.set_node(id, tnd);
+                }
+            }
+            for (id, value) in decided {
+                if template.get_node(&id).is_none() {
+                    // inside a branch pruned before
+                    continue;
+                }
+                if value {
+                    inline_children(template, &id);
+                    report.inlined_branches += 1;
+                } else {
+                    template.remove_node(id);
+                    report.pruned_branches += 1;
+                }
+            }
+        }
+
+        if !self.errors.is_empty() {
+            return Err(self.errors.into_iter().collect::<Vec<_>>().join("\n"));
+        }
+        report.used = self
+            .used
+            .iter()
+            .map(|key| (key.clone(), self.defines[key].clone()))
+            .collect();
+        report.unused = self
+            .defines
+            .keys()
+            .filter(|key| !self.used.contains(*key))
+            .cloned()
+            .collect();
+        manifest.defines = report.used.clone();
+        Ok(report)
+    }
+
+    fn visit_settings(&mut self, elements: &mut Vec<SettingElement>, substitute: bool) {
+        for element in elements.iter_mut() {
+            if let SettingElement::Setting(_, value) = element {
+                match value {
+                    ValueDefinition::Expression(info) => {
+                        self.visit_expression(info, substitute);
+                    }
+                    ValueDefinition::Block(block) => self.visit_block(block, substitute),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn visit_block(&mut self, block: &mut LiteralBlockDefinition, substitute: bool) {
+        self.visit_settings(&mut block.elements, substitute);
+    }
+
+    /// Replaces the defines in `info` if `substitute`, returning whether it referenced any
+    fn visit_expression(&mut self, info: &mut ExpressionInfo, substitute: bool) -> bool {
+        let defines = self.defines;
+        let used = &mut self.used;
+        let mut expression = info.expression.clone();
+        let result = expression.replace_identifier(DEFINES_SYMBOL, &mut |accessors| {
+            let [PaxAccessor::Struct(key)] = accessors else {
+                return Err(format!(
+                    "`{}` must be followed by the name of a define, e.g. `{}.my_flag`, in `{}`",
+                    DEFINES_SYMBOL, DEFINES_SYMBOL, info
+                ));
+            };
+            let value = defines
+                .get(key)
+                .ok_or_else(|| undefined_define_message(key, defines))?;
+            used.insert(key.clone());
+            Ok(value.clone())
+        });
+        match result {
+            Ok(replaced) => {
+                if substitute {
+                    *info = ExpressionInfo::new(expression);
+                }
+                replaced
+            }
+            Err(error) => {
+                self.errors.insert(error);
+                false
+            }
+        }
+    }
+}
+
+fn undefined_define_message(key: &str, defines: &BTreeMap<String, PaxValue>) -> String {
+    let available = if defines.is_empty() {
+        "none (pass `--define key=value`, or list them under `[package.metadata.pax.defines]`)"
+            .to_string()
+    } else {
+        defines.keys().cloned().collect::<Vec<_>>().join(", ")
+    };
+    format!(
+        "`{}.{}` is not defined; available defines: {}",
+        DEFINES_SYMBOL, key, available
+    )
+}
+
+/// Replaces node `id` by its children
+fn inline_children(template: &mut ComponentTemplate, id: &TemplateNodeId) {
+    let Some(location) = template.get_location(id) else {
+        return;
+    };
+    let TreeIndexPosition::At(index) = location.index else {
+        return;
+    };
+    for (offset, child) in template
+        .get_children(id)
+        .unwrap_or_default()
+        .into_iter()
+        .enumerate()
+    {
+        let mut child_location = location.clone();
+        child_location.set_index(TreeIndexPosition::At(index + 1 + offset));
+        template.move_node(&child, child_location);
+    }
+    template.remove_node(id.clone());
+}
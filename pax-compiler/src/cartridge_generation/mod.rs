@@ -3,46 +3,152 @@
 //! The `code_generation` module provides structures and functions for generating Pax Cartridges
 //! from Pax Manifests. The `generate_and_overwrite_cartridge` function is the main entrypoint.
 
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
 use std::fs;
+use std::hash::{Hash, Hasher};
 
-use pax_manifest::{cartridge_generation::CommonProperty, PaxManifest};
+use pax_manifest::{
+    cartridge_generation::{CommonProperty, ComponentInfo},
+    constants::{DESIGNER_MANIFEST_ROLE, USERLAND_MANIFEST_ROLE},
+    PaxManifest, TaggedManifest,
+};
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+pub mod cfg;
+pub mod constant_folding;
+pub mod defines;
 pub mod templating;
+pub mod tree_shaking;
+pub mod unused_properties;
 
 pub const CARTRIDGE_PARTIAL_PATH: &str = "cartridge.partial.rs";
+/// Directory of `.pax` the component modules of a split cartridge are generated into
+pub const CARTRIDGE_COMPONENTS_DIR: &str = "cartridge";
+
+/// A generated `cartridge.partial.rs`
+pub struct GeneratedCartridge {
+    pub path: PathBuf,
+    /// Files under [`CARTRIDGE_COMPONENTS_DIR`] `include!`d by `path`, one per component, if the
+    /// cartridge was split
+    pub component_paths: Vec<PathBuf>,
+    /// Hash of the manifests baked into the cartridge, defines included, so that builds of
+    /// different variants of a project are told apart
+    pub manifest_hash: u64,
+}
 
 // Generates (codegens) the PaxCartridge definition, abiding by the PaxCartridge trait.
 // Side-effect: writes the generated string to disk as .pax/cartridge.partial.rs,
-// so that it may be `include!`d by the  #[pax] #[main] macro
+// so that it may be `include!`d by the  #[pax] #[main] macro.  The file is left untouched
+// if its content hasn't changed, so that cargo doesn't rebuild a cartridge that isn't stale.
+// With `split`, the factory of each component is instead generated into a module of its own under
+// .pax/cartridge/, which cartridge.partial.rs `include!`s, so that rustc can compile components in
+// parallel and incremental builds only recompile the modules of the components that changed
 pub fn generate_cartridge_partial_rs(
     pax_dir: &PathBuf,
     merged_manifest: &PaxManifest,
     userland_manifest: &PaxManifest,
     designer_manifest: Option<PaxManifest>,
-) -> PathBuf {
+    split: bool,
+) -> GeneratedCartridge {
+    let manifests = std::iter::once(TaggedManifest::new(
+        USERLAND_MANIFEST_ROLE,
+        userland_manifest,
+    ))
+    .chain(
+        designer_manifest
+            .as_ref()
+            .map(|m| TaggedManifest::new(DESIGNER_MANIFEST_ROLE, m)),
+    )
+    .collect::<Vec<_>>();
+    // going through `Value` sorts the keys of the manifests' hash maps, for a stable hash
+    let manifests_value = serde_json::to_value(&manifests).unwrap();
+    let mut hasher = DefaultHasher::new();
+    manifests_value.to_string().hash(&mut hasher);
+    let manifest_hash = hasher.finish();
+
+    let components = merged_manifest.generate_codegen_component_info();
+    let engine_import_path = userland_manifest.engine_import_path.clone();
+    let component_modules = if split {
+        generate_component_modules(pax_dir, &components, &engine_import_path)
+    } else {
+        vec![]
+    };
+
     //press template into String
     let generated_lib_rs = templating::press_template_codegen_cartridge_snippet(
         templating::TemplateArgsCodegenCartridgeSnippet {
             cartridge_struct_id: merged_manifest.get_main_cartridge_struct_id(),
             definition_to_instance_traverser_struct_id: merged_manifest
                 .get_main_definition_to_instance_traverser_struct_id(),
-            components: merged_manifest.generate_codegen_component_info(),
+            components,
+            component_modules: component_modules.clone(),
             common_properties: CommonProperty::get_as_common_property(),
             type_table: merged_manifest.type_table.clone(),
             is_designtime: cfg!(feature = "designtime"),
-            userland_manifest_json: serde_json::to_string(userland_manifest).unwrap(),
-            designer_manifest_json: if let Some(designer_manifest) = designer_manifest {
-                serde_json::to_string(&designer_manifest).unwrap()
-            } else {
-                "{}".to_string()
-            },
-            engine_import_path: userland_manifest.engine_import_path.clone(),
+            manifests_json: serde_json::to_string(&manifests).unwrap(),
+            engine_import_path,
         },
     );
+    let generated_lib_rs = format!(
+        "// pax manifest hash: {:016x}\n{}",
+        manifest_hash, generated_lib_rs
+    );
 
     let path = pax_dir.join(CARTRIDGE_PARTIAL_PATH);
-    fs::write(path.clone(), generated_lib_rs).unwrap();
-    path
+    write_if_changed(&path, &generated_lib_rs);
+    GeneratedCartridge {
+        path,
+        component_paths: component_modules
+            .into_iter()
+            .map(|module| module.path)
+            .collect(),
+        manifest_hash,
+    }
+}
+
+/// Generates the factory of each of `components` into its own file under
+/// [`CARTRIDGE_COMPONENTS_DIR`], removing the files of components that are gone
+fn generate_component_modules(
+    pax_dir: &Path,
+    components: &[ComponentInfo],
+    engine_import_path: &str,
+) -> Vec<templating::ComponentModule> {
+    let dir = pax_dir.join(CARTRIDGE_COMPONENTS_DIR);
+    fs::create_dir_all(&dir).unwrap();
+    let modules: Vec<_> = components
+        .iter()
+        .map(|component| {
+            let id = component.type_id.get_snake_case_id();
+            let module = templating::ComponentModule {
+                module_name: format!("component_{}", id),
+                factory_name: format!("{}Factory", component.pascal_identifier),
+                path: dir.join(format!("{}.rs", id)),
+            };
+            let generated = templating::press_template_codegen_component_module(
+                templating::TemplateArgsCodegenComponentModule {
+                    component,
+                    engine_import_path,
+                },
+            );
+            write_if_changed(&module.path, &generated);
+            module
+        })
+        .collect();
+
+    let generated_paths: HashSet<_> = modules.iter().map(|m| m.path.clone()).collect();
+    for entry in fs::read_dir(&dir).unwrap().flatten() {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "rs") && !generated_paths.contains(&path) {
+            let _ = fs::remove_file(path);
+        }
+    }
+    modules
+}
+
+fn write_if_changed(path: &Path, content: &str) {
+    if fs::read_to_string(path).ok().as_deref() != Some(content) {
+        fs::write(path, content).unwrap();
+    }
 }
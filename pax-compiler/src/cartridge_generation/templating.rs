@@ -4,6 +4,7 @@ use pax_runtime::api::serde::{Deserialize, Serialize};
 #[allow(unused_imports)]
 use serde_json;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use tera::{Context, Tera};
 
 use pax_manifest::{
@@ -13,6 +14,7 @@ use pax_manifest::{
 
 static TEMPLATE_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/templates/cartridge_generation");
 static CARTRIDGE_TEMPLATE: &str = "cartridge.tera";
+static COMPONENT_TEMPLATE: &str = "component.tera";
 static MACROS_TEMPLATE: &str = "macros.tera";
 
 #[serde_with::serde_as]
@@ -27,6 +29,10 @@ pub struct TemplateArgsCodegenCartridgeSnippet {
     // List of relevant component information for codegen (e.g handlers)
     pub components: Vec<ComponentInfo>,
 
+    // Modules the factories of `components` are generated into, for a split cartridge.  Empty if
+    // the factories are generated inline
+    pub component_modules: Vec<ComponentModule>,
+
     // Information about known common properties
     pub common_properties: Vec<CommonProperty>,
 
@@ -37,22 +43,52 @@ pub struct TemplateArgsCodegenCartridgeSnippet {
     // Whether this is a designtime cartridge
     pub is_designtime: bool,
 
-    // JSON string representation of the manifest, used at least for designtime builds
-    pub userland_manifest_json: String,
-
-    // JSON string representation of the manifest, used at least for designtime builds
-    pub designer_manifest_json: String,
+    // JSON list of the role-tagged manifests (userland, and designer for designtime builds)
+    pub manifests_json: String,
 
     /// Customizable import path for pax_engine, for codegen
     pub engine_import_path: String,
 }
 
+/// The module of a split cartridge that the factory of a component is generated into
+#[derive(Serialize, Clone)]
+pub struct ComponentModule {
+    pub module_name: String,
+    pub factory_name: String,
+    #[serde(serialize_with = "serialize_path_literal")]
+    pub path: PathBuf,
+}
+
+/// Serializes a path as a Rust string literal, for `include!`
+fn serialize_path_literal<S: serde::Serializer>(
+    path: &Path,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("{:?}", path.to_string_lossy()))
+}
+
+#[derive(Serialize)]
+pub struct TemplateArgsCodegenComponentModule<'a> {
+    pub component: &'a ComponentInfo,
+    pub engine_import_path: &'a str,
+}
+
 #[allow(unused)]
 static TEMPLATE_CODEGEN_CARTRIDGE_SNIPPET: &str =
     include_str!("../../templates/cartridge_generation/cartridge.tera");
 pub fn press_template_codegen_cartridge_snippet(
     args: TemplateArgsCodegenCartridgeSnippet,
 ) -> String {
+    press_template(CARTRIDGE_TEMPLATE, args)
+}
+
+/// Generates the module of a split cartridge holding the factory of a component, see
+/// [`ComponentModule`]
+pub fn press_template_codegen_component_module(args: TemplateArgsCodegenComponentModule) -> String {
+    press_template(COMPONENT_TEMPLATE, args)
+}
+
+fn press_template(template: &str, args: impl Serialize) -> String {
     let mut tera = Tera::default();
     tera.add_raw_template(
         MACROS_TEMPLATE,
@@ -65,15 +101,15 @@ pub fn press_template_codegen_cartridge_snippet(
     .expect("Failed to add macros.tera");
 
     tera.add_raw_template(
-        CARTRIDGE_TEMPLATE,
+        template,
         TEMPLATE_DIR
-            .get_file(CARTRIDGE_TEMPLATE)
+            .get_file(template)
             .unwrap()
             .contents_utf8()
             .unwrap(),
     )
-    .expect("Failed to add cartridge.tera");
+    .unwrap_or_else(|e| panic!("Failed to add {}: {}", template, e));
 
-    tera.render(CARTRIDGE_TEMPLATE, &Context::from_serialize(args).unwrap())
+    tera.render(template, &Context::from_serialize(args).unwrap())
         .expect("Failed to render template")
 }
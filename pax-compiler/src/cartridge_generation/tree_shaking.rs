@@ -0,0 +1,295 @@
+//! # Tree Shaking
+//!
+//! Release builds only need the components reachable from the main component, and the assets those
+//! components reference.  `TreeShakingPass` walks the component graph from a set of roots, following
+//! the components used in each template, and removes the components it never reaches from the
+//! manifest, so that their factories aren't generated into the cartridge.  The components the
+//! cartridge and engine construct themselves, see [`ENGINE_COMPONENTS`], are always roots.
+//!
+//! Components can also be constructed in ways the template graph doesn't show.  Any component whose
+//! name appears in an expression or string of a reachable component is conservatively kept, along with
+//! the reason, and components can be force-retained by listing them under `[package.metadata.pax]`
+//! `keep = ["MyComponent"]` in the app crate's `Cargo.toml`.
+
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use pax_manifest::constants::{BLANK_COMPONENT, UNRESOLVED_PLACEHOLDER};
+use pax_manifest::{
+    ComponentDefinition, PaxManifest, SettingElement, SettingsBlockElement, TypeId, ValueDefinition,
+};
+
+/// Components constructed by name rather than from a template: the cartridge's factory lookup
+/// returns `BlankComponent` for every blank component type, and the engine renders
+/// `UnresolvedPlaceholder` in place of nodes of unknown types
+pub const ENGINE_COMPONENTS: [&str; 2] = [BLANK_COMPONENT, UNRESOLVED_PLACEHOLDER];
+
+pub struct TreeShakingPass {
+    roots: Vec<TypeId>,
+    keep: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct TreeShakingReport {
+    pub excluded_components: Vec<TypeId>,
+    /// Unreachable components that were kept anyway, and why
+    pub retained_components: Vec<(TypeId, String)>,
+    /// Files in the asset directories not referenced by any reachable component, with their size
+    pub excluded_assets: Vec<(PathBuf, u64)>,
+}
+
+impl TreeShakingPass {
+    /// `keep` lists components to retain even if unreachable, by name or full type id
+    pub fn new(roots: Vec<TypeId>, keep: Vec<String>) -> Self {
+        Self { roots, keep }
+    }
+
+    /// Removes the components unreachable from the roots, or from [`ENGINE_COMPONENTS`], from
+    /// `manifest`, in place.  Struct-only components are types rather than nodes, and are always kept.
+    pub fn run(&self, manifest: &mut PaxManifest) -> TreeShakingReport {
+        let mut report = TreeShakingReport::default();
+        // names of the components, to look for in expressions and strings
+        let names: BTreeMap<String, TypeId> = manifest
+            .components
+            .values()
+            .filter(|c| !c.is_struct_only_component)
+            .filter_map(|c| Some((c.type_id.get_pascal_identifier()?, c.type_id.clone())))
+            .collect();
+
+        let mut reachable: HashSet<TypeId> = HashSet::new();
+        let mut scanned: HashSet<TypeId> = HashSet::new();
+        let mut queue: VecDeque<TypeId> = self.roots.iter().cloned().collect();
+        queue.extend(
+            ENGINE_COMPONENTS
+                .iter()
+                .filter_map(|name| names.get(*name))
+                .cloned(),
+        );
+        let mut keep_pending = true;
+        loop {
+            while let Some(type_id) = queue.pop_front() {
+                if !reachable.insert(type_id.clone()) {
+                    continue;
+                }
+                if let Some(component) = manifest.components.get(&type_id) {
+                    let used = component.template.iter().flat_map(|t| t.get_nodes());
+                    queue.extend(used.map(|tnd| tnd.type_id.clone()));
+                }
+            }
+
+            let unscanned: Vec<TypeId> = reachable.difference(&scanned).cloned().collect();
+            for type_id in unscanned {
+                scanned.insert(type_id.clone());
+                let Some(component) = manifest.components.get(&type_id) else {
+                    continue;
+                };
+                for text in component_text(component) {
+                    for word in text.split(|c: char| !c.is_alphanumeric() && c != '_') {
+                        let Some(named) = names.get(word) else {
+                            continue;
+                        };
+                        if reachable.contains(named) || queue.contains(named) {
+                            continue;
+                        }
+                        report.retained_components.push((
+                            named.clone(),
+                            format!(
+                                "named in `{}`, so it may be constructed dynamically",
+                                type_id
+                                    .get_pascal_identifier()
+                                    .unwrap_or_else(|| type_id.to_string())
+                            ),
+                        ));
+                        queue.push_back(named.clone());
+                    }
+                }
+            }
+
+            if queue.is_empty() && keep_pending {
+                keep_pending = false;
+                for component in manifest.components.values() {
+                    if self.is_kept(&component.type_id) && !reachable.contains(&component.type_id) {
+                        report
+                            .retained_components
+                            .push((component.type_id.clone(), "listed in `keep`".to_string()));
+                        queue.push_back(component.type_id.clone());
+                    }
+                }
+            }
+            if queue.is_empty() {
+                break;
+            }
+        }
+
+        manifest.components.retain(|type_id, component| {
+            let keep = component.is_struct_only_component || reachable.contains(type_id);
+            if !keep {
+                report.excluded_components.push(type_id.clone());
+            }
+            keep
+        });
+        report
+    }
+
+    fn is_kept(&self, type_id: &TypeId) -> bool {
+        let unique = type_id.get_unique_identifier();
+        let pascal = type_id.get_pascal_identifier();
+        self.keep
+            .iter()
+            .any(|k| *k == unique || Some(k) == pascal.as_ref())
+    }
+}
+
+impl TreeShakingReport {
+    /// Removes the components excluded by the pass from another manifest of the same project
+    pub fn apply(&self, manifest: &mut PaxManifest) {
+        for type_id in &self.excluded_components {
+            manifest.components.remove(type_id);
+        }
+    }
+
+    /// Collects the files in `assets_dirs` whose name appears neither in the remaining components or
+    /// tokens of `manifest` nor in the Rust sources under `src_dir`.  Paths built in Rust code can't be
+    /// followed, so any mention of the file name in Rust keeps it.
+    pub fn exclude_unreferenced_assets(
+        &mut self,
+        manifest: &PaxManifest,
+        assets_dirs: &[String],
+        src_dir: &Path,
+    ) {
+        let mut texts: Vec<String> = manifest
+            .components
+            .values()
+            .flat_map(component_text)
+            .collect();
+        // settings referencing a token, e.g. an image path, reference its value
+        texts.extend(manifest.tokens.values().map(|value| value.to_string()));
+        let mut sources = vec![];
+        collect_files(src_dir, &mut sources);
+        texts.extend(
+            sources
+                .iter()
+                .filter(|p| p.extension().is_some_and(|e| e == "rs"))
+                .filter_map(|p| fs::read_to_string(p).ok()),
+        );
+
+        let mut assets = vec![];
+        for dir in assets_dirs {
+            collect_files(Path::new(dir), &mut assets);
+        }
+        for asset in assets {
+            let Some(name) = asset.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if !texts.iter().any(|t| t.contains(name)) {
+                let size = fs::metadata(&asset).map(|m| m.len()).unwrap_or(0);
+                self.excluded_assets.push((asset, size));
+            }
+        }
+        self.excluded_assets.sort();
+    }
+
+    pub fn excluded_asset_paths(&self) -> HashSet<PathBuf> {
+        self.excluded_assets
+            .iter()
+            .map(|(p, _)| p.clone())
+            .collect()
+    }
+
+    /// e.g. "excluded 17 components, 4.2 MB of assets"
+    pub fn summary(&self) -> String {
+        let bytes: u64 = self.excluded_assets.iter().map(|(_, size)| size).sum();
+        format!(
+            "excluded {} components, {} of assets",
+            self.excluded_components.len(),
+            format_bytes(bytes)
+        )
+    }
+
+    /// Full listing of what was excluded and retained, for auditing
+    pub fn to_report_string(&self) -> String {
+        let mut out = format!("Tree shaking: {}\n", self.summary());
+        out.push_str("\nExcluded components:\n");
+        for type_id in &self.excluded_components {
+            out.push_str(&format!("  {}\n", type_id));
+        }
+        out.push_str("\nRetained unreachable components:\n");
+        for (type_id, reason) in &self.retained_components {
+            out.push_str(&format!("  {}: {}\n", type_id, reason));
+        }
+        out.push_str("\nExcluded assets:\n");
+        for (path, size) in &self.excluded_assets {
+            out.push_str(&format!("  {} ({})\n", path.display(), format_bytes(*size)));
+        }
+        out
+    }
+}
+
+/// Expressions, identifiers and literal values set anywhere in `component`
+fn component_text(component: &ComponentDefinition) -> Vec<String> {
+    let mut texts = vec![];
+    for tnd in component.template.iter().flat_map(|t| t.get_nodes()) {
+        if let Some(settings) = &tnd.settings {
+            settings_text(settings, &mut texts);
+        }
+        if let Some(cfsd) = &tnd.control_flow_settings {
+            let expressions = [
+                &cfsd.condition_expression,
+                &cfsd.slot_index_expression,
+                &cfsd.repeat_source_expression,
+            ];
+            texts.extend(expressions.into_iter().flatten().map(|e| e.to_string()));
+        }
+    }
+    for element in component.settings.iter().flatten() {
+        if let SettingsBlockElement::SelectorBlock(_, block) = element {
+            settings_text(&block.elements, &mut texts);
+        }
+    }
+    texts
+}
+
+fn settings_text(elements: &[SettingElement], texts: &mut Vec<String>) {
+    for element in elements {
+        let SettingElement::Setting(_, value) = element else {
+            continue;
+        };
+        match value {
+            ValueDefinition::LiteralValue(value) => texts.push(value.to_string()),
+            ValueDefinition::Expression(info) => texts.push(info.to_string()),
+            ValueDefinition::Identifier(identifier) => texts.push(identifier.name.clone()),
+            ValueDefinition::Block(block) => settings_text(&block.elements, texts),
+            _ => {}
+        }
+    }
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
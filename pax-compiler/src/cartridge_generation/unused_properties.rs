@@ -0,0 +1,103 @@
+//! # Unused Properties
+//!
+//! Finds component properties that no template ever sets, either inline (`<Foo bar=1/>`) or
+//! through a settings block selector (`#foo { bar: 1 }`).  Such properties tend to accumulate as
+//! components evolve.  Properties that are only meant to be set from Rust can be marked with
+//! `#[pax_allow(unused_property)]` to leave them out.
+
+use std::collections::HashSet;
+
+use pax_manifest::{
+    ComponentDefinition, PaxManifest, SettingElement, SettingsBlockElement, TypeId,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnusedProperty {
+    pub component_type_id: TypeId,
+    pub property_name: String,
+}
+
+/// Lists the properties of the components of `manifest`'s crate that aren't set by any template
+/// node in the manifest.  Only components of the crate of the main component are checked, since
+/// the properties of library components are set by other crates.  The main component (whose
+/// properties can't be set from a template), private `_`-prefixed properties and properties
+/// marked `#[pax_allow(unused_property)]` are skipped.
+pub fn detect_unused_properties(manifest: &PaxManifest) -> Vec<UnusedProperty> {
+    // (component, property) pairs set anywhere in the manifest
+    let mut set: HashSet<(&TypeId, &str)> = HashSet::new();
+    for component in manifest.components.values() {
+        let Some(template) = &component.template else {
+            continue;
+        };
+        for tnd in template.get_nodes() {
+            for key in setting_keys(tnd.settings.iter().flatten()) {
+                set.insert((&tnd.type_id, key));
+            }
+        }
+        // selector blocks can apply to any node of the template
+        let block_keys: Vec<&str> = component
+            .settings
+            .iter()
+            .flatten()
+            .filter_map(|element| match element {
+                SettingsBlockElement::SelectorBlock(_, block) => Some(&block.elements),
+                _ => None,
+            })
+            .flat_map(|elements| setting_keys(elements.iter()))
+            .collect();
+        for tnd in template.get_nodes() {
+            for &key in &block_keys {
+                set.insert((&tnd.type_id, key));
+            }
+        }
+    }
+
+    let main_crate = crate_name(&manifest.main_component_type_id);
+    let mut unused = vec![];
+    for component in manifest.components.values() {
+        if !is_checked(component, manifest, &main_crate) {
+            continue;
+        }
+        let Some(type_definition) = manifest.type_table.get(&component.type_id) else {
+            continue;
+        };
+        for property in &type_definition.property_definitions {
+            if property.name.starts_with('_')
+                || property.flags.allow_unused
+                || set.contains(&(&component.type_id, property.name.as_str()))
+            {
+                continue;
+            }
+            unused.push(UnusedProperty {
+                component_type_id: component.type_id.clone(),
+                property_name: property.name.clone(),
+            });
+        }
+    }
+    unused
+}
+
+fn setting_keys<'a>(elements: impl Iterator<Item = &'a SettingElement>) -> Vec<&'a str> {
+    elements
+        .filter_map(|element| match element {
+            SettingElement::Setting(key, _) => Some(key.token_value.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn is_checked(component: &ComponentDefinition, manifest: &PaxManifest, main_crate: &str) -> bool {
+    !component.is_main_component
+        && !component.is_primitive
+        && !component.is_struct_only_component
+        && component.type_id != manifest.main_component_type_id
+        && crate_name(&component.type_id) == main_crate
+}
+
+/// The first segment of the import path of `type_id`, e.g. `my_app` for `my_app::ui::Card`
+fn crate_name(type_id: &TypeId) -> String {
+    type_id
+        .import_path()
+        .and_then(|path| path.split("::").next().map(str::to_string))
+        .unwrap_or_default()
+}
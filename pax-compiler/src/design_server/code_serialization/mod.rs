@@ -2,7 +2,7 @@ use colored::Colorize;
 use core::panic;
 use serde_derive::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs::{self, File},
     io::{self, Write},
     path::{Path, PathBuf},
@@ -15,7 +15,8 @@ use tera::{Context, Tera};
 use include_dir::{include_dir, Dir};
 
 use pax_manifest::{
-    pax_runtime_api::PaxValue, ComponentDefinition, ExpressionInfo, PaxManifest, PaxType,
+    pax_runtime_api::PaxValue, ComponentDefinition, ComponentTemplate, ExpressionInfo, PaxManifest,
+    PaxType, TemplateNodeId,
 };
 
 use crate::{
@@ -93,7 +94,21 @@ pub fn press_code_serialization_template(args: ComponentDefinition) -> String {
     )
     .expect("Failed to add manifest-code-serialization.tera");
 
-    let context = Context::from_serialize(args).unwrap();
+    let mut context = Context::from_serialize(&args).unwrap();
+    if let Some(extension) = &args.extends {
+        // only what the component declares itself is written, the rest comes from the base
+        context.insert(
+            "extends",
+            &extension.base.get_pascal_identifier().unwrap_or_default(),
+        );
+        context.insert("settings", &extension.own_settings);
+        let overrides = args
+            .template
+            .as_ref()
+            .map(|template| own_subtrees(template, &extension.inherited_nodes))
+            .unwrap_or_default();
+        context.insert("overrides", &overrides);
+    }
 
     // Serialize component
     let template = tera
@@ -104,6 +119,32 @@ pub fn press_code_serialization_template(args: ComponentDefinition) -> String {
     format_pax_template(template).expect("Failed to format template")
 }
 
+/// Topmost nodes of `template` that aren't inherited, with their labels, in template order
+fn own_subtrees(
+    template: &ComponentTemplate,
+    inherited_nodes: &HashSet<TemplateNodeId>,
+) -> Vec<(TemplateNodeId, Option<String>)> {
+    let mut own = vec![];
+    let mut stack: Vec<TemplateNodeId> = template.get_root().into_iter().rev().collect();
+    while let Some(id) = stack.pop() {
+        if !inherited_nodes.contains(&id) {
+            let label = template
+                .get_node(&id)
+                .and_then(|node| node.get_template_label());
+            own.push((id, label));
+            continue;
+        }
+        stack.extend(
+            template
+                .get_children(&id)
+                .unwrap_or_default()
+                .into_iter()
+                .rev(),
+        );
+    }
+    own
+}
+
 fn print_diff(old_content: &str, new_content: &str, _file_path: &str) {
     let diff = TextDiff::from_lines(old_content, new_content);
     for change in diff.iter_all_changes() {
@@ -1,4 +1,5 @@
 use actix::Addr;
+use actix_web::dev::Server;
 use actix_web::middleware::Logger;
 
 use actix_web::web::Data;
@@ -14,15 +15,20 @@ use std::{env, fs};
 use env_logger;
 use std::io::Write;
 
+use crate::cancellation::{CancellationToken, LatestBuild};
 use crate::helpers::PAX_BADGE;
-use crate::{RunContext, RunTarget};
+use crate::progress::{ProgressMode, ProgressReporter};
+use crate::{BuildArtifacts, BuildError, RunContext, RunTarget, Verbosity};
 use notify::{Error, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
-use pax_manifest::PaxManifest;
+use pax_designtime::messages::NodeBounds;
+use pax_designtime::snippet::SnippetArchive;
+use pax_manifest::{PaxManifest, UniqueTemplateNodeIdentifier};
 
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use websocket::PrivilegedAgentWebSocket;
 
@@ -32,6 +38,41 @@ mod llm;
 pub mod static_server;
 pub mod websocket;
 
+/// File in the userland project root that the designer's workspace state (guides, grid settings,
+/// etc.) is persisted to
+pub const WORKSPACE_STATE_FILE_NAME: &str = ".pax-designer-workspace.json";
+
+/// File in the userland project root that the session being recorded in the designer is appended
+/// to, one JSON object per line, see `pax_designtime::orm::session`.  Replayable with `pax-cli replay`
+pub const SESSION_LOG_FILE_NAME: &str = ".pax-designer-session.jsonl";
+
+/// Prefix of the files in the userland project root that bug reports exported from the designer
+/// are saved to, followed by the time of the export
+pub const BUG_REPORT_FILE_PREFIX: &str = "pax-bug-report-";
+
+/// File in the user's `~/.pax` directory that the designer keymap is persisted to, shared
+/// between all of the user's projects
+pub const KEYMAP_FILE_NAME: &str = "designer-keymap.json";
+
+/// Path of the persisted designer keymap, `None` if the home directory of the user can't be determined
+fn keymap_path() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+    Some(PathBuf::from(home).join(".pax").join(KEYMAP_FILE_NAME))
+}
+
+/// Directory in the user's `~/.pax` directory that exported snippets are saved to, shared
+/// between all of the user's projects
+pub const SNIPPETS_DIR_NAME: &str = "snippets";
+
+/// Path of the user's snippet library, `None` if the home directory of the user can't be determined
+fn snippets_dir() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+    Some(PathBuf::from(home).join(".pax").join(SNIPPETS_DIR_NAME))
+}
+
+/// Largest snippet accepted by `/import_snippet`, assets included
+const MAX_SNIPPET_SIZE: usize = 64 * 1024 * 1024;
+
 pub struct AppState {
     serve_dir: Mutex<PathBuf>,
     userland_project_root: Mutex<PathBuf>,
@@ -39,6 +80,10 @@ pub struct AppState {
     request_id_counter: Mutex<usize>,
     manifest: Mutex<Option<PaxManifest>>,
     last_written_timestamp: Mutex<SystemTime>,
+    /// Layout read back from the running engine, keyed by template node
+    node_bounds: Mutex<HashMap<UniqueTemplateNodeIdentifier, Vec<NodeBounds>>>,
+    /// Rebuilds of the userland project, each superseding the one in flight
+    builds: LatestBuild,
 }
 
 impl AppState {
@@ -50,6 +95,8 @@ impl AppState {
             request_id_counter: Mutex::new(0),
             manifest: Mutex::new(None),
             last_written_timestamp: Mutex::new(UNIX_EPOCH),
+            node_bounds: Mutex::new(HashMap::new()),
+            builds: LatestBuild::new(),
         }
     }
     pub fn new(serve_dir: PathBuf, project_root: PathBuf, manifest: PaxManifest) -> Self {
@@ -60,6 +107,8 @@ impl AppState {
             request_id_counter: Mutex::new(0),
             manifest: Mutex::new(Some(manifest)),
             last_written_timestamp: Mutex::new(SystemTime::now()),
+            node_bounds: Mutex::new(HashMap::new()),
+            builds: LatestBuild::new(),
         }
     }
 
@@ -73,6 +122,28 @@ impl AppState {
         let mut last_written = self.last_written_timestamp.lock().unwrap();
         *last_written = SystemTime::now();
     }
+
+    pub fn get_node_bounds(&self, uni: &UniqueTemplateNodeIdentifier) -> Option<Vec<NodeBounds>> {
+        self.node_bounds.lock().unwrap().get(uni).cloned()
+    }
+
+    fn set_node_bounds(&self, uni: UniqueTemplateNodeIdentifier, bounds: Vec<NodeBounds>) {
+        self.node_bounds.lock().unwrap().insert(uni, bounds);
+    }
+
+    fn workspace_state_path(&self) -> PathBuf {
+        self.userland_project_root
+            .lock()
+            .unwrap()
+            .join(WORKSPACE_STATE_FILE_NAME)
+    }
+
+    fn session_log_path(&self) -> PathBuf {
+        self.userland_project_root
+            .lock()
+            .unwrap()
+            .join(SESSION_LOG_FILE_NAME)
+    }
 }
 
 #[get("/ws")]
@@ -86,17 +157,98 @@ pub async fn web_socket(
         .start()
 }
 
-#[allow(unused_assignments)]
-pub fn start_server(
+const NODE_BOUNDS_TIMEOUT: Duration = Duration::from_secs(2);
+const NODE_BOUNDS_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Reads back the computed layout of a template node from the running designtime.
+/// Responds with one `NodeBounds` per expanded instance of the node.
+#[post("/node_bounds")]
+pub async fn node_bounds(
+    uni: web::Json<UniqueTemplateNodeIdentifier>,
+    state: web::Data<AppState>,
+) -> HttpResponse {
+    let uni = uni.into_inner();
+    let Some(addr) = state.active_websocket_client.lock().unwrap().clone() else {
+        return HttpResponse::ServiceUnavailable().json(json!({
+            "status": "error",
+            "message": "no designtime is connected"
+        }));
+    };
+
+    // Drop any stale result so that we only return layout read back after this request
+    state.node_bounds.lock().unwrap().remove(&uni);
+    addr.do_send(NodeBoundsRequested { uni: uni.clone() });
+
+    let start = SystemTime::now();
+    while start.elapsed().unwrap_or_default() < NODE_BOUNDS_TIMEOUT {
+        if let Some(bounds) = state.get_node_bounds(&uni) {
+            return HttpResponse::Ok().json(bounds);
+        }
+        actix_web::rt::time::sleep(NODE_BOUNDS_POLL_INTERVAL).await;
+    }
+    HttpResponse::GatewayTimeout().json(json!({
+        "status": "error",
+        "message": "designtime did not respond with node bounds"
+    }))
+}
+
+/// Imports a snippet (the contents of a `.paxsnip` file) into the component being edited in the
+/// connected designer, e.g. for `pax-cli import-snippet`.
+#[post("/import_snippet")]
+pub async fn import_snippet(archive: web::Bytes, state: web::Data<AppState>) -> HttpResponse {
+    if let Err(e) = SnippetArchive::from_bytes(&archive) {
+        return HttpResponse::BadRequest().json(json!({
+            "status": "error",
+            "message": format!("invalid snippet: {}", e)
+        }));
+    }
+    let Some(addr) = state.active_websocket_client.lock().unwrap().clone() else {
+        return HttpResponse::ServiceUnavailable().json(json!({
+            "status": "error",
+            "message": "no designtime is connected"
+        }));
+    };
+    addr.do_send(SnippetImportRequested {
+        archive: archive.to_vec(),
+    });
+    HttpResponse::Ok().json(json!({
+        "status": "success",
+        "message": "snippet sent to the designer, see the designer for anything that was skipped"
+    }))
+}
+
+/// A server bound to its port, which serves once its `server` is awaited, see `crate::perform_run`
+pub struct BoundServer {
+    pub server: Server,
+    pub port: u16,
+    /// Watches the userland project for the design server, as long as it's kept alive
+    pub watcher: Option<RecommendedWatcher>,
+}
+
+/// Routes actix' request logs through the pax badge.  Idempotent, since a process may run
+/// several servers one after another
+pub(crate) fn init_logging() {
+    std::env::set_var("RUST_LOG", "actix_web=info");
+    let _ = env_logger::Builder::from_env(env_logger::Env::default())
+        .format(|buf, record| writeln!(buf, "{} 🍱 Served {}", *PAX_BADGE, record.args()))
+        .try_init();
+}
+
+/// A listener on the first port from 8080 up that's free on localhost
+pub(crate) fn listen_on_free_port() -> std::io::Result<TcpListener> {
+    (8080..=u16::MAX)
+        .find_map(|port| TcpListener::bind(("127.0.0.1", port)).ok())
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::AddrInUse, "no free port"))
+}
+
+/// Binds the design server, serving the designer build at `static_file_path` while watching
+/// `src_folder_to_watch` for changes.  Must be called from within an actix system
+pub fn bind_server(
     static_file_path: &str,
     src_folder_to_watch: &str,
     manifest: PaxManifest,
-) -> std::io::Result<()> {
-    // Initialize logging
-    std::env::set_var("RUST_LOG", "actix_web=info");
-    env_logger::Builder::from_env(env_logger::Env::default())
-        .format(|buf, record| writeln!(buf, "{} 🍱 Served {}", *PAX_BADGE, record.args()))
-        .init();
+) -> std::io::Result<BoundServer> {
+    init_logging();
 
     let initial_state = AppState::new(
         PathBuf::from(static_file_path),
@@ -105,47 +257,40 @@ pub fn start_server(
     );
     let fs_path = initial_state.serve_dir.lock().unwrap().clone();
     let state = Data::new(initial_state);
-    let _watcher = setup_file_watcher(state.clone(), src_folder_to_watch)
+    let watcher = setup_file_watcher(state.clone(), src_folder_to_watch)
         .expect("Failed to setup file watcher");
 
-    // Create a Runtime
-    let runtime = actix_web::rt::System::new().block_on(async {
-        let mut port = 8080;
-        let server = loop {
-            // Check if the port is available
-            if TcpListener::bind(("127.0.0.1", port)).is_ok() {
-                // Log the server details
-                println!(
-                    "{} 🗂️  Serving static files from {}",
-                    *PAX_BADGE,
-                    &fs_path.to_str().unwrap()
-                );
-                let address_msg = format!("http://127.0.0.1:{}", port).blue();
-                let server_running_at_msg = format!("Server running at {}", address_msg).bold();
-                println!("{} 📠 {}", *PAX_BADGE, server_running_at_msg);
-                break HttpServer::new(move || {
-                    App::new()
-                        .wrap(Logger::new("| %s | %U"))
-                        .app_data(state.clone())
-                        .service(ai_page)
-                        .service(ai_submit)
-                        .service(web_socket)
-                        .service(
-                            actix_files::Files::new("/*", fs_path.clone()).index_file("index.html"),
-                        )
-                })
-                .bind(("127.0.0.1", port))
-                .expect("Error binding to address")
-                .workers(2);
-            } else {
-                port += 1; // Try the next port
-            }
-        };
-
-        server.run().await
-    });
+    let listener = listen_on_free_port()?;
+    let port = listener.local_addr()?.port();
+    println!(
+        "{} 🗂️  Serving static files from {}",
+        *PAX_BADGE,
+        &fs_path.to_str().unwrap()
+    );
+    let address_msg = format!("http://127.0.0.1:{}", port).blue();
+    let server_running_at_msg = format!("Server running at {}", address_msg).bold();
+    println!("{} 📠 {}", *PAX_BADGE, server_running_at_msg);
+    let server = HttpServer::new(move || {
+        App::new()
+            .wrap(Logger::new("| %s | %U"))
+            .app_data(state.clone())
+            .app_data(web::PayloadConfig::new(MAX_SNIPPET_SIZE))
+            .service(ai_page)
+            .service(ai_submit)
+            .service(web_socket)
+            .service(node_bounds)
+            .service(import_snippet)
+            .service(actix_files::Files::new("/*", fs_path.clone()).index_file("index.html"))
+    })
+    .listen(listener)?
+    .workers(2)
+    .run();
 
-    runtime
+    Ok(BoundServer {
+        server,
+        port,
+        watcher: Some(watcher),
+    })
 }
 
 #[derive(Default)]
@@ -166,6 +311,22 @@ impl actix::Message for WatcherFileChanged {
     type Result = ();
 }
 
+struct NodeBoundsRequested {
+    pub uni: UniqueTemplateNodeIdentifier,
+}
+
+impl actix::Message for NodeBoundsRequested {
+    type Result = ();
+}
+
+struct SnippetImportRequested {
+    pub archive: Vec<u8>,
+}
+
+impl actix::Message for SnippetImportRequested {
+    type Result = ();
+}
+
 pub fn setup_file_watcher(state: Data<AppState>, path: &str) -> Result<RecommendedWatcher, Error> {
     let mut watcher = RecommendedWatcher::new(
         move |res: Result<Event, Error>| match res {
@@ -271,29 +432,53 @@ struct AiMessage {
     message: String,
 }
 
-fn create_designer_run_context() -> RunContext {
+fn create_designer_run_context(cancellation: CancellationToken) -> RunContext {
     RunContext {
         target: RunTarget::Web,
         project_path: PathBuf::from("../pax-designer".to_string()),
-        verbose: false,
-        should_also_run: false,
+        verbosity: Verbosity::Normal,
         is_libdev_mode: true,
         should_run_designer: true,
         process_child_ids: Arc::new(Mutex::new(vec![])),
         is_release: false,
+        timeout: crate::helpers::default_build_timeout(),
+        cargo_bin: None,
+        toolchain: None,
+        defines: BTreeMap::new(),
+        extra_features: vec![],
+        emit_manifest: None,
+        split_cartridge: false,
+        cancellation,
+        progress: ProgressReporter::new(ProgressMode::detect()),
     }
 }
 
-fn perform_build() -> std::io::Result<(PaxManifest, Option<PathBuf>)> {
-    let ctx = create_designer_run_context();
-    crate::perform_build(&ctx).map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
+/// Rebuilds the project, superseding the rebuild in flight.  `None` if this rebuild was
+/// superseded in turn
+fn perform_build(state: &AppState) -> std::io::Result<Option<BuildArtifacts>> {
+    let result = state
+        .builds
+        .run(|cancellation| crate::perform_build(&create_designer_run_context(cancellation)));
+    match result {
+        Ok(artifacts) => Ok(Some(artifacts)),
+        Err(e) if BuildError::is_cancellation(&e) => Ok(None),
+        Err(e) => Err(std::io::Error::other(e)),
+    }
 }
 
 fn perform_build_and_update_state(state: &AppState, folder_to_watch: &str) -> std::io::Result<()> {
-    let (manifest, fs_path) = perform_build()?;
+    let Some(BuildArtifacts {
+        manifest,
+        interface_dir,
+        ..
+    }) = perform_build(state)?
+    else {
+        // the newer build updates the state instead
+        return Ok(());
+    };
 
     // Update the state
-    *state.serve_dir.lock().unwrap() = fs_path.expect("serve directory should exist");
+    *state.serve_dir.lock().unwrap() = interface_dir.expect("serve directory should exist");
     *state.userland_project_root.lock().unwrap() = PathBuf::from_str(folder_to_watch).unwrap();
     *state.manifest.lock().unwrap() = Some(manifest);
 
@@ -1,49 +1,37 @@
+use crate::design_server::{init_logging, listen_on_free_port, BoundServer};
 use crate::helpers::PAX_BADGE;
 use actix_web::middleware::Logger;
 use actix_web::{App, HttpServer};
 use colored::Colorize;
-use env_logger;
-use std::io::Write;
-use std::net::TcpListener;
 use std::path::PathBuf;
 
-pub fn start_server(fs_path: PathBuf) -> std::io::Result<()> {
-    // Initialize logging
-    std::env::set_var("RUST_LOG", "actix_web=info");
-    env_logger::Builder::from_env(env_logger::Env::default())
-        .format(|buf, record| writeln!(buf, "{} 🍱 Served {}", *PAX_BADGE, record.args()))
-        .init();
+/// Binds a server for the static files of a build at `fs_path`.  Must be called from within an
+/// actix system
+pub fn bind_server(fs_path: PathBuf) -> std::io::Result<BoundServer> {
+    init_logging();
 
-    // Create a Runtime
-    let runtime = actix_web::rt::System::new().block_on(async {
-        let mut port = 8080;
-        let server = loop {
-            // Check if the port is available
-            if TcpListener::bind(("127.0.0.1", port)).is_ok() {
-                // Log the server details
-                println!(
-                    "{} 🗂️  Serving static files from {}",
-                    *PAX_BADGE,
-                    &fs_path.to_str().unwrap()
-                );
-                let address_msg = format!("http://127.0.0.1:{}", port).blue();
-                let server_running_at_msg = format!("Server running at {}", address_msg).bold();
-                println!("{} 📠 {}", *PAX_BADGE, server_running_at_msg);
-                break HttpServer::new(move || {
-                    App::new().wrap(Logger::new("| %s | %U")).service(
-                        actix_files::Files::new("/*", fs_path.clone()).index_file("index.html"),
-                    )
-                })
-                .bind(("127.0.0.1", port))
-                .expect("Error binding to address")
-                .workers(2);
-            } else {
-                port += 1; // Try the next port
-            }
-        };
+    let listener = listen_on_free_port()?;
+    let port = listener.local_addr()?.port();
+    println!(
+        "{} 🗂️  Serving static files from {}",
+        *PAX_BADGE,
+        &fs_path.to_str().unwrap()
+    );
+    let address_msg = format!("http://127.0.0.1:{}", port).blue();
+    let server_running_at_msg = format!("Server running at {}", address_msg).bold();
+    println!("{} 📠 {}", *PAX_BADGE, server_running_at_msg);
+    let server = HttpServer::new(move || {
+        App::new()
+            .wrap(Logger::new("| %s | %U"))
+            .service(actix_files::Files::new("/*", fs_path.clone()).index_file("index.html"))
+    })
+    .listen(listener)?
+    .workers(2)
+    .run();
 
-        server.run().await
-    });
-
-    runtime
+    Ok(BoundServer {
+        server,
+        port,
+        watcher: None,
+    })
 }
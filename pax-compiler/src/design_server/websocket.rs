@@ -1,5 +1,7 @@
 use crate::design_server::{
-    code_serialization::serialize_component_to_file, AppState, FileContent, WatcherFileChanged,
+    code_serialization::serialize_component_to_file, keymap_path, snippets_dir, AppState,
+    FileContent, NodeBoundsRequested, SnippetImportRequested, WatcherFileChanged,
+    BUG_REPORT_FILE_PREFIX,
 };
 
 use pax_manifest::parsing::TemplateNodeParseContext;
@@ -8,12 +10,20 @@ use actix::{Actor, AsyncContext, Handler, Running, StreamHandler};
 use actix_web::web::Data;
 use actix_web_actors::ws::{self};
 use pax_designtime::messages::{
-    AgentMessage, ComponentSerializationRequest, FileChangedNotification,
-    LoadFileToStaticDirRequest, LoadManifestResponse, ManifestSerializationRequest,
-    UpdateTemplateRequest,
+    AgentMessage, AppendSessionLogRequest, BugReportStatusNotification,
+    ComponentSerializationRequest, ExportBugReportRequest, ExportSnippetRequest,
+    FileChangedNotification, ImportSnippetRequest, ListSnippetsResponse,
+    LoadFileToStaticDirRequest, LoadKeymapResponse, LoadManifestComponents, LoadManifestHeader,
+    LoadSnippetRequest, LoadWorkspaceStateResponse, ManifestSerializationRequest,
+    NodeBoundsRequest, NodeBoundsResponse, SaveKeymapRequest, SaveWorkspaceStateRequest,
+    SnippetStatusNotification, UpdateTemplateRequest,
 };
+use pax_designtime::snippet::{SnippetArchive, SNIPPET_FILE_EXTENSION};
 use pax_manifest::{ComponentDefinition, ComponentTemplate, PaxManifest, TypeId};
 use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use self::socket_message_accumulator::SocketMessageAccumulator;
 
@@ -76,12 +86,24 @@ impl Handler<WatcherFileChanged> for PrivilegedAgentWebSocket {
 
                     if let Some(self_type_id) = matched_component {
                         let original_template = original_template.unwrap();
+                        // components extending another one start from a copy of its template
+                        let base_template = manifest.components[&self_type_id]
+                            .extends
+                            .as_ref()
+                            .and_then(|extension| manifest.components.get(&extension.base))
+                            .and_then(|base| base.template.clone());
                         let mut tpc = TemplateNodeParseContext {
                             pascal_identifier_to_type_id_map: template_map,
-                            template: ComponentTemplate::new(
-                                self_type_id.clone(),
-                                original_template.get_file_path(),
-                            ),
+                            template: match &base_template {
+                                Some(base_template) => base_template.extended_by(
+                                    self_type_id.clone(),
+                                    original_template.get_file_path(),
+                                ),
+                                None => ComponentTemplate::new(
+                                    self_type_id.clone(),
+                                    original_template.get_file_path(),
+                                ),
+                            },
                         };
 
                         let ast = pax_lang::parse_pax_str(
@@ -93,17 +115,32 @@ impl Handler<WatcherFileChanged> for PrivilegedAgentWebSocket {
                             pax_manifest::parsing::parse_settings_from_component_definition_string(
                                 ast.clone(),
                             );
-                        pax_manifest::parsing::parse_template_from_component_definition_string(
-                            &mut tpc,
-                            &content,
-                            ast.clone(),
-                        );
+                        if let Err(e) =
+                            pax_manifest::parsing::parse_template_from_component_definition_string(
+                                &mut tpc,
+                                &content,
+                                ast.clone(),
+                            )
+                        {
+                            eprintln!("{}", e);
+                            return;
+                        }
 
                         let new_template = tpc.template;
 
                         // update the manifest with this new template
                         let comp = manifest.components.get_mut(&self_type_id).unwrap();
                         comp.template = Some(new_template.clone());
+                        if let (Some(extension), Some(base_template)) =
+                            (&mut comp.extends, &base_template)
+                        {
+                            extension.inherited_nodes = new_template
+                                .get_ids()
+                                .into_iter()
+                                .filter(|id| id.as_usize() < base_template.get_next_id())
+                                .cloned()
+                                .collect();
+                        }
                         let msg =
                             AgentMessage::UpdateTemplateRequest(Box::new(UpdateTemplateRequest {
                                 type_id: self_type_id,
@@ -123,6 +160,26 @@ impl Handler<WatcherFileChanged> for PrivilegedAgentWebSocket {
     }
 }
 
+impl Handler<NodeBoundsRequested> for PrivilegedAgentWebSocket {
+    type Result = ();
+
+    fn handle(&mut self, msg: NodeBoundsRequested, ctx: &mut Self::Context) -> Self::Result {
+        let request = AgentMessage::NodeBoundsRequest(NodeBoundsRequest { uni: msg.uni });
+        ctx.binary(rmp_serde::to_vec(&request).unwrap());
+    }
+}
+
+impl Handler<SnippetImportRequested> for PrivilegedAgentWebSocket {
+    type Result = ();
+
+    fn handle(&mut self, msg: SnippetImportRequested, ctx: &mut Self::Context) -> Self::Result {
+        let request = AgentMessage::ImportSnippetRequest(ImportSnippetRequest {
+            archive: msg.archive,
+        });
+        ctx.binary(rmp_serde::to_vec(&request).unwrap());
+    }
+}
+
 impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for PrivilegedAgentWebSocket {
     fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
         let Ok(msg) = msg else {
@@ -134,12 +191,11 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for PrivilegedAgentWe
         if let Ok(Some(bin_data)) = processed_message {
             match rmp_serde::from_slice::<AgentMessage>(&bin_data) {
                 Ok(AgentMessage::LoadManifestRequest) => {
-                    let manifest =
-                        rmp_serde::to_vec(&*self.state.manifest.lock().unwrap()).unwrap();
-
-                    let message =
-                        AgentMessage::LoadManifestResponse(LoadManifestResponse { manifest });
-                    ctx.binary(rmp_serde::to_vec(&message).unwrap());
+                    if let Some(manifest) = self.state.manifest.lock().unwrap().as_ref() {
+                        send_manifest(manifest, ctx);
+                    } else {
+                        eprintln!("manifest requested before the project was built");
+                    }
                 }
                 Ok(AgentMessage::ComponentSerializationRequest(request)) => {
                     handle_component_serialization_request(
@@ -189,10 +245,142 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for PrivilegedAgentWe
                         eprintln!("server couldn't write to served folder: {:?}", path);
                     };
                 }
+                Ok(AgentMessage::NodeBoundsResponse(NodeBoundsResponse { uni, bounds })) => {
+                    self.state.set_node_bounds(uni, bounds);
+                }
+                Ok(AgentMessage::LoadWorkspaceStateRequest) => {
+                    // a missing file just means nothing has been saved for this project yet
+                    let state = std::fs::read_to_string(self.state.workspace_state_path()).ok();
+                    let message =
+                        AgentMessage::LoadWorkspaceStateResponse(LoadWorkspaceStateResponse {
+                            state,
+                        });
+                    ctx.binary(rmp_serde::to_vec(&message).unwrap());
+                }
+                Ok(AgentMessage::SaveWorkspaceStateRequest(SaveWorkspaceStateRequest {
+                    state,
+                })) => {
+                    let path = self.state.workspace_state_path();
+                    // keep the file watcher from treating this as a project change
+                    self.state.update_last_written_timestamp();
+                    if std::fs::write(&path, state).is_err() {
+                        eprintln!("server couldn't write workspace state: {:?}", path);
+                    };
+                }
+                Ok(AgentMessage::LoadKeymapRequest) => {
+                    // a missing file just means the user hasn't customized their keymap
+                    let keymap = keymap_path().and_then(|path| std::fs::read_to_string(path).ok());
+                    let message = AgentMessage::LoadKeymapResponse(LoadKeymapResponse { keymap });
+                    ctx.binary(rmp_serde::to_vec(&message).unwrap());
+                }
+                Ok(AgentMessage::SaveKeymapRequest(SaveKeymapRequest { keymap })) => {
+                    match keymap_path() {
+                        Some(path) => {
+                            let written = path
+                                .parent()
+                                .map_or(Ok(()), std::fs::create_dir_all)
+                                .and_then(|_| std::fs::write(&path, keymap));
+                            if written.is_err() {
+                                eprintln!("server couldn't write keymap: {:?}", path);
+                            };
+                        }
+                        None => eprintln!("server couldn't save keymap: home directory not found"),
+                    }
+                }
+                Ok(AgentMessage::ExportSnippetRequest(ExportSnippetRequest { archive })) => {
+                    let project_root = self.state.userland_project_root.lock().unwrap().clone();
+                    let message = match export_snippet(&archive, &project_root) {
+                        Ok(message) => message,
+                        Err(e) => format!("Couldn't export snippet: {}", e),
+                    };
+                    let message =
+                        AgentMessage::SnippetStatusNotification(SnippetStatusNotification {
+                            message,
+                        });
+                    ctx.binary(rmp_serde::to_vec(&message).unwrap());
+                }
+                Ok(AgentMessage::ListSnippetsRequest) => {
+                    // a missing directory just means the user hasn't exported any snippets yet
+                    let mut names: Vec<String> = snippets_dir()
+                        .and_then(|dir| std::fs::read_dir(dir).ok())
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|entry| entry.ok().map(|e| e.path()))
+                        .filter(|path| {
+                            path.extension()
+                                .is_some_and(|ext| ext == SNIPPET_FILE_EXTENSION)
+                        })
+                        .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+                        .collect();
+                    names.sort();
+                    let message =
+                        AgentMessage::ListSnippetsResponse(ListSnippetsResponse { names });
+                    ctx.binary(rmp_serde::to_vec(&message).unwrap());
+                }
+                Ok(AgentMessage::LoadSnippetRequest(LoadSnippetRequest { name })) => {
+                    let archive = snippets_dir()
+                        .ok_or_else(|| "home directory not found".to_string())
+                        .and_then(|dir| {
+                            let path = dir.join(format!("{}.{}", name, SNIPPET_FILE_EXTENSION));
+                            std::fs::read(path).map_err(|e| e.to_string())
+                        })
+                        .and_then(|archive| {
+                            SnippetArchive::from_bytes(&archive)
+                                .map(|_| archive)
+                                .map_err(|e| e.to_string())
+                        });
+                    let message = match archive {
+                        Ok(archive) => {
+                            AgentMessage::ImportSnippetRequest(ImportSnippetRequest { archive })
+                        }
+                        Err(e) => {
+                            AgentMessage::SnippetStatusNotification(SnippetStatusNotification {
+                                message: format!("Couldn't load snippet {}: {}", name, e),
+                            })
+                        }
+                    };
+                    ctx.binary(rmp_serde::to_vec(&message).unwrap());
+                }
+                Ok(AgentMessage::AppendSessionLogRequest(AppendSessionLogRequest {
+                    header,
+                    events,
+                })) => {
+                    let path = self.state.session_log_path();
+                    // keep the file watcher from treating this as a project change
+                    self.state.update_last_written_timestamp();
+                    if append_session_log(&path, header, events).is_err() {
+                        eprintln!("server couldn't write session log: {:?}", path);
+                    }
+                }
+                Ok(AgentMessage::ExportBugReportRequest(ExportBugReportRequest { report })) => {
+                    let project_root = self.state.userland_project_root.lock().unwrap().clone();
+                    self.state.update_last_written_timestamp();
+                    let message = match save_bug_report(&report, &project_root) {
+                        Ok(path) => {
+                            println!("Saved designer bug report to {}", path.display());
+                            format!("Saved bug report to {}", path.display())
+                        }
+                        Err(e) => format!("Couldn't save bug report: {}", e),
+                    };
+                    let message =
+                        AgentMessage::BugReportStatusNotification(BugReportStatusNotification {
+                            message,
+                        });
+                    ctx.binary(rmp_serde::to_vec(&message).unwrap());
+                }
                 Ok(
                     AgentMessage::UpdateTemplateRequest(_)
                     | AgentMessage::ProjectFileChangedNotification(_)
-                    | AgentMessage::LoadManifestResponse(_),
+                    | AgentMessage::LoadManifestResponse(_)
+                    | AgentMessage::LoadManifestHeader(_)
+                    | AgentMessage::LoadManifestComponents(_)
+                    | AgentMessage::NodeBoundsRequest(_)
+                    | AgentMessage::LoadWorkspaceStateResponse(_)
+                    | AgentMessage::LoadKeymapResponse(_)
+                    | AgentMessage::ListSnippetsResponse(_)
+                    | AgentMessage::ImportSnippetRequest(_)
+                    | AgentMessage::SnippetStatusNotification(_)
+                    | AgentMessage::BugReportStatusNotification(_),
                 ) => {}
                 Err(e) => {
                     eprintln!("Deserialization error: {:?}", e);
@@ -206,6 +394,91 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for PrivilegedAgentWe
     }
 }
 
+/// Number of components per `LoadManifestComponents` message
+const MANIFEST_COMPONENTS_PER_MESSAGE: usize = 16;
+
+/// Sends `manifest` as a `LoadManifestHeader` announcing the number of components, followed by
+/// the components in batches, so that the designer can show progress while it loads.
+fn send_manifest(manifest: &PaxManifest, ctx: &mut ws::WebsocketContext<PrivilegedAgentWebSocket>) {
+    let header_manifest = PaxManifest {
+        components: Default::default(),
+        main_component_type_id: manifest.main_component_type_id.clone(),
+        type_table: manifest.type_table.clone(),
+        assets_dirs: manifest.assets_dirs.clone(),
+        engine_import_path: manifest.engine_import_path.clone(),
+        defines: manifest.defines.clone(),
+        tokens: manifest.tokens.clone(),
+        build_cfg: manifest.build_cfg.clone(),
+    };
+    let header = AgentMessage::LoadManifestHeader(LoadManifestHeader {
+        manifest: rmp_serde::to_vec(&header_manifest).unwrap(),
+        total_components: manifest.components.len(),
+    });
+    ctx.binary(rmp_serde::to_vec(&header).unwrap());
+
+    let components: Vec<&ComponentDefinition> = manifest.components.values().collect();
+    for batch in components.chunks(MANIFEST_COMPONENTS_PER_MESSAGE) {
+        let message = AgentMessage::LoadManifestComponents(LoadManifestComponents {
+            components: rmp_serde::to_vec(batch).unwrap(),
+        });
+        ctx.binary(rmp_serde::to_vec(&message).unwrap());
+    }
+}
+
+/// Fills in the contents of the snippet's assets from the project, and saves it to the user's
+/// snippet library.  Returns a message describing the outcome for the user.
+/// Appends `events` to the session log at `path`, starting it over with `header` if there's one
+fn append_session_log(
+    path: &Path,
+    header: Option<String>,
+    events: Vec<String>,
+) -> std::io::Result<()> {
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(header.is_none())
+        .truncate(header.is_some())
+        .open(path)?;
+    for line in header.into_iter().chain(events) {
+        writeln!(file, "{}", line)?;
+    }
+    Ok(())
+}
+
+/// Saves `report` to a new file in `project_root`, named after the time, and returns its path
+fn save_bug_report(report: &str, project_root: &Path) -> Result<PathBuf, String> {
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| e.to_string())?;
+    let path = project_root.join(format!("{}{}.json", BUG_REPORT_FILE_PREFIX, time.as_secs()));
+    std::fs::write(&path, report).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+fn export_snippet(archive: &[u8], project_root: &Path) -> Result<String, String> {
+    let mut snippet = SnippetArchive::from_bytes(archive).map_err(|e| e.to_string())?;
+    let mut missing_assets = vec![];
+    for asset in &mut snippet.assets {
+        match std::fs::read(project_root.join(&asset.path)) {
+            Ok(data) => asset.data = data,
+            Err(_) => missing_assets.push(asset.path.clone()),
+        }
+    }
+
+    let dir = snippets_dir().ok_or_else(|| "home directory not found".to_string())?;
+    let path = dir.join(snippet.file_name());
+    let bytes = snippet.to_bytes().map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&dir)
+        .and_then(|_| std::fs::write(&path, bytes))
+        .map_err(|e| e.to_string())?;
+
+    let mut message = format!("Exported snippet {} to {}", snippet.name, path.display());
+    if !missing_assets.is_empty() {
+        message += &format!(" (couldn't read assets: {})", missing_assets.join(", "));
+    }
+    Ok(message)
+}
+
 fn handle_component_serialization_request(
     request: ComponentSerializationRequest,
     manifest: Option<&mut PaxManifest>,
@@ -218,6 +491,12 @@ fn handle_component_serialization_request(
         .get_file_path()
         .unwrap()
         .to_owned();
+    if !request.changes.is_empty() {
+        println!("Saving {} to {}:", component.type_id, file_path);
+        for change in &request.changes {
+            println!("    {}", change);
+        }
+    }
     serialize_component_to_file(&component, file_path.clone());
     // update in memory manifest
     if let Some(manifest) = manifest {
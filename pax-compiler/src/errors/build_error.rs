@@ -0,0 +1,87 @@
+//! Build failures with a known cause, reported with how to fix them rather than with the output
+//! of the tool that failed.  They're returned inside the `eyre::Report` of the failing build, so
+//! callers can tell them apart with [`eyre::Report::downcast_ref`].
+
+use std::fmt::{self, Display};
+
+use color_eyre::eyre;
+
+/// The `Cargo.toml` entries a project needs for the parser binary, as in the project templates
+const PARSER_TARGET_GUIDANCE: &str = r#"[[bin]]
+name = "parser"
+path = "src/lib.rs"
+required-features = ["parser"]
+
+[features]
+parser = ["pax-kit/parser"]"#;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuildError {
+    /// The project has no `parser` bin target, or no `parser` feature, so cargo couldn't build
+    /// the binary that parses its templates.  `cargo_error` is the error cargo reported
+    ParserTargetMissing { cargo_error: String },
+    /// The build's `CancellationToken` was cancelled, usually because a newer build superseded it
+    BuildCancelled,
+}
+
+impl BuildError {
+    /// Recognizes the known failures of the cargo invocation running the parser binary, from its
+    /// stderr
+    pub fn from_parser_stderr(stderr: &str) -> Option<Self> {
+        let stderr = strip_ansi_codes(stderr);
+        stderr
+            .lines()
+            .map(str::trim)
+            .find(|line| {
+                let is_missing_bin = line.contains("no bin target named `parser`");
+                let is_missing_feature = (line.contains("`parser`")
+                    && (line.contains("does not have the feature")
+                        || line.contains("does not have these features")))
+                    || line.contains("contains these features: parser");
+                line.starts_with("error") && (is_missing_bin || is_missing_feature)
+            })
+            .map(|line| BuildError::ParserTargetMissing {
+                cargo_error: line.to_string(),
+            })
+    }
+
+    /// Whether `report` is the failure of a cancelled build, rather than of a build that went wrong
+    pub fn is_cancellation(report: &eyre::Report) -> bool {
+        report.downcast_ref::<BuildError>() == Some(&BuildError::BuildCancelled)
+    }
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::ParserTargetMissing { cargo_error } => write!(
+                f,
+                "The project has no `parser` binary to parse its templates with ({}).\n\
+                 Pax projects build it from the library with the `parser` feature, add to Cargo.toml:\n\n{}",
+                cargo_error, PARSER_TARGET_GUIDANCE
+            ),
+            BuildError::BuildCancelled => write!(f, "The build was cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+/// Removes the color codes cargo adds to its output with `--color always`
+fn strip_ansi_codes(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // skip the `[..m` of the escape sequence
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            stripped.push(c);
+        }
+    }
+    stripped
+}
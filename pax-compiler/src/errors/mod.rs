@@ -0,0 +1,8 @@
+//! # Errors Module
+//!
+//! The `errors` module provides utilities for reporting build errors in terms of the user's
+//! `.pax` source rather than generated code, and build failures with a known cause in terms of
+//! how to fix them.
+
+pub mod build_error;
+pub mod source_map;
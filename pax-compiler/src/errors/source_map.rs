@@ -0,0 +1,272 @@
+//! # Source Map
+//!
+//! Code generated into `cartridge.partial.rs` (and the component modules it includes, for a split
+//! cartridge) is wrapped in `@source-map` marker comments naming the component (and handler) it was
+//! generated for.  `SourceMap` collects the line ranges between those markers, so that rustc
+//! diagnostics pointing into the cartridge can be re-pointed at the `.pax` source that produced the
+//! offending code.
+//!
+//! Ranges are only as fine as the generated code: a handler maps to the line it's bound on, while a
+//! component's factory or a primitive maps to the file alone.  Template expressions are interpreted
+//! at runtime rather than generated, so errors in them never reach rustc; mismatched settings are
+//! reported at their template span by `validate_settings` instead.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use pax_manifest::{
+    ComponentDefinition, PaxManifest, SettingElement, SettingsBlockElement, ValueDefinition,
+};
+use serde_json::Value;
+
+/// Opens a mapped range; followed by `<type_id>`, `<type_id>|<handler name>` or
+/// `<type_id>|@primitive`, the latter for the instantiation of a primitive
+pub const SOURCE_MAP_BEGIN_MARKER: &str = "// @source-map-begin ";
+/// Handler-position suffix of markers around the instantiation of a primitive
+pub const SOURCE_MAP_PRIMITIVE_SUFFIX: &str = "@primitive";
+/// Closes the innermost open mapped range
+pub const SOURCE_MAP_END_MARKER: &str = "// @source-map-end";
+
+/// Location in the user's `.pax` source that a range of generated code originates from
+#[derive(Debug, Clone, Serialize)]
+pub struct PaxSourceLocation {
+    /// Path to the `.pax` file, or a description of the inlined template
+    pub file: String,
+    /// 0-based (line, column), as stored in `LocationInfo`.  `None` if only the file is known
+    pub line_col: Option<(usize, usize)>,
+    /// What the generated code implements, e.g. "handler `increment` of `Counter`"
+    pub description: String,
+}
+
+impl std::fmt::Display for PaxSourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line_col {
+            Some((line, col)) => write!(f, "{}:{}:{}", self.file, line + 1, col + 1),
+            None => write!(f, "{}", self.file),
+        }
+    }
+}
+
+struct SourceMapEntry {
+    /// Generated file the range is in
+    file: PathBuf,
+    /// 1-based, inclusive line range in the generated file (matching rustc's spans)
+    start_line: usize,
+    end_line: usize,
+    source: PaxSourceLocation,
+}
+
+#[derive(Default)]
+pub struct SourceMap {
+    entries: Vec<SourceMapEntry>,
+}
+
+impl SourceMap {
+    /// Reads the marker comments from the files of the generated cartridge at `cartridge_paths`,
+    /// resolving each marked range to its origin in `manifest` (the manifest the cartridge was
+    /// generated from)
+    pub fn extract_ranges_from_generated_code<'a>(
+        cartridge_paths: impl IntoIterator<Item = &'a PathBuf>,
+        manifest: &PaxManifest,
+    ) -> Self {
+        let mut source_map = SourceMap::default();
+        for path in cartridge_paths {
+            source_map.extract_ranges_from_file(path, manifest);
+        }
+        source_map
+    }
+
+    fn extract_ranges_from_file(&mut self, cartridge_path: &Path, manifest: &PaxManifest) {
+        let Ok(generated) = fs::read_to_string(cartridge_path) else {
+            return;
+        };
+
+        let mut open: Vec<(usize, &str)> = Vec::new();
+        for (i, line) in generated.lines().enumerate() {
+            let line = line.trim();
+            if let Some(key) = line.strip_prefix(SOURCE_MAP_BEGIN_MARKER) {
+                open.push((i + 1, key.trim()));
+            } else if line.starts_with(SOURCE_MAP_END_MARKER) {
+                let Some((start_line, key)) = open.pop() else {
+                    continue;
+                };
+                if let Some(source) = resolve_marker(key, manifest) {
+                    self.entries.push(SourceMapEntry {
+                        file: cartridge_path.to_path_buf(),
+                        start_line,
+                        end_line: i + 1,
+                        source,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Origin of the given (1-based) line of the generated cartridge file `file_name`, as named by
+    /// rustc, using the innermost mapped range containing it
+    pub fn lookup(&self, file_name: &str, line: usize) -> Option<&PaxSourceLocation> {
+        self.entries
+            .iter()
+            .filter(|e| is_generated_file(file_name, &e.file))
+            .filter(|e| e.start_line <= line && line <= e.end_line)
+            .min_by_key(|e| e.end_line - e.start_line)
+            .map(|e| &e.source)
+    }
+
+    /// Takes the stdout of a cargo invocation run with `--message-format=json-diagnostic-rendered-ansi`
+    /// and returns the human-readable diagnostics, with those pointing into the generated cartridge
+    /// re-pointed at the `.pax` source they came from.  Lines that aren't cargo JSON messages are
+    /// passed through unchanged.
+    pub fn remap_cargo_messages(&self, stdout: &str) -> String {
+        self.remap_messages(stdout, false)
+    }
+
+    /// Same as [`Self::remap_cargo_messages`], also passing through cargo's other JSON messages,
+    /// e.g. the artifacts built and the build scripts run, for verbose builds
+    pub fn remap_all_cargo_messages(&self, stdout: &str) -> String {
+        self.remap_messages(stdout, true)
+    }
+
+    fn remap_messages(&self, stdout: &str, keep_other_messages: bool) -> String {
+        let mut out = String::new();
+        for line in stdout.lines() {
+            let Ok(message) = serde_json::from_str::<Value>(line) else {
+                out.push_str(line);
+                out.push('\n');
+                continue;
+            };
+            if message["reason"].as_str() != Some("compiler-message") {
+                if keep_other_messages {
+                    out.push_str(line);
+                    out.push('\n');
+                }
+                continue;
+            }
+            let diagnostic = &message["message"];
+            let Some(rendered) = diagnostic["rendered"].as_str() else {
+                continue;
+            };
+            out.push_str(&self.remap_diagnostic(diagnostic, rendered));
+        }
+        out
+    }
+
+    fn remap_diagnostic(&self, diagnostic: &Value, rendered: &str) -> String {
+        let primary_span = diagnostic["spans"].as_array().and_then(|spans| {
+            spans
+                .iter()
+                .find(|s| s["is_primary"].as_bool() == Some(true))
+        });
+        let Some(span) = primary_span else {
+            return rendered.to_string();
+        };
+        let (Some(file_name), Some(line), Some(column)) = (
+            span["file_name"].as_str(),
+            span["line_start"].as_u64(),
+            span["column_start"].as_u64(),
+        ) else {
+            return rendered.to_string();
+        };
+        let Some(source) = self.lookup(file_name, line as usize) else {
+            return rendered.to_string();
+        };
+
+        let generated_location = format!("{}:{}:{}", file_name, line, column);
+        let mut remapped = rendered.replace(&generated_location, &source.to_string());
+        if !remapped.ends_with('\n') {
+            remapped.push('\n');
+        }
+        remapped.push_str(&format!(
+            "note: this error is in code generated for {} (at {})\n\n",
+            source.description, generated_location
+        ));
+        remapped
+    }
+}
+
+/// Whether `file_name`, as named by rustc's spans, is the generated file at `path`.  rustc names
+/// files by the path they're compiled or `include!`d with, which may be relative
+fn is_generated_file(file_name: &str, path: &Path) -> bool {
+    let file_name = Path::new(file_name);
+    file_name == path
+        || path
+            .file_name()
+            .is_some_and(|name| file_name.ends_with(name))
+}
+
+/// Resolves a marker key (see `SOURCE_MAP_BEGIN_MARKER`) to its origin in the manifest
+fn resolve_marker(key: &str, manifest: &PaxManifest) -> Option<PaxSourceLocation> {
+    let (type_id, handler) = match key.rsplit_once('|') {
+        Some((type_id, handler)) => (type_id, Some(handler)),
+        None => (key, None),
+    };
+    let component = manifest
+        .components
+        .values()
+        .find(|c| c.type_id.get_unique_identifier() == type_id)?;
+    let name = component
+        .type_id
+        .get_pascal_identifier()
+        .unwrap_or_else(|| type_id.to_string());
+    let file = component
+        .template
+        .as_ref()
+        .and_then(|t| t.get_file_path())
+        .unwrap_or_else(|| format!("<inlined template of `{}`>", name));
+
+    match handler {
+        Some(SOURCE_MAP_PRIMITIVE_SUFFIX) => Some(PaxSourceLocation {
+            file: format!("<primitive `{}` in `{}`>", name, component.module_path),
+            line_col: None,
+            description: format!(
+                "primitive `{}`, whose instance `{}` (from its `#[primitive(..)]` attribute) must be a struct implementing `pax_runtime::InstanceNode`",
+                name,
+                component
+                    .primitive_instance_import_path
+                    .as_deref()
+                    .unwrap_or_default()
+            ),
+        }),
+        Some(handler) => Some(PaxSourceLocation {
+            file,
+            line_col: find_handler_binding(component, handler),
+            description: format!("handler `{}` of `{}`", handler, name),
+        }),
+        None => Some(PaxSourceLocation {
+            file,
+            line_col: None,
+            description: format!("component `{}`", name),
+        }),
+    }
+}
+
+/// Location of the first place `handler` is bound, either in the settings block
+/// (e.g. `@mount: handler`) or inline in the template (e.g. `@click=self.handler`)
+fn find_handler_binding(component: &ComponentDefinition, handler: &str) -> Option<(usize, usize)> {
+    let is_handler =
+        |name: &str| name.trim_start_matches("self.").trim_start_matches("this.") == handler;
+
+    for element in component.settings.iter().flatten() {
+        if let SettingsBlockElement::Handler(_, values) = element {
+            if let Some(value) = values.iter().find(|v| is_handler(&v.token_value)) {
+                return value.token_location.as_ref().map(|l| l.start_line_col);
+            }
+        }
+    }
+
+    // template nodes aren't stored in source order, so pick the earliest binding
+    component
+        .template
+        .iter()
+        .flat_map(|t| t.get_nodes())
+        .flat_map(|tnd| tnd.settings.iter().flatten())
+        .filter_map(|setting| match setting {
+            SettingElement::Setting(key, ValueDefinition::EventBindingTarget(e))
+                if is_handler(&e.name) =>
+            {
+                key.token_location.as_ref().map(|l| l.start_line_col)
+            }
+            _ => None,
+        })
+        .min()
+}
@@ -3,8 +3,10 @@ mod rules;
 use crate::helpers::{replace_by_line_column, InlinedTemplateFinder};
 use color_eyre::eyre::{self, Report};
 use pax_lang::{parse_pax_err, Rule};
+use similar::TextDiff;
 use std::fs;
-use std::path::Path;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
 use syn::parse_file;
 use syn::visit::Visit;
 
@@ -13,6 +15,140 @@ pub fn format_pax_template(code: String) -> Result<String, eyre::Report> {
     Ok(rules::format(pax_component_definition))
 }
 
+/// Formats the contents of a `.pax` file, ending it with a single newline
+pub fn format_pax_document(code: &str) -> Result<String, eyre::Report> {
+    // the rules panic on constructs they don't handle, which shouldn't take down a whole project run
+    let formatted = panic::catch_unwind(AssertUnwindSafe(|| format_pax_template(code.to_string())))
+        .map_err(|_| Report::msg("The formatter doesn't support a construct in this file"))??;
+    Ok(format!("{}\n", formatted.trim_end_matches('\n')))
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct FormatOptions {
+    /// Only report the files that would change, without writing them
+    pub check: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FileFormatStatus {
+    Unchanged,
+    /// Formatted, or would be in check mode, with the unified diff of the change
+    Changed(String),
+    Error(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct FileFormatResult {
+    pub path: PathBuf,
+    pub status: FileFormatStatus,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct FormatReport {
+    pub files: Vec<FileFormatResult>,
+}
+
+impl FormatReport {
+    pub fn changed(&self) -> impl Iterator<Item = &FileFormatResult> {
+        self.files
+            .iter()
+            .filter(|file| matches!(file.status, FileFormatStatus::Changed(_)))
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &FileFormatResult> {
+        self.files
+            .iter()
+            .filter(|file| matches!(file.status, FileFormatStatus::Error(_)))
+    }
+
+    pub fn summary(&self, check: bool) -> String {
+        let changed = self.changed().count();
+        format!(
+            "{} file(s) {}, {} unchanged, {} error(s)",
+            changed,
+            if check {
+                "would be reformatted"
+            } else {
+                "reformatted"
+            },
+            self.files.len() - changed - self.errors().count(),
+            self.errors().count()
+        )
+    }
+}
+
+/// The `.pax` files under `root`, skipping hidden directories (like `.pax`) and anything matched by a
+/// `.gitignore`, sorted by path
+pub fn discover_pax_files(root: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = ignore::WalkBuilder::new(root)
+        .require_git(false)
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .map(|entry| entry.into_path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "pax"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Formats every `.pax` file of the project at `root`, see [`discover_pax_files`].  Files are replaced
+/// atomically, and left untouched in check mode
+pub fn format_project(root: &Path, options: &FormatOptions) -> FormatReport {
+    let files = discover_pax_files(root)
+        .into_iter()
+        .map(|path| {
+            let status = match format_project_file(root, &path, options) {
+                Ok(status) => status,
+                Err(e) => FileFormatStatus::Error(e.to_string()),
+            };
+            FileFormatResult { path, status }
+        })
+        .collect();
+    FormatReport { files }
+}
+
+fn format_project_file(
+    root: &Path,
+    path: &Path,
+    options: &FormatOptions,
+) -> Result<FileFormatStatus, Report> {
+    let content = fs::read_to_string(path)?;
+    let formatted = format_pax_document(&content)?;
+    if formatted == content {
+        return Ok(FileFormatStatus::Unchanged);
+    }
+    let name = path.strip_prefix(root).unwrap_or(path).to_string_lossy();
+    let diff = unified_diff(&name, &content, &formatted);
+    if !options.check {
+        write_atomically(path, &formatted)?;
+    }
+    Ok(FileFormatStatus::Changed(diff))
+}
+
+pub fn unified_diff(name: &str, before: &str, after: &str) -> String {
+    TextDiff::from_lines(before, after)
+        .unified_diff()
+        .header(&format!("a/{}", name), &format!("b/{}", name))
+        .to_string()
+}
+
+/// Writes `content` next to `path` and renames it over `path`, so that readers never see a partial file
+fn write_atomically(path: &Path, content: &str) -> Result<(), Report> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| Report::msg(format!("{:?} isn't a file", path)))?;
+    let mut temp_name = file_name.to_os_string();
+    temp_name.push(".pax-fmt.tmp");
+    let temp_path = path.with_file_name(temp_name);
+    fs::write(&temp_path, content)?;
+    if let Err(e) = fs::rename(&temp_path, path) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
 pub fn format_file(file_path: &str) -> Result<(), Report> {
     let path = Path::new(file_path);
 
@@ -25,13 +161,8 @@ pub fn format_file(file_path: &str) -> Result<(), Report> {
 
 fn format_pax_file(path: &Path) -> Result<(), Report> {
     let content = fs::read_to_string(path)?;
-    match format_pax_template(content) {
-        Ok(formatted_content) => {
-            fs::write(path, formatted_content)?;
-            Ok(())
-        }
-        Err(e) => Err(e),
-    }
+    let formatted_content = format_pax_document(&content)?;
+    write_atomically(path, &formatted_content)
 }
 
 fn format_pax_in_rust_file(path: &Path) -> Result<(), Report> {
@@ -8,7 +8,7 @@ const INDENTATION: usize = 4;
 pub const PREFIX_OPERATORS: [Rule; 2] = [Rule::xo_neg, Rule::xo_bool_not];
 pub const DO_NOT_INSERT_TAB_MARKER: &str = "|-DO_NOT_INSERT_TAB-|";
 
-pub const INFIX_OPERATORS: [Rule; 16] = [
+pub const INFIX_OPERATORS: [Rule; 17] = [
     Rule::xo_add,
     Rule::xo_bool_and,
     Rule::xo_bool_or,
@@ -23,6 +23,7 @@ pub const INFIX_OPERATORS: [Rule; 16] = [
     Rule::xo_rel_lte,
     Rule::xo_rel_neq,
     Rule::xo_sub,
+    Rule::xo_null_coalesce,
     Rule::xo_tern_then,
     Rule::xo_tern_else,
 ];
@@ -109,6 +110,9 @@ fn get_formatting_rules(pest_rule: Rule) -> Vec<Box<dyn FormattingRule>> {
             Box::new(ColorFunctionDefaultRule),
         ],
         Rule::event_id => vec![Box::new(EventIdDefaultRule)],
+        Rule::extends_declaration => vec![Box::new(ExtendsDeclarationDefaultRule)],
+        Rule::override_directive => vec![Box::new(OverrideDirectiveDefaultRule)],
+        Rule::override_tag_pair => vec![Box::new(OverrideTagPairDefaultRule)],
         Rule::literal_enum_args_list
         | Rule::xo_enum_or_function_args_list
         | Rule::literal_color => vec![
@@ -146,6 +150,8 @@ fn get_formatting_rules(pest_rule: Rule) -> Vec<Box<dyn FormattingRule>> {
         | Rule::literal_list_access
         | Rule::closing_tag
         | Rule::xo_symbol
+        | Rule::xo_optional_field
+        | Rule::xo_optional_index
         | Rule::literal_color_channel
         | Rule::literal_option
         | Rule::EOI => vec![Box::new(RemoveWhitespaceRule)],
@@ -171,6 +177,7 @@ fn get_formatting_rules(pest_rule: Rule) -> Vec<Box<dyn FormattingRule>> {
         | Rule::xo_rel_lte
         | Rule::xo_rel_neq
         | Rule::xo_sub
+        | Rule::xo_null_coalesce
         | Rule::xo_tern_then
         | Rule::xo_tern_else
         | Rule::xo_range
@@ -220,15 +227,22 @@ struct PaxComponentDefinitionDefaultRule;
 
 impl FormattingRule for PaxComponentDefinitionDefaultRule {
     fn format(&self, _node: Pair<Rule>, children: Vec<Child>) -> String {
-        let tags = children
+        let extends = children
             .iter()
-            .filter(|child| child.node_type == Rule::root_tag_pair);
+            .filter(|child| child.node_type == Rule::extends_declaration);
+        let tags = children.iter().filter(|child| {
+            child.node_type == Rule::root_tag_pair || child.node_type == Rule::override_tag_pair
+        });
         let settings = children
             .iter()
             .filter(|child| child.node_type == Rule::settings_block_declaration);
 
         let mut component = vec![];
 
+        for extends in extends {
+            component.push(extends.formatted_node.clone());
+        }
+
         let mut formatted_tags = String::new();
         for (i, tag) in tags.enumerate() {
             if i > 0 {
@@ -498,6 +512,33 @@ impl FormattingRule for crate::formatting::rules::EventIdDefaultRule {
     }
 }
 
+#[derive(Clone)]
+struct ExtendsDeclarationDefaultRule;
+
+impl FormattingRule for ExtendsDeclarationDefaultRule {
+    fn format(&self, _node: Pair<Rule>, children: Vec<Child>) -> String {
+        format!("@extends({})", children[0].formatted_node)
+    }
+}
+
+#[derive(Clone)]
+struct OverrideDirectiveDefaultRule;
+
+impl FormattingRule for OverrideDirectiveDefaultRule {
+    fn format(&self, _node: Pair<Rule>, children: Vec<Child>) -> String {
+        format!("@override(label={})", children[0].formatted_node)
+    }
+}
+
+#[derive(Clone)]
+struct OverrideTagPairDefaultRule;
+
+impl FormattingRule for OverrideTagPairDefaultRule {
+    fn format(&self, _node: Pair<Rule>, children: Vec<Child>) -> String {
+        children[0].formatted_node.clone() + "\n" + &children[1].formatted_node
+    }
+}
+
 #[derive(Clone)]
 struct SettingsEventBindingDefaultRule;
 
@@ -872,7 +913,7 @@ impl FormattingRule for StatementSlotDefaultRule {
     fn format(&self, _node: Pair<Rule>, children: Vec<Child>) -> String {
         let mut formatted_node = String::new();
         let exp = children[0].formatted_node.clone();
-        formatted_node.push_str(format!("slot {}", exp).as_str());
+        formatted_node.push_str(format!("slot({})", exp).as_str());
         formatted_node
     }
 }
@@ -1,19 +1,30 @@
+use color_eyre::eyre;
 use colored::{ColoredString, Colorize};
-use include_dir::{include_dir, Dir};
+use eyre::eyre;
+use include_dir::{include_dir, Dir, DirEntry};
 use lazy_static::lazy_static;
 use pax_manifest::HostCrateInfo;
 use pax_runtime::api::serde::Deserialize;
+use std::collections::HashSet;
+use std::ffi::OsString;
 use std::fs;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 use syn::spanned::Spanned;
 use syn::visit::Visit;
 use syn::{parse_file, ItemStruct};
 use toml_edit;
 use toml_edit::Document;
 
+use crate::cancellation::CancellationToken;
+use crate::errors::build_error::BuildError;
+use crate::workspace::CargoProject;
+
 lazy_static! {
     #[allow(non_snake_case)]
     pub static ref PAX_BADGE: ColoredString = "[Pax]".bold().on_black().white();
@@ -23,6 +34,10 @@ lazy_static! {
 
 pub static PAX_CREATE_TEMPLATE: Dir<'_> =
     include_dir!("$CARGO_MANIFEST_DIR/files/new-project/new-project-template");
+pub static PAX_CREATE_MINIMAL_TEMPLATE: Dir<'_> =
+    include_dir!("$CARGO_MANIFEST_DIR/files/new-project/new-minimal-project-template");
+pub static PAX_CREATE_STACKER_TEMPLATE: Dir<'_> =
+    include_dir!("$CARGO_MANIFEST_DIR/files/new-project/new-stacker-project-template");
 pub static PAX_WEB_INTERFACE_TEMPLATE: Dir<'_> =
     include_dir!("$CARGO_MANIFEST_DIR/files/interfaces/web/public/");
 pub static PAX_MACOS_INTERFACE_TEMPLATE: Dir<'_> =
@@ -40,9 +55,19 @@ pub const INTERFACE_DIR_NAME: &str = "interface";
 pub const BUILD_DIR_NAME: &str = "build";
 pub const PUBLIC_DIR_NAME: &str = "public";
 pub const ASSETS_DIR_NAME: &str = "assets";
+/// Listing of what tree shaking excluded from a release build, written into the .pax dir
+pub const TREE_SHAKING_REPORT_FILE_NAME: &str = "tree-shaking-report.txt";
+/// Hash of the manifest baked into the cartridge and the defines it was built with, written into
+/// the .pax dir, to tell which variant a build is
+pub const BUILD_REPORT_FILE_NAME: &str = "build-report.txt";
 
 pub const ERR_SPAWN: &str = "failed to spawn child";
 
+/// Env var overriding the per-step build timeout, in seconds.  `0` disables the timeout
+pub const PAX_BUILD_TIMEOUT_ENV: &str = "PAX_BUILD_TIMEOUT_SECS";
+pub const DEFAULT_BUILD_TIMEOUT_SECS: u64 = 300;
+const TIMEOUT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
 //whitelist of package ids that are relevant to the compiler, e.g. for cloning & patching, for assembling FS paths,
 //or for looking up package IDs from a userland Cargo.lock.
 pub const ALL_PKGS: &[&str] = &[
@@ -121,6 +146,9 @@ pub fn set_path_on_pax_dependencies(full_path: &Path) {
         .expect("Failed to write modified Cargo.toml");
 }
 
+/// Sets the version of every `pax-*` dependency, both in `[dependencies]` and, for workspace
+/// root manifests, in `[workspace.dependencies]`.  Dependencies inherited with `workspace = true`
+/// are left as-is, since their version is declared at the workspace root.
 pub fn update_pax_dependency_versions(doc: &mut Document, ctx_version: &str) {
     if let Some(deps) = doc
         .as_table_mut()
@@ -128,36 +156,58 @@ pub fn update_pax_dependency_versions(doc: &mut Document, ctx_version: &str) {
         .or_insert_with(toml_edit::table)
         .as_table_mut()
     {
-        let keys: Vec<String> = deps
-            .iter()
-            .filter_map(|(key, _)| {
-                if key.starts_with("pax-") {
-                    Some(key.to_string())
-                } else {
-                    None
-                }
-            })
-            .collect();
+        update_pax_dependency_table(deps, ctx_version);
+    }
 
-        for key in keys {
-            let dep_entry = deps.get_mut(&key).unwrap();
+    if let Some(deps) = doc
+        .as_table_mut()
+        .get_mut("workspace")
+        .and_then(|workspace| workspace.as_table_mut())
+        .and_then(|workspace| workspace.get_mut("dependencies"))
+        .and_then(|deps| deps.as_table_mut())
+    {
+        update_pax_dependency_table(deps, ctx_version);
+    }
+}
 
-            if let toml_edit::Item::Value(toml_edit::Value::InlineTable(ref mut dep_table)) =
-                dep_entry
-            {
-                dep_table.insert(
-                    "version",
-                    toml_edit::Value::String(toml_edit::Formatted::new(ctx_version.to_string())),
-                );
+fn update_pax_dependency_table(deps: &mut toml_edit::Table, ctx_version: &str) {
+    let keys: Vec<String> = deps
+        .iter()
+        .filter_map(|(key, _)| {
+            if key.starts_with("pax-") {
+                Some(key.to_string())
             } else {
-                let dep_string = format!("version = \"{}\"", ctx_version);
-                *dep_entry = toml_edit::Item::from_str(&dep_string).unwrap_or_default();
+                None
             }
+        })
+        .collect();
+
+    for key in keys {
+        let dep_entry = deps.get_mut(&key).unwrap();
+
+        let is_workspace_inherited = dep_entry
+            .as_table_like()
+            .and_then(|dep| dep.get("workspace"))
+            .and_then(|workspace| workspace.as_bool())
+            .unwrap_or(false);
+        if is_workspace_inherited {
+            continue;
+        }
+
+        if let toml_edit::Item::Value(toml_edit::Value::InlineTable(ref mut dep_table)) = dep_entry
+        {
+            dep_table.insert(
+                "version",
+                toml_edit::Value::String(toml_edit::Formatted::new(ctx_version.to_string())),
+            );
+        } else {
+            let dep_string = format!("version = \"{}\"", ctx_version);
+            *dep_entry = toml_edit::Item::from_str(&dep_string).unwrap_or_default();
         }
     }
 }
 
-const ERR_LOCK: &str = "Failed to lock process_child_ids mutex";
+pub(crate) const ERR_LOCK: &str = "Failed to lock process_child_ids mutex";
 
 pub fn wait_with_output(
     process_child_ids: &Arc<Mutex<Vec<u64>>>,
@@ -182,8 +232,131 @@ pub fn wait_with_output(
     output
 }
 
-pub fn get_or_create_pax_directory(project_path: &PathBuf) -> PathBuf {
-    let working_path = std::path::Path::new(project_path).join(".pax");
+/// Per-step build timeout: `PAX_BUILD_TIMEOUT_SECS` if set, otherwise `DEFAULT_BUILD_TIMEOUT_SECS`
+pub fn default_build_timeout() -> Option<Duration> {
+    let secs = std::env::var(PAX_BUILD_TIMEOUT_ENV)
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(DEFAULT_BUILD_TIMEOUT_SECS);
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+/// A `cargo` command, run with `cargo_bin` instead of the `cargo` on `PATH` if set, and as
+/// `cargo +<toolchain>` if `toolchain` is set
+pub fn cargo_command(cargo_bin: Option<&Path>, toolchain: Option<&str>) -> Command {
+    let mut cmd = Command::new(cargo_bin.unwrap_or(Path::new("cargo")));
+    if let Some(toolchain) = toolchain {
+        cmd.arg(format!("+{}", toolchain));
+    }
+    cmd
+}
+
+/// Same as [`cargo_command`], for tools like `wasm-pack` that invoke cargo themselves: the
+/// toolchain is pinned through `RUSTUP_TOOLCHAIN`, and the directory of `cargo_bin` is put
+/// first on `PATH`.
+pub fn set_cargo_env(cmd: &mut Command, cargo_bin: Option<&Path>, toolchain: Option<&str>) {
+    if let Some(toolchain) = toolchain {
+        cmd.env("RUSTUP_TOOLCHAIN", toolchain);
+    }
+    if let Some(bin_dir) = cargo_bin.and_then(Path::parent) {
+        let mut paths = vec![bin_dir.to_path_buf()];
+        if let Some(path) = std::env::var_os("PATH") {
+            paths.extend(std::env::split_paths(&path));
+        }
+        if let Ok(path) = std::env::join_paths(paths) {
+            cmd.env("PATH", path);
+        }
+    }
+}
+
+/// Enables the app crate's `features` on a cargo (or `wasm-pack`) build, if there are any
+pub fn add_feature_args(cmd: &mut Command, features: &[String]) {
+    if !features.is_empty() {
+        cmd.arg("--features").arg(features.join(","));
+    }
+}
+
+/// Like `wait_with_output`, but kills the child and returns an error if it hasn't exited
+/// within `timeout`, or as soon as `cancellation` is cancelled, with
+/// [`BuildError::BuildCancelled`].  `step_name` describes the build step in the error message.
+/// Waits indefinitely if `timeout` is `None`.
+pub fn wait_with_timeout(
+    process_child_ids: &Arc<Mutex<Vec<u64>>>,
+    mut child: std::process::Child,
+    timeout: Option<Duration>,
+    cancellation: &CancellationToken,
+    step_name: &str,
+) -> Result<std::process::Output, eyre::Report> {
+    let child_id: u64 = child.id().into();
+    process_child_ids.lock().expect(ERR_LOCK).push(child_id);
+
+    // drain piped output on separate threads, so the child can't block on a full pipe
+    // while we're polling for it to exit
+    fn drain(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<Vec<u8>> {
+        thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buf);
+            }
+            buf
+        })
+    }
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    fn kill(child: &mut std::process::Child) {
+        // build steps run in their own process group (see `pre_exec_hook`),
+        // so take down e.g. the rustc processes spawned by cargo as well
+        #[cfg(unix)]
+        unsafe {
+            libc::killpg(child.id() as libc::pid_t, libc::SIGKILL);
+        }
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+
+    let start = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Ok(status),
+            Ok(None) if cancellation.is_cancelled() => {
+                kill(&mut child);
+                break Err(BuildError::BuildCancelled.into());
+            }
+            Ok(None) if timeout.is_some_and(|timeout| start.elapsed() >= timeout) => {
+                kill(&mut child);
+                break Err(eyre!(
+                    "Build step '{}' timed out after {:?}",
+                    step_name,
+                    timeout.unwrap()
+                ));
+            }
+            Ok(None) => thread::sleep(TIMEOUT_POLL_INTERVAL),
+            Err(e) => {
+                break Err(eyre!(
+                    "Failed to wait for build step '{}': {}",
+                    step_name,
+                    e
+                ))
+            }
+        }
+    };
+
+    process_child_ids
+        .lock()
+        .expect(ERR_LOCK)
+        .retain(|&id| id != child_id);
+
+    let status = status?;
+    Ok(std::process::Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+pub fn get_or_create_pax_directory(project: &CargoProject) -> PathBuf {
+    let working_path = project.pax_dir();
     std::fs::create_dir_all(&working_path).unwrap();
     fs::canonicalize(working_path).unwrap()
 }
@@ -254,6 +427,100 @@ pub fn copy_dir_recursively(
     Ok(())
 }
 
+/// Makes `dest` a copy of `src`, like `copy_dir_recursively`, but only copies the files that are
+/// missing in `dest`, differ in size, or were modified since they were last copied, and removes
+/// everything in `dest` that isn't in `src`.  Unchanged files keep their timestamps, so that they
+/// don't trigger rebuilds of whatever is built from `dest`.
+pub fn sync_dir_recursively(src: &Path, dest: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    if !src.is_dir() {
+        if !is_file_up_to_date(src, dest)? {
+            remove_dir_if_exists(dest)?;
+            fs::copy(src, dest)?;
+        }
+        return Ok(());
+    }
+
+    prepare_sync_dir(dest)?;
+    let mut names = HashSet::new();
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let dest_child = dest.join(entry.file_name());
+        sync_dir_recursively(&entry.path(), &dest_child)?;
+        names.insert(entry.file_name());
+    }
+    remove_entries_not_in(dest, &names)
+}
+
+/// Like `sync_dir_recursively`, for files embedded with `include_dir!`.  These don't have
+/// timestamps, so files are compared by content instead.
+pub fn sync_embedded_dir(src: &Dir, dest: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    prepare_sync_dir(dest)?;
+    let mut names = HashSet::new();
+    for entry in src.entries() {
+        let name = entry.path().file_name().ok_or("Invalid file name")?;
+        let dest_child = dest.join(name);
+        match entry {
+            DirEntry::Dir(dir) => sync_embedded_dir(dir, &dest_child)?,
+            DirEntry::File(file) => {
+                let is_up_to_date = fs::metadata(&dest_child)
+                    .is_ok_and(|meta| meta.is_file() && meta.len() == file.contents().len() as u64)
+                    && fs::read(&dest_child)? == file.contents();
+                if !is_up_to_date {
+                    remove_dir_if_exists(&dest_child)?;
+                    fs::write(&dest_child, file.contents())?;
+                }
+            }
+        }
+        names.insert(name.to_os_string());
+    }
+    remove_entries_not_in(dest, &names)
+}
+
+fn is_file_up_to_date(src: &Path, dest: &Path) -> std::io::Result<bool> {
+    let Ok(dest_meta) = fs::metadata(dest) else {
+        return Ok(false);
+    };
+    let src_meta = fs::metadata(src)?;
+    // copies get the time they were made as their modification time
+    Ok(dest_meta.is_file()
+        && dest_meta.len() == src_meta.len()
+        && src_meta.modified()? <= dest_meta.modified()?)
+}
+
+/// Creates the directory `dest`, replacing a file at its path
+fn prepare_sync_dir(dest: &Path) -> std::io::Result<()> {
+    if dest.is_file() {
+        fs::remove_file(dest)?;
+    }
+    fs::create_dir_all(dest)
+}
+
+fn remove_dir_if_exists(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)?;
+    }
+    Ok(())
+}
+
+/// Removes the files and directories directly in `dir` whose names aren't in `names`
+fn remove_entries_not_in(
+    dir: &Path,
+    names: &HashSet<OsString>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if names.contains(&entry.file_name()) {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            fs::remove_dir_all(entry.path())?;
+        } else {
+            fs::remove_file(entry.path())?;
+        }
+    }
+    Ok(())
+}
+
 pub fn get_host_crate_info(cargo_toml_path: &Path) -> HostCrateInfo {
     let existing_cargo_toml = toml_edit::Document::from_str(
         &fs::read_to_string(fs::canonicalize(cargo_toml_path).unwrap()).unwrap(),
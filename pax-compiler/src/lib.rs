@@ -14,52 +14,212 @@ extern crate serde;
 
 extern crate core;
 mod building;
+pub mod cancellation;
 mod cartridge_generation;
+mod errors;
 pub mod formatting;
 pub mod helpers;
+mod lint;
+pub mod manifest_export;
+pub mod progress;
+pub mod publishing;
+mod running;
+mod svg_import;
+mod validation;
 
 pub mod design_server;
+pub mod workspace;
 
 use color_eyre::eyre;
 use color_eyre::eyre::Report;
 use eyre::eyre;
 use fs_extra::dir::{self, CopyOptions};
-use helpers::{copy_dir_recursively, wait_with_output, ERR_SPAWN};
+use helpers::{
+    copy_dir_recursively, sync_dir_recursively, sync_embedded_dir, wait_with_timeout, ERR_SPAWN,
+};
+use include_dir::Dir;
+use pax_manifest::cfg::BuildCfg;
+use pax_manifest::constants::CFG_ATTRIBUTE;
 use pax_manifest::{
-    ComponentDefinition, ComponentTemplate, PaxManifest, TemplateNodeDefinition, TypeId,
+    ComponentDefinition, ComponentTemplate, ManifestSet, PaxManifest, TaggedManifest,
+    TemplateNodeDefinition, TypeId,
 };
+use pax_runtime_api::PaxValue;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fs;
 use std::io::Write;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 #[cfg(unix)]
 use std::os::unix::process::CommandExt;
 
 use crate::building::build_project_with_cartridge;
+pub use crate::building::std_features::{StdDependency, StdFeatureSelection, STD_FEATURES};
+pub use crate::cancellation::{CancellationToken, LatestBuild};
 
-use crate::cartridge_generation::generate_cartridge_partial_rs;
+pub use crate::cartridge_generation::cfg::{CfgPass, CfgReport};
+pub use crate::cartridge_generation::constant_folding::ConstantFoldingPass;
+pub use crate::cartridge_generation::defines::{
+    parse_define, DefinesPass, DefinesReport, DEFINES_SYMBOL,
+};
+pub use crate::cartridge_generation::tree_shaking::{TreeShakingPass, TreeShakingReport};
+pub use crate::cartridge_generation::{
+    generate_cartridge_partial_rs, GeneratedCartridge, CARTRIDGE_COMPONENTS_DIR,
+};
+pub use crate::errors::build_error::BuildError;
+pub use crate::errors::source_map::{PaxSourceLocation, SourceMap};
+pub use crate::formatting::{FileFormatStatus, FormatOptions, FormatReport};
+pub use crate::lint::{
+    lint, pax_lint, Lint, LintConfig, LintDiagnostic, LintKind, LintSeverity, MaxTemplateDepth,
+    NoDeprecatedTypes, NoEmptyComponents, PaxLintRule, RequireAccessibilityLabel,
+};
+pub use crate::progress::{Phase, PhaseTiming, ProgressMode, ProgressReporter};
+pub use crate::running::{AppAccess, BuildArtifacts, RunningApp};
+pub use crate::svg_import::{import_svg, import_svg_lossy, ImportError, SvgImport, UnsupportedSvg};
+pub use crate::validation::validate_settings;
+use crate::workspace::CargoProject;
+pub use pax_manifest::validation::{
+    LOSSY_COERCION, MISMATCHED_TYPE, UNKNOWN_PROPERTY, UNKNOWN_TOKEN, UNRESOLVED_TYPE,
+};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Output};
 
 use crate::helpers::{
-    get_or_create_pax_directory, update_pax_dependency_versions, INTERFACE_DIR_NAME, PAX_BADGE,
-    PAX_CREATE_LIBDEV_TEMPLATE_DIR_NAME, PAX_CREATE_TEMPLATE, PAX_IOS_INTERFACE_TEMPLATE,
-    PAX_MACOS_INTERFACE_TEMPLATE, PAX_SWIFT_CARTRIDGE_TEMPLATE, PAX_SWIFT_COMMON_TEMPLATE,
-    PAX_WEB_INTERFACE_TEMPLATE,
+    get_or_create_pax_directory, update_pax_dependency_versions, BUILD_REPORT_FILE_NAME,
+    INTERFACE_DIR_NAME, PAX_BADGE, PAX_CREATE_LIBDEV_TEMPLATE_DIR_NAME,
+    PAX_CREATE_MINIMAL_TEMPLATE, PAX_CREATE_STACKER_TEMPLATE, PAX_CREATE_TEMPLATE,
+    PAX_IOS_INTERFACE_TEMPLATE, PAX_MACOS_INTERFACE_TEMPLATE, PAX_SWIFT_CARTRIDGE_TEMPLATE,
+    PAX_SWIFT_COMMON_TEMPLATE, PAX_WEB_INTERFACE_TEMPLATE, TREE_SHAKING_REPORT_FILE_NAME,
 };
 
 pub struct RunContext {
     pub target: RunTarget,
     pub project_path: PathBuf,
-    pub verbose: bool,
-    pub should_also_run: bool,
+    /// How much the build prints, see [`Verbosity`]
+    pub verbosity: Verbosity,
     pub is_libdev_mode: bool,
     pub process_child_ids: Arc<Mutex<Vec<u64>>>,
     pub should_run_designer: bool,
     pub is_release: bool,
+    /// Deadline for each build subprocess (parser, cargo, wasm-pack, xcodebuild, ...),
+    /// after which it's killed and the build fails.  `None` waits indefinitely
+    pub timeout: Option<Duration>,
+    /// Cargo binary for the parser and chassis builds, instead of the `cargo` on `PATH`
+    pub cargo_bin: Option<PathBuf>,
+    /// Toolchain for the parser and chassis builds, passed as `cargo +<toolchain>`,
+    /// e.g. a pinned nightly
+    pub toolchain: Option<String>,
+    /// Defines passed with `--define key=value`, overriding those in `[package.metadata.pax.defines]`
+    pub defines: BTreeMap<String, PaxValue>,
+    /// Cargo features of the app crate enabled for both the parser and chassis builds,
+    /// e.g. to build the `premium` variant of a project
+    pub extra_features: Vec<String>,
+    /// Where to write the manifest baked into the cartridge, and its userland-only variant next to it,
+    /// for external tooling, see [`manifest_export`]
+    pub emit_manifest: Option<PathBuf>,
+    /// Generates the cartridge as one module per component under `.pax/cartridge/` rather than as a
+    /// single file, for projects whose cartridge is slow to compile, see [`generate_cartridge_partial_rs`]
+    pub split_cartridge: bool,
+    /// Cancels the build between phases, killing the build subprocess running at the time,
+    /// see [`cancellation`]
+    pub cancellation: CancellationToken,
+    /// Reports the phases of the build, and the output of its subprocesses, see [`progress`]
+    pub progress: ProgressReporter,
 }
 
-#[derive(PartialEq)]
+impl RunContext {
+    /// A `cargo` command honoring `cargo_bin`, `toolchain` and `verbosity`
+    pub fn cargo_command(&self) -> Command {
+        let mut cmd = helpers::cargo_command(self.cargo_bin.as_deref(), self.toolchain.as_deref());
+        self.set_cargo_verbosity(&mut cmd);
+        cmd
+    }
+
+    /// Makes the cargo run by `cmd`, directly or through a tool like `wasm-pack`, quiet at
+    /// [`Verbosity::Quiet`] and verbose from [`Verbosity::Verbose`] on
+    pub fn set_cargo_verbosity(&self, cmd: &mut Command) {
+        match self.verbosity {
+            Verbosity::Quiet => {
+                cmd.env("CARGO_TERM_QUIET", "true");
+            }
+            Verbosity::Normal => {}
+            Verbosity::Verbose | Verbosity::Trace => {
+                cmd.env("CARGO_TERM_VERBOSE", "true");
+            }
+        }
+    }
+
+    /// Whether output meant for `level` is printed, e.g. `prints(Verbosity::Normal)` for status badges
+    pub fn prints(&self, level: Verbosity) -> bool {
+        self.verbosity >= level
+    }
+
+    /// Prints a status line through `progress` if output meant for `level` is printed
+    pub fn status(&self, level: Verbosity, line: &str) {
+        if self.prints(level) {
+            self.progress.message(line);
+        }
+    }
+
+    /// Shows the captured output of the subprocess run for `step` through `progress`, expanded if
+    /// the step failed or the build is verbose
+    pub fn child_output(&self, step: &str, output: &str, failed: bool) {
+        self.progress
+            .child_output(step, output, failed || self.prints(Verbosity::Verbose));
+    }
+
+    /// `--color` for the cargo builds, whose output is shown through `progress`
+    pub fn cargo_color(&self) -> &'static str {
+        if self.progress.uses_ansi() {
+            "always"
+        } else {
+            "never"
+        }
+    }
+
+    /// `--message-format` for the cargo builds whose diagnostics are remapped through the source map
+    pub fn cargo_message_format(&self) -> &'static str {
+        if self.progress.uses_ansi() {
+            "--message-format=json-diagnostic-rendered-ansi"
+        } else {
+            "--message-format=json"
+        }
+    }
+}
+
+/// How much a build prints.  Errors and warnings are printed at every level
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Nothing but errors and warnings
+    Quiet,
+    /// Status badges, and the reports of the manifest passes that changed something
+    #[default]
+    Normal,
+    /// Also cargo's full output, with cargo itself made verbose, and every pass report
+    Verbose,
+    /// Also a summary of the manifest baked into the cartridge, and of the generated cartridge
+    Trace,
+}
+
+impl std::str::FromStr for Verbosity {
+    type Err = Report;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "quiet" => Ok(Verbosity::Quiet),
+            "normal" => Ok(Verbosity::Normal),
+            "verbose" => Ok(Verbosity::Verbose),
+            "trace" => Ok(Verbosity::Trace),
+            _ => Err(eyre!(
+                "Unknown verbosity `{}`, expected one of quiet, normal, verbose or trace",
+                input
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum RunTarget {
     #[allow(non_camel_case_types)]
     macOS,
@@ -68,10 +228,11 @@ pub enum RunTarget {
     iOS,
 }
 
-/// For the specified file path or current working directory, first compile Pax project,
-/// then run it with a patched build of the `chassis` appropriate for the specified platform
+/// For the specified file path or current working directory, compile the Pax project into a
+/// patched build of the `chassis` appropriate for the specified platform, to be launched with
+/// [`perform_run`]
 /// See: pax-compiler-sequence-diagram.png
-pub fn perform_build(ctx: &RunContext) -> eyre::Result<(PaxManifest, Option<PathBuf>), Report> {
+pub fn perform_build(ctx: &RunContext) -> eyre::Result<BuildArtifacts, Report> {
     //Compile ts files if applicable (this needs to happen before copying to .pax)
     if ctx.is_libdev_mode && ctx.target == RunTarget::Web {
         if let Ok(root) = std::env::var("PAX_WORKSPACE_ROOT") {
@@ -101,38 +262,210 @@ pub fn perform_build(ctx: &RunContext) -> eyre::Result<(PaxManifest, Option<Path
         }
     }
 
-    let pax_dir = get_or_create_pax_directory(&ctx.project_path);
+    let phase = ctx.progress.start(Phase::PkgSync);
+    let project = CargoProject::locate(&ctx.project_path)?;
+    project.prepare_root_manifest()?;
+    let pax_dir = get_or_create_pax_directory(&project);
 
     // Copy interface files for relevant path
-    copy_interface_files_for_target(ctx, &pax_dir);
+    ctx.cancellation.check()?;
+    copy_interface_files_for_target(ctx, &project, &pax_dir);
+    phase.done();
+
+    ctx.cancellation.check()?;
+    let manifests = parse_project(ctx, &project)?;
+    let phase = ctx.progress.start(Phase::Codegen);
+    let diagnostics = match ctx.emit_manifest {
+        Some(_) => project_diagnostics(&project, &manifests.userland)?,
+        None => vec![],
+    };
+    let processed = process_manifests(ctx, &project, &pax_dir, manifests)?;
+    if let Some(path) = &ctx.emit_manifest {
+        emit_manifest(ctx, path, &processed, &diagnostics)?;
+    }
+    let ProcessedManifests {
+        userland: userland_manifest,
+        merged: merged_manifest,
+        cartridge: cartridge_manifest,
+        designer: designer_manifest,
+        defines_report,
+        cfg_report,
+        std_features,
+        build_features,
+        excluded_assets,
+        ..
+    } = processed;
+    if ctx.prints(Verbosity::Trace) {
+        ctx.progress.details(&manifest_summary(&cartridge_manifest));
+    }
 
-    println!("{} 🛠️  Building parser binary with `cargo`...", *PAX_BADGE);
+    ctx.cancellation.check()?;
+    let cartridge = generate_cartridge_partial_rs(
+        &pax_dir,
+        &merged_manifest,
+        &cartridge_manifest,
+        designer_manifest,
+        ctx.split_cartridge,
+    );
+    let build_report = format!(
+        "manifest hash: {:016x}\n{}{}{}",
+        cartridge.manifest_hash,
+        defines_report.to_report_string(),
+        cfg_report.to_report_string(),
+        std_features.to_report_string()
+    );
+    if ctx.prints(Verbosity::Trace) {
+        for path in std::iter::once(&cartridge.path).chain(&cartridge.component_paths) {
+            let len = fs::metadata(path).map(|m| m.len()).unwrap_or_default();
+            ctx.progress
+                .message(&format!("📝 Generated {} ({} bytes)", path.display(), len));
+        }
+        ctx.progress.details(&build_report);
+    }
+    // the reporter appends the timings of the build's phases as they end
+    ctx.progress
+        .attach_report(pax_dir.join(BUILD_REPORT_FILE_NAME), build_report)?;
+    // maps compile errors in the generated cartridge back to the `.pax` source that produced them
+    let source_map = SourceMap::extract_ranges_from_generated_code(
+        std::iter::once(&cartridge.path).chain(&cartridge.component_paths),
+        &merged_manifest,
+    );
+    phase.done();
+
+    //7. Build full project from source
+    ctx.cancellation.check()?;
+    let (app_path, interface_dir) = build_project_with_cartridge(
+        &pax_dir,
+        &ctx,
+        &project,
+        Arc::clone(&ctx.process_child_ids),
+        merged_manifest.assets_dirs,
+        &excluded_assets,
+        &source_map,
+        &build_features,
+    )?;
+
+    Ok(BuildArtifacts {
+        target: ctx.target,
+        manifest: userland_manifest,
+        project_root: project.crate_root.clone(),
+        is_designer: ctx.should_run_designer,
+        app_path,
+        interface_dir,
+    })
+}
+
+/// Runs the parser binary of `project` with the features and cargo of `ctx`, and reads the manifests it prints
+fn parse_project(ctx: &RunContext, project: &CargoProject) -> eyre::Result<ManifestSet, Report> {
+    let phase = ctx.progress.start(Phase::Parse);
 
     // Run parser bin from host project with `--features parser`
-    let output = run_parser_binary(
-        &ctx.project_path,
+    let output = run_parser_binary_for_project(
+        project,
+        ctx.cargo_command(),
         Arc::clone(&ctx.process_child_ids),
         ctx.should_run_designer,
+        &ctx.extra_features,
+        ctx.timeout,
+        &ctx.cancellation,
+        ctx.cargo_color(),
+    )?;
+
+    // stdout carries the manifests, stderr cargo's output
+    ctx.child_output(
+        "cargo run --bin parser",
+        &String::from_utf8_lossy(&output.stderr),
+        !output.status.success(),
     );
 
-    // Forward stderr only
-    std::io::stderr()
-        .write_all(output.stderr.as_slice())
-        .unwrap();
+    check_parser_output(&output)?;
 
-    if !output.status.success() {
-        return Err(eyre!(
-            "Parsing failed — there is likely a syntax error in the provided pax"
+    let out = String::from_utf8(output.stdout).unwrap();
+    let mut manifests = parse_parser_output(&out)?;
+    manifests.userland.tokens = project.tokens()?;
+    phase.done();
+    Ok(manifests)
+}
+
+/// Lists the components of `manifest` with the size of their templates and settings, for
+/// [`Verbosity::Trace`] builds
+fn manifest_summary(manifest: &PaxManifest) -> String {
+    let mut summary = format!(
+        "📋 Manifest: main component `{}`, {} component(s), {} type(s)\n",
+        manifest.main_component_type_id,
+        manifest.components.len(),
+        manifest.type_table.len()
+    );
+    for (type_id, component) in &manifest.components {
+        let nodes = component
+            .template
+            .as_ref()
+            .map_or(0, |t| t.get_nodes().len());
+        let settings = component.settings.as_ref().map_or(0, Vec::len);
+        summary.push_str(&format!(
+            "  {}: {} template node(s), {} settings block(s){}\n",
+            type_id,
+            nodes,
+            settings,
+            if component.is_primitive {
+                ", primitive"
+            } else if component.is_struct_only_component {
+                ", struct-only"
+            } else {
+                ""
+            }
         ));
     }
+    summary
+}
 
-    let out = String::from_utf8(output.stdout).unwrap();
+/// The manifests of a project after the passes run ahead of cartridge generation, see [`process_manifests`]
+struct ProcessedManifests {
+    /// The userland manifest, with the root wrapper component and, in designer builds, the designer's components
+    userland: PaxManifest,
+    /// The userland and designer manifests merged, which components and types are generated from
+    merged: PaxManifest,
+    /// The manifest baked into the cartridge, with defines resolved, constants folded and unreachable
+    /// components shaken out
+    cartridge: PaxManifest,
+    designer: Option<PaxManifest>,
+    /// Type ids of the components and types of the userland manifest, before the designer's are merged in
+    userland_type_ids: HashSet<TypeId>,
+    defines_report: DefinesReport,
+    cfg_report: CfgReport,
+    std_features: StdFeatureSelection,
+    /// Cargo features of the app crate to build with, including the selected pax-std families
+    build_features: Vec<String>,
+    excluded_assets: HashSet<PathBuf>,
+}
 
-    let mut manifests: Vec<PaxManifest> =
-        serde_json::from_str(&out).expect(&format!("Malformed JSON from parser: {}", &out));
+/// Validates the settings of the parsed `manifests`, merges in the designer's, and runs the passes
+/// over the manifest baked into the cartridge, as configured by `ctx` and `project`
+fn process_manifests(
+    ctx: &RunContext,
+    project: &CargoProject,
+    pax_dir: &Path,
+    manifests: ManifestSet,
+) -> eyre::Result<ProcessedManifests, Report> {
+    // Check settings against the properties they set before generating any code for them
+    let (errors, warnings): (Vec<_>, Vec<_>) = validate_settings(&manifests.userland)
+        .into_iter()
+        .partition(|d| d.severity == LintSeverity::Error);
+    for warning in &warnings {
+        ctx.progress.warning(&warning.to_string());
+    }
+    if !errors.is_empty() {
+        return Err(eyre!(
+            "Invalid settings in the provided pax:\n{}",
+            errors
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>()
+                .join("\n")
+        ));
+    }
 
-    // Simple starting convention: first manifest is userland, second manifest is designer; other schemas are undefined
-    let mut userland_manifest = manifests.remove(0);
+    let mut userland_manifest = manifests.userland;
 
     let mut merged_manifest = userland_manifest.clone();
 
@@ -153,11 +486,21 @@ pub fn perform_build(ctx: &RunContext) -> eyre::Result<(PaxManifest, Option<Path
             primitive_instance_import_path: None,
             template: Some(wrapper_component_template),
             settings: None,
+            extends: None,
         },
     );
 
+    let userland_type_ids = userland_manifest
+        .components
+        .keys()
+        .chain(userland_manifest.type_table.keys())
+        .cloned()
+        .collect();
+
     let designer_manifest = if ctx.should_run_designer {
-        let designer_manifest = manifests.remove(0);
+        let designer_manifest = manifests.designer.ok_or_else(|| {
+            eyre!("Parser output has no `designer` manifest; was the parser built with the `designer` feature?")
+        })?;
         merged_manifest.merge_in_place(&designer_manifest);
 
         userland_manifest
@@ -172,49 +515,497 @@ pub fn perform_build(ctx: &RunContext) -> eyre::Result<(PaxManifest, Option<Path
         None
     };
 
-    println!("{} 🦀 Generating Rust", *PAX_BADGE);
-    generate_cartridge_partial_rs(
-        &pax_dir,
-        &merged_manifest,
-        &userland_manifest,
-        designer_manifest,
+    // Resolve `$defines` in the manifest baked into the cartridge, pruning the `if` branches they decide.
+    // Designer builds only check them, and resolve them at runtime, for the same reason as folding below.
+    let mut defines = project.defines()?;
+    defines.extend(ctx.defines.clone());
+    let mut cartridge_manifest = userland_manifest.clone();
+    let defines_report = DefinesPass::new(&defines)
+        .run(&mut cartridge_manifest, !ctx.should_run_designer)
+        .map_err(|e| eyre!("Invalid use of `{}`:\n{}", DEFINES_SYMBOL, e))?;
+    userland_manifest.defines = cartridge_manifest.defines.clone();
+    merged_manifest.defines = cartridge_manifest.defines.clone();
+    if ctx.prints(Verbosity::Verbose)
+        || defines_report.pruned_branches + defines_report.inlined_branches > 0
+    {
+        ctx.status(
+            Verbosity::Normal,
+            &format!(
+                "🚩 Resolved {} define(s), pruned {} and inlined {} `if` branch(es)",
+                defines_report.used.len(),
+                defines_report.pruned_branches,
+                defines_report.inlined_branches
+            ),
+        );
+    }
+
+    // Leave out the template nodes whose `cfg` condition the build's target and features don't satisfy.
+    // Designer builds keep them, for the same reason as folding below, and skip them at runtime.
+    let mut cfg_features: BTreeSet<String> = project.default_features()?.into_iter().collect();
+    cfg_features.extend(ctx.extra_features.iter().cloned());
+    let target_str: &str = (&ctx.target).into();
+    let build_cfg = BuildCfg {
+        target: target_str.to_lowercase(),
+        features: cfg_features,
+    };
+    let cfg_report = CfgPass::new(build_cfg)
+        .run(&mut cartridge_manifest, !ctx.should_run_designer)
+        .map_err(|e| eyre!("Invalid `{}` condition:\n{}", CFG_ATTRIBUTE, e))?;
+    userland_manifest.build_cfg = cartridge_manifest.build_cfg.clone();
+    merged_manifest.build_cfg = cartridge_manifest.build_cfg.clone();
+    if ctx.prints(Verbosity::Verbose) || cfg_report.pruned_nodes > 0 {
+        ctx.status(
+            Verbosity::Normal,
+            &format!(
+                "🔀 Checked {} cfg condition(s), pruned {} node(s)",
+                cfg_report.conditions, cfg_report.pruned_nodes
+            ),
+        );
+    }
+
+    // Fold constant expressions in the manifest baked into the cartridge.  Skipped when running the designer,
+    // since the designer serializes this manifest back into the user's `.pax` source.
+    if !ctx.should_run_designer {
+        let folded_count = ConstantFoldingPass::default().run(&mut cartridge_manifest);
+        ctx.status(
+            Verbosity::Verbose,
+            &format!("📐 Folded {} constant expression(s)", folded_count),
+        );
+    }
+
+    // Release builds leave out the components unreachable from the main component, and the assets
+    // nothing left references.  Dev and designer builds keep everything, so the designer's library is complete
+    let mut excluded_assets = HashSet::new();
+    if ctx.is_release && !ctx.should_run_designer {
+        let pass = TreeShakingPass::new(
+            vec![
+                wrapper_type_id.clone(),
+                userland_manifest.main_component_type_id.clone(),
+            ],
+            project.keep_components()?,
+        );
+        let mut report = pass.run(&mut cartridge_manifest);
+        report.apply(&mut merged_manifest);
+        report.exclude_unreferenced_assets(
+            &cartridge_manifest,
+            &merged_manifest.assets_dirs,
+            &project.crate_root.join("src"),
+        );
+        for (type_id, reason) in &report.retained_components {
+            ctx.progress
+                .warning(&format!("⚠️  Keeping `{}`: {}", type_id, reason));
+        }
+        let report_path = pax_dir.join(TREE_SHAKING_REPORT_FILE_NAME);
+        fs::write(&report_path, report.to_report_string())?;
+        ctx.status(
+            Verbosity::Normal,
+            &format!(
+                "🌳 Tree shaking {} (see {})",
+                report.summary(),
+                report_path.to_str().unwrap()
+            ),
+        );
+        excluded_assets = report.excluded_asset_paths();
+    }
+
+    // Enable only the pax-std families the project uses, leaving the rest out of the cartridge and the
+    // build.  Designer builds enable every family, so the designer's library is complete
+    let std_features = if ctx.should_run_designer {
+        StdFeatureSelection::all()
+    } else {
+        let selection =
+            StdFeatureSelection::select(&cartridge_manifest, &project.std_feature_overrides()?)
+                .map_err(|e| eyre!("Invalid pax-std feature selection:\n{}", e))?;
+        selection.prune(&mut cartridge_manifest);
+        selection.prune(&mut merged_manifest);
+        selection
+    };
+    let mut build_features = ctx.extra_features.clone();
+    let mut all_families_compiled = None;
+    match project.std_dependency()? {
+        Some(dependency) => {
+            build_features.extend(std_features.cargo_features(dependency));
+            if !ctx.should_run_designer && project.keeps_std_default_features(dependency)? {
+                all_families_compiled = Some(dependency.crate_name());
+            }
+        }
+        None => ctx.progress.warning(&format!(
+            "⚠️  {} depends on neither pax-kit nor pax-std; not enabling pax-std features",
+            project.package_name
+        )),
+    }
+    if ctx.prints(Verbosity::Verbose) {
+        ctx.progress.details(&std_features.to_report_string());
+    }
+    let summary = match all_families_compiled {
+        Some(dependency) => format!(
+            "{} (all families are compiled, as the default features of {} are on; set `default-features = false` to compile only these)",
+            std_features.summary(),
+            dependency
+        ),
+        None => std_features.summary(),
+    };
+    ctx.status(
+        Verbosity::Normal,
+        &format!("🧩 pax-std features: {}", summary),
     );
-    // source_map.extract_ranges_from_generated_code(cartridge_path.to_str().unwrap());
 
-    //7. Build full project from source
-    println!("{} 🧱 Building project with `cargo`", *PAX_BADGE);
-    let build_dir = build_project_with_cartridge(
-        &pax_dir,
-        &ctx,
-        Arc::clone(&ctx.process_child_ids),
-        merged_manifest.assets_dirs,
-        userland_manifest.clone(),
+    Ok(ProcessedManifests {
+        userland: userland_manifest,
+        merged: merged_manifest,
+        cartridge: cartridge_manifest,
+        designer: designer_manifest,
+        userland_type_ids,
+        defines_report,
+        cfg_report,
+        std_features,
+        build_features,
+        excluded_assets,
+    })
+}
+
+/// Writes the manifest baked into the cartridge to `path`, along with its userland-only variant, see
+/// [`manifest_export`]
+fn emit_manifest(
+    ctx: &RunContext,
+    path: &Path,
+    processed: &ProcessedManifests,
+    diagnostics: &[LintDiagnostic],
+) -> eyre::Result<(), Report> {
+    let userland_path = manifest_export::write_manifest_exports(
+        path,
+        &processed.cartridge,
+        &processed.userland_type_ids,
+        &processed.defines_report.used,
+        diagnostics,
     )?;
+    ctx.status(
+        Verbosity::Normal,
+        &format!(
+            "📄 Wrote manifest to {} and {}",
+            path.display(),
+            userland_path.display()
+        ),
+    );
+    Ok(())
+}
 
-    Ok((userland_manifest, build_dir))
+/// Launch an app built by [`perform_build`] on its target: web builds are served from this process
+/// (through the design server for designer builds), macOS apps are run, and iOS apps are installed and
+/// launched on a simulator.  The returned [`RunningApp`] stops the app when dropped
+pub fn perform_run(
+    ctx: &RunContext,
+    artifacts: &BuildArtifacts,
+) -> eyre::Result<RunningApp, Report> {
+    let phase = ctx.progress.start(Phase::Launch);
+    let app = match artifacts.target {
+        RunTarget::Web => running::serve_web_app(ctx, artifacts)?,
+        RunTarget::macOS | RunTarget::iOS => building::apple::launch_apple_app(ctx, artifacts)?,
+    };
+    phase.done();
+    Ok(app)
 }
 
-fn copy_interface_files_for_target(ctx: &RunContext, pax_dir: &PathBuf) {
+/// Formats the `.pax` files of the project at `project_path`, skipping those ignored by `.gitignore`,
+/// see [`formatting::format_project`]
+pub fn perform_format(
+    project_path: &Path,
+    options: &FormatOptions,
+) -> eyre::Result<FormatReport, Report> {
+    if !project_path.is_dir() {
+        return Err(eyre!("{:?} is not a directory", project_path));
+    }
+    Ok(formatting::format_project(project_path, options))
+}
+
+/// Parses the project at `project_path` and runs the advisory lints over its manifest, see [`lint`],
+/// followed by the rules selected in its `pax.workspace.toml`, see [`pax_lint`], and the checks of
+/// settings against the properties they set, see [`validate_settings`]
+pub fn perform_lint(
+    project_path: &PathBuf,
+    process_child_ids: Arc<Mutex<Vec<u64>>>,
+) -> eyre::Result<Vec<LintDiagnostic>, Report> {
+    let project = CargoProject::locate(project_path)?;
+    println!("{} 🛠️  Building parser binary with `cargo`...", *PAX_BADGE);
+    let output = run_parser_binary_for_project(
+        &project,
+        helpers::cargo_command(None, None),
+        process_child_ids,
+        false,
+        &[],
+        None,
+        &CancellationToken::new(),
+        "always",
+    )?;
+    std::io::stderr()
+        .write_all(output.stderr.as_slice())
+        .unwrap();
+    check_parser_output(&output)?;
+
+    let out = String::from_utf8(output.stdout).unwrap();
+    let mut manifests = parse_parser_output(&out)?;
+    manifests.userland.tokens = project.tokens()?;
+    project_diagnostics(&project, &manifests.userland)
+}
+
+/// The diagnostics [`perform_lint`] reports for the parsed `userland` manifest of `project`
+fn project_diagnostics(
+    project: &CargoProject,
+    userland: &PaxManifest,
+) -> eyre::Result<Vec<LintDiagnostic>, Report> {
+    let lint_config = match project.workspace_config()? {
+        Some(document) => LintConfig::from_document(&document)?,
+        None => LintConfig::default(),
+    };
+    let rules = lint_config.rules()?;
+    let mut diagnostics: Vec<LintDiagnostic> = lint(userland)
+        .into_iter()
+        .map(LintDiagnostic::from)
+        .collect();
+    diagnostics.extend(pax_lint(userland, &rules));
+    diagnostics.extend(validate_settings(userland));
+    Ok(diagnostics)
+}
+
+/// Parses the project of `ctx` and writes its manifest to `path` as [`perform_build`] does with
+/// `emit_manifest`, running the same passes over it, without generating a cartridge or building anything
+/// past the parser binary
+pub fn perform_emit_manifest(ctx: &RunContext, path: &Path) -> eyre::Result<(), Report> {
+    let project = CargoProject::locate(&ctx.project_path)?;
+    project.prepare_root_manifest()?;
+    let pax_dir = get_or_create_pax_directory(&project);
+    let manifests = parse_project(ctx, &project)?;
+    let diagnostics = project_diagnostics(&project, &manifests.userland)?;
+    let processed = process_manifests(ctx, &project, &pax_dir, manifests)?;
+    emit_manifest(ctx, path, &processed, &diagnostics)
+}
+
+/// Publishes the Pax component library at `project_path` to crates.io with `cargo publish`, or only
+/// packages and verifies it with `dry_run`.  Publishing is refused if the project has lint errors (see
+/// [`perform_lint`]), if its `Cargo.toml` has no `[package.metadata.pax]` section, or if templates its
+/// components reference with `#[file(...)]` would be left out of the package, see [`publishing`].
+/// Once published, the patch version in `Cargo.toml` is bumped for the next release
+pub fn perform_publish(project_path: &Path, dry_run: bool) -> eyre::Result<(), Report> {
+    let project = CargoProject::locate(project_path)?;
+    let manifest_path = project.crate_root.join("Cargo.toml");
+    let mut manifest = workspace::read_manifest(&manifest_path)?;
+    publishing::check_pax_metadata(&manifest)?;
+
+    let diagnostics = perform_lint(&project.crate_root, Arc::new(Mutex::new(vec![])))?;
+    let errors: Vec<_> = diagnostics
+        .iter()
+        .filter(|d| d.severity == LintSeverity::Error)
+        .collect();
+    if !errors.is_empty() {
+        for error in &errors {
+            eprintln!("{}\n", error);
+        }
+        return Err(eyre!("{} lint error(s), not publishing", errors.len()));
+    }
+
+    let mut cmd = helpers::cargo_command(None, None);
+    cmd.current_dir(project.cargo_cwd())
+        .arg("package")
+        .arg("--list")
+        .arg("--allow-dirty");
+    project.add_package_args(&mut cmd);
+    let output = cmd
+        .output()
+        .map_err(|e| eyre!("Failed to run `cargo package --list`: {}", e))?;
+    if !output.status.success() {
+        return Err(eyre!(
+            "`cargo package --list` failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    let packaged: Vec<PathBuf> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(PathBuf::from)
+        .collect();
+    let referenced = publishing::referenced_pax_files(&project.crate_root)?;
+    let unpackaged = publishing::unpackaged_pax_files(&referenced, &packaged);
+    if !unpackaged.is_empty() {
+        return Err(eyre!(
+            "These templates would be left out of the package, breaking the build of crates depending on it: {}.  Check `include` and `exclude` under `[package]` in Cargo.toml",
+            unpackaged
+                .iter()
+                .map(|file| file.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    println!(
+        "{} 📦 Publishing {}{}...",
+        *PAX_BADGE,
+        project.package_name,
+        if dry_run { " (dry run)" } else { "" }
+    );
+    let mut cmd = helpers::cargo_command(None, None);
+    cmd.current_dir(project.cargo_cwd()).arg("publish");
+    project.add_package_args(&mut cmd);
+    if dry_run {
+        cmd.arg("--dry-run");
+    }
+    let status = cmd
+        .status()
+        .map_err(|e| eyre!("Failed to run `cargo publish`: {}", e))?;
+    if !status.success() {
+        return Err(eyre!("`cargo publish` failed"));
+    }
+    if dry_run {
+        return Ok(());
+    }
+
+    match publishing::bump_patch_version(&mut manifest) {
+        Some(version) => {
+            fs::write(&manifest_path, manifest.to_string())
+                .map_err(|e| eyre!("Failed to write {:?}: {}", manifest_path, e))?;
+            println!("{} Bumped version to {}", *PAX_BADGE, version);
+        }
+        None => println!(
+            "{} Published.  The version in Cargo.toml isn't a plain `major.minor.patch`, bump it before the next release",
+            *PAX_BADGE
+        ),
+    }
+    Ok(())
+}
+
+/// Parses the project at `project_path` and serves its manifest over JSON-RPC on stdin and stdout
+/// until stdin is closed, see [`pax_designtime::orm::rpc`].  Progress is reported on stderr, so
+/// that stdout carries only responses.  Changes are made to the manifest in memory only
+pub fn perform_manifest_rpc(
+    project_path: &PathBuf,
+    process_child_ids: Arc<Mutex<Vec<u64>>>,
+) -> eyre::Result<(), Report> {
+    let project = CargoProject::locate(project_path)?;
+    eprintln!("{} 🛠️  Building parser binary with `cargo`...", *PAX_BADGE);
+    let output = run_parser_binary_for_project(
+        &project,
+        helpers::cargo_command(None, None),
+        process_child_ids,
+        false,
+        &[],
+        None,
+        &CancellationToken::new(),
+        "always",
+    )?;
+    std::io::stderr()
+        .write_all(output.stderr.as_slice())
+        .unwrap();
+    check_parser_output(&output)?;
+
+    let out = String::from_utf8(output.stdout).unwrap();
+    let mut manifests = parse_parser_output(&out)?;
+    manifests.userland.tokens = project.tokens()?;
+    let orm = pax_designtime::orm::PaxManifestORM::new(manifests.userland);
+    eprintln!("{} 📡 Serving manifest over JSON-RPC on stdio", *PAX_BADGE);
+    pax_designtime::orm::rpc::ManifestRpcServer::new(orm)
+        .serve(std::io::stdin().lock(), std::io::stdout().lock())?;
+    Ok(())
+}
+
+/// Replays the designer session recorded in `path` (a bug report, a session recording or the
+/// session log of a project, see [`pax_designtime::orm::session`]) from its starting manifest,
+/// checking the manifest against each checkpoint, and fails at the first divergence
+pub fn perform_replay(path: &Path) -> eyre::Result<(), Report> {
+    let json = fs::read_to_string(path)?;
+    let recording =
+        pax_designtime::orm::session::SessionRecording::from_json(&json).map_err(Report::msg)?;
+    println!(
+        "{} 🎬 Replaying {} events from manifest {:016x}{}",
+        *PAX_BADGE,
+        recording.events.len(),
+        recording.start_hash,
+        if recording.redacted {
+            " (redacted)"
+        } else {
+            ""
+        }
+    );
+    let outcome = recording.replay();
+    match outcome.divergence {
+        None => {
+            println!(
+                "{} ✅ Replayed {} events, every checkpoint matched",
+                *PAX_BADGE, outcome.events_replayed
+            );
+            Ok(())
+        }
+        Some(divergence) => Err(eyre!(
+            "replay diverged after {} events at {}",
+            outcome.events_replayed,
+            divergence
+        )),
+    }
+}
+
+/// Reads the manifests printed by the parser binary, a list of [`TaggedManifest`]s selected by
+/// role.  Roles this version doesn't know are skipped with a notice.  The legacy positional
+/// output (a bare list whose first manifest is userland and second is designer) is still
+/// accepted for one release, with a deprecation warning.
+pub fn parse_parser_output(out: &str) -> eyre::Result<ManifestSet, Report> {
+    if let Ok(tagged) = serde_json::from_str::<Vec<TaggedManifest<serde_json::Value>>>(out) {
+        let set = ManifestSet::from_tagged(tagged)
+            .map_err(|e| eyre!("Invalid manifests from parser: {}", e))?;
+        for role in &set.unknown_roles {
+            println!(
+                "{} ℹ️  Ignoring parser manifest with unknown role `{}`",
+                *PAX_BADGE, role
+            );
+        }
+        let from_value = |role: &str, value: serde_json::Value| {
+            serde_json::from_value::<PaxManifest>(value)
+                .map_err(|e| eyre!("Malformed `{}` manifest from parser: {}", role, e))
+        };
+        return Ok(ManifestSet {
+            userland: from_value(
+                pax_manifest::constants::USERLAND_MANIFEST_ROLE,
+                set.userland,
+            )?,
+            designer: set
+                .designer
+                .map(|m| from_value(pax_manifest::constants::DESIGNER_MANIFEST_ROLE, m))
+                .transpose()?,
+            unknown_roles: set.unknown_roles,
+        });
+    }
+
+    let mut manifests: Vec<PaxManifest> = serde_json::from_str(out)
+        .map_err(|e| eyre!("Malformed JSON from parser: {}\n{}", e, out))?;
+    println!(
+        "{} ⚠️  Parser printed untagged manifests; this format is deprecated and will be removed in the next release. Rebuild against the current pax-macro to emit role-tagged manifests.",
+        *PAX_BADGE
+    );
+    if manifests.is_empty() {
+        return Err(eyre!("Parser printed no manifests"));
+    }
+    let userland = manifests.remove(0);
+    let designer = (!manifests.is_empty()).then(|| manifests.remove(0));
+    Ok(ManifestSet {
+        userland,
+        designer,
+        unknown_roles: vec![],
+    })
+}
+
+fn copy_interface_files_for_target(ctx: &RunContext, project: &CargoProject, pax_dir: &PathBuf) {
     let target_str: &str = (&ctx.target).into();
     let target_str_lower = &target_str.to_lowercase();
     let interface_path = pax_dir.join(INTERFACE_DIR_NAME).join(target_str_lower);
 
-    let _ = fs::remove_dir_all(&interface_path);
-    let _ = fs::create_dir_all(&interface_path);
-
-    let mut custom_interface = pax_dir
-        .parent()
-        .unwrap()
-        .join("interfaces")
-        .join(target_str_lower);
+    let mut custom_interface = project.crate_root.join("interfaces").join(target_str_lower);
     if ctx.target == RunTarget::Web {
         custom_interface = custom_interface.join("public");
     }
 
+    // Synced rather than copied from scratch, so that unchanged interface files keep their
+    // timestamps and don't force the chassis to rebuild
     if custom_interface.exists() {
-        copy_interface_files(&custom_interface, &interface_path);
+        sync_dir_recursively(&custom_interface, &interface_path)
+            .expect("Failed to copy interface files");
     } else {
-        copy_default_interface_files(&interface_path, ctx);
+        sync_default_interface_files(&interface_path, ctx);
     }
 
     // Copy common files for macOS and iOS builds
@@ -224,46 +1015,18 @@ fn copy_interface_files_for_target(ctx: &RunContext, pax_dir: &PathBuf) {
     }
 }
 
-fn copy_interface_files(src: &Path, dest: &Path) {
-    copy_dir_recursively(src, dest, &[]).expect("Failed to copy interface files");
-}
-
-fn copy_default_interface_files(interface_path: &Path, ctx: &RunContext) {
+fn sync_default_interface_files(interface_path: &Path, ctx: &RunContext) {
     if ctx.is_libdev_mode {
-        let pax_compiler_root = Path::new(env!("CARGO_MANIFEST_DIR"));
-        let interface_src = match ctx.target {
-            RunTarget::Web => pax_compiler_root
-                .join("files")
-                .join("interfaces")
-                .join("web")
-                .join("public"),
-            RunTarget::macOS => pax_compiler_root
-                .join("files")
-                .join("interfaces")
-                .join("macos")
-                .join("pax-app-macos"),
-            RunTarget::iOS => pax_compiler_root
-                .join("files")
-                .join("interfaces")
-                .join("ios")
-                .join("pax-app-ios"),
-        };
-
-        copy_dir_recursively(&interface_src, interface_path, &[])
+        sync_dir_recursively(&get_libdev_interface_path(ctx), interface_path)
             .expect("Failed to copy interface files");
     } else {
-        // File src is include_dir — recursively extract files from include_dir into full_path
-        match ctx.target {
-            RunTarget::Web => PAX_WEB_INTERFACE_TEMPLATE
-                .extract(interface_path)
-                .expect("Failed to extract web interface files"),
-            RunTarget::macOS => PAX_MACOS_INTERFACE_TEMPLATE
-                .extract(interface_path)
-                .expect("Failed to extract macos interface files"),
-            RunTarget::iOS => PAX_IOS_INTERFACE_TEMPLATE
-                .extract(interface_path)
-                .expect("Failed to extract ios interface files"),
-        }
+        // File src is include_dir — recursively sync files from include_dir into full_path
+        let template = match ctx.target {
+            RunTarget::Web => &PAX_WEB_INTERFACE_TEMPLATE,
+            RunTarget::macOS => &PAX_MACOS_INTERFACE_TEMPLATE,
+            RunTarget::iOS => &PAX_IOS_INTERFACE_TEMPLATE,
+        };
+        sync_embedded_dir(template, interface_path).expect("Failed to extract interface files");
     }
 }
 
@@ -302,15 +1065,15 @@ fn copy_common_swift_files(ctx: &RunContext, common_dest: &Path) {
 /// Ejects the interface files for the specified target platform
 /// Interface files will then be used to build the project
 pub fn perform_eject(ctx: &RunContext) -> eyre::Result<(), Report> {
-    let pax_dir = get_or_create_pax_directory(&ctx.project_path);
-    eject_interface_files(ctx, &pax_dir);
+    let project = CargoProject::locate(&ctx.project_path)?;
+    eject_interface_files(ctx, &project);
     Ok(())
 }
 
-fn eject_interface_files(ctx: &RunContext, pax_dir: &PathBuf) {
+fn eject_interface_files(ctx: &RunContext, project: &CargoProject) {
     let target_str: &str = (&ctx.target).into();
     let target_str_lower = &target_str.to_lowercase();
-    let custom_interfaces_dir = pax_dir.parent().unwrap().join("interfaces");
+    let custom_interfaces_dir = project.crate_root.join("interfaces");
     let mut target_custom_interface_dir = custom_interfaces_dir.join(target_str_lower);
     if ctx.target == RunTarget::Web {
         target_custom_interface_dir = target_custom_interface_dir.join("public");
@@ -364,7 +1127,10 @@ fn extract_interface_template(ctx: &RunContext, dest: &Path) -> Result<(), std::
 /// Clean all `.pax` temp files
 pub fn perform_clean(path: &str) {
     let path = PathBuf::from(path);
-    let pax_dir = path.join(".pax");
+    let pax_dir = match CargoProject::locate(&path) {
+        Ok(project) => project.pax_dir(),
+        Err(_) => path.join(".pax"),
+    };
     fs::remove_dir_all(&pax_dir).ok();
 }
 
@@ -372,6 +1138,44 @@ pub struct CreateContext {
     pub path: String,
     pub is_libdev_mode: bool,
     pub version: String,
+    pub template: ProjectTemplate,
+}
+
+/// Starting point scaffolded by `pax create`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ProjectTemplate {
+    /// A single component with a click counter
+    Minimal,
+    /// Nested `Stacker` layouts with a repeated list of rows
+    Stacker,
+    /// The example gallery (calculator, fireworks, space game, …) used to try out the designer
+    #[default]
+    DesignerPlayground,
+}
+
+impl ProjectTemplate {
+    pub const NAMES: [&'static str; 3] = ["minimal", "stacker", "designer-playground"];
+
+    fn bundled_template(&self) -> &'static Dir<'static> {
+        match self {
+            ProjectTemplate::Minimal => &PAX_CREATE_MINIMAL_TEMPLATE,
+            ProjectTemplate::Stacker => &PAX_CREATE_STACKER_TEMPLATE,
+            ProjectTemplate::DesignerPlayground => &PAX_CREATE_TEMPLATE,
+        }
+    }
+}
+
+impl From<&str> for ProjectTemplate {
+    fn from(input: &str) -> Self {
+        match input.to_lowercase().as_str() {
+            "minimal" => ProjectTemplate::Minimal,
+            "stacker" => ProjectTemplate::Stacker,
+            "designer-playground" => ProjectTemplate::DesignerPlayground,
+            _ => {
+                unreachable!()
+            }
+        }
+    }
 }
 
 pub fn perform_create(ctx: &CreateContext) {
@@ -384,10 +1188,11 @@ pub fn perform_create(ctx: &CreateContext) {
     let _ = fs::create_dir_all(&full_path);
 
     // clone template into full_path
-    if ctx.is_libdev_mode {
+    if ctx.is_libdev_mode && ctx.template == ProjectTemplate::DesignerPlayground {
         //For is_libdev_mode, we copy our monorepo @/pax-compiler/new-project-template directory
         //to the target directly.  This enables iterating on new-project-template during libdev
-        //without the sticky caches associated with `include_dir`
+        //without the sticky caches associated with `include_dir`.  Only the designer playground
+        //has a libdev counterpart; other templates are always extracted from `include_dir`.
         let pax_compiler_cargo_root = Path::new(env!("CARGO_MANIFEST_DIR"));
         let template_src = pax_compiler_cargo_root
             .join("files")
@@ -408,7 +1213,8 @@ pub fn perform_create(ctx: &CreateContext) {
         }
     } else {
         // File src is include_dir — recursively extract files from include_dir into full_path
-        PAX_CREATE_TEMPLATE
+        ctx.template
+            .bundled_template()
             .extract(&full_path)
             .expect("Failed to extract files");
     }
@@ -463,23 +1269,63 @@ pub fn run_parser_binary(
     process_child_ids: Arc<Mutex<Vec<u64>>>,
     should_run_designer: bool,
 ) -> Output {
-    let mut cmd = Command::new("cargo");
-    cmd.current_dir(project_path)
-        .arg("run")
-        .arg("--bin")
+    let project = CargoProject::locate(project_path).expect("failed to locate cargo project");
+    run_parser_binary_for_project(
+        &project,
+        helpers::cargo_command(None, None),
+        process_child_ids,
+        should_run_designer,
+        &[],
+        None,
+        &CancellationToken::new(),
+        "always",
+    )
+    .expect("failed to run parser binary")
+}
+
+/// Fails if the parser binary run by [`run_parser_binary_for_project`] did, with
+/// [`BuildError::ParserTargetMissing`] if the project has no parser binary to run
+fn check_parser_output(output: &Output) -> eyre::Result<(), Report> {
+    if output.status.success() {
+        return Ok(());
+    }
+    if let Some(error) = BuildError::from_parser_stderr(&String::from_utf8_lossy(&output.stderr)) {
+        return Err(error.into());
+    }
+    Err(eyre!(
+        "Parsing failed — there is likely a syntax error in the provided pax"
+    ))
+}
+
+/// `cmd` is the `cargo` command to run the parser with, see [`helpers::cargo_command`],
+/// `extra_features` the features of the app crate to enable besides `parser`, and `color` cargo's `--color`
+fn run_parser_binary_for_project(
+    project: &CargoProject,
+    mut cmd: Command,
+    process_child_ids: Arc<Mutex<Vec<u64>>>,
+    should_run_designer: bool,
+    extra_features: &[String],
+    timeout: Option<Duration>,
+    cancellation: &CancellationToken,
+    color: &str,
+) -> eyre::Result<Output> {
+    cmd.current_dir(project.cargo_cwd()).arg("run");
+    project.add_package_args(&mut cmd);
+    cmd.arg("--bin")
         .arg("parser")
         .arg("--features")
         .arg("parser")
         .arg("--profile")
         .arg("parser")
         .arg("--color")
-        .arg("always")
+        .arg(color)
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped());
 
     if should_run_designer {
         cmd.arg("--features").arg("designer");
     }
+    helpers::add_feature_args(&mut cmd, extra_features);
 
     #[cfg(unix)]
     unsafe {
@@ -489,8 +1335,13 @@ pub fn run_parser_binary(
     let child = cmd.spawn().expect(ERR_SPAWN);
 
     // child.stdin.take().map(drop);
-    let output = wait_with_output(&process_child_ids, child);
-    output
+    wait_with_timeout(
+        &process_child_ids,
+        child,
+        timeout,
+        cancellation,
+        "run_parser_binary",
+    )
 }
 
 impl From<&str> for RunTarget {
@@ -0,0 +1,504 @@
+//! # Lint
+//!
+//! Advisory checks over a parsed `PaxManifest`, for issues that don't fail the build but likely
+//! aren't what the author intended: unused components, nodes sized to nothing, duplicate ids,
+//! settings restating a default and expressions referencing undefined identifiers.
+//!
+//! On top of these, [`pax_lint`] runs a selection of [`PaxLintRule`]s, configured per project in
+//! `pax.workspace.toml` (see [`LintConfig`]).
+
+mod rules;
+
+pub use rules::{
+    LintConfig, MaxTemplateDepth, NoDeprecatedTypes, NoEmptyComponents, RequireAccessibilityLabel,
+};
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::Display;
+
+use pax_manifest::{
+    ComponentDefinition, ComponentTemplate, ExpressionInfo, LiteralBlockDefinition, LocationInfo,
+    PaxManifest, SettingElement, SettingsBlockElement, TemplateNodeId, Token, ValueDefinition,
+};
+use pax_runtime_api::{PaxValue, Rotation, Size};
+
+use crate::cartridge_generation::unused_properties::detect_unused_properties;
+use crate::errors::source_map::PaxSourceLocation;
+
+/// Identifiers provided by the runtime to every expression
+const BUILTIN_IDENTIFIERS: [&str; 7] = [
+    "$mobile",
+    "$desktop",
+    "$viewport",
+    "$frames_elapsed",
+    "$suspended",
+    "$scroll_position",
+    "$defines",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintKind {
+    /// A component that isn't the main component and isn't used in any template
+    UnusedComponent,
+    /// A node with a literal `width` or `height` of zero
+    ZeroSize,
+    /// An `id` used by more than one node of the same template
+    DuplicateId,
+    /// A common property set to the value it has by default, e.g. `x=0px`
+    DefaultValue,
+    /// An expression symbol that isn't a property, `for` binding or builtin in scope
+    UndefinedIdentifier,
+    /// A component property never set by a template, see [`detect_unused_properties`]
+    UnusedProperty,
+}
+
+impl LintKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LintKind::UnusedComponent => "unused_component",
+            LintKind::ZeroSize => "zero_size",
+            LintKind::DuplicateId => "duplicate_id",
+            LintKind::DefaultValue => "default_value",
+            LintKind::UndefinedIdentifier => "undefined_identifier",
+            LintKind::UnusedProperty => "unused_property",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Lint {
+    pub kind: LintKind,
+    pub message: String,
+    pub location: PaxSourceLocation,
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "warning[{}]: {}\n  --> {} ({})",
+            self.kind.name(),
+            self.message,
+            self.location,
+            self.location.description
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    Warning,
+    Error,
+}
+
+impl Display for LintSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LintSeverity::Warning => write!(f, "warning"),
+            LintSeverity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// An issue reported by a [`PaxLintRule`], or by one of the built-in lints
+#[derive(Debug, Clone, Serialize)]
+pub struct LintDiagnostic {
+    /// Name of the rule that reported this diagnostic
+    pub rule: String,
+    pub severity: LintSeverity,
+    pub message: String,
+    pub location: PaxSourceLocation,
+}
+
+impl Display for LintDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}[{}]: {}\n  --> {} ({})",
+            self.severity, self.rule, self.message, self.location, self.location.description
+        )
+    }
+}
+
+impl From<Lint> for LintDiagnostic {
+    fn from(lint: Lint) -> Self {
+        LintDiagnostic {
+            rule: lint.kind.name().to_string(),
+            severity: LintSeverity::Warning,
+            message: lint.message,
+            location: lint.location,
+        }
+    }
+}
+
+/// A check over a whole manifest, run by [`pax_lint`]
+pub trait PaxLintRule {
+    /// Name used to select this rule in `pax.workspace.toml`, e.g. `max_template_depth`
+    fn name(&self) -> &str;
+
+    fn check(&self, manifest: &PaxManifest) -> Vec<LintDiagnostic>;
+}
+
+/// Runs `rules` over `manifest`, in order
+pub fn pax_lint(manifest: &PaxManifest, rules: &[Box<dyn PaxLintRule>]) -> Vec<LintDiagnostic> {
+    rules.iter().flat_map(|rule| rule.check(manifest)).collect()
+}
+
+/// Runs every lint over the components of `manifest`, which is expected to be the manifest
+/// produced by the parser (before settings blocks are merged into template nodes)
+pub fn lint(manifest: &PaxManifest) -> Vec<Lint> {
+    let mut lints = lint_unused_components(manifest);
+    for component in manifest.components.values() {
+        if component.is_primitive || component.is_struct_only_component {
+            continue;
+        }
+        ComponentLinter::new(manifest, component, &mut lints).run();
+    }
+    for unused in detect_unused_properties(manifest) {
+        let Some(component) = manifest.components.get(&unused.component_type_id) else {
+            continue;
+        };
+        let name = component_name(component);
+        lints.push(Lint {
+            kind: LintKind::UnusedProperty,
+            message: format!(
+                "property `{}` of `{}` is never set in a template (if it is set from Rust, \
+                 mark it `#[pax_allow(unused_property)]`)",
+                unused.property_name, name
+            ),
+            location: component_location(component, None, format!("component `{}`", name)),
+        });
+    }
+    lints
+}
+
+fn lint_unused_components(manifest: &PaxManifest) -> Vec<Lint> {
+    let used: HashSet<_> = manifest
+        .components
+        .values()
+        .filter_map(|c| c.template.as_ref())
+        .flat_map(|t| t.get_nodes())
+        .map(|tnd| &tnd.type_id)
+        .collect();
+    manifest
+        .components
+        .values()
+        .filter(|c| {
+            !c.is_main_component
+                && !c.is_primitive
+                && !c.is_struct_only_component
+                && !c.type_id.is_blank_component()
+                && c.type_id != manifest.main_component_type_id
+                && !used.contains(&c.type_id)
+        })
+        .map(|c| Lint {
+            kind: LintKind::UnusedComponent,
+            message: format!("component `{}` is never used", component_name(c)),
+            location: component_location(c, None, format!("component `{}`", component_name(c))),
+        })
+        .collect()
+}
+
+struct ComponentLinter<'a> {
+    component: &'a ComponentDefinition,
+    properties: HashSet<String>,
+    lints: &'a mut Vec<Lint>,
+}
+
+impl<'a> ComponentLinter<'a> {
+    fn new(
+        manifest: &PaxManifest,
+        component: &'a ComponentDefinition,
+        lints: &'a mut Vec<Lint>,
+    ) -> Self {
+        Self {
+            component,
+            properties: manifest.get_all_property_names(&component.type_id),
+            lints,
+        }
+    }
+
+    fn run(&mut self) {
+        let component = self.component;
+        if let Some(template) = &component.template {
+            for root in template.get_root() {
+                self.visit_node(template, root, &mut vec![]);
+            }
+            self.lint_duplicate_ids(template);
+        }
+
+        // selector blocks can target nodes inside any `for`, so all of its bindings are in scope
+        let repeat_symbols: Vec<String> = component
+            .template
+            .iter()
+            .flat_map(|t| t.get_nodes())
+            .filter_map(|tnd| tnd.control_flow_settings.as_ref())
+            .filter_map(|cfsd| cfsd.repeat_predicate_definition.as_ref())
+            .flat_map(|predicate| predicate.get_symbols())
+            .collect();
+        for element in component.settings.iter().flatten() {
+            if let SettingsBlockElement::SelectorBlock(selector, block) = element {
+                let description = format!("`{}` in `{}`", selector.token_value, self.name());
+                self.lint_settings(&block.elements, &repeat_symbols, &description);
+            }
+        }
+    }
+
+    fn visit_node(
+        &mut self,
+        template: &ComponentTemplate,
+        id: TemplateNodeId,
+        scope: &mut Vec<String>,
+    ) {
+        let Some(tnd) = template.get_node(&id) else {
+            return;
+        };
+        let scope_len = scope.len();
+        let description = format!(
+            "`<{}>` in `{}`",
+            tnd.type_id
+                .get_pascal_identifier()
+                .unwrap_or_else(|| tnd.type_id.to_string()),
+            self.name()
+        );
+
+        if let Some(cfsd) = &tnd.control_flow_settings {
+            let expressions = [
+                &cfsd.condition_expression,
+                &cfsd.slot_index_expression,
+                &cfsd.repeat_source_expression,
+            ];
+            for info in expressions.into_iter().flatten() {
+                self.lint_expression(info, None, scope, &description);
+            }
+            if let Some(predicate) = &cfsd.repeat_predicate_definition {
+                scope.extend(predicate.get_symbols());
+            }
+        }
+        if let Some(settings) = &tnd.settings {
+            self.lint_settings(settings, scope, &description);
+            self.lint_zero_size(settings, &description);
+        }
+
+        for child in template.get_children(&id).unwrap_or_default() {
+            self.visit_node(template, child, scope);
+        }
+        scope.truncate(scope_len);
+    }
+
+    fn lint_settings(&mut self, elements: &[SettingElement], scope: &[String], description: &str) {
+        for element in elements {
+            let SettingElement::Setting(key, value) = element else {
+                continue;
+            };
+            match value {
+                ValueDefinition::Expression(info) => {
+                    self.lint_expression(info, Some(key), scope, description)
+                }
+                ValueDefinition::LiteralValue(value)
+                    if is_default_value(&key.token_value, value) =>
+                {
+                    self.push(
+                        LintKind::DefaultValue,
+                        format!(
+                            "`{}` is set to its default value `{}`",
+                            key.token_value, value
+                        ),
+                        key.token_location.as_ref(),
+                        description,
+                    )
+                }
+                ValueDefinition::Block(block) => self.lint_block(block, key, scope, description),
+                _ => {}
+            }
+        }
+    }
+
+    fn lint_block(
+        &mut self,
+        block: &LiteralBlockDefinition,
+        key: &Token,
+        scope: &[String],
+        description: &str,
+    ) {
+        for (_, value) in block.get_all_settings() {
+            match value {
+                ValueDefinition::Expression(info) => {
+                    self.lint_expression(info, Some(key), scope, description)
+                }
+                ValueDefinition::Block(inner) => self.lint_block(inner, key, scope, description),
+                _ => {}
+            }
+        }
+    }
+
+    fn lint_expression(
+        &mut self,
+        info: &ExpressionInfo,
+        key: Option<&Token>,
+        scope: &[String],
+        description: &str,
+    ) {
+        let undefined: BTreeSet<&String> = info
+            .dependencies
+            .iter()
+            .filter(|dep| {
+                !self.properties.contains(*dep)
+                    && !scope.contains(*dep)
+                    && !BUILTIN_IDENTIFIERS.contains(&dep.as_str())
+            })
+            .collect();
+        for dep in undefined {
+            let message = match key {
+                Some(key) => format!(
+                    "`{}` in the expression for `{}` is not defined",
+                    dep, key.token_value
+                ),
+                None => format!("`{}` in the expression `{}` is not defined", dep, info),
+            };
+            self.push(
+                LintKind::UndefinedIdentifier,
+                message,
+                key.and_then(|k| k.token_location.as_ref()),
+                description,
+            );
+        }
+    }
+
+    fn lint_zero_size(&mut self, settings: &[SettingElement], description: &str) {
+        for element in settings {
+            let SettingElement::Setting(key, ValueDefinition::LiteralValue(value)) = element else {
+                continue;
+            };
+            if (key.token_value == "width" || key.token_value == "height")
+                && size_parts(value) == Some((0.0, 0.0))
+            {
+                self.push(
+                    LintKind::ZeroSize,
+                    format!(
+                        "`{}` is `{}`, so this node has no visible effect",
+                        key.token_value, value
+                    ),
+                    key.token_location.as_ref(),
+                    description,
+                );
+            }
+        }
+    }
+
+    fn lint_duplicate_ids(&mut self, template: &ComponentTemplate) {
+        let mut ids: HashMap<String, Vec<&Token>> = HashMap::new();
+        for tnd in template.get_nodes() {
+            for element in tnd.settings.iter().flatten() {
+                let SettingElement::Setting(key, value) = element else {
+                    continue;
+                };
+                if key.token_value != "id" {
+                    continue;
+                }
+                let id = match value {
+                    ValueDefinition::Identifier(identifier) => identifier.name.clone(),
+                    ValueDefinition::LiteralValue(PaxValue::String(id)) => id.clone(),
+                    _ => continue,
+                };
+                ids.entry(id).or_default().push(key);
+            }
+        }
+        let mut duplicates: Vec<_> = ids.into_iter().filter(|(_, keys)| keys.len() > 1).collect();
+        duplicates.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (id, keys) in duplicates {
+            let first = keys
+                .iter()
+                .filter_map(|k| k.token_location.as_ref())
+                .min_by_key(|l| l.start_line_col);
+            let description = format!("template of `{}`", self.name());
+            self.push(
+                LintKind::DuplicateId,
+                format!("id `{}` is used by {} nodes", id, keys.len()),
+                first,
+                &description,
+            );
+        }
+    }
+
+    fn push(
+        &mut self,
+        kind: LintKind,
+        message: String,
+        location: Option<&LocationInfo>,
+        description: &str,
+    ) {
+        self.lints.push(Lint {
+            kind,
+            message,
+            location: component_location(self.component, location, description.to_string()),
+        });
+    }
+
+    fn name(&self) -> String {
+        component_name(self.component)
+    }
+}
+
+pub(crate) fn component_name(component: &ComponentDefinition) -> String {
+    component
+        .type_id
+        .get_pascal_identifier()
+        .unwrap_or_else(|| component.type_id.to_string())
+}
+
+pub(crate) fn component_location(
+    component: &ComponentDefinition,
+    location: Option<&LocationInfo>,
+    description: String,
+) -> PaxSourceLocation {
+    PaxSourceLocation {
+        file: component
+            .template
+            .as_ref()
+            .and_then(|t| t.get_file_path())
+            .unwrap_or_else(|| format!("<inlined template of `{}`>", component_name(component))),
+        line_col: location.map(|l| l.start_line_col),
+        description,
+    }
+}
+
+/// (pixels, percent) components of a literal size
+fn size_parts(value: &PaxValue) -> Option<(f64, f64)> {
+    match value {
+        PaxValue::Numeric(n) => Some((n.to_float(), 0.0)),
+        PaxValue::Percent(p) => Some((0.0, p.0.to_float())),
+        PaxValue::Size(Size::Pixels(n)) => Some((n.to_float(), 0.0)),
+        PaxValue::Size(Size::Percent(n)) => Some((0.0, n.to_float())),
+        PaxValue::Size(Size::Combined(px, pct)) => Some((px.to_float(), pct.to_float())),
+        _ => None,
+    }
+}
+
+fn is_zero_rotation(value: &PaxValue) -> bool {
+    match value {
+        PaxValue::Numeric(n) => n.to_float() == 0.0,
+        PaxValue::Percent(p) => p.0.to_float() == 0.0,
+        PaxValue::Rotation(Rotation::Radians(n))
+        | PaxValue::Rotation(Rotation::Degrees(n))
+        | PaxValue::Rotation(Rotation::Percent(n)) => n.to_float() == 0.0,
+        _ => false,
+    }
+}
+
+/// Whether `value` is the default of common property `key`.  Only common properties are
+/// checked, since the defaults of component properties aren't known to the manifest
+fn is_default_value(key: &str, value: &PaxValue) -> bool {
+    match key {
+        "x" | "y" => size_parts(value) == Some((0.0, 0.0)),
+        "scale_x" | "scale_y" => {
+            matches!(
+                value,
+                PaxValue::Percent(_) | PaxValue::Size(Size::Percent(_))
+            ) && size_parts(value) == Some((0.0, 100.0))
+        }
+        "rotate" | "skew_x" | "skew_y" => is_zero_rotation(value),
+        _ => false,
+    }
+}
@@ -0,0 +1,359 @@
+//! Built-in [`PaxLintRule`]s, and their selection from `pax.workspace.toml`:
+//!
+//! ```toml
+//! [lint]
+//! rules = ["no_deprecated_types", "max_template_depth", "require_accessibility_label"]
+//! max_template_depth = 12
+//! ```
+
+use color_eyre::eyre;
+use eyre::eyre;
+use pax_manifest::{
+    ComponentDefinition, ComponentTemplate, LocationInfo, PaxManifest, SettingElement,
+    TemplateNodeDefinition, TemplateNodeId, ValueDefinition,
+};
+use toml_edit::{Document, Item};
+
+use super::{component_location, component_name, LintDiagnostic, LintSeverity, PaxLintRule};
+
+/// Depth used by [`MaxTemplateDepth`] if `pax.workspace.toml` doesn't set one
+pub const DEFAULT_MAX_TEMPLATE_DEPTH: usize = 16;
+
+/// Setting that describes an interactive node to assistive technologies
+pub const ACCESSIBILITY_LABEL_ATTRIBUTE: &str = "accessibility_label";
+
+/// Modules of pax-std that have been removed, with where their types live now
+const DEPRECATED_MODULES: [(&str, &str); 3] = [
+    (
+        "pax_std::primitives::",
+        "`pax_std::core`, `pax_std::drawing` or `pax_std::forms`",
+    ),
+    ("pax_std::components::", "`pax_std::layout`"),
+    ("pax_std::types::", "`pax_std::core` or `pax_std::drawing`"),
+];
+
+/// Modules of the pax-std components users interact with
+const INTERACTIVE_MODULES: [&str; 7] = [
+    "pax_std::forms::button::",
+    "pax_std::forms::checkbox::",
+    "pax_std::forms::dropdown::",
+    "pax_std::forms::radio_set::",
+    "pax_std::forms::slider::",
+    "pax_std::forms::textbox::",
+    "pax_std::core::link::",
+];
+
+/// Handlers that don't make a node interactive
+const LIFECYCLE_EVENTS: [&str; 4] = ["mount", "unmount", "tick", "pre_render"];
+
+/// Rule selection from the `[lint]` table of `pax.workspace.toml`
+#[derive(Debug, Clone, Default)]
+pub struct LintConfig {
+    /// Names of the rules to run, the default rules if `None`
+    pub rules: Option<Vec<String>>,
+    pub max_template_depth: Option<usize>,
+}
+
+impl LintConfig {
+    pub fn from_document(document: &Document) -> eyre::Result<Self> {
+        let Some(lint) = document.as_table().get("lint") else {
+            return Ok(Self::default());
+        };
+        let rules = match lint.get("rules") {
+            None => None,
+            Some(rules) => Some(
+                rules
+                    .as_array()
+                    .ok_or_else(|| eyre!("`lint.rules` must be an array of rule names"))?
+                    .iter()
+                    .map(|rule| {
+                        rule.as_str()
+                            .map(str::to_string)
+                            .ok_or_else(|| eyre!("`lint.rules` must be an array of rule names"))
+                    })
+                    .collect::<eyre::Result<Vec<_>>>()?,
+            ),
+        };
+        let max_template_depth = match lint.get("max_template_depth").map(Item::as_integer) {
+            None => None,
+            Some(Some(depth)) if depth > 0 => Some(depth as usize),
+            Some(_) => {
+                return Err(eyre!(
+                    "`lint.max_template_depth` must be a positive integer"
+                ))
+            }
+        };
+        Ok(Self {
+            rules,
+            max_template_depth,
+        })
+    }
+
+    /// The selected rules.  Without a `rules` list, every built-in rule that only warns is run:
+    /// [`RequireAccessibilityLabel`] has to be opted into.
+    pub fn rules(&self) -> eyre::Result<Vec<Box<dyn PaxLintRule>>> {
+        let max_template_depth = MaxTemplateDepth(
+            self.max_template_depth
+                .unwrap_or(DEFAULT_MAX_TEMPLATE_DEPTH),
+        );
+        let Some(names) = &self.rules else {
+            return Ok(vec![
+                Box::new(NoDeprecatedTypes),
+                Box::new(max_template_depth),
+                Box::new(NoEmptyComponents),
+            ]);
+        };
+        names
+            .iter()
+            .map(|name| -> eyre::Result<Box<dyn PaxLintRule>> {
+                match name.as_str() {
+                    "no_deprecated_types" => Ok(Box::new(NoDeprecatedTypes)),
+                    "max_template_depth" => Ok(Box::new(max_template_depth.clone())),
+                    "no_empty_components" => Ok(Box::new(NoEmptyComponents)),
+                    "require_accessibility_label" => Ok(Box::new(RequireAccessibilityLabel)),
+                    _ => Err(eyre!("Unknown lint rule `{}` in `lint.rules`", name)),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Warns on template nodes whose type lives in a module removed from pax-std
+pub struct NoDeprecatedTypes;
+
+impl PaxLintRule for NoDeprecatedTypes {
+    fn name(&self) -> &str {
+        "no_deprecated_types"
+    }
+
+    fn check(&self, manifest: &PaxManifest) -> Vec<LintDiagnostic> {
+        let mut diagnostics = vec![];
+        for (component, template) in templates(manifest) {
+            for tnd in template.get_nodes() {
+                let Some(import_path) = tnd.type_id.import_path() else {
+                    continue;
+                };
+                let Some((module, replacement)) = DEPRECATED_MODULES
+                    .iter()
+                    .find(|(module, _)| import_path.starts_with(module))
+                else {
+                    continue;
+                };
+                diagnostics.push(diagnostic(
+                    self,
+                    LintSeverity::Warning,
+                    format!(
+                        "`{}` is imported from `{}`, which has been removed from pax-std; \
+                         import it from {}",
+                        import_path,
+                        module.trim_end_matches("::"),
+                        replacement
+                    ),
+                    component,
+                    Some(tnd),
+                ));
+            }
+        }
+        diagnostics
+    }
+}
+
+/// Warns on template nodes nested deeper than the given depth, root nodes being at depth 1.
+/// Only the outermost node past the limit is reported.
+#[derive(Clone)]
+pub struct MaxTemplateDepth(pub usize);
+
+impl MaxTemplateDepth {
+    fn visit(
+        &self,
+        component: &ComponentDefinition,
+        template: &ComponentTemplate,
+        id: TemplateNodeId,
+        depth: usize,
+        diagnostics: &mut Vec<LintDiagnostic>,
+    ) {
+        let Some(tnd) = template.get_node(&id) else {
+            return;
+        };
+        if depth > self.0 {
+            diagnostics.push(diagnostic(
+                self,
+                LintSeverity::Warning,
+                format!(
+                    "`{}` is nested {} levels deep, more than the maximum of {}; consider \
+                     extracting part of this template into a component",
+                    node_name(tnd),
+                    depth,
+                    self.0
+                ),
+                component,
+                Some(tnd),
+            ));
+            return;
+        }
+        for child in template.get_children(&id).unwrap_or_default() {
+            self.visit(component, template, child, depth + 1, diagnostics);
+        }
+    }
+}
+
+impl PaxLintRule for MaxTemplateDepth {
+    fn name(&self) -> &str {
+        "max_template_depth"
+    }
+
+    fn check(&self, manifest: &PaxManifest) -> Vec<LintDiagnostic> {
+        let mut diagnostics = vec![];
+        for (component, template) in templates(manifest) {
+            for root in template.get_root() {
+                self.visit(component, template, root, 1, &mut diagnostics);
+            }
+        }
+        diagnostics
+    }
+}
+
+/// Warns on components whose template has nothing but comments
+pub struct NoEmptyComponents;
+
+impl PaxLintRule for NoEmptyComponents {
+    fn name(&self) -> &str {
+        "no_empty_components"
+    }
+
+    fn check(&self, manifest: &PaxManifest) -> Vec<LintDiagnostic> {
+        manifest
+            .components
+            .values()
+            .filter(|c| is_user_component(c))
+            .filter(|c| {
+                c.template.as_ref().map_or(true, |template| {
+                    template
+                        .get_nodes()
+                        .iter()
+                        .all(|tnd| tnd.raw_comment_string.is_some())
+                })
+            })
+            .map(|c| {
+                diagnostic(
+                    self,
+                    LintSeverity::Warning,
+                    format!("component `{}` has an empty template", component_name(c)),
+                    c,
+                    None,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Errors on interactive nodes (pax-std form controls and links, and nodes with event handlers)
+/// that have no `accessibility_label`, set inline or through a selector block
+pub struct RequireAccessibilityLabel;
+
+impl PaxLintRule for RequireAccessibilityLabel {
+    fn name(&self) -> &str {
+        "require_accessibility_label"
+    }
+
+    fn check(&self, manifest: &PaxManifest) -> Vec<LintDiagnostic> {
+        let mut diagnostics = vec![];
+        for (component, template) in templates(manifest) {
+            for tnd in template.get_nodes() {
+                if tnd.control_flow_settings.is_some() || tnd.raw_comment_string.is_some() {
+                    continue;
+                }
+                let settings = PaxManifest::merge_inline_settings_with_settings_block(
+                    &tnd.settings,
+                    &component.settings,
+                )
+                .unwrap_or_default();
+                let has_label = settings.iter().any(|setting| {
+                    matches!(setting, SettingElement::Setting(key, _)
+                        if key.token_value == ACCESSIBILITY_LABEL_ATTRIBUTE)
+                });
+                if has_label || !is_interactive(tnd, &settings) {
+                    continue;
+                }
+                diagnostics.push(diagnostic(
+                    self,
+                    LintSeverity::Error,
+                    format!(
+                        "interactive node `{}` has no `{}`",
+                        node_name(tnd),
+                        ACCESSIBILITY_LABEL_ATTRIBUTE
+                    ),
+                    component,
+                    Some(tnd),
+                ));
+            }
+        }
+        diagnostics
+    }
+}
+
+fn is_interactive(tnd: &TemplateNodeDefinition, settings: &[SettingElement]) -> bool {
+    let is_interactive_type = tnd.type_id.import_path().is_some_and(|import_path| {
+        INTERACTIVE_MODULES
+            .iter()
+            .any(|module| import_path.starts_with(module))
+    });
+    let has_event_handler = settings.iter().any(|setting| {
+        matches!(setting, SettingElement::Setting(key, ValueDefinition::EventBindingTarget(_))
+            if !LIFECYCLE_EVENTS.contains(&key.token_value.as_str()))
+    });
+    is_interactive_type || has_event_handler
+}
+
+fn is_user_component(component: &ComponentDefinition) -> bool {
+    !component.is_primitive
+        && !component.is_struct_only_component
+        && !component.type_id.is_blank_component()
+}
+
+/// Templates of the components defined by users of pax, as opposed to primitives
+fn templates(
+    manifest: &PaxManifest,
+) -> impl Iterator<Item = (&ComponentDefinition, &ComponentTemplate)> {
+    manifest
+        .components
+        .values()
+        .filter(|c| is_user_component(c))
+        .filter_map(|c| c.template.as_ref().map(|template| (c, template)))
+}
+
+fn node_name(tnd: &TemplateNodeDefinition) -> String {
+    tnd.type_id
+        .get_pascal_identifier()
+        .unwrap_or_else(|| tnd.type_id.to_string())
+}
+
+/// Location of the first setting of `tnd`, the closest thing to the location of the node itself
+fn node_location(tnd: &TemplateNodeDefinition) -> Option<&LocationInfo> {
+    tnd.settings
+        .iter()
+        .flatten()
+        .find_map(|setting| match setting {
+            SettingElement::Setting(key, _) => key.token_location.as_ref(),
+            SettingElement::Comment(_) => None,
+        })
+}
+
+fn diagnostic(
+    rule: &dyn PaxLintRule,
+    severity: LintSeverity,
+    message: String,
+    component: &ComponentDefinition,
+    tnd: Option<&TemplateNodeDefinition>,
+) -> LintDiagnostic {
+    let description = match tnd {
+        Some(tnd) => format!("`<{}>` in `{}`", node_name(tnd), component_name(component)),
+        None => format!("component `{}`", component_name(component)),
+    };
+    LintDiagnostic {
+        rule: rule.name().to_string(),
+        severity,
+        message,
+        location: component_location(component, tnd.and_then(node_location), description),
+    }
+}
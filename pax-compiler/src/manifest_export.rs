@@ -0,0 +1,157 @@
+//! # Manifest Export
+//!
+//! `--emit-manifest <path>` and `pax-cli manifest` write the manifest as the compiler sees it just before
+//! generating the cartridge, after the userland and designer manifests are merged, `$defines` resolved,
+//! unreachable components shaken out and settings validated, for tooling like docs generators, linters
+//! and CI snapshots of what a build ships.
+//!
+//! The manifest is wrapped in a [`ManifestExport`] envelope along with the resolved defines, the
+//! dependency graph between components and the lint and validation diagnostics.  Output is canonical:
+//! object keys are sorted and diagnostics ordered by location, so that the same project always exports
+//! byte-for-byte the same JSON.  Changes to the envelope's layout bump [`MANIFEST_SCHEMA_VERSION`].
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{self, eyre};
+use pax_manifest::{PaxManifest, TypeId};
+use pax_runtime_api::PaxValue;
+
+use crate::lint::LintDiagnostic;
+
+/// Version of the layout of [`ManifestExport`]
+pub const MANIFEST_SCHEMA_VERSION: u32 = 1;
+
+/// Which components an exported manifest holds
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ManifestVariant {
+    /// Every component baked into the cartridge, including the designer's in designer builds
+    Merged,
+    /// The components of the project and its dependencies, without the designer's
+    Userland,
+}
+
+/// Envelope of an exported manifest
+#[derive(Serialize)]
+pub struct ManifestExport<'a> {
+    pub schema_version: u32,
+    pub variant: ManifestVariant,
+    pub manifest: &'a PaxManifest,
+    /// The defines referenced by templates, with the values they resolved to
+    pub defines: &'a BTreeMap<String, PaxValue>,
+    /// For each component, the components instantiated in its template
+    pub dependency_graph: BTreeMap<String, BTreeSet<String>>,
+    /// Lint and validation results for the project's templates
+    pub diagnostics: Vec<&'a LintDiagnostic>,
+}
+
+impl<'a> ManifestExport<'a> {
+    pub fn new(
+        variant: ManifestVariant,
+        manifest: &'a PaxManifest,
+        defines: &'a BTreeMap<String, PaxValue>,
+        diagnostics: &'a [LintDiagnostic],
+    ) -> Self {
+        let mut diagnostics: Vec<_> = diagnostics.iter().collect();
+        diagnostics.sort_by(|a, b| {
+            (&a.location.file, a.location.line_col, &a.rule, &a.message).cmp(&(
+                &b.location.file,
+                b.location.line_col,
+                &b.rule,
+                &b.message,
+            ))
+        });
+        Self {
+            schema_version: MANIFEST_SCHEMA_VERSION,
+            variant,
+            manifest,
+            defines,
+            dependency_graph: dependency_graph(manifest),
+            diagnostics,
+        }
+    }
+
+    /// Pretty-printed JSON with sorted object keys
+    pub fn to_canonical_json(&self) -> String {
+        // going through `Value` sorts the keys of the manifest's hash maps
+        let value = serde_json::to_value(self).unwrap();
+        let mut json = serde_json::to_string_pretty(&value).unwrap();
+        json.push('\n');
+        json
+    }
+}
+
+/// For each component with a template, the components its nodes instantiate, by type id
+pub fn dependency_graph(manifest: &PaxManifest) -> BTreeMap<String, BTreeSet<String>> {
+    manifest
+        .components
+        .values()
+        .filter_map(|component| {
+            let template = component.template.as_ref()?;
+            let used = template
+                .get_nodes()
+                .into_iter()
+                .filter(|tnd| manifest.components.contains_key(&tnd.type_id))
+                .map(|tnd| tnd.type_id.to_string())
+                .collect();
+            Some((component.type_id.to_string(), used))
+        })
+        .collect()
+}
+
+/// `merged` with only the components and types of the userland manifest, whose type ids are `userland`,
+/// leaving out those only the designer uses
+pub fn userland_variant(merged: &PaxManifest, userland: &HashSet<TypeId>) -> PaxManifest {
+    let mut variant = merged.clone();
+    variant
+        .components
+        .retain(|type_id, _| userland.contains(type_id));
+    variant
+        .type_table
+        .retain(|type_id, _| userland.contains(type_id));
+    variant
+}
+
+/// Where the userland variant is written next to the merged manifest at `path`,
+/// e.g. `manifest.userland.json` for `manifest.json`
+pub fn userland_variant_path(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let file_name = match path.extension() {
+        Some(extension) => format!("{}.userland.{}", stem, extension.to_string_lossy()),
+        None => format!("{}.userland", stem),
+    };
+    path.with_file_name(file_name)
+}
+
+/// Writes the merged manifest to `path` and its userland variant next to it, see
+/// [`userland_variant_path`], creating the parent directory if needed
+pub(crate) fn write_manifest_exports(
+    path: &Path,
+    merged: &PaxManifest,
+    userland: &HashSet<TypeId>,
+    defines: &BTreeMap<String, PaxValue>,
+    diagnostics: &[LintDiagnostic],
+) -> eyre::Result<PathBuf> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| eyre!("Failed to create {:?}: {}", parent, e))?;
+    }
+    let userland = userland_variant(merged, userland);
+    let userland_path = userland_variant_path(path);
+    for (variant, manifest, path) in [
+        (ManifestVariant::Merged, merged, path),
+        (
+            ManifestVariant::Userland,
+            &userland,
+            userland_path.as_path(),
+        ),
+    ] {
+        let json = ManifestExport::new(variant, manifest, defines, diagnostics).to_canonical_json();
+        fs::write(path, json).map_err(|e| eyre!("Failed to write {:?}: {}", path, e))?;
+    }
+    Ok(userland_path)
+}
@@ -0,0 +1,472 @@
+//! # Progress
+//!
+//! Builds report the start and end of each of their [`Phase`]s, and the output of the subprocesses
+//! they run, through the [`ProgressReporter`] of their `RunContext`, which presents them as its
+//! [`ProgressMode`] says: with spinners in a terminal, as timestamped lines in CI logs, or not at
+//! all but for errors and the final summary.  The duration of each phase is recorded once, and the
+//! same timings are printed in the final summary and written to the build report, see
+//! [`ProgressReporter::attach_report`].
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{IsTerminal, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use color_eyre::eyre::{self, eyre};
+
+use crate::helpers::{ERR_LOCK, PAX_BADGE};
+
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+/// Clears the current line of the terminal, to redraw the spinner on it
+const CLEAR_LINE: &str = "\r\x1b[2K";
+
+/// How a [`ProgressReporter`] presents a build
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProgressMode {
+    /// Badged lines, with a spinner and the elapsed time of the running phase updated in place
+    /// when the terminal supports it.  The output of subprocesses is collapsed unless they fail
+    Interactive,
+    /// One timestamped line per phase start and end, without ANSI escapes, and the output of
+    /// subprocesses in full, for logs
+    Plain,
+    /// Errors and the final summary only
+    Quiet,
+}
+
+impl ProgressMode {
+    /// `Interactive` when stdout is a terminal, `Plain` otherwise, e.g. in CI
+    pub fn detect() -> Self {
+        if std::io::stdout().is_terminal() {
+            ProgressMode::Interactive
+        } else {
+            ProgressMode::Plain
+        }
+    }
+}
+
+impl FromStr for ProgressMode {
+    type Err = eyre::Report;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "interactive" => Ok(ProgressMode::Interactive),
+            "plain" => Ok(ProgressMode::Plain),
+            "quiet" => Ok(ProgressMode::Quiet),
+            _ => Err(eyre!(
+                "Unknown progress mode `{}`, expected one of interactive, plain or quiet",
+                input
+            )),
+        }
+    }
+}
+
+/// A step of a build, or of launching its app, timed by the [`ProgressReporter`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    /// Preparing the project's manifest and syncing the interface packages into `.pax`
+    PkgSync,
+    /// Building and running the parser binary
+    Parse,
+    /// Running the manifest passes and generating the cartridge
+    Codegen,
+    /// Building the chassis with the cartridge, with `cargo`, `wasm-pack` or `xcodebuild`
+    ChassisBuild,
+    /// Copying assets and the built app into `.pax/build`
+    Assets,
+    /// Serving or launching the built app
+    Launch,
+}
+
+impl Phase {
+    /// Short name, for log lines and the summary
+    pub fn name(&self) -> &'static str {
+        match self {
+            Phase::PkgSync => "pkg sync",
+            Phase::Parse => "parse",
+            Phase::Codegen => "codegen",
+            Phase::ChassisBuild => "chassis build",
+            Phase::Assets => "assets",
+            Phase::Launch => "launch",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Phase::PkgSync => "📦 Syncing interface packages",
+            Phase::Parse => "🛠️  Building and running parser binary with `cargo`",
+            Phase::Codegen => "🦀 Generating Rust",
+            Phase::ChassisBuild => "🧱 Building project with `cargo`",
+            Phase::Assets => "🗂️  Copying assets and build",
+            Phase::Launch => "🐇 Launching app",
+        }
+    }
+}
+
+/// How long a [`Phase`] took, and whether it succeeded
+#[derive(Clone, Debug, PartialEq)]
+pub struct PhaseTiming {
+    pub phase: Phase,
+    pub duration: Duration,
+    pub succeeded: bool,
+}
+
+/// Reports the phases of a build as its [`ProgressMode`] says.  Clones report to the same
+/// output and record into the same timings
+#[derive(Clone)]
+pub struct ProgressReporter {
+    mode: ProgressMode,
+    /// Whether the spinner can be redrawn in place, i.e. the terminal understands ANSI escapes
+    in_place: bool,
+    state: Arc<Mutex<State>>,
+}
+
+struct State {
+    started: Instant,
+    running: Option<RunningPhase>,
+    next_id: u64,
+    timings: Vec<PhaseTiming>,
+    report: Option<BuildReport>,
+    /// Whether a spinner line is on screen, to be cleared before anything else is printed
+    spinner_shown: bool,
+}
+
+struct RunningPhase {
+    phase: Phase,
+    started: Instant,
+    /// Tells the spinner of this phase apart from those of earlier ones, which stop once they see
+    /// another phase running
+    id: u64,
+}
+
+/// `build-report.txt`: the details of the build's passes, followed by the duration of each phase
+/// that has ended, rewritten as phases end
+struct BuildReport {
+    path: PathBuf,
+    details: String,
+}
+
+impl BuildReport {
+    fn write(&self, timings: &[PhaseTiming]) -> std::io::Result<()> {
+        fs::write(
+            &self.path,
+            format!(
+                "{}phase timings:\n{}",
+                self.details,
+                format_timings(timings)
+            ),
+        )
+    }
+}
+
+impl ProgressReporter {
+    pub fn new(mode: ProgressMode) -> Self {
+        let in_place = mode == ProgressMode::Interactive
+            && std::io::stdout().is_terminal()
+            && std::env::var("TERM").map_or(true, |term| term != "dumb");
+        Self {
+            mode,
+            in_place,
+            state: Arc::new(Mutex::new(State {
+                started: Instant::now(),
+                running: None,
+                next_id: 0,
+                timings: vec![],
+                report: None,
+                spinner_shown: false,
+            })),
+        }
+    }
+
+    pub fn mode(&self) -> ProgressMode {
+        self.mode
+    }
+
+    /// Whether subprocess output may carry ANSI colors, i.e. it isn't written to a plain log
+    pub fn uses_ansi(&self) -> bool {
+        self.mode != ProgressMode::Plain && std::io::stdout().is_terminal()
+    }
+
+    /// Reports the start of `phase`, which ends when the returned guard is [`PhaseGuard::done`],
+    /// or fails if it's dropped before, e.g. by an early return on error
+    pub fn start(&self, phase: Phase) -> PhaseGuard {
+        let started = Instant::now();
+        let mut state = self.state.lock().expect(ERR_LOCK);
+        let id = state.next_id;
+        state.next_id += 1;
+        state.running = Some(RunningPhase { phase, started, id });
+        match self.mode {
+            ProgressMode::Interactive if self.in_place => {
+                self.spawn_spinner(id);
+            }
+            ProgressMode::Interactive => {
+                println!("{} {}...", *PAX_BADGE, phase.description());
+            }
+            ProgressMode::Plain => {
+                println!("[{}] start {}", timestamp(), phase.name());
+            }
+            ProgressMode::Quiet => {}
+        }
+        PhaseGuard {
+            reporter: self.clone(),
+            phase,
+            started,
+            id,
+            ended: false,
+        }
+    }
+
+    fn end(&self, phase: Phase, started: Instant, id: u64, succeeded: bool) {
+        let duration = started.elapsed();
+        let mut state = self.state.lock().expect(ERR_LOCK);
+        if state
+            .running
+            .as_ref()
+            .is_some_and(|running| running.id == id)
+        {
+            state.running = None;
+        }
+        state.timings.push(PhaseTiming {
+            phase,
+            duration,
+            succeeded,
+        });
+        if let Some(report) = &state.report {
+            let _ = report.write(&state.timings);
+        }
+        let outcome = if succeeded { "✔" } else { "✖" };
+        match self.mode {
+            ProgressMode::Interactive => {
+                self.clear_spinner(&mut state);
+                println!(
+                    "{} {} {} ({})",
+                    *PAX_BADGE,
+                    outcome,
+                    phase.description(),
+                    format_duration(duration)
+                );
+            }
+            ProgressMode::Plain => {
+                println!(
+                    "[{}] {} {} ({})",
+                    timestamp(),
+                    if succeeded { "done" } else { "failed" },
+                    phase.name(),
+                    format_duration(duration)
+                );
+            }
+            ProgressMode::Quiet => {}
+        }
+    }
+
+    /// Redraws the spinner of the phase `id` until another phase starts or it ends.  The first
+    /// frame is only drawn after an interval, so that quick phases don't flicker
+    fn spawn_spinner(&self, id: u64) {
+        let state = Arc::clone(&self.state);
+        thread::spawn(move || {
+            for frame in SPINNER_FRAMES.iter().cycle() {
+                thread::sleep(SPINNER_INTERVAL);
+                let mut state = state.lock().expect(ERR_LOCK);
+                let Some(running) = state.running.as_ref().filter(|running| running.id == id)
+                else {
+                    break;
+                };
+                print!(
+                    "{}{} {} {}... {}",
+                    CLEAR_LINE,
+                    *PAX_BADGE,
+                    frame,
+                    running.phase.description(),
+                    format_duration(running.started.elapsed())
+                );
+                let _ = std::io::stdout().flush();
+                state.spinner_shown = true;
+            }
+        });
+    }
+
+    fn clear_spinner(&self, state: &mut State) {
+        if state.spinner_shown {
+            print!("{}", CLEAR_LINE);
+            state.spinner_shown = false;
+        }
+    }
+
+    /// Prints a status line, badged or timestamped, unless quiet
+    pub fn message(&self, line: &str) {
+        if self.mode == ProgressMode::Quiet {
+            return;
+        }
+        self.print_line(line);
+    }
+
+    /// Prints a warning, in every mode
+    pub fn warning(&self, line: &str) {
+        self.print_line(line);
+    }
+
+    fn print_line(&self, line: &str) {
+        let mut state = self.state.lock().expect(ERR_LOCK);
+        self.clear_spinner(&mut state);
+        match self.mode {
+            ProgressMode::Plain => println!("[{}] {}", timestamp(), line),
+            _ => println!("{} {}", *PAX_BADGE, line),
+        }
+    }
+
+    /// Prints a multi-line block as is, e.g. a pass report, unless quiet
+    pub fn details(&self, text: &str) {
+        if self.mode == ProgressMode::Quiet {
+            return;
+        }
+        let mut state = self.state.lock().expect(ERR_LOCK);
+        self.clear_spinner(&mut state);
+        print!("{}", text);
+    }
+
+    /// Shows the captured `output` of the subprocess run for `step`: in full in plain mode or if
+    /// `expand`, e.g. because the step failed, and otherwise as a one-line summary in interactive
+    /// mode, and not at all in quiet mode
+    pub fn child_output(&self, step: &str, output: &str, expand: bool) {
+        if output.trim().is_empty() {
+            return;
+        }
+        let mut state = self.state.lock().expect(ERR_LOCK);
+        self.clear_spinner(&mut state);
+        match self.mode {
+            ProgressMode::Plain => {
+                println!("[{}] output of {}:", timestamp(), step);
+                println!("{}", output.trim_end());
+            }
+            _ if expand => {
+                println!("{} output of {}:", *PAX_BADGE, step);
+                println!("{}", output.trim_end());
+            }
+            ProgressMode::Interactive => {
+                println!(
+                    "{}   ▸ {}: {} line(s) of output collapsed, shown on failure or when verbose",
+                    *PAX_BADGE,
+                    step,
+                    output.trim_end().lines().count()
+                );
+            }
+            ProgressMode::Quiet => {}
+        }
+    }
+
+    /// Writes the build report to `path`: `details`, followed by the timings of the phases ended
+    /// so far, rewritten as each further phase ends
+    pub fn attach_report(&self, path: PathBuf, details: String) -> std::io::Result<()> {
+        let mut state = self.state.lock().expect(ERR_LOCK);
+        let report = BuildReport { path, details };
+        report.write(&state.timings)?;
+        state.report = Some(report);
+        Ok(())
+    }
+
+    /// The phases ended so far, in the order they ended
+    pub fn timings(&self) -> Vec<PhaseTiming> {
+        self.state.lock().expect(ERR_LOCK).timings.clone()
+    }
+
+    /// The timings of the phases ended so far as a table, with the time since the reporter was
+    /// created as the total
+    pub fn summary(&self) -> String {
+        let state = self.state.lock().expect(ERR_LOCK);
+        let mut summary = format_timings(&state.timings);
+        let _ = writeln!(
+            summary,
+            "  {:<16}{:>8}",
+            "total",
+            format_duration(state.started.elapsed())
+        );
+        summary
+    }
+
+    /// Prints the per-phase timing table, in every mode
+    pub fn finish(&self) {
+        let summary = self.summary();
+        let mut state = self.state.lock().expect(ERR_LOCK);
+        self.clear_spinner(&mut state);
+        match self.mode {
+            ProgressMode::Plain => println!("[{}] build phases:", timestamp()),
+            _ => println!("{} ⏱️  Build phases:", *PAX_BADGE),
+        }
+        print!("{}", summary);
+    }
+}
+
+/// A running [`Phase`], see [`ProgressReporter::start`]
+#[must_use = "the phase fails when its guard is dropped without calling `done`"]
+pub struct PhaseGuard {
+    reporter: ProgressReporter,
+    phase: Phase,
+    started: Instant,
+    id: u64,
+    ended: bool,
+}
+
+impl PhaseGuard {
+    /// Reports that the phase succeeded
+    pub fn done(mut self) {
+        self.end(true);
+    }
+
+    fn end(&mut self, succeeded: bool) {
+        self.ended = true;
+        self.reporter
+            .end(self.phase, self.started, self.id, succeeded);
+    }
+}
+
+impl Drop for PhaseGuard {
+    fn drop(&mut self) {
+        if !self.ended {
+            self.end(false);
+        }
+    }
+}
+
+/// One line per phase, with its duration, as printed in the summary and written to the build report
+fn format_timings(timings: &[PhaseTiming]) -> String {
+    let mut out = String::new();
+    for timing in timings {
+        let _ = writeln!(
+            out,
+            "  {:<16}{:>8}{}",
+            timing.phase.name(),
+            format_duration(timing.duration),
+            if timing.succeeded { "" } else { "  (failed)" }
+        );
+    }
+    out
+}
+
+/// e.g. `0.4s`, `12.3s` or `2m05s`
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs_f64();
+    if secs < 60.0 {
+        format!("{:.1}s", secs)
+    } else {
+        let secs = duration.as_secs();
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    }
+}
+
+/// The current UTC time of day, e.g. `14:03:22Z`
+fn timestamp() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since_epoch| since_epoch.as_secs());
+    let secs_of_day = secs % 86_400;
+    format!(
+        "{:02}:{:02}:{:02}Z",
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60
+    )
+}
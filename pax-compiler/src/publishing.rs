@@ -0,0 +1,101 @@
+//! # Publishing Module
+//!
+//! Checks that [`crate::perform_publish`] makes before handing a Pax component library to
+//! `cargo publish`, and the patch version bump it makes afterwards.  Templates referenced with
+//! `#[file("...")]` are compiled into downstream crates from the published sources (the `#[pax]`
+//! macro `include_str!`s them), so a template left out of the package breaks every dependent build.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre;
+use eyre::eyre;
+use lazy_static::lazy_static;
+use regex::Regex;
+use toml_edit::{Document, Value};
+use walkdir::WalkDir;
+
+lazy_static! {
+    static ref FILE_ATTRIBUTE: Regex =
+        Regex::new(r#"#\[\s*file\s*\(\s*"([^"]+)"\s*\)\s*\]"#).unwrap();
+}
+
+/// Fails unless the manifest has a `[package.metadata.pax]` table, which marks the crate as a Pax
+/// library and holds its Pax settings, e.g. `keep` and `std-features`
+pub fn check_pax_metadata(manifest: &Document) -> eyre::Result<()> {
+    let pax = manifest
+        .as_table()
+        .get("package")
+        .and_then(|package| package.get("metadata"))
+        .and_then(|metadata| metadata.get("pax"));
+    match pax {
+        Some(pax) if pax.is_table_like() => Ok(()),
+        Some(_) => Err(eyre!("`package.metadata.pax` must be a table")),
+        None => Err(eyre!(
+            "Cargo.toml has no `[package.metadata.pax]` section, add one (it may be empty) to publish a Pax library"
+        )),
+    }
+}
+
+/// Templates referenced by `#[file("...")]` attributes in the `.rs` files under `src`, relative to
+/// `crate_root`, resolved the way the `#[pax]` macro resolves them: relative to the crate root if the
+/// file exists there, else relative to `src`
+pub fn referenced_pax_files(crate_root: &Path) -> eyre::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for entry in WalkDir::new(crate_root.join("src")) {
+        let entry = entry?;
+        if entry.path().extension().and_then(|e| e.to_str()) != Some("rs") {
+            continue;
+        }
+        let source = fs::read_to_string(entry.path())
+            .map_err(|e| eyre!("Failed to read {:?}: {}", entry.path(), e))?;
+        for capture in FILE_ATTRIBUTE.captures_iter(&source) {
+            let file = PathBuf::from(&capture[1]);
+            let path = if crate_root.join(&file).exists() {
+                file
+            } else {
+                Path::new("src").join(file)
+            };
+            if !files.contains(&path) {
+                files.push(path);
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// The referenced templates that aren't among the `packaged` files, as listed by
+/// `cargo package --list`, e.g. because they're left out by `package.include` or `package.exclude`
+pub fn unpackaged_pax_files(referenced: &[PathBuf], packaged: &[PathBuf]) -> Vec<PathBuf> {
+    let packaged: HashSet<_> = packaged.iter().collect();
+    referenced
+        .iter()
+        .filter(|file| !packaged.contains(file))
+        .cloned()
+        .collect()
+}
+
+/// Bumps the patch component of `package.version`, e.g. `0.3.9` to `0.3.10`, returning the new version.
+/// `None` if the version isn't a plain `major.minor.patch` literal, e.g. a pre-release or a version
+/// inherited from the workspace, which is left for the author to bump
+pub fn bump_patch_version(manifest: &mut Document) -> Option<String> {
+    let version = manifest
+        .get_mut("package")?
+        .get_mut("version")?
+        .as_value_mut()?;
+    let parts: Vec<u64> = version
+        .as_str()?
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect::<Option<_>>()?;
+    let [major, minor, patch] = parts[..] else {
+        return None;
+    };
+    let bumped = format!("{}.{}.{}", major, minor, patch + 1);
+    // keeps the whitespace and comments around the version
+    let decor = version.decor().clone();
+    *version = Value::from(bumped.as_str());
+    *version.decor_mut() = decor;
+    Some(bumped)
+}
@@ -0,0 +1,360 @@
+//! # Running Module
+//!
+//! [`crate::perform_build`] produces [`BuildArtifacts`], which [`crate::perform_run`] launches on
+//! their target, returning a [`RunningApp`] through which tooling can reach the app (its URL, process
+//! or simulator) and stop it again.  Processes of running apps are tracked in
+//! `RunContext::process_child_ids`, like those of build steps, so that they're cleaned up on interrupt.
+
+use std::path::PathBuf;
+use std::process::Child;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use actix_web::dev::ServerHandle;
+use color_eyre::eyre;
+use dotenv::dotenv;
+use eyre::eyre;
+use pax_manifest::PaxManifest;
+
+use crate::design_server::{self, static_server, BoundServer};
+use crate::helpers::{wait_with_output, ERR_LOCK, PAX_BADGE};
+use crate::{RunContext, RunTarget, Verbosity};
+
+/// How often `RunningApp` checks whether the app has exited while waiting for it
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long dropping a `RunningApp` waits for the app to stop gracefully before killing it
+const DROP_STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What `perform_build` built, to be launched with `perform_run`
+pub struct BuildArtifacts {
+    pub target: RunTarget,
+    /// The userland manifest of the app
+    pub manifest: PaxManifest,
+    /// Root of the app crate
+    pub project_root: PathBuf,
+    /// Built for the designer, see `RunContext::should_run_designer`
+    pub is_designer: bool,
+    /// The built app under `.pax/build`: the directory of static files for web builds, the `.app`
+    /// bundle for macOS and iOS builds
+    pub app_path: PathBuf,
+    /// Web builds: the interface directory the app was assembled in, served by the design server
+    /// while it rebuilds
+    pub interface_dir: Option<PathBuf>,
+}
+
+/// How to reach a `RunningApp`, depending on its target
+#[derive(Clone, Debug, PartialEq)]
+pub enum AppAccess {
+    /// Address the app is served at, e.g. `http://127.0.0.1:8080`
+    Web { url: String },
+    #[allow(non_camel_case_types)]
+    macOS { pid: u32 },
+    /// Simulator the app was launched on, and the app's process if the launch reported it
+    #[allow(non_camel_case_types)]
+    iOS {
+        simulator_udid: String,
+        pid: Option<u32>,
+    },
+}
+
+enum AppProcess {
+    /// A server serving the app, running on a thread of this process
+    Server {
+        handle: ServerHandle,
+        thread: Option<JoinHandle<std::io::Result<()>>>,
+    },
+    /// The app's own process
+    Child(Child),
+    /// An app launched on a simulator
+    Simulator { udid: String, bundle_id: String },
+}
+
+/// An app launched by `perform_run`.  Dropping it stops the app, unless it's been `detach`ed
+pub struct RunningApp {
+    access: AppAccess,
+    process: AppProcess,
+    process_child_ids: Arc<Mutex<Vec<u64>>>,
+    detached: bool,
+}
+
+impl RunningApp {
+    /// Takes over the app's process, which it tracks in `process_child_ids` until the app exits
+    pub(crate) fn from_child(child: Child, process_child_ids: Arc<Mutex<Vec<u64>>>) -> Self {
+        process_child_ids
+            .lock()
+            .expect(ERR_LOCK)
+            .push(child.id().into());
+        Self {
+            access: AppAccess::macOS { pid: child.id() },
+            process: AppProcess::Child(child),
+            process_child_ids,
+            detached: false,
+        }
+    }
+
+    pub(crate) fn from_simulator(
+        udid: &str,
+        bundle_id: &str,
+        pid: Option<u32>,
+        process_child_ids: Arc<Mutex<Vec<u64>>>,
+    ) -> Self {
+        Self {
+            access: AppAccess::iOS {
+                simulator_udid: udid.to_string(),
+                pid,
+            },
+            process: AppProcess::Simulator {
+                udid: udid.to_string(),
+                bundle_id: bundle_id.to_string(),
+            },
+            process_child_ids,
+            detached: false,
+        }
+    }
+
+    pub fn access(&self) -> &AppAccess {
+        &self.access
+    }
+
+    /// Address of a web app
+    pub fn url(&self) -> Option<&str> {
+        match &self.access {
+            AppAccess::Web { url } => Some(url),
+            _ => None,
+        }
+    }
+
+    /// Process of a macOS app, or of an iOS app on its simulator
+    pub fn pid(&self) -> Option<u32> {
+        match &self.access {
+            AppAccess::macOS { pid } => Some(*pid),
+            AppAccess::iOS { pid, .. } => *pid,
+            AppAccess::Web { .. } => None,
+        }
+    }
+
+    /// Simulator an iOS app runs on
+    pub fn simulator_udid(&self) -> Option<&str> {
+        match &self.access {
+            AppAccess::iOS { simulator_udid, .. } => Some(simulator_udid),
+            _ => None,
+        }
+    }
+
+    pub fn is_alive(&mut self) -> bool {
+        let alive = match &mut self.process {
+            AppProcess::Server { thread, .. } => {
+                thread.as_ref().is_some_and(|thread| !thread.is_finished())
+            }
+            AppProcess::Child(child) => matches!(child.try_wait(), Ok(None)),
+            AppProcess::Simulator { .. } => self.pid().is_some_and(is_process_alive),
+        };
+        if !alive {
+            self.untrack();
+        }
+        alive
+    }
+
+    /// Blocks until the app exits on its own, e.g. when its window is closed
+    pub fn wait(&mut self) -> eyre::Result<()> {
+        match &mut self.process {
+            AppProcess::Server { thread, .. } => {
+                if let Some(thread) = thread.take() {
+                    thread
+                        .join()
+                        .map_err(|_| eyre!("Server thread panicked"))?
+                        .map_err(|e| eyre!("Server failed: {}", e))?;
+                }
+            }
+            AppProcess::Child(child) => {
+                let status = child.wait()?;
+                println!("App exited with: {:?}", status);
+            }
+            AppProcess::Simulator { .. } => {
+                while self.is_alive() {
+                    thread::sleep(POLL_INTERVAL);
+                }
+            }
+        }
+        self.untrack();
+        Ok(())
+    }
+
+    /// Asks the app to exit, and kills it if it hasn't within `timeout`
+    pub fn stop(&mut self, timeout: Duration) -> eyre::Result<()> {
+        if !self.is_alive() {
+            return Ok(());
+        }
+        match &mut self.process {
+            AppProcess::Server { handle, .. } => {
+                // the stop command is sent right away; the returned future only reports completion
+                drop(handle.stop(true));
+                if !self.wait_for_exit(timeout) {
+                    if let AppProcess::Server { handle, .. } = &self.process {
+                        drop(handle.stop(false));
+                    }
+                }
+                self.wait()
+            }
+            AppProcess::Child(child) => {
+                #[cfg(unix)]
+                unsafe {
+                    libc::kill(child.id() as libc::pid_t, libc::SIGTERM);
+                }
+                if !self.wait_for_exit(timeout) {
+                    if let AppProcess::Child(child) = &mut self.process {
+                        child.kill()?;
+                    }
+                }
+                self.wait()
+            }
+            AppProcess::Simulator { udid, bundle_id } => {
+                let mut cmd = std::process::Command::new("xcrun");
+                cmd.arg("simctl")
+                    .arg("terminate")
+                    .arg(udid.as_str())
+                    .arg(bundle_id.as_str())
+                    .stdout(std::process::Stdio::piped())
+                    .stderr(std::process::Stdio::piped());
+                let child = cmd.spawn()?;
+                let output = wait_with_output(&self.process_child_ids, child);
+                if !output.status.success() {
+                    return Err(eyre!("Error terminating app on iOS simulator."));
+                }
+                if !self.wait_for_exit(timeout) {
+                    return Err(eyre!(
+                        "App on iOS simulator didn't exit within {:?}",
+                        timeout
+                    ));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Leaves the app running once this is dropped, e.g. for a CLI that exits after launching
+    pub fn detach(mut self) {
+        self.untrack();
+        self.detached = true;
+    }
+
+    /// Whether the app exited within `timeout`
+    fn wait_for_exit(&mut self, timeout: Duration) -> bool {
+        let start = Instant::now();
+        while self.is_alive() {
+            if start.elapsed() >= timeout {
+                return false;
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+        true
+    }
+
+    fn untrack(&self) {
+        if let AppProcess::Child(child) = &self.process {
+            let child_id: u64 = child.id().into();
+            self.process_child_ids
+                .lock()
+                .expect(ERR_LOCK)
+                .retain(|&id| id != child_id);
+        }
+    }
+}
+
+impl Drop for RunningApp {
+    fn drop(&mut self) {
+        if !self.detached {
+            if let Err(e) = self.stop(DROP_STOP_TIMEOUT) {
+                eprintln!("{} ⚠️  Failed to stop app: {}", *PAX_BADGE, e);
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_process_alive(pid: u32) -> bool {
+    unsafe { libc::kill(pid as libc::pid_t, 0) == 0 }
+}
+
+#[cfg(not(unix))]
+fn is_process_alive(_pid: u32) -> bool {
+    false
+}
+
+/// Serves a web build from a thread of this process, through the design server for designer builds
+pub(crate) fn serve_web_app(
+    ctx: &RunContext,
+    artifacts: &BuildArtifacts,
+) -> eyre::Result<RunningApp> {
+    let app_path = artifacts.app_path.clone();
+    let process_child_ids = Arc::clone(&ctx.process_child_ids);
+    if artifacts.is_designer {
+        ctx.status(
+            Verbosity::Normal,
+            "🐇🎨 Running Pax Web with Pax Designer...",
+        );
+        dotenv().ok();
+        let project_root = artifacts.project_root.clone();
+        let manifest = artifacts.manifest.clone();
+        serve_in_background(
+            move || {
+                design_server::bind_server(
+                    app_path.to_str().unwrap(),
+                    project_root.to_str().unwrap(),
+                    manifest,
+                )
+            },
+            process_child_ids,
+        )
+    } else {
+        ctx.status(Verbosity::Normal, "🐇 Running Pax Web...");
+        serve_in_background(
+            move || static_server::bind_server(app_path),
+            process_child_ids,
+        )
+    }
+}
+
+/// Runs the server bound by `bind` in an actix system on its own thread
+fn serve_in_background(
+    bind: impl FnOnce() -> std::io::Result<BoundServer> + Send + 'static,
+    process_child_ids: Arc<Mutex<Vec<u64>>>,
+) -> eyre::Result<RunningApp> {
+    let (bound_tx, bound_rx) = mpsc::channel();
+    let thread = thread::spawn(move || {
+        actix_web::rt::System::new().block_on(async move {
+            let BoundServer {
+                server,
+                port,
+                watcher,
+            } = match bind() {
+                Ok(bound) => bound,
+                Err(e) => {
+                    let _ = bound_tx.send(Err(e.to_string()));
+                    return Ok(());
+                }
+            };
+            let _ = bound_tx.send(Ok((server.handle(), port)));
+            let _watcher = watcher;
+            server.await
+        })
+    });
+    let (handle, port) = bound_rx
+        .recv()
+        .map_err(|_| eyre!("Server thread exited before binding"))?
+        .map_err(|e| eyre!("Failed to start server: {}", e))?;
+
+    Ok(RunningApp {
+        access: AppAccess::Web {
+            url: format!("http://127.0.0.1:{}", port),
+        },
+        process: AppProcess::Server {
+            handle,
+            thread: Some(thread),
+        },
+        process_child_ids,
+        detached: false,
+    })
+}
@@ -0,0 +1,1131 @@
+//! # SVG Import
+//!
+//! Converts the shapes of an SVG document into the template of a new component, so that vector art
+//! made in other tools can be brought into a Pax project: `<g>` becomes a `Group`, `<rect>` a
+//! `Rectangle`, `<circle>` and `<ellipse>` an `Ellipse`, and `<path>`, `<line>`, `<polyline>` and
+//! `<polygon>` a `Path`, with their fills, strokes and transforms.
+//!
+//! Only this common subset of SVG is handled.  Everything else that affects rendering, like
+//! `<text>`, gradients or filters, is collected as [`UnsupportedSvg`] entries: [`import_svg`] fails
+//! with them, and [`import_svg_lossy`] returns them along with what it could import.  Non-rendered
+//! content, like `<title>`, `<defs>` or editor metadata in foreign namespaces, is skipped.
+
+use std::fmt::{self, Display};
+
+use pax_manifest::utils::parse_value;
+use pax_manifest::{
+    ComponentDefinition, ComponentTemplate, SettingElement, TemplateNodeDefinition, TemplateNodeId,
+    Token, TypeId,
+};
+
+const GROUP: &str = "pax_std::core::group::Group";
+const RECTANGLE: &str = "pax_std::drawing::rectangle::Rectangle";
+const ELLIPSE: &str = "pax_std::drawing::ellipse::Ellipse";
+const PATH: &str = "pax_std::drawing::path::Path";
+
+/// Presentation attributes with no Pax equivalent, reported when set
+const UNSUPPORTED_ATTRIBUTES: &[&str] = &[
+    "clip-path",
+    "mask",
+    "filter",
+    "marker-start",
+    "marker-mid",
+    "marker-end",
+    "stroke-dasharray",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportError {
+    /// The document isn't well-formed XML
+    Malformed { line: usize, message: String },
+    /// The root element of the document isn't `<svg>`
+    NotSvg,
+    /// The component name isn't a PascalCase identifier
+    InvalidComponentName(String),
+    /// The document uses SVG features that can't be imported, see [`import_svg_lossy`]
+    Unsupported(Vec<UnsupportedSvg>),
+}
+
+impl Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Malformed { line, message } => {
+                write!(f, "malformed SVG at line {}: {}", line, message)
+            }
+            ImportError::NotSvg => write!(f, "the document's root element isn't <svg>"),
+            ImportError::InvalidComponentName(name) => {
+                write!(f, "`{}` isn't a valid component name", name)
+            }
+            ImportError::Unsupported(unsupported) => {
+                write!(f, "the SVG uses unsupported features:")?;
+                for u in unsupported {
+                    write!(f, "\n  {}", u)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+/// Something in an SVG document that couldn't be imported, or only approximately
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnsupportedSvg {
+    /// Line of the element in the document, starting at 1
+    pub line: usize,
+    pub element: String,
+    /// What isn't supported, e.g. "element" or "attribute `filter`"
+    pub feature: String,
+}
+
+impl Display for UnsupportedSvg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: <{}>: {} not supported",
+            self.line, self.element, self.feature
+        )
+    }
+}
+
+/// A component imported from SVG, with what was left out of it
+#[derive(Clone)]
+pub struct SvgImport {
+    pub component: ComponentDefinition,
+    pub unsupported: Vec<UnsupportedSvg>,
+}
+
+/// Imports the shapes of `svg` as the template of a component named `component_name`, failing
+/// with [`ImportError::Unsupported`] if it uses anything that can't be imported
+pub fn import_svg(svg: &str, component_name: &str) -> Result<ComponentDefinition, ImportError> {
+    let import = import_svg_lossy(svg, component_name)?;
+    if !import.unsupported.is_empty() {
+        return Err(ImportError::Unsupported(import.unsupported));
+    }
+    Ok(import.component)
+}
+
+/// Imports the shapes of `svg` as the template of a component named `component_name`, leaving out
+/// or approximating what can't be imported, as listed in [`SvgImport::unsupported`]
+pub fn import_svg_lossy(svg: &str, component_name: &str) -> Result<SvgImport, ImportError> {
+    if !is_pascal_case_identifier(component_name) {
+        return Err(ImportError::InvalidComponentName(
+            component_name.to_string(),
+        ));
+    }
+    let root = parse_xml(svg)?;
+    if root.name != "svg" {
+        return Err(ImportError::NotSvg);
+    }
+
+    let type_id =
+        TypeId::build_singleton(&format!("crate::{}", component_name), Some(component_name));
+    let mut importer = Importer {
+        template: ComponentTemplate::new(type_id.clone(), None),
+        unsupported: vec![],
+    };
+    let parent = viewbox_transform(&root).map(|transform| {
+        importer.add(
+            None,
+            GROUP,
+            None,
+            vec![("transform", format!("{{{}}}", transform))],
+        )
+    });
+    let style = Style::default().inherit(&root, &mut importer.unsupported);
+    for child in &root.children {
+        importer.import(child, parent.clone(), &style);
+    }
+
+    Ok(SvgImport {
+        component: ComponentDefinition {
+            type_id,
+            is_main_component: false,
+            is_primitive: false,
+            is_struct_only_component: false,
+            module_path: "crate".to_string(),
+            primitive_instance_import_path: None,
+            template: Some(importer.template),
+            settings: None,
+            extends: None,
+        },
+        unsupported: importer.unsupported,
+    })
+}
+
+fn is_pascal_case_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_uppercase())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Maps the `viewBox` of the root element onto its `width` and `height`, if that isn't the identity
+fn viewbox_transform(root: &Element) -> Option<String> {
+    let viewbox = numbers(root.attr("viewBox")?)?;
+    let [min_x, min_y, width, height] = viewbox[..] else {
+        return None;
+    };
+    let scale_x = root
+        .attr("width")
+        .and_then(length)
+        .map_or(1.0, |w| w / width);
+    let scale_y = root
+        .attr("height")
+        .and_then(length)
+        .map_or(1.0, |h| h / height);
+    let mut factors = vec![];
+    if scale_x != 1.0 || scale_y != 1.0 {
+        factors.push(format!(
+            "Transform2D::scale({}%, {}%)",
+            num(scale_x * 100.0),
+            num(scale_y * 100.0)
+        ));
+    }
+    if min_x != 0.0 || min_y != 0.0 {
+        factors.push(format!(
+            "Transform2D::translate({}px, {}px)",
+            num(-min_x),
+            num(-min_y)
+        ));
+    }
+    (!factors.is_empty()).then(|| factors.join(" * "))
+}
+
+struct Importer {
+    template: ComponentTemplate,
+    unsupported: Vec<UnsupportedSvg>,
+}
+
+impl Importer {
+    fn report(&mut self, element: &Element, feature: impl Into<String>) {
+        self.unsupported.push(UnsupportedSvg {
+            line: element.line,
+            element: element.name.clone(),
+            feature: feature.into(),
+        });
+    }
+
+    fn add(
+        &mut self,
+        parent: Option<TemplateNodeId>,
+        import_path: &str,
+        label: Option<String>,
+        settings: Vec<(&str, String)>,
+    ) -> TemplateNodeId {
+        let tnd = TemplateNodeDefinition {
+            type_id: TypeId::build_singleton(import_path, None),
+            control_flow_settings: None,
+            settings: Some(
+                settings
+                    .into_iter()
+                    .map(|(key, value)| {
+                        SettingElement::Setting(
+                            Token::new_without_location(key.to_string()),
+                            parse_value(&value).unwrap_or_else(|e| {
+                                panic!("generated setting `{}={}` should parse: {}", key, value, e)
+                            }),
+                        )
+                    })
+                    .collect(),
+            ),
+            raw_comment_string: None,
+            label,
+        };
+        let id = match parent {
+            Some(parent) => self.template.add_child_back(parent, tnd),
+            None => self.template.add_root_node_back(tnd),
+        };
+        id.get_template_node_id()
+    }
+
+    fn import(&mut self, element: &Element, parent: Option<TemplateNodeId>, style: &Style) {
+        match element.name.as_str() {
+            // not rendered, or only through references, which are reported where they're made
+            "title" | "desc" | "metadata" | "defs" | "symbol" => return,
+            // editor metadata, e.g. `sodipodi:namedview`
+            name if name.contains(':') => return,
+            _ => (),
+        }
+        if element.attr("display") == Some("none") {
+            return;
+        }
+        for attribute in UNSUPPORTED_ATTRIBUTES {
+            if element.attr(attribute).is_some() {
+                self.report(element, format!("attribute `{}`", attribute));
+            }
+        }
+        if element.attr("fill-rule") == Some("evenodd") {
+            self.report(element, "attribute `fill-rule=evenodd`");
+        }
+
+        let style = style.inherit(element, &mut self.unsupported);
+        let label = element.attr("id").map(str::to_string);
+        let transform = element
+            .attr("transform")
+            .and_then(|t| self.transform(element, t));
+
+        if element.name == "g" {
+            let settings = transform
+                .map(|t| vec![("transform", format!("{{{}}}", t))])
+                .unwrap_or_default();
+            let group = self.add(parent, GROUP, label, settings);
+            for child in &element.children {
+                self.import(child, Some(group.clone()), &style);
+            }
+            return;
+        }
+
+        let Some((import_path, mut settings)) = self.shape(element, &style) else {
+            return;
+        };
+        // a node's `transform` applies within its own layout box, so the transform of an SVG shape,
+        // which applies to its position as well, goes on a group around it
+        let (parent, label) = match transform {
+            Some(t) => (
+                Some(self.add(
+                    parent,
+                    GROUP,
+                    label,
+                    vec![("transform", format!("{{{}}}", t))],
+                )),
+                None,
+            ),
+            None => (parent, label),
+        };
+        if let Some(stroke) = style.stroke_setting() {
+            settings.push(("stroke", stroke));
+        }
+        self.add(parent, import_path, label, settings);
+    }
+
+    /// The primitive for shape `element`, with its geometry and fill
+    fn shape(
+        &mut self,
+        element: &Element,
+        style: &Style,
+    ) -> Option<(&'static str, Vec<(&'static str, String)>)> {
+        let fill = ("fill", style.fill_color().to_pax());
+        let shape = match element.name.as_str() {
+            "rect" => {
+                let [x, y, width, height] = self.lengths(element, ["x", "y", "width", "height"])?;
+                let mut settings = position(x, y, width, height);
+                let rx = element.attr("rx").and_then(length);
+                let ry = element.attr("ry").and_then(length);
+                if let Some(r) = rx.or(ry) {
+                    if rx.zip(ry).is_some_and(|(rx, ry)| rx != ry) {
+                        self.report(element, "elliptical corners");
+                    }
+                    let r = num(r.min(width / 2.0).min(height / 2.0));
+                    settings.push((
+                        "corner_radii",
+                        format!("{{RectangleCornerRadii::radii({r}, {r}, {r}, {r})}}"),
+                    ));
+                }
+                (RECTANGLE, settings)
+            }
+            "circle" => {
+                let [cx, cy, r] = self.lengths(element, ["cx", "cy", "r"])?;
+                (ELLIPSE, position(cx - r, cy - r, 2.0 * r, 2.0 * r))
+            }
+            "ellipse" => {
+                let [cx, cy, rx, ry] = self.lengths(element, ["cx", "cy", "rx", "ry"])?;
+                (ELLIPSE, position(cx - rx, cy - ry, 2.0 * rx, 2.0 * ry))
+            }
+            "line" => {
+                let [x1, y1, x2, y2] = self.lengths(element, ["x1", "y1", "x2", "y2"])?;
+                let path = vec![
+                    PathSegment::Point(x1, y1),
+                    PathSegment::Line,
+                    PathSegment::Point(x2, y2),
+                ];
+                (PATH, vec![("elements", elements_setting(&path))])
+            }
+            "polyline" | "polygon" => {
+                let points = numbers(element.attr("points").unwrap_or_default())
+                    .filter(|points| points.len() % 2 == 0);
+                let Some(points) = points else {
+                    self.report(element, "value of `points`");
+                    return None;
+                };
+                let mut path = vec![];
+                for (i, point) in points.chunks(2).enumerate() {
+                    if i > 0 {
+                        path.push(PathSegment::Line);
+                    }
+                    path.push(PathSegment::Point(point[0], point[1]));
+                }
+                if element.name == "polygon" && !path.is_empty() {
+                    path.push(PathSegment::Close);
+                }
+                (PATH, vec![("elements", elements_setting(&path))])
+            }
+            "path" => {
+                let data = element.attr("d").unwrap_or_default();
+                let path = match parse_path_data(data) {
+                    Ok(path) => path,
+                    Err(feature) => {
+                        self.report(element, feature);
+                        return None;
+                    }
+                };
+                if path.has_arcs {
+                    self.report(element, "arc commands, drawn as lines,");
+                }
+                if path.segments.is_empty() {
+                    return None;
+                }
+                (PATH, vec![("elements", elements_setting(&path.segments))])
+            }
+            "svg" => {
+                self.report(element, "nested <svg>");
+                return None;
+            }
+            _ => {
+                self.report(element, "element");
+                return None;
+            }
+        };
+        let (import_path, mut settings) = shape;
+        settings.push(fill);
+        Some((import_path, settings))
+    }
+
+    /// The values of the length attributes `names` of `element`, missing ones being 0
+    fn lengths<const N: usize>(&mut self, element: &Element, names: [&str; N]) -> Option<[f64; N]> {
+        let mut values = [0.0; N];
+        for (value, name) in values.iter_mut().zip(names) {
+            let Some(attr) = element.attr(name) else {
+                continue;
+            };
+            let Some(v) = length(attr) else {
+                self.report(element, format!("value `{}` of `{}`", attr, name));
+                return None;
+            };
+            *value = v;
+        }
+        Some(values)
+    }
+
+    /// The `Transform2D` expression for SVG transform list `transform`, reporting the functions
+    /// without an equivalent
+    fn transform(&mut self, element: &Element, transform: &str) -> Option<String> {
+        let mut factors = vec![];
+        for function in transform.split_inclusive(')') {
+            let function = function.trim().trim_start_matches(',').trim();
+            if function.is_empty() {
+                continue;
+            }
+            let Some((name, args)) = function.strip_suffix(')').and_then(|f| f.split_once('('))
+            else {
+                self.report(element, format!("transform `{}`", function));
+                continue;
+            };
+            let args = numbers(args).unwrap_or_default();
+            match (name.trim(), &args[..]) {
+                ("translate", &[x]) => factors.push(translate(x, 0.0)),
+                ("translate", &[x, y]) => factors.push(translate(x, y)),
+                ("scale", &[s]) => factors.push(scale(s, s)),
+                ("scale", &[x, y]) => factors.push(scale(x, y)),
+                ("rotate", &[a]) => factors.push(rotate(a)),
+                ("rotate", &[a, cx, cy]) => {
+                    factors.push(translate(cx, cy));
+                    factors.push(rotate(a));
+                    factors.push(translate(-cx, -cy));
+                }
+                _ => self.report(element, format!("transform `{}`", function)),
+            }
+        }
+        // `Transform2D` products apply right to left, as SVG transform lists do
+        (!factors.is_empty()).then(|| factors.join(" * "))
+    }
+}
+
+fn translate(x: f64, y: f64) -> String {
+    format!("Transform2D::translate({}px, {}px)", num(x), num(y))
+}
+
+fn scale(x: f64, y: f64) -> String {
+    format!(
+        "Transform2D::scale({}%, {}%)",
+        num(x * 100.0),
+        num(y * 100.0)
+    )
+}
+
+fn rotate(degrees: f64) -> String {
+    format!("Transform2D::rotate({}deg)", num(degrees))
+}
+
+fn position(x: f64, y: f64, width: f64, height: f64) -> Vec<(&'static str, String)> {
+    vec![
+        ("x", format!("{}px", num(x))),
+        ("y", format!("{}px", num(y))),
+        ("width", format!("{}px", num(width))),
+        ("height", format!("{}px", num(height))),
+    ]
+}
+
+/// `v` rounded to thousandths, without trailing zeros
+fn num(v: f64) -> String {
+    let v = (v * 1000.0).round() / 1000.0;
+    // avoid printing `-0`
+    format!("{}", if v == 0.0 { 0.0 } else { v })
+}
+
+/// A length in user units: a number, optionally in `px`
+fn length(value: &str) -> Option<f64> {
+    let value = value.trim();
+    value
+        .strip_suffix("px")
+        .unwrap_or(value)
+        .trim()
+        .parse()
+        .ok()
+        .filter(|v: &f64| v.is_finite())
+}
+
+/// A list of numbers separated by whitespace and/or commas
+fn numbers(value: &str) -> Option<Vec<f64>> {
+    let mut lexer = PathLexer::new(value);
+    let mut numbers = vec![];
+    while !lexer.at_end() {
+        numbers.push(lexer.number()?);
+    }
+    Some(numbers)
+}
+
+/// The inherited presentation properties in effect for an element
+#[derive(Clone)]
+struct Style {
+    fill: Option<Rgba>,
+    fill_opacity: f64,
+    stroke: Option<Rgba>,
+    stroke_opacity: f64,
+    stroke_width: f64,
+    /// Product of the `opacity` of the element and its ancestors, applied to its fill and stroke
+    opacity: f64,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style {
+            fill: Some(Rgba(0, 0, 0, 1.0)),
+            fill_opacity: 1.0,
+            stroke: None,
+            stroke_opacity: 1.0,
+            stroke_width: 1.0,
+            opacity: 1.0,
+        }
+    }
+}
+
+impl Style {
+    /// The style of `element`, a child of an element with this style
+    fn inherit(&self, element: &Element, unsupported: &mut Vec<UnsupportedSvg>) -> Style {
+        let mut style = self.clone();
+        let mut report = |feature: String| {
+            unsupported.push(UnsupportedSvg {
+                line: element.line,
+                element: element.name.clone(),
+                feature,
+            })
+        };
+        let mut paint = |name: &str, target: &mut Option<Rgba>| {
+            if let Some(value) = element.attr(name) {
+                match parse_paint(value) {
+                    Some(paint) => *target = paint,
+                    None => {
+                        report(format!("{} `{}`", name, value));
+                        *target = None;
+                    }
+                }
+            }
+        };
+        paint("fill", &mut style.fill);
+        paint("stroke", &mut style.stroke);
+        let opacity = |name: &str| {
+            element
+                .attr(name)
+                .and_then(|v| v.trim().parse::<f64>().ok())
+                .map(|v| v.clamp(0.0, 1.0))
+        };
+        if let Some(o) = opacity("fill-opacity") {
+            style.fill_opacity = o;
+        }
+        if let Some(o) = opacity("stroke-opacity") {
+            style.stroke_opacity = o;
+        }
+        if let Some(o) = opacity("opacity") {
+            style.opacity *= o;
+        }
+        if let Some(width) = element.attr("stroke-width").and_then(length) {
+            style.stroke_width = width;
+        }
+        style
+    }
+
+    fn fill_color(&self) -> Rgba {
+        match &self.fill {
+            Some(fill) => fill.with_opacity(self.fill_opacity * self.opacity),
+            None => Rgba(0, 0, 0, 0.0),
+        }
+    }
+
+    /// The `stroke` setting of a shape with this style, if it has a stroke
+    fn stroke_setting(&self) -> Option<String> {
+        let stroke = self.stroke.as_ref()?;
+        let color = stroke.with_opacity(self.stroke_opacity * self.opacity);
+        Some(format!(
+            "{{color: {}, width: {}px}}",
+            color.to_pax(),
+            num(self.stroke_width)
+        ))
+    }
+}
+
+/// A color with alpha from 0 to 1
+#[derive(Clone, Debug, PartialEq)]
+struct Rgba(u8, u8, u8, f64);
+
+impl Rgba {
+    fn with_opacity(&self, opacity: f64) -> Rgba {
+        Rgba(self.0, self.1, self.2, self.3 * opacity)
+    }
+
+    fn to_pax(&self) -> String {
+        let Rgba(r, g, b, a) = self;
+        if *a >= 1.0 {
+            format!("rgb({}, {}, {})", r, g, b)
+        } else {
+            format!("rgba({}, {}, {}, {})", r, g, b, (a * 255.0).round() as u8)
+        }
+    }
+}
+
+/// An SVG paint: `Some(None)` for `none`, `None` if it isn't a supported color
+fn parse_paint(value: &str) -> Option<Option<Rgba>> {
+    let value = value.trim().to_ascii_lowercase();
+    if value == "none" {
+        return Some(None);
+    }
+    if let Some(hex) = value.strip_prefix('#') {
+        let digits: Vec<u8> = hex
+            .chars()
+            .map(|c| c.to_digit(16).map(|d| d as u8))
+            .collect::<Option<_>>()?;
+        let channel = |hi: u8, lo: u8| hi * 16 + lo;
+        return match digits[..] {
+            [r, g, b] => Some(Some(Rgba(r * 17, g * 17, b * 17, 1.0))),
+            [r1, r2, g1, g2, b1, b2] => Some(Some(Rgba(
+                channel(r1, r2),
+                channel(g1, g2),
+                channel(b1, b2),
+                1.0,
+            ))),
+            _ => None,
+        };
+    }
+    if let Some(args) = value
+        .strip_prefix("rgba(")
+        .or_else(|| value.strip_prefix("rgb("))
+        .and_then(|args| args.strip_suffix(')'))
+    {
+        let args: Vec<&str> = args.split(',').map(str::trim).collect();
+        let channel = |v: &str| -> Option<u8> {
+            let v = match v.strip_suffix('%') {
+                Some(percent) => percent.parse::<f64>().ok()? * 2.55,
+                None => v.parse::<f64>().ok()?,
+            };
+            Some(v.round().clamp(0.0, 255.0) as u8)
+        };
+        let (r, g, b) = (
+            channel(args.first()?)?,
+            channel(args.get(1)?)?,
+            channel(args.get(2)?)?,
+        );
+        let a = match args.get(3) {
+            Some(a) => a.parse::<f64>().ok()?.clamp(0.0, 1.0),
+            None => 1.0,
+        };
+        return (args.len() <= 4).then_some(Some(Rgba(r, g, b, a)));
+    }
+    let (r, g, b) = match value.as_str() {
+        "transparent" => return Some(None),
+        "black" => (0, 0, 0),
+        "white" => (255, 255, 255),
+        "red" => (255, 0, 0),
+        "lime" => (0, 255, 0),
+        "green" => (0, 128, 0),
+        "blue" => (0, 0, 255),
+        "yellow" => (255, 255, 0),
+        "cyan" | "aqua" => (0, 255, 255),
+        "magenta" | "fuchsia" => (255, 0, 255),
+        "gray" | "grey" => (128, 128, 128),
+        "silver" => (192, 192, 192),
+        "maroon" => (128, 0, 0),
+        "olive" => (128, 128, 0),
+        "purple" => (128, 0, 128),
+        "teal" => (0, 128, 128),
+        "navy" => (0, 0, 128),
+        "orange" => (255, 165, 0),
+        _ => return None,
+    };
+    Some(Some(Rgba(r, g, b, 1.0)))
+}
+
+/// A `PathElement` in absolute coordinates
+#[derive(Clone, Debug, PartialEq)]
+enum PathSegment {
+    Point(f64, f64),
+    Line,
+    Quadratic(f64, f64),
+    Cubic(f64, f64, f64, f64),
+    Close,
+}
+
+/// The `elements` setting of a `Path`
+fn elements_setting(path: &[PathSegment]) -> String {
+    let px = |v: &f64| format!("{}px", num(*v));
+    let elements: Vec<String> = path
+        .iter()
+        .map(|segment| match segment {
+            PathSegment::Point(x, y) => format!("PathElement::Point({}, {})", px(x), px(y)),
+            PathSegment::Line => "PathElement::Line".to_string(),
+            PathSegment::Quadratic(x, y) => {
+                format!("PathElement::Quadratic({}, {})", px(x), px(y))
+            }
+            PathSegment::Cubic(x1, y1, x2, y2) => format!(
+                "PathElement::Cubic({}, {}, {}, {})",
+                px(x1),
+                px(y1),
+                px(x2),
+                px(y2)
+            ),
+            PathSegment::Close => "PathElement::Close".to_string(),
+        })
+        .collect();
+    format!("{{[{}]}}", elements.join(", "))
+}
+
+struct PathData {
+    segments: Vec<PathSegment>,
+    /// Whether the data has elliptical arcs, drawn as straight lines to their end points
+    has_arcs: bool,
+}
+
+/// The segments of SVG path data `d`, or what's wrong with it
+fn parse_path_data(d: &str) -> Result<PathData, String> {
+    let malformed = || format!("path data `{}`", d);
+    let mut lexer = PathLexer::new(d);
+    let mut data = PathData {
+        segments: vec![],
+        has_arcs: false,
+    };
+    let (mut current, mut start) = ((0.0, 0.0), (0.0, 0.0));
+    // the control point of the previous curve, reflected by the smooth curve commands
+    let mut last_cubic: Option<(f64, f64)> = None;
+    let mut last_quadratic: Option<(f64, f64)> = None;
+    let mut closed = false;
+    let mut command = None;
+
+    while !lexer.at_end() {
+        let cmd = match lexer.command() {
+            Some(c) => c,
+            // repeated arguments repeat the command, with `M` followed by `L`
+            None => match command {
+                Some('M') => 'L',
+                Some('m') => 'l',
+                Some(c) => c,
+                None => return Err(malformed()),
+            },
+        };
+        command = Some(cmd);
+        let relative = cmd.is_ascii_lowercase();
+        let origin = if relative { current } else { (0.0, 0.0) };
+        let point = |lexer: &mut PathLexer| -> Result<(f64, f64), String> {
+            let x = lexer.number().ok_or_else(malformed)?;
+            let y = lexer.number().ok_or_else(malformed)?;
+            Ok((origin.0 + x, origin.1 + y))
+        };
+        let upper = cmd.to_ascii_uppercase();
+
+        // after `Z`, drawing resumes from the start of the closed subpath
+        if closed && upper != 'M' && upper != 'Z' {
+            data.segments.push(PathSegment::Point(start.0, start.1));
+        }
+        closed = false;
+        let (mut cubic, mut quadratic) = (None, None);
+        match upper {
+            'M' => {
+                current = point(&mut lexer)?;
+                start = current;
+                data.segments.push(PathSegment::Point(current.0, current.1));
+            }
+            'L' => {
+                current = point(&mut lexer)?;
+                data.segments.push(PathSegment::Line);
+                data.segments.push(PathSegment::Point(current.0, current.1));
+            }
+            'H' | 'V' => {
+                let v = lexer.number().ok_or_else(malformed)?;
+                if upper == 'H' {
+                    current.0 = if relative { current.0 + v } else { v };
+                } else {
+                    current.1 = if relative { current.1 + v } else { v };
+                }
+                data.segments.push(PathSegment::Line);
+                data.segments.push(PathSegment::Point(current.0, current.1));
+            }
+            'C' | 'S' => {
+                let c1 = if upper == 'C' {
+                    point(&mut lexer)?
+                } else {
+                    reflect(last_cubic, current)
+                };
+                let c2 = point(&mut lexer)?;
+                current = point(&mut lexer)?;
+                data.segments
+                    .push(PathSegment::Cubic(c1.0, c1.1, c2.0, c2.1));
+                data.segments.push(PathSegment::Point(current.0, current.1));
+                cubic = Some(c2);
+            }
+            'Q' | 'T' => {
+                let c = if upper == 'Q' {
+                    point(&mut lexer)?
+                } else {
+                    reflect(last_quadratic, current)
+                };
+                current = point(&mut lexer)?;
+                data.segments.push(PathSegment::Quadratic(c.0, c.1));
+                data.segments.push(PathSegment::Point(current.0, current.1));
+                quadratic = Some(c);
+            }
+            'A' => {
+                for _ in 0..3 {
+                    lexer.number().ok_or_else(malformed)?;
+                }
+                lexer.flag().ok_or_else(malformed)?;
+                lexer.flag().ok_or_else(malformed)?;
+                let x = lexer.number().ok_or_else(malformed)?;
+                let y = lexer.number().ok_or_else(malformed)?;
+                current = (origin.0 + x, origin.1 + y);
+                data.has_arcs = true;
+                data.segments.push(PathSegment::Line);
+                data.segments.push(PathSegment::Point(current.0, current.1));
+            }
+            'Z' => {
+                data.segments.push(PathSegment::Close);
+                current = start;
+                closed = true;
+            }
+            _ => return Err(malformed()),
+        }
+        if data
+            .segments
+            .first()
+            .is_some_and(|s| !matches!(s, PathSegment::Point(..)))
+        {
+            return Err(malformed());
+        }
+        last_cubic = cubic;
+        last_quadratic = quadratic;
+    }
+    Ok(data)
+}
+
+/// Reflection of control point `control` about `current`, or `current` without a control point
+fn reflect(control: Option<(f64, f64)>, current: (f64, f64)) -> (f64, f64) {
+    match control {
+        Some((x, y)) => (2.0 * current.0 - x, 2.0 * current.1 - y),
+        None => current,
+    }
+}
+
+/// Tokenizer of path data and number lists, where numbers can be packed, e.g. `10-5.5.5`
+struct PathLexer<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> PathLexer<'a> {
+    fn new(input: &'a str) -> Self {
+        PathLexer {
+            input: input.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    fn skip_separators(&mut self) {
+        while self
+            .input
+            .get(self.pos)
+            .is_some_and(|c| c.is_ascii_whitespace() || *c == b',')
+        {
+            self.pos += 1;
+        }
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_separators();
+        self.pos >= self.input.len()
+    }
+
+    fn command(&mut self) -> Option<char> {
+        self.skip_separators();
+        let c = *self.input.get(self.pos)?;
+        if c.is_ascii_alphabetic() && !matches!(c, b'e' | b'E') {
+            self.pos += 1;
+            return Some(c as char);
+        }
+        None
+    }
+
+    /// An arc flag, `0` or `1`, which needn't be separated from what follows
+    fn flag(&mut self) -> Option<bool> {
+        self.skip_separators();
+        let flag = match self.input.get(self.pos)? {
+            b'0' => false,
+            b'1' => true,
+            _ => return None,
+        };
+        self.pos += 1;
+        Some(flag)
+    }
+
+    fn number(&mut self) -> Option<f64> {
+        self.skip_separators();
+        let start = self.pos;
+        let at = |pos: usize| self.input.get(pos).copied();
+        let mut end = start;
+        if matches!(at(end), Some(b'+' | b'-')) {
+            end += 1;
+        }
+        let mut seen_dot = false;
+        while let Some(c) = at(end) {
+            match c {
+                b'0'..=b'9' => end += 1,
+                b'.' if !seen_dot => {
+                    seen_dot = true;
+                    end += 1;
+                }
+                _ => break,
+            }
+        }
+        if matches!(at(end), Some(b'e' | b'E')) {
+            let mut exp = end + 1;
+            if matches!(at(exp), Some(b'+' | b'-')) {
+                exp += 1;
+            }
+            if at(exp).is_some_and(|c| c.is_ascii_digit()) {
+                end = exp;
+                while at(end).is_some_and(|c| c.is_ascii_digit()) {
+                    end += 1;
+                }
+            }
+        }
+        let number = std::str::from_utf8(&self.input[start..end])
+            .ok()?
+            .parse()
+            .ok()?;
+        self.pos = end;
+        Some(number)
+    }
+}
+
+/// An XML element, with its attributes and the declarations of its `style` attribute
+struct Element {
+    name: String,
+    /// Line of the start tag, starting at 1
+    line: usize,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+}
+
+impl Element {
+    /// The value of presentation attribute `name`, the `style` attribute taking precedence
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// The root element of XML document `xml`.  Text content is ignored
+fn parse_xml(xml: &str) -> Result<Element, ImportError> {
+    let mut parser = XmlParser { xml, pos: 0 };
+    let mut stack: Vec<Element> = vec![];
+    let mut root = None;
+    while let Some(offset) = parser.rest().find('<') {
+        parser.pos += offset;
+        let line = parser.line();
+        let rest = parser.rest();
+        if rest.starts_with("<!--") {
+            parser.skip_past("-->")?;
+        } else if rest.starts_with("<![CDATA[") {
+            parser.skip_past("]]>")?;
+        } else if rest.starts_with("<?") {
+            parser.skip_past("?>")?;
+        } else if rest.starts_with("<!") {
+            parser.skip_past(">")?;
+        } else if let Some(rest) = rest.strip_prefix("</") {
+            let name_len = rest.find('>').ok_or_else(|| parser.error("unclosed tag"))?;
+            let name = rest[..name_len].trim();
+            let element = stack
+                .pop()
+                .filter(|e| e.name == name)
+                .ok_or_else(|| parser.error(&format!("unexpected </{}>", name)))?;
+            parser.pos += 2 + name_len + 1;
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(element),
+                None => root = Some(element),
+            }
+        } else {
+            let (element, self_closing) = parser.start_tag(line)?;
+            if self_closing {
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => root = Some(element),
+                }
+            } else {
+                stack.push(element);
+            }
+        }
+        if root.is_some() {
+            break;
+        }
+    }
+    if let Some(unclosed) = stack.last() {
+        return Err(ImportError::Malformed {
+            line: unclosed.line,
+            message: format!("<{}> is never closed", unclosed.name),
+        });
+    }
+    root.ok_or(ImportError::Malformed {
+        line: 1,
+        message: "no root element".to_string(),
+    })
+}
+
+struct XmlParser<'a> {
+    xml: &'a str,
+    pos: usize,
+}
+
+impl<'a> XmlParser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.xml[self.pos..]
+    }
+
+    fn line(&self) -> usize {
+        self.xml[..self.pos].matches('\n').count() + 1
+    }
+
+    fn error(&self, message: &str) -> ImportError {
+        ImportError::Malformed {
+            line: self.line(),
+            message: message.to_string(),
+        }
+    }
+
+    fn skip_past(&mut self, end: &str) -> Result<(), ImportError> {
+        let offset = self
+            .rest()
+            .find(end)
+            .ok_or_else(|| self.error(&format!("missing `{}`", end)))?;
+        self.pos += offset + end.len();
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        let trimmed = self.rest().trim_start();
+        self.pos = self.xml.len() - trimmed.len();
+    }
+
+    fn name(&mut self) -> Result<String, ImportError> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '=' | '>' | '/'))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error("expected a name"));
+        }
+        self.pos += len;
+        Ok(rest[..len].to_string())
+    }
+
+    /// The element started at the current `<`, and whether its tag is self-closing
+    fn start_tag(&mut self, line: usize) -> Result<(Element, bool), ImportError> {
+        self.pos += 1;
+        let mut element = Element {
+            name: self.name()?,
+            line,
+            attributes: vec![],
+            children: vec![],
+        };
+        let mut style = None;
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("/>") {
+                self.pos += 2;
+                break;
+            }
+            if rest.starts_with('>') {
+                self.pos += 1;
+                element.attributes.extend(style.unwrap_or_default());
+                return Ok((element, false));
+            }
+            if rest.is_empty() {
+                return Err(self.error("unclosed tag"));
+            }
+            let name = self.name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                return Err(self.error(&format!("attribute `{}` has no value", name)));
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let quote = self
+                .rest()
+                .chars()
+                .next()
+                .filter(|c| matches!(c, '"' | '\''))
+                .ok_or_else(|| self.error(&format!("value of `{}` isn't quoted", name)))?;
+            self.pos += 1;
+            let len = self
+                .rest()
+                .find(quote)
+                .ok_or_else(|| self.error(&format!("value of `{}` isn't closed", name)))?;
+            let value = unescape(&self.rest()[..len]);
+            self.pos += len + 1;
+            if name == "style" {
+                style = Some(parse_style(&value));
+            } else {
+                element.attributes.push((name, value));
+            }
+        }
+        element.attributes.extend(style.unwrap_or_default());
+        Ok((element, true))
+    }
+}
+
+/// The declarations of a `style` attribute, e.g. `fill: red; stroke: none`
+fn parse_style(style: &str) -> Vec<(String, String)> {
+    style
+        .split(';')
+        .filter_map(|declaration| declaration.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
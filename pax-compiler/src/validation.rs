@@ -0,0 +1,39 @@
+//! # Validation
+//!
+//! Checks the settings of template nodes against the properties of the component (or primitive)
+//! they instantiate, so that `<Card padding="lots"/>` fails the build at the line that says so
+//! instead of in the generated cartridge or at runtime.  The checks themselves are shared with the
+//! designtime, see [`pax_manifest::validation`]; this reports their issues as lint diagnostics.
+
+use pax_manifest::validation::{self, TemplateIssue};
+use pax_manifest::{ComponentDefinition, PaxManifest};
+
+use crate::lint::{component_location, component_name, LintDiagnostic, LintSeverity};
+
+/// Validates the settings of every template node of `manifest`, which is expected to be the
+/// manifest produced by the parser.  Any diagnostic with [`LintSeverity::Error`] fails the build
+pub fn validate_settings(manifest: &PaxManifest) -> Vec<LintDiagnostic> {
+    manifest
+        .components
+        .values()
+        .flat_map(|component| {
+            validation::validate_settings(manifest, component)
+                .into_iter()
+                .map(move |issue| to_diagnostic(component, issue))
+        })
+        .collect()
+}
+
+fn to_diagnostic(component: &ComponentDefinition, issue: TemplateIssue) -> LintDiagnostic {
+    let description = format!("`<{}>` in `{}`", issue.node_name, component_name(component));
+    LintDiagnostic {
+        rule: issue.rule.to_string(),
+        severity: if issue.is_error {
+            LintSeverity::Error
+        } else {
+            LintSeverity::Warning
+        },
+        message: issue.message,
+        location: component_location(component, issue.location.as_ref(), description),
+    }
+}
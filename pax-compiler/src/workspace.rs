@@ -0,0 +1,462 @@
+//! # Workspace
+//!
+//! A Pax app crate may be a standalone crate, or one member of a larger cargo workspace (e.g. an app
+//! crate alongside shared logic crates and a server.)  `CargoProject` captures where the app crate
+//! lives relative to its enclosing workspace, so that cargo is invoked from the workspace root with
+//! an explicit `-p <app-crate>`, and so that settings cargo only honors at the workspace root
+//! (profiles, patches) end up there.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+
+use color_eyre::eyre;
+use eyre::eyre;
+use pax_runtime_api::{Numeric, PaxValue};
+use toml_edit::{Document, Item, Table, Value};
+
+use crate::building::std_features::StdDependency;
+
+/// Env var that, when set, overrides the directory used for `.pax` state
+pub const PAX_DIR_ENV: &str = "PAX_DIR";
+
+/// Per-project configuration of pax tooling, e.g. lint rule selection, read from the app crate's
+/// directory or the workspace root
+pub const PAX_WORKSPACE_CONFIG: &str = "pax.workspace.toml";
+
+const PAX_DIR_NAME: &str = ".pax";
+const WORKSPACE_PAX_DIR_NAME: &str = "pax";
+
+/// Profiles that the compiler passes to cargo by name, and must therefore be defined at the root
+const REQUIRED_PROFILES: &[&str] = &["parser"];
+
+#[derive(Clone, Debug)]
+pub struct CargoProject {
+    /// Directory containing the app crate's `Cargo.toml`
+    pub crate_root: PathBuf,
+    /// `package.name` of the app crate
+    pub package_name: String,
+    /// Root of the enclosing workspace, if the app crate is a member of a larger workspace
+    pub workspace_root: Option<PathBuf>,
+}
+
+impl CargoProject {
+    /// Locates the app crate at `project_path` and its enclosing workspace, if any
+    pub fn locate(project_path: &Path) -> eyre::Result<Self> {
+        let crate_root = fs::canonicalize(project_path)
+            .map_err(|e| eyre!("Couldn't resolve project path {:?}: {}", project_path, e))?;
+        let manifest_path = crate_root.join("Cargo.toml");
+        let manifest = read_manifest(&manifest_path)?;
+        let package_name = manifest
+            .as_table()
+            .get("package")
+            .and_then(Item::as_table_like)
+            .and_then(|package| package.get("name"))
+            .and_then(|name| name.as_str())
+            .ok_or_else(|| eyre!("{:?} has no `package.name`", manifest_path))?
+            .to_string();
+
+        let output = Command::new("cargo")
+            .current_dir(&crate_root)
+            .arg("locate-project")
+            .arg("--workspace")
+            .arg("--message-format")
+            .arg("plain")
+            .output()
+            .map_err(|e| eyre!("Failed to run `cargo locate-project`: {}", e))?;
+        if !output.status.success() {
+            return Err(eyre!(
+                "`cargo locate-project` failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        let root_manifest_path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+        let workspace_root = root_manifest_path
+            .parent()
+            .map(|root| fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf()))
+            .filter(|root| *root != crate_root);
+
+        Ok(CargoProject {
+            crate_root,
+            package_name,
+            workspace_root,
+        })
+    }
+
+    pub fn is_workspace_member(&self) -> bool {
+        self.workspace_root.is_some()
+    }
+
+    /// Directory cargo should be invoked from
+    pub fn cargo_cwd(&self) -> &Path {
+        self.workspace_root.as_deref().unwrap_or(&self.crate_root)
+    }
+
+    pub fn root_manifest_path(&self) -> PathBuf {
+        self.cargo_cwd().join("Cargo.toml")
+    }
+
+    /// Selects the app crate for cargo invocations made from `cargo_cwd`
+    pub fn add_package_args(&self, cmd: &mut Command) {
+        if self.is_workspace_member() {
+            cmd.arg("-p").arg(&self.package_name);
+        }
+    }
+
+    pub fn target_dir(&self) -> PathBuf {
+        match env::var_os("CARGO_TARGET_DIR") {
+            Some(dir) => self.cargo_cwd().join(dir),
+            None => self.cargo_cwd().join("target"),
+        }
+    }
+
+    /// Directory for generated `.pax` state.  Standalone crates keep `.pax` beside their `Cargo.toml`;
+    /// workspace members each get their own directory under the workspace target dir, so that several
+    /// Pax apps in one workspace don't share state.  `PAX_DIR` overrides either location.
+    pub fn pax_dir(&self) -> PathBuf {
+        let base = match env::var_os(PAX_DIR_ENV) {
+            Some(dir) => PathBuf::from(dir),
+            None if self.is_workspace_member() => self.target_dir().join(WORKSPACE_PAX_DIR_NAME),
+            None => return self.crate_root.join(PAX_DIR_NAME),
+        };
+        if self.is_workspace_member() {
+            base.join(&self.package_name)
+        } else {
+            base
+        }
+    }
+
+    /// Components listed under `[package.metadata.pax] keep = [...]` in the app crate's manifest, which
+    /// release builds retain even if nothing in the component graph reaches them
+    pub fn keep_components(&self) -> eyre::Result<Vec<String>> {
+        let manifest = read_manifest(&self.crate_root.join("Cargo.toml"))?;
+        let keep = manifest
+            .as_table()
+            .get("package")
+            .and_then(|package| package.get("metadata"))
+            .and_then(|metadata| metadata.get("pax"))
+            .and_then(|pax| pax.get("keep"))
+            .and_then(Item::as_array);
+        Ok(keep
+            .map(|keep| {
+                keep.iter()
+                    .filter_map(|k| k.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    /// Features of the app crate listed under `default` in its `[features]`, enabled unless the build
+    /// passes `--no-default-features`, which the compiler never does
+    pub fn default_features(&self) -> eyre::Result<Vec<String>> {
+        let manifest = read_manifest(&self.crate_root.join("Cargo.toml"))?;
+        let default = manifest
+            .as_table()
+            .get("features")
+            .and_then(|features| features.get("default"))
+            .and_then(Item::as_array);
+        Ok(default
+            .map(|default| {
+                default
+                    .iter()
+                    .filter_map(|f| f.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    /// Defines listed under `[package.metadata.pax.defines]` in the app crate's manifest, which
+    /// expressions read as `$defines.<name>`.  Defines passed with `--define` take precedence
+    pub fn defines(&self) -> eyre::Result<BTreeMap<String, PaxValue>> {
+        let manifest = read_manifest(&self.crate_root.join("Cargo.toml"))?;
+        let Some(defines) = manifest
+            .as_table()
+            .get("package")
+            .and_then(|package| package.get("metadata"))
+            .and_then(|metadata| metadata.get("pax"))
+            .and_then(|pax| pax.get("defines"))
+        else {
+            return Ok(BTreeMap::new());
+        };
+        let defines = defines
+            .as_table_like()
+            .ok_or_else(|| eyre!("`package.metadata.pax.defines` must be a table"))?;
+        defines
+            .iter()
+            .map(|(key, value)| {
+                let value = match value.as_value() {
+                    Some(Value::Boolean(b)) => PaxValue::Bool(*b.value()),
+                    Some(Value::Integer(i)) => PaxValue::Numeric(Numeric::I64(*i.value())),
+                    Some(Value::Float(f)) => PaxValue::Numeric(Numeric::F64(*f.value())),
+                    Some(Value::String(s)) => PaxValue::String(s.value().clone()),
+                    _ => {
+                        return Err(eyre!(
+                            "Define `{}` in `package.metadata.pax.defines` must be a bool, number or string",
+                            key
+                        ))
+                    }
+                };
+                Ok((key.to_string(), value))
+            })
+            .collect()
+    }
+
+    /// Design tokens listed under `[package.metadata.pax.tokens]` in the app crate's manifest, which
+    /// settings reference as `{$tokens.<name>}`.  Strings are read as Pax literals, e.g.
+    /// `primary = "rgb(20, 40, 200)"` or `gutter = "16px"`, so string tokens need inner quotes
+    pub fn tokens(&self) -> eyre::Result<BTreeMap<String, PaxValue>> {
+        let manifest = read_manifest(&self.crate_root.join("Cargo.toml"))?;
+        let Some(tokens) = manifest
+            .as_table()
+            .get("package")
+            .and_then(|package| package.get("metadata"))
+            .and_then(|metadata| metadata.get("pax"))
+            .and_then(|pax| pax.get("tokens"))
+        else {
+            return Ok(BTreeMap::new());
+        };
+        let tokens = tokens
+            .as_table_like()
+            .ok_or_else(|| eyre!("`package.metadata.pax.tokens` must be a table"))?;
+        tokens
+            .iter()
+            .map(|(key, value)| {
+                let value = match value.as_value() {
+                    Some(Value::Boolean(b)) => PaxValue::Bool(*b.value()),
+                    Some(Value::Integer(i)) => PaxValue::Numeric(Numeric::I64(*i.value())),
+                    Some(Value::Float(f)) => PaxValue::Numeric(Numeric::F64(*f.value())),
+                    Some(Value::String(s)) => pax_lang::from_pax(s.value()).map_err(|e| {
+                        eyre!(
+                            "Token `{}` in `package.metadata.pax.tokens` isn't a Pax literal ({}); quote strings, e.g. `\"'{}'\"`",
+                            key,
+                            e,
+                            s.value()
+                        )
+                    })?,
+                    _ => {
+                        return Err(eyre!(
+                            "Token `{}` in `package.metadata.pax.tokens` must be a bool, number or string",
+                            key
+                        ))
+                    }
+                };
+                Ok((key.to_string(), value))
+            })
+            .collect()
+    }
+
+    /// pax-std features turned on or off under `[package.metadata.pax.std-features]` in the app crate's
+    /// manifest, overriding those selected from its templates
+    pub fn std_feature_overrides(&self) -> eyre::Result<BTreeMap<String, bool>> {
+        let manifest = read_manifest(&self.crate_root.join("Cargo.toml"))?;
+        let Some(features) = manifest
+            .as_table()
+            .get("package")
+            .and_then(|package| package.get("metadata"))
+            .and_then(|metadata| metadata.get("pax"))
+            .and_then(|pax| pax.get("std-features"))
+        else {
+            return Ok(BTreeMap::new());
+        };
+        let features = features
+            .as_table_like()
+            .ok_or_else(|| eyre!("`package.metadata.pax.std-features` must be a table"))?;
+        features
+            .iter()
+            .map(|(key, value)| match value.as_bool() {
+                Some(enabled) => Ok((key.to_string(), enabled)),
+                None => Err(eyre!(
+                    "Feature `{}` in `package.metadata.pax.std-features` must be a bool",
+                    key
+                )),
+            })
+            .collect()
+    }
+
+    /// The crate through which the app crate depends on pax-std, and so through which its features are
+    /// enabled, if any
+    pub fn std_dependency(&self) -> eyre::Result<Option<StdDependency>> {
+        let manifest = read_manifest(&self.crate_root.join("Cargo.toml"))?;
+        let dependencies = manifest.as_table().get("dependencies");
+        let has = |name: &str| dependencies.and_then(|deps| deps.get(name)).is_some();
+        Ok(if has("pax-kit") {
+            Some(StdDependency::PaxKit)
+        } else if has("pax-std") {
+            Some(StdDependency::PaxStd)
+        } else {
+            None
+        })
+    }
+
+    /// Whether the app crate keeps the default features of the crate it depends on pax-std through.
+    /// Those enable every pax-std family, so that the families selected from its templates don't
+    /// narrow the build.  The defaults of a dependency inherited with `workspace = true` are
+    /// declared under `[workspace.dependencies]` of the workspace root
+    pub fn keeps_std_default_features(&self, dependency: StdDependency) -> eyre::Result<bool> {
+        let name = dependency.crate_name();
+        let manifest = read_manifest(&self.crate_root.join("Cargo.toml"))?;
+        let Some(entry) = manifest
+            .as_table()
+            .get("dependencies")
+            .and_then(|deps| deps.get(name))
+        else {
+            return Ok(true);
+        };
+        let inherited = entry.get("workspace").and_then(|w| w.as_bool()) == Some(true);
+        let default_features = match (&self.workspace_root, inherited) {
+            (Some(workspace_root), true) => read_manifest(&workspace_root.join("Cargo.toml"))?
+                .as_table()
+                .get("workspace")
+                .and_then(|workspace| workspace.get("dependencies"))
+                .and_then(|deps| deps.get(name))
+                .and_then(|entry| entry.get("default-features"))
+                .and_then(|d| d.as_bool()),
+            _ => entry.get("default-features").and_then(|d| d.as_bool()),
+        };
+        Ok(default_features != Some(false))
+    }
+
+    /// The `pax.workspace.toml` of the app crate, or else of the enclosing workspace, if any
+    pub fn workspace_config(&self) -> eyre::Result<Option<Document>> {
+        let candidates = [Some(&self.crate_root), self.workspace_root.as_ref()];
+        for dir in candidates.into_iter().flatten() {
+            let path = dir.join(PAX_WORKSPACE_CONFIG);
+            if path.exists() {
+                return read_manifest(&path).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Copies the profiles the compiler requires, and any patches, from the app crate's manifest into the
+    /// workspace root manifest, since cargo ignores both outside the root.  Entries already present at the
+    /// root are left untouched, and the root manifest is only rewritten if something was added.
+    pub fn prepare_root_manifest(&self) -> eyre::Result<()> {
+        let Some(workspace_root) = &self.workspace_root else {
+            return Ok(());
+        };
+        let member = read_manifest(&self.crate_root.join("Cargo.toml"))?;
+        let root_manifest_path = self.root_manifest_path();
+        let mut root = read_manifest(&root_manifest_path)?;
+
+        let changed = sync_root_manifest(&member, &mut root, &self.crate_root, workspace_root);
+        if changed {
+            fs::write(&root_manifest_path, root.to_string())
+                .map_err(|e| eyre!("Failed to write {:?}: {}", root_manifest_path, e))?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn read_manifest(path: &Path) -> eyre::Result<Document> {
+    let contents =
+        fs::read_to_string(path).map_err(|e| eyre!("Failed to read {:?}: {}", path, e))?;
+    Document::from_str(&contents).map_err(|e| eyre!("Failed to parse {:?}: {}", path, e))
+}
+
+/// Adds the profiles the compiler requires and any `[patch.*]` entries from `member` that `root` is
+/// missing, rebasing relative patch paths from `member_dir` onto `root_dir`.  Returns whether `root`
+/// was modified.
+pub fn sync_root_manifest(
+    member: &Document,
+    root: &mut Document,
+    member_dir: &Path,
+    root_dir: &Path,
+) -> bool {
+    let mut changed = false;
+
+    let member_profiles = member.as_table().get("profile").and_then(Item::as_table);
+    let profiles = implicit_table(root, "profile");
+    for name in REQUIRED_PROFILES {
+        if profiles.contains_key(name) {
+            continue;
+        }
+        let profile = match member_profiles.and_then(|p| p.get(name)) {
+            Some(profile) => profile.clone(),
+            None => {
+                let mut profile = Table::new();
+                profile.insert("inherits", toml_edit::value("dev"));
+                profile.insert("opt-level", toml_edit::value(0i64));
+                Item::Table(profile)
+            }
+        };
+        profiles.insert(name, profile);
+        changed = true;
+    }
+
+    if let Some(member_patches) = member.as_table().get("patch").and_then(Item::as_table) {
+        let patches = implicit_table(root, "patch");
+        for (registry, member_registry_patches) in member_patches.iter() {
+            let Some(member_registry_patches) = member_registry_patches.as_table_like() else {
+                continue;
+            };
+            let registry_patches = patches
+                .entry(registry)
+                .or_insert_with(toml_edit::table)
+                .as_table_mut();
+            let Some(registry_patches) = registry_patches else {
+                continue;
+            };
+            for (dep, patch) in member_registry_patches.iter() {
+                if !registry_patches.contains_key(dep) {
+                    let mut patch = patch.clone();
+                    rebase_path(&mut patch, member_dir, root_dir);
+                    registry_patches.insert(dep, patch);
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    changed
+}
+
+fn implicit_table<'a>(doc: &'a mut Document, key: &str) -> &'a mut Table {
+    let item = doc.as_table_mut().entry(key).or_insert_with(|| {
+        let mut table = Table::new();
+        table.set_implicit(true);
+        Item::Table(table)
+    });
+    item.as_table_mut()
+        .expect("manifest key should hold a table")
+}
+
+/// Rewrites a relative `path = "..."` in a dependency spec so it resolves identically from `root_dir`
+fn rebase_path(dep: &mut Item, member_dir: &Path, root_dir: &Path) {
+    let Some(dep) = dep.as_table_like_mut() else {
+        return;
+    };
+    let Some(path) = dep.get("path").and_then(Item::as_str) else {
+        return;
+    };
+    if Path::new(path).is_absolute() {
+        return;
+    }
+    let absolute = normalize(&member_dir.join(path));
+    let rebased = absolute
+        .strip_prefix(root_dir)
+        .map(Path::to_path_buf)
+        .unwrap_or(absolute);
+    dep.insert(
+        "path",
+        toml_edit::value(rebased.to_string_lossy().to_string()),
+    );
+}
+
+/// Resolves `.` and `..` components without touching the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                normalized.pop();
+            }
+            c => normalized.push(c),
+        }
+    }
+    normalized
+}
@@ -0,0 +1,127 @@
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use pax_compiler::helpers::wait_with_timeout;
+use pax_compiler::{BuildError, CancellationToken, LatestBuild};
+
+/// Stands in for a slow build step, e.g. a `cargo build`
+#[cfg(unix)]
+fn slow_step() -> std::process::Child {
+    Command::new("sleep")
+        .arg("30")
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap()
+}
+
+#[cfg(unix)]
+#[test]
+fn cancelling_kills_the_running_step_and_returns_promptly() {
+    let process_child_ids = Arc::new(Mutex::new(vec![]));
+    let cancellation = CancellationToken::new();
+    let canceller = cancellation.clone();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(200));
+        canceller.cancel();
+    });
+
+    let start = Instant::now();
+    let result = wait_with_timeout(
+        &process_child_ids,
+        slow_step(),
+        None,
+        &cancellation,
+        "slow step",
+    );
+    assert!(BuildError::is_cancellation(&result.unwrap_err()));
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert!(process_child_ids.lock().unwrap().is_empty());
+
+    // the follow-up build isn't affected by the cancelled one
+    let output = wait_with_timeout(
+        &process_child_ids,
+        Command::new("echo")
+            .arg("built")
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap(),
+        None,
+        &CancellationToken::new(),
+        "follow-up step",
+    )
+    .unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "built");
+}
+
+#[test]
+fn cancelled_token_fails_the_phase_check() {
+    let cancellation = CancellationToken::new();
+    assert!(cancellation.check().is_ok());
+    cancellation.clone().cancel();
+    let error = cancellation.check().unwrap_err();
+    assert!(BuildError::is_cancellation(&error));
+    assert!(!BuildError::is_cancellation(&color_eyre::eyre::eyre!(
+        "failed to compile project"
+    )));
+}
+
+#[cfg(unix)]
+#[test]
+fn newer_builds_supersede_the_build_in_flight_without_overlapping() {
+    let builds = Arc::new(LatestBuild::new());
+    let concurrent = Arc::new(AtomicUsize::new(0));
+    let max_concurrent = Arc::new(AtomicUsize::new(0));
+
+    let build = |builds: Arc<LatestBuild>,
+                 concurrent: Arc<AtomicUsize>,
+                 max_concurrent: Arc<AtomicUsize>| {
+        builds.run(move |cancellation| {
+            let running = concurrent.fetch_add(1, Ordering::SeqCst) + 1;
+            max_concurrent.fetch_max(running, Ordering::SeqCst);
+            let result = cancellation.check().and_then(|_| {
+                wait_with_timeout(
+                    &Arc::new(Mutex::new(vec![])),
+                    slow_step(),
+                    None,
+                    &cancellation,
+                    "slow step",
+                )
+            });
+            concurrent.fetch_sub(1, Ordering::SeqCst);
+            result
+        })
+    };
+
+    let first = {
+        let (builds, concurrent, max_concurrent) =
+            (builds.clone(), concurrent.clone(), max_concurrent.clone());
+        thread::spawn(move || build(builds, concurrent, max_concurrent))
+    };
+    thread::sleep(Duration::from_millis(200));
+    let second = {
+        let (builds, concurrent, max_concurrent) =
+            (builds.clone(), concurrent.clone(), max_concurrent.clone());
+        thread::spawn(move || build(builds, concurrent, max_concurrent))
+    };
+    thread::sleep(Duration::from_millis(200));
+
+    let start = Instant::now();
+    let third = builds.run(|cancellation| {
+        assert!(!cancellation.is_cancelled());
+        "built"
+    });
+    assert_eq!(third, "built");
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    assert!(BuildError::is_cancellation(
+        &first.join().unwrap().unwrap_err()
+    ));
+    assert!(BuildError::is_cancellation(
+        &second.join().unwrap().unwrap_err()
+    ));
+    assert_eq!(max_concurrent.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,40 @@
+use pax_compiler::BuildError;
+
+#[test]
+fn test_missing_parser_bin_is_recognized_through_color_codes() {
+    let stderr = "\u{1b}[0m\u{1b}[1m\u{1b}[31merror\u{1b}[0m\u{1b}[1m:\u{1b}[0m no bin target named `parser` in default-run packages\n";
+    let error = BuildError::from_parser_stderr(stderr).unwrap();
+    assert_eq!(
+        error,
+        BuildError::ParserTargetMissing {
+            cargo_error: "error: no bin target named `parser` in default-run packages".to_string()
+        }
+    );
+    let message = error.to_string();
+    assert!(message.contains("name = \"parser\""));
+    assert!(message.contains("parser = [\"pax-kit/parser\"]"));
+}
+
+#[test]
+fn test_missing_parser_feature_is_recognized() {
+    for stderr in [
+        "error: Package `app v0.1.0 (/app)` does not have the feature `parser`",
+        "error: none of the selected packages contains these features: parser",
+    ] {
+        assert!(
+            matches!(
+                BuildError::from_parser_stderr(stderr),
+                Some(BuildError::ParserTargetMissing { .. })
+            ),
+            "{}",
+            stderr
+        );
+    }
+}
+
+#[test]
+fn test_other_parser_failures_are_not_recognized() {
+    let stderr =
+        "   Compiling app v0.1.0 (/app)\nerror[E0425]: cannot find value `parser` in this scope\n";
+    assert_eq!(BuildError::from_parser_stderr(stderr), None);
+}
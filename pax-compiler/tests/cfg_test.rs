@@ -0,0 +1,144 @@
+mod common;
+
+use std::collections::BTreeSet;
+
+use common::{main_component, manifest_of, node, primitive, setting};
+use pax_compiler::CfgPass;
+use pax_manifest::cfg::BuildCfg;
+use pax_manifest::{
+    ComponentTemplate, PaxManifest, TemplateNodeDefinition, TypeId, ValueDefinition,
+};
+use pax_runtime_api::PaxValue;
+
+/// A node conditioned on `cfg`, if any
+fn cfg_node(type_id: &TypeId, cfg: Option<&str>) -> TemplateNodeDefinition {
+    let settings = cfg
+        .map(|cfg| {
+            setting(
+                "cfg",
+                ValueDefinition::LiteralValue(PaxValue::String(cfg.to_string())),
+            )
+        })
+        .into_iter()
+        .collect();
+    node(type_id, settings)
+}
+
+fn main_type_id() -> TypeId {
+    TypeId::build_singleton("crate::Main", Some("Main"))
+}
+
+/// `Main` is a group holding a web-only navigation with a child, a native one, and an upsell
+/// behind the `premium` feature
+fn create_manifest(upsell_cfg: &str) -> PaxManifest {
+    let main = main_type_id();
+    let group = TypeId::build_singleton("pax_std::core::group::Group", Some("Group"));
+    let web_nav = TypeId::build_singleton("crate::WebNav", Some("WebNav"));
+    let native_nav = TypeId::build_singleton("crate::NativeNav", Some("NativeNav"));
+    let upsell = TypeId::build_singleton("crate::Upsell", Some("Upsell"));
+
+    let mut template = ComponentTemplate::new(main.clone(), None);
+    let root = template.add(cfg_node(&group, None)).get_template_node_id();
+    let web = template
+        .add_child(root.clone(), cfg_node(&group, Some("target == web")))
+        .get_template_node_id();
+    template.add_child(web, cfg_node(&web_nav, None));
+    template.add_child(root.clone(), cfg_node(&native_nav, Some("target != web")));
+    template.add_child(root, cfg_node(&upsell, Some(upsell_cfg)));
+
+    let mut components = vec![main_component(template)];
+    components.extend([&group, &web_nav, &native_nav, &upsell].map(primitive));
+    manifest_of(&main, components)
+}
+
+fn build_cfg(target: &str, features: &[&str]) -> BuildCfg {
+    BuildCfg {
+        target: target.to_string(),
+        features: features.iter().map(|f| f.to_string()).collect(),
+    }
+}
+
+fn main_template_types(manifest: &PaxManifest) -> BTreeSet<String> {
+    manifest.components[&main_type_id()]
+        .template
+        .as_ref()
+        .unwrap()
+        .get_nodes()
+        .into_iter()
+        .map(|tnd| tnd.type_id.get_pascal_identifier().unwrap())
+        .collect()
+}
+
+#[test]
+fn test_disabled_nodes_are_pruned_with_their_children() {
+    let mut manifest = create_manifest("premium");
+    let report = CfgPass::new(build_cfg("macos", &[]))
+        .run(&mut manifest, true)
+        .unwrap();
+    assert_eq!(report.conditions, 3);
+    // the web group, holding the web navigation, and the upsell
+    assert_eq!(report.pruned_nodes, 2);
+    assert_eq!(
+        report.disabled_features,
+        BTreeSet::from(["premium".to_string()])
+    );
+    assert_eq!(
+        main_template_types(&manifest),
+        BTreeSet::from(["Group".to_string(), "NativeNav".to_string()])
+    );
+    assert_eq!(manifest.build_cfg, Some(build_cfg("macos", &[])));
+}
+
+#[test]
+fn test_enabled_features_keep_nodes() {
+    let mut manifest = create_manifest("premium && target == web");
+    let report = CfgPass::new(build_cfg("web", &["premium"]))
+        .run(&mut manifest, true)
+        .unwrap();
+    assert_eq!(report.pruned_nodes, 1);
+    assert!(report.disabled_features.is_empty());
+    assert_eq!(
+        main_template_types(&manifest),
+        BTreeSet::from([
+            "Group".to_string(),
+            "WebNav".to_string(),
+            "Upsell".to_string()
+        ])
+    );
+}
+
+#[test]
+fn test_nodes_are_kept_without_pruning() {
+    let mut manifest = create_manifest("premium");
+    let report = CfgPass::new(build_cfg("ios", &[]))
+        .run(&mut manifest, false)
+        .unwrap();
+    assert_eq!(report.pruned_nodes, 0);
+    assert_eq!(main_template_types(&manifest).len(), 4);
+
+    // the runtime skips the nodes disabled for the recorded build
+    let build_cfg = manifest.build_cfg.as_ref().unwrap();
+    let template = manifest.components[&main_type_id()]
+        .template
+        .as_ref()
+        .unwrap();
+    let enabled: Vec<_> = template
+        .get_nodes()
+        .into_iter()
+        .filter(|tnd| tnd.is_enabled_for(build_cfg))
+        .map(|tnd| tnd.type_id.get_pascal_identifier().unwrap())
+        .collect();
+    assert!(enabled.contains(&"NativeNav".to_string()));
+    assert!(!enabled.contains(&"Upsell".to_string()));
+}
+
+#[test]
+fn test_malformed_conditions_are_errors() {
+    let mut manifest = create_manifest("target == linux");
+    let error = CfgPass::new(build_cfg("web", &[]))
+        .run(&mut manifest, true)
+        .unwrap_err();
+    assert!(error.contains("crate::Main"), "{}", error);
+    assert!(error.contains("Unknown target `linux`"), "{}", error);
+    assert_eq!(manifest.build_cfg, None);
+}
@@ -0,0 +1,74 @@
+//! Manifest fixtures shared by the integration tests.  Each test crate uses its own subset of them.
+#![allow(dead_code)]
+
+use std::collections::{BTreeMap, HashMap};
+
+use pax_manifest::{
+    ComponentDefinition, ComponentTemplate, PaxManifest, SettingElement, TemplateNodeDefinition,
+    Token, TypeId, ValueDefinition,
+};
+
+pub fn node(type_id: &TypeId, settings: Vec<SettingElement>) -> TemplateNodeDefinition {
+    TemplateNodeDefinition {
+        type_id: type_id.clone(),
+        control_flow_settings: None,
+        settings: Some(settings),
+        raw_comment_string: None,
+        label: None,
+    }
+}
+
+pub fn setting(key: &str, value: ValueDefinition) -> SettingElement {
+    SettingElement::Setting(Token::new_without_location(key.to_string()), value)
+}
+
+/// A userland component, with `template` or, like a component only referenced by type, without
+pub fn component(type_id: &TypeId, template: Option<ComponentTemplate>) -> ComponentDefinition {
+    ComponentDefinition {
+        type_id: type_id.clone(),
+        is_main_component: false,
+        is_primitive: false,
+        is_struct_only_component: false,
+        module_path: "crate".to_string(),
+        primitive_instance_import_path: None,
+        template,
+        settings: None,
+        extends: None,
+    }
+}
+
+/// The main component rendering `template`
+pub fn main_component(template: ComponentTemplate) -> ComponentDefinition {
+    ComponentDefinition {
+        is_main_component: true,
+        ..component(&template.get_containing_component_type_id(), Some(template))
+    }
+}
+
+/// A primitive, which has no template
+pub fn primitive(type_id: &TypeId) -> ComponentDefinition {
+    ComponentDefinition {
+        is_primitive: true,
+        ..component(type_id, None)
+    }
+}
+
+/// A manifest of `components`, rendering `main`, with an empty type table
+pub fn manifest_of(
+    main: &TypeId,
+    components: impl IntoIterator<Item = ComponentDefinition>,
+) -> PaxManifest {
+    PaxManifest {
+        components: components
+            .into_iter()
+            .map(|c| (c.type_id.clone(), c))
+            .collect(),
+        main_component_type_id: main.clone(),
+        type_table: HashMap::new(),
+        assets_dirs: vec![],
+        engine_import_path: "pax_engine".to_string(),
+        defines: BTreeMap::new(),
+        tokens: BTreeMap::new(),
+        build_cfg: None,
+    }
+}
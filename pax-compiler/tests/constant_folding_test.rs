@@ -0,0 +1,186 @@
+mod common;
+
+use common::{main_component, manifest_of, node, setting};
+use pax_compiler::ConstantFoldingPass;
+use pax_lang::parse_pax_expression;
+use pax_manifest::{
+    ComponentTemplate, ExpressionInfo, LiteralBlockDefinition, PaxManifest, SettingElement,
+    SettingsBlockElement, Token, TypeId, ValueDefinition,
+};
+use pax_runtime_api::{Numeric, PaxValue};
+
+fn main_type_id() -> TypeId {
+    TypeId::build_singleton("crate::Main", Some("Main"))
+}
+
+fn expression(expr: &str) -> ValueDefinition {
+    ValueDefinition::Expression(ExpressionInfo::new(parse_pax_expression(expr).unwrap()))
+}
+
+/// `Main` holds a `Rectangle` with `settings`, and styles `.card` with `card_settings`
+fn create_manifest(
+    settings: Vec<SettingElement>,
+    card_settings: Vec<SettingElement>,
+) -> PaxManifest {
+    let main = main_type_id();
+    let rectangle = TypeId::build_singleton("pax_std::Rectangle", Some("Rectangle"));
+    let mut template = ComponentTemplate::new(main.clone(), None);
+    template.add(node(&rectangle, settings));
+
+    let mut main_component = main_component(template);
+    main_component.settings = Some(vec![SettingsBlockElement::SelectorBlock(
+        Token::new_without_location(".card".to_string()),
+        LiteralBlockDefinition::new(card_settings),
+    )]);
+    manifest_of(&main, [main_component])
+}
+
+/// The settings of the `Rectangle` and of `.card`
+fn settings_of(manifest: &PaxManifest) -> (Vec<SettingElement>, Vec<SettingElement>) {
+    let main = &manifest.components[&main_type_id()];
+    let settings = main.template.as_ref().unwrap().get_nodes()[0]
+        .settings
+        .clone()
+        .unwrap();
+    let Some(SettingsBlockElement::SelectorBlock(_, block)) =
+        main.settings.as_ref().unwrap().first()
+    else {
+        panic!("expected the .card selector block");
+    };
+    (settings, block.elements.clone())
+}
+
+fn value<'a>(elements: &'a [SettingElement], key: &str) -> &'a ValueDefinition {
+    elements
+        .iter()
+        .find_map(|e| match e {
+            SettingElement::Setting(token, value) if token.token_value == key => Some(value),
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("no setting {}", key))
+}
+
+fn assert_folded(value: &ValueDefinition, expected: PaxValue) {
+    match value {
+        ValueDefinition::LiteralValue(folded) => assert_eq!(*folded, expected),
+        other => panic!("expected {:?}, found {:?}", expected, other),
+    }
+}
+
+fn assert_not_folded(value: &ValueDefinition) {
+    assert!(
+        matches!(value, ValueDefinition::Expression(_)),
+        "{:?} was folded",
+        value
+    );
+}
+
+#[test]
+fn test_constant_expressions_are_folded() {
+    let mut manifest = create_manifest(
+        vec![
+            setting("x", expression("1 + 2")),
+            setting("opacity", expression("100.0 * 0.5")),
+            setting("visible", expression("!(3 > 4)")),
+        ],
+        vec![setting("radius", expression("4 * 2"))],
+    );
+    let count = ConstantFoldingPass::default().run(&mut manifest);
+
+    assert_eq!(count, 4);
+    let (settings, card_settings) = settings_of(&manifest);
+    assert_folded(value(&settings, "x"), PaxValue::Numeric(Numeric::I64(3)));
+    assert_folded(
+        value(&settings, "opacity"),
+        PaxValue::Numeric(Numeric::F64(50.0)),
+    );
+    assert_folded(value(&settings, "visible"), PaxValue::Bool(true));
+    assert_folded(
+        value(&card_settings, "radius"),
+        PaxValue::Numeric(Numeric::I64(8)),
+    );
+}
+
+#[test]
+fn test_expressions_in_nested_blocks_are_folded() {
+    let mut manifest = create_manifest(
+        vec![setting(
+            "stroke",
+            ValueDefinition::Block(LiteralBlockDefinition::new(vec![setting(
+                "width",
+                expression("2 * 3"),
+            )])),
+        )],
+        vec![],
+    );
+    let count = ConstantFoldingPass::default().run(&mut manifest);
+
+    assert_eq!(count, 1);
+    let (settings, _) = settings_of(&manifest);
+    let ValueDefinition::Block(block) = value(&settings, "stroke") else {
+        panic!("stroke is no longer a block");
+    };
+    assert_folded(
+        value(&block.elements, "width"),
+        PaxValue::Numeric(Numeric::I64(6)),
+    );
+}
+
+#[test]
+fn test_symbol_dependent_expressions_and_function_calls_are_left_alone() {
+    let mut manifest = create_manifest(
+        vec![
+            setting("x", expression("self.offset + 1")),
+            setting("y", expression("$defines.margin * 2")),
+            setting("fill", expression("rgb(10, 20, 30)")),
+            setting(
+                "stroke",
+                ValueDefinition::LiteralValue(PaxValue::String("red".to_string())),
+            ),
+        ],
+        vec![setting("radius", expression("corner_radius"))],
+    );
+    let count = ConstantFoldingPass::default().run(&mut manifest);
+
+    assert_eq!(count, 0);
+    let (settings, card_settings) = settings_of(&manifest);
+    assert_not_folded(value(&settings, "x"));
+    assert_not_folded(value(&settings, "y"));
+    assert_not_folded(value(&settings, "fill"));
+    assert_folded(
+        value(&settings, "stroke"),
+        PaxValue::String("red".to_string()),
+    );
+    assert_not_folded(value(&card_settings, "radius"));
+}
+
+#[test]
+fn test_overflowing_and_dividing_by_zero_fold_to_floats() {
+    let mut manifest = create_manifest(
+        vec![
+            setting("width", expression("9223372036854775807 * 2")),
+            setting("height", expression("1 / 0")),
+            setting("x", expression("2 ^ 64")),
+            setting("y", expression("2 ^ -1")),
+        ],
+        vec![],
+    );
+    let count = ConstantFoldingPass::default().run(&mut manifest);
+
+    assert_eq!(count, 4);
+    let (settings, _) = settings_of(&manifest);
+    assert_folded(
+        value(&settings, "width"),
+        PaxValue::Numeric(Numeric::F64(9223372036854775807.0 * 2.0)),
+    );
+    // infinity doesn't compare equal to itself as a `PaxValue`
+    assert!(matches!(
+        value(&settings, "height"),
+        ValueDefinition::LiteralValue(PaxValue::Numeric(Numeric::F64(f))) if *f == f64::INFINITY
+    ));
+    assert_folded(
+        value(&settings, "x"),
+        PaxValue::Numeric(Numeric::F64(2f64.powi(64))),
+    );
+    assert_folded(value(&settings, "y"), PaxValue::Numeric(Numeric::F64(0.5)));
+}
@@ -0,0 +1,11 @@
+#![allow(unused_imports)]
+use pax_kit::*;
+
+#[pax]
+#[main]
+#[inlined(
+    <Text text={greeting} x=50% y=50% width=200px height=40px/>
+)]
+pub struct MyApp {
+    pub greeting: Property<String>,
+}
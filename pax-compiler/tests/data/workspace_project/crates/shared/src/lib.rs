@@ -0,0 +1,3 @@
+pub fn greeting() -> String {
+    "Hello from a workspace".to_string()
+}
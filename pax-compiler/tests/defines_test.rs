@@ -0,0 +1,164 @@
+mod common;
+
+use std::collections::BTreeMap;
+
+use common::{main_component, manifest_of, node, setting};
+use pax_compiler::{parse_define, DefinesPass};
+use pax_lang::parse_pax_expression;
+use pax_manifest::{
+    ComponentTemplate, ControlFlowSettingsDefinition, ExpressionInfo, PaxManifest,
+    TemplateNodeDefinition, TypeId, ValueDefinition,
+};
+use pax_runtime_api::{Numeric, PaxValue};
+
+fn if_node(condition: &str) -> TemplateNodeDefinition {
+    TemplateNodeDefinition {
+        type_id: TypeId::build_if(),
+        control_flow_settings: Some(ControlFlowSettingsDefinition {
+            condition_expression: Some(expression(condition)),
+            ..Default::default()
+        }),
+        settings: None,
+        raw_comment_string: None,
+        label: None,
+    }
+}
+
+fn expression(expr: &str) -> ExpressionInfo {
+    ExpressionInfo::new(parse_pax_expression(expr).unwrap())
+}
+
+/// `Main` is a group holding an `if` for each navigation variant, the width of the group
+/// depending on `$defines.nav_width`
+fn create_manifest() -> PaxManifest {
+    let main = TypeId::build_singleton("crate::Main", Some("Main"));
+    let group = TypeId::build_singleton("pax_std::core::group::Group", Some("Group"));
+    let new_nav = TypeId::build_singleton("crate::NewNav", Some("NewNav"));
+    let old_nav = TypeId::build_singleton("crate::OldNav", Some("OldNav"));
+
+    let mut template = ComponentTemplate::new(main.clone(), None);
+    let root = template
+        .add(node(
+            &group,
+            vec![setting(
+                "width",
+                ValueDefinition::Expression(expression("$defines.nav_width + 10")),
+            )],
+        ))
+        .get_template_node_id();
+    let new_branch = template
+        .add_child(root.clone(), if_node("$defines.new_nav"))
+        .get_template_node_id();
+    template.add_child(new_branch, node(&new_nav, vec![]));
+    let old_branch = template
+        .add_child(root, if_node("!$defines.new_nav"))
+        .get_template_node_id();
+    template.add_child(old_branch, node(&old_nav, vec![]));
+
+    manifest_of(&main, [main_component(template)])
+}
+
+fn defines(new_nav: bool) -> BTreeMap<String, PaxValue> {
+    [
+        format!("new_nav={}", new_nav),
+        "nav_width=90".to_string(),
+        "unused=\"true\"".to_string(),
+    ]
+    .iter()
+    .map(|define| parse_define(define).unwrap())
+    .collect()
+}
+
+fn node_names(manifest: &PaxManifest) -> Vec<String> {
+    let template = manifest.components[&manifest.main_component_type_id]
+        .template
+        .as_ref()
+        .unwrap();
+    let mut names: Vec<_> = template
+        .get_nodes()
+        .iter()
+        .map(|tnd| tnd.type_id.get_pascal_identifier().unwrap_or_default())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_parse_define() {
+    assert_eq!(parse_define("a=true").unwrap().1, PaxValue::Bool(true));
+    assert_eq!(
+        parse_define("a = 12").unwrap().1,
+        PaxValue::Numeric(Numeric::I64(12))
+    );
+    assert_eq!(
+        parse_define("a=0.5").unwrap().1,
+        PaxValue::Numeric(Numeric::F64(0.5))
+    );
+    assert_eq!(
+        parse_define("a='true'").unwrap().1,
+        PaxValue::String("true".to_string())
+    );
+    assert_eq!(
+        parse_define("a=blue").unwrap().1,
+        PaxValue::String("blue".to_string())
+    );
+    assert!(parse_define("a").is_err());
+    assert!(parse_define("a-b=1").is_err());
+}
+
+#[test]
+fn test_only_the_selected_branch_is_kept() {
+    for (new_nav, kept, dropped) in [(true, "NewNav", "OldNav"), (false, "OldNav", "NewNav")] {
+        let mut manifest = create_manifest();
+        let defines = defines(new_nav);
+        let report = DefinesPass::new(&defines).run(&mut manifest, true).unwrap();
+        assert_eq!((report.pruned_branches, report.inlined_branches), (1, 1));
+
+        let names = node_names(&manifest);
+        assert_eq!(names, vec!["Group".to_string(), kept.to_string()]);
+        assert!(!names.contains(&dropped.to_string()));
+
+        // the kept branch's content takes the place of its `if`
+        let template = manifest.components[&manifest.main_component_type_id]
+            .template
+            .as_ref()
+            .unwrap();
+        let root = template.get_root()[0].clone();
+        assert_eq!(template.get_children(&root).unwrap().len(), 1);
+
+        assert_eq!(
+            manifest.defines.keys().collect::<Vec<_>>(),
+            vec!["nav_width", "new_nav"]
+        );
+        assert!(report.unused.contains("unused"));
+    }
+}
+
+#[test]
+fn test_designer_builds_keep_defines() {
+    let mut manifest = create_manifest();
+    let defines = defines(true);
+    let report = DefinesPass::new(&defines)
+        .run(&mut manifest, false)
+        .unwrap();
+    assert_eq!((report.pruned_branches, report.inlined_branches), (0, 0));
+    assert_eq!(
+        node_names(&manifest),
+        vec!["Group", "If", "If", "NewNav", "OldNav"]
+    );
+    assert_eq!(manifest.defines.len(), 2);
+}
+
+#[test]
+fn test_undefined_define_is_an_error() {
+    let mut manifest = create_manifest();
+    let defines: BTreeMap<_, _> = [parse_define("new_nav=true").unwrap()]
+        .into_iter()
+        .collect();
+    let err = DefinesPass::new(&defines)
+        .run(&mut manifest, true)
+        .err()
+        .unwrap();
+    assert!(err.contains("`$defines.nav_width` is not defined"));
+    assert!(err.contains("available defines: new_nav"));
+}
@@ -7,8 +7,8 @@ use std::{
 use actix_web::{web::Data, App};
 use pax_compiler::design_server::{web_socket, AppState};
 use pax_manifest::{
-    ComponentDefinition, ComponentTemplate, LiteralBlockDefinition, PaxManifest,
-    SettingsBlockElement, TemplateNodeDefinition, Token, TypeId,
+    validation::UNRESOLVED_TYPE, ComponentDefinition, ComponentTemplate, LiteralBlockDefinition,
+    PaxManifest, SettingsBlockElement, TemplateNodeDefinition, Token, TypeId,
 };
 
 pub fn get_test_server() -> actix_test::TestServer {
@@ -27,10 +27,11 @@ fn create_basic_manifest(source_path: String) -> PaxManifest {
     let mut template = ComponentTemplate::new(component_type_id.clone(), Some(source_path));
 
     template.add(TemplateNodeDefinition {
-        type_id: special_component_type_id,
+        type_id: special_component_type_id.clone(),
         control_flow_settings: None,
         settings: None,
         raw_comment_string: None,
+        label: None,
     });
 
     // .add puts the node on the top of the template by default in line with designing
@@ -39,6 +40,7 @@ fn create_basic_manifest(source_path: String) -> PaxManifest {
         control_flow_settings: None,
         settings: None,
         raw_comment_string: Some("// Hello world\n".to_owned()),
+        label: None,
     });
 
     components.insert(
@@ -61,6 +63,23 @@ fn create_basic_manifest(source_path: String) -> PaxManifest {
                     vec![Token::new_without_location("handler_action".to_string())],
                 ),
             ]),
+            extends: None,
+        },
+    );
+
+    // the designtime only sends components whose nodes resolve to components of the manifest
+    components.insert(
+        special_component_type_id.clone(),
+        ComponentDefinition {
+            type_id: special_component_type_id.clone(),
+            is_main_component: false,
+            is_primitive: false,
+            is_struct_only_component: false,
+            module_path: "module_path1".to_string(),
+            primitive_instance_import_path: None,
+            template: None,
+            settings: None,
+            extends: None,
         },
     );
 
@@ -70,6 +89,9 @@ fn create_basic_manifest(source_path: String) -> PaxManifest {
         type_table: HashMap::new(),
         assets_dirs: vec![],
         engine_import_path: "".to_string(),
+        defines: BTreeMap::new(),
+        tokens: BTreeMap::new(),
+        build_cfg: None,
     }
 }
 
@@ -95,6 +117,7 @@ async fn designtime_integration_test() {
     let manifest: PaxManifest = create_basic_manifest(path_str.to_owned());
     let mut designer = pax_designtime::DesigntimeManager::new_with_addr(manifest, srv.addr());
     designer.send_component_update(&component_type_id).unwrap();
+    designer.finish_jobs();
 
     std::thread::sleep(Duration::from_secs(1));
 
@@ -103,3 +126,25 @@ async fn designtime_integration_test() {
     std::fs::write(path_str, b"FILE HAS NOT BEEN UPDATED BY DESIGNTIME")
         .expect("couldn't reset file");
 }
+
+#[actix_web::test]
+async fn designtime_doesnt_send_components_that_would_fail_to_build() {
+    let component_type_id = TypeId::build_singleton("Component1", Some("Component1"));
+    let special_component_type_id =
+        TypeId::build_singleton("SpecialComponent", Some("SpecialComponent"));
+    let srv = get_test_server();
+
+    let mut manifest = create_basic_manifest("unused.pax".to_owned());
+    manifest.components.remove(&special_component_type_id);
+    let mut designer = pax_designtime::DesigntimeManager::new_with_addr(manifest, srv.addr());
+
+    let errors = designer.validate_before_send(&component_type_id).unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].rule, UNRESOLVED_TYPE);
+    assert_eq!(errors[0].node_name, "SpecialComponent");
+
+    let err = designer
+        .send_component_update(&component_type_id)
+        .unwrap_err();
+    assert!(err.to_string().contains("didn't save `Component1`"));
+}
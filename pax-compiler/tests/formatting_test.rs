@@ -0,0 +1,143 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use pax_compiler::formatting::{
+    discover_pax_files, format_pax_document, format_project, FileFormatStatus, FormatOptions,
+};
+
+/// Each file in `input` formats to the file of the same name in `expected`.  Run with
+/// `PAX_UPDATE_GOLDENS=1` to rewrite `expected` from the current formatter, then review the diff
+const GOLDENS: &str = "tests/data/formatting";
+const UPDATE_GOLDENS_ENV: &str = "PAX_UPDATE_GOLDENS";
+
+fn golden_inputs() -> Vec<PathBuf> {
+    let mut inputs: Vec<_> = fs::read_dir(Path::new(GOLDENS).join("input"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "pax"))
+        .collect();
+    inputs.sort();
+    inputs
+}
+
+#[test]
+fn test_golden_files() {
+    let update = std::env::var(UPDATE_GOLDENS_ENV).is_ok();
+    let mut mismatches = vec![];
+    for input in golden_inputs() {
+        let expected_path = Path::new(GOLDENS)
+            .join("expected")
+            .join(input.file_name().unwrap());
+        let formatted = format_pax_document(&fs::read_to_string(&input).unwrap())
+            .unwrap_or_else(|e| panic!("failed to format {:?}: {}", input, e));
+        if update {
+            fs::write(&expected_path, &formatted).unwrap();
+            continue;
+        }
+        let expected = fs::read_to_string(&expected_path).unwrap_or_default();
+        if formatted != expected {
+            mismatches.push(format!("{:?}:\n{}", input, formatted));
+        }
+        // formatting formatted code changes nothing
+        assert_eq!(
+            format_pax_document(&expected).unwrap(),
+            expected,
+            "{:?} isn't stable under formatting",
+            expected_path
+        );
+    }
+    assert!(
+        mismatches.is_empty(),
+        "formatting changed, rerun with {}=1 if intended:\n{}",
+        UPDATE_GOLDENS_ENV,
+        mismatches.join("\n")
+    );
+}
+
+/// A project with a formatted file, an unformatted one, one that doesn't parse, and unformatted
+/// files that are ignored or in `.pax`
+fn create_project() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    fs::create_dir_all(root.join("src/nested")).unwrap();
+    fs::create_dir_all(root.join("generated")).unwrap();
+    fs::create_dir_all(root.join(".pax/pkg")).unwrap();
+    fs::write(root.join(".gitignore"), "generated/\n").unwrap();
+    fs::write(root.join("src/formatted.pax"), "<Rectangle fill=RED/>\n").unwrap();
+    fs::write(
+        root.join("src/nested/messy.pax"),
+        "<Group>\n<Text   text=\"hi\"/>\n</Group>",
+    )
+    .unwrap();
+    fs::write(root.join("src/broken.pax"), "<Group>").unwrap();
+    fs::write(root.join("generated/messy.pax"), "<Text   text=\"hi\"/>").unwrap();
+    fs::write(root.join(".pax/pkg/messy.pax"), "<Text   text=\"hi\"/>").unwrap();
+    dir
+}
+
+fn relative_paths(root: &Path, paths: impl Iterator<Item = PathBuf>) -> Vec<String> {
+    paths
+        .map(|path| {
+            path.strip_prefix(root)
+                .unwrap()
+                .to_string_lossy()
+                .to_string()
+        })
+        .collect()
+}
+
+#[test]
+fn test_discovery_skips_ignored_and_hidden_files() {
+    let project = create_project();
+    let root = project.path();
+    assert_eq!(
+        relative_paths(root, discover_pax_files(root).into_iter()),
+        vec![
+            "src/broken.pax",
+            "src/formatted.pax",
+            "src/nested/messy.pax"
+        ]
+    );
+}
+
+#[test]
+fn test_check_reports_without_writing() {
+    let project = create_project();
+    let root = project.path();
+    let report = format_project(root, &FormatOptions { check: true });
+
+    assert_eq!(
+        relative_paths(root, report.changed().map(|file| file.path.clone())),
+        vec!["src/nested/messy.pax"]
+    );
+    assert_eq!(
+        relative_paths(root, report.errors().map(|file| file.path.clone())),
+        vec!["src/broken.pax"]
+    );
+    let FileFormatStatus::Changed(diff) = &report.changed().next().unwrap().status else {
+        unreachable!()
+    };
+    assert!(diff.contains("--- a/src/nested/messy.pax"));
+    assert!(diff.contains("+    <Text text=\"hi\"/>"));
+    assert_eq!(
+        fs::read_to_string(root.join("src/nested/messy.pax")).unwrap(),
+        "<Group>\n<Text   text=\"hi\"/>\n</Group>"
+    );
+}
+
+#[test]
+fn test_format_writes_changes() {
+    let project = create_project();
+    let root = project.path();
+    let report = format_project(root, &FormatOptions::default());
+    assert_eq!(report.changed().count(), 1);
+    assert_eq!(
+        fs::read_to_string(root.join("src/nested/messy.pax")).unwrap(),
+        "<Group>\n    <Text text=\"hi\"/>\n</Group>\n"
+    );
+    // no temporary files are left behind
+    assert_eq!(fs::read_dir(root.join("src/nested")).unwrap().count(), 1);
+
+    let report = format_project(root, &FormatOptions { check: true });
+    assert_eq!(report.changed().count(), 0);
+}
@@ -0,0 +1,85 @@
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use pax_compiler::helpers::{sync_dir_recursively, sync_embedded_dir, PAX_WEB_INTERFACE_TEMPLATE};
+use tempfile::TempDir;
+
+fn modified(path: &Path) -> SystemTime {
+    fs::metadata(path).unwrap().modified().unwrap()
+}
+
+fn set_modified(path: &Path, time: SystemTime) {
+    fs::File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(time)
+        .unwrap();
+}
+
+#[test]
+fn test_sync_dir_only_copies_changed_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    let dest = temp_dir.path().join("dest");
+    fs::create_dir_all(src.join("nested")).unwrap();
+    fs::write(src.join("index.html"), "<html></html>").unwrap();
+    fs::write(src.join("nested/app.js"), "console.log(1)").unwrap();
+    fs::write(src.join("unchanged.css"), "body {}").unwrap();
+
+    sync_dir_recursively(&src, &dest).unwrap();
+    assert_eq!(
+        fs::read_to_string(dest.join("nested/app.js")).unwrap(),
+        "console.log(1)"
+    );
+    let unchanged_modified = modified(&dest.join("unchanged.css"));
+
+    // changed size
+    fs::write(src.join("index.html"), "<html><body></body></html>").unwrap();
+    // same size, but modified after it was copied
+    fs::write(src.join("nested/app.js"), "console.log(2)").unwrap();
+    let later = modified(&dest.join("nested/app.js")) + Duration::from_secs(60);
+    set_modified(&src.join("nested/app.js"), later);
+    // no longer in the source
+    fs::create_dir_all(dest.join("stale_dir")).unwrap();
+    fs::write(dest.join("stale_dir/old.js"), "").unwrap();
+    fs::write(dest.join("stale.txt"), "").unwrap();
+
+    sync_dir_recursively(&src, &dest).unwrap();
+    assert_eq!(
+        fs::read_to_string(dest.join("index.html")).unwrap(),
+        "<html><body></body></html>"
+    );
+    assert_eq!(
+        fs::read_to_string(dest.join("nested/app.js")).unwrap(),
+        "console.log(2)"
+    );
+    assert_eq!(modified(&dest.join("unchanged.css")), unchanged_modified);
+    assert!(!dest.join("stale_dir").exists());
+    assert!(!dest.join("stale.txt").exists());
+}
+
+#[test]
+fn test_sync_embedded_dir_compares_contents() {
+    let temp_dir = TempDir::new().unwrap();
+    let dest = temp_dir.path().join("interface");
+
+    sync_embedded_dir(&PAX_WEB_INTERFACE_TEMPLATE, &dest).unwrap();
+    let index = dest.join("index.html");
+    let expected = PAX_WEB_INTERFACE_TEMPLATE
+        .get_file("index.html")
+        .unwrap()
+        .contents();
+    assert_eq!(fs::read(&index).unwrap(), expected);
+    set_modified(&index, SystemTime::UNIX_EPOCH);
+    fs::write(dest.join("stale.js"), "").unwrap();
+
+    sync_embedded_dir(&PAX_WEB_INTERFACE_TEMPLATE, &dest).unwrap();
+    assert_eq!(modified(&index), SystemTime::UNIX_EPOCH);
+    assert!(!dest.join("stale.js").exists());
+
+    fs::write(&index, "edited").unwrap();
+    sync_embedded_dir(&PAX_WEB_INTERFACE_TEMPLATE, &dest).unwrap();
+    assert_eq!(fs::read(&index).unwrap(), expected);
+}
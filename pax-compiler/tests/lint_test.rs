@@ -0,0 +1,126 @@
+mod common;
+
+use std::str::FromStr;
+
+use common::{component, main_component, manifest_of, node, setting};
+use pax_compiler::{
+    pax_lint, LintConfig, LintSeverity, MaxTemplateDepth, NoDeprecatedTypes, NoEmptyComponents,
+    PaxLintRule, RequireAccessibilityLabel,
+};
+use pax_manifest::{ComponentTemplate, PaxManifest, TypeId, ValueDefinition};
+use pax_runtime_api::PaxValue;
+use toml_edit::Document;
+
+/// `Main` has a chain of three nested groups and two buttons, one of them labeled, and `Empty`
+/// has an empty template
+fn create_manifest() -> PaxManifest {
+    let main = TypeId::build_singleton("crate::Main", Some("Main"));
+    let empty = TypeId::build_singleton("crate::Empty", Some("Empty"));
+    let group = TypeId::build_singleton("pax_std::primitives::Group", Some("Group"));
+    let button = TypeId::build_singleton("pax_std::forms::button::Button", Some("Button"));
+
+    let mut template = ComponentTemplate::new(main.clone(), None);
+    let outer = template.add(node(&group, vec![])).get_template_node_id();
+    let middle = template
+        .add_child(outer.clone(), node(&group, vec![]))
+        .get_template_node_id();
+    template.add_child(middle.clone(), node(&group, vec![]));
+    template.add_child(
+        middle,
+        node(
+            &button,
+            vec![setting(
+                "accessibility_label",
+                ValueDefinition::LiteralValue(PaxValue::String("Save".to_string())),
+            )],
+        ),
+    );
+    template.add_child(outer, node(&button, vec![]));
+
+    manifest_of(
+        &main,
+        [
+            main_component(template),
+            component(&empty, Some(ComponentTemplate::new(empty.clone(), None))),
+        ],
+    )
+}
+
+fn rules_of(diagnostics: &[pax_compiler::LintDiagnostic]) -> Vec<&str> {
+    diagnostics.iter().map(|d| d.rule.as_str()).collect()
+}
+
+#[test]
+fn test_builtin_rules() {
+    let manifest = create_manifest();
+
+    let deprecated = pax_lint(&manifest, &[Box::new(NoDeprecatedTypes)]);
+    assert_eq!(deprecated.len(), 3);
+
+    let depth = pax_lint(&manifest, &[Box::new(MaxTemplateDepth(2))]);
+    // the innermost group and the labeled button are at depth 3
+    assert_eq!(depth.len(), 2);
+    assert!(pax_lint(&manifest, &[Box::new(MaxTemplateDepth(3))]).is_empty());
+
+    let empty = pax_lint(&manifest, &[Box::new(NoEmptyComponents)]);
+    assert_eq!(empty.len(), 1);
+    assert!(empty[0].message.contains("`Empty`"));
+
+    let accessibility = pax_lint(&manifest, &[Box::new(RequireAccessibilityLabel)]);
+    assert_eq!(accessibility.len(), 1);
+    assert_eq!(accessibility[0].severity, LintSeverity::Error);
+}
+
+#[test]
+fn test_rules_run_in_order() {
+    let manifest = create_manifest();
+    let rules: Vec<Box<dyn PaxLintRule>> = vec![
+        Box::new(NoEmptyComponents),
+        Box::new(RequireAccessibilityLabel),
+    ];
+    assert_eq!(
+        rules_of(&pax_lint(&manifest, &rules)),
+        vec!["no_empty_components", "require_accessibility_label"]
+    );
+}
+
+#[test]
+fn test_lint_config() {
+    let rule_names = |toml: &str| -> Vec<String> {
+        let config = LintConfig::from_document(&Document::from_str(toml).unwrap()).unwrap();
+        config
+            .rules()
+            .unwrap()
+            .iter()
+            .map(|rule| rule.name().to_string())
+            .collect()
+    };
+
+    // accessibility labels are opt-in
+    assert_eq!(
+        rule_names(""),
+        vec![
+            "no_deprecated_types",
+            "max_template_depth",
+            "no_empty_components"
+        ]
+    );
+    assert_eq!(
+        rule_names("[lint]\nrules = [\"require_accessibility_label\"]"),
+        vec!["require_accessibility_label"]
+    );
+
+    let config = LintConfig::from_document(
+        &Document::from_str("[lint]\nrules = [\"max_template_depth\"]\nmax_template_depth = 2")
+            .unwrap(),
+    )
+    .unwrap();
+    let diagnostics = pax_lint(&create_manifest(), &config.rules().unwrap());
+    assert_eq!(diagnostics.len(), 2);
+
+    let unknown = LintConfig::from_document(
+        &Document::from_str("[lint]\nrules = [\"no_such_rule\"]").unwrap(),
+    )
+    .unwrap();
+    assert!(unknown.rules().is_err());
+}
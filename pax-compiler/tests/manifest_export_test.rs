@@ -0,0 +1,182 @@
+mod common;
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use common::{component, main_component, manifest_of, node, primitive};
+use pax_compiler::manifest_export::{
+    dependency_graph, userland_variant, userland_variant_path, ManifestExport, ManifestVariant,
+    MANIFEST_SCHEMA_VERSION,
+};
+use pax_compiler::{LintDiagnostic, LintSeverity, PaxSourceLocation};
+use pax_manifest::{ComponentTemplate, PaxManifest, TypeId};
+use pax_runtime_api::PaxValue;
+use serde_json::Value;
+
+fn main_type_id() -> TypeId {
+    TypeId::build_singleton("crate::Main", Some("Main"))
+}
+
+fn rectangle_type_id() -> TypeId {
+    TypeId::build_singleton("pax_std::drawing::rectangle::Rectangle", Some("Rectangle"))
+}
+
+fn designer_type_id() -> TypeId {
+    TypeId::build_singleton("pax_designer::Glass", Some("Glass"))
+}
+
+/// `Main` holds a `Group` with a `Rectangle` in it
+fn create_manifest() -> PaxManifest {
+    let main = main_type_id();
+    let group = TypeId::build_singleton("pax_std::core::group::Group", Some("Group"));
+    let rectangle = rectangle_type_id();
+
+    let mut template = ComponentTemplate::new(main.clone(), None);
+    let root = template.add(node(&group, vec![])).get_template_node_id();
+    template.add_child(root, node(&rectangle, vec![]));
+
+    manifest_of(
+        &main,
+        [
+            main_component(template),
+            primitive(&group),
+            primitive(&rectangle),
+        ],
+    )
+}
+
+/// A manifest with the designer's `Glass`, which holds a `Rectangle`
+fn create_designer_manifest() -> PaxManifest {
+    let glass = designer_type_id();
+    let rectangle = rectangle_type_id();
+    let mut template = ComponentTemplate::new(glass.clone(), None);
+    template.add(node(&rectangle, vec![]));
+    let mut manifest = create_manifest();
+    manifest.components = BTreeMap::from([
+        (glass.clone(), component(&glass, Some(template))),
+        (rectangle.clone(), primitive(&rectangle)),
+    ]);
+    manifest
+}
+
+fn diagnostic(file: &str, line: usize, rule: &str) -> LintDiagnostic {
+    LintDiagnostic {
+        rule: rule.to_string(),
+        severity: LintSeverity::Warning,
+        message: format!("{} at line {}", rule, line),
+        location: PaxSourceLocation {
+            file: file.to_string(),
+            line_col: Some((line, 0)),
+            description: "template".to_string(),
+        },
+    }
+}
+
+fn export_json(manifest: &PaxManifest, diagnostics: &[LintDiagnostic]) -> String {
+    let defines = BTreeMap::from([("new_nav".to_string(), PaxValue::Bool(true))]);
+    ManifestExport::new(ManifestVariant::Merged, manifest, &defines, diagnostics)
+        .to_canonical_json()
+}
+
+#[test]
+fn test_export_is_wrapped_in_a_versioned_envelope() {
+    let json: Value = serde_json::from_str(&export_json(&create_manifest(), &[])).unwrap();
+    assert_eq!(json["schema_version"], MANIFEST_SCHEMA_VERSION);
+    assert_eq!(json["variant"], "merged");
+    assert_eq!(
+        json["defines"]["new_nav"],
+        serde_json::json!({"Bool": true})
+    );
+    let manifest: PaxManifest = serde_json::from_value(json["manifest"].clone()).unwrap();
+    assert_eq!(manifest.main_component_type_id, main_type_id());
+    assert_eq!(manifest.components.len(), 3);
+}
+
+#[test]
+fn test_export_is_deterministic() {
+    let mut manifest = create_manifest();
+    manifest.type_table = (0..20)
+        .map(|i| {
+            let type_id = TypeId::build_singleton(&format!("crate::T{}", i), None);
+            (
+                type_id.clone(),
+                pax_manifest::TypeDefinition::primitive(&format!("T{}", i)),
+            )
+        })
+        .collect();
+    let diagnostics = [
+        diagnostic("b.pax", 1, "no-empty-components"),
+        diagnostic("a.pax", 7, "unused-component"),
+        diagnostic("a.pax", 2, "unused-component"),
+    ];
+    let mut reversed = diagnostics.clone();
+    reversed.reverse();
+
+    let first = export_json(&manifest, &diagnostics);
+    // a clone rebuilds the hash maps of the type table, likely in another order
+    assert_eq!(first, export_json(&manifest.clone(), &reversed));
+
+    let json: Value = serde_json::from_str(&first).unwrap();
+    let files: Vec<(String, u64)> = json["diagnostics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| {
+            (
+                d["location"]["file"].as_str().unwrap().to_string(),
+                d["location"]["line_col"][0].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        files,
+        vec![
+            ("a.pax".to_string(), 2),
+            ("a.pax".to_string(), 7),
+            ("b.pax".to_string(), 1)
+        ]
+    );
+    assert_eq!(json["diagnostics"][0]["severity"], "warning");
+}
+
+#[test]
+fn test_dependency_graph_lists_components_used_in_templates() {
+    let graph = dependency_graph(&create_manifest());
+    assert_eq!(graph.len(), 1);
+    let used: Vec<&String> = graph[&main_type_id().to_string()].iter().collect();
+    assert_eq!(
+        used,
+        vec![
+            &TypeId::build_singleton("pax_std::core::group::Group", Some("Group")).to_string(),
+            &rectangle_type_id().to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_userland_variant_leaves_out_designer_components() {
+    let userland = create_manifest();
+    let userland_type_ids: HashSet<TypeId> = userland.components.keys().cloned().collect();
+    let mut merged = userland.clone();
+    merged
+        .components
+        .extend(create_designer_manifest().components);
+
+    let variant = userland_variant(&merged, &userland_type_ids);
+    assert!(!variant.components.contains_key(&designer_type_id()));
+    // used by both the designer and userland
+    assert!(variant.components.contains_key(&rectangle_type_id()));
+    assert_eq!(variant.components.len(), 3);
+}
+
+#[test]
+fn test_userland_variant_path() {
+    assert_eq!(
+        userland_variant_path(Path::new("target/manifest.json")),
+        PathBuf::from("target/manifest.userland.json")
+    );
+    assert_eq!(
+        userland_variant_path(Path::new("manifest")),
+        PathBuf::from("manifest.userland")
+    );
+}
@@ -44,6 +44,7 @@ fn create_basic_manifest(source_path: String) -> PaxManifest {
         control_flow_settings: None,
         settings: None,
         raw_comment_string: None,
+        label: None,
     });
 
     // .add puts the node on the top of the template by default in line with designing
@@ -52,6 +53,7 @@ fn create_basic_manifest(source_path: String) -> PaxManifest {
         control_flow_settings: None,
         settings: None,
         raw_comment_string: Some("// Hello world\n".to_owned()),
+        label: None,
     });
 
     components.insert(
@@ -74,6 +76,7 @@ fn create_basic_manifest(source_path: String) -> PaxManifest {
                     vec![Token::new_without_location("handler_action".to_string())],
                 ),
             ]),
+            extends: None,
         },
     );
 
@@ -83,6 +86,9 @@ fn create_basic_manifest(source_path: String) -> PaxManifest {
         type_table: HashMap::new(),
         assets_dirs: vec![],
         engine_import_path: "".to_string(),
+        defines: BTreeMap::new(),
+        tokens: BTreeMap::new(),
+        build_cfg: None,
     }
 }
 
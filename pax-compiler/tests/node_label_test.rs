@@ -0,0 +1,44 @@
+mod common;
+
+use std::collections::HashMap;
+
+use common::{main_component, node};
+use pax_compiler::design_server::code_serialization::press_code_serialization_template;
+use pax_manifest::parsing::{assemble_component_definition, ParsingContext};
+use pax_manifest::{ComponentTemplate, TemplateNodeDefinition, TypeId};
+
+fn rectangle_type_id() -> TypeId {
+    TypeId::build_singleton("pax_std::Rectangle", Some("Rectangle"))
+}
+
+#[test]
+fn test_node_labels_round_trip() {
+    let type_id = TypeId::build_singleton("crate::Main", Some("Main"));
+    let mut template = ComponentTemplate::new(type_id.clone(), None);
+    template.add(TemplateNodeDefinition {
+        label: Some("CTA Button".to_string()),
+        ..node(&rectangle_type_id(), vec![])
+    });
+
+    let pax = press_code_serialization_template(main_component(template));
+    assert!(
+        pax.contains(r#"<Rectangle designer_label="CTA Button"/>"#),
+        "{}",
+        pax
+    );
+
+    let template_map = HashMap::from([("Rectangle".to_string(), rectangle_type_id())]);
+    let (_, parsed) = assemble_component_definition(
+        ParsingContext::default(),
+        &pax,
+        true,
+        template_map,
+        "crate",
+        type_id,
+        "main.pax",
+    );
+    let template = parsed.template.unwrap();
+    let node = template.get_node(&template.get_root()[0]).unwrap();
+    assert_eq!(node.label.as_deref(), Some("CTA Button"));
+    assert_eq!(node.settings.as_ref().map(Vec::len), Some(0));
+}
@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use pax_compiler::progress::format_duration;
+use pax_compiler::{Phase, ProgressMode, ProgressReporter};
+
+#[test]
+fn progress_mode_parses_its_lowercase_names() {
+    assert_eq!(
+        "interactive".parse::<ProgressMode>().unwrap(),
+        ProgressMode::Interactive
+    );
+    assert_eq!(
+        "Plain".parse::<ProgressMode>().unwrap(),
+        ProgressMode::Plain
+    );
+    assert_eq!(
+        "quiet".parse::<ProgressMode>().unwrap(),
+        ProgressMode::Quiet
+    );
+    assert!("fancy".parse::<ProgressMode>().is_err());
+}
+
+#[test]
+fn phases_are_timed_and_fail_when_their_guard_is_dropped() {
+    let progress = ProgressReporter::new(ProgressMode::Quiet);
+    progress.start(Phase::Parse).done();
+    {
+        let _phase = progress.start(Phase::ChassisBuild);
+        // e.g. an early return on a failed `cargo build`
+    }
+
+    let timings = progress.timings();
+    assert_eq!(timings.len(), 2);
+    assert_eq!(timings[0].phase, Phase::Parse);
+    assert!(timings[0].succeeded);
+    assert_eq!(timings[1].phase, Phase::ChassisBuild);
+    assert!(!timings[1].succeeded);
+
+    let summary = progress.summary();
+    assert!(summary.contains("parse"));
+    assert!(summary.contains("chassis build"));
+    assert!(summary.contains("(failed)"));
+    assert!(summary.contains("total"));
+}
+
+#[test]
+fn build_report_records_the_timings_printed_in_the_summary() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("build-report.txt");
+    let progress = ProgressReporter::new(ProgressMode::Quiet);
+    progress.start(Phase::PkgSync).done();
+    progress
+        .attach_report(path.clone(), "manifest hash: 0\n".to_string())
+        .unwrap();
+    progress.start(Phase::Codegen).done();
+
+    let report = std::fs::read_to_string(&path).unwrap();
+    assert!(report.starts_with("manifest hash: 0\nphase timings:\n"));
+    // every phase line of the summary, i.e. all but the total, is in the report as is
+    let summary = progress.summary();
+    let phase_lines: Vec<_> = summary.lines().filter(|l| !l.contains("total")).collect();
+    assert_eq!(phase_lines.len(), 2);
+    for line in phase_lines {
+        assert!(
+            report.contains(line),
+            "{:?} missing from {:?}",
+            line,
+            report
+        );
+    }
+}
+
+#[test]
+fn durations_are_formatted_in_seconds_then_minutes() {
+    assert_eq!(format_duration(Duration::from_millis(400)), "0.4s");
+    assert_eq!(format_duration(Duration::from_millis(12_340)), "12.3s");
+    assert_eq!(format_duration(Duration::from_secs(125)), "2m05s");
+}
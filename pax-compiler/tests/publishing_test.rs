@@ -0,0 +1,108 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use pax_compiler::publishing::{
+    bump_patch_version, check_pax_metadata, referenced_pax_files, unpackaged_pax_files,
+};
+use toml_edit::Document;
+
+const LIBRARY_MANIFEST: &str = r#"[package]
+name = "fancy-buttons"
+version = "0.3.9" # released from main
+
+[package.metadata.pax]
+
+[dependencies]
+pax-engine = "0.36"
+"#;
+
+#[test]
+fn pax_metadata_is_required() {
+    let manifest = Document::from_str(LIBRARY_MANIFEST).unwrap();
+    assert!(check_pax_metadata(&manifest).is_ok());
+
+    let manifest =
+        Document::from_str("[package]\nname = \"plain\"\nversion = \"1.0.0\"\n").unwrap();
+    let err = check_pax_metadata(&manifest).unwrap_err();
+    assert!(
+        err.to_string().contains("[package.metadata.pax]"),
+        "{}",
+        err
+    );
+
+    let manifest =
+        Document::from_str("[package]\nname = \"odd\"\nmetadata = { pax = 1 }\n").unwrap();
+    assert!(check_pax_metadata(&manifest).is_err());
+}
+
+#[test]
+fn patch_version_is_bumped() {
+    let mut manifest = Document::from_str(LIBRARY_MANIFEST).unwrap();
+    assert_eq!(
+        bump_patch_version(&mut manifest),
+        Some("0.3.10".to_string())
+    );
+    let written = manifest.to_string();
+    assert!(
+        written.contains("version = \"0.3.10\" # released from main"),
+        "{}",
+        written
+    );
+    // the rest of the manifest is left as it was
+    assert!(written.contains("[package.metadata.pax]"), "{}", written);
+    assert!(written.contains("pax-engine = \"0.36\""), "{}", written);
+
+    for unbumpable in [
+        "[package]\nversion = \"1.0.0-beta.1\"\n",
+        "[package]\nversion.workspace = true\n",
+        "[package]\nname = \"unversioned\"\n",
+    ] {
+        let mut manifest = Document::from_str(unbumpable).unwrap();
+        assert_eq!(bump_patch_version(&mut manifest), None, "{}", unbumpable);
+        assert_eq!(manifest.to_string(), unbumpable);
+    }
+}
+
+#[test]
+fn unpackaged_templates_are_found() {
+    let crate_root = tempfile::tempdir().unwrap();
+    let src = crate_root.path().join("src");
+    std::fs::create_dir_all(src.join("buttons")).unwrap();
+    std::fs::create_dir_all(crate_root.path().join("templates")).unwrap();
+    std::fs::write(
+        src.join("lib.rs"),
+        "#[pax]\n#[file(\"lib.pax\")]\npub struct Lib;\n\n#[pax]\n#[inlined(<Text/>)]\npub struct Inline;\n",
+    )
+    .unwrap();
+    std::fs::write(
+        src.join("buttons/mod.rs"),
+        "#[pax]\n#[file( \"buttons/fancy.pax\" )]\npub struct Fancy;\n\n#[pax]\n#[file(\"templates/plain.pax\")]\npub struct Plain;\n",
+    )
+    .unwrap();
+    std::fs::write(crate_root.path().join("templates/plain.pax"), "<Text/>").unwrap();
+
+    let mut referenced = referenced_pax_files(crate_root.path()).unwrap();
+    referenced.sort();
+    assert_eq!(
+        referenced,
+        vec![
+            PathBuf::from("src/buttons/fancy.pax"),
+            PathBuf::from("src/lib.pax"),
+            // resolved relative to the crate root, since it exists there
+            PathBuf::from("templates/plain.pax"),
+        ]
+    );
+
+    let packaged = [
+        "Cargo.toml",
+        "src/lib.rs",
+        "src/lib.pax",
+        "src/buttons/mod.rs",
+        "templates/plain.pax",
+    ]
+    .map(PathBuf::from);
+    assert_eq!(
+        unpackaged_pax_files(&referenced, &packaged),
+        vec![PathBuf::from("src/buttons/fancy.pax")]
+    );
+}
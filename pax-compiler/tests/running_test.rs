@@ -0,0 +1,111 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{Read, Write},
+    net::TcpStream,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use pax_compiler::{
+    AppAccess, BuildArtifacts, CancellationToken, ProgressMode, ProgressReporter, RunContext,
+    RunTarget, Verbosity,
+};
+use pax_manifest::{PaxManifest, TypeId};
+
+const INDEX_HTML: &str = "<html><body>built app</body></html>";
+
+fn create_run_context(process_child_ids: Arc<Mutex<Vec<u64>>>) -> RunContext {
+    RunContext {
+        target: RunTarget::Web,
+        project_path: PathBuf::from("."),
+        verbosity: Verbosity::Normal,
+        is_libdev_mode: false,
+        process_child_ids,
+        should_run_designer: false,
+        is_release: false,
+        timeout: None,
+        cargo_bin: None,
+        toolchain: None,
+        defines: BTreeMap::new(),
+        extra_features: vec![],
+        emit_manifest: None,
+        split_cartridge: false,
+        cancellation: CancellationToken::new(),
+        progress: ProgressReporter::new(ProgressMode::Quiet),
+    }
+}
+
+/// A web build as `perform_build` would leave it, with only an `index.html`
+fn create_web_artifacts(build_dir: &tempfile::TempDir) -> BuildArtifacts {
+    std::fs::write(build_dir.path().join("index.html"), INDEX_HTML).unwrap();
+    BuildArtifacts {
+        target: RunTarget::Web,
+        manifest: PaxManifest {
+            components: BTreeMap::new(),
+            main_component_type_id: TypeId::build_singleton("crate::Main", Some("Main")),
+            type_table: HashMap::new(),
+            assets_dirs: vec![],
+            engine_import_path: "pax_engine".to_string(),
+            defines: BTreeMap::new(),
+            tokens: BTreeMap::new(),
+            build_cfg: None,
+        },
+        project_root: build_dir.path().to_path_buf(),
+        is_designer: false,
+        app_path: build_dir.path().to_path_buf(),
+        interface_dir: None,
+    }
+}
+
+fn http_get(url: &str) -> std::io::Result<String> {
+    let address = url.trim_start_matches("http://");
+    let mut stream = TcpStream::connect(address)?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    write!(
+        stream,
+        "GET / HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        address
+    )?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    Ok(response)
+}
+
+#[test]
+fn web_app_is_served_until_stopped() {
+    let build_dir = tempfile::tempdir().unwrap();
+    let artifacts = create_web_artifacts(&build_dir);
+    let process_child_ids = Arc::new(Mutex::new(vec![]));
+    let ctx = create_run_context(Arc::clone(&process_child_ids));
+
+    let mut app = pax_compiler::perform_run(&ctx, &artifacts).unwrap();
+    let url = app.url().expect("web apps have a url").to_string();
+    assert!(matches!(app.access(), AppAccess::Web { .. }));
+    assert_eq!(app.pid(), None);
+    assert!(app.is_alive());
+
+    let response = http_get(&url).unwrap();
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+    assert!(response.ends_with(INDEX_HTML), "{}", response);
+
+    app.stop(Duration::from_secs(5)).unwrap();
+    assert!(!app.is_alive());
+    assert!(http_get(&url).is_err());
+    // serving from this process leaves no processes for the CLI to clean up
+    assert!(process_child_ids.lock().unwrap().is_empty());
+}
+
+#[test]
+fn dropping_a_running_app_stops_it() {
+    let build_dir = tempfile::tempdir().unwrap();
+    let artifacts = create_web_artifacts(&build_dir);
+    let ctx = create_run_context(Arc::new(Mutex::new(vec![])));
+
+    let app = pax_compiler::perform_run(&ctx, &artifacts).unwrap();
+    let url = app.url().unwrap().to_string();
+    assert!(http_get(&url).is_ok());
+
+    drop(app);
+    assert!(http_get(&url).is_err());
+}
@@ -0,0 +1,184 @@
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use common::{component, manifest_of, node, primitive};
+use pax_compiler::SourceMap;
+use pax_manifest::{
+    ComponentDefinition, ComponentTemplate, LocationInfo, PaxIdentifier, PaxManifest,
+    SettingElement, SettingsBlockElement, Token, TypeId, ValueDefinition,
+};
+use serde_json::json;
+
+fn counter_type_id() -> TypeId {
+    TypeId::build_singleton("crate::Counter", Some("Counter"))
+}
+
+fn location(line: usize, col: usize) -> LocationInfo {
+    LocationInfo {
+        start_line_col: (line, col),
+        end_line_col: (line, col + 8),
+    }
+}
+
+/// `Counter`, from `src/counter.pax`, binds `increment` in its settings block on line 12 and
+/// `reset` inline in its template on line 3.  `Spinner` is a primitive
+fn create_manifest() -> PaxManifest {
+    let counter = counter_type_id();
+    let text = TypeId::build_singleton("pax_std::Text", Some("Text"));
+    let mut template = ComponentTemplate::new(counter.clone(), Some("src/counter.pax".to_string()));
+    template.add(node(
+        &text,
+        vec![SettingElement::Setting(
+            Token::new("@click".to_string(), location(2, 10)),
+            ValueDefinition::EventBindingTarget(PaxIdentifier::new("self.reset")),
+        )],
+    ));
+    let counter_component = ComponentDefinition {
+        settings: Some(vec![SettingsBlockElement::Handler(
+            Token::new_without_location("@mount".to_string()),
+            vec![Token::new("increment".to_string(), location(11, 12))],
+        )]),
+        ..component(&counter, Some(template))
+    };
+    let spinner = ComponentDefinition {
+        primitive_instance_import_path: Some("pax_std::SpinnerInstance".to_string()),
+        ..primitive(&TypeId::build_singleton(
+            "pax_std::Spinner",
+            Some("Spinner"),
+        ))
+    };
+    manifest_of(&counter, [counter_component, spinner])
+}
+
+/// A generated cartridge with markers around `Counter`'s factory, its `increment` and `reset`
+/// handlers and `Spinner`'s instantiation, and unmapped code after it
+fn generated_cartridge() -> String {
+    let counter = counter_type_id().get_unique_identifier();
+    let spinner =
+        TypeId::build_singleton("pax_std::Spinner", Some("Spinner")).get_unique_identifier();
+    [
+        "use pax_engine::*;".to_string(),
+        format!("// @source-map-begin {}", counter),
+        "pub(crate) struct CounterFactory{}".to_string(),
+        format!("    // @source-map-begin {}|increment", counter),
+        "    Counter::increment(properties, ctx);".to_string(),
+        "    // @source-map-end".to_string(),
+        format!("    // @source-map-begin {}|reset", counter),
+        "    Counter::reset(properties, ctx);".to_string(),
+        "    // @source-map-end".to_string(),
+        "// @source-map-end".to_string(),
+        format!("// @source-map-begin {}|@primitive", spinner),
+        "<pax_std::SpinnerInstance as InstanceNode>::instantiate(args)".to_string(),
+        "// @source-map-end".to_string(),
+        "fn unmapped() {}".to_string(),
+    ]
+    .join("\n")
+}
+
+fn create_source_map(dir: &Path) -> (SourceMap, PathBuf) {
+    let path = dir.join("cartridge.partial.rs");
+    fs::write(&path, generated_cartridge()).unwrap();
+    let source_map = SourceMap::extract_ranges_from_generated_code([&path], &create_manifest());
+    (source_map, path)
+}
+
+/// A cargo JSON message for an error at `line`:`column` of `file_name`
+fn compiler_message(file_name: &str, line: usize, column: usize) -> String {
+    let location = format!("{}:{}:{}", file_name, line, column);
+    json!({
+        "reason": "compiler-message",
+        "message": {
+            "rendered": format!("error[E0308]: mismatched types\n --> {}\n", location),
+            "spans": [{
+                "file_name": file_name,
+                "line_start": line,
+                "column_start": column,
+                "is_primary": true,
+            }],
+        },
+    })
+    .to_string()
+}
+
+#[test]
+fn test_handlers_map_to_the_line_they_are_bound_on() {
+    let dir = tempfile::tempdir().unwrap();
+    let (source_map, path) = create_source_map(dir.path());
+    let file_name = path.to_str().unwrap();
+
+    let increment = source_map.lookup(file_name, 5).unwrap();
+    assert_eq!(increment.file, "src/counter.pax");
+    assert_eq!(increment.line_col, Some((11, 12)));
+    assert_eq!(increment.to_string(), "src/counter.pax:12:13");
+    assert_eq!(increment.description, "handler `increment` of `Counter`");
+
+    let reset = source_map.lookup(file_name, 8).unwrap();
+    assert_eq!(reset.to_string(), "src/counter.pax:3:11");
+    assert_eq!(reset.description, "handler `reset` of `Counter`");
+}
+
+#[test]
+fn test_component_and_primitive_code_maps_to_the_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let (source_map, path) = create_source_map(dir.path());
+    let file_name = path.to_str().unwrap();
+
+    // the innermost range wins, so only code outside the handlers maps to the component
+    for line in [2, 3, 10] {
+        let counter = source_map.lookup(file_name, line).unwrap();
+        assert_eq!(counter.to_string(), "src/counter.pax");
+        assert_eq!(counter.description, "component `Counter`");
+    }
+    let spinner = source_map.lookup(file_name, 12).unwrap();
+    assert_eq!(spinner.line_col, None);
+    assert!(spinner.description.starts_with("primitive `Spinner`"));
+}
+
+#[test]
+fn test_locations_without_a_mapping() {
+    let dir = tempfile::tempdir().unwrap();
+    let (source_map, path) = create_source_map(dir.path());
+    let file_name = path.to_str().unwrap();
+
+    assert!(source_map.lookup(file_name, 1).is_none());
+    assert!(source_map.lookup(file_name, 14).is_none());
+    assert!(source_map.lookup("src/lib.rs", 5).is_none());
+    // rustc may name the generated file relative to the crate
+    assert!(source_map
+        .lookup(".pax/pax-app/src/cartridge.partial.rs", 5)
+        .is_some());
+}
+
+#[test]
+fn test_cargo_diagnostics_are_remapped_to_the_pax_source() {
+    let dir = tempfile::tempdir().unwrap();
+    let (source_map, path) = create_source_map(dir.path());
+    let file_name = path.to_str().unwrap();
+    let stdout = [
+        compiler_message(file_name, 5, 5),
+        compiler_message("src/lib.rs", 40, 1),
+        json!({"reason": "build-finished", "success": false}).to_string(),
+        "not a json line".to_string(),
+    ]
+    .join("\n");
+
+    let remapped = source_map.remap_cargo_messages(&stdout);
+    assert!(
+        remapped.contains(" --> src/counter.pax:12:13\n"),
+        "{}",
+        remapped
+    );
+    assert!(remapped.contains(&format!(
+        "note: this error is in code generated for handler `increment` of `Counter` (at {}:5:5)",
+        file_name
+    )));
+    // diagnostics outside the cartridge are passed through as rendered
+    assert!(remapped.contains(" --> src/lib.rs:40:1\n"));
+    assert!(remapped.contains("not a json line\n"));
+    assert!(!remapped.contains("build-finished"));
+
+    let verbose = source_map.remap_all_cargo_messages(&stdout);
+    assert!(verbose.contains("build-finished"));
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use std::fs;
+
+use common::{component, main_component, manifest_of, node};
+use pax_compiler::{generate_cartridge_partial_rs, CARTRIDGE_COMPONENTS_DIR};
+use pax_manifest::{ComponentTemplate, PaxManifest, TypeDefinition, TypeId};
+
+/// `Main` holding a `Card`
+fn create_manifest() -> PaxManifest {
+    let main = TypeId::build_singleton("crate::Main", Some("Main"));
+    let card = TypeId::build_singleton("crate::Card", Some("Card"));
+
+    let mut template = ComponentTemplate::new(main.clone(), None);
+    template.add(node(&card, vec![]));
+
+    let mut manifest = manifest_of(&main, [main_component(template), component(&card, None)]);
+    for type_id in [main, card] {
+        manifest.type_table.insert(
+            type_id.clone(),
+            TypeDefinition {
+                type_id,
+                inner_iterable_type_id: None,
+                property_definitions: vec![],
+            },
+        );
+    }
+    manifest
+}
+
+#[test]
+fn test_split_cartridge_has_a_module_per_component() {
+    let pax_dir = tempfile::tempdir().unwrap();
+    let pax_dir = pax_dir.path().to_path_buf();
+    let manifest = create_manifest();
+    let components_dir = pax_dir.join(CARTRIDGE_COMPONENTS_DIR);
+    fs::create_dir_all(&components_dir).unwrap();
+    let stale = components_dir.join("crate_Removed.rs");
+    fs::write(&stale, "").unwrap();
+
+    let cartridge = generate_cartridge_partial_rs(&pax_dir, &manifest, &manifest, None, true);
+
+    let mut file_names: Vec<_> = cartridge
+        .component_paths
+        .iter()
+        .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+        .collect();
+    file_names.sort();
+    assert_eq!(file_names, vec!["crate_Card.rs", "crate_Main.rs"]);
+    assert!(!stale.exists());
+
+    let top_level = fs::read_to_string(&cartridge.path).unwrap();
+    for path in &cartridge.component_paths {
+        assert!(top_level.contains(&format!("include!({:?})", path.to_string_lossy())));
+        let module = fs::read_to_string(path).unwrap();
+        assert_eq!(
+            module
+                .matches("impl pax_engine::pax_runtime::ComponentFactory")
+                .count(),
+            1
+        );
+    }
+    assert!(top_level.contains("use component_crate_Card::CardFactory;"));
+    assert!(!top_level.contains("impl pax_engine::pax_runtime::ComponentFactory"));
+}
+
+#[test]
+fn test_unsplit_cartridge_inlines_components() {
+    let pax_dir = tempfile::tempdir().unwrap();
+    let pax_dir = pax_dir.path().to_path_buf();
+    let manifest = create_manifest();
+
+    let cartridge = generate_cartridge_partial_rs(&pax_dir, &manifest, &manifest, None, false);
+
+    assert!(cartridge.component_paths.is_empty());
+    assert!(!pax_dir.join(CARTRIDGE_COMPONENTS_DIR).exists());
+    let top_level = fs::read_to_string(&cartridge.path).unwrap();
+    assert_eq!(
+        top_level
+            .matches("impl pax_engine::pax_runtime::ComponentFactory")
+            .count(),
+        2
+    );
+    assert!(!top_level.contains("include!"));
+}
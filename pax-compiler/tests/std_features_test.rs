@@ -0,0 +1,86 @@
+mod common;
+
+use std::collections::BTreeMap;
+
+use common::{main_component, manifest_of, node, primitive};
+use pax_compiler::{StdDependency, StdFeatureSelection};
+use pax_manifest::{ComponentTemplate, PaxManifest, TypeId};
+
+/// `Main` holds a `Group` and a `Rectangle`; the manifest also has pax-std's `Text` and `Button`,
+/// as the parser's manifest does
+fn create_manifest() -> PaxManifest {
+    let main = TypeId::build_singleton("crate::Main", Some("Main"));
+    let group = TypeId::build_singleton("pax_std::core::group::Group", Some("Group"));
+    let rectangle =
+        TypeId::build_singleton("pax_std::drawing::rectangle::Rectangle", Some("Rectangle"));
+    let text = TypeId::build_singleton("pax_std::core::text::Text", Some("Text"));
+    let button = TypeId::build_singleton("pax_std::forms::button::Button", Some("Button"));
+
+    let mut template = ComponentTemplate::new(main.clone(), None);
+    let root = template.add(node(&group, vec![])).get_template_node_id();
+    template.add_child(root, node(&rectangle, vec![]));
+
+    let mut components = vec![main_component(template)];
+    components.extend([&group, &rectangle, &text, &button].map(primitive));
+    manifest_of(&main, components)
+}
+
+fn overrides(entries: &[(&str, bool)]) -> BTreeMap<String, bool> {
+    entries
+        .iter()
+        .map(|(feature, enabled)| (feature.to_string(), *enabled))
+        .collect()
+}
+
+fn features(selection: &StdFeatureSelection) -> Vec<&'static str> {
+    selection.features.iter().copied().collect()
+}
+
+#[test]
+fn test_only_used_families_are_enabled() {
+    let mut manifest = create_manifest();
+    let selection = StdFeatureSelection::select(&manifest, &BTreeMap::new()).unwrap();
+    assert_eq!(features(&selection), vec!["drawing"]);
+    assert!(selection.reasons["drawing"].contains("Rectangle"));
+    assert_eq!(
+        selection.cargo_features(StdDependency::PaxKit),
+        vec!["pax-kit/std-drawing"]
+    );
+
+    // `Text` and `Button` can't be compiled without their features, so they leave the manifest
+    assert_eq!(selection.prune(&mut manifest), 2);
+    let mut names: Vec<_> = manifest
+        .components
+        .keys()
+        .filter_map(TypeId::get_pascal_identifier)
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["Group", "Main", "Rectangle"]);
+}
+
+#[test]
+fn test_overrides_enable_families_and_their_dependencies() {
+    let manifest = create_manifest();
+    let selection = StdFeatureSelection::select(&manifest, &overrides(&[("forms", true)])).unwrap();
+    assert_eq!(features(&selection), vec!["drawing", "forms", "text"]);
+    assert_eq!(selection.reasons["text"], "needed by `forms`");
+    assert_eq!(
+        selection.cargo_features(StdDependency::PaxStd),
+        vec!["pax-std/drawing", "pax-std/forms", "pax-std/text"]
+    );
+}
+
+#[test]
+fn test_disabling_a_used_family_is_an_error() {
+    let manifest = create_manifest();
+    let err = StdFeatureSelection::select(&manifest, &overrides(&[("drawing", false)]))
+        .err()
+        .unwrap();
+    assert!(err.contains("`Rectangle`"));
+    assert!(err.contains("needs pax-std feature `drawing`"));
+
+    let err = StdFeatureSelection::select(&manifest, &overrides(&[("widgets", true)]))
+        .err()
+        .unwrap();
+    assert!(err.contains("Unknown pax-std feature `widgets`"));
+}
@@ -0,0 +1,119 @@
+use pax_compiler::design_server::code_serialization::press_code_serialization_template;
+use pax_compiler::{import_svg, import_svg_lossy, ImportError};
+
+const LOGO: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<!-- exported from a vector editor -->
+<svg xmlns="http://www.w3.org/2000/svg" width="200" height="100" viewBox="0 0 200 100">
+  <title>Logo</title>
+  <g id="badge" transform="translate(10, 20) rotate(45)" fill="#ff0000">
+    <rect x="0" y="0" width="50" height="30" rx="4"/>
+    <circle cx="25" cy="15" r="5" fill="none" stroke="blue" stroke-width="2"/>
+  </g>
+  <ellipse cx="100" cy="50" rx="20" ry="10" style="fill: rgb(0, 128, 0); opacity: 0.5"/>
+  <path id="arrow" d="M10 10 h20 v20 Z l5 5" transform="scale(2)"/>
+</svg>"##;
+
+#[test]
+fn test_import_svg_shapes() {
+    let component = import_svg(LOGO, "Logo").unwrap();
+    assert_eq!(
+        component.type_id.get_pascal_identifier(),
+        Some("Logo".to_string())
+    );
+    let pax = press_code_serialization_template(component);
+    // the transform of a shape applies to its position, so goes on a group around it
+    let expected = r#"<Group designer_label="badge" transform={Transform2D::translate(10px, 20px) * Transform2D::rotate(45deg)}>
+    <Rectangle x=0px y=0px width=50px height=30px corner_radii={RectangleCornerRadii::radii(4, 4, 4, 4)} fill=rgb(255, 0, 0)/>
+    <Ellipse x=20px y=10px width=10px height=10px fill=rgba(0, 0, 0, 0) stroke={
+        color: rgb(0, 0, 255)
+        width: 2px
+    }/>
+</Group>
+<Ellipse x=80px y=40px width=40px height=20px fill=rgba(0, 128, 0, 128)/>
+<Group designer_label="arrow" transform={Transform2D::scale(200%, 200%)}>
+    <Path elements={[
+        PathElement::Point(10px, 10px),
+        PathElement::Line,
+        PathElement::Point(30px, 10px),
+        PathElement::Line,
+        PathElement::Point(30px, 30px),
+        PathElement::Close,
+        PathElement::Point(10px, 10px),
+        PathElement::Line,
+        PathElement::Point(15px, 15px)
+    ]} 
+        fill=rgb(0, 0, 0)/>
+</Group>"#;
+    assert_eq!(pax.trim(), expected);
+}
+
+#[test]
+fn test_import_svg_viewbox() {
+    let svg =
+        r#"<svg width="100" height="100" viewBox="-10 0 50 50"><rect width="5" height="5"/></svg>"#;
+    let pax = press_code_serialization_template(import_svg(svg, "Icon").unwrap());
+    assert!(
+        pax.contains(
+            "<Group transform={Transform2D::scale(200%, 200%) * Transform2D::translate(10px, 0px)}>"
+        ),
+        "{}",
+        pax
+    );
+}
+
+#[test]
+fn test_import_svg_reports_unsupported() {
+    let svg = r##"<svg>
+  <rect width="10" height="10" fill="url(#gradient)" filter="url(#blur)"/>
+  <text x="5" y="5">Hello</text>
+  <path d="M0 0 A 5 5 0 0 1 10 10"/>
+  <g transform="skewX(10)"><circle r="4"/></g>
+</svg>"##;
+    let Err(ImportError::Unsupported(unsupported)) = import_svg(svg, "Art") else {
+        panic!("expected unsupported features to be reported");
+    };
+    let reported: Vec<String> = unsupported.iter().map(|u| u.to_string()).collect();
+    assert_eq!(
+        reported,
+        vec![
+            "line 2: <rect>: attribute `filter` not supported",
+            "line 2: <rect>: fill `url(#gradient)` not supported",
+            "line 3: <text>: element not supported",
+            "line 4: <path>: arc commands, drawn as lines, not supported",
+            "line 5: <g>: transform `skewX(10)` not supported",
+        ]
+    );
+
+    // the lossy import keeps what it could, approximating arcs with lines
+    let import = import_svg_lossy(svg, "Art").unwrap();
+    assert_eq!(import.unsupported, unsupported);
+    let pax = press_code_serialization_template(import.component);
+    assert!(!pax.contains("Text"), "{}", pax);
+    assert!(
+        pax.contains(
+            "PathElement::Point(0px, 0px), PathElement::Line, PathElement::Point(10px, 10px)"
+        ),
+        "{}",
+        pax
+    );
+    assert!(pax.contains("<Ellipse x=-4px y=-4px"), "{}", pax);
+}
+
+#[test]
+fn test_import_svg_errors() {
+    assert_eq!(
+        import_svg("<svg>", "Art").err(),
+        Some(ImportError::Malformed {
+            line: 1,
+            message: "<svg> is never closed".to_string()
+        })
+    );
+    assert_eq!(
+        import_svg("<html></html>", "Art").err(),
+        Some(ImportError::NotSvg)
+    );
+    assert_eq!(
+        import_svg("<svg/>", "my-art").err(),
+        Some(ImportError::InvalidComponentName("my-art".to_string()))
+    );
+}
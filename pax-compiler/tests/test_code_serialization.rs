@@ -7,8 +7,7 @@ use std::sync::{Arc, Mutex};
 use pax_compiler::design_server::code_serialization::serialize_component_to_file;
 use pax_compiler::formatting::format_file;
 use pax_compiler::helpers::clear_inlined_template;
-use pax_compiler::run_parser_binary;
-use pax_manifest::PaxManifest;
+use pax_compiler::{parse_parser_output, run_parser_binary};
 
 const PATH: &str = "tests/data/code_serialization/serialization_test_project";
 
@@ -65,13 +64,14 @@ fn test_code_serializaton() {
     );
 
     let out = String::from_utf8(output.stdout).unwrap();
-    let manifests: Vec<PaxManifest> = serde_json::from_str(&out).unwrap_or_else(|e| {
-        panic!(
-            "Malformed JSON from parser: {e:?}, raw parser output:\n{}",
-            &out
-        )
-    });
-    let manifest = manifests.into_iter().next().unwrap();
+    let manifest = parse_parser_output(&out)
+        .unwrap_or_else(|e| {
+            panic!(
+                "Malformed JSON from parser: {e:?}, raw parser output:\n{}",
+                &out
+            )
+        })
+        .userland;
     let main_component = manifest
         .components
         .get(&manifest.main_component_type_id)
@@ -0,0 +1,219 @@
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use common::{component, main_component, manifest_of, node, primitive, setting};
+use pax_compiler::{TreeShakingPass, TreeShakingReport};
+use pax_lang::parse_pax_expression;
+use pax_manifest::{
+    ComponentDefinition, ComponentTemplate, ExpressionInfo, PaxManifest, SettingElement, TypeId,
+    ValueDefinition,
+};
+use pax_runtime_api::PaxValue;
+
+fn type_id(name: &str) -> TypeId {
+    TypeId::build_singleton(&format!("crate::{}", name), Some(name))
+}
+
+fn string(value: &str) -> ValueDefinition {
+    ValueDefinition::LiteralValue(PaxValue::String(value.to_string()))
+}
+
+/// A component whose template is a single node of each of `children`, the first with `settings`
+fn composite(name: &str, children: &[&str], settings: Vec<SettingElement>) -> ComponentDefinition {
+    let mut template = ComponentTemplate::new(type_id(name), None);
+    let mut settings = Some(settings);
+    for child in children {
+        template.add(node(&type_id(child), settings.take().unwrap_or_default()));
+    }
+    component(&type_id(name), Some(template))
+}
+
+/// `Main` holds a `Card` holding an `Icon`, with `main_settings` on the `Card`.  `Unused`,
+/// `Dialog`, `Legacy` and `Other` aren't used in any template, nor is the struct-only `Point`.
+/// `BlankComponent` and `UnresolvedPlaceholder`, which the engine constructs itself, are made of a
+/// `Group` and a `Rectangle`
+fn create_manifest(main_settings: Vec<SettingElement>) -> PaxManifest {
+    let main = type_id("Main");
+    let mut template = ComponentTemplate::new(main.clone(), None);
+    template.add(node(&type_id("Card"), main_settings));
+
+    let point = ComponentDefinition {
+        is_struct_only_component: true,
+        ..component(&type_id("Point"), None)
+    };
+    manifest_of(
+        &main,
+        [
+            main_component(template),
+            composite("Card", &["Icon"], vec![]),
+            primitive(&type_id("Icon")),
+            composite("Unused", &["Icon"], vec![]),
+            primitive(&type_id("Dialog")),
+            primitive(&type_id("Legacy")),
+            primitive(&type_id("Other")),
+            point,
+            composite("BlankComponent", &["Group"], vec![]),
+            composite("UnresolvedPlaceholder", &["Rectangle"], vec![]),
+            primitive(&type_id("Group")),
+            primitive(&type_id("Rectangle")),
+        ],
+    )
+}
+
+fn names(type_ids: &[TypeId]) -> Vec<String> {
+    let mut names: Vec<String> = type_ids
+        .iter()
+        .map(|t| t.get_pascal_identifier().unwrap())
+        .collect();
+    names.sort();
+    names
+}
+
+fn retained_names(report: &TreeShakingReport) -> Vec<String> {
+    let type_ids: Vec<TypeId> = report
+        .retained_components
+        .iter()
+        .map(|(t, _)| t.clone())
+        .collect();
+    names(&type_ids)
+}
+
+fn run(manifest: &mut PaxManifest, keep: Vec<String>) -> TreeShakingReport {
+    TreeShakingPass::new(vec![manifest.main_component_type_id.clone()], keep).run(manifest)
+}
+
+#[test]
+fn test_unreachable_components_are_excluded() {
+    let mut manifest = create_manifest(vec![]);
+    let report = run(&mut manifest, vec![]);
+
+    assert_eq!(
+        names(&report.excluded_components),
+        ["Dialog", "Legacy", "Other", "Unused"]
+    );
+    assert!(report.retained_components.is_empty());
+    let remaining: Vec<TypeId> = manifest.components.keys().cloned().collect();
+    assert_eq!(
+        names(&remaining),
+        [
+            "BlankComponent",
+            "Card",
+            "Group",
+            "Icon",
+            "Main",
+            "Point",
+            "Rectangle",
+            "UnresolvedPlaceholder"
+        ]
+    );
+}
+
+#[test]
+fn test_components_named_in_expressions_and_strings_are_retained() {
+    let mut manifest = create_manifest(vec![
+        setting(
+            "kind",
+            ValueDefinition::Expression(ExpressionInfo::new(
+                parse_pax_expression("open_dialog == \"Dialog\"").unwrap(),
+            )),
+        ),
+        setting("fallback", string("Legacy")),
+    ]);
+    let report = run(&mut manifest, vec![]);
+
+    assert_eq!(retained_names(&report), ["Dialog", "Legacy"]);
+    for (_, reason) in &report.retained_components {
+        assert!(reason.contains("named in `Main`"), "{}", reason);
+    }
+    assert_eq!(names(&report.excluded_components), ["Other", "Unused"]);
+}
+
+#[test]
+fn test_kept_components_are_retained_by_name_or_unique_id() {
+    let mut manifest = create_manifest(vec![]);
+    let keep = vec![
+        "Legacy".to_string(),
+        type_id("Unused").get_unique_identifier(),
+    ];
+    let report = run(&mut manifest, keep);
+
+    assert_eq!(retained_names(&report), ["Legacy", "Unused"]);
+    for (_, reason) in &report.retained_components {
+        assert_eq!(reason, "listed in `keep`");
+    }
+    assert_eq!(names(&report.excluded_components), ["Dialog", "Other"]);
+}
+
+#[test]
+fn test_struct_only_components_are_always_kept() {
+    let mut manifest = create_manifest(vec![]);
+    let report = run(&mut manifest, vec![]);
+
+    assert!(manifest.components.contains_key(&type_id("Point")));
+    assert!(!report.excluded_components.contains(&type_id("Point")));
+    assert!(!report
+        .retained_components
+        .iter()
+        .any(|(t, _)| *t == type_id("Point")));
+}
+
+#[test]
+fn test_unreferenced_assets_are_excluded() {
+    let dir = tempfile::tempdir().unwrap();
+    let assets = dir.path().join("assets");
+    let src = dir.path().join("src");
+    fs::create_dir_all(assets.join("icons")).unwrap();
+    fs::create_dir_all(&src).unwrap();
+    fs::write(assets.join("logo.png"), [0u8; 10]).unwrap();
+    fs::write(assets.join("icons/close.svg"), [0u8; 20]).unwrap();
+    fs::write(assets.join("brand.jpg"), [0u8; 30]).unwrap();
+    fs::write(assets.join("unused.png"), [0u8; 40]).unwrap();
+    fs::write(
+        src.join("lib.rs"),
+        "fn icon() -> String { format!(\"assets/icons/{}\", \"close.svg\") }",
+    )
+    .unwrap();
+
+    let mut manifest = create_manifest(vec![setting("src", string("assets/logo.png"))]);
+    manifest.tokens.insert(
+        "brand".to_string(),
+        PaxValue::String("brand.jpg".to_string()),
+    );
+    let mut report = run(&mut manifest, vec![]);
+    report.exclude_unreferenced_assets(&manifest, &[assets.to_str().unwrap().to_string()], &src);
+
+    assert_eq!(report.excluded_assets, [(assets.join("unused.png"), 40)]);
+    assert_eq!(
+        report.excluded_asset_paths(),
+        [assets.join("unused.png")].into_iter().collect()
+    );
+}
+
+#[test]
+fn test_report_summary_and_listing() {
+    let report = TreeShakingReport {
+        excluded_components: vec![type_id("Unused"), type_id("Other")],
+        retained_components: vec![(type_id("Legacy"), "listed in `keep`".to_string())],
+        excluded_assets: vec![
+            (PathBuf::from("assets/a.png"), 1024),
+            (PathBuf::from("assets/b.png"), 512),
+        ],
+    };
+    assert_eq!(report.summary(), "excluded 2 components, 1.5 KB of assets");
+    assert_eq!(
+        TreeShakingReport::default().summary(),
+        "excluded 0 components, 0 B of assets"
+    );
+
+    let listing = report.to_report_string();
+    assert!(listing.starts_with("Tree shaking: excluded 2 components, 1.5 KB of assets\n"));
+    assert!(listing.contains("\nExcluded components:\n  crate::Unused\n  crate::Other\n"));
+    assert!(
+        listing.contains("\nRetained unreachable components:\n  crate::Legacy: listed in `keep`\n")
+    );
+    assert!(
+        listing.contains("\nExcluded assets:\n  assets/a.png (1.0 KB)\n  assets/b.png (512 B)\n")
+    );
+}
@@ -0,0 +1,92 @@
+use std::collections::{BTreeMap, HashMap};
+
+use pax_compiler::design_server::code_serialization::press_code_serialization_template;
+use pax_compiler::{validate_settings, LintSeverity, UNRESOLVED_TYPE};
+use pax_manifest::parsing::{assemble_component_definition, ParsingContext};
+use pax_manifest::{ComponentDefinition, PaxManifest, TypeId};
+
+const TEMPLATE: &str = r#"<Group>
+    <OldCard x=10px title="Hello">
+        <Rectangle />
+    </OldCard>
+    <Missing />
+</Group>"#;
+
+/// `Main`'s template, parsed while `OldCard` and `Missing` don't exist
+fn parse_main() -> ComponentDefinition {
+    let template_map: HashMap<String, TypeId> = ["Group", "Rectangle"]
+        .into_iter()
+        .map(|name| {
+            let import_path = format!("pax_std::{}", name);
+            (
+                name.to_string(),
+                TypeId::build_singleton(&import_path, Some(name)),
+            )
+        })
+        .collect();
+    let (_, main) = assemble_component_definition(
+        ParsingContext::default(),
+        TEMPLATE,
+        true,
+        template_map,
+        "crate",
+        TypeId::build_singleton("crate::Main", Some("Main")),
+        "main.pax",
+    );
+    main
+}
+
+#[test]
+fn test_unresolved_tags_are_kept() {
+    let main = parse_main();
+    let template = main.template.as_ref().unwrap();
+    let group = template.get_root()[0].clone();
+    let children = template.get_children(&group).unwrap();
+    let old_card = template.get_node(&children[0]).unwrap();
+    assert!(old_card.type_id.is_unresolved());
+    assert_eq!(
+        old_card.type_id.get_pascal_identifier().as_deref(),
+        Some("OldCard")
+    );
+    assert_eq!(old_card.settings.as_ref().map(Vec::len), Some(2));
+    assert_eq!(
+        template.get_children(&children[0]).map(|c| c.len()),
+        Some(1)
+    );
+    assert!(template
+        .get_node(&children[1])
+        .unwrap()
+        .type_id
+        .is_unresolved());
+
+    // writing the template back leaves the tags untouched
+    let pax = press_code_serialization_template(main);
+    assert_eq!(pax.trim(), TEMPLATE);
+}
+
+#[test]
+fn test_unresolved_tags_fail_builds() {
+    let main = parse_main();
+    let manifest = PaxManifest {
+        components: BTreeMap::from([(main.type_id.clone(), main.clone())]),
+        main_component_type_id: main.type_id.clone(),
+        type_table: HashMap::new(),
+        assets_dirs: vec![],
+        engine_import_path: "pax_engine".to_string(),
+        defines: BTreeMap::new(),
+        tokens: BTreeMap::new(),
+        build_cfg: None,
+    };
+    let diagnostics = validate_settings(&manifest);
+    let messages: Vec<_> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "`OldCard` isn't a known component",
+            "`Missing` isn't a known component"
+        ]
+    );
+    assert!(diagnostics
+        .iter()
+        .all(|d| d.rule == UNRESOLVED_TYPE && d.severity == LintSeverity::Error));
+}
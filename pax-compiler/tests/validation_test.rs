@@ -0,0 +1,138 @@
+mod common;
+
+use common::{component, main_component, manifest_of, node};
+use pax_compiler::{
+    validate_settings, LintSeverity, LOSSY_COERCION, MISMATCHED_TYPE, UNKNOWN_PROPERTY,
+    UNKNOWN_TOKEN,
+};
+use pax_manifest::{
+    ComponentTemplate, ExpressionInfo, LocationInfo, PaxManifest, PropertyDefinition,
+    SettingElement, Token, TypeDefinition, TypeId, ValueDefinition,
+};
+use pax_runtime_api::{Numeric, PaxValue};
+
+/// A setting whose key is on line `line` of the template
+fn setting(key: &str, line: usize, value: ValueDefinition) -> SettingElement {
+    let location = LocationInfo {
+        start_line_col: (line, 4),
+        end_line_col: (line, 4 + key.len()),
+    };
+    SettingElement::Setting(Token::new(key.to_string(), location), value)
+}
+
+fn literal(value: PaxValue) -> ValueDefinition {
+    ValueDefinition::LiteralValue(value)
+}
+
+fn property(name: &str, type_id: TypeId) -> PropertyDefinition {
+    PropertyDefinition {
+        name: name.to_string(),
+        flags: Default::default(),
+        type_id,
+    }
+}
+
+/// `Main` instantiates `Card`, which has a `Numeric` padding and a `u32` column count, with
+/// `settings`
+fn create_manifest(settings: Vec<SettingElement>) -> PaxManifest {
+    let main = TypeId::build_singleton("crate::Main", Some("Main"));
+    let card = TypeId::build_singleton("crate::Card", Some("Card"));
+
+    let mut template = ComponentTemplate::new(main.clone(), Some("src/main.pax".to_string()));
+    template.add(node(&card, settings));
+
+    let mut manifest = manifest_of(&main, [main_component(template), component(&card, None)]);
+    manifest.type_table.insert(
+        card.clone(),
+        TypeDefinition {
+            type_id: card,
+            inner_iterable_type_id: None,
+            property_definitions: vec![
+                property(
+                    "padding",
+                    TypeId::build_singleton("pax_engine::api::Numeric", Some("Numeric")),
+                ),
+                property("columns", TypeId::build_primitive("u32")),
+            ],
+        },
+    );
+    manifest
+}
+
+#[test]
+fn test_mismatched_literal_is_an_error_at_the_call_site() {
+    let manifest = create_manifest(vec![setting(
+        "padding",
+        6,
+        literal(PaxValue::String("lots".to_string())),
+    )]);
+    let diagnostics = validate_settings(&manifest);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].rule, MISMATCHED_TYPE);
+    assert_eq!(diagnostics[0].severity, LintSeverity::Error);
+    assert!(diagnostics[0]
+        .message
+        .contains("expected Numeric for Card::padding"));
+    assert_eq!(diagnostics[0].location.to_string(), "src/main.pax:7:5");
+}
+
+#[test]
+fn test_float_into_integer_is_a_lossy_coercion_warning() {
+    let manifest = create_manifest(vec![setting(
+        "columns",
+        2,
+        literal(PaxValue::Numeric(Numeric::F64(2.5))),
+    )]);
+    let diagnostics = validate_settings(&manifest);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].rule, LOSSY_COERCION);
+    assert_eq!(diagnostics[0].severity, LintSeverity::Warning);
+    assert!(diagnostics[0].message.contains("Card::columns"));
+}
+
+#[test]
+fn test_expressions_are_only_checked_for_the_property_existing() {
+    let expression = || ValueDefinition::Expression(ExpressionInfo::default());
+    let manifest = create_manifest(vec![
+        setting("padding", 1, expression()),
+        setting("margin", 2, expression()),
+    ]);
+    let diagnostics = validate_settings(&manifest);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].rule, UNKNOWN_PROPERTY);
+    assert!(diagnostics[0].message.contains("`margin`"));
+}
+
+#[test]
+fn test_valid_settings_pass() {
+    let manifest = create_manifest(vec![
+        setting("padding", 1, literal(PaxValue::Numeric(Numeric::F64(8.0)))),
+        setting("columns", 2, literal(PaxValue::Numeric(Numeric::I64(3)))),
+        setting("width", 3, literal(PaxValue::Numeric(Numeric::I64(100)))),
+        setting("class", 4, literal(PaxValue::String("card".to_string()))),
+    ]);
+    assert!(validate_settings(&manifest).is_empty());
+}
+
+#[test]
+fn test_tokens_are_checked_like_the_literals_they_stand_for() {
+    let token = |name: &str| ValueDefinition::Token(name.to_string());
+    let mut manifest = create_manifest(vec![
+        setting("padding", 1, token("gutter")),
+        setting("columns", 2, token("brand")),
+        setting("width", 3, token("missing")),
+    ]);
+    manifest
+        .tokens
+        .insert("gutter".to_string(), PaxValue::Numeric(Numeric::F64(16.0)));
+    manifest
+        .tokens
+        .insert("brand".to_string(), PaxValue::String("red".to_string()));
+    let diagnostics = validate_settings(&manifest);
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[0].rule, MISMATCHED_TYPE);
+    assert!(diagnostics[0].message.contains("Card::columns"));
+    assert_eq!(diagnostics[1].rule, UNKNOWN_TOKEN);
+    assert_eq!(diagnostics[1].severity, LintSeverity::Error);
+    assert!(diagnostics[1].message.contains("`$tokens.missing`"));
+}
@@ -0,0 +1,70 @@
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use pax_compiler::{
+    CancellationToken, ProgressMode, ProgressReporter, RunContext, RunTarget, Verbosity,
+};
+
+fn create_run_context(verbosity: Verbosity) -> RunContext {
+    RunContext {
+        target: RunTarget::Web,
+        project_path: PathBuf::from("."),
+        verbosity,
+        is_libdev_mode: false,
+        process_child_ids: Arc::new(Mutex::new(vec![])),
+        should_run_designer: false,
+        is_release: false,
+        timeout: None,
+        cargo_bin: None,
+        toolchain: None,
+        defines: BTreeMap::new(),
+        extra_features: vec![],
+        emit_manifest: None,
+        split_cartridge: false,
+        cancellation: CancellationToken::new(),
+        progress: ProgressReporter::new(ProgressMode::Quiet),
+    }
+}
+
+/// The value `cmd` sets `key` to in its environment, if any
+fn env_of<'a>(cmd: &'a std::process::Command, key: &str) -> Option<&'a OsStr> {
+    cmd.get_envs()
+        .find(|(k, _)| *k == OsStr::new(key))
+        .and_then(|(_, v)| v)
+}
+
+#[test]
+fn verbosity_parses_its_lowercase_names() {
+    assert_eq!("quiet".parse::<Verbosity>().unwrap(), Verbosity::Quiet);
+    assert_eq!("normal".parse::<Verbosity>().unwrap(), Verbosity::Normal);
+    assert_eq!("Verbose".parse::<Verbosity>().unwrap(), Verbosity::Verbose);
+    assert_eq!("trace".parse::<Verbosity>().unwrap(), Verbosity::Trace);
+    assert!("loud".parse::<Verbosity>().is_err());
+}
+
+#[test]
+fn each_level_prints_what_the_levels_below_it_do() {
+    let ctx = create_run_context(Verbosity::Verbose);
+    assert!(ctx.prints(Verbosity::Normal));
+    assert!(ctx.prints(Verbosity::Verbose));
+    assert!(!ctx.prints(Verbosity::Trace));
+
+    let ctx = create_run_context(Verbosity::Quiet);
+    assert!(ctx.prints(Verbosity::Quiet));
+    assert!(!ctx.prints(Verbosity::Normal));
+}
+
+#[test]
+fn cargo_is_made_quiet_or_verbose_to_match() {
+    let cmd = create_run_context(Verbosity::Quiet).cargo_command();
+    assert_eq!(env_of(&cmd, "CARGO_TERM_QUIET"), Some(OsStr::new("true")));
+
+    let cmd = create_run_context(Verbosity::Normal).cargo_command();
+    assert_eq!(env_of(&cmd, "CARGO_TERM_QUIET"), None);
+    assert_eq!(env_of(&cmd, "CARGO_TERM_VERBOSE"), None);
+
+    let cmd = create_run_context(Verbosity::Trace).cargo_command();
+    assert_eq!(env_of(&cmd, "CARGO_TERM_VERBOSE"), Some(OsStr::new("true")));
+}
@@ -0,0 +1,223 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use pax_compiler::helpers::{copy_dir_recursively, update_pax_dependency_versions};
+use pax_compiler::workspace::{sync_root_manifest, CargoProject};
+use pax_compiler::StdDependency;
+use tempfile::TempDir;
+use toml_edit::Document;
+
+const PATH: &str = "tests/data/workspace_project";
+
+fn fixture_path() -> PathBuf {
+    let current_dir = env::current_dir().expect("Failed to get current directory");
+    fs::canonicalize(current_dir.join(PATH)).expect("Fixture workspace should exist")
+}
+
+fn copy_fixture() -> (TempDir, PathBuf) {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let root = temp_dir.path().join("workspace_project");
+    copy_dir_recursively(&fixture_path(), &root, &["target"]).expect("Failed to copy fixture");
+    let root = fs::canonicalize(root).unwrap();
+    (temp_dir, root)
+}
+
+#[test]
+fn test_locate_workspace_member() {
+    let root = fixture_path();
+    let project = CargoProject::locate(&root.join("crates/my-app")).unwrap();
+
+    assert_eq!(project.package_name, "my-app");
+    assert_eq!(project.workspace_root.as_ref(), Some(&root));
+    assert_eq!(project.cargo_cwd(), root.as_path());
+    assert_eq!(project.root_manifest_path(), root.join("Cargo.toml"));
+
+    // Each member of the workspace gets its own `.pax` state
+    let shared = CargoProject::locate(&root.join("crates/shared")).unwrap();
+    assert_ne!(project.pax_dir(), shared.pax_dir());
+    assert!(project.pax_dir().starts_with(project.target_dir()));
+}
+
+#[test]
+fn test_locate_standalone_crate() {
+    let current_dir = env::current_dir().unwrap();
+    let path = current_dir.join("tests/data/code_serialization/serialization_test_project");
+    let project = CargoProject::locate(&path).unwrap();
+
+    assert!(!project.is_workspace_member());
+    assert_eq!(project.cargo_cwd(), project.crate_root.as_path());
+    assert_eq!(project.pax_dir(), project.crate_root.join(".pax"));
+}
+
+#[test]
+fn test_prepare_root_manifest_is_idempotent() {
+    let (_temp_dir, root) = copy_fixture();
+    let project = CargoProject {
+        crate_root: root.join("crates/my-app"),
+        package_name: "my-app".to_string(),
+        workspace_root: Some(root.clone()),
+    };
+
+    project.prepare_root_manifest().unwrap();
+    let once = fs::read_to_string(root.join("Cargo.toml")).unwrap();
+    project.prepare_root_manifest().unwrap();
+    let twice = fs::read_to_string(root.join("Cargo.toml")).unwrap();
+
+    assert_eq!(once, twice);
+    let doc = Document::from_str(&once).unwrap();
+    assert_eq!(doc["profile"]["parser"]["inherits"].as_str(), Some("dev"));
+    // Existing content is preserved
+    assert!(once.starts_with("[workspace]\nresolver = \"2\""));
+}
+
+#[test]
+fn test_sync_root_manifest_rebases_patch_paths() {
+    let member = Document::from_str(
+        r#"
+[package]
+name = "my-app"
+
+[patch.crates-io]
+pax-kit = { path = "../../vendor/pax-kit" }
+"#,
+    )
+    .unwrap();
+    let mut root = Document::from_str(
+        r#"
+[workspace]
+members = ["crates/my-app"]
+
+[profile.parser]
+opt-level = 1
+"#,
+    )
+    .unwrap();
+
+    let changed = sync_root_manifest(
+        &member,
+        &mut root,
+        &PathBuf::from("/ws/crates/my-app"),
+        &PathBuf::from("/ws"),
+    );
+
+    assert!(changed);
+    assert_eq!(
+        root["patch"]["crates-io"]["pax-kit"]["path"].as_str(),
+        Some("vendor/pax-kit")
+    );
+    // Profiles already defined at the root are left alone
+    assert_eq!(root["profile"]["parser"]["opt-level"].as_integer(), Some(1));
+}
+
+#[test]
+fn test_update_workspace_dependency_versions() {
+    let mut doc = Document::from_str(
+        r#"
+[workspace.dependencies]
+pax-kit = { version = "0.1.0" }
+
+[dependencies]
+pax-engine = { workspace = true }
+pax-std = "0.1.0"
+"#,
+    )
+    .unwrap();
+
+    update_pax_dependency_versions(&mut doc, "0.36.9");
+
+    assert_eq!(
+        doc["workspace"]["dependencies"]["pax-kit"]["version"].as_str(),
+        Some("0.36.9")
+    );
+    assert_eq!(
+        doc["dependencies"]["pax-engine"]["workspace"].as_bool(),
+        Some(true)
+    );
+    assert!(doc["dependencies"]["pax-engine"]
+        .as_table_like()
+        .unwrap()
+        .get("version")
+        .is_none());
+}
+
+#[test]
+fn test_std_default_features_of_inherited_and_direct_dependencies() {
+    let (_temp_dir, root) = copy_fixture();
+    let project = CargoProject {
+        crate_root: root.join("crates/my-app"),
+        package_name: "my-app".to_string(),
+        workspace_root: Some(root.clone()),
+    };
+    assert_eq!(
+        project.std_dependency().unwrap(),
+        Some(StdDependency::PaxKit)
+    );
+    assert!(project
+        .keeps_std_default_features(StdDependency::PaxKit)
+        .unwrap());
+
+    // `workspace = true` inherits the defaults of `[workspace.dependencies]`
+    let root_manifest = fs::read_to_string(root.join("Cargo.toml")).unwrap();
+    fs::write(
+        root.join("Cargo.toml"),
+        root_manifest.replace(
+            "pax-kit = { version = \"0.36.9\",",
+            "pax-kit = { default-features = false, version = \"0.36.9\",",
+        ),
+    )
+    .unwrap();
+    assert!(!project
+        .keeps_std_default_features(StdDependency::PaxKit)
+        .unwrap());
+
+    let standalone = CargoProject {
+        crate_root: root.join("crates/my-app"),
+        package_name: "my-app".to_string(),
+        workspace_root: None,
+    };
+    fs::write(
+        root.join("crates/my-app/Cargo.toml"),
+        "[package]\nname = \"my-app\"\n\n[dependencies]\npax-std = { version = \"0.36.9\", default-features = false }\n",
+    )
+    .unwrap();
+    assert_eq!(
+        standalone.std_dependency().unwrap(),
+        Some(StdDependency::PaxStd)
+    );
+    assert!(!standalone
+        .keeps_std_default_features(StdDependency::PaxStd)
+        .unwrap());
+}
+
+#[test]
+fn test_new_project_templates_compile_only_selected_std_families() {
+    let templates = env::current_dir().unwrap().join("files/new-project");
+    for template in fs::read_dir(&templates).unwrap() {
+        let template = template.unwrap().path();
+        let mut doc =
+            Document::from_str(&fs::read_to_string(template.join("Cargo.toml.template")).unwrap())
+                .unwrap();
+        update_pax_dependency_versions(&mut doc, "0.36.9");
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("Cargo.toml"), doc.to_string()).unwrap();
+        let project = CargoProject {
+            crate_root: temp_dir.path().to_path_buf(),
+            package_name: "CRATE_NAME".to_string(),
+            workspace_root: None,
+        };
+        assert_eq!(
+            project.std_dependency().unwrap(),
+            Some(StdDependency::PaxKit)
+        );
+        assert!(
+            !project
+                .keeps_std_default_features(StdDependency::PaxKit)
+                .unwrap(),
+            "{:?} keeps the default features of pax-kit",
+            template
+        );
+    }
+}
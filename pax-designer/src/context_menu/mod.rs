@@ -9,6 +9,8 @@ use crate::model;
 use crate::model::action::orm::group_ungroup::{
     GroupNodes, GroupSelected, GroupType, UngroupSelected,
 };
+use crate::model::action::orm::skeleton::PreviewSkeletonSelected;
+use crate::model::action::orm::snippets::{ExportSnippet, OpenSnippetLibrary};
 use crate::model::action::orm::tree_movement::{RelativeMove, RelativeMoveSelected};
 use crate::model::action::orm::SelectedIntoNewComponent;
 use crate::model::action::{Action, ActionContext};
@@ -129,6 +131,21 @@ impl DesignerContextMenu {
         self.move_relative(RelativeMove::BumpDown, ctx);
     }
 
+    pub fn export_snippet(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(&ExportSnippet, ctx);
+        self.close_menu();
+    }
+
+    pub fn import_snippet(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(&OpenSnippetLibrary, ctx);
+        self.close_menu();
+    }
+
+    pub fn preview_skeleton(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(&PreviewSkeletonSelected, ctx);
+        self.close_menu();
+    }
+
     fn move_relative(&self, relative_move: RelativeMove, ctx: &NodeContext) {
         model::perform_action(&RelativeMoveSelected { relative_move }, ctx);
     }
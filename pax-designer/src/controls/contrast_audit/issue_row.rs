@@ -0,0 +1,28 @@
+use pax_engine::api::*;
+use pax_engine::pax_manifest::{TemplateNodeId, TypeId, UniqueTemplateNodeIdentifier};
+use pax_engine::*;
+use pax_std::*;
+
+use crate::model;
+use crate::model::contrast::SelectContrastIssue;
+
+#[pax]
+#[engine_import_path("pax_engine")]
+#[file("controls/contrast_audit/issue_row.pax")]
+pub struct ContrastIssueRow {
+    pub component_id: Property<TypeId>,
+    pub node_id: Property<usize>,
+    pub name: Property<String>,
+    pub component: Property<String>,
+    pub ratio: Property<String>,
+}
+
+impl ContrastIssueRow {
+    pub fn select(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        let node = UniqueTemplateNodeIdentifier::build(
+            self.component_id.get(),
+            TemplateNodeId::build(self.node_id.get()),
+        );
+        model::perform_action(&SelectContrastIssue(node), ctx);
+    }
+}
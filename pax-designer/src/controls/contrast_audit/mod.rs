@@ -0,0 +1,115 @@
+use std::rc::Rc;
+
+use pax_engine::api::*;
+use pax_engine::pax_manifest::TypeId;
+use pax_engine::*;
+use pax_std::*;
+
+use crate::designer_node_type::DesignerNodeType;
+use crate::model;
+use crate::model::contrast::{self, ToggleContrastAudit};
+
+pub mod issue_row;
+use issue_row::ContrastIssueRow;
+
+/// Lists the text rendered in the component being edited, including text in the
+/// components it uses, with a contrast against its background failing WCAG AA.
+/// Clicking a node selects it. The audit runs when the panel opens, the edited
+/// component changes, or "Re-run" is clicked.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[file("controls/contrast_audit/mod.pax")]
+pub struct ContrastAuditPanel {
+    pub open: Property<bool>,
+    pub rows: Property<Vec<ContrastIssueRowData>>,
+    pub summary: Property<String>,
+    /// Bumped to run the audit again
+    pub runs: Property<usize>,
+}
+
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct ContrastIssueRowData {
+    pub component_id: TypeId,
+    pub node_id: usize,
+    /// Node type and id, e.g. "Text #4"
+    pub name: String,
+    /// Component the node is part of
+    pub component: String,
+    /// Contrast ratio, e.g. "2.9:1"
+    pub ratio: String,
+}
+
+impl ContrastAuditPanel {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        let (open, component) = model::read_app_state(|app_state| {
+            (
+                app_state.contrast_audit_open.clone(),
+                app_state.selected_component_id.clone(),
+            )
+        });
+        let deps = [open.untyped()];
+        let open_cp = open.clone();
+        self.open
+            .replace_with(Property::computed(move || open_cp.get(), &deps));
+
+        let runs = self.runs.clone();
+        let deps = [open.untyped(), component.untyped(), runs.untyped()];
+        let ctx = ctx.clone();
+        let dt = Rc::clone(&ctx.designtime);
+        self.rows.replace_with(Property::computed(
+            move || {
+                if !open.get() {
+                    return vec![];
+                }
+                let Some(root) = ctx.get_userland_root_expanded_node() else {
+                    return vec![];
+                };
+                let issues = contrast::audit(&ctx, &root);
+                let mut dt = borrow_mut!(dt);
+                let orm = dt.get_orm_mut();
+                issues
+                    .into_iter()
+                    .map(|issue| {
+                        let node_id = issue.node.get_template_node_id();
+                        let node_type = orm
+                            .get_node(issue.node.clone(), false)
+                            .map(|node| DesignerNodeType::from_type_id(node.get_type_id()))
+                            .unwrap_or(DesignerNodeType::Unregistered);
+                        ContrastIssueRowData {
+                            name: format!("{} #{}", node_type.metadata(orm).name, node_id),
+                            component: issue
+                                .node
+                                .get_containing_component_type_id()
+                                .get_pascal_identifier()
+                                .unwrap_or_default(),
+                            ratio: contrast::format_ratio(issue.ratio),
+                            component_id: issue.node.get_containing_component_type_id(),
+                            node_id: node_id.as_usize(),
+                        }
+                    })
+                    .collect()
+            },
+            &deps,
+        ));
+
+        let rows = self.rows.clone();
+        let deps = [rows.untyped()];
+        self.summary.replace_with(Property::computed(
+            move || match rows.read(Vec::len) {
+                0 => "No text fails AA".to_string(),
+                1 => "1 text node fails AA".to_string(),
+                n => format!("{} text nodes fail AA", n),
+            },
+            &deps,
+        ));
+    }
+
+    pub fn rerun(&mut self, _ctx: &NodeContext, _args: Event<Click>) {
+        self.runs.set(self.runs.get() + 1);
+    }
+
+    pub fn close(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(&ToggleContrastAudit, ctx);
+    }
+}
@@ -2,12 +2,13 @@ use std::cell::RefCell;
 use std::ops::ControlFlow;
 use std::rc::Rc;
 
-use model::action::orm::CreateComponent;
+use model::action::orm::{CreateComponent, ReplaceNodeType};
 use pax_engine::api::*;
 use pax_engine::math::Vector2;
 use pax_engine::node_layout::TransformAndBounds;
 use pax_engine::*;
 use pax_manifest::TypeId;
+use pax_std::core::image::ImageSource;
 use pax_std::*;
 
 use crate::controls::toolbar::SelectTool;
@@ -28,7 +29,7 @@ use crate::model::GlassNode;
 use crate::model::ToolBehavior;
 use math::Point2;
 
-use super::SetLibraryState;
+use super::{SetLibraryState, LIBRARY_STATE};
 
 #[pax]
 #[engine_import_path("pax_engine")]
@@ -44,6 +45,8 @@ pub struct ComponentLibraryItemData {
     pub file_path: String,
     pub type_id: TypeId,
     pub bounds_pixels: (f64, f64),
+    /// Preview of the component, `ImageSource::Empty` until it's been rendered
+    pub thumbnail: ImageSource,
 }
 
 struct DropComponent {
@@ -97,7 +100,7 @@ impl ToolBehavior for DropComponent {
                     designer_node_type: DesignerNodeType::from_type_id(self.type_id.clone()),
                 }
                 .perform(ctx)?;
-                SetLibraryState { open: false }.perform(ctx)?;
+                SetLibraryState::default().perform(ctx)?;
                 SelectNodes {
                     ids: &[uid.get_template_node_id()],
                     mode: SelectMode::DiscardOthers,
@@ -129,6 +132,18 @@ impl ToolBehavior for DropComponent {
 impl ComponentLibraryItem {
     pub fn on_down(&mut self, ctx: &NodeContext, _args: Event<MouseDown>) {
         let data = self.data.get();
+        // the library was opened to pick a replacement for a node, see `TreeMsg::ObjReplace`
+        if let Some(id) = LIBRARY_STATE.with(|state| state.get().replacing) {
+            model::perform_action(
+                &ReplaceNodeType {
+                    id,
+                    type_id: data.type_id.clone(),
+                },
+                ctx,
+            );
+            model::perform_action(&SetLibraryState::default(), ctx);
+            return;
+        }
         model::perform_action(
             &SetToolBehaviour(Some(Rc::new(RefCell::new(DropComponent {
                 type_id: data.type_id.clone(),
@@ -1,7 +1,8 @@
 use pax_designtime::DesigntimeManager;
 use pax_engine::api::*;
 use pax_engine::*;
-use pax_manifest::{PaxType, TypeId};
+use pax_manifest::{PaxType, TemplateNodeId, TypeId};
+use pax_std::core::image::ImageSource;
 use std::rc::Rc;
 
 use crate::model;
@@ -20,11 +21,15 @@ pub struct FileAndComponentPicker {
     pub registered_components: Property<Vec<ComponentLibraryItemData>>,
     pub library_active_toggle_image: Property<String>,
     pub current_selected_component: Property<String>,
+    pub manifest_loaded: Property<bool>,
 }
 
 #[derive(Clone, Default)]
 pub struct SetLibraryState {
     pub open: bool,
+    /// Node the picked component replaces, instead of being dropped onto the glass, see
+    /// `ReplaceNodeType`
+    pub replacing: Option<TemplateNodeId>,
 }
 
 impl Interpolatable for SetLibraryState {}
@@ -37,7 +42,7 @@ impl Action for SetLibraryState {
 }
 
 thread_local! {
-    static LIBRARY_STATE: Property<SetLibraryState> = Property::new(SetLibraryState { open: false });
+    static LIBRARY_STATE: Property<SetLibraryState> = Property::new(SetLibraryState::default());
 }
 
 impl FileAndComponentPicker {
@@ -46,6 +51,15 @@ impl FileAndComponentPicker {
         self.bind_library_active_toggle_image();
         self.bind_current_selected_component();
         self.bind_registered_components(ctx);
+        self.bind_manifest_loaded();
+    }
+
+    fn bind_manifest_loaded(&mut self) {
+        let manifest_loaded =
+            model::read_app_state_with_derived(|_, derived| derived.manifest_loaded.clone());
+        let deps = [manifest_loaded.untyped()];
+        self.manifest_loaded
+            .replace_with(Property::computed(move || manifest_loaded.get(), &deps));
     }
 
     fn bind_library_active(&mut self) {
@@ -88,11 +102,13 @@ impl FileAndComponentPicker {
         let selected_component =
             model::read_app_state(|app_state| app_state.selected_component_id.clone());
         let manifest_ver = borrow!(ctx.designtime).get_manifest_version();
+        let thumbnails_ver = borrow!(ctx.designtime).get_thumbnails_version();
 
         let deps = [
             library_active.untyped(),
             selected_component.untyped(),
             manifest_ver.untyped(),
+            thumbnails_ver.untyped(),
         ];
         self.registered_components.replace_with(Property::computed(
             move || {
@@ -100,13 +116,12 @@ impl FileAndComponentPicker {
                     return vec![];
                 }
 
-                let dt = borrow_mut!(dt);
-                let data = dt
-                    .get_orm()
-                    .get_components()
+                let mut dt = borrow_mut!(dt);
+                let components = dt.get_orm().get_components();
+                let data = components
                     .iter()
                     .filter_map(|type_id| {
-                        Self::get_component_data(&dt, type_id, &[selected_component.get()])
+                        Self::get_component_data(&mut dt, type_id, &[selected_component.get()])
                     })
                     .collect();
                 data
@@ -116,7 +131,7 @@ impl FileAndComponentPicker {
     }
 
     fn get_component_data(
-        dt: &DesigntimeManager,
+        dt: &mut DesigntimeManager,
         type_id: &TypeId,
         filter: &[TypeId],
     ) -> Option<ComponentLibraryItemData> {
@@ -136,11 +151,20 @@ impl FileAndComponentPicker {
             return None;
         }
 
+        let name = comp.type_id.get_pascal_identifier().unwrap_or_default();
+        let file_path = comp.module_path.clone();
+        let thumbnail = match dt.request_component_thumbnail(type_id) {
+            Some(thumbnail) => {
+                ImageSource::Data(thumbnail.width, thumbnail.height, thumbnail.rgba.clone())
+            }
+            None => ImageSource::Empty,
+        };
         Some(ComponentLibraryItemData {
-            name: comp.type_id.get_pascal_identifier().unwrap_or_default(),
-            file_path: comp.module_path.clone(),
-            type_id: comp.type_id.clone(),
+            name,
+            file_path,
+            type_id: type_id.clone(),
             bounds_pixels: (200.0, 200.0),
+            thumbnail,
         })
     }
 
@@ -148,6 +172,7 @@ impl FileAndComponentPicker {
         model::perform_action(
             &SetLibraryState {
                 open: !self.library_active.get(),
+                replacing: None,
             },
             ctx,
         );
@@ -0,0 +1,82 @@
+use pax_engine::api::*;
+use pax_engine::math::TransformParts;
+use pax_engine::node_layout::TransformAndBounds;
+use pax_engine::*;
+use pax_std::*;
+
+use crate::model;
+
+/// Read-only display of the layout the selected node was computed with, in
+/// world (project) pixels, as opposed to the authored values shown by the
+/// settings panel.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[file("controls/inspector/mod.pax")]
+pub struct InspectorPanel {
+    pub has_selection: Property<bool>,
+    pub fields: Property<Vec<InspectorField>>,
+}
+
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct InspectorField {
+    pub label: String,
+    pub value: String,
+}
+
+impl InspectorPanel {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        let (selection_state, glass_to_world) =
+            model::read_app_state_with_derived(|app_state, derived| {
+                (
+                    derived.selection_state.clone(),
+                    app_state.glass_to_world_transform.clone(),
+                )
+            });
+        // the layout of the selected node isn't observable from here, so the
+        // fields are recomputed whenever the manifest changes instead
+        let manifest_ver = borrow!(ctx.designtime).get_manifest_version();
+        let deps = [
+            selection_state.untyped(),
+            glass_to_world.untyped(),
+            manifest_ver.untyped(),
+        ];
+        self.fields.replace_with(Property::computed(
+            move || {
+                let selection = selection_state.get();
+                let [item] = selection.items.as_slice() else {
+                    return vec![];
+                };
+                let t_and_b = TransformAndBounds {
+                    transform: glass_to_world.get(),
+                    bounds: (1.0, 1.0),
+                } * item.transform_and_bounds.get();
+                let parts: TransformParts = t_and_b.transform.into();
+                let (width, height) = t_and_b.bounds;
+                [
+                    ("x", format!("{:.1}px", parts.origin.x)),
+                    ("y", format!("{:.1}px", parts.origin.y)),
+                    ("width", format!("{:.1}px", width)),
+                    ("height", format!("{:.1}px", height)),
+                    ("rotation", format!("{:.1}°", parts.rotation.to_degrees())),
+                    ("scale_x", format!("{:.1}%", parts.scale.x * 100.0)),
+                    ("scale_y", format!("{:.1}%", parts.scale.y * 100.0)),
+                ]
+                .into_iter()
+                .map(|(label, value)| InspectorField {
+                    label: label.to_string(),
+                    value,
+                })
+                .collect()
+            },
+            &deps,
+        ));
+
+        let fields = self.fields.clone();
+        let deps = [fields.untyped()];
+        self.has_selection.replace_with(Property::computed(
+            move || !fields.read(Vec::is_empty),
+            &deps,
+        ));
+    }
+}
@@ -0,0 +1,31 @@
+use pax_engine::api::*;
+use pax_engine::*;
+use pax_std::*;
+
+use crate::model;
+use crate::model::keymap::{ResetKeymap, StartKeymapCapture};
+
+#[pax]
+#[engine_import_path("pax_engine")]
+#[file("controls/keymap_settings/keymap_row.pax")]
+pub struct KeymapRow {
+    pub action_id: Property<String>,
+    pub name: Property<String>,
+    pub binding: Property<String>,
+    pub is_default: Property<bool>,
+}
+
+impl KeymapRow {
+    pub fn capture(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(
+            &StartKeymapCapture {
+                action_id: self.action_id.get(),
+            },
+            ctx,
+        );
+    }
+
+    pub fn reset(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(&ResetKeymap(Some(self.action_id.get())), ctx);
+    }
+}
@@ -0,0 +1,113 @@
+use pax_engine::api::*;
+use pax_engine::*;
+use pax_std::*;
+
+use crate::model;
+use crate::model::keymap::{
+    registered_actions, CancelKeymapCapture, ResetKeymap, SwapConflictingBindings,
+    ToggleKeymapSettings,
+};
+
+pub mod keymap_row;
+use keymap_row::KeymapRow;
+
+/// Lists every bindable action with its current bindings. Clicking a binding
+/// captures the next chord pressed as its new binding.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[file("controls/keymap_settings/mod.pax")]
+pub struct KeymapSettings {
+    pub open: Property<bool>,
+    pub rows: Property<Vec<KeymapRowData>>,
+    pub has_conflict: Property<bool>,
+    pub conflict_message: Property<String>,
+}
+
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct KeymapRowData {
+    pub action_id: String,
+    pub name: String,
+    pub binding: String,
+    pub is_default: bool,
+}
+
+impl KeymapSettings {
+    pub fn on_mount(&mut self, _ctx: &NodeContext) {
+        let (open, input_mapper, capture, conflict) = model::read_app_state(|app_state| {
+            (
+                app_state.keymap_settings_open.clone(),
+                app_state.input_mapper.clone(),
+                app_state.keymap_capture.clone(),
+                app_state.keymap_conflict.clone(),
+            )
+        });
+        let deps = [open.untyped()];
+        self.open
+            .replace_with(Property::computed(move || open.get(), &deps));
+
+        let deps = [input_mapper.untyped(), capture.untyped()];
+        self.rows.replace_with(Property::computed(
+            move || {
+                let keymap = input_mapper.get().keymap;
+                let capture = capture.get();
+                registered_actions()
+                    .into_iter()
+                    .map(|action| {
+                        let chords = keymap.chords(action.id);
+                        let binding = if capture.as_deref() == Some(action.id) {
+                            "Press keys...".to_string()
+                        } else if chords.is_empty() {
+                            "-".to_string()
+                        } else {
+                            chords
+                                .iter()
+                                .map(ToString::to_string)
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        };
+                        KeymapRowData {
+                            action_id: action.id.to_string(),
+                            name: action.name.to_string(),
+                            binding,
+                            is_default: keymap.is_default(action.id),
+                        }
+                    })
+                    .collect()
+            },
+            &deps,
+        ));
+
+        let deps = [conflict.untyped()];
+        let conflict_cp = conflict.clone();
+        self.has_conflict.replace_with(Property::computed(
+            move || conflict_cp.get().is_some(),
+            &deps,
+        ));
+        self.conflict_message.replace_with(Property::computed(
+            move || {
+                conflict
+                    .get()
+                    .map(|conflict| conflict.message())
+                    .unwrap_or_default()
+            },
+            &deps,
+        ));
+    }
+
+    pub fn close(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(&ToggleKeymapSettings, ctx);
+    }
+
+    pub fn reset_all(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(&ResetKeymap(None), ctx);
+    }
+
+    pub fn swap(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(&SwapConflictingBindings, ctx);
+    }
+
+    pub fn cancel(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(&CancelKeymapCapture, ctx);
+    }
+}
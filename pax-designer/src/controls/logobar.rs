@@ -1,3 +1,5 @@
+use std::rc::Rc;
+
 use pax_engine::api::*;
 use pax_engine::*;
 
@@ -5,14 +7,84 @@ use pax_std::*;
 
 use crate::model;
 use crate::model::action::orm::SerializeRequested;
+use crate::model::contrast::ToggleContrastAudit;
+use crate::model::keymap::ToggleKeymapSettings;
+use crate::model::performance::TogglePerformanceOverlay;
+use crate::model::session::{ExportBugReport, ToggleSessionRecording};
 
 #[pax]
 #[engine_import_path("pax_engine")]
 #[file("controls/logobar.pax")]
-pub struct Logobar {}
+pub struct Logobar {
+    /// The build-time defines of the project, e.g. `new_nav = true`, which the designer can't edit
+    pub defines_text: Property<String>,
+    /// Records the session, or once it's being recorded exports a bug report
+    pub bug_report_text: Property<String>,
+}
 
 impl Logobar {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        let dt = Rc::clone(&ctx.designtime);
+        let manifest_ver = borrow!(ctx.designtime).get_manifest_version();
+        let deps = [manifest_ver.untyped()];
+        self.defines_text.replace_with(Property::computed(
+            move || {
+                let dt = borrow!(dt);
+                let defines: Vec<_> = dt
+                    .get_manifest()
+                    .defines
+                    .iter()
+                    .map(|(key, value)| format!("{} = {}", key, value))
+                    .collect();
+                if defines.is_empty() {
+                    String::new()
+                } else {
+                    format!("Defines: {}", defines.join(", "))
+                }
+            },
+            &deps,
+        ));
+        let session_recording =
+            model::read_app_state(|app_state| app_state.session_recording.clone());
+        let deps = [session_recording.untyped()];
+        self.bug_report_text.replace_with(Property::computed(
+            move || {
+                if session_recording.get() {
+                    "Export bug report".to_string()
+                } else {
+                    "Record session".to_string()
+                }
+            },
+            &deps,
+        ));
+    }
+
     pub fn handle_logo_click(&mut self, ctx: &NodeContext, _args: Event<Click>) {
         model::perform_action(&SerializeRequested {}, ctx);
     }
+
+    pub fn handle_shortcuts_click(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(&ToggleKeymapSettings, ctx);
+    }
+
+    pub fn handle_performance_click(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(&TogglePerformanceOverlay, ctx);
+    }
+
+    pub fn handle_contrast_click(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(&ToggleContrastAudit, ctx);
+    }
+
+    pub fn handle_bug_report_click(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        if model::read_app_state(|app_state| app_state.session_recording.get()) {
+            model::perform_action(&ExportBugReport, ctx);
+        } else {
+            model::perform_action(
+                &ToggleSessionRecording {
+                    redact_strings: false,
+                },
+                ctx,
+            );
+        }
+    }
 }
@@ -1,6 +1,14 @@
+pub mod contrast_audit;
 pub mod file_and_component_picker;
+pub mod inspector;
+pub mod keymap_settings;
 pub mod logobar;
+pub mod performance;
+pub mod property_timeline;
+pub mod review_changes;
 pub mod settings;
+pub mod snippets;
+pub mod styles;
 pub mod tool_settings_views;
 pub mod toolbar;
 pub mod tree;
@@ -11,8 +19,11 @@ use pax_engine::*;
 use pax_std::*;
 
 use file_and_component_picker::FileAndComponentPicker;
+use inspector::InspectorPanel;
 use logobar::Logobar;
+use property_timeline::PropertyTimeline;
 use settings::Settings;
+use styles::StylesPanel;
 use tool_settings_views::paintbrush_settings_view::PaintbrushSettings;
 use toolbar::Toolbar;
 use tree::Tree;
@@ -24,6 +35,10 @@ use crate::model::{self, Tool};
 #[file("controls/mod.pax")]
 pub struct Controls {
     pub tool_with_tool_editor_selected: Property<bool>,
+    /// Height of the property timeline between the settings and the
+    /// inspector, zero unless property recording is on
+    pub timeline_height: Property<f64>,
+    pub styles_panel_open: Property<bool>,
 }
 
 impl Controls {
@@ -35,5 +50,16 @@ impl Controls {
                 move || selected_tool.get() == Tool::Paintbrush,
                 &deps,
             ));
+        let recording = model::read_app_state(|app_state| app_state.property_recording.clone());
+        let deps = [recording.untyped()];
+        self.timeline_height.replace_with(Property::computed(
+            move || if recording.get() { 300.0 } else { 0.0 },
+            &deps,
+        ));
+        let styles_panel_open =
+            model::read_app_state(|app_state| app_state.styles_panel_open.clone());
+        let deps = [styles_panel_open.untyped()];
+        self.styles_panel_open
+            .replace_with(Property::computed(move || styles_panel_open.get(), &deps));
     }
 }
@@ -0,0 +1,31 @@
+use pax_engine::api::*;
+use pax_engine::pax_manifest::TemplateNodeId;
+use pax_engine::*;
+use pax_std::*;
+
+use crate::model;
+use crate::model::action::world::{SelectMode, SelectNodes};
+
+#[pax]
+#[engine_import_path("pax_engine")]
+#[file("controls/performance/hot_node_row.pax")]
+pub struct HotNodeRow {
+    pub node_id: Property<usize>,
+    pub name: Property<String>,
+    pub total: Property<String>,
+    pub breakdown: Property<String>,
+    pub instances: Property<String>,
+    pub color: Property<Color>,
+}
+
+impl HotNodeRow {
+    pub fn select(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(
+            &SelectNodes {
+                ids: &[TemplateNodeId::build(self.node_id.get())],
+                mode: SelectMode::DiscardOthers,
+            },
+            ctx,
+        );
+    }
+}
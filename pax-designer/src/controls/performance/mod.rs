@@ -0,0 +1,112 @@
+use std::rc::Rc;
+
+use pax_engine::api::*;
+use pax_engine::*;
+use pax_std::*;
+
+use crate::designer_node_type::DesignerNodeType;
+use crate::model;
+use crate::model::performance::{heat_color, TogglePerformanceOverlay};
+
+pub mod hot_node_row;
+use hot_node_row::HotNodeRow;
+
+/// Lists the most expensive nodes of the component being edited while the
+/// performance overlay is open, with the time spent on them per frame.
+/// Clicking a node selects it.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[file("controls/performance/mod.pax")]
+pub struct PerformancePanel {
+    pub open: Property<bool>,
+    pub rows: Property<Vec<HotNodeRowData>>,
+    /// Whether no node costs have been sampled yet
+    pub waiting: Property<bool>,
+}
+
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct HotNodeRowData {
+    pub node_id: usize,
+    pub name: String,
+    /// Total time per frame, e.g. "1.25 ms"
+    pub total: String,
+    /// Time per frame split up into properties / layout / render
+    pub breakdown: String,
+    /// Number of expanded instances, e.g. "400x"
+    pub instances: String,
+    /// Tint of the node on the glass
+    pub color: Color,
+}
+
+impl PerformancePanel {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        let (open, component) = model::read_app_state(|app_state| {
+            (
+                app_state.performance_overlay_open.clone(),
+                app_state.selected_component_id.clone(),
+            )
+        });
+        let deps = [open.untyped()];
+        let open_cp = open.clone();
+        self.open
+            .replace_with(Property::computed(move || open_cp.get(), &deps));
+
+        let costs_version = borrow!(ctx.designtime).get_node_costs_version();
+        let deps = [open.untyped(), component.untyped(), costs_version.untyped()];
+        let dt = Rc::clone(&ctx.designtime);
+        self.rows.replace_with(Property::computed(
+            move || {
+                if !open.get() {
+                    return vec![];
+                }
+                let mut dt = borrow_mut!(dt);
+                let hot_nodes = dt.get_hot_nodes(&component.get());
+                let max_cost = hot_nodes
+                    .first()
+                    .map(|n| n.cost.total_us())
+                    .unwrap_or_default()
+                    .max(f64::EPSILON);
+                let orm = dt.get_orm_mut();
+                hot_nodes
+                    .into_iter()
+                    .map(|hot_node| {
+                        let node_id = hot_node.uni.get_template_node_id();
+                        let node_type = orm
+                            .get_node(hot_node.uni.clone(), false)
+                            .map(|node| DesignerNodeType::from_type_id(node.get_type_id()))
+                            .unwrap_or(DesignerNodeType::Unregistered);
+                        let cost = hot_node.cost;
+                        HotNodeRowData {
+                            node_id: node_id.as_usize(),
+                            name: format!("{} #{}", node_type.metadata(orm).name, node_id),
+                            total: format_ms(cost.total_us()),
+                            breakdown: format!(
+                                "{} / {} / {}",
+                                format_ms(cost.properties_us),
+                                format_ms(cost.layout_us),
+                                format_ms(cost.render_us)
+                            ),
+                            instances: format!("{}x", cost.instances),
+                            color: heat_color(cost.total_us() / max_cost),
+                        }
+                    })
+                    .collect()
+            },
+            &deps,
+        ));
+
+        let rows = self.rows.clone();
+        let deps = [rows.untyped()];
+        self.waiting
+            .replace_with(Property::computed(move || rows.read(Vec::is_empty), &deps));
+    }
+
+    pub fn close(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(&TogglePerformanceOverlay, ctx);
+    }
+}
+
+fn format_ms(us: f64) -> String {
+    format!("{:.2} ms", us / 1000.0)
+}
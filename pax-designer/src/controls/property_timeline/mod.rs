@@ -0,0 +1,357 @@
+use std::rc::Rc;
+
+use pax_designtime::property_recorder::{NodeRecording, RecordedFrame};
+use pax_engine::api::*;
+use pax_engine::math::{Transform2, TransformParts};
+use pax_engine::node_layout::TransformAndBounds;
+use pax_engine::pax_manifest::UniqueTemplateNodeIdentifier;
+use pax_engine::*;
+use pax_std::*;
+
+use crate::designer_node_type::DesignerNodeType;
+use crate::math::coordinate_spaces::World;
+use crate::model::property_recording::TogglePinRecordedNode;
+use crate::model::{self, ProjectMode};
+
+pub mod timeline_row;
+use timeline_row::TimelineRow;
+
+/// Sparklines are drawn through at most this many of the recorded frames
+const SPARKLINE_POINTS: usize = 120;
+
+/// Timeline of the property values recorded of the selected node while
+/// property recording is on, see `model::property_recording`. Scrubbing shows
+/// the values at a recorded frame, numeric values are drawn as sparklines and
+/// the frames a value changed on are tick-marked.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[file("controls/property_timeline/mod.pax")]
+pub struct PropertyTimeline {
+    pub open: Property<bool>,
+    pub node_name: Property<String>,
+    pub status: Property<String>,
+    pub has_node: Property<bool>,
+    pub pin_label: Property<String>,
+    /// Index into the recorded frames of the frame shown, the most recent one
+    /// when at `scrub_max`
+    pub scrub: Property<f64>,
+    pub scrub_max: Property<f64>,
+    pub rows: Property<Vec<TimelineRowData>>,
+}
+
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct TimelineRowData {
+    pub name: String,
+    /// Value at the frame scrubbed to
+    pub value: String,
+    /// Empty if the value isn't numeric
+    pub sparkline: Vec<PathElement>,
+    /// A vertical line at each frame the value changed on
+    pub ticks: Vec<PathElement>,
+    /// Horizontal position of the frame scrubbed to, in percent
+    pub cursor: f64,
+}
+
+impl PropertyTimeline {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        let (open, component, node_ids, project_mode, glass_to_world, to_glass) =
+            model::read_app_state_with_derived(|app_state, derived| {
+                (
+                    app_state.property_recording.clone(),
+                    app_state.selected_component_id.clone(),
+                    app_state.selected_template_node_ids.clone(),
+                    app_state.project_mode.clone(),
+                    app_state.glass_to_world_transform.clone(),
+                    derived.to_glass_transform.clone(),
+                )
+            });
+        let deps = [open.untyped()];
+        let open_cp = open.clone();
+        self.open
+            .replace_with(Property::computed(move || open_cp.get(), &deps));
+
+        let deps = [component.untyped(), node_ids.untyped()];
+        let selected = Property::computed(
+            move || match node_ids.get().as_slice() {
+                [id] => Some(UniqueTemplateNodeIdentifier::build(
+                    component.get(),
+                    id.clone(),
+                )),
+                _ => None,
+            },
+            &deps,
+        );
+        let deps = [selected.untyped()];
+        let selected_cp = selected.clone();
+        self.has_node.replace_with(Property::computed(
+            move || selected_cp.get().is_some(),
+            &deps,
+        ));
+
+        let dt = Rc::clone(&ctx.designtime);
+        let selected_cp = selected.clone();
+        self.node_name.replace_with(Property::computed(
+            move || {
+                let Some(uni) = selected_cp.get() else {
+                    return String::new();
+                };
+                let mut dt = borrow_mut!(dt);
+                let orm = dt.get_orm_mut();
+                let node_type = orm
+                    .get_node(uni.clone(), false)
+                    .map(|node| DesignerNodeType::from_type_id(node.get_type_id()))
+                    .unwrap_or(DesignerNodeType::Unregistered);
+                format!(
+                    "{} #{}",
+                    node_type.metadata(orm).name,
+                    uni.get_template_node_id()
+                )
+            },
+            &deps,
+        ));
+
+        let version = borrow!(ctx.designtime).get_property_recordings_version();
+        let deps = [selected.untyped(), version.untyped()];
+        let dt = Rc::clone(&ctx.designtime);
+        let selected_cp = selected.clone();
+        self.pin_label.replace_with(Property::computed(
+            move || {
+                let pinned = selected_cp
+                    .get()
+                    .is_some_and(|uni| borrow!(dt).is_recorded_node_pinned(&uni));
+                match pinned {
+                    true => "Unpin".to_string(),
+                    false => "Pin".to_string(),
+                }
+            },
+            &deps,
+        ));
+
+        let deps = [version.untyped()];
+        let dt = Rc::clone(&ctx.designtime);
+        self.scrub_max.replace_with(Property::computed(
+            move || {
+                borrow!(dt)
+                    .get_property_recording_capacity()
+                    .saturating_sub(1) as f64
+            },
+            &deps,
+        ));
+        // follow the most recent frame until scrubbed
+        self.scrub.set(self.scrub_max.get());
+
+        let scrub = self.scrub.clone();
+        let deps = [
+            open.untyped(),
+            selected.untyped(),
+            version.untyped(),
+            scrub.untyped(),
+            project_mode.untyped(),
+        ];
+        let dt = Rc::clone(&ctx.designtime);
+        let selected_cp = selected.clone();
+        self.status.replace_with(Property::computed(
+            move || {
+                if !open.get() {
+                    return String::new();
+                }
+                let Some(uni) = selected_cp.get() else {
+                    return "Select a node to record its properties".to_string();
+                };
+                let dt = borrow!(dt);
+                let playing = matches!(project_mode.get(), ProjectMode::Playing);
+                let Some(recording) = dt.get_node_recording(&uni).filter(|r| !r.is_empty()) else {
+                    return match playing {
+                        true => "Recording...".to_string(),
+                        false => "Play the project to record".to_string(),
+                    };
+                };
+                let index = shown_frame_index(recording, scrub.get());
+                let frames_ago = recording.len() - 1 - index;
+                let frame = recording.frames()[index].frame;
+                let paused = if playing { "" } else { " (paused)" };
+                format!("Frame {frame}, {frames_ago} frames ago{paused}")
+            },
+            &deps,
+        ));
+
+        let scrub = self.scrub.clone();
+        let deps = [
+            selected.untyped(),
+            version.untyped(),
+            scrub.untyped(),
+            glass_to_world.untyped(),
+            to_glass.untyped(),
+        ];
+        let dt = Rc::clone(&ctx.designtime);
+        self.rows.replace_with(Property::computed(
+            move || {
+                let Some(uni) = selected.get() else {
+                    return vec![];
+                };
+                let dt = borrow!(dt);
+                let Some(recording) = dt.get_node_recording(&uni).filter(|r| !r.is_empty()) else {
+                    return vec![];
+                };
+                let to_world = glass_to_world.get() * to_glass.get().get();
+                timeline_rows(
+                    recording,
+                    shown_frame_index(recording, scrub.get()),
+                    dt.get_property_recording_capacity(),
+                    to_world,
+                )
+            },
+            &deps,
+        ));
+    }
+
+    pub fn toggle_pin(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        let uni = model::read_app_state(|app_state| {
+            match app_state.selected_template_node_ids.get().as_slice() {
+                [id] => Some(UniqueTemplateNodeIdentifier::build(
+                    app_state.selected_component_id.get(),
+                    id.clone(),
+                )),
+                _ => None,
+            }
+        });
+        if let Some(uni) = uni {
+            model::perform_action(&TogglePinRecordedNode(uni), ctx);
+        }
+    }
+}
+
+/// Index into the recorded frames of the frame scrubbed to
+fn shown_frame_index(recording: &NodeRecording, scrub: f64) -> usize {
+    (scrub.max(0.0) as usize).min(recording.len() - 1)
+}
+
+/// Computed layout of a node in world (project) pixels
+type Layout = (TransformParts, (f64, f64));
+
+/// Name, value and unit of a part of the computed layout
+type LayoutField = (&'static str, fn(&Layout) -> f64, &'static str);
+
+/// Rows for the computed layout in world (project) pixels, as shown by the
+/// inspector, followed by one row per recorded property
+fn timeline_rows(
+    recording: &NodeRecording,
+    index: usize,
+    capacity: usize,
+    to_world: Transform2<Window, World>,
+) -> Vec<TimelineRowData> {
+    let frames = recording.frames();
+    let layouts: Vec<_> = frames.iter().map(|f| world_layout(f, to_world)).collect();
+    let layout_fields: [LayoutField; 5] = [
+        ("layout x", |(parts, _)| parts.origin.x, "px"),
+        ("layout y", |(parts, _)| parts.origin.y, "px"),
+        ("layout width", |(_, bounds)| bounds.0, "px"),
+        ("layout height", |(_, bounds)| bounds.1, "px"),
+        (
+            "layout rotation",
+            |(parts, _)| parts.rotation.to_degrees(),
+            "°",
+        ),
+    ];
+    let mut rows: Vec<_> = layout_fields
+        .into_iter()
+        .map(|(name, field, unit)| {
+            let series: Vec<_> = layouts.iter().map(|l| Some(field(l))).collect();
+            let ticks = (1..series.len())
+                .filter(|&i| series[i] != series[i - 1])
+                .collect();
+            row(
+                name,
+                format!("{:.1}{}", field(&layouts[index]), unit),
+                &series,
+                ticks,
+                index,
+                capacity,
+            )
+        })
+        .collect();
+    rows.extend(recording.property_names().into_iter().map(|name| {
+        let value = frames[index]
+            .properties
+            .get(&name)
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        let series = recording.numeric_series(&name);
+        let ticks = recording.discontinuities(&name);
+        row(&name, value, &series, ticks, index, capacity)
+    }));
+    rows
+}
+
+fn world_layout(frame: &RecordedFrame, to_world: Transform2<Window, World>) -> Layout {
+    let t_and_b = TransformAndBounds {
+        transform: to_world,
+        bounds: (1.0, 1.0),
+    } * TransformAndBounds::<NodeLocal, Window> {
+        transform: Transform2::new(frame.bounds.transform),
+        bounds: frame.bounds.bounds,
+    };
+    (t_and_b.transform.into(), t_and_b.bounds)
+}
+
+/// Frames are laid out over the full capacity of the recording, so the
+/// timeline fills up from the left while recording starts
+fn row(
+    name: &str,
+    value: String,
+    series: &[Option<f64>],
+    ticks: Vec<usize>,
+    index: usize,
+    capacity: usize,
+) -> TimelineRowData {
+    let x = |i: usize| Size::Percent((100.0 * i as f64 / (capacity.max(2) - 1) as f64).into());
+    TimelineRowData {
+        name: name.to_string(),
+        value,
+        sparkline: sparkline(series, x),
+        ticks: ticks
+            .into_iter()
+            .flat_map(|i| {
+                [
+                    PathElement::Point(x(i), Size::Percent(0.0.into())),
+                    PathElement::Line,
+                    PathElement::Point(x(i), Size::Percent(100.0.into())),
+                ]
+            })
+            .collect(),
+        cursor: 100.0 * index as f64 / (capacity.max(2) - 1) as f64,
+    }
+}
+
+/// Line through the numeric values of `series`, scaled to fill the height of
+/// the row, broken where values are missing
+fn sparkline(series: &[Option<f64>], x: impl Fn(usize) -> Size) -> Vec<PathElement> {
+    let (min, max) = series
+        .iter()
+        .flatten()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &v| {
+            (min.min(v), max.max(v))
+        });
+    if min > max {
+        return vec![];
+    }
+    let range = (max - min).max(f64::EPSILON);
+    let y = |v: f64| Size::Percent((90.0 - 80.0 * (v - min) / range).into());
+    let step = series.len().div_ceil(SPARKLINE_POINTS).max(1);
+    let mut elements = vec![];
+    let mut drawing = false;
+    for (i, value) in series.iter().enumerate().step_by(step) {
+        match value {
+            Some(v) => {
+                if drawing {
+                    elements.push(PathElement::Line);
+                }
+                elements.push(PathElement::Point(x(i), y(*v)));
+                drawing = true;
+            }
+            None => drawing = false,
+        }
+    }
+    elements
+}
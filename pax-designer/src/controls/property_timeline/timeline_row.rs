@@ -0,0 +1,14 @@
+use pax_engine::api::*;
+use pax_engine::*;
+use pax_std::*;
+
+#[pax]
+#[engine_import_path("pax_engine")]
+#[file("controls/property_timeline/timeline_row.pax")]
+pub struct TimelineRow {
+    pub name: Property<String>,
+    pub value: Property<String>,
+    pub sparkline: Property<Vec<PathElement>>,
+    pub ticks: Property<Vec<PathElement>>,
+    pub cursor: Property<f64>,
+}
@@ -0,0 +1,13 @@
+use pax_engine::api::*;
+use pax_engine::*;
+use pax_std::*;
+
+/// A line of the review changes panel, either naming what the following
+/// changes apply to, or one change
+#[pax]
+#[engine_import_path("pax_engine")]
+#[file("controls/review_changes/change_row.pax")]
+pub struct ChangeRow {
+    pub text: Property<String>,
+    pub is_subject: Property<bool>,
+}
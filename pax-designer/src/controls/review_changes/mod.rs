@@ -0,0 +1,97 @@
+use std::rc::Rc;
+
+use pax_engine::api::*;
+use pax_engine::*;
+use pax_std::*;
+
+use crate::model;
+use crate::model::action::orm::ToggleReviewChanges;
+
+pub mod change_row;
+use change_row::ChangeRow;
+
+/// Lists the changes made to the component being edited since the project
+/// was loaded, grouped by the node, selector or handler they apply to. Edits
+/// are saved as they're made, so this is the session's changes rather than
+/// the unsaved ones.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[file("controls/review_changes/mod.pax")]
+pub struct ReviewChangesPanel {
+    pub open: Property<bool>,
+    pub rows: Property<Vec<ChangeRowData>>,
+    pub summary: Property<String>,
+}
+
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct ChangeRowData {
+    /// A node, selector or handler, e.g. "<Rectangle> #3", or a change to
+    /// it, e.g. "fill: BLUE → RED"
+    pub text: String,
+    pub is_subject: bool,
+}
+
+impl ReviewChangesPanel {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        let (open, component) = model::read_app_state(|app_state| {
+            (
+                app_state.review_changes_open.clone(),
+                app_state.selected_component_id.clone(),
+            )
+        });
+        let deps = [open.untyped()];
+        let open_cp = open.clone();
+        self.open
+            .replace_with(Property::computed(move || open_cp.get(), &deps));
+
+        let dt = Rc::clone(&ctx.designtime);
+        let manifest_ver = borrow!(ctx.designtime).get_manifest_version();
+        let deps = [open.untyped(), component.untyped(), manifest_ver.untyped()];
+        let diff = Property::computed(
+            move || {
+                if !open.get() {
+                    return Default::default();
+                }
+                borrow!(dt)
+                    .get_orm()
+                    .diff_against_loaded(&component.get())
+                    .unwrap_or_default()
+            },
+            &deps,
+        );
+
+        let diff_cp = diff.clone();
+        let deps = [diff.untyped()];
+        self.rows.replace_with(Property::computed(
+            move || {
+                let mut rows = vec![];
+                for (subject, changes) in diff_cp.get().describe_grouped() {
+                    rows.push(ChangeRowData {
+                        text: subject,
+                        is_subject: true,
+                    });
+                    rows.extend(changes.into_iter().map(|text| ChangeRowData {
+                        text,
+                        is_subject: false,
+                    }));
+                }
+                rows
+            },
+            &deps,
+        ));
+
+        self.summary.replace_with(Property::computed(
+            move || match diff.get().change_count() {
+                0 => "No changes".to_string(),
+                1 => "1 change".to_string(),
+                n => format!("{} changes", n),
+            },
+            &deps,
+        ));
+    }
+
+    pub fn close(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(&ToggleReviewChanges, ctx);
+    }
+}
@@ -3,6 +3,8 @@ use pax_engine::*;
 use pax_manifest::*;
 use std::collections::HashMap;
 
+use crate::model::property_recording::TogglePropertyRecording;
+use crate::model::styles::OpenStyles;
 use crate::{designer_node_type::DesignerNodeType, model};
 use pax_std::*;
 
@@ -11,9 +13,11 @@ pub mod color_picker;
 pub mod control_flow_for_editor;
 pub mod control_flow_if_editor;
 pub mod property_editor;
+pub mod property_source;
 use control_flow_for_editor::ControlFlowForEditor;
 use control_flow_if_editor::ControlFlowIfEditor;
 use property_editor::PropertyEditor;
+use property_source::PropertySource;
 
 #[pax]
 #[engine_import_path("pax_engine")]
@@ -27,6 +31,9 @@ pub struct Settings {
     pub custom_properties_total_height: Property<f64>,
     pub stid: Property<TypeId>,
     pub snid: Property<TemplateNodeId>,
+    pub manifest_loaded: Property<bool>,
+    /// Filled while property values are recorded, see `model::property_recording`
+    pub record_toggle_fill: Property<Color>,
 }
 
 #[pax]
@@ -53,7 +60,17 @@ impl Settings {
             self.bind_stid(&app_state);
             self.bind_custom_properties(ctx);
             self.bind_custom_properties_total_height();
+            self.bind_record_toggle_fill(app_state);
         });
+        self.bind_manifest_loaded();
+    }
+
+    fn bind_manifest_loaded(&mut self) {
+        let manifest_loaded =
+            model::read_app_state_with_derived(|_, derived| derived.manifest_loaded.clone());
+        let deps = [manifest_loaded.untyped()];
+        self.manifest_loaded
+            .replace_with(Property::computed(move || manifest_loaded.get(), &deps));
     }
 
     fn bind_selected(&mut self, app_state: &model::AppState, ctx: &NodeContext) {
@@ -119,6 +136,26 @@ impl Settings {
             ));
     }
 
+    fn bind_record_toggle_fill(&mut self, app_state: &model::AppState) {
+        let recording = app_state.property_recording.clone();
+        let deps = [recording.untyped()];
+        self.record_toggle_fill.replace_with(Property::computed(
+            move || match recording.get() {
+                true => Color::rgb(230.into(), 60.into(), 60.into()),
+                false => Color::TRANSPARENT,
+            },
+            &deps,
+        ));
+    }
+
+    pub fn toggle_property_recording(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(&TogglePropertyRecording, ctx);
+    }
+
+    pub fn open_styles(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(&OpenStyles(None), ctx);
+    }
+
     fn bind_snid(&mut self, app_state: &model::AppState) {
         let stnids = app_state.selected_template_node_ids.clone();
         let deps = [stnids.untyped()];
@@ -152,8 +189,7 @@ impl Settings {
         // Here, AREAS_PROP and the above custom prop definition is being combined to what
         // actually gets rendered
         let areas = AREAS_PROP.with(|p| p.clone());
-        let adjusted_custom_props =
-            self.adjust_custom_props_positions(custom_props_default_position, areas);
+        let adjusted_custom_props = layout_property_areas(custom_props_default_position, areas);
 
         self.custom_properties.replace_with(adjusted_custom_props);
     }
@@ -185,6 +221,7 @@ impl Settings {
                 let uni = UniqueTemplateNodeIdentifier::build(stid.get(), snid.get());
                 let mut dt = borrow_mut!(ctx.designtime);
 
+                let mut is_container = false;
                 if let Some(node) = dt
                     .get_orm()
                     .get_component(&stid.get())
@@ -192,6 +229,9 @@ impl Settings {
                     .and_then(|c| c.template.as_ref())
                     .and_then(|t| t.get_node(&snid.get()))
                 {
+                    is_container = DesignerNodeType::from_type_id(node.type_id.clone())
+                        .metadata(dt.get_orm())
+                        .is_container;
                     selected_component_name.set(
                         node.type_id
                             .get_pascal_identifier()
@@ -224,7 +264,9 @@ impl Settings {
                                 | "skew_y"
                                 | "id"
                                 | "transform"
-                        ) || prop_def.name.starts_with('_'))
+                        ) || prop_def.name.starts_with('_')
+                            // text style defaults only apply to descendants
+                            || (prop_def.name == "text_style" && !is_container))
                     })
                     .enumerate()
                     .map(|(i, (propdef, _))| PropertyArea {
@@ -239,28 +281,29 @@ impl Settings {
             &deps,
         )
     }
+}
 
-    fn adjust_custom_props_positions(
-        &self,
-        custom_props: Property<Vec<PropertyArea>>,
-        areas: Property<Vec<f64>>,
-    ) -> Property<Vec<PropertyArea>> {
-        let deps = [custom_props.untyped(), areas.untyped()];
-        Property::computed(
-            move || {
-                let mut adjusted_props = custom_props.get();
-                let areas = areas.get();
-                let mut running_sum = 0.0;
-                for prop in &mut adjusted_props {
-                    let area = areas.get(prop.index - 1).unwrap_or(&10.0);
-                    prop.vertical_space = *area - 40.0;
-                    prop.vertical_pos = running_sum;
-                    running_sum += area + SPACING;
-                }
-                let res = adjusted_props.into_iter().rev().collect();
-                res
-            },
-            &deps,
-        )
-    }
+/// Stacks the property editors of `custom_props` with the heights they report
+/// in `areas`, see `AREAS_PROP`
+pub(crate) fn layout_property_areas(
+    custom_props: Property<Vec<PropertyArea>>,
+    areas: Property<Vec<f64>>,
+) -> Property<Vec<PropertyArea>> {
+    let deps = [custom_props.untyped(), areas.untyped()];
+    Property::computed(
+        move || {
+            let mut adjusted_props = custom_props.get();
+            let areas = areas.get();
+            let mut running_sum = 0.0;
+            for prop in &mut adjusted_props {
+                let area = areas.get(prop.index - 1).unwrap_or(&10.0);
+                prop.vertical_space = *area - 40.0;
+                prop.vertical_pos = running_sum;
+                running_sum += area + SPACING;
+            }
+            let res = adjusted_props.into_iter().rev().collect();
+            res
+        },
+        &deps,
+    )
 }
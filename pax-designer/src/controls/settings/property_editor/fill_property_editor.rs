@@ -3,6 +3,8 @@ use pax_engine::*;
 
 use crate::controls::settings::color_picker::ColorPicker;
 use crate::controls::settings::AREAS_PROP;
+use crate::model;
+use crate::model::action::orm::gradient::ToggleGradientEditing;
 
 use super::PropertyEditorData;
 
@@ -16,6 +18,8 @@ pub struct FillPropertyEditor {
     pub color: Property<Color>,
     pub external: Property<bool>,
     pub property_listener: Property<bool>,
+    pub is_gradient: Property<bool>,
+    pub editing_on_canvas: Property<bool>,
 }
 
 impl FillPropertyEditor {
@@ -31,6 +35,27 @@ impl FillPropertyEditor {
                 });
             });
         }
+        let data = self.data.clone();
+        let deps = [data.untyped()];
+        let ctxc = ctx.clone();
+        self.is_gradient.replace_with(Property::computed(
+            move || {
+                let value = pax_engine::pax_lang::from_pax(&data.get().get_value_as_str(&ctxc));
+                value
+                    .ok()
+                    .and_then(|value| Fill::try_coerce(value).ok())
+                    .is_some_and(|fill| !matches!(fill, Fill::Solid(_)))
+            },
+            &deps,
+        ));
+        let gradient_editing =
+            model::read_app_state(|app_state| app_state.gradient_editing.clone());
+        let deps = [gradient_editing.untyped()];
+        self.editing_on_canvas.replace_with(Property::computed(
+            move || gradient_editing.get().is_some(),
+            &deps,
+        ));
+
         let data = self.data.clone();
         let deps = [data.untyped()];
         let ctxc = ctx.clone();
@@ -53,10 +78,12 @@ impl FillPropertyEditor {
         let external = self.external.clone();
         let data = self.data.clone();
         let ctxc = ctx.clone();
+        let is_gradient = self.is_gradient.clone();
         self.property_listener.replace_with(Property::computed(
             move || {
                 let color = color.get();
-                if !external.get() {
+                // the picker shows solid colors only, don't let it clobber a gradient
+                if !external.get() && !is_gradient.get() {
                     let col_str = color_to_str(color);
                     if let Err(e) = data.get().set_value(&ctxc, &col_str) {
                         log::warn!("failed to set fill color: {e}");
@@ -72,6 +99,10 @@ impl FillPropertyEditor {
     pub fn pre_render(&mut self, _ctx: &NodeContext) {
         self.property_listener.get();
     }
+
+    pub fn toggle_edit_on_canvas(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(&ToggleGradientEditing, ctx);
+    }
 }
 
 pub fn color_to_str(color: Color) -> String {
@@ -85,3 +116,7 @@ pub fn color_to_str(color: Color) -> String {
     );
     col_str
 }
+
+pub fn fill_to_str(fill: &Fill) -> String {
+    fill.clone().to_pax_value().to_string()
+}
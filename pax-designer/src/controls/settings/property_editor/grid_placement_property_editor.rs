@@ -0,0 +1,111 @@
+use pax_engine::api::*;
+use pax_engine::*;
+use pax_manifest::*;
+use pax_std::*;
+
+use crate::controls::settings::AREAS_PROP;
+
+use super::PropertyEditorData;
+
+/// Edits the `grid_column`/`grid_row` of a `GridItem` as a start line and a span, either left
+/// empty for the defaults: the next free cell, spanning one track
+#[pax]
+#[engine_import_path("pax_engine")]
+#[file("controls/settings/property_editor/grid_placement_property_editor.pax")]
+pub struct GridPlacementPropertyEditor {
+    pub data: Property<PropertyEditorData>,
+
+    // All the below props should be private: never set by user, used for internal state
+    pub placement: Property<GridPlacement>,
+    pub start: Property<String>,
+    pub span: Property<String>,
+}
+
+impl GridPlacementPropertyEditor {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        let index = self.data.get().editor_index;
+        if index != 0 {
+            AREAS_PROP.with(|areas| {
+                areas.update(|areas| {
+                    while areas.len() <= index {
+                        areas.push(0.0)
+                    }
+                    areas[index - 1] = 70.0;
+                });
+            });
+        }
+        let data = self.data.clone();
+        let deps = [data.untyped()];
+        let ctx = ctx.clone();
+        self.placement.replace_with(Property::computed(
+            move || match data.get().get_value(&ctx) {
+                Some(ValueDefinition::LiteralValue(PaxValue::String(value))) => {
+                    GridPlacement::parse(&value).unwrap_or_default()
+                }
+                _ => GridPlacement::default(),
+            },
+            &deps,
+        ));
+
+        let placement = self.placement.clone();
+        let deps = [placement.untyped()];
+        self.start.replace_with(Property::computed(
+            move || {
+                placement
+                    .get()
+                    .start
+                    .map(|start| (start + 1).to_string())
+                    .unwrap_or_default()
+            },
+            &deps,
+        ));
+        let placement = self.placement.clone();
+        self.span.replace_with(Property::computed(
+            move || match placement.get().span {
+                1 => String::new(),
+                span => span.to_string(),
+            },
+            &deps,
+        ));
+    }
+
+    pub fn change_start(&mut self, ctx: &NodeContext, event: Event<TextboxChange>) {
+        let placement = self.placement.get();
+        let start = match event.text.trim() {
+            "" => None,
+            line => match line.parse::<usize>() {
+                Ok(line) if line >= 1 => Some(line - 1),
+                _ => {
+                    log::warn!("grid start line has to be a number from 1");
+                    return;
+                }
+            },
+        };
+        self.write(ctx, GridPlacement { start, ..placement });
+    }
+
+    pub fn change_span(&mut self, ctx: &NodeContext, event: Event<TextboxChange>) {
+        let placement = self.placement.get();
+        let span = match event.text.trim() {
+            "" => 1,
+            span => match span.parse::<usize>() {
+                Ok(span) if span >= 1 => span,
+                _ => {
+                    log::warn!("grid span has to be a number from 1");
+                    return;
+                }
+            },
+        };
+        self.write(ctx, GridPlacement { span, ..placement });
+    }
+
+    fn write(&self, ctx: &NodeContext, placement: GridPlacement) {
+        let value = match placement.to_placement_string() {
+            value if value.is_empty() => value,
+            value => format!("\"{}\"", value),
+        };
+        if let Err(e) = self.data.get().set_value(ctx, &value) {
+            log::warn!("failed to write grid placement: {e}");
+        }
+    }
+}
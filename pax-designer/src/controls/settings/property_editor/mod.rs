@@ -12,18 +12,22 @@ pub mod color_property_editor;
 pub mod corner_radii_property_editor;
 pub mod direction_property_editor;
 pub mod fill_property_editor;
+pub mod grid_placement_property_editor;
 pub mod stroke_property_editor;
 pub mod text_property_editor;
 pub mod text_style_property_editor;
+pub mod track_list_property_editor;
 
 use border_radius_property_editor::BorderRadiusPropertyEditor;
 use color_property_editor::ColorPropertyEditor;
 use corner_radii_property_editor::CornerRadiiPropertyEditor;
 use direction_property_editor::DirectionPropertyEditor;
 use fill_property_editor::FillPropertyEditor;
+use grid_placement_property_editor::GridPlacementPropertyEditor;
 use stroke_property_editor::StrokePropertyEditor;
 use text_property_editor::TextPropertyEditor;
 use text_style_property_editor::TextStylePropertyEditor;
+use track_list_property_editor::TrackListPropertyEditor;
 
 use crate::model;
 
@@ -36,6 +40,9 @@ pub struct PropertyEditor {
     pub stid: Property<TypeId>,
     pub snid: Property<TemplateNodeId>,
     pub is_custom_property: Property<bool>,
+    /// Selector of `stid` whose property is edited instead of that of the
+    /// node, see `model::styles`
+    pub selector: Property<String>,
 
     // internal repr, always set to collection of above
     pub prop_type_ident_id: Property<usize>,
@@ -51,12 +58,14 @@ impl PropertyEditor {
         let snid = self.snid.clone();
         let name = self.name.clone();
         let ind = self.ind.clone();
+        let selector = self.selector.clone();
         let manifest_ver = borrow!(ctxs.designtime).get_manifest_version();
         let deps = [
             stid.untyped(),
             snid.untyped(),
             name.untyped(),
             ind.untyped(),
+            selector.untyped(),
             manifest_ver.untyped(),
         ];
         self.data.replace_with(Property::computed(
@@ -65,6 +74,7 @@ impl PropertyEditor {
                 name: name.get(),
                 stid: stid.get(),
                 snid: snid.get(),
+                selector: selector.get(),
             },
             &deps,
         ));
@@ -86,12 +96,15 @@ impl PropertyEditor {
                     // TODO rename RectangleCornerRadii to CornerRadii and use for button/textbox
                     // etc. as well.
                     ("border_radius", "f64") => 6,
+                    ("grid_column" | "grid_row", "String") => 10,
                     (_, "pax_engine::api::Color") => 5,
                     (_, "pax_engine::api::Fill") => 2,
                     (_, "pax_engine::api::Stroke") => 3,
                     (_, "pax_std::layout::stacker::StackerDirection") => 4,
-                    (_, "pax_std::core::text::TextStyle") => 7,
+                    // the text style editor shows what's inherited from the ancestors of the node
+                    (_, "pax_std::core::text::TextStyle") if data.selector.is_empty() => 7,
                     (_, "pax_std::drawing::rectangle::RectangleCornerRadii") => 8,
+                    (_, "std::vec::Vec<pax_std::layout::grid::TrackSize>") => 9,
                     _ => 1,
                 }
             },
@@ -157,11 +170,18 @@ pub struct PropertyEditorData {
     pub name: String,
     pub stid: TypeId,
     pub snid: TemplateNodeId,
+    /// Empty unless editing a selector, see `PropertyEditor::selector`
+    pub selector: String,
 }
 
 impl PropertyEditorData {
     pub fn get_prop_type_id(&self, ctx: &NodeContext) -> Option<TypeId> {
         let dt = borrow!(ctx.designtime);
+        if !self.selector.is_empty() {
+            return dt
+                .get_orm()
+                .get_selector_property_type(&self.stid, &self.selector, &self.name);
+        }
         dt.get_orm().get_property_type(
             &UniqueTemplateNodeIdentifier::build(self.stid.clone(), self.snid.clone()),
             self.name.as_str(),
@@ -170,6 +190,11 @@ impl PropertyEditorData {
 
     pub fn get_value(&self, ctx: &NodeContext) -> Option<ValueDefinition> {
         let dt = borrow!(ctx.designtime);
+        if !self.selector.is_empty() {
+            return dt
+                .get_orm()
+                .get_selector_property(&self.stid, &self.selector, &self.name);
+        }
         dt.get_orm().get_property(
             &UniqueTemplateNodeIdentifier::build(self.stid.clone(), self.snid.clone()),
             self.name.as_str(),
@@ -185,6 +210,26 @@ impl PropertyEditorData {
     pub fn set_value(&self, ctx: &NodeContext, val: &str) -> anyhow::Result<()> {
         // save-point before property edit
         let t = model::with_action_context(ctx, |ac| ac.transaction("updating property"));
+        if !self.selector.is_empty() {
+            return t.run(|| {
+                let val = val.trim();
+                let value = if val.is_empty() {
+                    None
+                } else {
+                    Some(pax_manifest::utils::parse_value(val).map_err(|e| anyhow!(e.to_owned()))?)
+                };
+                borrow_mut!(ctx.designtime)
+                    .get_orm_mut()
+                    .set_selector_property(
+                        self.stid.clone(),
+                        self.selector.clone(),
+                        self.name.clone(),
+                        value,
+                    )
+                    .map(|_| ())
+                    .map_err(|e| anyhow!(e))
+            });
+        }
         t.run(|| {
             match self.with_node_def(ctx, |mut node| {
                 node.set_property(&self.name, val.trim())?;
@@ -217,6 +262,9 @@ pub fn stringify_value_definition(value: &ValueDefinition) -> String {
         ValueDefinition::LiteralValue(v) => v.to_string(),
         ValueDefinition::Expression(e) => format!("{{{}}}", e),
         ValueDefinition::DoubleBinding(i) | ValueDefinition::Identifier(i) => i.to_string(),
+        ValueDefinition::Token(name) => {
+            format!("{{{}.{}}}", pax_manifest::constants::TOKENS_SYMBOL, name)
+        }
         ValueDefinition::Block(LiteralBlockDefinition { elements, .. }) => {
             let mut block = String::new();
             write!(block, "{{").unwrap();
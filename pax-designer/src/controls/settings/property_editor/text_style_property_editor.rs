@@ -11,6 +11,7 @@ use crate::controls::settings::color_picker::ColorPicker;
 use crate::controls::settings::AREAS_PROP;
 use crate::model;
 use crate::model::action::orm::{NodeLayoutSettings, SetNodeLayout};
+use crate::model::contrast::{self, ContrastCheck, AAA_RATIO, AA_RATIO};
 
 use super::PropertyEditorData;
 
@@ -36,6 +37,18 @@ pub struct TextStylePropertyEditor {
     pub font_size: Property<String>,
     pub font_color: Property<Color>,
 
+    // contrast of the text against what's behind it
+    pub has_contrast: Property<bool>,
+    pub contrast_background: Property<Option<Color>>,
+    pub contrast_known: Property<bool>,
+    pub contrast_text: Property<String>,
+    pub passes_aa: Property<bool>,
+    pub passes_aaa: Property<bool>,
+
+    // fields inherited from the text styles of ancestors, and where from
+    pub has_inherited: Property<bool>,
+    pub inherited_text: Property<String>,
+
     pub property_listeners: Property<bool>,
     pub external_change: Property<bool>,
 }
@@ -196,7 +209,7 @@ impl TextStylePropertyEditor {
                     while areas.len() <= index {
                         areas.push(0.0)
                     }
-                    areas[index - 1] = 150.0;
+                    areas[index - 1] = 190.0;
                 });
             });
         }
@@ -311,6 +324,9 @@ impl TextStylePropertyEditor {
             &deps,
         ));
 
+        self.bind_contrast(ctx);
+        self.bind_inherited(ctx);
+
         // save and trigger listeners if dirty on tick
         let deps = [
             self.font_family_index.untyped(),
@@ -380,6 +396,104 @@ impl TextStylePropertyEditor {
         ));
     }
 
+    fn bind_contrast(&mut self, ctx: &NodeContext) {
+        let data = self.data.clone();
+        let manifest_ver = borrow!(ctx.designtime).get_manifest_version();
+        let deps = [data.untyped(), manifest_ver.untyped()];
+        let cctx = ctx.clone();
+        // None for nodes without a text color, e.g. components with a text style
+        let background = Property::computed(
+            move || {
+                let data = data.get();
+                let uid = UniqueTemplateNodeIdentifier::build(data.stid, data.snid);
+                let node = cctx.get_nodes_by_global_id(uid).into_iter().next()?;
+                Some(contrast::check_node(&cctx, &node)?.background)
+            },
+            &deps,
+        );
+
+        let bg = background.clone();
+        let deps = [bg.untyped()];
+        self.has_contrast
+            .replace_with(Property::computed(move || bg.get().is_some(), &deps));
+        let bg = background.clone();
+        self.contrast_background
+            .replace_with(Property::computed(move || bg.get().flatten(), &deps));
+
+        let font_color = self.font_color.clone();
+        let bg = self.contrast_background.clone();
+        let deps = [font_color.untyped(), bg.untyped()];
+        // the color being edited is checked, rather than the last rendered one
+        let check = Property::computed(
+            move || ContrastCheck {
+                foreground: font_color.get(),
+                background: bg.get(),
+            },
+            &deps,
+        );
+        let deps = [check.untyped()];
+        let ch = check.clone();
+        self.contrast_known.replace_with(Property::computed(
+            move || ch.get().ratio().is_some(),
+            &deps,
+        ));
+        let ch = check.clone();
+        self.contrast_text
+            .replace_with(Property::computed(move || ch.get().describe(), &deps));
+        let ch = check.clone();
+        self.passes_aa.replace_with(Property::computed(
+            move || ch.get().ratio().is_some_and(|r| r >= AA_RATIO),
+            &deps,
+        ));
+        self.passes_aaa.replace_with(Property::computed(
+            move || check.get().ratio().is_some_and(|r| r >= AAA_RATIO),
+            &deps,
+        ));
+    }
+
+    fn bind_inherited(&mut self, ctx: &NodeContext) {
+        let data = self.data.clone();
+        let manifest_ver = borrow!(ctx.designtime).get_manifest_version();
+        let deps = [data.untyped(), manifest_ver.untyped()];
+        let cctx = ctx.clone();
+        let inherited = Property::computed(
+            move || {
+                let data = data.get();
+                let uid = UniqueTemplateNodeIdentifier::build(data.stid, data.snid);
+                let Some(node) = cctx.get_nodes_by_global_id(uid).into_iter().next() else {
+                    return vec![];
+                };
+                inherited_fields(&cctx, &node, &data.name)
+            },
+            &deps,
+        );
+        let inh = inherited.clone();
+        let deps = [inh.untyped()];
+        self.has_inherited
+            .replace_with(Property::computed(move || !inh.get().is_empty(), &deps));
+        self.inherited_text.replace_with(Property::computed(
+            move || {
+                let sources = inherited
+                    .get()
+                    .into_iter()
+                    .map(|(source, fields)| format!("{} from {}", fields.join(", "), source))
+                    .collect::<Vec<_>>();
+                format!("Inherits {}", sources.join("; "))
+            },
+            &deps,
+        ));
+    }
+
+    pub fn fix_contrast(&mut self, _ctx: &NodeContext, _event: Event<Click>) {
+        let check = ContrastCheck {
+            foreground: self.font_color.get(),
+            background: self.contrast_background.get(),
+        };
+        if let Some(color) = check.nearest_passing_color(AA_RATIO) {
+            self.font_color.set(color);
+        }
+    }
+
     fn update_textstyle(
         data: &Property<PropertyEditorData>,
         text_style: &TextStyle,
@@ -388,17 +502,8 @@ impl TextStylePropertyEditor {
         // TODO use this, and change out color serialization/deserialization
         // let var_str = pax_designtime::serde_pax::se::to_pax(&style);
         // let var_str = var_str.unwrap();
-        let var_str = format!(
-            "{{
-        font: {}
-        font_size: {}
-        fill: {}
-        align_vertical: TextAlignVertical::{:?},
-        align_horizontal: TextAlignHorizontal::{:?},
-        align_multiline: TextAlignHorizontal::{:?},
-        underline: false
-        }}",
-            {
+        let fields = [
+            ("font", {
                 let font = text_style.font.get();
                 match font {
                     Font::Web(family, url, style, weight) => {
@@ -408,25 +513,59 @@ impl TextStylePropertyEditor {
                         )
                     }
                 }
-            },
-            match text_style.font_size.get() {
-                Size::Pixels(px) => format!("{}px", px),
-                Size::Percent(perc) => format!("{}%", perc),
-                Size::Combined(px, perc) => format!("{}px + {}%", px, perc),
-            },
-            {
+            }),
+            (
+                "font_size",
+                match text_style.font_size.get() {
+                    Size::Pixels(px) => format!("{}px", px),
+                    Size::Percent(perc) => format!("{}%", perc),
+                    Size::Combined(px, perc) => format!("{}px + {}%", px, perc),
+                },
+            ),
+            ("fill", {
                 let rgba = text_style.fill.get().to_rgba_0_1();
                 format!(
                     "rgba({}, {}, {}, {})",
-                    (rgba[0] * 255.0) as u8,
-                    (rgba[1] * 255.0) as u8,
-                    (rgba[2] * 255.0) as u8,
-                    (rgba[3] * 255.0) as u8
+                    (rgba[0] * 255.0).round() as u8,
+                    (rgba[1] * 255.0).round() as u8,
+                    (rgba[2] * 255.0).round() as u8,
+                    (rgba[3] * 255.0).round() as u8
                 )
-            },
-            text_style.align_vertical.get(),
-            text_style.align_horizontal.get(),
-            text_style.align_horizontal.get(),
+            }),
+            (
+                "align_vertical",
+                format!("TextAlignVertical::{:?}", text_style.align_vertical.get()),
+            ),
+            (
+                "align_horizontal",
+                format!(
+                    "TextAlignHorizontal::{:?}",
+                    text_style.align_horizontal.get()
+                ),
+            ),
+            (
+                "align_multiline",
+                format!(
+                    "TextAlignHorizontal::{:?}",
+                    text_style.align_horizontal.get()
+                ),
+            ),
+            ("underline", "false".to_string()),
+        ];
+        // text style defaults of containers only hold the fields that are set,
+        // so that the rest keeps being inherited from further up
+        let set_fields =
+            (data.get().name == "text_style").then(|| text_style.set_fields(&TextStyle::default()));
+        let var_str = format!(
+            "{{\n{}\n}}",
+            fields
+                .into_iter()
+                .filter(|(name, _)| set_fields
+                    .as_ref()
+                    .map_or(true, |set| set.iter().any(|s| s == name)))
+                .map(|(name, value)| format!("{}: {}", name, value))
+                .collect::<Vec<_>>()
+                .join(",\n")
         );
         if let Err(e) = data.get().set_value(ctx, &var_str) {
             log::warn!("failed to write textstyle: {e}");
@@ -478,3 +617,81 @@ impl TextStylePropertyEditor {
         );
     }
 }
+
+/// The style of a text element, and the style it has when its style is left unset
+fn own_text_style(node: &NodeInterface) -> Option<(TextStyle, TextStyle)> {
+    node.with_properties(|text: &mut Text| (text.style.get(), TextStyle::default()))
+        .or_else(|| {
+            node.with_properties(|button: &mut Button| {
+                (button.style.get(), Button::default().style.get())
+            })
+        })
+        .or_else(|| {
+            node.with_properties(|textbox: &mut Textbox| {
+                (textbox.style.get(), Textbox::default().style.get())
+            })
+        })
+        .or_else(|| {
+            node.with_properties(|dropdown: &mut Dropdown| {
+                (dropdown.style.get(), Dropdown::default().style.get())
+            })
+        })
+        .or_else(|| {
+            node.with_properties(|radio_set: &mut RadioSet| {
+                (radio_set.style.get(), RadioSet::default().style.get())
+            })
+        })
+}
+
+/// The fields of the text style edited as property `name` of `node` that are inherited from the
+/// `text_style` of an ancestor, grouped by the ancestor they're inherited from, nearest first
+fn inherited_fields(
+    ctx: &NodeContext,
+    node: &NodeInterface,
+    name: &str,
+) -> Vec<(String, Vec<String>)> {
+    let mut taken: Vec<String> = if name == "text_style" {
+        match node.text_style() {
+            Some(PaxValue::Object(fields)) => fields.into_keys().collect(),
+            _ => vec![],
+        }
+    } else {
+        match own_text_style(node) {
+            Some((style, defaults)) => style.set_fields(&defaults),
+            None => return vec![],
+        }
+    };
+    let dt = borrow!(ctx.designtime);
+    let mut sources = vec![];
+    let mut ancestor = node.render_parent();
+    while let Some(node) = ancestor {
+        if let Some(PaxValue::Object(fields)) = node.text_style() {
+            let mut inherited: Vec<String> = fields
+                .into_keys()
+                .filter(|field| !taken.contains(field))
+                .collect();
+            if !inherited.is_empty() {
+                inherited.sort();
+                taken.extend(inherited.iter().cloned());
+                let source = node
+                    .global_id()
+                    .and_then(|uid| {
+                        let component = dt
+                            .get_orm()
+                            .get_component(&uid.get_containing_component_type_id())
+                            .ok()?;
+                        let tnd = component
+                            .template
+                            .as_ref()?
+                            .get_node(&uid.get_template_node_id())?;
+                        tnd.type_id.get_pascal_identifier()
+                    })
+                    .unwrap_or_else(|| "an ancestor".to_string());
+                let fields = inherited.iter().map(|f| f.replace('_', " ")).collect();
+                sources.push((source, fields));
+            }
+        }
+        ancestor = node.render_parent();
+    }
+    sources
+}
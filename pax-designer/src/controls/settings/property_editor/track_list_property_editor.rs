@@ -0,0 +1,71 @@
+use pax_engine::api::*;
+use pax_engine::*;
+use pax_manifest::*;
+use pax_std::*;
+
+use crate::controls::settings::AREAS_PROP;
+
+use super::PropertyEditorData;
+
+/// Edits the `columns`/`rows` of a `Grid` as a list like `auto 1fr 120px 25%`
+#[pax]
+#[engine_import_path("pax_engine")]
+#[file("controls/settings/property_editor/track_list_property_editor.pax")]
+pub struct TrackListPropertyEditor {
+    pub data: Property<PropertyEditorData>,
+
+    // All the below props should be private: never set by user, used for internal state
+    pub textbox: Property<String>,
+    pub error: Property<String>,
+}
+
+impl TrackListPropertyEditor {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        let index = self.data.get().editor_index;
+        if index != 0 {
+            AREAS_PROP.with(|areas| {
+                areas.update(|areas| {
+                    while areas.len() <= index {
+                        areas.push(0.0)
+                    }
+                    areas[index - 1] = 75.0;
+                });
+            });
+        }
+        let data = self.data.clone();
+        let deps = [data.untyped()];
+        let ctx = ctx.clone();
+        let err = self.error.clone();
+        self.textbox.replace_with(Property::computed(
+            move || {
+                err.set("".to_string());
+                let tracks = match data.get().get_value(&ctx) {
+                    Some(ValueDefinition::LiteralValue(value)) => {
+                        Vec::<TrackSize>::try_coerce(value).unwrap_or_default()
+                    }
+                    _ => vec![],
+                };
+                TrackSize::format_list(&tracks)
+            },
+            &deps,
+        ));
+    }
+
+    pub fn text_input(&mut self, _ctx: &NodeContext, args: Event<TextboxInput>) {
+        self.textbox.set(args.text.to_owned());
+    }
+
+    pub fn text_change(&mut self, ctx: &NodeContext, args: Event<TextboxChange>) {
+        self.textbox.set(args.text.to_owned());
+        let res = TrackSize::parse_list(&args.text).and_then(|tracks| {
+            self.data
+                .get()
+                .set_value(ctx, &TrackSize::to_pax_list(&tracks))
+                .map_err(|e| e.to_string())
+        });
+        match res {
+            Ok(()) => self.error.set("".to_owned()),
+            Err(e) => self.error.set(e),
+        }
+    }
+}
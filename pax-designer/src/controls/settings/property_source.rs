@@ -0,0 +1,67 @@
+use std::rc::Rc;
+
+use pax_engine::api::*;
+use pax_engine::*;
+use pax_manifest::cartridge_generation::SettingSource;
+use pax_manifest::*;
+use pax_std::*;
+
+use crate::model;
+use crate::model::styles::OpenStyles;
+
+/// Where a property of the selected node takes its value from, if not from
+/// the node itself, e.g. "from .card-title". Opens the selector in the styles
+/// panel when clicked.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[file("controls/settings/property_source.pax")]
+pub struct PropertySource {
+    pub stid: Property<TypeId>,
+    pub snid: Property<TemplateNodeId>,
+    pub name: Property<String>,
+    /// Empty if the property is set on the node, or not at all
+    pub selector: Property<String>,
+    pub text: Property<String>,
+}
+
+impl PropertySource {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        let stid = self.stid.clone();
+        let snid = self.snid.clone();
+        let name = self.name.clone();
+        let manifest_ver = borrow!(ctx.designtime).get_manifest_version();
+        let deps = [
+            stid.untyped(),
+            snid.untyped(),
+            name.untyped(),
+            manifest_ver.untyped(),
+        ];
+        let dt = Rc::clone(&ctx.designtime);
+        self.selector.replace_with(Property::computed(
+            move || {
+                let uni = UniqueTemplateNodeIdentifier::build(stid.get(), snid.get());
+                match borrow!(dt).get_orm().get_property_source(&uni, &name.get()) {
+                    Some(SettingSource::Selector(selector)) => selector,
+                    Some(SettingSource::Inline) | None => String::new(),
+                }
+            },
+            &deps,
+        ));
+        let selector = self.selector.clone();
+        let deps = [selector.untyped()];
+        self.text.replace_with(Property::computed(
+            move || match selector.get() {
+                selector if selector.is_empty() => selector,
+                selector => format!("from {}", selector),
+            },
+            &deps,
+        ));
+    }
+
+    pub fn open(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        let selector = self.selector.get();
+        if !selector.is_empty() {
+            model::perform_action(&OpenStyles(Some(selector)), ctx);
+        }
+    }
+}
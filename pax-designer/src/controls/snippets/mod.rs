@@ -0,0 +1,84 @@
+use pax_engine::api::*;
+use pax_engine::*;
+use pax_std::*;
+
+use crate::model;
+use crate::model::action::orm::snippets::{
+    CancelSnippetImport, CloseSnippetLibrary, ConfirmSnippetImport,
+};
+
+pub mod snippet_row;
+use snippet_row::SnippetRow;
+
+/// Lists the snippets in the user's snippet library, importing the one
+/// clicked. Also asks for confirmation when an imported snippet's components
+/// collide with components of the project.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[file("controls/snippets/mod.pax")]
+pub struct SnippetLibrary {
+    pub library_open: Property<bool>,
+    pub library_empty: Property<bool>,
+    pub snippets: Property<Vec<String>>,
+    pub import_pending: Property<bool>,
+    pub collision_message: Property<String>,
+}
+
+impl SnippetLibrary {
+    pub fn on_mount(&mut self, _ctx: &NodeContext) {
+        let (library, pending_import) = model::read_app_state(|app_state| {
+            (
+                app_state.snippet_library.clone(),
+                app_state.pending_snippet_import.clone(),
+            )
+        });
+        let deps = [library.untyped()];
+        let library_cp = library.clone();
+        self.library_open.replace_with(Property::computed(
+            move || library_cp.get().is_some(),
+            &deps,
+        ));
+        let library_cp = library.clone();
+        self.library_empty.replace_with(Property::computed(
+            move || library_cp.get().is_some_and(|names| names.is_empty()),
+            &deps,
+        ));
+        self.snippets.replace_with(Property::computed(
+            move || library.get().unwrap_or_default(),
+            &deps,
+        ));
+
+        let deps = [pending_import.untyped()];
+        let pending_import_cp = pending_import.clone();
+        self.import_pending.replace_with(Property::computed(
+            move || pending_import_cp.get().is_some(),
+            &deps,
+        ));
+        self.collision_message.replace_with(Property::computed(
+            move || {
+                pending_import
+                    .get()
+                    .map(|pending| {
+                        format!(
+                            "This project already has components named {}. Import the snippet with its components renamed?",
+                            pending.collisions.join(", ")
+                        )
+                    })
+                    .unwrap_or_default()
+            },
+            &deps,
+        ));
+    }
+
+    pub fn close_library(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(&CloseSnippetLibrary, ctx);
+    }
+
+    pub fn confirm_import(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(&ConfirmSnippetImport, ctx);
+    }
+
+    pub fn cancel_import(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(&CancelSnippetImport, ctx);
+    }
+}
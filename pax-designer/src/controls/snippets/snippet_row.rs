@@ -0,0 +1,24 @@
+use pax_engine::api::*;
+use pax_engine::*;
+use pax_std::*;
+
+use crate::model;
+use crate::model::action::orm::snippets::ImportSnippetFromLibrary;
+
+#[pax]
+#[engine_import_path("pax_engine")]
+#[file("controls/snippets/snippet_row.pax")]
+pub struct SnippetRow {
+    pub name: Property<String>,
+}
+
+impl SnippetRow {
+    pub fn import(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(
+            &ImportSnippetFromLibrary {
+                name: self.name.get(),
+            },
+            ctx,
+        );
+    }
+}
@@ -0,0 +1,259 @@
+use std::rc::Rc;
+
+use convert_case::{Case, Casing};
+use pax_designtime::orm::selectors::DeleteSelectorPolicy;
+use pax_engine::api::*;
+use pax_engine::pax_manifest::{PaxManifest, TypeId};
+use pax_engine::*;
+use pax_std::*;
+
+use crate::controls::settings::property_editor::PropertyEditor;
+use crate::controls::settings::{layout_property_areas, PropertyArea, AREAS_PROP};
+use crate::model;
+use crate::model::styles::{
+    AddSelector, CloseStyles, DeleteSelector, RenameSelector, SetSelectorOnSelection,
+};
+
+pub mod selector_row;
+use selector_row::SelectorRow;
+
+/// Lists the selector blocks of the edited component, and edits the one
+/// selected with the property editors of the settings, see `model::styles`
+#[pax]
+#[engine_import_path("pax_engine")]
+#[file("controls/styles/mod.pax")]
+pub struct StylesPanel {
+    pub stid: Property<TypeId>,
+    pub selectors: Property<Vec<SelectorRowData>>,
+    pub selector: Property<String>,
+    pub has_selector: Property<bool>,
+    pub is_class: Property<bool>,
+    pub new_selector: Property<String>,
+    pub properties: Property<Vec<PropertyArea>>,
+    pub properties_total_height: Property<f64>,
+}
+
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct SelectorRowData {
+    pub selector: String,
+    /// Number of nodes matched, e.g. "3 nodes"
+    pub nodes: String,
+    pub selected: bool,
+}
+
+impl StylesPanel {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        let (component, selected_selector) = model::read_app_state(|app_state| {
+            (
+                app_state.selected_component_id.clone(),
+                app_state.selected_selector.clone(),
+            )
+        });
+        let deps = [component.untyped()];
+        let component_cp = component.clone();
+        self.stid
+            .replace_with(Property::computed(move || component_cp.get(), &deps));
+
+        let manifest_ver = borrow!(ctx.designtime).get_manifest_version();
+        let deps = [
+            component.untyped(),
+            selected_selector.untyped(),
+            manifest_ver.untyped(),
+        ];
+        let dt = Rc::clone(&ctx.designtime);
+        let component_cp = component.clone();
+        let selected_selector_cp = selected_selector.clone();
+        self.selectors.replace_with(Property::computed(
+            move || {
+                let selected = selected_selector_cp.get();
+                borrow!(dt)
+                    .get_orm()
+                    .get_selectors(&component_cp.get())
+                    .into_iter()
+                    .map(|info| SelectorRowData {
+                        selected: selected.as_ref() == Some(&info.selector),
+                        nodes: match info.node_count {
+                            1 => "1 node".to_string(),
+                            n => format!("{} nodes", n),
+                        },
+                        selector: info.selector,
+                    })
+                    .collect()
+            },
+            &deps,
+        ));
+
+        // the selected selector, if the component still has it
+        let selectors = self.selectors.clone();
+        let deps = [selectors.untyped()];
+        self.selector.replace_with(Property::computed(
+            move || {
+                selectors
+                    .get()
+                    .into_iter()
+                    .find(|row| row.selected)
+                    .map(|row| row.selector)
+                    .unwrap_or_default()
+            },
+            &deps,
+        ));
+        let selector = self.selector.clone();
+        let deps = [selector.untyped()];
+        self.has_selector.replace_with(Property::computed(
+            move || !selector.get().is_empty(),
+            &deps,
+        ));
+        let selector = self.selector.clone();
+        self.is_class.replace_with(Property::computed(
+            move || selector.get().starts_with('.'),
+            &deps,
+        ));
+
+        let selector = self.selector.clone();
+        let deps = [selector.untyped(), manifest_ver.untyped()];
+        let dt = Rc::clone(&ctx.designtime);
+        let properties = Property::computed(
+            move || {
+                let selector = selector.get();
+                if selector.is_empty() {
+                    return vec![];
+                }
+                selector_property_names(borrow!(dt).get_orm(), &component.get(), &selector)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, name)| PropertyArea {
+                        index: i + 1,
+                        vertical_space: 10.0,
+                        vertical_pos: Default::default(),
+                        name_friendly: name.to_case(Case::Title),
+                        name,
+                    })
+                    .collect()
+            },
+            &deps,
+        );
+        let areas = AREAS_PROP.with(|p| p.clone());
+        self.properties
+            .replace_with(layout_property_areas(properties, areas));
+
+        let properties = self.properties.clone();
+        let deps = [properties.untyped()];
+        self.properties_total_height
+            .replace_with(Property::computed(
+                move || {
+                    // laid out in reverse, see `layout_property_areas`
+                    let last = properties.get().into_iter().next().unwrap_or_default();
+                    last.vertical_pos + last.vertical_space
+                },
+                &deps,
+            ));
+    }
+
+    pub fn close(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(&CloseStyles, ctx);
+    }
+
+    pub fn add_selector(&mut self, ctx: &NodeContext, event: Event<TextboxChange>) {
+        let selector = event.text.trim().to_string();
+        if selector.is_empty() {
+            return;
+        }
+        model::perform_action(&AddSelector(selector), ctx);
+        self.new_selector.set(String::new());
+    }
+
+    pub fn rename(&mut self, ctx: &NodeContext, event: Event<TextboxChange>) {
+        let new_selector = event.text.trim().to_string();
+        let selector = self.selector.get();
+        if new_selector.is_empty() || new_selector == selector {
+            return;
+        }
+        model::perform_action(
+            &RenameSelector {
+                selector,
+                new_selector,
+            },
+            ctx,
+        );
+    }
+
+    pub fn apply_to_selection(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        self.set_on_selection(ctx, true);
+    }
+
+    pub fn remove_from_selection(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        self.set_on_selection(ctx, false);
+    }
+
+    fn set_on_selection(&self, ctx: &NodeContext, applied: bool) {
+        model::perform_action(
+            &SetSelectorOnSelection {
+                selector: self.selector.get(),
+                applied,
+            },
+            ctx,
+        );
+    }
+
+    pub fn delete(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        self.delete_with(ctx, DeleteSelectorPolicy::LeaveUnstyled);
+    }
+
+    pub fn delete_and_inline(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        self.delete_with(ctx, DeleteSelectorPolicy::InlineValues);
+    }
+
+    fn delete_with(&self, ctx: &NodeContext, policy: DeleteSelectorPolicy) {
+        model::perform_action(
+            &DeleteSelector {
+                selector: self.selector.get(),
+                policy,
+            },
+            ctx,
+        );
+    }
+}
+
+/// The properties edited for `selector`: those it sets, followed by the other
+/// properties of the nodes it matches
+fn selector_property_names(
+    orm: &pax_designtime::orm::PaxManifestORM,
+    type_id: &TypeId,
+    selector: &str,
+) -> Vec<String> {
+    let mut names: Vec<String> = orm
+        .get_selector_settings(type_id, selector)
+        .into_iter()
+        .map(|(token, _)| token.token_value)
+        .collect();
+    let manifest = orm.get_manifest();
+    let matched_types = orm
+        .get_component(type_id)
+        .ok()
+        .and_then(|c| c.template.as_ref())
+        .map(|template| {
+            template
+                .get_nodes()
+                .into_iter()
+                .filter(|tnd| PaxManifest::selector_matches(&tnd.settings, selector))
+                .map(|tnd| tnd.type_id.clone())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    for type_id in matched_types {
+        let Some(type_def) = manifest.type_table.get(&type_id) else {
+            continue;
+        };
+        for prop_def in &type_def.property_definitions {
+            let name = &prop_def.name;
+            if name.starts_with('_') || matches!(name.as_str(), "id" | "class") {
+                continue;
+            }
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+    }
+    names
+}
@@ -0,0 +1,21 @@
+use pax_engine::api::*;
+use pax_engine::*;
+use pax_std::*;
+
+use crate::model;
+use crate::model::styles::SelectSelector;
+
+#[pax]
+#[engine_import_path("pax_engine")]
+#[file("controls/styles/selector_row.pax")]
+pub struct SelectorRow {
+    pub selector: Property<String>,
+    pub nodes: Property<String>,
+    pub selected: Property<bool>,
+}
+
+impl SelectorRow {
+    pub fn select(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(&SelectSelector(self.selector.get()), ctx);
+    }
+}
@@ -321,6 +321,11 @@ pub struct SelectTool {
 
 impl Action for SelectTool {
     fn perform(&self, ctx: &mut model::action::ActionContext) -> Result<()> {
+        if !ctx.derived_state.manifest_loaded.get() {
+            return Err(anyhow::anyhow!(
+                "can't select a tool before the project has loaded"
+            ));
+        }
         // set px/percent mode if a new pointer tool is selected,
         // is there some better way of persisting this? (tool stack?)
         match self.tool {
@@ -7,16 +7,43 @@ use std::collections::HashMap;
 use std::sync::mpsc::channel;
 
 use super::ToolbarItemView;
+use crate::model;
 
 #[pax]
 #[engine_import_path("pax_engine")]
 #[file("controls/toolbar/toolbar_item.pax")]
 pub struct ToolbarItemVisual {
     pub data: Property<ToolbarItemView>,
+    pub tip: Property<String>,
+    pub enabled: Property<bool>,
 }
 
 impl ToolbarItemVisual {
+    pub fn on_mount(&mut self, _ctx: &NodeContext) {
+        let manifest_loaded =
+            model::read_app_state_with_derived(|_, derived| derived.manifest_loaded.clone());
+        let deps = [manifest_loaded.untyped()];
+        let loaded = manifest_loaded.clone();
+        self.enabled
+            .replace_with(Property::computed(move || loaded.get(), &deps));
+        let data = self.data.clone();
+        let deps = [data.untyped(), manifest_loaded.untyped()];
+        self.tip.replace_with(Property::computed(
+            move || {
+                if manifest_loaded.get() {
+                    data.get().tooltip
+                } else {
+                    "Available once the project has loaded".to_string()
+                }
+            },
+            &deps,
+        ));
+    }
+
     pub fn on_click(&mut self, _ctx: &NodeContext, _args: Event<Click>) {
+        if !self.enabled.get() {
+            return;
+        }
         super::CLICK_PROP.with(|click_msg| {
             let data = self.data.get();
             click_msg.set(super::ToolbarClickEvent::Select(data.row, data.col));
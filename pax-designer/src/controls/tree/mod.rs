@@ -22,11 +22,14 @@ use serde::Deserialize;
 use std::collections::{HashMap, HashSet, VecDeque};
 use treeobj::TreeObj;
 
+use crate::controls::file_and_component_picker::SetLibraryState;
 use crate::designer_node_type::DesignerNodeType;
 use crate::glass::SetEditingComponent;
 use crate::math::coordinate_spaces::Glass;
 use crate::math::IntoDecompositionConfiguration;
-use crate::model::action::orm::{tree_movement::MoveNode, NodeLayoutSettings};
+use crate::model::action::orm::{
+    tree_movement::MoveNode, NodeLayoutSettings, OverrideInheritedNode, RemoveNode,
+};
 use crate::model::action::world::SelectNodes;
 use crate::model::action::Action;
 use crate::model::{self, GlassNode};
@@ -51,6 +54,9 @@ pub enum TreeMsg {
     ObjDoubleClicked(usize),
     ObjMouseDown(usize, f64),
     ObjMouseMove(usize, f64, bool),
+    ObjOverride(usize),
+    ObjReplace(usize),
+    ObjRemove(usize),
 }
 
 thread_local! {
@@ -87,6 +93,9 @@ impl TreeEntry {
             is_visible: true,
             is_selected: false,
             is_container: desc.is_container,
+            is_inherited: false,
+            is_overridable: false,
+            is_unresolved: false,
         });
         *ind += 1;
         all.extend(
@@ -110,6 +119,11 @@ pub struct FlattenedTreeEntry {
     pub is_visible: bool,
     pub is_selected: bool,
     pub is_container: bool,
+    /// Inherited from the component extended with `@extends`, and locked until overridden
+    pub is_inherited: bool,
+    pub is_overridable: bool,
+    /// Instantiates a component that no longer exists, and is rendered as a placeholder
+    pub is_unresolved: bool,
 }
 
 impl Tree {
@@ -179,6 +193,32 @@ impl Tree {
                     self.drag_indent
                         .set(tree_obj.read(|t| t[sender].indent_level));
                 }
+                TreeMsg::ObjOverride(sender) => {
+                    model::perform_action(
+                        &OverrideInheritedNode {
+                            id: tree_obj.read(|t| t[sender].node_id.clone()),
+                        },
+                        &ctx,
+                    );
+                }
+                TreeMsg::ObjReplace(sender) => {
+                    // the component picked from the library replaces the node
+                    model::perform_action(
+                        &SetLibraryState {
+                            open: true,
+                            replacing: Some(tree_obj.read(|t| t[sender].node_id.clone())),
+                        },
+                        ctx,
+                    );
+                }
+                TreeMsg::ObjRemove(sender) => {
+                    model::perform_action(
+                        &RemoveNode {
+                            id: tree_obj.read(|t| t[sender].node_id.clone()),
+                        },
+                        ctx,
+                    );
+                }
                 // TODO make less ugly
                 TreeMsg::ObjMouseMove(sender, x_offset, top_half) => {
                     drag_id.set(sender);
@@ -366,8 +406,9 @@ fn get_tree(type_id: TypeId, ctx: &NodeContext) -> Vec<FlattenedTreeEntry> {
         pax_engine::log::warn!("treeview component template embty");
         return Vec::new();
     };
+    let manifest = dt.get_orm().get_manifest();
     let mut ind = 0;
-    let flattened: Vec<FlattenedTreeEntry> = template
+    let mut flattened: Vec<FlattenedTreeEntry> = template
         .get_root()
         .iter()
         .flat_map(|tnid| {
@@ -376,6 +417,20 @@ fn get_tree(type_id: TypeId, ctx: &NodeContext) -> Vec<FlattenedTreeEntry> {
                 .unwrap_or_default()
         })
         .collect();
+    for entry in &mut flattened {
+        let node = template.get_node(&entry.node_id);
+        entry.is_inherited = comp.is_inherited_node(&entry.node_id);
+        entry.is_overridable =
+            entry.is_inherited && node.is_some_and(|node| node.get_template_label().is_some());
+        if let Some(node) = node.filter(|node| manifest.is_unresolved_type(&node.type_id)) {
+            entry.is_unresolved = true;
+            entry.name = node.type_id.get_pascal_identifier().unwrap_or_default();
+        }
+        // names given by designers take the place of the type name
+        if let Some(label) = node.and_then(|node| node.label.clone()) {
+            entry.name = label;
+        }
+    }
     flattened
 }
 
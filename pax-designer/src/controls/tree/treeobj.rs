@@ -21,6 +21,9 @@ pub struct TreeObj {
     pub arrow_path: Property<String>,
     pub is_container: Property<bool>,
     pub uid: Property<TemplateNodeId>,
+    pub is_inherited: Property<bool>,
+    pub is_overridable: Property<bool>,
+    pub is_unresolved: Property<bool>,
 }
 
 impl TreeObj {
@@ -78,6 +81,24 @@ impl TreeObj {
         });
     }
 
+    pub fn override_clicked(&mut self, _ctx: &NodeContext, _event: Event<MouseDown>) {
+        super::TREE_CLICK_PROP.with_borrow_mut(|cn| {
+            cn.push_back(super::TreeMsg::ObjOverride(self.ind.get().clone().into()));
+        });
+    }
+
+    pub fn replace_clicked(&mut self, _ctx: &NodeContext, _event: Event<MouseDown>) {
+        super::TREE_CLICK_PROP.with_borrow_mut(|cn| {
+            cn.push_back(super::TreeMsg::ObjReplace(self.ind.get().into()));
+        });
+    }
+
+    pub fn remove_clicked(&mut self, _ctx: &NodeContext, _event: Event<MouseDown>) {
+        super::TREE_CLICK_PROP.with_borrow_mut(|cn| {
+            cn.push_back(super::TreeMsg::ObjRemove(self.ind.get().into()));
+        });
+    }
+
     pub fn mouse_move(&mut self, ctx: &NodeContext, event: Event<MouseMove>) {
         let local = ctx.local_point(Point2::new(event.mouse.x, event.mouse.y));
         let top_half = local.y < 0.5;
@@ -38,6 +38,8 @@ pub enum DesignerNodeType {
     Slot,
     Unregistered,
     Carousel,
+    Grid,
+    GridItem,
 }
 
 #[derive(Debug)]
@@ -113,6 +115,8 @@ impl DesignerNodeType {
                     "forms::slider::Slider" => DesignerNodeType::Slider,
                     "forms::dropdown::Dropdown" => DesignerNodeType::Dropdown,
                     "layout::carousel::Carousel" => DesignerNodeType::Carousel,
+                    "layout::grid::Grid" => DesignerNodeType::Grid,
+                    "layout::grid::GridItem" => DesignerNodeType::GridItem,
                     _ => {
                         let (_, name) = import_path.rsplit_once("::").unwrap_or(("", &import_path));
                         DesignerNodeType::Component {
@@ -169,6 +173,18 @@ impl DesignerNodeType {
                 TypeId::build_singleton("pax_std::layout::carousel::Carousel", None),
                 true,
             ),
+            DesignerNodeType::Grid => (
+                "Grid",
+                "component", // TODO image
+                TypeId::build_singleton("pax_std::layout::grid::Grid", None),
+                true,
+            ),
+            DesignerNodeType::GridItem => (
+                "Grid Item",
+                "component", // TODO image
+                TypeId::build_singleton("pax_std::layout::grid::GridItem", None),
+                true,
+            ),
             DesignerNodeType::Rectangle => (
                 "Rectangle",
                 "rectangle",
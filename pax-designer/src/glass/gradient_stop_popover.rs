@@ -0,0 +1,88 @@
+use pax_engine::api::*;
+use pax_engine::*;
+use pax_std::*;
+
+use crate::controls::settings::color_picker::ColorPicker;
+use crate::model;
+use crate::model::action::orm::gradient::{gradient_stops_mut, node_fill, SetGradientStopColor};
+
+/// Color picker for a gradient stop, shown next to its marker on the glass after
+/// double-clicking it while editing a gradient on the canvas
+#[pax]
+#[engine_import_path("pax_engine")]
+#[file("glass/gradient_stop_popover.pax")]
+pub struct GradientStopPopover {
+    pub visible: Property<bool>,
+    pub x: Property<f64>,
+    pub y: Property<f64>,
+    pub stop_color: Property<Color>,
+    pub external: Property<bool>,
+    pub property_listener: Property<bool>,
+}
+
+impl GradientStopPopover {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        let edit = model::read_app_state(|app_state| app_state.gradient_stop_color_edit.clone());
+        let deps = [edit.untyped()];
+        let e = edit.clone();
+        self.visible
+            .replace_with(Property::computed(move || e.get().is_some(), &deps));
+        let e = edit.clone();
+        self.x.replace_with(Property::computed(
+            move || e.get().map(|edit| edit.position.x).unwrap_or_default(),
+            &deps,
+        ));
+        let e = edit.clone();
+        self.y.replace_with(Property::computed(
+            move || e.get().map(|edit| edit.position.y).unwrap_or_default(),
+            &deps,
+        ));
+
+        let manifest_ver = borrow!(ctx.designtime).get_manifest_version();
+        let deps = [edit.untyped(), manifest_ver.untyped()];
+        let external = self.external.clone();
+        let ctxc = ctx.clone();
+        self.stop_color.replace_with(Property::computed(
+            move || {
+                external.set(true);
+                let Some(edit) = edit.get() else {
+                    return Color::default();
+                };
+                ctxc.get_nodes_by_global_id(edit.node.clone())
+                    .into_iter()
+                    .next()
+                    .and_then(|node| node_fill(&node))
+                    .and_then(|mut fill| {
+                        gradient_stops_mut(&mut fill)
+                            .and_then(|stops| stops.get(edit.stop).map(|stop| stop.color.clone()))
+                    })
+                    .unwrap_or_default()
+            },
+            &deps,
+        ));
+
+        let stop_color = self.stop_color.clone();
+        let deps = [stop_color.untyped()];
+        let external = self.external.clone();
+        let ctxc = ctx.clone();
+        self.property_listener.replace_with(Property::computed(
+            move || {
+                let color = stop_color.get();
+                if !external.get() {
+                    model::perform_action(&SetGradientStopColor { color }, &ctxc);
+                }
+                external.set(false);
+                true
+            },
+            &deps,
+        ));
+    }
+
+    pub fn pre_render(&mut self, _ctx: &NodeContext) {
+        self.property_listener.get();
+    }
+
+    pub fn close(&mut self, _ctx: &NodeContext, _args: Event<Click>) {
+        model::read_app_state(|app_state| app_state.gradient_stop_color_edit.set(None));
+    }
+}
@@ -0,0 +1,185 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use pax_engine::api::*;
+use pax_engine::math::Point2;
+use pax_engine::*;
+use pax_std::*;
+
+use super::{SnapInfo, SnapLine};
+use crate::math::coordinate_spaces::Glass;
+use crate::message_log_display::{self, DesignerLogMsg};
+use crate::model::action::tool::SetToolBehaviour;
+use crate::model::action::ActionContext;
+use crate::model::tools::GuideTool;
+use crate::model::workspace::{GridSettings, Guide, GuideAxis, SetGuidePosition};
+use crate::model::{self, ToolBehavior};
+
+/// Distance (in glass pixels) from a guide within which it can be picked up
+const GUIDE_HIT_DISTANCE: f64 = 3.5;
+
+/// Guides of the component currently being edited, and the grid overlay.
+/// Only the guides themselves are raycastable, and only while the glass
+/// is intercepting events.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[file("glass/guides.pax")]
+pub struct Guides {
+    pub interactive: Property<bool>,
+    pub guides: Property<SnapInfo>,
+    pub grid: Property<SnapInfo>,
+    pub editor: Property<GuideEditor>,
+}
+
+/// Textbox for entering the exact position of a guide, opened on double click
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct GuideEditor {
+    pub active: bool,
+    pub index: usize,
+    pub x: f64,
+    pub y: f64,
+    pub text: String,
+}
+
+impl Guides {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        let (workspace, glass_to_world, component) = model::read_app_state(|app_state| {
+            (
+                app_state.workspace.clone(),
+                app_state.glass_to_world_transform.clone(),
+                app_state.selected_component_id.clone(),
+            )
+        });
+
+        let deps = [
+            workspace.untyped(),
+            glass_to_world.untyped(),
+            component.untyped(),
+        ];
+        let (workspace_cp, glass_to_world_cp) = (workspace.clone(), glass_to_world.clone());
+        self.guides.replace_with(Property::computed(
+            move || {
+                let glass_to_world = glass_to_world_cp.get();
+                let mut guides = SnapInfo::default();
+                for guide in workspace_cp.get().guides(&component.get()) {
+                    let line = SnapLine {
+                        line: guide.glass_position(glass_to_world),
+                        color: Guide::color(),
+                    };
+                    match guide.axis {
+                        GuideAxis::Vertical => guides.vertical.push(line),
+                        GuideAxis::Horizontal => guides.horizontal.push(line),
+                    }
+                }
+                guides
+            },
+            &deps,
+        ));
+
+        let bounds = ctx.bounds_self.clone();
+        let deps = [
+            workspace.untyped(),
+            glass_to_world.untyped(),
+            bounds.untyped(),
+        ];
+        self.grid.replace_with(Property::computed(
+            move || {
+                let (vertical, horizontal) = workspace
+                    .get()
+                    .grid
+                    .visible_lines(glass_to_world.get(), bounds.get());
+                let to_snap_lines = |lines: Vec<_>| {
+                    lines
+                        .into_iter()
+                        .map(|l: model::workspace::GridLine| SnapLine {
+                            line: l.position,
+                            color: GridSettings::line_color(l.major),
+                        })
+                        .collect()
+                };
+                SnapInfo {
+                    vertical: to_snap_lines(vertical),
+                    horizontal: to_snap_lines(horizontal),
+                    points: Vec::new(),
+                }
+            },
+            &deps,
+        ));
+    }
+
+    pub fn guide_mouse_down(&mut self, ctx: &NodeContext, args: Event<MouseDown>) {
+        args.prevent_default();
+        let pos = Point2::new(args.mouse.x, args.mouse.y);
+        let tool = model::with_action_context(ctx, |ac| {
+            let point = ac.glass_transform().get() * pos;
+            let index = closest_guide(ac, point)?;
+            GuideTool::existing_guide(ac, index, point)
+        });
+        if let Some(tool) = tool {
+            let tool: Rc<RefCell<dyn ToolBehavior>> = Rc::new(RefCell::new(tool));
+            model::perform_action(&SetToolBehaviour(Some(tool)), ctx);
+        }
+    }
+
+    pub fn guide_double_click(&mut self, ctx: &NodeContext, args: Event<DoubleClick>) {
+        args.prevent_default();
+        let pos = Point2::new(args.mouse.x, args.mouse.y);
+        let editor = model::with_action_context(ctx, |ac| {
+            let point = ac.glass_transform().get() * pos;
+            let index = closest_guide(ac, point)?;
+            let component = ac.app_state.selected_component_id.get();
+            let guide = *ac.app_state.workspace.get().guides(&component).get(index)?;
+            Some(GuideEditor {
+                active: true,
+                index,
+                x: point.x + 8.0,
+                y: point.y + 8.0,
+                text: guide.position.to_string(),
+            })
+        });
+        if let Some(editor) = editor {
+            self.editor.set(editor);
+        }
+    }
+
+    pub fn commit_guide_position(&mut self, ctx: &NodeContext, event: Event<TextboxChange>) {
+        let editor = self.editor.get();
+        self.editor.set(GuideEditor::default());
+        match event.text.trim().parse::<f64>() {
+            Ok(position) if position.is_finite() => model::perform_action(
+                &SetGuidePosition {
+                    index: editor.index,
+                    position,
+                },
+                ctx,
+            ),
+            _ => message_log_display::log(DesignerLogMsg::message(format!(
+                "{:?} is not a valid guide position",
+                event.text
+            ))),
+        }
+    }
+}
+
+/// Index of the guide of the edited component closest to point, if any is within hit distance
+fn closest_guide(ctx: &ActionContext, point: Point2<Glass>) -> Option<usize> {
+    let glass_to_world = ctx.world_transform();
+    let component = ctx.app_state.selected_component_id.get();
+    ctx.app_state
+        .workspace
+        .get()
+        .guides(&component)
+        .iter()
+        .enumerate()
+        .map(|(i, guide)| {
+            let along = match guide.axis {
+                GuideAxis::Vertical => point.x,
+                GuideAxis::Horizontal => point.y,
+            };
+            (i, (guide.glass_position(glass_to_world) - along).abs())
+        })
+        .filter(|(_, dist)| *dist <= GUIDE_HIT_DISTANCE)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(i, _)| i)
+}
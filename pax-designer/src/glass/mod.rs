@@ -13,10 +13,12 @@ use serde::Deserialize;
 
 use crate::controls::file_and_component_picker::SetLibraryState;
 use crate::designer_node_type::DesignerNodeType;
+use crate::model::action::orm::snippets::ImportSnippet;
 use crate::model::action::orm::CreateComponent;
 use crate::model::action::tool::SetToolBehaviour;
 use crate::model::action::world::Translate;
 use crate::model::action::world::{SelectMode, SelectNodes};
+use crate::model::property_recording::sync_recorded_selection;
 use crate::model::{AppState, GlassNode};
 use crate::{message_log_display, model, SetStage, StageInfo};
 
@@ -27,8 +29,12 @@ use crate::model::action::{Action, ActionContext, RaycastMode};
 use crate::model::input::{Dir, ModifierKey};
 
 pub mod control_point;
+pub mod gradient_stop_popover;
+pub mod guides;
 pub mod intent;
 pub mod outline;
+pub mod performance_overlay;
+pub mod rulers;
 pub mod tool_editors;
 pub mod wireframe_editor;
 
@@ -36,8 +42,12 @@ use self::intent::IntentDef;
 pub use self::tool_editors::TextEdit;
 use crate::message_log_display::DesignerLogMsg;
 use control_point::ControlPoint;
+use gradient_stop_popover::GradientStopPopover;
+use guides::Guides;
 use intent::Intent;
 use outline::PathOutline;
+use performance_overlay::PerformanceOverlay;
+use rulers::Rulers;
 use wireframe_editor::WireframeEditor;
 
 #[pax]
@@ -231,6 +241,18 @@ impl Glass {
     }
 
     pub fn handle_drop(&mut self, ctx: &NodeContext, event: Event<Drop>) {
+        if event.args.name.ends_with(&format!(
+            ".{}",
+            pax_designtime::snippet::SNIPPET_FILE_EXTENSION
+        )) {
+            model::perform_action(
+                &ImportSnippet {
+                    archive: &event.args.data,
+                },
+                ctx,
+            );
+            return;
+        }
         {
             let dt = borrow_mut!(ctx.designtime);
             if let Err(e) = dt.send_file_to_static_dir(&event.args.name, event.args.data) {
@@ -281,24 +303,28 @@ impl Glass {
     }
 }
 
+/// Whether the component is part of the project, rather than of pax_std or the
+/// designer itself, and can be opened for editing
+pub fn is_userland_component(type_id: &TypeId) -> bool {
+    !type_id
+        .import_path()
+        .is_some_and(|p| p.starts_with("pax_std") || p.starts_with("pax_designer"))
+}
+
 pub struct SetEditingComponent(pub TypeId);
 
 impl Action for SetEditingComponent {
     fn perform(&self, ctx: &mut ActionContext) -> anyhow::Result<()> {
         let type_id = &self.0;
 
-        let is_pax_std = type_id
-            .import_path()
-            .is_some_and(|p| p.starts_with("pax_std") || p.starts_with("pax_designer"));
-
-        if is_pax_std {
+        if !is_userland_component(type_id) {
             return Err(anyhow!(
                 "tried to edit a non-userland comp: {:?}",
                 type_id.import_path()
             ));
         }
 
-        SetLibraryState { open: false }.perform(ctx)?;
+        SetLibraryState::default().perform(ctx)?;
 
         // TODO set stage defaults for opened component using "SetStage" action
         {
@@ -310,6 +336,7 @@ impl Action for SetEditingComponent {
             .selected_template_node_ids
             .update(|v| v.clear());
         ctx.app_state.selected_component_id.set(type_id.clone());
+        sync_recorded_selection(ctx);
         Ok(())
     }
 }
@@ -0,0 +1,80 @@
+use std::rc::Rc;
+
+use pax_engine::api::*;
+use pax_engine::node_layout::TransformAndBounds;
+use pax_engine::pax_manifest::UniqueTemplateNodeIdentifier;
+use pax_engine::*;
+
+use super::intent::{Intent, IntentDef};
+use crate::model;
+use crate::model::performance::heat_color;
+
+/// Instances of a single template node (e.g. in a `for`) tinted at most, to keep
+/// the overlay itself from slowing down frames
+const MAX_TINTED_INSTANCES: usize = 100;
+
+/// Tints the bounds of the most expensive nodes of the component being edited
+/// while the performance overlay is open, the more expensive the redder.
+/// Updated every time new node costs are sampled.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[file("glass/performance_overlay.pax")]
+pub struct PerformanceOverlay {
+    pub heat_areas: Property<Vec<IntentDef>>,
+}
+
+impl PerformanceOverlay {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        let (open, component) = model::read_app_state(|app_state| {
+            (
+                app_state.performance_overlay_open.clone(),
+                app_state.selected_component_id.clone(),
+            )
+        });
+        let to_glass =
+            model::read_app_state_with_derived(|_, derived| derived.to_glass_transform.get());
+        let costs_version = borrow!(ctx.designtime).get_node_costs_version();
+        let deps = [
+            open.untyped(),
+            component.untyped(),
+            to_glass.untyped(),
+            costs_version.untyped(),
+        ];
+        let ctx = ctx.clone();
+        let dt = Rc::clone(&ctx.designtime);
+        self.heat_areas.replace_with(Property::computed(
+            move || {
+                if !open.get() {
+                    return vec![];
+                }
+                let hot_nodes = borrow!(dt).get_hot_nodes(&component.get());
+                let Some(max_cost) = hot_nodes.first().map(|n| n.cost.total_us()) else {
+                    return vec![];
+                };
+                let to_glass = TransformAndBounds {
+                    transform: to_glass.get(),
+                    bounds: (1.0, 1.0),
+                };
+                // most expensive first, so that it's drawn on top of cheaper children
+                hot_nodes
+                    .iter()
+                    .flat_map(|hot_node| {
+                        let fill =
+                            heat_color(hot_node.cost.total_us() / max_cost.max(f64::EPSILON));
+                        instances(&ctx, &hot_node.uni).into_iter().map(move |node| {
+                            let t_and_b = to_glass * node.transform_and_bounds().get();
+                            IntentDef::new(t_and_b.as_transform(), fill.clone(), None)
+                        })
+                    })
+                    .collect()
+            },
+            &deps,
+        ));
+    }
+}
+
+fn instances(ctx: &NodeContext, uni: &UniqueTemplateNodeIdentifier) -> Vec<NodeInterface> {
+    let mut nodes = ctx.get_nodes_by_global_id(uni.clone());
+    nodes.truncate(MAX_TINTED_INSTANCES);
+    nodes
+}
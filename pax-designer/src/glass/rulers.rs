@@ -0,0 +1,268 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use pax_engine::api::*;
+use pax_engine::math::{Point2, Transform2};
+use pax_engine::*;
+use pax_std::*;
+
+use crate::math::coordinate_spaces::{Glass, World};
+use crate::math::AxisAlignedBox;
+use crate::message_log_display::{self, DesignerLogMsg};
+use crate::model::action::tool::SetToolBehaviour;
+use crate::model::tools::GuideTool;
+use crate::model::workspace::{GridSettings, GuideAxis, SetGridSettings, ToggleGrid, RULER_SIZE};
+use crate::model::{self, ToolBehavior};
+
+/// Labeled ticks are spaced at least this far apart (in glass pixels),
+/// using the smallest "round" world distance that satisfies it
+const MIN_LABEL_DISTANCE: f64 = 60.0;
+
+/// Rulers along the top and left edges of the glass, showing world
+/// coordinates at the current zoom level and the extent of the selection.
+/// Dragging out of a ruler creates a guide, and the corner between the
+/// rulers opens the grid settings.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[file("glass/rulers.pax")]
+pub struct Rulers {
+    pub visible: Property<bool>,
+    pub horizontal: Property<RulerMarks>,
+    pub vertical: Property<RulerMarks>,
+    pub selection_extent: Property<RulerExtent>,
+    pub on_selection_changed: Property<bool>,
+    pub grid_settings_open: Property<bool>,
+    pub grid_toggle_label: Property<String>,
+    pub grid_spacing: Property<String>,
+    pub grid_subdivisions: Property<String>,
+}
+
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct RulerMarks {
+    pub ticks: Vec<RulerTick>,
+    pub labels: Vec<RulerLabel>,
+}
+
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct RulerTick {
+    /// glass coordinate along the ruler
+    pub position: f64,
+    pub length: f64,
+}
+
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct RulerLabel {
+    /// glass coordinate along the ruler
+    pub position: f64,
+    pub text: String,
+}
+
+/// Glass space extent of the current selection
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct RulerExtent {
+    pub active: bool,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rulers {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        let (workspace, glass_to_world) = model::read_app_state(|app_state| {
+            (
+                app_state.workspace.clone(),
+                app_state.glass_to_world_transform.clone(),
+            )
+        });
+        let bounds = ctx.bounds_self.clone();
+
+        let deps = [glass_to_world.untyped(), bounds.untyped()];
+        let (glass_to_world_cp, bounds_cp) = (glass_to_world.clone(), bounds.clone());
+        self.horizontal.replace_with(Property::computed(
+            move || {
+                let glass_to_world = glass_to_world_cp.get();
+                let (width, _) = bounds_cp.get();
+                ruler_marks(glass_to_world, width, |p| p.x)
+            },
+            &deps,
+        ));
+        self.vertical.replace_with(Property::computed(
+            move || {
+                let glass_to_world = glass_to_world.get();
+                let (_, height) = bounds.get();
+                ruler_marks(glass_to_world, height, |p| p.y)
+            },
+            &deps,
+        ));
+
+        let deps = [workspace.untyped()];
+        let workspace_cp = workspace.clone();
+        self.visible.replace_with(Property::computed(
+            move || workspace_cp.get().show_rulers,
+            &deps,
+        ));
+        let workspace_cp = workspace.clone();
+        self.grid_toggle_label.replace_with(Property::computed(
+            move || {
+                match workspace_cp.get().grid.enabled {
+                    true => "Hide grid",
+                    false => "Show grid",
+                }
+                .to_string()
+            },
+            &deps,
+        ));
+        let workspace_cp = workspace.clone();
+        self.grid_spacing.replace_with(Property::computed(
+            move || workspace_cp.get().grid.spacing.to_string(),
+            &deps,
+        ));
+        self.grid_subdivisions.replace_with(Property::computed(
+            move || workspace.get().grid.subdivisions.to_string(),
+            &deps,
+        ));
+
+        // rebind the extent to the bounds of the selection whenever the selection changes
+        let selection =
+            model::read_app_state_with_derived(|_, derived| derived.selection_state.clone());
+        let deps = [selection.untyped()];
+        let selection_extent = self.selection_extent.clone();
+        self.on_selection_changed.replace_with(Property::computed(
+            move || {
+                let selection = selection.get();
+                if selection.items.is_empty() {
+                    selection_extent.replace_with(Property::new(RulerExtent::default()));
+                } else {
+                    let total_bounds = selection.total_bounds.clone();
+                    let deps = [total_bounds.untyped()];
+                    selection_extent.replace_with(Property::computed(
+                        move || {
+                            let axis_box =
+                                AxisAlignedBox::bound_of_points(total_bounds.get().corners());
+                            RulerExtent {
+                                active: true,
+                                x: axis_box.top_left().x,
+                                y: axis_box.top_left().y,
+                                width: axis_box.width(),
+                                height: axis_box.height(),
+                            }
+                        },
+                        &deps,
+                    ));
+                }
+                true
+            },
+            &deps,
+        ));
+    }
+
+    pub fn pre_render(&mut self, _ctx: &NodeContext) {
+        // Fire lazy prop if dirty every tick
+        self.on_selection_changed.get();
+    }
+
+    pub fn top_ruler_mouse_down(&mut self, ctx: &NodeContext, args: Event<MouseDown>) {
+        args.prevent_default();
+        Self::start_guide(ctx, GuideAxis::Horizontal, &args);
+    }
+
+    pub fn left_ruler_mouse_down(&mut self, ctx: &NodeContext, args: Event<MouseDown>) {
+        args.prevent_default();
+        Self::start_guide(ctx, GuideAxis::Vertical, &args);
+    }
+
+    fn start_guide(ctx: &NodeContext, axis: GuideAxis, args: &Event<MouseDown>) {
+        let pos = Point2::new(args.mouse.x, args.mouse.y);
+        let tool = model::with_action_context(ctx, |ac| {
+            let point = ac.glass_transform().get() * pos;
+            GuideTool::new_guide(ac, axis, point)
+        });
+        let tool: Rc<RefCell<dyn ToolBehavior>> = Rc::new(RefCell::new(tool));
+        model::perform_action(&SetToolBehaviour(Some(tool)), ctx);
+    }
+
+    pub fn toggle_grid_settings(&mut self, _ctx: &NodeContext, _args: Event<Click>) {
+        self.grid_settings_open.set(!self.grid_settings_open.get());
+    }
+
+    pub fn toggle_grid(&mut self, ctx: &NodeContext, _args: Event<Click>) {
+        model::perform_action(&ToggleGrid, ctx);
+    }
+
+    pub fn set_grid_spacing(&mut self, ctx: &NodeContext, event: Event<TextboxChange>) {
+        match event.text.trim().parse::<f64>() {
+            Ok(spacing) => Self::update_grid(ctx, |grid| grid.spacing = spacing),
+            Err(_) => message_log_display::log(DesignerLogMsg::message(format!(
+                "{:?} is not a valid grid spacing",
+                event.text
+            ))),
+        }
+    }
+
+    pub fn set_grid_subdivisions(&mut self, ctx: &NodeContext, event: Event<TextboxChange>) {
+        match event.text.trim().parse::<u32>() {
+            Ok(subdivisions) => Self::update_grid(ctx, |grid| grid.subdivisions = subdivisions),
+            Err(_) => message_log_display::log(DesignerLogMsg::message(format!(
+                "{:?} is not a valid number of grid subdivisions",
+                event.text
+            ))),
+        }
+    }
+
+    fn update_grid(ctx: &NodeContext, update: impl FnOnce(&mut GridSettings)) {
+        let mut grid = model::read_app_state(|app_state| app_state.workspace.get().grid);
+        update(&mut grid);
+        model::perform_action(&SetGridSettings(grid), ctx);
+    }
+}
+
+/// Ticks and labels for a ruler spanning glass coordinates 0..length, where
+/// axis picks the coordinate the ruler measures
+fn ruler_marks(
+    glass_to_world: Transform2<Glass, World>,
+    length: f64,
+    axis: impl Fn(Point2<World>) -> f64,
+) -> RulerMarks {
+    let world_per_glass = glass_to_world.get_scale().x;
+    let label_step = nice_step(MIN_LABEL_DISTANCE * world_per_glass);
+    let tick_step = label_step / 10.0;
+    let decimals = (-label_step.log10().floor()).max(0.0) as usize;
+
+    let start = axis(glass_to_world * Point2::new(0.0, 0.0));
+    let end = axis(glass_to_world * Point2::new(length, length));
+    let glass_per_world = 1.0 / world_per_glass;
+
+    let mut marks = RulerMarks::default();
+    for i in (start / tick_step).ceil() as i64..=(end / tick_step).floor() as i64 {
+        let world = i as f64 * tick_step;
+        let position = (world - start) * glass_per_world;
+        let length = match i.rem_euclid(10) {
+            0 => RULER_SIZE,
+            5 => RULER_SIZE * 0.4,
+            _ => RULER_SIZE * 0.2,
+        };
+        marks.ticks.push(RulerTick { position, length });
+        if i.rem_euclid(10) == 0 {
+            marks.labels.push(RulerLabel {
+                position,
+                text: format!("{:.*}", decimals, world),
+            });
+        }
+    }
+    marks
+}
+
+/// Smallest 1, 2 or 5 times a power of ten that is at least min_step
+fn nice_step(min_step: f64) -> f64 {
+    let magnitude = 10f64.powf(min_step.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|m| m * magnitude)
+        .find(|step| *step >= min_step)
+        .unwrap_or(10.0 * magnitude)
+}
@@ -8,6 +8,7 @@ use pax_std::core::text::Text;
 
 use crate::model::action::tool::SetToolBehaviour;
 use crate::model::input::{InputEvent, ModifierKey};
+use crate::model::keymap::KeyContext;
 use crate::{
     math::coordinate_spaces::Glass,
     model::{
@@ -66,13 +67,17 @@ impl TextEditTool {
             }
             _ => return Err("can't edit non-text node".to_owned()),
         }
+        // keep single key canvas shortcuts from firing while typing
+        ctx.app_state
+            .input_mapper
+            .update(|input_mapper| input_mapper.push_scope(KeyContext::TextEditing));
         Ok(Self { uid, text_binding })
     }
 }
 
 impl ToolBehavior for TextEditTool {
     fn pointer_down(&mut self, point: Point2<Glass>, ctx: &mut ActionContext) -> ControlFlow<()> {
-        if let Some(hit) = ctx.raycast_glass(point, RaycastMode::Top, &[]) {
+        if let Some(hit) = ctx.raycast_glass_ignoring_chrome(point, RaycastMode::Top, &[]) {
             let node_id = hit.global_id().unwrap().get_template_node_id();
             if node_id == self.uid.get_template_node_id() {
                 return ControlFlow::Continue(());
@@ -93,6 +98,9 @@ impl ToolBehavior for TextEditTool {
     }
 
     fn finish(&mut self, ctx: &mut ActionContext) -> anyhow::Result<()> {
+        ctx.app_state
+            .input_mapper
+            .update(|input_mapper| input_mapper.pop_scope(KeyContext::TextEditing));
         let node = ctx.get_glass_node_by_global_id(&self.uid)?;
         node.raw_node_interface.with_properties(|text: &mut Text| {
             text.editable.replace_with(Property::new(false));
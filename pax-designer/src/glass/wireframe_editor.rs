@@ -49,7 +49,10 @@ impl WireframeEditor {
             derived_state.selection_state.clone()
         });
         let selected_cp = selected.clone();
-        let deps = [selected.untyped()];
+        // the controls of the selection change when entering or leaving gradient editing
+        let gradient_editing =
+            model::read_app_state(|app_state| app_state.gradient_editing.clone());
+        let deps = [selected.untyped(), gradient_editing.untyped()];
 
         let control_points = self.control_points.clone();
         let bounding_segments = self.bounding_segments.clone();
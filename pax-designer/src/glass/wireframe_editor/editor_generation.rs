@@ -17,12 +17,14 @@ use crate::{
         BoxPoint,
     },
     model::{
+        self,
         action::{self, Action, ActionContext},
         GlassNodeSnapshot, SelectionState, SelectionStateSnapshot, ToolBehavior,
     },
 };
 
 impl Interpolatable for Editor {}
+pub mod gradient_control;
 pub mod slot_control;
 pub mod stacker_control;
 
@@ -68,15 +70,18 @@ impl Editor {
             move || {
                 let (resize_and_rotate_sets, bounding_segments) = total_bound_derived.get();
                 let anchor = anchor_derived.get();
-                let object_specific_derived =
+                let object_specific_derived: Vec<ControlPointSet> =
                     object_specific_derived.iter().map(Property::get).collect();
-                Self {
-                    controls: [resize_and_rotate_sets, anchor, object_specific_derived]
+                let controls: Vec<ControlPointSet> =
+                    [resize_and_rotate_sets, anchor, object_specific_derived]
                         .into_iter()
                         .flatten()
-                        .collect(),
-                    segments: bounding_segments,
-                }
+                        .collect();
+                let segments = bounding_segments
+                    .into_iter()
+                    .chain(controls.iter().flat_map(|set| set.segments.clone()))
+                    .collect();
+                Self { controls, segments }
             },
             &deps,
         )
@@ -140,6 +145,7 @@ impl Editor {
         ControlPointSet {
             points: rotate_control_points,
             styling: rotate_control_point_styling,
+            segments: vec![],
         }
     }
 
@@ -248,6 +254,7 @@ impl Editor {
         ControlPointSet {
             points: resize_control_points,
             styling: resize_control_point_styling,
+            segments: vec![],
         }
     }
 
@@ -319,6 +326,7 @@ impl Editor {
         ControlPointSet {
             points: anchor_control_point,
             styling: anchor_control_point_styling,
+            segments: vec![],
         }
     }
 
@@ -330,6 +338,10 @@ impl Editor {
             return Vec::default();
         }
         let item = selection.items.into_iter().next().unwrap();
+        let gradient_editing = model::read_app_state(|app_state| app_state.gradient_editing.get());
+        if gradient_editing.as_ref() == Some(&item.id) {
+            return gradient_control::gradient_control_sets(ctx, item);
+        }
         let type_id = {
             let mut dt = borrow_mut!(ctx.designtime);
             let Some(builder) = dt.get_orm_mut().get_node(item.id.clone(), false) else {
@@ -371,4 +383,6 @@ impl Interpolatable for ControlPointSet {}
 pub struct ControlPointSet {
     pub points: Vec<CPoint>,
     pub styling: ControlPointStyling,
+    /// Lines drawn along with the control points, e.g. the line of a gradient
+    pub segments: Vec<(Point2<Glass>, Point2<Glass>)>,
 }
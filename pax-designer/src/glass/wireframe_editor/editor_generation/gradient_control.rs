@@ -0,0 +1,369 @@
+use std::{cell::RefCell, rc::Rc};
+
+use pax_engine::api::{borrow, Color, Fill, Size};
+use pax_engine::math::{Transform2, Vector2};
+use pax_engine::pax_manifest::UniqueTemplateNodeIdentifier;
+use pax_engine::{api::NodeContext, math::Point2, NodeLocal, Property};
+
+use crate::glass::control_point::{
+    ControlPointBehavior, ControlPointStyling, ControlPointTool, ControlPointToolFactory,
+};
+use crate::math::coordinate_spaces::Glass;
+use crate::model::action::orm::gradient::{
+    gradient_stops_mut, node_fill, GradientStopColorEdit, SetFill,
+};
+use crate::model::input::ModifierKey;
+use crate::model::{
+    self,
+    action::{Action, ActionContext},
+    GlassNode,
+};
+
+use super::{CPoint, ControlPointSet};
+
+/// Control points for editing the gradient fill of `item` on the canvas: handles for the
+/// geometry of the gradient (start/end for linear gradients, center/radius for radial ones) and
+/// markers for the stops along the gradient line.
+///
+/// Gradient coordinates are relative to the bounds of the node, so all of the math happens in
+/// the unit space of the node, mapped to the glass by its transform. This keeps handles attached
+/// to the right spots of rotated and scaled nodes.
+pub fn gradient_control_sets(ctx: NodeContext, item: GlassNode) -> Vec<Property<ControlPointSet>> {
+    let to_glass_transform =
+        model::read_app_state_with_derived(|_, derived| derived.to_glass_transform.get());
+    let manifest_ver = borrow!(ctx.designtime).get_manifest_version();
+    let object_transform = item.transform_and_bounds.clone();
+    let deps = [object_transform.untyped(), manifest_ver.untyped()];
+
+    let current = {
+        let item_id = item.id.clone();
+        let ctx = ctx.clone();
+        move || {
+            let node = ctx
+                .get_nodes_by_global_id(item_id.clone())
+                .into_iter()
+                .next()?;
+            let node = GlassNode::new(&node, &to_glass_transform);
+            let fill = node_fill(&node.raw_node_interface)?;
+            let geometry = GradientGeometry::new(&fill, node.transform_and_bounds.get().bounds)?;
+            Some((node, fill, geometry))
+        }
+    };
+
+    let handles = {
+        let current = current.clone();
+        Property::computed(
+            move || {
+                let Some((node, fill, geometry)) = current() else {
+                    return ControlPointSet::default();
+                };
+                let t = node.transform_and_bounds.get().as_transform();
+                let points = geometry
+                    .handles()
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, p)| {
+                        CPoint::new(
+                            t * p,
+                            geometry_handle_factory(node.clone(), fill.clone(), i),
+                        )
+                    })
+                    .collect();
+                ControlPointSet {
+                    points,
+                    styling: ControlPointStyling {
+                        affected_by_transform: false,
+                        round: true,
+                        stroke: Color::BLUE,
+                        fill: Color::WHITE,
+                        stroke_width_pixels: 2.0,
+                        width: 12.0,
+                        height: 12.0,
+                    },
+                    segments: vec![(t * geometry.line.0, t * geometry.line.1)],
+                }
+            },
+            &deps,
+        )
+    };
+
+    let stops = Property::computed(
+        move || {
+            let Some((node, fill, geometry)) = current() else {
+                return ControlPointSet::default();
+            };
+            let t = node.transform_and_bounds.get().as_transform();
+            let (a, b) = geometry.line;
+            let points = stop_positions(&fill)
+                .into_iter()
+                .enumerate()
+                .map(|(i, position)| {
+                    CPoint::new(
+                        t * a.lerp_towards(b, position),
+                        stop_marker_factory(node.clone(), fill.clone(), geometry.clone(), i),
+                    )
+                })
+                .collect();
+            ControlPointSet {
+                points,
+                styling: ControlPointStyling {
+                    affected_by_transform: false,
+                    round: false,
+                    stroke: Color::BLUE,
+                    fill: Color::WHITE,
+                    stroke_width_pixels: 1.0,
+                    width: 8.0,
+                    height: 8.0,
+                },
+                segments: vec![],
+            }
+        },
+        &deps,
+    );
+
+    vec![handles, stops]
+}
+
+/// Geometry of a gradient in the unit space of its node, where (0, 0) is the top left corner
+/// and (1, 1) the bottom right one
+#[derive(Clone)]
+struct GradientGeometry {
+    /// Line along which the stops are placed
+    line: (Point2<NodeLocal>, Point2<NodeLocal>),
+    bounds: (f64, f64),
+}
+
+impl GradientGeometry {
+    fn new(fill: &Fill, bounds: (f64, f64)) -> Option<Self> {
+        let line = match fill {
+            Fill::Solid(_) => return None,
+            Fill::LinearGradient(linear) => {
+                (to_unit(&linear.start, bounds), to_unit(&linear.end, bounds))
+            }
+            // piet centers radial gradients on `end`, the radius is in unit space
+            Fill::RadialGradient(radial) => {
+                let center = to_unit(&radial.end, bounds);
+                (center, center + Vector2::new(radial.radius, 0.0))
+            }
+        };
+        Some(Self { line, bounds })
+    }
+
+    /// Start and end of a linear gradient, or center and radius of a radial one
+    fn handles(&self) -> [Point2<NodeLocal>; 2] {
+        [self.line.0, self.line.1]
+    }
+
+    /// `fill` with handle `handle` of its geometry moved to `point`
+    fn move_handle(&self, fill: &Fill, handle: usize, point: Point2<NodeLocal>) -> Fill {
+        let mut fill = fill.clone();
+        match &mut fill {
+            Fill::Solid(_) => (),
+            Fill::LinearGradient(linear) => {
+                let target = if handle == 0 {
+                    &mut linear.start
+                } else {
+                    &mut linear.end
+                };
+                *target = from_unit(point, target, self.bounds);
+            }
+            Fill::RadialGradient(radial) => {
+                if handle == 0 {
+                    // move the focal point along with the center
+                    let offset = point - self.line.0;
+                    let origin = to_unit(&radial.start, self.bounds) + offset;
+                    radial.start = from_unit(origin, &radial.start, self.bounds);
+                    radial.end = from_unit(point, &radial.end, self.bounds);
+                } else {
+                    radial.radius = round_4_dec((point - self.line.0).length());
+                }
+            }
+        }
+        fill
+    }
+}
+
+fn geometry_handle_factory(node: GlassNode, fill: Fill, handle: usize) -> ControlPointToolFactory {
+    struct GeometryHandleBehavior {
+        node: UniqueTemplateNodeIdentifier,
+        to_unit: Transform2<Glass, NodeLocal>,
+        geometry: GradientGeometry,
+        initial_fill: Fill,
+        handle: usize,
+    }
+
+    impl ControlPointBehavior for GeometryHandleBehavior {
+        fn step(&self, ctx: &mut ActionContext, point: Point2<Glass>) -> anyhow::Result<()> {
+            let fill =
+                self.geometry
+                    .move_handle(&self.initial_fill, self.handle, self.to_unit * point);
+            SetFill {
+                node: &self.node,
+                fill: &fill,
+            }
+            .perform(ctx)
+        }
+    }
+
+    ControlPointToolFactory {
+        tool_factory: Rc::new(move |ac, _p| {
+            let tab = node.transform_and_bounds.get();
+            Rc::new(RefCell::new(ControlPointTool::new(
+                ac.transaction("moving gradient handle"),
+                None,
+                GeometryHandleBehavior {
+                    node: node.id.clone(),
+                    to_unit: tab.as_transform().inverse(),
+                    geometry: GradientGeometry::new(&fill, tab.bounds)
+                        .expect("handles only exist for gradients"),
+                    initial_fill: fill.clone(),
+                    handle,
+                },
+            )))
+        }),
+        double_click_behavior: Rc::new(|_| ()),
+    }
+}
+
+fn stop_marker_factory(
+    node: GlassNode,
+    fill: Fill,
+    geometry: GradientGeometry,
+    stop: usize,
+) -> ControlPointToolFactory {
+    struct StopMarkerBehavior {
+        node: UniqueTemplateNodeIdentifier,
+        line: (Point2<Glass>, Point2<Glass>),
+        initial_fill: Fill,
+        stop: usize,
+    }
+
+    impl ControlPointBehavior for StopMarkerBehavior {
+        fn step(&self, ctx: &mut ActionContext, point: Point2<Glass>) -> anyhow::Result<()> {
+            // project onto the gradient line as drawn, so that dragging follows the pointer
+            // regardless of how the node is scaled
+            let (a, b) = self.line;
+            let line = b - a;
+            let offset = point - a;
+            let t = if line.length_squared() > 0.0 {
+                ((offset.x * line.x + offset.y * line.y) / line.length_squared()).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let mut fill = self.initial_fill.clone();
+            if let Some(stops) = gradient_stops_mut(&mut fill) {
+                if let Some(stop) = stops.get_mut(self.stop) {
+                    stop.position = Size::Percent(round_2_dec(t * 100.0).into());
+                }
+                stops.sort_by(|a, b| {
+                    percent(&a.position)
+                        .partial_cmp(&percent(&b.position))
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                });
+            }
+            SetFill {
+                node: &self.node,
+                fill: &fill,
+            }
+            .perform(ctx)
+        }
+    }
+
+    let node_id = node.id.clone();
+    let double_click_fill = fill.clone();
+    let double_click_geometry = geometry.clone();
+    let double_click_node = node.clone();
+    ControlPointToolFactory {
+        tool_factory: Rc::new(move |ac, _p| {
+            let t = node.transform_and_bounds.get().as_transform();
+            let mut initial_fill = fill.clone();
+            let mut stop = stop;
+            // alt-dragging duplicates the stop and drags the copy
+            if ac.app_state.modifiers.get().contains(&ModifierKey::Alt) {
+                if let Some(stops) = gradient_stops_mut(&mut initial_fill) {
+                    if let Some(copy) = stops.get(stop).cloned() {
+                        stops.insert(stop + 1, copy);
+                        stop += 1;
+                    }
+                }
+            }
+            Rc::new(RefCell::new(ControlPointTool::new(
+                ac.transaction("moving gradient stop"),
+                None,
+                StopMarkerBehavior {
+                    node: node_id.clone(),
+                    line: (t * geometry.line.0, t * geometry.line.1),
+                    initial_fill,
+                    stop,
+                },
+            )))
+        }),
+        double_click_behavior: Rc::new(move |ac| {
+            let t = double_click_node.transform_and_bounds.get().as_transform();
+            let (a, b) = double_click_geometry.line;
+            let position = stop_positions(&double_click_fill)
+                .get(stop)
+                .map(|&position| t * a.lerp_towards(b, position))
+                .unwrap_or(t * a);
+            ac.app_state
+                .gradient_stop_color_edit
+                .set(Some(GradientStopColorEdit {
+                    node: double_click_node.id.clone(),
+                    stop,
+                    position,
+                }));
+        }),
+    }
+}
+
+/// Positions of the stops of `fill` along the gradient line, from 0 to 1
+fn stop_positions(fill: &Fill) -> Vec<f64> {
+    let stops = match fill {
+        Fill::Solid(_) => return vec![],
+        Fill::LinearGradient(linear) => &linear.stops,
+        Fill::RadialGradient(radial) => &radial.stops,
+    };
+    stops
+        .iter()
+        .map(|stop| percent(&stop.position) / 100.0)
+        .collect()
+}
+
+fn percent(size: &Size) -> f64 {
+    match size {
+        Size::Percent(p) | Size::Combined(_, p) => p.to_float(),
+        Size::Pixels(_) => 0.0,
+    }
+}
+
+fn to_unit((x, y): &(Size, Size), (width, height): (f64, f64)) -> Point2<NodeLocal> {
+    let unit = |size: &Size, length: f64| {
+        if length > 0.0 {
+            size.get_pixels(length) / length
+        } else {
+            0.0
+        }
+    };
+    Point2::new(unit(x, width), unit(y, height))
+}
+
+/// `point` as a gradient coordinate, in the same units as `like`
+fn from_unit(
+    point: Point2<NodeLocal>,
+    (like_x, like_y): &(Size, Size),
+    (width, height): (f64, f64),
+) -> (Size, Size) {
+    let size = |value: f64, like: &Size, length: f64| match like {
+        Size::Pixels(_) => Size::Pixels(round_2_dec(value * length).into()),
+        _ => Size::Percent(round_2_dec(value * 100.0).into()),
+    };
+    (size(point.x, like_x, width), size(point.y, like_y, height))
+}
+
+fn round_2_dec(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+fn round_4_dec(v: f64) -> f64 {
+    (v * 10000.0).round() / 10000.0
+}
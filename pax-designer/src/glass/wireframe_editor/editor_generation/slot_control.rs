@@ -212,6 +212,7 @@ pub fn slot_dot_control_set(ctx: NodeContext, item: GlassNode) -> Property<Contr
             ControlPointSet {
                 points: slot_dot_control_points,
                 styling: slot_dot_point_styling.clone(),
+                segments: vec![],
             }
         },
         &deps,
@@ -234,6 +234,7 @@ pub fn stacker_divider_control_set(ctx: NodeContext, item: GlassNode) -> Propert
             ControlPointSet {
                 points: stacker_divider_control_points,
                 styling: cp_style,
+                segments: vec![],
             }
         },
         &deps,
@@ -8,13 +8,13 @@ use pax_engine::{
 };
 use pax_manifest::TypeId;
 use pax_std::*;
-use std::{collections::HashSet, rc::Rc, sync::Mutex};
+use std::{collections::HashSet, rc::Rc, sync::Mutex, time::Duration};
 
 use pax_std::inline_frame::InlineFrame;
 
 use crate::math::coordinate_spaces::{self, World};
 use model::{
-    action::{meta::Schedule, pointer::Pointer, Action, ActionContext},
+    action::{inspect::InspectDuringPlay, meta::Schedule, pointer::Pointer, Action, ActionContext},
     input::Dir,
     ProjectMode, StageInfo,
 };
@@ -33,6 +33,11 @@ pub mod project_mode_toggle;
 pub mod project_publish_button;
 
 use context_menu::DesignerContextMenu;
+use controls::keymap_settings::KeymapSettings;
+use controls::contrast_audit::ContrastAuditPanel;
+use controls::performance::PerformancePanel;
+use controls::review_changes::ReviewChangesPanel;
+use controls::snippets::SnippetLibrary;
 use controls::{
     settings::color_picker,
     toolbar::{self, CloseDropdown},
@@ -59,6 +64,10 @@ pub const DESIGNER_GLASS_ID: &str = "designer_glass";
 //we are reading this env var at compiletime and exposing it via a const to runtime.
 const PAX_PUBLISH_BUTTON_ENABLED: bool = option_env!("PAX_PUBLISH_BUTTON").is_some();
 
+//Seconds to wait for the manifest from the design server before offering a retry, read at
+//compiletime like PAX_PUBLISH_BUTTON; defaults to DEFAULT_MANIFEST_LOAD_TIMEOUT
+const PAX_MANIFEST_LOAD_TIMEOUT_SECS: Option<&str> = option_env!("PAX_MANIFEST_LOAD_TIMEOUT_SECS");
+
 #[pax]
 #[engine_import_path("pax_engine")]
 #[main]
@@ -70,6 +79,10 @@ pub struct PaxDesigner {
     pub play_active: Property<bool>,
     pub glass_active: Property<bool>,
     pub manifest_loaded_from_server: Property<bool>,
+    pub manifest_load_failed: Property<bool>,
+    pub manifest_load_message: Property<String>,
+    /// Fraction of the manifest received, from 0 to 1
+    pub manifest_load_progress: Property<f64>,
     pub show_publish_button: Property<bool>,
 }
 
@@ -85,11 +98,33 @@ impl PaxDesigner {
             self.bind_stage_outline_width_property(&app_state);
         });
 
-        // used to show "loading screen"
-        let manifest_load_state = borrow!(ctx.designtime).get_manifest_loaded_from_server_prop();
-        let deps = [manifest_load_state.untyped()];
+        self.bind_manifest_load_state(ctx);
+    }
+
+    // used to show the loading screen
+    fn bind_manifest_load_state(&mut self, ctx: &NodeContext) {
+        if let Some(secs) = PAX_MANIFEST_LOAD_TIMEOUT_SECS.and_then(|s| s.parse().ok()) {
+            borrow_mut!(ctx.designtime).set_manifest_load_timeout(Duration::from_secs(secs));
+        }
+        let load_state = borrow!(ctx.designtime).get_manifest_load_state_prop();
+        let deps = [load_state.untyped()];
+        let state = load_state.clone();
         self.manifest_loaded_from_server
-            .replace_with(Property::computed(move || manifest_load_state.get(), &deps));
+            .replace_with(Property::computed(move || state.get().is_complete(), &deps));
+        let state = load_state.clone();
+        self.manifest_load_failed
+            .replace_with(Property::computed(move || state.get().is_failed(), &deps));
+        let state = load_state.clone();
+        self.manifest_load_message
+            .replace_with(Property::computed(move || state.get().message(), &deps));
+        self.manifest_load_progress.replace_with(Property::computed(
+            move || load_state.get().progress(),
+            &deps,
+        ));
+    }
+
+    pub fn retry_manifest_load(&mut self, ctx: &NodeContext, _args: Event<ButtonClick>) {
+        borrow_mut!(ctx.designtime).retry_manifest_load();
     }
 
     pub fn tick(&mut self, ctx: &NodeContext) {
@@ -116,6 +151,10 @@ impl PaxDesigner {
             });
         }
 
+        model::workspace::load_persisted_workspace_state(ctx);
+        model::keymap::load_persisted_keymap(ctx);
+        model::action::orm::snippets::process_snippet_inbox(ctx);
+        model::session::process_bug_report_messages(ctx);
         model::action::meta::flush_sheduled_actions(ctx);
     }
 
@@ -223,6 +262,35 @@ impl PaxDesigner {
         }
     }
 
+    /// Cmd+Shift+Click while playing inspects the clicked node in edit mode,
+    /// adding Alt drills into the component it's part of
+    pub fn handle_click(&mut self, ctx: &NodeContext, event: Event<Click>) {
+        let project_mode = model::read_app_state(|app_state| app_state.project_mode.get());
+        if !matches!(project_mode, ProjectMode::Playing) {
+            return;
+        }
+        let modifiers = &event.mouse.modifiers;
+        let pressed = |f: fn(&pax_engine::api::ModifierKey) -> bool| modifiers.iter().any(f);
+        let command = pressed(|m| {
+            matches!(
+                m,
+                pax_engine::api::ModifierKey::Command | pax_engine::api::ModifierKey::Control
+            )
+        });
+        let shift = pressed(|m| matches!(m, pax_engine::api::ModifierKey::Shift));
+        if !(command && shift) {
+            return;
+        }
+        event.prevent_default();
+        model::perform_action(
+            &InspectDuringPlay {
+                point: Point2::new(event.mouse.x, event.mouse.y),
+                drill_in: pressed(|m| matches!(m, pax_engine::api::ModifierKey::Alt)),
+            },
+            ctx,
+        );
+    }
+
     pub fn handle_key_down(&mut self, ctx: &NodeContext, event: Event<KeyDown>) {
         event.prevent_default();
         model::process_keyboard_input(ctx, Dir::Down, event.keyboard.key.clone());
@@ -239,6 +307,9 @@ pub struct ProjectMsg(pub ProjectMode);
 impl Action for ProjectMsg {
     fn perform(&self, ctx: &mut ActionContext) -> anyhow::Result<()> {
         ctx.app_state.project_mode.set(self.0.clone());
+        // recorded values only change while playing
+        borrow_mut!(ctx.engine_context.designtime)
+            .set_property_recording_paused(matches!(self.0, ProjectMode::Edit));
         Ok(())
     }
 }
@@ -9,6 +9,7 @@ use pax_std::*;
 use crate::model::{
     action::{orm::SerializeRequested, Action, ActionContext},
     input::InputEvent,
+    keymap::KeyContext,
 };
 #[pax]
 #[engine_import_path("pax_engine")]
@@ -23,7 +24,18 @@ pub struct SetLLMPromptState(pub bool);
 impl Action for SetLLMPromptState {
     fn perform(&self, ctx: &mut ActionContext) -> anyhow::Result<()> {
         SerializeRequested.perform(ctx)?;
+        let was_open = OPEN_LLM_PROMPT_PROP.with(|p| p.get());
         OPEN_LLM_PROMPT_PROP.with(|p| p.set(self.0));
+        if was_open == self.0 {
+            return Ok(());
+        }
+        ctx.app_state.input_mapper.update(|input_mapper| {
+            if self.0 {
+                input_mapper.push_scope(KeyContext::TextEditing);
+            } else {
+                input_mapper.pop_scope(KeyContext::TextEditing);
+            }
+        });
         Ok(())
     }
 }
@@ -14,8 +14,10 @@ use crate::{
     model::{
         action::ActionContext,
         input::{InputEvent, ModifierKey},
+        workspace::{GridSettings, Guide, GuideAxis},
         GlassNode,
     },
+    DESIGNER_GLASS_ID,
 };
 
 use super::{
@@ -57,14 +59,54 @@ impl IntentSnapper {
             snap_set_children.add_lines_from_axis_aligned_bounds(t_and_b);
             to_process.extend(node.children())
         }
+        let [snap_set_guides, snap_set_grid] = Self::workspace_snap_sets(ctx);
         Self::new(
             ctx,
             SnapCollection {
-                sets: vec![snap_set_scene, snap_set_children],
+                sets: vec![
+                    snap_set_scene,
+                    snap_set_children,
+                    snap_set_guides,
+                    snap_set_grid,
+                ],
             },
         )
     }
 
+    /// Guides of the edited component, and grid lines in view if the grid is enabled
+    fn workspace_snap_sets(ctx: &ActionContext) -> [SnapSet; 2] {
+        let workspace = ctx.app_state.workspace.get();
+        let glass_to_world = ctx.world_transform();
+        let component = ctx.app_state.selected_component_id.get();
+
+        let mut snap_set_guides = SnapSet::new(Guide::color());
+        for guide in workspace.guides(&component) {
+            let position = guide.glass_position(glass_to_world);
+            match guide.axis {
+                GuideAxis::Vertical => snap_set_guides.scene_vert.push(position),
+                GuideAxis::Horizontal => snap_set_guides.scene_horiz.push(position),
+            }
+        }
+
+        let mut snap_set_grid = SnapSet::new(GridSettings::snap_color());
+        if let Some(glass) = ctx
+            .engine_context
+            .get_nodes_by_id(DESIGNER_GLASS_ID)
+            .into_iter()
+            .next()
+        {
+            let glass_bounds = glass.transform_and_bounds().get().bounds;
+            let (vertical, horizontal) = workspace.grid.visible_lines(glass_to_world, glass_bounds);
+            snap_set_grid
+                .scene_vert
+                .extend(vertical.iter().map(|l| l.position));
+            snap_set_grid
+                .scene_horiz
+                .extend(horizontal.iter().map(|l| l.position));
+        }
+        [snap_set_guides, snap_set_grid]
+    }
+
     pub fn new(ctx: &ActionContext, snap_collection: SnapCollection) -> Self {
         let keys = ctx.app_state.modifiers.clone();
         let deps = [keys.untyped()];
@@ -0,0 +1,173 @@
+use std::rc::Rc;
+
+use anyhow::{anyhow, Result};
+use pax_engine::api::Window;
+use pax_engine::math::{Point2, Transform2, Vector2};
+use pax_engine::pax_manifest::UniqueTemplateNodeIdentifier;
+use pax_engine::NodeInterface;
+
+use super::meta::Schedule;
+use super::tool::SetToolBehaviour;
+use super::world::{SelectMode, SelectNodes};
+use super::{Action, ActionContext};
+use crate::controls::toolbar::SelectTool;
+use crate::glass::{is_userland_component, SetEditingComponent};
+use crate::math::coordinate_spaces::{Glass, World};
+use crate::math::AxisAlignedBox;
+use crate::model::{GlassNode, ProjectMode, Tool};
+use crate::{ProjectMsg, DESIGNER_GLASS_ID};
+
+/// Fraction of the glass a framed node is zoomed to fill, in its larger dimension
+const FRAME_FILL: f64 = 0.6;
+
+/// Ticks to wait for the edit mode tree to be rebuilt before giving up on framing a node
+const FRAME_ATTEMPTS: usize = 10;
+
+/// Inspect during play: stops playing, and selects and frames the node under
+/// `point` in the component being edited. Nodes inside other components resolve
+/// to the instance of that component, unless `drill_in` is set, in which case
+/// the innermost userland node is selected, opening its component for editing.
+pub struct InspectDuringPlay {
+    pub point: Point2<Window>,
+    pub drill_in: bool,
+}
+
+impl Action for InspectDuringPlay {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        if !matches!(ctx.app_state.project_mode.get(), ProjectMode::Playing) {
+            return Err(anyhow!("can only inspect nodes while playing"));
+        }
+        let userland = ctx
+            .engine_context
+            .get_userland_root_expanded_node()
+            .ok_or_else(|| anyhow!("no userland tree to inspect"))?;
+        let hit = ctx
+            .engine_context
+            .raycast(self.point, false)
+            .into_iter()
+            .find(|n| n.is_descendant_of(&userland))
+            .ok_or_else(|| anyhow!("no node under the cursor"))?;
+
+        let edited_component = ctx.app_state.selected_component_id.get();
+        let target = if self.drill_in {
+            innermost_userland_node(&hit)
+        } else {
+            hit.template_node_in_component(&edited_component)
+        };
+        let uid = target
+            .and_then(|n| n.global_id())
+            .ok_or_else(|| anyhow!("node under the cursor isn't part of the project"))?;
+
+        // same steps as the play/edit toggle, which also restores the edit
+        // mode tree, so that no state from playing is carried over
+        SetToolBehaviour(None).perform(ctx)?;
+        ProjectMsg(ProjectMode::Edit).perform(ctx)?;
+        let component = uid.get_containing_component_type_id();
+        if component != edited_component {
+            SetEditingComponent(component).perform(ctx)?;
+        }
+        // the settings of the node aren't shown while a tool with its own settings is selected
+        if ctx.app_state.selected_tool.get() == Tool::Paintbrush {
+            SelectTool {
+                tool: Tool::PointerPercent,
+            }
+            .perform(ctx)?;
+        }
+        SelectNodes {
+            ids: &[uid.get_template_node_id()],
+            mode: SelectMode::DiscardOthers,
+        }
+        .perform(ctx)?;
+        Schedule {
+            action: Rc::new(FrameNode {
+                id: uid,
+                attempts_left: FRAME_ATTEMPTS,
+            }),
+        }
+        .perform(ctx)
+    }
+}
+
+/// The innermost node at or above `node` that is declared in a userland
+/// component, skipping the internals of pax_std components
+fn innermost_userland_node(node: &NodeInterface) -> Option<NodeInterface> {
+    let mut node = node.clone();
+    loop {
+        if node
+            .global_id()
+            .is_some_and(|uid| is_userland_component(&uid.get_containing_component_type_id()))
+        {
+            return Some(node);
+        }
+        node = node.template_parent()?;
+    }
+}
+
+/// Pans and zooms the glass to center the node `id` of the edit mode tree,
+/// retrying on the following ticks while the tree is being rebuilt
+pub struct FrameNode {
+    pub id: UniqueTemplateNodeIdentifier,
+    pub attempts_left: usize,
+}
+
+impl Action for FrameNode {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let glass = ctx
+            .engine_context
+            .get_nodes_by_id(DESIGNER_GLASS_ID)
+            .into_iter()
+            .next();
+        // nodes of the play mode tree share global ids with the edit mode ones
+        let node = glass.as_ref().and_then(|glass| {
+            ctx.engine_context
+                .get_nodes_by_global_id(self.id.clone())
+                .into_iter()
+                .filter(|n| n.is_descendant_of(glass))
+                .max()
+        });
+        let (Some(glass), Some(node)) = (glass, node) else {
+            if self.attempts_left == 0 {
+                return Err(anyhow!("node to frame was never shown in the glass"));
+            }
+            return Schedule {
+                action: Rc::new(FrameNode {
+                    id: self.id.clone(),
+                    attempts_left: self.attempts_left - 1,
+                }),
+            }
+            .perform(ctx);
+        };
+
+        let world_transform = ctx.world_transform();
+        let node = GlassNode::new(&node, &ctx.glass_transform());
+        let target = AxisAlignedBox::<World>::bound_of_points(
+            node.transform_and_bounds
+                .get()
+                .corners()
+                .map(|p| world_transform * p),
+        );
+        let (glass_width, glass_height) = glass.transform_and_bounds().get().bounds;
+        let fit = (target.width() / (glass_width * FRAME_FILL))
+            .max(target.height() / (glass_height * FRAME_FILL));
+        if !fit.is_finite() {
+            return Err(anyhow!("can't frame a node in an empty glass"));
+        }
+        // zoom out as far as needed to fit the node, but don't zoom in past 100%
+        // unless already zoomed in further
+        let current = world_transform.get_scale().x;
+        let scale = fit.max(current.min(1.0));
+
+        let target_center = target.top_left().midpoint_towards(target.bottom_right());
+        let glass_center = Point2::<Glass>::new(glass_width / 2.0, glass_height / 2.0);
+        let new_transform = Transform2::<Glass, World>::translate(Vector2::new(
+            target_center.x - scale * glass_center.x,
+            target_center.y - scale * glass_center.y,
+        )) * Transform2::<Glass, Glass>::scale(scale);
+        ctx.app_state.glass_to_world_transform.ease_to(
+            new_transform,
+            20,
+            pax_engine::api::EasingCurve::OutQuad,
+        );
+        Ok(())
+    }
+}
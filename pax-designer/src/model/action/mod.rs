@@ -32,6 +32,7 @@ pax_engine::pax_message::use_RefCell!();
 
 use crate::math::coordinate_spaces::Glass;
 
+pub mod inspect;
 pub mod meta;
 pub mod orm;
 pub mod pointer;
@@ -75,6 +76,11 @@ impl UndoRedoStack {
 
 pub trait Action<R = ()> {
     fn perform(&self, ctx: &mut ActionContext) -> Result<R>;
+
+    /// Name of the action in session recordings, see `model::session`
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
 }
 
 // make this a trait that's implemented for NodeContext instead? (since app state is static)
@@ -117,6 +123,28 @@ impl<'a> ActionContext<'a> {
         point: Point2<Glass>,
         mode: RaycastMode,
         skip: &[NodeInterface],
+    ) -> Option<NodeInterface> {
+        self.raycast_glass_filtered(point, mode, skip, false)
+    }
+
+    /// Same as `raycast_glass`, but ignores nodes that are part of the designer's own
+    /// components (selection handles, tool visuals, etc.), so that clicking on chrome drawn over
+    /// the user's content hits the content beneath it
+    pub fn raycast_glass_ignoring_chrome(
+        &self,
+        point: Point2<Glass>,
+        mode: RaycastMode,
+        skip: &[NodeInterface],
+    ) -> Option<NodeInterface> {
+        self.raycast_glass_filtered(point, mode, skip, true)
+    }
+
+    fn raycast_glass_filtered(
+        &self,
+        point: Point2<Glass>,
+        mode: RaycastMode,
+        skip: &[NodeInterface],
+        ignore_chrome: bool,
     ) -> Option<NodeInterface> {
         let window_point = self.glass_transform().get().inverse() * point;
         let all_elements_beneath_ray = self.engine_context.raycast(window_point, false);
@@ -126,6 +154,7 @@ impl<'a> ActionContext<'a> {
 
         let mut potential_targets = all_elements_beneath_ray
             .into_iter()
+            .filter(|elem| !(ignore_chrome && is_designer_chrome(elem, &userland)))
             .filter(|elem| !skip.iter().any(|v| elem == v || elem.is_descendant_of(v)))
             .filter(|elem| elem.is_descendant_of(&userland));
 
@@ -289,11 +318,38 @@ impl Drop for Transaction {
             let mut dt = borrow_mut!(self.design_time);
             if let Err(e) = dt.send_component_update(&self.component_id.get()) {
                 pax_engine::log::error!("failed to save component to file: {:?}", e);
+                message_log_display::log(DesignerLogMsg::message(e.to_string()));
             }
         }
     }
 }
 
+/// Prefix of the type ids of the designer's own components
+const DESIGNER_TYPE_ID_PREFIX: &str = "pax_designer::";
+
+/// Whether `node` is part of one of the designer's components, as opposed to user content.
+/// Containing components are only checked up to `userland`, which itself lives in the designer.
+fn is_designer_chrome(node: &NodeInterface, userland: &NodeInterface) -> bool {
+    let is_designer_type = |node: &NodeInterface| {
+        node.global_id().is_some_and(|id| {
+            id.get_containing_component_type_id()
+                .import_path()
+                .is_some_and(|path| path.starts_with(DESIGNER_TYPE_ID_PREFIX))
+        })
+    };
+    let mut current = Some(node.clone());
+    while let Some(node) = current {
+        if &node == userland {
+            return false;
+        }
+        if is_designer_type(&node) {
+            return true;
+        }
+        current = node.containing_component();
+    }
+    false
+}
+
 pub enum RaycastMode {
     // Only hit elements that are either directly bellow the userland project
     // root, or ones that are at the same level as an already selected node
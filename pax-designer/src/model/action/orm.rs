@@ -12,6 +12,7 @@ use crate::math::{
     self, AxisAlignedBox, DecompositionConfiguration, GetUnit, IntoDecompositionConfiguration,
     RotationUnit, SizeUnit,
 };
+use crate::message_log_display::{self, DesignerLogMsg};
 use crate::model::action::world::{SelectMode, SelectNodes};
 use crate::model::input::{InputEvent, ModifierKey};
 use crate::model::{GlassNode, GlassNodeSnapshot, SelectionStateSnapshot};
@@ -37,8 +38,11 @@ use pax_engine::{
 };
 use pax_engine::{log, NodeInterface, NodeLocal, Slot};
 use pax_std::layout::stacker::Stacker;
+pub mod gradient;
 pub mod group_ungroup;
 pub mod other;
+pub mod skeleton;
+pub mod snippets;
 pub mod space_movement;
 pub mod space_movement_primitives;
 pub mod tree_movement;
@@ -185,7 +189,8 @@ impl Action for SelectedIntoNewComponent {
         let t = ctx.transaction("moving selected into new component");
         t.run(|| {
             dt.get_orm_mut()
-                .move_to_new_component(&entries, o.x, o.y, u.length(), v.length())
+                .move_to_new_component(&entries, None, o.x, o.y, u.length(), v.length())
+                .map(|_| ())
                 .map_err(|e| anyhow!("couldn't move to component: {}", e))
         })
     }
@@ -198,11 +203,23 @@ impl Action for SerializeRequested {
         let mut dt = borrow_mut!(ctx.engine_context.designtime);
         if let Err(e) = dt.send_component_update(&ctx.app_state.selected_component_id.get()) {
             pax_engine::log::error!("failed to save component to file: {:?}", e);
+            message_log_display::log(DesignerLogMsg::message(e.to_string()));
         }
         Ok(())
     }
 }
 
+pub struct ToggleReviewChanges;
+
+impl Action for ToggleReviewChanges {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        ctx.app_state
+            .review_changes_open
+            .set(!ctx.app_state.review_changes_open.get());
+        Ok(())
+    }
+}
+
 pub struct UndoRequested;
 
 impl Action for UndoRequested {
@@ -255,6 +272,77 @@ impl Action for DeleteSelected {
     }
 }
 
+/// Overrides a node the selected component inherited with `@extends`, unlocking it for editing
+pub struct OverrideInheritedNode {
+    pub id: TemplateNodeId,
+}
+
+impl Action for OverrideInheritedNode {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let t = ctx.transaction("override inherited node");
+        t.run(|| {
+            let mut dt = borrow_mut!(ctx.engine_context.designtime);
+            let uid = UniqueTemplateNodeIdentifier::build(
+                ctx.app_state.selected_component_id.get(),
+                self.id.clone(),
+            );
+            dt.get_orm_mut()
+                .override_inherited_node(uid)
+                .map_err(|e| anyhow!("couldn't override node: {}", e))?;
+            Ok(())
+        })
+    }
+}
+
+/// Makes a node of the selected component instantiate another component, e.g. to fix a node whose
+/// component no longer exists.  Settings the new component has a property for are kept
+pub struct ReplaceNodeType {
+    pub id: TemplateNodeId,
+    pub type_id: TypeId,
+}
+
+impl Action for ReplaceNodeType {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let t = ctx.transaction("replace node type");
+        t.run(|| {
+            let mut dt = borrow_mut!(ctx.engine_context.designtime);
+            let uid = UniqueTemplateNodeIdentifier::build(
+                ctx.app_state.selected_component_id.get(),
+                self.id.clone(),
+            );
+            dt.get_orm_mut()
+                .replace_node_type(uid, self.type_id.clone())
+                .map_err(|e| anyhow!("couldn't replace node: {}", e))?;
+            Ok(())
+        })
+    }
+}
+
+/// Removes a node of the selected component, whether or not it's selected
+pub struct RemoveNode {
+    pub id: TemplateNodeId,
+}
+
+impl Action for RemoveNode {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let t = ctx.transaction("remove node");
+        t.run(|| {
+            let mut dt = borrow_mut!(ctx.engine_context.designtime);
+            let uid = UniqueTemplateNodeIdentifier::build(
+                ctx.app_state.selected_component_id.get(),
+                self.id.clone(),
+            );
+            dt.get_orm_mut()
+                .remove_node(uid)
+                .map_err(|_| anyhow!("couldn't remove node"))?;
+            ctx.app_state
+                .selected_template_node_ids
+                .update(|ids| ids.retain(|id| id != &self.id));
+            Ok(())
+        })
+    }
+}
+
 pub struct Copy<'a> {
     pub ids: &'a [TemplateNodeId],
 }
@@ -0,0 +1,135 @@
+use anyhow::{anyhow, Result};
+use pax_engine::api::{borrow_mut, Color, Fill, GradientStop, Interpolatable};
+use pax_engine::math::Point2;
+use pax_engine::pax_manifest::UniqueTemplateNodeIdentifier;
+use pax_engine::NodeInterface;
+use pax_std::drawing::ellipse::Ellipse;
+use pax_std::drawing::rectangle::Rectangle;
+
+use crate::controls::settings::property_editor::fill_property_editor::fill_to_str;
+use crate::math::coordinate_spaces::Glass;
+use crate::model::action::{Action, ActionContext};
+use crate::model::input::ModifierKey;
+
+/// A gradient stop whose color is being edited in the color popover on the glass
+#[derive(Clone)]
+pub struct GradientStopColorEdit {
+    pub node: UniqueTemplateNodeIdentifier,
+    pub stop: usize,
+    /// Position of the stop marker, where the popover is shown
+    pub position: Point2<Glass>,
+}
+
+impl Interpolatable for GradientStopColorEdit {}
+
+/// Toggles editing the gradient fill of the selected node on the canvas
+pub struct ToggleGradientEditing;
+
+impl Action for ToggleGradientEditing {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        if ctx.app_state.gradient_editing.get().is_some() {
+            return StopGradientEditing.perform(ctx);
+        }
+        let selection = ctx.derived_state.selection_state.get();
+        let [item] = selection.items.as_slice() else {
+            return Err(anyhow!("select a single node to edit its gradient"));
+        };
+        let is_gradient =
+            node_fill(&item.raw_node_interface).is_some_and(|fill| !matches!(fill, Fill::Solid(_)));
+        if !is_gradient {
+            return Err(anyhow!("the selected node doesn't have a gradient fill"));
+        }
+        ctx.app_state.gradient_editing.set(Some(item.id.clone()));
+        Ok(())
+    }
+}
+
+pub struct StopGradientEditing;
+
+impl Action for StopGradientEditing {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        if ctx.app_state.gradient_editing.get().is_some() {
+            ctx.app_state.gradient_editing.set(None);
+        }
+        if ctx.app_state.gradient_stop_color_edit.get().is_some() {
+            ctx.app_state.gradient_stop_color_edit.set(None);
+        }
+        Ok(())
+    }
+}
+
+/// Writes `fill` to the `fill` property of `node`
+pub struct SetFill<'a> {
+    pub node: &'a UniqueTemplateNodeIdentifier,
+    pub fill: &'a Fill,
+}
+
+impl Action for SetFill<'_> {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let mut dt = borrow_mut!(ctx.engine_context.designtime);
+        let mut builder = dt
+            .get_orm_mut()
+            .get_node(
+                self.node.clone(),
+                ctx.app_state
+                    .modifiers
+                    .get()
+                    .contains(&ModifierKey::Control),
+            )
+            .ok_or_else(|| anyhow!("couldn't find node to set fill of"))?;
+        builder.set_property("fill", &fill_to_str(self.fill))?;
+        builder
+            .save()
+            .map_err(|e| anyhow!("could not save: {}", e))
+            .map(|_| ())
+    }
+}
+
+/// Sets the color of the gradient stop being edited in the color popover
+pub struct SetGradientStopColor {
+    pub color: Color,
+}
+
+impl Action for SetGradientStopColor {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let Some(edit) = ctx.app_state.gradient_stop_color_edit.get() else {
+            return Ok(());
+        };
+        let node = ctx
+            .engine_context
+            .get_nodes_by_global_id(edit.node.clone())
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("node with gradient no longer exists"))?;
+        let mut fill = node_fill(&node).ok_or_else(|| anyhow!("node has no fill"))?;
+        let stop = gradient_stops_mut(&mut fill)
+            .and_then(|stops| stops.get_mut(edit.stop))
+            .ok_or_else(|| anyhow!("gradient stop no longer exists"))?;
+        if stop.color == self.color {
+            return Ok(());
+        }
+        stop.color = self.color.clone();
+        let t = ctx.transaction("changing gradient stop color");
+        t.run(|| {
+            SetFill {
+                node: &edit.node,
+                fill: &fill,
+            }
+            .perform(ctx)
+        })
+    }
+}
+
+/// The fill of a node, if it's of a type with a `Fill` fill property
+pub fn node_fill(node: &NodeInterface) -> Option<Fill> {
+    node.with_properties(|rectangle: &mut Rectangle| rectangle.fill.get())
+        .or_else(|| node.with_properties(|ellipse: &mut Ellipse| ellipse.fill.get()))
+}
+
+pub fn gradient_stops_mut(fill: &mut Fill) -> Option<&mut Vec<GradientStop>> {
+    match fill {
+        Fill::Solid(_) => None,
+        Fill::LinearGradient(linear) => Some(&mut linear.stops),
+        Fill::RadialGradient(radial) => Some(&mut radial.stops),
+    }
+}
@@ -0,0 +1,96 @@
+use anyhow::{anyhow, Result};
+use pax_designtime::orm::SubTrees;
+use pax_engine::api::{borrow, borrow_mut};
+use pax_engine::pax_manifest::{NodeLocation, TreeIndexPosition};
+use pax_std::core::skeleton::SkeletonScreen;
+
+use crate::designer_node_type::DesignerNodeType;
+use crate::model::action::world::{SelectMode, SelectNodes};
+use crate::model::action::{Action, ActionContext};
+use crate::model::SelectionStateSnapshot;
+
+use super::{CreateComponent, NodeLayoutSettings};
+
+/// Adds a group with the skeleton screen of the selected component instance on top of it, to
+/// preview the component's loading state
+pub struct PreviewSkeletonSelected;
+
+impl Action for PreviewSkeletonSelected {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let selected: SelectionStateSnapshot = (&ctx.derived_state.selection_state.get()).into();
+        let [item] = selected.items.as_slice() else {
+            return Err(anyhow!("select a single component to preview its skeleton"));
+        };
+        let (type_id, index) = {
+            let mut dt = borrow_mut!(ctx.engine_context.designtime);
+            let orm = dt.get_orm_mut();
+            let type_id = orm
+                .get_node(item.id.clone(), false)
+                .ok_or_else(|| anyhow!("selected node doesn't exist"))?
+                .get_type_id();
+            let index = orm
+                .get_node_location(&item.id)
+                .map(|l| l.index)
+                .unwrap_or(TreeIndexPosition::Top);
+            (type_id, index)
+        };
+        if !matches!(
+            DesignerNodeType::from_type_id(type_id.clone()),
+            DesignerNodeType::Component { .. }
+        ) {
+            return Err(anyhow!("only components have a skeleton to preview"));
+        }
+        let screen = SkeletonScreen::from_component(
+            &type_id,
+            borrow!(ctx.engine_context.designtime).get_manifest(),
+        );
+
+        let parent = ctx.derived_state.open_containers.get()[0].clone();
+        let parent_data = ctx.get_glass_node_by_global_id(&parent)?;
+        let node_transform_and_bounds = item.transform_and_bounds.as_pure_size();
+        let parent_transform_and_bounds = parent_data.transform_and_bounds.get().as_pure_size();
+        let decomp_config = Default::default();
+
+        let t = ctx.transaction(&format!(
+            "previewing skeleton of {}",
+            type_id
+                .get_pascal_identifier()
+                .unwrap_or_else(|| "<no ident>".to_string())
+        ));
+        t.run(|| {
+            let group_uid = CreateComponent {
+                parent_id: &parent,
+                parent_index: index.clone(),
+                designer_node_type: DesignerNodeType::Group,
+                builder_extra_commands: None,
+                node_layout: Some(NodeLayoutSettings::KeepScreenBounds {
+                    node_transform_and_bounds: &node_transform_and_bounds,
+                    parent_transform_and_bounds: &parent_transform_and_bounds,
+                    node_decomposition_config: &decomp_config,
+                }),
+            }
+            .perform(ctx)?;
+
+            {
+                let mut dt = borrow_mut!(ctx.engine_context.designtime);
+                let mut location = NodeLocation::parent(
+                    group_uid.get_containing_component_type_id(),
+                    group_uid.get_template_node_id(),
+                );
+                location.set_index(TreeIndexPosition::Bottom);
+                dt.get_orm_mut()
+                    .paste_subtrees(
+                        location,
+                        SubTrees::from_template(&screen.template, &screen.roots()),
+                    )
+                    .map_err(|e| anyhow!("couldn't add skeleton: {e}"))?;
+            }
+
+            SelectNodes {
+                ids: &[group_uid.get_template_node_id()],
+                mode: SelectMode::DiscardOthers,
+            }
+            .perform(ctx)
+        })
+    }
+}
@@ -0,0 +1,264 @@
+use anyhow::{anyhow, Result};
+use pax_designtime::snippet::SnippetArchive;
+use pax_designtime::DesigntimeManager;
+use pax_engine::api::{borrow, borrow_mut, Interpolatable, NodeContext};
+use pax_engine::math::TransformParts;
+use pax_engine::node_layout::TransformAndBounds;
+use pax_engine::pax_manifest::{NodeLocation, TreeIndexPosition, TreeLocation};
+use pax_engine::log;
+
+use crate::message_log_display::{self, DesignerLogMsg};
+use crate::model::action::world::{SelectMode, SelectNodes};
+use crate::model::action::{Action, ActionContext};
+use crate::model::GlassNode;
+
+/// Size of the instance exported when exporting a whole component
+const COMPONENT_SNIPPET_SIZE: (f64, f64) = (200.0, 200.0);
+
+/// A snippet whose components collide with components of the project,
+/// waiting for the user to confirm importing it with renamed components
+#[derive(Clone)]
+pub struct PendingSnippetImport {
+    pub archive: SnippetArchive,
+    pub collisions: Vec<String>,
+}
+
+impl Interpolatable for PendingSnippetImport {}
+
+/// Exports the selected nodes, or the component being edited if nothing is
+/// selected, to the user's snippet library
+pub struct ExportSnippet;
+
+impl Action for ExportSnippet {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let type_id = ctx.app_state.selected_component_id.get();
+        let component_name = type_id
+            .get_pascal_identifier()
+            .unwrap_or_else(|| "Snippet".to_string());
+        let selection = ctx.derived_state.selection_state.get();
+        let dt = borrow!(ctx.engine_context.designtime);
+
+        let archive = if selection.items.is_empty() {
+            SnippetArchive::for_component(dt.get_manifest(), &type_id, COMPONENT_SNIPPET_SIZE)
+        } else {
+            let world_transform = ctx.world_transform();
+            let total = TransformAndBounds {
+                transform: world_transform,
+                bounds: (1.0, 1.0),
+            } * selection.total_bounds.get();
+            let (origin, u, v) = total.transform.decompose();
+            let size = (u.length() * total.bounds.0, v.length() * total.bounds.1);
+            let root_bounds: Vec<_> = selection
+                .items
+                .iter()
+                .map(|item| {
+                    let b = TransformAndBounds {
+                        transform: world_transform,
+                        bounds: (1.0, 1.0),
+                    } * item.transform_and_bounds.get();
+                    let parts: TransformParts = b.transform.into();
+                    (
+                        parts.origin.x - origin.x,
+                        parts.origin.y - origin.y,
+                        parts.scale.x * b.bounds.0,
+                        parts.scale.y * b.bounds.1,
+                    )
+                })
+                .collect();
+            let ids: Vec<_> = selection
+                .items
+                .iter()
+                .map(|item| item.id.get_template_node_id())
+                .collect();
+            let fragment = dt
+                .get_orm()
+                .copy_subtrees(&type_id, &ids)
+                .ok_or_else(|| anyhow!("couldn't copy selection"))?;
+            let name = match selection.items.as_slice() {
+                [item] => node_type_name(&dt, item),
+                _ => None,
+            }
+            .unwrap_or_else(|| format!("{}Selection", component_name));
+            SnippetArchive::new(dt.get_manifest(), &name, fragment, &root_bounds, size)
+        };
+        dt.export_snippet(&archive)
+    }
+}
+
+/// Name of the type of a selected node, used to name a snippet of only that node
+fn node_type_name(dt: &DesigntimeManager, item: &GlassNode) -> Option<String> {
+    let component = dt
+        .get_manifest()
+        .components
+        .get(&item.id.get_containing_component_type_id())?;
+    let node = component
+        .template
+        .as_ref()?
+        .get_node(&item.id.get_template_node_id())?;
+    node.type_id.get_pascal_identifier()
+}
+
+/// Opens the snippet library, requesting the list of snippets from the design server
+pub struct OpenSnippetLibrary;
+
+impl Action for OpenSnippetLibrary {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        borrow!(ctx.engine_context.designtime).request_snippet_library()?;
+        ctx.app_state.snippet_library.set(Some(vec![]));
+        Ok(())
+    }
+}
+
+pub struct CloseSnippetLibrary;
+
+impl Action for CloseSnippetLibrary {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        ctx.app_state.snippet_library.set(None);
+        Ok(())
+    }
+}
+
+/// Requests a snippet from the user's library, which is imported once
+/// received from the design server (see [`process_snippet_inbox`])
+pub struct ImportSnippetFromLibrary {
+    pub name: String,
+}
+
+impl Action for ImportSnippetFromLibrary {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        borrow!(ctx.engine_context.designtime).load_snippet(&self.name)?;
+        ctx.app_state.snippet_library.set(None);
+        Ok(())
+    }
+}
+
+/// Imports an encoded snippet into the component being edited, first asking
+/// the user to confirm renaming its components if any of their names are taken
+pub struct ImportSnippet<'a> {
+    pub archive: &'a [u8],
+}
+
+impl Action for ImportSnippet<'_> {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let archive = SnippetArchive::from_bytes(self.archive)
+            .map_err(|e| anyhow!("invalid snippet: {e}"))?;
+        let collisions = archive.collisions(borrow!(ctx.engine_context.designtime).get_manifest());
+        if collisions.is_empty() {
+            PerformSnippetImport { archive }.perform(ctx)
+        } else {
+            ctx.app_state
+                .pending_snippet_import
+                .set(Some(PendingSnippetImport {
+                    archive,
+                    collisions,
+                }));
+            Ok(())
+        }
+    }
+}
+
+/// Imports the pending snippet, renaming its colliding components
+pub struct ConfirmSnippetImport;
+
+impl Action for ConfirmSnippetImport {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let Some(pending) = ctx.app_state.pending_snippet_import.get() else {
+            return Ok(());
+        };
+        ctx.app_state.pending_snippet_import.set(None);
+        PerformSnippetImport {
+            archive: pending.archive,
+        }
+        .perform(ctx)
+    }
+}
+
+pub struct CancelSnippetImport;
+
+impl Action for CancelSnippetImport {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        ctx.app_state.pending_snippet_import.set(None);
+        Ok(())
+    }
+}
+
+/// Adds the snippet's components and pastes its nodes at the center of the
+/// stage, as a single undoable step
+struct PerformSnippetImport {
+    archive: SnippetArchive,
+}
+
+impl Action for PerformSnippetImport {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let type_id = ctx.app_state.selected_component_id.get();
+        let stage = ctx.app_state.stage.get();
+        let name = self.archive.name.clone();
+        let mut import = self
+            .archive
+            .clone()
+            .into_import(
+                borrow!(ctx.engine_context.designtime).get_manifest(),
+                &type_id,
+            )
+            .map_err(|e| anyhow!("couldn't import snippet {name}: {e}"))?;
+        import.place_at((stage.width as f64 / 2.0, stage.height as f64 / 2.0));
+
+        {
+            let dt = borrow!(ctx.engine_context.designtime);
+            for asset in &import.assets {
+                let file_name = asset.path.trim_start_matches("assets/");
+                dt.send_file_to_static_dir(file_name, asset.data.clone())?;
+            }
+        }
+
+        let t = ctx.transaction("importing snippet");
+        t.run(|| {
+            let ids = {
+                let mut dt = borrow_mut!(ctx.engine_context.designtime);
+                let orm = dt.get_orm_mut();
+                if !import.components.is_empty() {
+                    orm.add_components(import.components.clone())
+                        .map_err(|e| anyhow!("couldn't add components: {e}"))?;
+                }
+                let location =
+                    NodeLocation::new(type_id.clone(), TreeLocation::Root, TreeIndexPosition::Top);
+                orm.paste_subtrees(location, import.fragment.clone())
+                    .map_err(|e| anyhow!("couldn't paste snippet: {e}"))?
+            };
+            SelectNodes {
+                ids: &ids,
+                mode: SelectMode::DiscardOthers,
+            }
+            .perform(ctx)
+        })?;
+
+        message_log_display::log(DesignerLogMsg::message(format!("Imported snippet {name}")));
+        for line in import.report {
+            message_log_display::log(DesignerLogMsg::message(line));
+        }
+        Ok(())
+    }
+}
+
+/// Handles the snippet messages received from the design server since the last tick
+pub fn process_snippet_inbox(ctx: &NodeContext) {
+    let inbox = borrow_mut!(ctx.designtime).take_snippet_inbox();
+    for message in inbox.messages {
+        message_log_display::log(DesignerLogMsg::message(message));
+    }
+    if let Some(names) = inbox.library {
+        crate::model::read_app_state(|app_state| {
+            if app_state.snippet_library.get().is_some() {
+                app_state.snippet_library.set(Some(names));
+            }
+        });
+    }
+    for archive in inbox.imports {
+        crate::model::with_action_context(ctx, |ac| {
+            if let Err(e) = (ImportSnippet { archive: &archive }).perform(ac) {
+                log::warn!("failed to import snippet: {e}");
+                message_log_display::log(DesignerLogMsg::message(e.to_string()));
+            }
+        });
+    }
+}
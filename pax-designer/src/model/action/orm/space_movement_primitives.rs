@@ -104,7 +104,7 @@ impl Action for SetNodeLayoutProperties<'_> {
 
         if !matches!(
             builder.get_type_id().get_pax_type(),
-            PaxType::Singleton { .. } | PaxType::BlankComponent { .. }
+            PaxType::Singleton { .. } | PaxType::BlankComponent { .. } | PaxType::Unresolved { .. }
         ) {
             return Ok(());
         };
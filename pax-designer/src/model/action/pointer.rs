@@ -43,6 +43,12 @@ impl Action for MouseEntryPointAction<'_> {
     fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
         let point_glass = ctx.glass_transform().get() * self.point;
         ctx.app_state.mouse_position.set(point_glass);
+
+        // selection, tools and the context menu edit the manifest, so wait until it's loaded
+        if !ctx.derived_state.manifest_loaded.get() {
+            return Ok(());
+        }
+
         let spacebar = ctx.app_state.modifiers.get().contains(&ModifierKey::Space);
         let zoom = ctx.app_state.modifiers.get().contains(&ModifierKey::Z);
         let tool_behavior = ctx.app_state.tool_behavior.clone();
@@ -81,8 +87,11 @@ impl Action for MouseEntryPointAction<'_> {
                     match ctx.app_state.selected_tool.get() {
                         Tool::PointerPercent | Tool::PointerPixels => {
                             (self.prevent_default)();
-                            if let Some(hit) = ctx.raycast_glass(point_glass, RaycastMode::Top, &[])
-                            {
+                            if let Some(hit) = ctx.raycast_glass_ignoring_chrome(
+                                point_glass,
+                                RaycastMode::Top,
+                                &[],
+                            ) {
                                 tool_behavior.set(Some(Rc::new(RefCell::new(MovingTool::new(
                                     ctx,
                                     point_glass,
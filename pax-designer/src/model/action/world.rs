@@ -1,10 +1,12 @@
 use std::any::Any;
 use std::ops::ControlFlow;
 
+use super::orm::gradient::StopGradientEditing;
 use super::{pointer::Pointer, Action, ActionContext};
 use crate::math::coordinate_spaces::{Glass, World};
 use crate::math::AxisAlignedBox;
 use crate::model::input::ModifierKey;
+use crate::model::property_recording::sync_recorded_selection;
 use crate::model::{input::InputEvent, AppState, ToolBehavior};
 use crate::DESIGNER_GLASS_ID;
 use anyhow::{anyhow, Result};
@@ -190,6 +192,8 @@ impl Action for SelectNodes<'_> {
         // Only set if changed, otherwise re-triggers when same object gets re-selected
         if ids != ctx.app_state.selected_template_node_ids.get() {
             ctx.app_state.selected_template_node_ids.set(ids);
+            StopGradientEditing.perform(ctx)?;
+            sync_recorded_selection(ctx);
         }
         Ok(())
     }
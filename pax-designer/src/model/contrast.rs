@@ -0,0 +1,393 @@
+//! Color contrast checks of text against what's rendered behind it, following
+//! the WCAG 2 definitions of relative luminance and contrast ratio. Used by the
+//! text style editor to show whether the selected text passes, and by the
+//! contrast audit panel listing all rendered text failing AA.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use pax_engine::api::{Color, ColorChannel, Fill, Interpolatable, NodeContext, Numeric, Rotation};
+use pax_engine::pax_manifest::UniqueTemplateNodeIdentifier;
+use pax_engine::NodeInterface;
+use pax_std::core::group::Group;
+use pax_std::core::image::Image;
+use pax_std::core::text::{Text, TextStyle};
+use pax_std::drawing::path::Path;
+use pax_std::forms::button::Button;
+use pax_std::forms::dropdown::Dropdown;
+use pax_std::forms::radio_set::RadioSet;
+use pax_std::forms::textbox::Textbox;
+
+use super::action::orm::gradient::node_fill;
+use super::action::world::{SelectMode, SelectNodes};
+use super::action::{Action, ActionContext};
+use crate::glass::SetEditingComponent;
+
+/// Minimum contrast ratio of normal sized text for WCAG level AA
+pub const AA_RATIO: f64 = 4.5;
+
+/// Minimum contrast ratio of normal sized text for WCAG level AAA
+pub const AAA_RATIO: f64 = 7.0;
+
+/// Steps of lightness searched for a passing color, in each direction
+const LIGHTNESS_STEPS: usize = 1000;
+
+/// Background of the canvas where nothing is drawn behind a node
+const CANVAS_BACKGROUND: [f64; 4] = [1.0, 1.0, 1.0, 1.0];
+
+pub struct ToggleContrastAudit;
+
+impl Action for ToggleContrastAudit {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        ctx.app_state
+            .contrast_audit_open
+            .set(!ctx.app_state.contrast_audit_open.get());
+        Ok(())
+    }
+}
+
+/// Selects a node found by the audit, first opening its component for
+/// editing if it's another one than the component being edited
+pub struct SelectContrastIssue(pub UniqueTemplateNodeIdentifier);
+
+impl Action for SelectContrastIssue {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let component = self.0.get_containing_component_type_id();
+        if component != ctx.app_state.selected_component_id.get() {
+            SetEditingComponent(component).perform(ctx)?;
+        }
+        SelectNodes {
+            ids: &[self.0.get_template_node_id()],
+            mode: SelectMode::DiscardOthers,
+        }
+        .perform(ctx)
+    }
+}
+
+/// Colors of a text node and of what's behind it
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContrastCheck {
+    pub foreground: Color,
+    /// Composited color behind the text, `None` if a gradient or image is
+    /// behind it, in which case the contrast is indeterminate
+    pub background: Option<Color>,
+}
+
+impl Interpolatable for ContrastCheck {}
+
+impl ContrastCheck {
+    /// Contrast ratio from 1.0 to 21.0, `None` if indeterminate
+    pub fn ratio(&self) -> Option<f64> {
+        let background = self.background.as_ref()?;
+        let foreground = composite(&self.foreground, background);
+        Some(contrast_ratio(&foreground, background))
+    }
+
+    /// The closest color in lightness to the foreground, with the same hue,
+    /// passing `min_ratio` against the background
+    pub fn nearest_passing_color(&self, min_ratio: f64) -> Option<Color> {
+        nearest_passing_color(&self.foreground, self.background.as_ref()?, min_ratio)
+    }
+
+    /// Summary shown next to the color editors, e.g. "2.9:1 — fails AA"
+    pub fn describe(&self) -> String {
+        let Some(ratio) = self.ratio() else {
+            return "Contrast indeterminate".to_string();
+        };
+        let verdict = if ratio >= AAA_RATIO {
+            "passes AAA"
+        } else if ratio >= AA_RATIO {
+            "passes AA"
+        } else {
+            "fails AA"
+        };
+        format!("{} — {}", format_ratio(ratio), verdict)
+    }
+}
+
+/// A ratio as "4.4:1", rounded down so that failing ratios never show as passing
+pub fn format_ratio(ratio: f64) -> String {
+    format!("{:.1}:1", (ratio * 10.0).floor() / 10.0)
+}
+
+/// Relative luminance of the color, ignoring its alpha
+pub fn relative_luminance(color: &Color) -> f64 {
+    let [r, g, b, _] = color.to_rgba_0_1();
+    let linear = |c: f64| {
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+pub fn contrast_ratio(a: &Color, b: &Color) -> f64 {
+    let (la, lb) = (relative_luminance(a), relative_luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+/// `top` drawn over the opaque color `bottom`
+pub fn composite(top: &Color, bottom: &Color) -> Color {
+    let top = top.to_rgba_0_1();
+    let bottom = bottom.to_rgba_0_1();
+    Color::from_rgba_0_1(blend(top, bottom))
+}
+
+fn blend(top: [f64; 4], bottom: [f64; 4]) -> [f64; 4] {
+    let a = top[3];
+    [
+        top[0] * a + bottom[0] * (1.0 - a),
+        top[1] * a + bottom[1] * (1.0 - a),
+        top[2] * a + bottom[2] * (1.0 - a),
+        1.0,
+    ]
+}
+
+/// Searches the lightness of `foreground` outwards from its own, keeping hue,
+/// saturation and alpha, for the first color passing `min_ratio` against
+/// `background`.  Channels are rounded to integers, as they're written to the
+/// template, and the rounded color is what's checked.
+pub fn nearest_passing_color(
+    foreground: &Color,
+    background: &Color,
+    min_ratio: f64,
+) -> Option<Color> {
+    let [h, s, l, a] = foreground.to_hsla_0_1();
+    let candidate = |lightness: f64| {
+        let hsla = Color::hsla(
+            Rotation::Degrees(Numeric::F64(h * 360.0)),
+            ColorChannel::Percent(Numeric::F64(s * 100.0)),
+            ColorChannel::Percent(Numeric::F64(lightness * 100.0)),
+            ColorChannel::Percent(Numeric::F64(a * 100.0)),
+        );
+        let [r, g, b, a] = hsla.to_rgba_0_1();
+        let channel = |c: f64| ColorChannel::Integer(Numeric::I64((c * 255.0).round() as i64));
+        Color::rgba(channel(r), channel(g), channel(b), channel(a))
+    };
+    let passes = |color: &Color| {
+        let check = ContrastCheck {
+            foreground: color.clone(),
+            background: Some(background.clone()),
+        };
+        check.ratio().is_some_and(|ratio| ratio >= min_ratio)
+    };
+    (0..=LIGHTNESS_STEPS).find_map(|step| {
+        let delta = step as f64 / LIGHTNESS_STEPS as f64;
+        [l - delta, l + delta]
+            .into_iter()
+            .filter(|lightness| (0.0..=1.0).contains(lightness))
+            .map(candidate)
+            .find(passes)
+    })
+}
+
+/// Fill of the text of a node, taking inherited text styles into account, and
+/// the background the node draws behind its own text if it's a form control with one
+pub fn text_colors(node: &NodeInterface) -> Option<(Color, Option<Color>)> {
+    let fill = |style: TextStyle, defaults: TextStyle| {
+        style
+            .layered_over(node.resolved_text_style().get(), &defaults)
+            .fill
+            .get()
+    };
+    node.with_properties(|text: &mut Text| (fill(text.style.get(), TextStyle::default()), None))
+        .or_else(|| {
+            node.with_properties(|button: &mut Button| {
+                (
+                    fill(button.style.get(), Button::default().style.get()),
+                    Some(button.color.get()),
+                )
+            })
+        })
+        .or_else(|| {
+            node.with_properties(|textbox: &mut Textbox| {
+                (
+                    fill(textbox.style.get(), Textbox::default().style.get()),
+                    Some(textbox.background.get()),
+                )
+            })
+        })
+        .or_else(|| {
+            node.with_properties(|dropdown: &mut Dropdown| {
+                (
+                    fill(dropdown.style.get(), Dropdown::default().style.get()),
+                    Some(dropdown.background.get()),
+                )
+            })
+        })
+        .or_else(|| {
+            node.with_properties(|radio_set: &mut RadioSet| {
+                (
+                    fill(radio_set.style.get(), RadioSet::default().style.get()),
+                    None,
+                )
+            })
+        })
+}
+
+/// Checks the contrast of the text of `node`, `None` if it has no text style
+pub fn check_node(ctx: &NodeContext, node: &NodeInterface) -> Option<ContrastCheck> {
+    let (foreground, own_background) = text_colors(node)?;
+    let behind = || background_behind(ctx, node);
+    let background = match own_background {
+        Some(own) if own.to_rgba_0_1()[3] >= 1.0 => Some(own),
+        Some(own) => behind().map(|behind| composite(&own, &behind)),
+        None => behind(),
+    };
+    Some(ContrastCheck {
+        foreground,
+        background,
+    })
+}
+
+/// Samples the color rendered behind the center of `node`, compositing the
+/// solid fills beneath it down to the first opaque one.  `None` if a gradient,
+/// image or path is in the way, since their color at that point isn't known.
+pub fn background_behind(ctx: &NodeContext, node: &NodeInterface) -> Option<Color> {
+    let center = node.transform_and_bounds().get().center();
+    // topmost first, and nodes after `node` that aren't its descendants are drawn beneath it
+    let hits = ctx.raycast(center, true);
+    let position = hits.iter().position(|hit| hit == node)?;
+    let mut layers = vec![];
+    for hit in &hits[position + 1..] {
+        if hit.is_descendant_of(node) {
+            continue;
+        }
+        let fill = match node_fill(hit) {
+            Some(fill) => Some(fill),
+            None => hit
+                .with_properties(|group: &mut Group| group.fill.get())
+                .flatten(),
+        };
+        let color = match fill {
+            Some(Fill::Solid(color)) => color.to_rgba_0_1(),
+            Some(Fill::LinearGradient(_) | Fill::RadialGradient(_)) => return None,
+            None if hit.is_of_type::<Image>() || hit.is_of_type::<Path>() => return None,
+            None => continue,
+        };
+        layers.push(color);
+        if color[3] >= 1.0 {
+            break;
+        }
+    }
+    let rgba = layers
+        .into_iter()
+        .rev()
+        .fold(CANVAS_BACKGROUND, |bottom, top| blend(top, bottom));
+    Some(Color::from_rgba_0_1(rgba))
+}
+
+/// A text node failing AA, found by `audit`
+#[derive(Clone, Debug)]
+pub struct ContrastIssue {
+    pub node: UniqueTemplateNodeIdentifier,
+    /// Lowest contrast ratio among the node's rendered instances
+    pub ratio: f64,
+}
+
+/// Finds the userland text nodes rendered below `root` failing AA, lowest
+/// ratio first.  Instances of the same template node (e.g. in a `for`) are
+/// reported once, with their lowest ratio.  Text with an indeterminate
+/// contrast is left out.
+pub fn audit(ctx: &NodeContext, root: &NodeInterface) -> Vec<ContrastIssue> {
+    let mut worst: HashMap<UniqueTemplateNodeIdentifier, f64> = HashMap::new();
+    let mut to_visit = vec![root.clone()];
+    while let Some(node) = to_visit.pop() {
+        to_visit.extend(node.children());
+        let Some(uni) = node.global_id() else {
+            continue;
+        };
+        if !is_userland(&uni) {
+            continue;
+        }
+        let Some(ratio) = check_node(ctx, &node).and_then(|check| check.ratio()) else {
+            continue;
+        };
+        if ratio < AA_RATIO {
+            let entry = worst.entry(uni).or_insert(ratio);
+            *entry = entry.min(ratio);
+        }
+    }
+    let mut issues: Vec<_> = worst
+        .into_iter()
+        .map(|(node, ratio)| ContrastIssue { node, ratio })
+        .collect();
+    issues.sort_by(|a, b| a.ratio.total_cmp(&b.ratio));
+    issues
+}
+
+/// Whether the node is part of the project, not of a pax-std or designer component
+fn is_userland(uni: &UniqueTemplateNodeIdentifier) -> bool {
+    !uni.get_containing_component_type_id()
+        .import_path()
+        .is_some_and(|p| p.starts_with("pax_std") || p.starts_with("pax_designer"))
+}
+
+#[cfg(test)]
+mod tests {
+    use pax_engine::api::{Color, ColorChannel, Numeric};
+
+    use super::*;
+
+    fn rgb(r: i64, g: i64, b: i64) -> Color {
+        let channel = |c| ColorChannel::Integer(Numeric::I64(c));
+        Color::rgb(channel(r), channel(g), channel(b))
+    }
+
+    #[test]
+    fn contrast_ratios() {
+        assert_eq!(
+            format_ratio(contrast_ratio(&Color::BLACK, &Color::WHITE)),
+            "21.0:1"
+        );
+        assert_eq!(contrast_ratio(&Color::WHITE, &Color::WHITE), 1.0);
+
+        let grey_on_grey = ContrastCheck {
+            foreground: rgb(0x80, 0x80, 0x80),
+            background: Some(rgb(0xdd, 0xdd, 0xdd)),
+        };
+        assert_eq!(grey_on_grey.describe(), "2.9:1 — fails AA");
+
+        let indeterminate = ContrastCheck {
+            foreground: Color::BLACK,
+            background: None,
+        };
+        assert_eq!(indeterminate.ratio(), None);
+        assert_eq!(indeterminate.describe(), "Contrast indeterminate");
+    }
+
+    #[test]
+    fn translucent_text_is_composited() {
+        let half_black = Color::rgba(
+            ColorChannel::Integer(Numeric::I64(0)),
+            ColorChannel::Integer(Numeric::I64(0)),
+            ColorChannel::Integer(Numeric::I64(0)),
+            ColorChannel::Percent(Numeric::F64(50.0)),
+        );
+        let check = ContrastCheck {
+            foreground: half_black,
+            background: Some(Color::WHITE),
+        };
+        let ratio = check.ratio().unwrap();
+        let mid_grey = Color::from_rgba_0_1([0.5, 0.5, 0.5, 1.0]);
+        assert!((ratio - contrast_ratio(&mid_grey, &Color::WHITE)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn nearest_passing_color_keeps_hue() {
+        let foreground = rgb(0x55, 0x88, 0xcc);
+        let background = rgb(0x33, 0x44, 0x55);
+        let fixed = nearest_passing_color(&foreground, &background, AA_RATIO).unwrap();
+        assert!(contrast_ratio(&fixed, &background) >= AA_RATIO);
+        let hue = |c: &Color| c.to_hsla_0_1()[0];
+        assert!((hue(&fixed) - hue(&foreground)).abs() < 0.01);
+        // the dark background makes a lighter color the nearest fix
+        assert!(fixed.to_hsla_0_1()[2] > foreground.to_hsla_0_1()[2]);
+
+        // passing colors are left as they are
+        let fixed = nearest_passing_color(&Color::BLACK, &Color::WHITE, AAA_RATIO).unwrap();
+        assert_eq!(fixed.to_hex_string(), "#000000");
+    }
+}
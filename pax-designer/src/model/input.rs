@@ -5,23 +5,32 @@ use pax_designtime::DesigntimeManager;
 use pax_engine::api::{borrow, borrow_mut, Fill, Interpolatable, Stroke};
 use pax_engine::math::Vector2;
 use pax_engine::pax_manifest::{UniqueTemplateNodeIdentifier, ValueDefinition};
+use pax_engine::serde::{Deserialize, Serialize};
 use pax_engine::{log, CoercionRules, Property};
 
 use crate::controls::toolbar::FinishCurrentTool;
-use crate::model::action::orm::{RedoRequested, SerializeRequested, UndoRequested};
+use crate::model::action::orm::{
+    RedoRequested, SerializeRequested, ToggleReviewChanges, UndoRequested,
+};
 use crate::model::SelectionStateSnapshot;
 use crate::{controls::toolbar, glass, llm_interface::SetLLMPromptState};
 
+use super::action::orm::gradient::ToggleGradientEditing;
 use super::action::orm::group_ungroup::{GroupNodes, GroupSelected, GroupType, UngroupSelected};
 use super::action::orm::other::SwapFillStrokeAction;
 use super::action::orm::space_movement::TranslateFromSnapshot;
 use super::action::orm::tree_movement::{RelativeMove, RelativeMoveSelected};
 use super::action::orm::{Copy, Paste};
 use super::action::world::SelectAllInOpenContainer;
+use super::contrast::ToggleContrastAudit;
+use super::keymap::{KeyContext, Keymap, ToggleKeymapSettings};
+use super::performance::TogglePerformanceOverlay;
 use super::read_app_state;
+use super::session::{ExportBugReport, ToggleSessionRecording};
+use super::workspace::{ToggleGrid, ToggleRulers};
 use super::{
     action::{self, orm::DeleteSelected, world, Action, ActionContext},
-    Tool, ToolbarComponent,
+    Tool,
 };
 
 impl Interpolatable for InputMapper {}
@@ -29,7 +38,14 @@ impl Interpolatable for InputMapper {}
 #[derive(Clone)]
 pub struct InputMapper {
     modifier_map: HashMap<RawInput, ModifierKey>,
-    key_map: Vec<((RawInput, HashSet<ModifierKey>), InputEvent)>,
+    /// User-editable bindings from keys to events
+    pub keymap: Keymap,
+    /// Keyboard input isn't handled until the keymap of the user has been
+    /// loaded from the design server, see [`super::keymap::load_persisted_keymap`]
+    pub keymap_loaded: bool,
+    /// Input scopes pushed while something other than the canvas has the keyboard,
+    /// e.g. [`KeyContext::TextEditing`] while a text editor is focused
+    scopes: Vec<KeyContext>,
 }
 
 impl Default for InputMapper {
@@ -45,178 +61,56 @@ impl Default for InputMapper {
                 (RawInput::Z, ModifierKey::Z),
                 (RawInput::Space, ModifierKey::Space),
             ]),
-            //Default keymap, will be configurable in settings
-            key_map: [
-                // --- Select tools ---
-                // rectangle
-                (
-                    (RawInput::R, HashSet::new()),
-                    InputEvent::SelectTool(Tool::CreateComponent(ToolbarComponent::Rectangle)),
-                ),
-                (
-                    (RawInput::M, HashSet::new()),
-                    InputEvent::SelectTool(Tool::CreateComponent(ToolbarComponent::Rectangle)),
-                ),
-                // ellipse
-                (
-                    (RawInput::O, HashSet::new()),
-                    InputEvent::SelectTool(Tool::CreateComponent(ToolbarComponent::Ellipse)),
-                ),
-                (
-                    (RawInput::E, HashSet::new()),
-                    InputEvent::SelectTool(Tool::CreateComponent(ToolbarComponent::Ellipse)),
-                ),
-                // pointers
-                (
-                    (RawInput::V, HashSet::new()),
-                    InputEvent::SelectTool(Tool::PointerPercent),
-                ),
-                (
-                    (RawInput::V, HashSet::from([ModifierKey::Shift])),
-                    InputEvent::SelectTool(Tool::PointerPixels),
-                ),
-                // text
-                (
-                    (RawInput::T, HashSet::new()),
-                    InputEvent::SelectTool(Tool::CreateComponent(ToolbarComponent::Text)),
-                ),
-                (
-                    (RawInput::T, HashSet::from([ModifierKey::Shift])),
-                    InputEvent::SelectTool(Tool::CreateComponent(ToolbarComponent::Textbox)),
-                ),
-                // button
-                (
-                    (RawInput::B, HashSet::new()),
-                    InputEvent::SelectTool(Tool::CreateComponent(ToolbarComponent::Button)),
-                ),
-                // -- Group/ungroup ops
-                (
-                    (RawInput::L, HashSet::from([ModifierKey::Meta])),
-                    InputEvent::Group(GroupType::Link),
-                ),
-                (
-                    (RawInput::G, HashSet::from([ModifierKey::Meta])),
-                    InputEvent::Group(GroupType::Group),
-                ),
-                (
-                    (
-                        RawInput::G,
-                        HashSet::from([ModifierKey::Meta, ModifierKey::Shift]),
-                    ),
-                    InputEvent::Ungroup,
-                ),
-                // --- Copy/Paste ---
-                (
-                    (RawInput::C, HashSet::from([ModifierKey::Meta])),
-                    InputEvent::Copy,
-                ),
-                (
-                    (RawInput::V, HashSet::from([ModifierKey::Meta])),
-                    InputEvent::Paste,
-                ),
-                // --- Undo and Redo ---
-                (
-                    (RawInput::Z, HashSet::from([ModifierKey::Meta])),
-                    InputEvent::Undo,
-                ),
-                (
-                    (
-                        RawInput::Z,
-                        HashSet::from([ModifierKey::Meta, ModifierKey::Shift]),
-                    ),
-                    InputEvent::Redo,
-                ),
-                // --- Serialize/save ---
-                ((RawInput::S, HashSet::new()), InputEvent::Serialize),
-                // --- Zoom ---
-                (
-                    (RawInput::Plus, HashSet::from([ModifierKey::Meta])),
-                    InputEvent::ZoomIn,
-                ),
-                (
-                    (RawInput::Minus, HashSet::from([ModifierKey::Meta])),
-                    InputEvent::ZoomOut,
-                ),
-                // --- Movement between layers ---
-                (
-                    (RawInput::OpenSquareBracket, HashSet::from([])),
-                    InputEvent::LayerMove(RelativeMove::BumpDown),
-                ),
-                (
-                    (RawInput::CloseSquareBracket, HashSet::from([])),
-                    InputEvent::LayerMove(RelativeMove::BumpUp),
-                ),
-                (
-                    (
-                        RawInput::OpenSquareBracket,
-                        HashSet::from([ModifierKey::Meta]),
-                    ),
-                    InputEvent::LayerMove(RelativeMove::Bottom),
-                ),
-                (
-                    (
-                        RawInput::CloseSquareBracket,
-                        HashSet::from([ModifierKey::Meta]),
-                    ),
-                    InputEvent::LayerMove(RelativeMove::Top),
-                ),
-                // --- LLM Prompt ---
-                // (
-                //     (RawInput::K, HashSet::from([ModifierKey::Meta])),
-                //     InputEvent::OpenLLMPrompt,
-                // ),
-                // --- Deletion ---
-                (
-                    (RawInput::Delete, HashSet::new()),
-                    InputEvent::DeleteSelected,
-                ),
-                (
-                    (RawInput::Backspace, HashSet::new()),
-                    InputEvent::DeleteSelected,
-                ),
-                // --- Nudge Objects ---
-                (
-                    (RawInput::ArrowRight, HashSet::new()),
-                    InputEvent::Nudge(NudgeDir::Right),
-                ),
-                (
-                    (RawInput::ArrowLeft, HashSet::new()),
-                    InputEvent::Nudge(NudgeDir::Left),
-                ),
-                (
-                    (RawInput::ArrowUp, HashSet::new()),
-                    InputEvent::Nudge(NudgeDir::Up),
-                ),
-                (
-                    (RawInput::ArrowDown, HashSet::new()),
-                    InputEvent::Nudge(NudgeDir::Down),
-                ),
-                // --- Util ---
-                (
-                    (RawInput::X, HashSet::from([ModifierKey::Shift])),
-                    InputEvent::SwapFillStroke,
-                ),
-                (
-                    (RawInput::A, HashSet::from([ModifierKey::Meta])),
-                    InputEvent::SelectAllInOpenContainer,
-                ),
-                (
-                    (RawInput::Esc, HashSet::from([])),
-                    InputEvent::FinishCurrentTool,
-                ),
-            ]
-            .to_vec(),
+            keymap: Keymap::default(),
+            keymap_loaded: false,
+            scopes: Vec::new(),
         }
     }
 }
 
 impl InputMapper {
+    /// Makes `scope` the active input scope, until it's popped again
+    pub fn push_scope(&mut self, scope: KeyContext) {
+        self.scopes.push(scope);
+    }
+
+    /// Pops `scope` along with the scopes pushed after it. Does nothing if `scope`
+    /// isn't pushed, so that it's fine to pop a scope more than once.
+    pub fn pop_scope(&mut self, scope: KeyContext) {
+        if let Some(index) = self.scopes.iter().rposition(|s| *s == scope) {
+            self.scopes.truncate(index);
+        }
+    }
+
+    /// The scope keys are resolved against: the last pushed scope, else the
+    /// context of the tool in use (`tool_context`), else the canvas
+    pub fn active_scope(&self, tool_context: Option<KeyContext>) -> KeyContext {
+        self.scopes
+            .last()
+            .copied()
+            .or(tool_context)
+            .unwrap_or(KeyContext::Canvas)
+    }
+
+    /// Updates the pressed modifiers, and returns the event bound to input in
+    /// scope, or the scopes it falls back to (see [`Keymap::lookup`])
     pub fn to_event(
         &self,
         input: RawInput,
         dir: Dir,
         modifiers: Property<HashSet<ModifierKey>>,
-    ) -> Option<&InputEvent> {
+        scope: KeyContext,
+    ) -> Option<InputEvent> {
+        self.update_modifiers(input, dir, &modifiers);
+        self.keymap.lookup(input, &modifiers.get(), scope)
+    }
+
+    pub fn update_modifiers(
+        &self,
+        input: RawInput,
+        dir: Dir,
+        modifiers: &Property<HashSet<ModifierKey>>,
+    ) {
         if let Some(modifier) = self.modifier_map.get(&input) {
             modifiers.update(|modifiers| {
                 // HACK: browser for some reason doesn't trigger key up for "z" when
@@ -231,16 +125,11 @@ impl InputMapper {
                 };
             });
         }
-        let modifiers = modifiers.get();
-        // find the key combination that matches all required keys,
-        // and contains the largest number of required keys
-        let res = self
-            .key_map
-            .iter()
-            .filter(|((i, m), _)| i == &input && m.is_subset(&modifiers))
-            .max_by_key(|((_, m), _)| m.len())
-            .map(|(_, v)| v);
-        res
+    }
+
+    /// Whether input is only used as a modifier, and can't be bound on its own
+    pub fn is_modifier_only(&self, input: RawInput) -> bool {
+        self.modifier_map.contains_key(&input) && input != RawInput::Z
     }
 
     pub fn to_action(&self, event: &InputEvent, dir: Dir) -> Option<Box<dyn Action>> {
@@ -269,6 +158,25 @@ impl InputMapper {
                 }
                 CopySelected
             })),
+            InputEvent::Duplicate => Some(Box::new({
+                struct DuplicateSelected;
+
+                impl Action for DuplicateSelected {
+                    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+                        let ids = ctx.app_state.selected_template_node_ids.get();
+                        let subtrees = Copy { ids: &ids }.perform(ctx)?;
+                        let t = ctx.transaction("duplicate");
+                        t.run(|| {
+                            Paste {
+                                subtrees: &subtrees,
+                            }
+                            .perform(ctx)
+                        })
+                        .map(|_| ())
+                    }
+                }
+                DuplicateSelected
+            })),
             InputEvent::Paste => Some(Box::new({
                 struct PasteClipboard;
 
@@ -294,6 +202,19 @@ impl InputMapper {
             InputEvent::Ungroup => Some(Box::new(UngroupSelected {})),
             InputEvent::SelectAllInOpenContainer => Some(Box::new(SelectAllInOpenContainer)),
             InputEvent::FinishCurrentTool => Some(Box::new(FinishCurrentTool)),
+            InputEvent::ToggleRulers => Some(Box::new(ToggleRulers)),
+            InputEvent::ToggleGrid => Some(Box::new(ToggleGrid)),
+            InputEvent::ToggleKeymapSettings => Some(Box::new(ToggleKeymapSettings)),
+            InputEvent::TogglePerformanceOverlay => Some(Box::new(TogglePerformanceOverlay)),
+            InputEvent::ToggleContrastAudit => Some(Box::new(ToggleContrastAudit)),
+            InputEvent::ToggleReviewChanges => Some(Box::new(ToggleReviewChanges)),
+            InputEvent::ToggleGradientEditing => Some(Box::new(ToggleGradientEditing)),
+            InputEvent::ToggleSessionRecording { redact_strings } => {
+                Some(Box::new(ToggleSessionRecording {
+                    redact_strings: *redact_strings,
+                }))
+            }
+            InputEvent::ExportBugReport => Some(Box::new(ExportBugReport)),
             InputEvent::Nudge(n_dir) => {
                 struct Nudge(NudgeDir);
 
@@ -332,41 +253,137 @@ pub enum Dir {
 
 // This represents the actual input performed by the user
 // to be rebindable in a settingsview
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(crate = "pax_engine::serde")]
 pub enum RawInput {
     Delete,
     Backspace,
-    A,
-    R,
-    V,
-    C,
-    Control,
+    Esc,
+    Enter,
+    Tab,
     Space,
-    Plus,
-    Minus,
-    Alt,
-    Z,
+    Control,
     Meta,
     Shift,
+    Alt,
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
     K,
-    S,
+    L,
     M,
+    N,
     O,
-    E,
+    P,
+    Q,
+    R,
+    S,
     T,
-    B,
-    L,
-    G,
+    U,
+    V,
+    W,
     X,
+    Y,
+    Z,
+    Digit0,
+    Digit1,
+    Digit2,
+    Digit3,
+    Digit4,
+    Digit5,
+    Digit6,
+    Digit7,
+    Digit8,
+    Digit9,
+    Plus,
+    Minus,
+    Comma,
+    Period,
+    Slash,
+    Semicolon,
+    Quote,
     OpenSquareBracket,
     CloseSquareBracket,
-    Esc,
     ArrowRight,
     ArrowLeft,
     ArrowUp,
     ArrowDown,
 }
 
+impl RawInput {
+    /// Name of the key, as shown in the keymap settings
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Delete => "Delete",
+            Self::Backspace => "Backspace",
+            Self::Esc => "Esc",
+            Self::Enter => "Enter",
+            Self::Tab => "Tab",
+            Self::Space => "Space",
+            Self::Control => "Ctrl",
+            Self::Meta => "Cmd",
+            Self::Shift => "Shift",
+            Self::Alt => "Alt",
+            Self::A => "A",
+            Self::B => "B",
+            Self::C => "C",
+            Self::D => "D",
+            Self::E => "E",
+            Self::F => "F",
+            Self::G => "G",
+            Self::H => "H",
+            Self::I => "I",
+            Self::J => "J",
+            Self::K => "K",
+            Self::L => "L",
+            Self::M => "M",
+            Self::N => "N",
+            Self::O => "O",
+            Self::P => "P",
+            Self::Q => "Q",
+            Self::R => "R",
+            Self::S => "S",
+            Self::T => "T",
+            Self::U => "U",
+            Self::V => "V",
+            Self::W => "W",
+            Self::X => "X",
+            Self::Y => "Y",
+            Self::Z => "Z",
+            Self::Digit0 => "0",
+            Self::Digit1 => "1",
+            Self::Digit2 => "2",
+            Self::Digit3 => "3",
+            Self::Digit4 => "4",
+            Self::Digit5 => "5",
+            Self::Digit6 => "6",
+            Self::Digit7 => "7",
+            Self::Digit8 => "8",
+            Self::Digit9 => "9",
+            Self::Plus => "+",
+            Self::Minus => "-",
+            Self::Comma => ",",
+            Self::Period => ".",
+            Self::Slash => "/",
+            Self::Semicolon => ";",
+            Self::Quote => "'",
+            Self::OpenSquareBracket => "[",
+            Self::CloseSquareBracket => "]",
+            Self::ArrowRight => "→",
+            Self::ArrowLeft => "←",
+            Self::ArrowUp => "↑",
+            Self::ArrowDown => "↓",
+        }
+    }
+}
+
 // TODO make RawInput be what is returned by the engine itself, instead
 // of performing conversion here
 impl TryFrom<String> for RawInput {
@@ -374,37 +391,65 @@ impl TryFrom<String> for RawInput {
 
     fn try_from(value: String) -> std::result::Result<Self, Self::Error> {
         Ok(match value.to_lowercase().as_str() {
+            "delete" => Self::Delete,
+            "backspace" => Self::Backspace,
+            "escape" => Self::Esc,
+            "enter" => Self::Enter,
+            "tab" => Self::Tab,
+            " " => Self::Space,
+            "control" => Self::Control,
+            "meta" => Self::Meta,
+            "shift" => Self::Shift,
+            "alt" => Self::Alt,
             "a" => Self::A,
-            "r" => Self::R,
-            "m" => Self::M,
-            "v" => Self::V,
-            "z" => Self::Z,
-            "k" => Self::K,
-            "s" => Self::S,
+            "b" => Self::B,
             "c" => Self::C,
-            "o" => Self::O,
+            "d" => Self::D,
             "e" => Self::E,
-            "t" => Self::T,
-            "b" => Self::B,
-            "l" => Self::L,
+            "f" => Self::F,
             "g" => Self::G,
+            "h" => Self::H,
+            "i" => Self::I,
+            "j" => Self::J,
+            "k" => Self::K,
+            "l" => Self::L,
+            "m" => Self::M,
+            "n" => Self::N,
+            "o" => Self::O,
+            "p" => Self::P,
+            "q" => Self::Q,
+            "r" => Self::R,
+            "s" => Self::S,
+            "t" => Self::T,
+            "u" => Self::U,
+            "v" => Self::V,
+            "w" => Self::W,
             "x" => Self::X,
+            "y" => Self::Y,
+            "z" => Self::Z,
+            "0" => Self::Digit0,
+            "1" => Self::Digit1,
+            "2" => Self::Digit2,
+            "3" => Self::Digit3,
+            "4" => Self::Digit4,
+            "5" => Self::Digit5,
+            "6" => Self::Digit6,
+            "7" => Self::Digit7,
+            "8" => Self::Digit8,
+            "9" => Self::Digit9,
+            "=" => Self::Plus,
+            "-" => Self::Minus,
+            "," => Self::Comma,
+            "." => Self::Period,
+            "/" => Self::Slash,
+            ";" => Self::Semicolon,
+            "'" => Self::Quote,
             "[" => Self::OpenSquareBracket,
             "]" => Self::CloseSquareBracket,
-            " " => Self::Space,
             "arrowright" => Self::ArrowRight,
             "arrowleft" => Self::ArrowLeft,
             "arrowup" => Self::ArrowUp,
             "arrowdown" => Self::ArrowDown,
-            "control" => Self::Control,
-            "=" => Self::Plus,
-            "-" => Self::Minus,
-            "alt" => Self::Alt,
-            "meta" => Self::Meta,
-            "shift" => Self::Shift,
-            "delete" => Self::Delete,
-            "backspace" => Self::Backspace,
-            "escape" => Self::Esc,
             _ => return Err(anyhow!("no configured raw input mapping for {:?}", value)),
         })
     }
@@ -426,6 +471,7 @@ pub enum InputEvent {
     Serialize,
     Copy,
     Paste,
+    Duplicate,
     Group(GroupType),
     Ungroup,
     SwapFillStroke,
@@ -433,6 +479,15 @@ pub enum InputEvent {
     SelectAllInOpenContainer,
     FinishCurrentTool,
     Nudge(NudgeDir),
+    ToggleRulers,
+    ToggleGrid,
+    ToggleKeymapSettings,
+    TogglePerformanceOverlay,
+    ToggleContrastAudit,
+    ToggleReviewChanges,
+    ToggleGradientEditing,
+    ToggleSessionRecording { redact_strings: bool },
+    ExportBugReport,
 }
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
@@ -445,7 +500,8 @@ pub enum NudgeDir {
 
 impl Interpolatable for ModifierKey {}
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Copy)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Copy, Serialize, Deserialize)]
+#[serde(crate = "pax_engine::serde")]
 pub enum ModifierKey {
     Control,
     Alt,
@@ -455,3 +511,17 @@ pub enum ModifierKey {
     // "zoom mode"
     Z,
 }
+
+impl ModifierKey {
+    /// Name of the modifier, as shown in the keymap settings
+    pub fn label(&self) -> &'static str {
+        match self {
+            ModifierKey::Control => "Ctrl",
+            ModifierKey::Alt => "Alt",
+            ModifierKey::Shift => "Shift",
+            ModifierKey::Meta => "Cmd",
+            ModifierKey::Space => "Space",
+            ModifierKey::Z => "Z",
+        }
+    }
+}
@@ -0,0 +1,742 @@
+//! User-editable keyboard bindings of the designer.
+//! Every action that can be bound to keys is listed in [`registered_actions`],
+//! together with its default bindings and the context it's active in. The
+//! [`Keymap`] maps the id of each of these actions to the chords bound to it,
+//! and is persisted per user through the design server, see [`SaveKeymap`].
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::Display;
+
+use anyhow::{anyhow, Result};
+use pax_engine::api::{borrow, borrow_mut, Interpolatable, NodeContext};
+use pax_engine::serde::{Deserialize, Serialize};
+use pax_engine::{log, serde_json};
+
+use super::action::orm::group_ungroup::GroupType;
+use super::action::orm::tree_movement::RelativeMove;
+use super::action::{Action, ActionContext};
+use super::input::{InputEvent, ModifierKey, NudgeDir, RawInput};
+use super::{Tool, ToolbarComponent};
+
+/// A key together with the modifiers that need to be held down for it to trigger
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(crate = "pax_engine::serde")]
+pub struct Chord {
+    pub key: RawInput,
+    pub modifiers: BTreeSet<ModifierKey>,
+}
+
+impl Chord {
+    pub fn new(key: RawInput, modifiers: impl IntoIterator<Item = ModifierKey>) -> Self {
+        Self {
+            key,
+            modifiers: modifiers.into_iter().collect(),
+        }
+    }
+}
+
+impl Display for Chord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for modifier in &self.modifiers {
+            write!(f, "{}+", modifier.label())?;
+        }
+        write!(f, "{}", self.key.label())
+    }
+}
+
+/// The input scope the bindings of an action belong to. Scopes nest: bindings of a scope
+/// override the bindings of the scopes it falls back to (see [`KeyContext::fallback`]).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyContext {
+    /// Always active, also while editing text
+    Global,
+    /// Active while the canvas has the keyboard, i.e. no text editor is focused
+    Canvas,
+    /// Only active while a tool is in use, e.g. while drawing or dragging
+    ToolActive,
+    /// Only active while editing text, suppresses the canvas bindings
+    TextEditing,
+}
+
+impl KeyContext {
+    /// The scope whose bindings are used for keys not bound in this one
+    pub fn fallback(&self) -> Option<KeyContext> {
+        match self {
+            KeyContext::Global => None,
+            KeyContext::Canvas | KeyContext::TextEditing => Some(KeyContext::Global),
+            KeyContext::ToolActive => Some(KeyContext::Canvas),
+        }
+    }
+
+    /// This scope followed by the scopes it falls back to, most specific first
+    pub fn resolution_order(self) -> impl Iterator<Item = KeyContext> {
+        std::iter::successors(Some(self), KeyContext::fallback)
+    }
+
+    /// Whether bindings in this context are active when `scope` is the active scope
+    pub fn is_active(&self, scope: KeyContext) -> bool {
+        scope.resolution_order().any(|context| context == *self)
+    }
+
+    /// Whether bindings in the two contexts can be active at the same time
+    fn overlaps(&self, other: KeyContext) -> bool {
+        self.is_active(other) || other.is_active(*self)
+    }
+}
+
+/// An action that can be bound to keys
+pub struct KeymapAction {
+    /// Stable identifier, used as the key of the persisted keymap
+    pub id: &'static str,
+    /// Human readable name, shown in the keymap settings
+    pub name: &'static str,
+    pub event: InputEvent,
+    pub context: KeyContext,
+    pub default_chords: Vec<Chord>,
+}
+
+impl KeymapAction {
+    fn new(
+        id: &'static str,
+        name: &'static str,
+        event: InputEvent,
+        default_chords: impl IntoIterator<Item = Chord>,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            event,
+            context: KeyContext::Canvas,
+            default_chords: default_chords.into_iter().collect(),
+        }
+    }
+
+    /// Restricts the bindings of the action to when the keyboard is in `context`
+    pub fn in_context(mut self, context: KeyContext) -> Self {
+        self.context = context;
+        self
+    }
+}
+
+/// All actions that can be bound to keys, in the order they are listed in the keymap settings.
+/// New bindable actions (and tools) need to be added here to be reachable from the keyboard.
+pub fn registered_actions() -> Vec<KeymapAction> {
+    use InputEvent as E;
+    use ModifierKey::{Meta, Shift};
+    use RawInput as K;
+    let create = |component| E::SelectTool(Tool::CreateComponent(component));
+    let key = |key| Chord::new(key, []);
+    vec![
+        // --- Select tools ---
+        KeymapAction::new(
+            "tool.pointer_percent",
+            "Pointer (%)",
+            E::SelectTool(Tool::PointerPercent),
+            [key(K::V)],
+        ),
+        KeymapAction::new(
+            "tool.pointer_pixels",
+            "Pointer (px)",
+            E::SelectTool(Tool::PointerPixels),
+            [Chord::new(K::V, [Shift])],
+        ),
+        KeymapAction::new(
+            "tool.rectangle",
+            "Rectangle tool",
+            create(ToolbarComponent::Rectangle),
+            [key(K::R), key(K::M)],
+        ),
+        KeymapAction::new(
+            "tool.ellipse",
+            "Ellipse tool",
+            create(ToolbarComponent::Ellipse),
+            [key(K::O), key(K::E)],
+        ),
+        KeymapAction::new(
+            "tool.text",
+            "Text tool",
+            create(ToolbarComponent::Text),
+            [key(K::T)],
+        ),
+        KeymapAction::new(
+            "tool.textbox",
+            "Textbox tool",
+            create(ToolbarComponent::Textbox),
+            [Chord::new(K::T, [Shift])],
+        ),
+        KeymapAction::new(
+            "tool.button",
+            "Button tool",
+            create(ToolbarComponent::Button),
+            [key(K::B)],
+        ),
+        KeymapAction::new(
+            "tool.finish",
+            "Finish tool / deselect",
+            E::FinishCurrentTool,
+            [key(K::Esc)],
+        )
+        .in_context(KeyContext::Global),
+        // --- Group/ungroup ops ---
+        KeymapAction::new(
+            "group.group",
+            "Group",
+            E::Group(GroupType::Group),
+            [Chord::new(K::G, [Meta])],
+        ),
+        KeymapAction::new(
+            "group.link",
+            "Make link",
+            E::Group(GroupType::Link),
+            [Chord::new(K::L, [Meta])],
+        ),
+        KeymapAction::new(
+            "group.ungroup",
+            "Ungroup",
+            E::Ungroup,
+            [Chord::new(K::G, [Meta, Shift])],
+        ),
+        // --- Edit ---
+        KeymapAction::new("edit.copy", "Copy", E::Copy, [Chord::new(K::C, [Meta])]),
+        KeymapAction::new("edit.paste", "Paste", E::Paste, [Chord::new(K::V, [Meta])]),
+        KeymapAction::new(
+            "edit.duplicate",
+            "Duplicate",
+            E::Duplicate,
+            [Chord::new(K::D, [Meta])],
+        ),
+        KeymapAction::new(
+            "edit.delete",
+            "Delete",
+            E::DeleteSelected,
+            [key(K::Delete), key(K::Backspace)],
+        ),
+        KeymapAction::new("edit.undo", "Undo", E::Undo, [Chord::new(K::Z, [Meta])]),
+        KeymapAction::new(
+            "edit.redo",
+            "Redo",
+            E::Redo,
+            [Chord::new(K::Z, [Meta, Shift])],
+        ),
+        KeymapAction::new(
+            "edit.select_all",
+            "Select all",
+            E::SelectAllInOpenContainer,
+            [Chord::new(K::A, [Meta])],
+        ),
+        KeymapAction::new(
+            "edit.swap_fill_stroke",
+            "Swap fill and stroke",
+            E::SwapFillStroke,
+            [Chord::new(K::X, [Shift])],
+        ),
+        KeymapAction::new(
+            "edit.gradient_on_canvas",
+            "Edit gradient on canvas",
+            E::ToggleGradientEditing,
+            [key(K::G)],
+        ),
+        // --- Serialize/save ---
+        KeymapAction::new("file.save", "Save", E::Serialize, [key(K::S)]),
+        KeymapAction::new(
+            "file.review_changes",
+            "Review changes",
+            E::ToggleReviewChanges,
+            [],
+        ),
+        // --- Movement between layers ---
+        KeymapAction::new(
+            "layer.bump_up",
+            "Move up",
+            E::LayerMove(RelativeMove::BumpUp),
+            [key(K::CloseSquareBracket)],
+        ),
+        KeymapAction::new(
+            "layer.bump_down",
+            "Move down",
+            E::LayerMove(RelativeMove::BumpDown),
+            [key(K::OpenSquareBracket)],
+        ),
+        KeymapAction::new(
+            "layer.top",
+            "Move to top",
+            E::LayerMove(RelativeMove::Top),
+            [Chord::new(K::CloseSquareBracket, [Meta])],
+        ),
+        KeymapAction::new(
+            "layer.bottom",
+            "Move to bottom",
+            E::LayerMove(RelativeMove::Bottom),
+            [Chord::new(K::OpenSquareBracket, [Meta])],
+        ),
+        // --- Nudge objects ---
+        KeymapAction::new(
+            "nudge.left",
+            "Nudge left",
+            E::Nudge(NudgeDir::Left),
+            [key(K::ArrowLeft)],
+        ),
+        KeymapAction::new(
+            "nudge.right",
+            "Nudge right",
+            E::Nudge(NudgeDir::Right),
+            [key(K::ArrowRight)],
+        ),
+        KeymapAction::new(
+            "nudge.up",
+            "Nudge up",
+            E::Nudge(NudgeDir::Up),
+            [key(K::ArrowUp)],
+        ),
+        KeymapAction::new(
+            "nudge.down",
+            "Nudge down",
+            E::Nudge(NudgeDir::Down),
+            [key(K::ArrowDown)],
+        ),
+        // --- View ---
+        KeymapAction::new(
+            "view.zoom_in",
+            "Zoom in",
+            E::ZoomIn,
+            [Chord::new(K::Plus, [Meta])],
+        ),
+        KeymapAction::new(
+            "view.zoom_out",
+            "Zoom out",
+            E::ZoomOut,
+            [Chord::new(K::Minus, [Meta])],
+        ),
+        KeymapAction::new(
+            "view.toggle_rulers",
+            "Toggle rulers",
+            E::ToggleRulers,
+            [Chord::new(K::R, [Shift])],
+        ),
+        KeymapAction::new(
+            "view.toggle_grid",
+            "Toggle grid",
+            E::ToggleGrid,
+            [Chord::new(K::Quote, [Meta])],
+        ),
+        KeymapAction::new(
+            "view.toggle_performance_overlay",
+            "Toggle performance overlay",
+            E::TogglePerformanceOverlay,
+            [Chord::new(K::P, [Shift])],
+        ),
+        KeymapAction::new(
+            "view.toggle_contrast_audit",
+            "Toggle contrast audit",
+            E::ToggleContrastAudit,
+            [],
+        ),
+        // --- Bug reports ---
+        KeymapAction::new(
+            "help.toggle_session_recording",
+            "Record session for bug reports",
+            E::ToggleSessionRecording {
+                redact_strings: false,
+            },
+            [],
+        ),
+        KeymapAction::new(
+            "help.toggle_redacted_session_recording",
+            "Record session for bug reports, without text",
+            E::ToggleSessionRecording {
+                redact_strings: true,
+            },
+            [],
+        ),
+        KeymapAction::new(
+            "help.export_bug_report",
+            "Export bug report",
+            E::ExportBugReport,
+            [],
+        ),
+        // --- Other ---
+        KeymapAction::new("llm.open_prompt", "Open LLM prompt", E::OpenLLMPrompt, []),
+        KeymapAction::new(
+            "settings.keymap",
+            "Keyboard shortcuts",
+            E::ToggleKeymapSettings,
+            [Chord::new(K::Comma, [Meta])],
+        )
+        .in_context(KeyContext::Global),
+    ]
+}
+
+fn registered_action(id: &str) -> Option<KeymapAction> {
+    registered_actions().into_iter().find(|a| a.id == id)
+}
+
+/// The chords bound to each registered action, keyed by action id.
+/// The default instance contains the default bindings of every action.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "pax_engine::serde", transparent)]
+pub struct Keymap {
+    bindings: BTreeMap<String, Vec<Chord>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            bindings: registered_actions()
+                .into_iter()
+                .map(|action| (action.id.to_string(), action.default_chords))
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    pub fn chords(&self, action_id: &str) -> &[Chord] {
+        self.bindings
+            .get(action_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn is_default(&self, action_id: &str) -> bool {
+        registered_action(action_id).is_some_and(|a| a.default_chords == self.chords(action_id))
+    }
+
+    /// Finds the event bound to `key` given the currently pressed modifiers, resolving against
+    /// the bindings of `scope` first and then the scopes it falls back to. Within a scope, if
+    /// several chords match, the one requiring the most modifiers wins.
+    pub fn lookup(
+        &self,
+        key: RawInput,
+        modifiers: &HashSet<ModifierKey>,
+        scope: KeyContext,
+    ) -> Option<InputEvent> {
+        let actions = registered_actions();
+        scope.resolution_order().find_map(|context| {
+            actions
+                .iter()
+                .filter(|action| action.context == context)
+                .filter_map(|action| {
+                    self.chords(action.id)
+                        .iter()
+                        .filter(|c| {
+                            c.key == key && c.modifiers.iter().all(|m| modifiers.contains(m))
+                        })
+                        .map(|c| c.modifiers.len())
+                        .max()
+                        .map(|len| (len, action.event.clone()))
+                })
+                .max_by_key(|(len, _)| *len)
+                .map(|(_, event)| event)
+        })
+    }
+
+    /// Ids of the other actions that `chord` is bound to, that could be active at the same
+    /// time as the action with `action_id`
+    pub fn conflicts(&self, action_id: &str, chord: &Chord) -> Vec<&'static str> {
+        let Some(action) = registered_action(action_id) else {
+            return vec![];
+        };
+        registered_actions()
+            .into_iter()
+            .filter(|other| other.id != action_id && other.context.overlaps(action.context))
+            .filter(|other| self.chords(other.id).contains(chord))
+            .map(|other| other.id)
+            .collect()
+    }
+
+    /// Replaces the bindings of the action with `chord`
+    pub fn bind(&mut self, action_id: &str, chord: Chord) {
+        self.bindings.insert(action_id.to_string(), vec![chord]);
+    }
+
+    /// Binds `chord` to the action, handing the previous bindings of the action
+    /// over to the other action that `chord` was bound to
+    pub fn swap(&mut self, action_id: &str, chord: Chord, other_id: &str) {
+        let previous = self.chords(action_id).to_vec();
+        let other = self.bindings.entry(other_id.to_string()).or_default();
+        other.retain(|c| c != &chord);
+        other.extend(previous.into_iter().filter(|c| c != &chord));
+        self.bind(action_id, chord);
+    }
+
+    pub fn reset(&mut self, action_id: &str) {
+        if let Some(action) = registered_action(action_id) {
+            self.bindings
+                .insert(action_id.to_string(), action.default_chords);
+        }
+    }
+
+    /// Adds the default bindings of actions that aren't part of this keymap (e.g.
+    /// registered after it was saved), and drops bindings of unknown actions
+    fn with_missing_defaults(mut self) -> Self {
+        let defaults = Keymap::default();
+        self.bindings
+            .retain(|id, _| defaults.bindings.contains_key(id));
+        for (id, chords) in defaults.bindings {
+            self.bindings.entry(id).or_insert(chords);
+        }
+        self
+    }
+}
+
+/// A chord captured in the keymap settings, that is already bound to other actions.
+/// Waits for the user to either swap bindings or cancel.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeymapConflict {
+    pub action_id: String,
+    pub chord: Chord,
+    pub conflicting_ids: Vec<&'static str>,
+}
+
+impl Interpolatable for KeymapConflict {}
+
+impl KeymapConflict {
+    pub fn message(&self) -> String {
+        let names: Vec<_> = self
+            .conflicting_ids
+            .iter()
+            .filter_map(|id| registered_action(id))
+            .map(|a| format!("\"{}\"", a.name))
+            .collect();
+        format!("{} is already bound to {}", self.chord, names.join(", "))
+    }
+}
+
+/// Replaces the keymap with the one persisted for the user, once it has been
+/// received from the design server. Keyboard input isn't handled before then.
+pub fn load_persisted_keymap(ctx: &NodeContext) {
+    let Some(keymap) = borrow_mut!(ctx.designtime).take_loaded_keymap() else {
+        return;
+    };
+    let keymap = match keymap.map(|k| serde_json::from_str::<Keymap>(&k)) {
+        Some(Ok(keymap)) => keymap.with_missing_defaults(),
+        Some(Err(e)) => {
+            log::warn!("couldn't parse persisted keymap, using defaults: {e}");
+            Keymap::default()
+        }
+        None => Keymap::default(),
+    };
+    super::read_app_state(|app_state| {
+        app_state.input_mapper.update(|input_mapper| {
+            input_mapper.keymap = keymap;
+            input_mapper.keymap_loaded = true;
+        })
+    });
+}
+
+pub struct SaveKeymap;
+
+impl Action for SaveKeymap {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let keymap = serde_json::to_string(&ctx.app_state.input_mapper.get().keymap)
+            .map_err(|e| anyhow!("couldn't serialize keymap: {e}"))?;
+        borrow!(ctx.engine_context.designtime).save_keymap(keymap)
+    }
+}
+
+fn update_keymap(ctx: &mut ActionContext, update: impl FnOnce(&mut Keymap)) -> Result<()> {
+    ctx.app_state
+        .input_mapper
+        .update(|input_mapper| update(&mut input_mapper.keymap));
+    SaveKeymap.perform(ctx)
+}
+
+pub struct ToggleKeymapSettings;
+
+impl Action for ToggleKeymapSettings {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let open = !ctx.app_state.keymap_settings_open.get();
+        ctx.app_state.keymap_settings_open.set(open);
+        ctx.app_state.keymap_capture.set(None);
+        ctx.app_state.keymap_conflict.set(None);
+        Ok(())
+    }
+}
+
+/// Starts capturing a new binding for the action: the next chord
+/// pressed is passed to [`CaptureChord`] instead of being handled
+pub struct StartKeymapCapture {
+    pub action_id: String,
+}
+
+impl Action for StartKeymapCapture {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        ctx.app_state.keymap_conflict.set(None);
+        ctx.app_state
+            .keymap_capture
+            .set(Some(self.action_id.clone()));
+        Ok(())
+    }
+}
+
+/// Binds the captured chord to the action being captured for,
+/// unless it conflicts with another binding
+pub struct CaptureChord(pub Chord);
+
+impl Action for CaptureChord {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let Some(action_id) = ctx.app_state.keymap_capture.get() else {
+            return Ok(());
+        };
+        ctx.app_state.keymap_capture.set(None);
+        let conflicting_ids = ctx
+            .app_state
+            .input_mapper
+            .get()
+            .keymap
+            .conflicts(&action_id, &self.0);
+        if !conflicting_ids.is_empty() {
+            ctx.app_state.keymap_conflict.set(Some(KeymapConflict {
+                action_id,
+                chord: self.0.clone(),
+                conflicting_ids,
+            }));
+            return Ok(());
+        }
+        update_keymap(ctx, |keymap| keymap.bind(&action_id, self.0.clone()))
+    }
+}
+
+/// Resolves the pending conflict by swapping bindings with the conflicting actions
+pub struct SwapConflictingBindings;
+
+impl Action for SwapConflictingBindings {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let conflict = ctx
+            .app_state
+            .keymap_conflict
+            .get()
+            .ok_or_else(|| anyhow!("no keymap conflict to resolve"))?;
+        ctx.app_state.keymap_conflict.set(None);
+        update_keymap(ctx, |keymap| {
+            for other_id in &conflict.conflicting_ids {
+                keymap.swap(&conflict.action_id, conflict.chord.clone(), other_id);
+            }
+        })
+    }
+}
+
+pub struct CancelKeymapCapture;
+
+impl Action for CancelKeymapCapture {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        ctx.app_state.keymap_capture.set(None);
+        ctx.app_state.keymap_conflict.set(None);
+        Ok(())
+    }
+}
+
+/// Resets the bindings of the action with the given id, or of all actions if `None`
+pub struct ResetKeymap(pub Option<String>);
+
+impl Action for ResetKeymap {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        ctx.app_state.keymap_conflict.set(None);
+        update_keymap(ctx, |keymap| match &self.0 {
+            Some(action_id) => keymap.reset(action_id),
+            None => *keymap = Keymap::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use pax_engine::serde_json;
+
+    use super::{Chord, KeyContext, Keymap};
+    use crate::model::input::{InputEvent, InputMapper, ModifierKey, RawInput};
+    use crate::model::{Tool, ToolbarComponent};
+
+    #[test]
+    fn rebind_and_persist() {
+        let mut keymap = Keymap::default();
+        let chord = Chord::new(RawInput::J, [ModifierKey::Meta]);
+        assert!(keymap.conflicts("edit.duplicate", &chord).is_empty());
+        keymap.bind("edit.duplicate", chord.clone());
+        assert!(!keymap.is_default("edit.duplicate"));
+
+        let pressed = HashSet::from([ModifierKey::Meta]);
+        assert_eq!(
+            keymap.lookup(RawInput::J, &pressed, KeyContext::Canvas),
+            Some(InputEvent::Duplicate)
+        );
+        assert_eq!(
+            keymap.lookup(RawInput::D, &pressed, KeyContext::Canvas),
+            None
+        );
+
+        // actions missing from the persisted keymap get their default bindings
+        let mut persisted: serde_json::Value =
+            serde_json::from_str(&serde_json::to_string(&keymap).unwrap()).unwrap();
+        persisted.as_object_mut().unwrap().remove("edit.undo");
+        let loaded = serde_json::from_value::<Keymap>(persisted)
+            .unwrap()
+            .with_missing_defaults();
+        assert_eq!(loaded.chords("edit.duplicate"), &[chord]);
+        assert!(loaded.is_default("edit.undo"));
+    }
+
+    #[test]
+    fn conflicts_and_swap() {
+        let mut keymap = Keymap::default();
+        let undo = Chord::new(RawInput::Z, [ModifierKey::Meta]);
+        assert_eq!(keymap.conflicts("edit.duplicate", &undo), vec!["edit.undo"]);
+
+        keymap.swap("edit.duplicate", undo.clone(), "edit.undo");
+        assert_eq!(keymap.chords("edit.duplicate"), &[undo]);
+        assert_eq!(
+            keymap.chords("edit.undo"),
+            &[Chord::new(RawInput::D, [ModifierKey::Meta])]
+        );
+
+        keymap.reset("edit.undo");
+        assert!(keymap.is_default("edit.undo"));
+    }
+
+    #[test]
+    fn most_specific_chord_wins() {
+        let keymap = Keymap::default();
+        let pressed = HashSet::from([ModifierKey::Meta, ModifierKey::Shift]);
+        assert_eq!(
+            keymap.lookup(RawInput::Z, &pressed, KeyContext::Canvas),
+            Some(InputEvent::Redo)
+        );
+        assert!(KeyContext::Global.is_active(KeyContext::TextEditing));
+        assert!(KeyContext::Canvas.is_active(KeyContext::ToolActive));
+        assert!(!KeyContext::TextEditing.is_active(KeyContext::ToolActive));
+    }
+
+    #[test]
+    fn text_editing_scope_suppresses_canvas_bindings() {
+        let mut input_mapper = InputMapper::default();
+        let keymap = Keymap::default();
+        let none = HashSet::new();
+        let rectangle = Some(InputEvent::SelectTool(Tool::CreateComponent(
+            ToolbarComponent::Rectangle,
+        )));
+        assert_eq!(input_mapper.active_scope(None), KeyContext::Canvas);
+        assert_eq!(
+            keymap.lookup(RawInput::R, &none, KeyContext::Canvas),
+            rectangle
+        );
+        // canvas bindings stay active while a tool is in use
+        let scope = input_mapper.active_scope(Some(KeyContext::ToolActive));
+        assert_eq!(keymap.lookup(RawInput::R, &none, scope), rectangle);
+
+        input_mapper.push_scope(KeyContext::TextEditing);
+        let scope = input_mapper.active_scope(Some(KeyContext::ToolActive));
+        assert_eq!(scope, KeyContext::TextEditing);
+        assert_eq!(keymap.lookup(RawInput::R, &none, scope), None);
+        // global bindings are still reachable
+        assert_eq!(
+            keymap.lookup(RawInput::Esc, &none, scope),
+            Some(InputEvent::FinishCurrentTool)
+        );
+
+        input_mapper.pop_scope(KeyContext::TextEditing);
+        input_mapper.pop_scope(KeyContext::TextEditing);
+        assert_eq!(input_mapper.active_scope(None), KeyContext::Canvas);
+    }
+}
@@ -1,6 +1,13 @@
 pub mod action;
+pub mod contrast;
 pub mod input;
+pub mod keymap;
+pub mod performance;
+pub mod property_recording;
+pub mod session;
+pub mod styles;
 pub mod tools;
+pub mod workspace;
 
 use crate::glass;
 use crate::glass::control_point::ControlPointBehavior;
@@ -37,7 +44,7 @@ use pax_engine::pax_manifest::ValueDefinition;
 use pax_engine::NodeInterface;
 use pax_engine::NodeLocal;
 use pax_engine::Property;
-use pax_engine::{api::borrow, api::NodeContext, math::Point2};
+use pax_engine::{api::borrow, api::borrow_mut, api::NodeContext, math::Point2};
 use std::any::Any;
 use std::cell::OnceCell;
 use std::cell::RefCell;
@@ -50,11 +57,15 @@ use crate::math::coordinate_spaces::{self, Glass};
 mod selection_state;
 pub use selection_state::*;
 
+use self::action::orm::gradient::GradientStopColorEdit;
+use self::action::orm::snippets::PendingSnippetImport;
 use self::action::pointer::MouseEntryPointAction;
 use self::action::pointer::Pointer;
 use self::action::UndoRedoStack;
 use self::input::ModifierKey;
 use self::input::{Dir, InputEvent, InputMapper};
+use self::keymap::{CancelKeymapCapture, CaptureChord, Chord, KeyContext, KeymapConflict};
+use self::workspace::WorkspaceState;
 
 /// Represents the global source-of-truth for the designer.
 /// Invalid if any of the bellow :INVALID_IF: statements hold true.
@@ -105,6 +116,11 @@ pub struct AppState {
     /// from keystrokes.
     /// INVALID_IF: doesn't represent current mouse pos
     pub mouse_position: Property<Point2<Glass>>,
+    /// Rulers, guides (per edited component) and grid settings. Persisted per
+    /// project, see model::workspace.
+    /// INVALID_IF: no invalid states (guides of components that no longer
+    /// exist are simply never shown)
+    pub workspace: Property<WorkspaceState>,
     /// Current tool state while in use (ie in the process of drawing a rect,
     /// moving an object, moving a control point, drawing a line)
     /// OBS: needs to be wrapped in Rc<RefCell since tool_behavior itself needs
@@ -132,6 +148,73 @@ pub struct AppState {
     /// to be configured
     /// INVALID_IF: no invalid states
     pub input_mapper: Property<InputMapper>,
+    /// Whether the keyboard shortcut settings are open
+    /// INVALID_IF: no invalid states
+    pub keymap_settings_open: Property<bool>,
+    /// Id of the action a new binding is being captured for in the keyboard
+    /// shortcut settings. While set, the next chord pressed is bound to the
+    /// action instead of being handled.
+    /// INVALID_IF: the id isn't the id of a registered keymap action
+    pub keymap_capture: Property<Option<String>>,
+    /// A captured chord that is already bound to other actions, waiting for
+    /// the user to either swap bindings or cancel
+    /// INVALID_IF: keymap_capture is set at the same time
+    pub keymap_conflict: Property<Option<KeymapConflict>>,
+
+    //--------------snippets-----------------
+    /// Names of the snippets in the user's snippet library while the library
+    /// is open (empty until received from the design server), `None` while closed
+    /// INVALID_IF: no invalid states
+    pub snippet_library: Property<Option<Vec<String>>>,
+    /// A snippet whose components collide with components of the project,
+    /// waiting for the user to either import it with renamed components or cancel
+    /// INVALID_IF: no invalid states
+    pub pending_snippet_import: Property<Option<PendingSnippetImport>>,
+
+    //--------------gradient editing---------
+    /// Node whose gradient fill is being edited on the canvas, if any. Editing
+    /// stops when the selection changes.
+    /// INVALID_IF: the node isn't the only selected node
+    pub gradient_editing: Property<Option<UniqueTemplateNodeIdentifier>>,
+    /// Gradient stop whose color is being edited in the color popover
+    /// INVALID_IF: set while gradient_editing isn't
+    pub gradient_stop_color_edit: Property<Option<GradientStopColorEdit>>,
+
+    //--------------performance--------------
+    /// Whether the performance overlay is open, during which node costs are
+    /// sampled by the engine
+    /// INVALID_IF: differs from DesigntimeManager::is_node_cost_profiling
+    pub performance_overlay_open: Property<bool>,
+    /// Whether the property values of the selected and pinned nodes are
+    /// recorded, and the timeline scrubbing through them is shown
+    /// INVALID_IF: differs from DesigntimeManager::is_property_recording
+    pub property_recording: Property<bool>,
+
+    //--------------styles-------------------
+    /// Whether the styles panel, editing the selector blocks of the edited
+    /// component, is shown in place of the settings
+    /// INVALID_IF: no invalid states
+    pub styles_panel_open: Property<bool>,
+    /// Selector edited in the styles panel, e.g. `.card-title`
+    /// INVALID_IF: no invalid states
+    pub selected_selector: Property<Option<String>>,
+
+    //--------------accessibility------------
+    /// Whether the contrast audit panel, listing the rendered text failing
+    /// WCAG AA contrast, is open
+    /// INVALID_IF: no invalid states
+    pub contrast_audit_open: Property<bool>,
+
+    //--------------bug reports--------------
+    /// Whether the session is being recorded for bug reports
+    /// INVALID_IF: differs from PaxManifestORM::is_recording
+    pub session_recording: Property<bool>,
+
+    //--------------saving-------------------
+    /// Whether the panel listing the changes made to the component being
+    /// edited since the project was loaded is open
+    /// INVALID_IF: no invalid states
+    pub review_changes_open: Property<bool>,
 }
 
 // This represents values that can be deterministically produced from the app
@@ -142,6 +225,9 @@ pub struct DerivedAppState {
     pub selection_state: Property<SelectionState>,
     /// The currently open containers, example: the parent group of the rectangle currently selected, and the scroller this group is inside
     pub open_containers: Property<Vec<UniqueTemplateNodeIdentifier>>,
+    /// Whether the manifest has been loaded from the design server. Interactions that
+    /// edit the manifest (selection, tools) are disabled until it has.
+    pub manifest_loaded: Property<bool>,
 }
 
 const INITIALIZED: &'static str = "model should have been initialized";
@@ -198,15 +284,23 @@ impl Model {
         let selection_state =
             Self::derive_selection_state(selected_nodes.clone(), to_glass_transform.clone());
         let open_containers = Self::derive_open_container(ctx, app_state);
+        let manifest_loaded = Self::derive_manifest_loaded(ctx);
 
         DerivedAppState {
             to_glass_transform,
             selection_state,
             open_containers,
             selected_nodes,
+            manifest_loaded,
         }
     }
 
+    fn derive_manifest_loaded(ctx: &NodeContext) -> Property<bool> {
+        let load_state = borrow!(ctx.designtime).get_manifest_load_state_prop();
+        let deps = [load_state.untyped()];
+        Property::computed(move || load_state.get().is_complete(), &deps)
+    }
+
     fn derive_selected_nodes(
         ctx: &NodeContext,
         app_state: &AppState,
@@ -369,6 +463,9 @@ pub fn read_app_state_with_derived<V>(closure: impl FnOnce(&AppState, &DerivedAp
 }
 
 pub fn perform_action(action: &dyn Action, ctx: &NodeContext) {
+    borrow_mut!(ctx.designtime)
+        .get_orm_mut()
+        .record_action(action.name());
     if let Err(e) = with_action_context(ctx, |ac| action.perform(ac)) {
         pax_engine::log::warn!("action failed: {:?}", e);
     }
@@ -377,19 +474,49 @@ pub fn perform_action(action: &dyn Action, ctx: &NodeContext) {
 pub fn process_keyboard_input(ctx: &NodeContext, dir: Dir, input: String) {
     // useful! keeping around for now
     // pax_engine::log::info!("key {:?}: {}", dir, input);
+    borrow_mut!(ctx.designtime)
+        .get_orm_mut()
+        .record_input(&input, dir == Dir::Down);
     let action = MODEL.with_borrow_mut(|model| -> anyhow::Result<Option<Box<dyn Action>>> {
         let raw_input = RawInput::try_from(input)?;
         let AppState {
             ref input_mapper,
             ref modifiers,
+            ref keymap_capture,
+            ref tool_behavior,
             ..
         } = model.as_ref().expect(INITIALIZED).app_state;
 
         let input_mapper = input_mapper.get();
+        if keymap_capture.get().is_some() {
+            input_mapper.update_modifiers(raw_input, dir, modifiers);
+            if dir == Dir::Up || input_mapper.is_modifier_only(raw_input) {
+                return Ok(None);
+            }
+            if raw_input == RawInput::Esc {
+                return Ok(Some(Box::new(CancelKeymapCapture)));
+            }
+            // Z is both a key and the zoom mode modifier
+            let chord = Chord::new(
+                raw_input,
+                modifiers
+                    .get()
+                    .into_iter()
+                    .filter(|m| !(raw_input == RawInput::Z && *m == ModifierKey::Z)),
+            );
+            return Ok(Some(Box::new(CaptureChord(chord))));
+        }
+        if !input_mapper.keymap_loaded {
+            input_mapper.update_modifiers(raw_input, dir, modifiers);
+            return Ok(None);
+        }
+
+        let scope =
+            input_mapper.active_scope(tool_behavior.get().map(|tool| tool.borrow().key_context()));
         let event = input_mapper
-            .to_event(raw_input, dir, modifiers.clone())
+            .to_event(raw_input, dir, modifiers.clone(), scope)
             .with_context(|| "no mapped input")?;
-        let action = input_mapper.to_action(event, dir);
+        let action = input_mapper.to_action(&event, dir);
         Ok(action)
     });
     match action {
@@ -451,6 +578,11 @@ pub trait ToolBehavior {
     fn keyboard(&mut self, event: InputEvent, dir: Dir, ctx: &mut ActionContext)
         -> ControlFlow<()>;
     fn get_visual(&self) -> Property<ToolVisualizationState>;
+    /// The context the keyboard is in while this tool is in use, which decides
+    /// which keymap bindings are active
+    fn key_context(&self) -> KeyContext {
+        KeyContext::ToolActive
+    }
 }
 
 impl Interpolatable for ProjectMode {}
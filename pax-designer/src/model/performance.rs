@@ -0,0 +1,27 @@
+//! Performance overlay of the designer: while open, the engine times the work
+//! on each node of the userland app (see `pax_designtime::node_costs`), the
+//! glass tints the bounds of the most expensive nodes of the component being
+//! edited, and a panel lists them with their costs.
+
+use anyhow::Result;
+use pax_engine::api::{borrow_mut, Color};
+
+use super::action::{Action, ActionContext};
+
+pub struct TogglePerformanceOverlay;
+
+impl Action for TogglePerformanceOverlay {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let open = !ctx.app_state.performance_overlay_open.get();
+        ctx.app_state.performance_overlay_open.set(open);
+        borrow_mut!(ctx.engine_context.designtime).set_node_cost_profiling(open);
+        Ok(())
+    }
+}
+
+/// Color of a node costing `relative` (0.0 to 1.0) times as much as the most
+/// expensive node, from a faint yellow for cheap nodes to a strong red
+pub fn heat_color(relative: f64) -> Color {
+    let relative = relative.clamp(0.0, 1.0);
+    Color::from_rgba_0_1([1.0, 0.9 * (1.0 - relative), 0.0, 0.15 + 0.4 * relative])
+}
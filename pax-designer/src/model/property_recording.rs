@@ -0,0 +1,61 @@
+//! Time-travel property inspector: while recording is on, the engine records
+//! the property values of the selected node and the pinned ones each frame
+//! (see `pax_designtime::property_recorder`), and a timeline under the
+//! settings panel scrubs through them. Values only change while the project
+//! plays, so recording pauses in edit mode.
+
+use anyhow::Result;
+use pax_engine::api::borrow_mut;
+use pax_engine::pax_manifest::UniqueTemplateNodeIdentifier;
+
+use super::action::{Action, ActionContext};
+use super::ProjectMode;
+
+pub struct TogglePropertyRecording;
+
+impl Action for TogglePropertyRecording {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let recording = !ctx.app_state.property_recording.get();
+        ctx.app_state.property_recording.set(recording);
+        {
+            let mut dt = borrow_mut!(ctx.engine_context.designtime);
+            dt.set_property_recording(recording);
+            dt.set_property_recording_paused(matches!(
+                ctx.app_state.project_mode.get(),
+                ProjectMode::Edit
+            ));
+        }
+        sync_recorded_selection(ctx);
+        Ok(())
+    }
+}
+
+/// Pins node `uni` if it isn't, keeping it recorded while deselected, and
+/// unpins it otherwise
+pub struct TogglePinRecordedNode(pub UniqueTemplateNodeIdentifier);
+
+impl Action for TogglePinRecordedNode {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let mut dt = borrow_mut!(ctx.engine_context.designtime);
+        if dt.is_recorded_node_pinned(&self.0) {
+            dt.unpin_recorded_node(&self.0);
+        } else {
+            dt.pin_recorded_node(self.0.clone());
+        }
+        Ok(())
+    }
+}
+
+/// Tells the designtime which node is selected, to record it. Nothing is
+/// recorded of multiple selected nodes
+pub fn sync_recorded_selection(ctx: &ActionContext) {
+    let ids = ctx.app_state.selected_template_node_ids.get();
+    let selected = match ids.as_slice() {
+        [id] => Some(UniqueTemplateNodeIdentifier::build(
+            ctx.app_state.selected_component_id.get(),
+            id.clone(),
+        )),
+        _ => None,
+    };
+    borrow_mut!(ctx.engine_context.designtime).set_property_recording_selection(selected);
+}
@@ -0,0 +1,82 @@
+//! Session recording for bug reports: while recording, the ORM keeps the last
+//! minutes of commands executed, along with the actions performed and keys
+//! pressed (see `pax_designtime::orm::session`), and exporting a bug report
+//! has the design server save them with the manifest to the project, for
+//! replaying with `pax-cli replay`.
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
+use pax_designtime::orm::session::RecordingOptions;
+use pax_engine::api::{borrow_mut, NodeContext};
+
+use super::action::{Action, ActionContext};
+use crate::message_log_display::{self, DesignerLogMsg};
+
+/// Starts recording the session, or stops recording it if it's being recorded
+pub struct ToggleSessionRecording {
+    /// Whether to leave string literal values (e.g. text) out of the recording
+    pub redact_strings: bool,
+}
+
+impl Action for ToggleSessionRecording {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let mut dt = borrow_mut!(ctx.engine_context.designtime);
+        let orm = dt.get_orm_mut();
+        let message = if orm.is_recording() {
+            orm.stop_recording();
+            "Stopped recording session".to_string()
+        } else {
+            let options = RecordingOptions {
+                redact_strings: self.redact_strings,
+                ..Default::default()
+            };
+            let minutes = options.window_ms.unwrap_or_default() / 60_000.0;
+            orm.start_recording(options);
+            format!(
+                "Recording session{}, the last {} minutes are kept for bug reports",
+                if self.redact_strings {
+                    " without text"
+                } else {
+                    ""
+                },
+                minutes
+            )
+        };
+        ctx.app_state.session_recording.set(orm.is_recording());
+        message_log_display::log(DesignerLogMsg::message(message));
+        Ok(())
+    }
+}
+
+/// Saves a bug report with the session recorded so far to the project
+pub struct ExportBugReport;
+
+impl Action for ExportBugReport {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let mut dt = borrow_mut!(ctx.engine_context.designtime);
+        if !dt.get_orm_mut().is_recording() {
+            message_log_display::log(DesignerLogMsg::message(
+                "Record the session to export a bug report".to_string(),
+            ));
+            return Err(anyhow!("session isn't being recorded"));
+        }
+        let environment = BTreeMap::from([(
+            "pax_designer_version".to_string(),
+            env!("CARGO_PKG_VERSION").to_string(),
+        )]);
+        dt.export_bug_report(environment)?;
+        message_log_display::log(DesignerLogMsg::message(
+            "Exporting bug report...".to_string(),
+        ));
+        Ok(())
+    }
+}
+
+/// Shows where the design server saved the bug reports exported since the last tick
+pub fn process_bug_report_messages(ctx: &NodeContext) {
+    let messages = borrow_mut!(ctx.designtime).take_bug_report_messages();
+    for message in messages {
+        message_log_display::log(DesignerLogMsg::message(message));
+    }
+}
@@ -0,0 +1,146 @@
+//! Styles panel: edits the selector blocks of the edited component, e.g.
+//! `.muted { fill: ... }`, shared by every node with `class=muted` (see
+//! `pax_designtime::orm::selectors`). Shown in place of the settings, which
+//! link the properties a node takes from a selector to it.
+
+use anyhow::{anyhow, Result};
+use pax_designtime::orm::selectors::DeleteSelectorPolicy;
+use pax_engine::api::borrow_mut;
+use pax_engine::pax_manifest::{PaxIdentifier, UniqueTemplateNodeIdentifier, ValueDefinition};
+
+use super::action::{Action, ActionContext};
+
+/// Opens the styles panel, editing `selector` if given
+pub struct OpenStyles(pub Option<String>);
+
+impl Action for OpenStyles {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        if self.0.is_some() {
+            ctx.app_state.selected_selector.set(self.0.clone());
+        }
+        ctx.app_state.styles_panel_open.set(true);
+        Ok(())
+    }
+}
+
+pub struct CloseStyles;
+
+impl Action for CloseStyles {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        ctx.app_state.styles_panel_open.set(false);
+        Ok(())
+    }
+}
+
+/// Selects the selector edited in the styles panel
+pub struct SelectSelector(pub String);
+
+impl Action for SelectSelector {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        ctx.app_state.selected_selector.set(Some(self.0.clone()));
+        Ok(())
+    }
+}
+
+/// Adds an empty selector block to the edited component, and selects it
+pub struct AddSelector(pub String);
+
+impl Action for AddSelector {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let component = ctx.app_state.selected_component_id.get();
+        let t = ctx.transaction("adding selector");
+        t.run(|| {
+            borrow_mut!(ctx.engine_context.designtime)
+                .get_orm_mut()
+                .add_selector(component, self.0.clone(), vec![])
+                .map_err(|e| anyhow!(e))
+        })?;
+        ctx.app_state.selected_selector.set(Some(self.0.clone()));
+        Ok(())
+    }
+}
+
+pub struct RenameSelector {
+    pub selector: String,
+    pub new_selector: String,
+}
+
+impl Action for RenameSelector {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let component = ctx.app_state.selected_component_id.get();
+        let t = ctx.transaction("renaming selector");
+        t.run(|| {
+            borrow_mut!(ctx.engine_context.designtime)
+                .get_orm_mut()
+                .rename_selector(component, self.selector.clone(), self.new_selector.clone())
+                .map_err(|e| anyhow!(e))
+        })?;
+        ctx.app_state
+            .selected_selector
+            .set(Some(self.new_selector.clone()));
+        Ok(())
+    }
+}
+
+pub struct DeleteSelector {
+    pub selector: String,
+    pub policy: DeleteSelectorPolicy,
+}
+
+impl Action for DeleteSelector {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let component = ctx.app_state.selected_component_id.get();
+        let t = ctx.transaction("deleting selector");
+        t.run(|| {
+            borrow_mut!(ctx.engine_context.designtime)
+                .get_orm_mut()
+                .delete_selector(component, self.selector.clone(), self.policy)
+                .map_err(|e| anyhow!(e))
+        })?;
+        ctx.app_state.selected_selector.set(None);
+        Ok(())
+    }
+}
+
+/// Sets the class of the selected nodes to that of class selector `selector`,
+/// or if not `applied`, removes it from those having it
+pub struct SetSelectorOnSelection {
+    pub selector: String,
+    pub applied: bool,
+}
+
+impl Action for SetSelectorOnSelection {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let Some(class) = self.selector.strip_prefix('.') else {
+            return Err(anyhow!("only class selectors can be applied to nodes"));
+        };
+        let component = ctx.app_state.selected_component_id.get();
+        let selected = ctx.app_state.selected_template_node_ids.get();
+        let t = ctx.transaction("applying selector");
+        t.run(|| {
+            let mut dt = borrow_mut!(ctx.engine_context.designtime);
+            let orm = dt.get_orm_mut();
+            for id in selected {
+                let uni = UniqueTemplateNodeIdentifier::build(component.clone(), id);
+                let has_class = matches!(
+                    orm.get_property(&uni, "class"),
+                    Some(ValueDefinition::Identifier(ident)) if ident.name == class
+                );
+                if has_class == self.applied {
+                    continue;
+                }
+                let mut node = orm
+                    .get_node(uni, true)
+                    .ok_or_else(|| anyhow!("couldn't find node"))?;
+                let value = self.applied.then(|| {
+                    ValueDefinition::Identifier(PaxIdentifier {
+                        name: class.to_string(),
+                    })
+                });
+                node.set_property_from_value_definition("class", value)?;
+                node.save().map_err(|e| anyhow!(e))?;
+            }
+            Ok(())
+        })
+    }
+}
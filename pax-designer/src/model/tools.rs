@@ -1,4 +1,5 @@
 mod create_component_tool;
+mod guide_tool;
 mod moving_tool;
 mod multi_select_tool;
 mod paintbrush_tool;
@@ -6,6 +7,7 @@ pub mod tool_plugins;
 mod zoom_to_fit_tool;
 
 pub use create_component_tool::*;
+pub use guide_tool::*;
 pub use moving_tool::*;
 pub use multi_select_tool::*;
 pub use paintbrush_tool::*;
@@ -0,0 +1,139 @@
+use std::ops::ControlFlow;
+
+use pax_engine::{log, math::Point2, pax_manifest::TypeId, Property};
+
+use crate::{
+    glass::ToolVisualizationState,
+    math::coordinate_spaces::Glass,
+    model::{
+        action::{Action, ActionContext},
+        input::{Dir, InputEvent},
+        workspace::{Guide, GuideAxis, SaveWorkspaceState},
+        ToolBehavior,
+    },
+};
+
+/// Drags a guide of the currently edited component. Dropping the guide
+/// back onto its ruler removes it.
+pub struct GuideTool {
+    axis: GuideAxis,
+    /// index of the guide in the guides of the edited component
+    index: usize,
+    component: TypeId,
+    pickup_point: Point2<Glass>,
+    moved: bool,
+}
+
+impl GuideTool {
+    /// Creates a new guide at point and starts dragging it
+    /// (used when dragging out of a ruler)
+    pub fn new_guide(ctx: &mut ActionContext, axis: GuideAxis, point: Point2<Glass>) -> Self {
+        let component = ctx.app_state.selected_component_id.get();
+        let position = Self::rounded_position(ctx, axis, point);
+        let mut index = 0;
+        ctx.app_state.workspace.update(|workspace| {
+            let guides = workspace.guides_mut(&component);
+            guides.push(Guide { axis, position });
+            index = guides.len() - 1;
+        });
+        Self {
+            axis,
+            index,
+            component,
+            pickup_point: point,
+            moved: false,
+        }
+    }
+
+    /// Starts dragging the existing guide with index of the edited component
+    pub fn existing_guide(
+        ctx: &mut ActionContext,
+        index: usize,
+        point: Point2<Glass>,
+    ) -> Option<Self> {
+        let component = ctx.app_state.selected_component_id.get();
+        let guide = *ctx
+            .app_state
+            .workspace
+            .get()
+            .guides(&component)
+            .get(index)?;
+        Some(Self {
+            axis: guide.axis,
+            index,
+            component,
+            pickup_point: point,
+            moved: false,
+        })
+    }
+
+    // guides are placed on whole world units unless zoomed in far enough
+    // for fractional positions to be meaningful
+    fn rounded_position(ctx: &ActionContext, axis: GuideAxis, point: Point2<Glass>) -> f64 {
+        let glass_to_world = ctx.world_transform();
+        let position = Guide::world_position(axis, point, glass_to_world);
+        let glass_per_world = 1.0 / glass_to_world.get_scale().x;
+        let precision = if glass_per_world >= 10.0 { 10.0 } else { 1.0 };
+        (position * precision).round() / precision
+    }
+}
+
+impl ToolBehavior for GuideTool {
+    fn pointer_down(&mut self, _point: Point2<Glass>, _ctx: &mut ActionContext) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    fn pointer_move(&mut self, point: Point2<Glass>, ctx: &mut ActionContext) -> ControlFlow<()> {
+        if !self.moved && (self.pickup_point - point).length_squared() < 3.0 {
+            // don't move the guide for very small pixel changes,
+            // to keep double click on a guide from nudging it
+            return ControlFlow::Continue(());
+        }
+        self.moved = true;
+        let position = Self::rounded_position(ctx, self.axis, point);
+        let (component, index) = (&self.component, self.index);
+        ctx.app_state.workspace.update(|workspace| {
+            if let Some(guide) = workspace.guides_mut(component).get_mut(index) {
+                guide.position = position;
+            }
+        });
+        ControlFlow::Continue(())
+    }
+
+    fn pointer_up(&mut self, point: Point2<Glass>, ctx: &mut ActionContext) -> ControlFlow<()> {
+        let _ = self.pointer_move(point, ctx);
+        if Guide::is_over_ruler(self.axis, point) {
+            let (component, index) = (&self.component, self.index);
+            ctx.app_state.workspace.update(|workspace| {
+                let guides = workspace.guides_mut(component);
+                if index < guides.len() {
+                    guides.remove(index);
+                }
+            });
+            self.moved = true;
+        }
+        ControlFlow::Break(())
+    }
+
+    fn keyboard(
+        &mut self,
+        _event: InputEvent,
+        _dir: Dir,
+        _ctx: &mut ActionContext,
+    ) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    fn get_visual(&self) -> Property<ToolVisualizationState> {
+        Property::new(ToolVisualizationState::default())
+    }
+
+    fn finish(&mut self, ctx: &mut ActionContext) -> anyhow::Result<()> {
+        if self.moved {
+            if let Err(e) = SaveWorkspaceState.perform(ctx) {
+                log::warn!("failed to save guides: {e}");
+            }
+        }
+        Ok(())
+    }
+}
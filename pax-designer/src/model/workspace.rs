@@ -0,0 +1,279 @@
+//! Workspace state of the designer: rulers, guides and grid settings.
+//! This is view state rather than part of the manifest, and is therefore not
+//! part of the undo/redo history. It is persisted per project through the
+//! design server, see [`SaveWorkspaceState`].
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use pax_engine::api::{borrow, borrow_mut, Color, Interpolatable, NodeContext};
+use pax_engine::math::{Point2, Transform2};
+use pax_engine::pax_manifest::TypeId;
+use pax_engine::serde::{Deserialize, Serialize};
+use pax_engine::{log, serde_json};
+
+use super::action::{Action, ActionContext};
+use crate::math::coordinate_spaces::{Glass, World};
+
+/// Width of the rulers along the top and left edges of the glass, in glass pixels
+pub const RULER_SIZE: f64 = 20.0;
+
+/// Grid lines closer together than this (in glass pixels) are neither drawn nor snapped to
+const MIN_GRID_LINE_DISTANCE: f64 = 6.0;
+
+impl Interpolatable for WorkspaceState {}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(crate = "pax_engine::serde", default)]
+pub struct WorkspaceState {
+    pub show_rulers: bool,
+    pub grid: GridSettings,
+    /// Guides per editing context, keyed by the unique identifier of the
+    /// component being edited
+    pub guides: HashMap<String, Vec<Guide>>,
+}
+
+impl Default for WorkspaceState {
+    fn default() -> Self {
+        Self {
+            show_rulers: true,
+            grid: Default::default(),
+            guides: Default::default(),
+        }
+    }
+}
+
+impl WorkspaceState {
+    pub fn guides(&self, component: &TypeId) -> &[Guide] {
+        self.guides
+            .get(&component.get_unique_identifier())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn guides_mut(&mut self, component: &TypeId) -> &mut Vec<Guide> {
+        self.guides
+            .entry(component.get_unique_identifier())
+            .or_default()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "pax_engine::serde")]
+pub enum GuideAxis {
+    /// A line at a fixed x coordinate, dragged out of the left ruler
+    Vertical,
+    /// A line at a fixed y coordinate, dragged out of the top ruler
+    Horizontal,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "pax_engine::serde")]
+pub struct Guide {
+    pub axis: GuideAxis,
+    /// x coordinate for vertical guides, y coordinate for horizontal ones (world space)
+    pub position: f64,
+}
+
+impl Guide {
+    pub fn color() -> Color {
+        Color::rgba(255.into(), 64.into(), 160.into(), 200.into())
+    }
+
+    pub fn glass_position(&self, glass_to_world: Transform2<Glass, World>) -> f64 {
+        let point = glass_to_world.inverse() * Point2::<World>::new(self.position, self.position);
+        match self.axis {
+            GuideAxis::Vertical => point.x,
+            GuideAxis::Horizontal => point.y,
+        }
+    }
+
+    pub fn world_position(
+        axis: GuideAxis,
+        point: Point2<Glass>,
+        glass_to_world: Transform2<Glass, World>,
+    ) -> f64 {
+        let point = glass_to_world * point;
+        match axis {
+            GuideAxis::Vertical => point.x,
+            GuideAxis::Horizontal => point.y,
+        }
+    }
+
+    /// Whether a guide dropped at this glass point should be removed
+    /// (it has been dragged back onto its ruler)
+    pub fn is_over_ruler(axis: GuideAxis, point: Point2<Glass>) -> bool {
+        match axis {
+            GuideAxis::Vertical => point.x < RULER_SIZE,
+            GuideAxis::Horizontal => point.y < RULER_SIZE,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "pax_engine::serde", default)]
+pub struct GridSettings {
+    pub enabled: bool,
+    /// Distance between major grid lines, in world units
+    pub spacing: f64,
+    /// Number of cells each major grid cell is divided into
+    pub subdivisions: u32,
+}
+
+impl Default for GridSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            spacing: 100.0,
+            subdivisions: 4,
+        }
+    }
+}
+
+pub struct GridLine {
+    /// Glass coordinate of the line
+    pub position: f64,
+    pub major: bool,
+}
+
+impl GridSettings {
+    pub fn line_color(major: bool) -> Color {
+        let alpha = if major { 60 } else { 25 };
+        Color::rgba(90.into(), 130.into(), 255.into(), alpha.into())
+    }
+
+    pub fn snap_color() -> Color {
+        Color::rgba(90.into(), 130.into(), 255.into(), 180.into())
+    }
+
+    /// Returns the vertical and horizontal grid lines that cross the visible
+    /// glass region. Subdivisions (and at very low zoom levels the entire grid)
+    /// are left out when the lines would be too dense to be useful.
+    pub fn visible_lines(
+        &self,
+        glass_to_world: Transform2<Glass, World>,
+        glass_bounds: (f64, f64),
+    ) -> (Vec<GridLine>, Vec<GridLine>) {
+        if !self.enabled || self.spacing <= 0.0 {
+            return Default::default();
+        }
+        let glass_per_world = 1.0 / glass_to_world.get_scale().x;
+        let subdivisions = self.subdivisions.max(1);
+        let minor_spacing = self.spacing / subdivisions as f64;
+        let (step, lines_per_major) = if minor_spacing * glass_per_world >= MIN_GRID_LINE_DISTANCE {
+            (minor_spacing, subdivisions as i64)
+        } else if self.spacing * glass_per_world >= MIN_GRID_LINE_DISTANCE {
+            (self.spacing, 1)
+        } else {
+            return Default::default();
+        };
+
+        let world_to_glass = glass_to_world.inverse();
+        let top_left = glass_to_world * Point2::<Glass>::new(0.0, 0.0);
+        let bottom_right = glass_to_world * Point2::<Glass>::new(glass_bounds.0, glass_bounds.1);
+        let vertical = axis_lines(top_left.x, bottom_right.x, step, lines_per_major, |x| {
+            (world_to_glass * Point2::new(x, 0.0)).x
+        });
+        let horizontal = axis_lines(top_left.y, bottom_right.y, step, lines_per_major, |y| {
+            (world_to_glass * Point2::new(0.0, y)).y
+        });
+        (vertical, horizontal)
+    }
+}
+
+fn axis_lines(
+    start: f64,
+    end: f64,
+    step: f64,
+    lines_per_major: i64,
+    to_glass: impl Fn(f64) -> f64,
+) -> Vec<GridLine> {
+    let first = (start / step).ceil() as i64;
+    let last = (end / step).floor() as i64;
+    (first..=last)
+        .map(|i| GridLine {
+            position: to_glass(i as f64 * step),
+            major: i.rem_euclid(lines_per_major) == 0,
+        })
+        .collect()
+}
+
+/// Replaces the workspace state with the state persisted for the project,
+/// once it has been received from the design server.
+pub fn load_persisted_workspace_state(ctx: &NodeContext) {
+    let Some(state) = borrow_mut!(ctx.designtime).take_loaded_workspace_state() else {
+        return;
+    };
+    match serde_json::from_str::<WorkspaceState>(&state) {
+        Ok(state) => super::read_app_state(|app_state| app_state.workspace.set(state)),
+        Err(e) => log::warn!("couldn't parse persisted workspace state: {e}"),
+    }
+}
+
+pub struct SaveWorkspaceState;
+
+impl Action for SaveWorkspaceState {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let state = serde_json::to_string(&ctx.app_state.workspace.get())
+            .map_err(|e| anyhow!("couldn't serialize workspace state: {e}"))?;
+        borrow!(ctx.engine_context.designtime).save_workspace_state(state)
+    }
+}
+
+pub struct SetGuidePosition {
+    pub index: usize,
+    pub position: f64,
+}
+
+impl Action for SetGuidePosition {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        let component = ctx.app_state.selected_component_id.get();
+        let mut workspace = ctx.app_state.workspace.get();
+        let guide = workspace
+            .guides_mut(&component)
+            .get_mut(self.index)
+            .ok_or_else(|| anyhow!("no guide with index {}", self.index))?;
+        guide.position = self.position;
+        ctx.app_state.workspace.set(workspace);
+        SaveWorkspaceState.perform(ctx)
+    }
+}
+
+pub struct ToggleRulers;
+
+impl Action for ToggleRulers {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        ctx.app_state
+            .workspace
+            .update(|workspace| workspace.show_rulers = !workspace.show_rulers);
+        SaveWorkspaceState.perform(ctx)
+    }
+}
+
+pub struct ToggleGrid;
+
+impl Action for ToggleGrid {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        ctx.app_state
+            .workspace
+            .update(|workspace| workspace.grid.enabled = !workspace.grid.enabled);
+        SaveWorkspaceState.perform(ctx)
+    }
+}
+
+pub struct SetGridSettings(pub GridSettings);
+
+impl Action for SetGridSettings {
+    fn perform(&self, ctx: &mut ActionContext) -> Result<()> {
+        if !(self.0.spacing > 0.0) || self.0.subdivisions == 0 {
+            return Err(anyhow!(
+                "grid spacing and subdivisions must be positive, got {:?}",
+                self.0
+            ));
+        }
+        ctx.app_state
+            .workspace
+            .update(|workspace| workspace.grid = self.0);
+        SaveWorkspaceState.perform(ctx)
+    }
+}
@@ -0,0 +1,56 @@
+//! Checks the designer's own templates with the validation pass builds run over userland
+//! templates, so that drift between e.g. `glass/mod.pax` and `Glass` fails here instead of in the
+//! build of a project with the designer enabled.  Needs the `parser` feature:
+//!
+//! `cargo test -p pax-designer --features parser --test manifest_validation`
+
+use std::sync::OnceLock;
+
+use pax_compiler::{validate_settings, LintSeverity};
+use pax_designer::PaxDesigner;
+use pax_engine::pax_manifest::parsing::parse_manifest;
+use pax_engine::pax_manifest::validation::{self, UNRESOLVED_TYPE};
+use pax_engine::pax_manifest::{PaxManifest, TypeId};
+
+/// The designer's manifest, parsed once for every test
+fn designer_manifest() -> &'static PaxManifest {
+    static MANIFEST: OnceLock<PaxManifest> = OnceLock::new();
+    MANIFEST.get_or_init(|| {
+        pax_engine::api::Functions::register_all_functions();
+        parse_manifest::<PaxDesigner>(
+            TypeId::build_singleton("pax_designer::PaxDesigner", Some("PaxDesigner")),
+            "pax_engine",
+        )
+    })
+}
+
+#[test]
+fn designer_settings_are_valid() {
+    let errors: Vec<_> = validate_settings(designer_manifest())
+        .into_iter()
+        .filter(|d| d.severity == LintSeverity::Error)
+        .map(|d| d.to_string())
+        .collect();
+    assert!(
+        errors.is_empty(),
+        "Invalid settings in the designer's templates:\n{}",
+        errors.join("\n")
+    );
+}
+
+#[test]
+fn designer_node_types_resolve() {
+    let manifest = designer_manifest();
+    let unresolved: Vec<_> = manifest
+        .components
+        .values()
+        .flat_map(|component| validation::validate_component(manifest, component))
+        .filter(|issue| issue.rule == UNRESOLVED_TYPE)
+        .map(|issue| issue.message)
+        .collect();
+    assert!(
+        unresolved.is_empty(),
+        "Unresolved types in the designer's templates:\n{}",
+        unresolved.join("\n")
+    );
+}
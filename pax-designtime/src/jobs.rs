@@ -0,0 +1,341 @@
+//! # Jobs
+//!
+//! Background work of the `DesigntimeManager`, kept off the UI tick so that e.g. serializing a
+//! large component doesn't make the designer hitch. A job is submitted under a key, and a newer
+//! job with the same key cancels the one in flight, e.g. a newer serialization of a component
+//! supersedes an older one. On native targets every job runs on its own thread; on wasm, where
+//! threads aren't available, jobs run cooperatively on the UI thread, one step at a time, for at
+//! most the queue's budget per tick. Either way results are only delivered by `JobQueue::service`,
+//! which the manager calls from `handle_recv`.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Time cooperative jobs may run for per tick, unless configured with `JobQueue::set_budget`
+pub const DEFAULT_TICK_BUDGET: Duration = Duration::from_millis(4);
+
+/// Number of ticks `JobQueueProfile` keeps the service time of
+pub const PROFILE_TICKS: usize = 120;
+
+/// Time `JobQueue::finish_all` waits for a job running on a thread before giving up on it
+const FINISH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Outcome of running one step of a job
+pub enum JobStep<T> {
+    /// The job has more work to do, and is run again later
+    Yield,
+    Done(T),
+}
+
+/// A job, run one step at a time until it returns `JobStep::Done`. Jobs that do their work in one
+/// go can be submitted with `JobQueue::submit_once` instead.
+pub type Job<T> = Box<dyn FnMut() -> JobStep<T> + Send>;
+
+struct InFlight {
+    id: u64,
+    cancelled: Arc<AtomicBool>,
+}
+
+struct CooperativeJob<T> {
+    key: String,
+    id: u64,
+    cancelled: Arc<AtomicBool>,
+    job: Job<T>,
+}
+
+struct Finished<T> {
+    key: String,
+    id: u64,
+    result: T,
+}
+
+/// Time spent servicing the queue on recent ticks, including running cooperative jobs
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct JobQueueProfile {
+    /// Milliseconds per tick, oldest first, at most `PROFILE_TICKS` of them
+    pub tick_ms: VecDeque<f64>,
+}
+
+impl JobQueueProfile {
+    fn record(&mut self, ms: f64) {
+        if self.tick_ms.len() == PROFILE_TICKS {
+            self.tick_ms.pop_front();
+        }
+        self.tick_ms.push_back(ms);
+    }
+
+    pub fn last_ms(&self) -> f64 {
+        self.tick_ms.back().copied().unwrap_or(0.0)
+    }
+
+    pub fn max_ms(&self) -> f64 {
+        self.tick_ms.iter().copied().fold(0.0, f64::max)
+    }
+}
+
+pub struct JobQueue<T> {
+    threaded: bool,
+    budget: Duration,
+    next_id: u64,
+    /// Most recently submitted job per key, until its result is delivered
+    in_flight: HashMap<String, InFlight>,
+    /// Jobs run on the UI thread, in the order they're stepped in
+    cooperative: VecDeque<CooperativeJob<T>>,
+    sender: Sender<Finished<T>>,
+    receiver: Receiver<Finished<T>>,
+    profile: JobQueueProfile,
+}
+
+impl<T: Send + 'static> Default for JobQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Send + 'static> JobQueue<T> {
+    /// A queue running jobs on threads where available, and cooperatively on wasm
+    pub fn new() -> Self {
+        Self::with_threads(cfg!(not(target_arch = "wasm32")))
+    }
+
+    /// A queue running every job cooperatively on the thread calling `service`
+    pub fn cooperative() -> Self {
+        Self::with_threads(false)
+    }
+
+    fn with_threads(threaded: bool) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        JobQueue {
+            threaded,
+            budget: DEFAULT_TICK_BUDGET,
+            next_id: 0,
+            in_flight: HashMap::new(),
+            cooperative: VecDeque::new(),
+            sender,
+            receiver,
+            profile: JobQueueProfile::default(),
+        }
+    }
+
+    /// Sets the time cooperative jobs may run for per tick. At least one step runs per tick
+    /// regardless, so that jobs make progress.
+    pub fn set_budget(&mut self, budget: Duration) {
+        self.budget = budget;
+    }
+
+    /// Submits `job` under `key`, cancelling the job in flight under the same key if any
+    pub fn submit(&mut self, key: impl Into<String>, mut job: Job<T>) {
+        let key = key.into();
+        self.cancel(&key);
+        let id = self.next_id;
+        self.next_id += 1;
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.in_flight.insert(
+            key.clone(),
+            InFlight {
+                id,
+                cancelled: Arc::clone(&cancelled),
+            },
+        );
+
+        if self.threaded {
+            let sender = self.sender.clone();
+            std::thread::spawn(move || {
+                while !cancelled.load(Ordering::Relaxed) {
+                    if let JobStep::Done(result) = job() {
+                        // the queue may have been dropped in the meantime
+                        let _ = sender.send(Finished { key, id, result });
+                        return;
+                    }
+                }
+            });
+        } else {
+            self.cooperative.push_back(CooperativeJob {
+                key,
+                id,
+                cancelled,
+                job,
+            });
+        }
+    }
+
+    /// Submits a job doing its work in one go, see `submit`
+    pub fn submit_once(
+        &mut self,
+        key: impl Into<String>,
+        work: impl FnOnce() -> T + Send + 'static,
+    ) {
+        let mut work = Some(work);
+        self.submit(
+            key,
+            Box::new(move || match work.take() {
+                Some(work) => JobStep::Done(work()),
+                None => JobStep::Yield,
+            }),
+        );
+    }
+
+    /// Cancels the job in flight under `key`, whose result is then never delivered
+    pub fn cancel(&mut self, key: &str) {
+        if let Some(in_flight) = self.in_flight.remove(key) {
+            in_flight.cancelled.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub fn is_in_flight(&self, key: &str) -> bool {
+        self.in_flight.contains_key(key)
+    }
+
+    pub fn in_flight_count(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    pub fn get_profile(&self) -> &JobQueueProfile {
+        &self.profile
+    }
+
+    /// Runs cooperative jobs for up to the tick budget, and returns the results of the jobs that
+    /// finished since the last call, with their keys, in the order they finished
+    pub fn service(&mut self) -> Vec<(String, T)> {
+        let start = now_ms();
+        let budget_ms = self.budget.as_secs_f64() * 1000.0;
+        while let Some(mut job) = self.cooperative.pop_front() {
+            if !job.cancelled.load(Ordering::Relaxed) {
+                match (job.job)() {
+                    JobStep::Done(result) => self.finish(job.key, job.id, result),
+                    // round robin, so that a long job doesn't starve the others
+                    JobStep::Yield => self.cooperative.push_back(job),
+                }
+            }
+            if now_ms() - start >= budget_ms {
+                break;
+            }
+        }
+        let results = self.take_finished();
+        self.profile.record(now_ms() - start);
+        results
+    }
+
+    /// Runs every job to completion, blocking until all of them finished, and returns their
+    /// results like `service`
+    pub fn finish_all(&mut self) -> Vec<(String, T)> {
+        while let Some(mut job) = self.cooperative.pop_front() {
+            while !job.cancelled.load(Ordering::Relaxed) {
+                if let JobStep::Done(result) = (job.job)() {
+                    self.finish(job.key, job.id, result);
+                    break;
+                }
+            }
+        }
+        let mut results = self.take_finished();
+        while !self.in_flight.is_empty() {
+            let Ok(finished) = self.receiver.recv_timeout(FINISH_TIMEOUT) else {
+                log::warn!(
+                    "gave up waiting for {} background jobs",
+                    self.in_flight.len()
+                );
+                break;
+            };
+            results.extend(self.deliver(finished));
+        }
+        results
+    }
+
+    fn finish(&mut self, key: String, id: u64, result: T) {
+        // never fails, as the queue holds the receiver
+        let _ = self.sender.send(Finished { key, id, result });
+    }
+
+    fn take_finished(&mut self) -> Vec<(String, T)> {
+        let mut results = vec![];
+        while let Ok(finished) = self.receiver.try_recv() {
+            results.extend(self.deliver(finished));
+        }
+        results
+    }
+
+    /// The result of `finished`, unless its job was cancelled or superseded
+    fn deliver(&mut self, finished: Finished<T>) -> Option<(String, T)> {
+        let current = self
+            .in_flight
+            .get(&finished.key)
+            .is_some_and(|in_flight| in_flight.id == finished.id);
+        if !current {
+            return None;
+        }
+        self.in_flight.remove(&finished.key);
+        Some((finished.key, finished.result))
+    }
+}
+
+/// Milliseconds since some fixed point in time
+pub(crate) fn now_ms() -> f64 {
+    #[cfg(target_arch = "wasm32")]
+    return crate::manifest_loading::now_ms();
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use std::sync::OnceLock;
+        use std::time::Instant;
+        static START: OnceLock<Instant> = OnceLock::new();
+        START.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A job counting down from `steps`, yielding in between
+    fn countdown(mut steps: usize, result: &'static str) -> Job<&'static str> {
+        Box::new(move || {
+            if steps == 0 {
+                JobStep::Done(result)
+            } else {
+                steps -= 1;
+                JobStep::Yield
+            }
+        })
+    }
+
+    #[test]
+    fn test_cooperative_jobs_resume_across_ticks() {
+        let mut queue = JobQueue::cooperative();
+        queue.set_budget(Duration::ZERO);
+        queue.submit("a", countdown(2, "a"));
+        queue.submit("b", countdown(0, "b"));
+
+        // one step per tick with no budget, round robin
+        assert!(queue.service().is_empty());
+        assert_eq!(queue.service(), vec![("b".to_string(), "b")]);
+        assert!(queue.service().is_empty());
+        assert_eq!(queue.service(), vec![("a".to_string(), "a")]);
+        assert_eq!(queue.in_flight_count(), 0);
+        assert_eq!(queue.get_profile().tick_ms.len(), 4);
+    }
+
+    #[test]
+    fn test_newer_job_cancels_older_with_same_key() {
+        for mut queue in [JobQueue::cooperative(), JobQueue::new()] {
+            queue.submit_once("component", || "old");
+            queue.submit_once("component", || "new");
+            queue.submit_once("other", || "other");
+            queue.cancel("other");
+            assert_eq!(queue.finish_all(), vec![("component".to_string(), "new")]);
+            assert!(!queue.is_in_flight("component"));
+        }
+    }
+
+    #[test]
+    fn test_threaded_results_are_delivered_by_service() {
+        let mut queue = JobQueue::new();
+        queue.submit("job", countdown(100, "done"));
+        let mut results = vec![];
+        while results.is_empty() {
+            results = queue.service();
+        }
+        assert_eq!(results, vec![("job".to_string(), "done")]);
+    }
+}
@@ -1,22 +1,38 @@
 use std::any::Any;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::net::{Ipv4Addr, SocketAddr};
 use std::rc::Rc;
+use std::time::Duration;
 
+pub mod jobs;
+pub mod manifest_loading;
 pub mod orm;
 pub mod privileged_agent;
 
 pub mod messages;
+pub mod node_costs;
+pub mod property_recorder;
 pub mod serde_pax;
-
+pub mod snippet;
+pub mod thumbnails;
+
+use jobs::{JobQueue, JobQueueProfile};
+use manifest_loading::{ManifestLoadState, DEFAULT_MANIFEST_LOAD_TIMEOUT, RECONNECT_INTERVAL};
+use messages::NodeBounds;
+use node_costs::{HotNode, NodeCost, NodeCosts, HOT_NODES_PER_COMPONENT};
+use orm::diff::diff_components;
 use orm::ReloadType;
 use pax_manifest::pax_runtime_api::Property;
-use privileged_agent::PrivilegedAgentConnection;
+use privileged_agent::{serialize_component_update, PrivilegedAgentConnection};
+use property_recorder::{NodeRecording, PropertyRecordings, RecordedFrame};
+use snippet::{SnippetArchive, SnippetInbox};
+use thumbnails::{ComponentThumbnail, ThumbnailRequest};
 
 use core::fmt::Debug;
 
 pub use pax_manifest;
+use pax_manifest::validation::{validate_component, TemplateIssue};
 use pax_manifest::{
     server::*, ComponentDefinition, PaxManifest, TypeId, UniqueTemplateNodeIdentifier,
 };
@@ -33,13 +49,63 @@ pub struct DesigntimeManager {
     orm: PaxManifestORM,
     factories: Factories,
     priv_agent_connection: Rc<RefCell<PrivilegedAgentConnection>>,
+    priv_addr: SocketAddr,
+    manifest_load_timeout: Duration,
+    /// When the current attempt at loading the manifest started, see `manifest_loading::now_ms`
+    manifest_load_started_ms: f64,
+    last_reconnect_ms: f64,
     #[allow(unused)]
     last_written_manifest_version: usize,
     project_query: Option<String>,
     response_queue: Rc<RefCell<Vec<DesigntimeResponseMessage>>>,
+    /// Nodes whose layout has been requested but not yet read back from the engine
+    pending_bounds_requests: Vec<UniqueTemplateNodeIdentifier>,
+    /// Most recently read back layout per node, one entry per expanded instance
+    node_bounds_cache: HashMap<UniqueTemplateNodeIdentifier, Vec<NodeBounds>>,
+    /// Workspace state loaded from the design server, not yet picked up by the designer
+    loaded_workspace_state: Option<String>,
+    /// Keymap loaded from the design server, not yet picked up by the designer.
+    /// `Some(None)` if the server responded, but the user hasn't saved a keymap
+    loaded_keymap: Option<Option<String>>,
+    /// Snippet messages received from the design server, not yet picked up by the designer
+    snippet_inbox: SnippetInbox,
+    /// Bug report messages received from the design server, not yet picked up by the designer
+    bug_report_messages: Vec<String>,
+    /// Components whose thumbnail has been requested but not yet rendered by the chassis
+    pending_thumbnail_requests: Vec<ThumbnailRequest>,
+    /// Most recently rendered thumbnail per component, see `thumbnails`
+    thumbnail_cache: HashMap<TypeId, ComponentThumbnail>,
+    /// Incremented whenever a thumbnail is added to the cache
+    thumbnails_version: Property<usize>,
+    /// Manifest version at which the content hash of each component with a requested thumbnail
+    /// was last computed
+    thumbnail_hash_versions: HashMap<TypeId, usize>,
+    /// Background work, serviced in `handle_recv`, see `jobs`
+    jobs: JobQueue<JobOutput>,
+    /// Whether the engine samples the cost of each node, see `node_costs`
+    node_cost_profiling: bool,
+    node_costs: NodeCosts,
+    /// Incremented whenever a new sample of node costs is reported
+    node_costs_version: Property<usize>,
+    /// Recent property values of the nodes being recorded, see `property_recorder`
+    property_recordings: PropertyRecordings,
+    /// Incremented whenever a frame is recorded or the recorded nodes change
+    property_recordings_version: Property<usize>,
     pub publish_state: Property<Option<PublishResponse>>,
 }
 
+/// Results of the jobs run by the `DesigntimeManager`
+enum JobOutput {
+    /// Component update serialized with `serialize_component_update`, ready to be sent, and the
+    /// component it saves
+    ComponentUpdate {
+        component: ComponentDefinition,
+        msg_bytes: anyhow::Result<Vec<u8>>,
+    },
+    /// Content hash of a component with a requested thumbnail, see `request_component_thumbnail`
+    ThumbnailHash { type_id: TypeId, content_hash: u64 },
+}
+
 pub enum DesigntimeResponseMessage {
     LLMResponse(ComponentDefinition),
     PublishResponse(PublishResponse),
@@ -75,9 +141,29 @@ impl DesigntimeManager {
             orm,
             factories,
             priv_agent_connection: priv_agent,
+            priv_addr,
+            manifest_load_timeout: DEFAULT_MANIFEST_LOAD_TIMEOUT,
+            manifest_load_started_ms: manifest_loading::now_ms(),
+            last_reconnect_ms: manifest_loading::now_ms(),
             last_written_manifest_version: 0,
             project_query: None,
             response_queue: Rc::new(RefCell::new(Vec::new())),
+            pending_bounds_requests: Vec::new(),
+            node_bounds_cache: HashMap::new(),
+            loaded_workspace_state: None,
+            loaded_keymap: None,
+            snippet_inbox: SnippetInbox::default(),
+            bug_report_messages: Vec::new(),
+            pending_thumbnail_requests: Vec::new(),
+            thumbnail_cache: HashMap::new(),
+            thumbnails_version: Property::new(0),
+            thumbnail_hash_versions: HashMap::new(),
+            jobs: JobQueue::new(),
+            node_cost_profiling: false,
+            node_costs: NodeCosts::default(),
+            node_costs_version: Property::new(0),
+            property_recordings: PropertyRecordings::default(),
+            property_recordings_version: Property::new(0),
             publish_state: Default::default(),
         }
     }
@@ -97,22 +183,262 @@ impl DesigntimeManager {
         Ok(())
     }
 
-    pub fn get_manifest_loaded_from_server_prop(&self) -> Property<bool> {
-        self.orm.manifest_loaded_from_server.clone()
+    /// Persists the designer workspace state for the project through the design server.
+    pub fn save_workspace_state(&self, state: String) -> anyhow::Result<()> {
+        self.priv_agent_connection
+            .borrow_mut()
+            .send_workspace_state(state)?;
+        Ok(())
+    }
+
+    /// Returns the workspace state most recently loaded from the design server, if it hasn't
+    /// already been taken.
+    pub fn take_loaded_workspace_state(&mut self) -> Option<String> {
+        self.loaded_workspace_state.take()
     }
 
-    pub fn send_component_update(&mut self, type_id: &TypeId) -> anyhow::Result<()> {
-        let component = self.orm.get_component(type_id)?;
+    /// Persists the designer keymap of the user through the design server.
+    pub fn save_keymap(&self, keymap: String) -> anyhow::Result<()> {
         self.priv_agent_connection
             .borrow_mut()
-            .send_component_update(component)?;
+            .send_keymap(keymap)?;
+        Ok(())
+    }
 
-        for c in self.orm.get_new_components() {
-            self.priv_agent_connection
-                .borrow_mut()
-                .send_component_update(&c)?;
+    /// Returns the response to the keymap load request once it has been received from the
+    /// design server (containing `None` if the user has no saved keymap), if it hasn't already
+    /// been taken.
+    pub fn take_loaded_keymap(&mut self) -> Option<Option<String>> {
+        self.loaded_keymap.take()
+    }
+
+    /// Saves `archive` to the user's snippet library through the design server, which reports
+    /// the outcome through `take_snippet_inbox`.
+    pub fn export_snippet(&self, archive: &SnippetArchive) -> anyhow::Result<()> {
+        self.priv_agent_connection
+            .borrow_mut()
+            .send_snippet_export(archive.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Requests the names of the snippets in the user's snippet library,
+    /// which become available through `take_snippet_inbox`.
+    pub fn request_snippet_library(&self) -> anyhow::Result<()> {
+        self.priv_agent_connection
+            .borrow_mut()
+            .send_snippet_list_request()?;
+        Ok(())
+    }
+
+    /// Requests the snippet `name` from the user's snippet library to be imported,
+    /// which becomes available through `take_snippet_inbox`.
+    pub fn load_snippet(&self, name: &str) -> anyhow::Result<()> {
+        self.priv_agent_connection
+            .borrow_mut()
+            .send_snippet_load_request(name.to_string())?;
+        Ok(())
+    }
+
+    /// Returns the snippet messages received from the design server since the last call.
+    pub fn take_snippet_inbox(&mut self) -> SnippetInbox {
+        std::mem::take(&mut self.snippet_inbox)
+    }
+
+    /// Asks the design server to save a bug report with the session being recorded (see
+    /// `PaxManifestORM::start_recording`), the current manifest and `environment` (e.g. the
+    /// designer version) to the project.  Where it was saved becomes available through
+    /// `take_bug_report_messages`.
+    pub fn export_bug_report(
+        &mut self,
+        mut environment: BTreeMap<String, String>,
+    ) -> anyhow::Result<()> {
+        let session = self
+            .orm
+            .get_session_recording()
+            .ok_or_else(|| anyhow::anyhow!("session isn't being recorded"))?;
+        let manifest = self.orm.get_manifest();
+        let manifest = if session.redacted {
+            orm::session::redact(manifest)
+        } else {
+            manifest.clone()
+        };
+        environment.insert(
+            "pax_designtime_version".to_string(),
+            env!("CARGO_PKG_VERSION").to_string(),
+        );
+        environment.insert(
+            "target".to_string(),
+            format!("{}-{}", std::env::consts::ARCH, std::env::consts::OS),
+        );
+        let report = orm::session::BugReport {
+            format_version: orm::session::SESSION_FORMAT_VERSION,
+            session,
+            manifest,
+            environment,
+        };
+        self.priv_agent_connection
+            .borrow_mut()
+            .send_bug_report(serde_json::to_string(&report)?)?;
+        Ok(())
+    }
+
+    /// Returns the bug report messages received from the design server since the last call.
+    pub fn take_bug_report_messages(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.bug_report_messages)
+    }
+
+    /// Sends the session events recorded since the last call to the session log of the design
+    /// server, keeping them for later while disconnected
+    fn flush_session_log(&mut self) -> anyhow::Result<()> {
+        if !self.priv_agent_connection.borrow().alive {
+            return Ok(());
+        }
+        let Some(entries) = self.orm.take_session_log() else {
+            return Ok(());
+        };
+        let header = entries
+            .header
+            .map(|header| serde_json::to_string(&header))
+            .transpose()?;
+        let events = entries
+            .events
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<serde_json::Result<_>>()?;
+        self.priv_agent_connection
+            .borrow_mut()
+            .send_session_log(header, events)?;
+        Ok(())
+    }
+
+    pub fn get_manifest_load_state_prop(&self) -> Property<ManifestLoadState> {
+        self.orm.manifest_load_state.clone()
+    }
+
+    /// Sets how long to wait for the manifest from the design server before
+    /// reporting the load as failed, `DEFAULT_MANIFEST_LOAD_TIMEOUT` by default.
+    pub fn set_manifest_load_timeout(&mut self, timeout: Duration) {
+        self.manifest_load_timeout = timeout;
+    }
+
+    /// Reconnects to the design server and starts loading the manifest over,
+    /// e.g. after loading failed.
+    pub fn retry_manifest_load(&mut self) {
+        self.orm
+            .manifest_load_state
+            .set(ManifestLoadState::Connecting);
+        self.manifest_load_started_ms = manifest_loading::now_ms();
+        self.reconnect();
+    }
+
+    /// Reconnects if the connection dropped while the manifest was loading (e.g. the design
+    /// server restarted), and gives up once the manifest hasn't arrived within the timeout.
+    fn check_manifest_load(&mut self) {
+        let state = self.orm.manifest_load_state.get();
+        if state.is_complete() || state.is_failed() {
+            return;
+        }
+        let now = manifest_loading::now_ms();
+        if now - self.manifest_load_started_ms > self.manifest_load_timeout.as_secs_f64() * 1000.0 {
+            let error = self
+                .priv_agent_connection
+                .borrow()
+                .last_error
+                .clone()
+                .unwrap_or_else(|| {
+                    format!(
+                        "no response from the design server at {} after {}s",
+                        self.priv_addr,
+                        self.manifest_load_timeout.as_secs()
+                    )
+                });
+            self.orm
+                .manifest_load_state
+                .set(ManifestLoadState::Failed(error));
+            return;
+        }
+        let alive = self.priv_agent_connection.borrow().alive;
+        if !alive && now - self.last_reconnect_ms > RECONNECT_INTERVAL.as_secs_f64() * 1000.0 {
+            self.reconnect();
         }
+    }
+
+    fn reconnect(&mut self) {
+        self.last_reconnect_ms = manifest_loading::now_ms();
+        let mut connection = self.priv_agent_connection.borrow_mut();
+        match PrivilegedAgentConnection::new(self.priv_addr) {
+            Ok(mut new_connection) => {
+                new_connection.last_error = connection.last_error.take();
+                *connection = new_connection;
+            }
+            Err(e) => connection.last_error = Some(e.to_string()),
+        }
+    }
 
+    /// Checks component `type_id`, and any newly created components sent along with it, for
+    /// the errors the rebuild triggered by sending them would fail on: nodes of types that
+    /// aren't components, and settings of properties that don't exist or with literal values
+    /// of the wrong type.  Returns the errors, empty if the components can be sent.
+    pub fn validate_before_send(&self, type_id: &TypeId) -> anyhow::Result<Vec<TemplateIssue>> {
+        let manifest = self.orm.get_manifest();
+        let mut components = vec![self.orm.get_component(type_id)?];
+        components.extend(
+            self.orm
+                .peek_new_components()
+                .into_iter()
+                .filter(|component| &component.type_id != type_id),
+        );
+        Ok(components
+            .into_iter()
+            .flat_map(|component| validate_component(manifest, component))
+            .filter(|issue| issue.is_error)
+            .collect())
+    }
+
+    /// Sends component `type_id`, and any newly created components, to the design server to be
+    /// written back to their files.  Components are serialized in the background and sent from
+    /// `handle_recv`, a newer update of a component cancelling one still being serialized.
+    /// Nothing is sent if [`Self::validate_before_send`] finds errors, which are returned instead.
+    pub fn send_component_update(&mut self, type_id: &TypeId) -> anyhow::Result<()> {
+        if !self.priv_agent_connection.borrow().alive {
+            return Err(anyhow::anyhow!(
+                "couldn't send component update: connection to design-server was lost"
+            ));
+        }
+        let errors = self.validate_before_send(type_id)?;
+        if !errors.is_empty() {
+            let errors: Vec<_> = errors.into_iter().map(|issue| issue.message).collect();
+            return Err(anyhow::anyhow!(
+                "didn't save `{}`: {}",
+                type_id
+                    .get_pascal_identifier()
+                    .unwrap_or_else(|| type_id.to_string()),
+                errors.join("; ")
+            ));
+        }
+        let mut components = vec![self.orm.get_component(type_id)?.clone()];
+        components.extend(self.orm.get_new_components());
+        for component in components {
+            // the baseline only moves once an update was sent, so that an update superseding
+            // one still in flight lists the changes of both
+            let saved = self.orm.get_saved_component(&component.type_id).cloned();
+            self.jobs.submit_once(
+                format!("component-update:{}", component.type_id),
+                move || {
+                    let saved = saved.unwrap_or_else(|| ComponentDefinition {
+                        template: None,
+                        settings: None,
+                        ..component.clone()
+                    });
+                    let changes = diff_components(&saved, &component).describe();
+                    let msg_bytes = serialize_component_update(&component, changes);
+                    JobOutput::ComponentUpdate {
+                        component,
+                        msg_bytes,
+                    }
+                },
+            );
+        }
         Ok(())
     }
 
@@ -245,9 +571,16 @@ impl DesigntimeManager {
         if current_manifest_version != self.last_written_manifest_version {
             self.last_written_manifest_version = current_manifest_version;
         }
-        self.priv_agent_connection
-            .borrow_mut()
-            .handle_recv(&mut self.orm)?;
+        self.priv_agent_connection.borrow_mut().handle_recv(
+            &mut self.orm,
+            &mut self.pending_bounds_requests,
+            &mut self.loaded_workspace_state,
+            &mut self.loaded_keymap,
+            &mut self.snippet_inbox,
+            &mut self.bug_report_messages,
+        )?;
+        self.check_manifest_load();
+        self.flush_session_log()?;
 
         let response_queue = {
             let mut queue = self.response_queue.borrow_mut();
@@ -256,9 +589,241 @@ impl DesigntimeManager {
         for response in response_queue {
             self.handle_response(response);
         }
+
+        for (_, output) in self.jobs.service() {
+            self.handle_job_output(output);
+        }
         Ok(())
     }
 
+    /// Runs all background jobs to completion, blocking until they're done, and handles their
+    /// results like `handle_recv` does, e.g. to make sure component updates have been sent
+    pub fn finish_jobs(&mut self) {
+        for (_, output) in self.jobs.finish_all() {
+            self.handle_job_output(output);
+        }
+    }
+
+    /// Time spent on background jobs on recent ticks
+    pub fn get_job_queue_profile(&self) -> &JobQueueProfile {
+        self.jobs.get_profile()
+    }
+
+    /// Sets the time background jobs may take up per tick where they share the UI thread
+    pub fn set_job_tick_budget(&mut self, budget: Duration) {
+        self.jobs.set_budget(budget);
+    }
+
+    fn handle_job_output(&mut self, output: JobOutput) {
+        match output {
+            JobOutput::ComponentUpdate {
+                component,
+                msg_bytes,
+            } => {
+                let sent = msg_bytes.and_then(|msg_bytes| {
+                    self.priv_agent_connection
+                        .borrow_mut()
+                        .send_component_update(msg_bytes)
+                });
+                match sent {
+                    Ok(()) => self.orm.mark_saved(component),
+                    Err(e) => log::error!("couldn't send component update: {e}"),
+                }
+            }
+            JobOutput::ThumbnailHash {
+                type_id,
+                content_hash,
+            } => {
+                let up_to_date = self
+                    .thumbnail_cache
+                    .get(&type_id)
+                    .is_some_and(|thumbnail| thumbnail.content_hash == content_hash);
+                let request = ThumbnailRequest {
+                    type_id,
+                    content_hash,
+                };
+                if !up_to_date && !self.pending_thumbnail_requests.contains(&request) {
+                    self.pending_thumbnail_requests.push(request);
+                }
+            }
+        }
+    }
+
+    /// Queues a layout readback for `uni`, to be served by the chassis on its next tick.
+    /// The result becomes available through `get_cached_node_bounds`.
+    pub fn request_node_bounds(&mut self, uni: UniqueTemplateNodeIdentifier) {
+        if !self.pending_bounds_requests.contains(&uni) {
+            self.pending_bounds_requests.push(uni);
+        }
+    }
+
+    pub fn take_pending_bounds_requests(&mut self) -> Vec<UniqueTemplateNodeIdentifier> {
+        std::mem::take(&mut self.pending_bounds_requests)
+    }
+
+    /// Caches the layout read back from the engine for `uni` and forwards it to the design server.
+    pub fn set_node_bounds(
+        &mut self,
+        uni: UniqueTemplateNodeIdentifier,
+        bounds: Vec<NodeBounds>,
+    ) -> anyhow::Result<()> {
+        self.node_bounds_cache.insert(uni.clone(), bounds.clone());
+        self.priv_agent_connection
+            .borrow_mut()
+            .send_node_bounds_response(uni, bounds)
+    }
+
+    /// Returns the cached thumbnail of component `type_id`, and if it's missing or was rendered
+    /// from an older definition of the component, queues a new render to be served by the chassis
+    /// once the component's content hash has been computed in the background.
+    /// `get_thumbnails_version` changes once it's ready.
+    pub fn request_component_thumbnail(&mut self, type_id: &TypeId) -> Option<&ComponentThumbnail> {
+        let manifest_version = self.orm.get_manifest_version().get();
+        if self.thumbnail_hash_versions.get(type_id) != Some(&manifest_version) {
+            let component = self.orm.get_component(type_id).ok()?.clone();
+            self.thumbnail_hash_versions
+                .insert(type_id.clone(), manifest_version);
+            self.jobs
+                .submit_once(format!("thumbnail-hash:{}", type_id), move || {
+                    JobOutput::ThumbnailHash {
+                        content_hash: thumbnails::component_content_hash(&component),
+                        type_id: component.type_id,
+                    }
+                });
+        }
+        // a stale thumbnail is still better than none until the new one is rendered
+        self.thumbnail_cache.get(type_id)
+    }
+
+    pub fn take_pending_thumbnail_requests(&mut self) -> Vec<ThumbnailRequest> {
+        std::mem::take(&mut self.pending_thumbnail_requests)
+    }
+
+    pub fn set_component_thumbnail(&mut self, type_id: TypeId, thumbnail: ComponentThumbnail) {
+        self.thumbnail_cache.insert(type_id, thumbnail);
+        self.thumbnails_version.update(|v| *v += 1);
+    }
+
+    pub fn get_thumbnails_version(&self) -> Property<usize> {
+        self.thumbnails_version.clone()
+    }
+
+    /// Starts or stops the engine sampling the cost of each node, see `node_costs`.  Stopping
+    /// clears the costs sampled so far
+    pub fn set_node_cost_profiling(&mut self, enabled: bool) {
+        self.node_cost_profiling = enabled;
+        if !enabled {
+            self.node_costs.clear();
+            self.node_costs_version.update(|v| *v += 1);
+        }
+    }
+
+    pub fn is_node_cost_profiling(&self) -> bool {
+        self.node_cost_profiling
+    }
+
+    /// Replaces the node costs with those of a newly sampled frame, reported by the engine
+    pub fn set_node_costs(&mut self, costs: HashMap<UniqueTemplateNodeIdentifier, NodeCost>) {
+        self.node_costs.replace(costs);
+        self.node_costs_version.update(|v| *v += 1);
+    }
+
+    /// Cost of template node `uni` on the most recently sampled frame
+    pub fn get_node_cost(&self, uni: &UniqueTemplateNodeIdentifier) -> Option<&NodeCost> {
+        self.node_costs.get(uni)
+    }
+
+    /// The most expensive nodes of the template of component `type_id` on the most recently
+    /// sampled frame, most expensive first
+    pub fn get_hot_nodes(&self, type_id: &TypeId) -> Vec<HotNode> {
+        self.node_costs.hot_nodes(type_id, HOT_NODES_PER_COMPONENT)
+    }
+
+    pub fn get_node_costs_version(&self) -> Property<usize> {
+        self.node_costs_version.clone()
+    }
+
+    /// Starts or stops the engine recording the property values of the selected and pinned
+    /// nodes, see `property_recorder`.  Stopping drops what has been recorded so far
+    pub fn set_property_recording(&mut self, enabled: bool) {
+        self.property_recordings.set_enabled(enabled);
+        self.property_recordings_version.update(|v| *v += 1);
+    }
+
+    pub fn is_property_recording(&self) -> bool {
+        self.property_recordings.is_enabled()
+    }
+
+    /// Pauses or resumes property recording, keeping what has been recorded so far
+    pub fn set_property_recording_paused(&mut self, paused: bool) {
+        self.property_recordings.set_paused(paused);
+        self.property_recordings_version.update(|v| *v += 1);
+    }
+
+    pub fn is_property_recording_paused(&self) -> bool {
+        self.property_recordings.is_paused()
+    }
+
+    /// Sets the number of frames recorded per node, `DEFAULT_CAPACITY_FRAMES` by default
+    pub fn set_property_recording_capacity(&mut self, frames: usize) {
+        self.property_recordings.set_capacity(frames);
+        self.property_recordings_version.update(|v| *v += 1);
+    }
+
+    pub fn get_property_recording_capacity(&self) -> usize {
+        self.property_recordings.capacity()
+    }
+
+    /// Sets the selected node, whose property values are recorded while recording is enabled
+    pub fn set_property_recording_selection(&mut self, uni: Option<UniqueTemplateNodeIdentifier>) {
+        self.property_recordings.set_selected(uni);
+        self.property_recordings_version.update(|v| *v += 1);
+    }
+
+    /// Keeps recording the property values of node `uni` while it's not selected
+    pub fn pin_recorded_node(&mut self, uni: UniqueTemplateNodeIdentifier) {
+        self.property_recordings.pin(uni);
+        self.property_recordings_version.update(|v| *v += 1);
+    }
+
+    pub fn unpin_recorded_node(&mut self, uni: &UniqueTemplateNodeIdentifier) {
+        self.property_recordings.unpin(uni);
+        self.property_recordings_version.update(|v| *v += 1);
+    }
+
+    pub fn is_recorded_node_pinned(&self, uni: &UniqueTemplateNodeIdentifier) -> bool {
+        self.property_recordings.is_pinned(uni)
+    }
+
+    /// Nodes whose property values the engine should report this frame, none while recording
+    /// is disabled or paused
+    pub fn get_recorded_nodes(&self) -> Vec<UniqueTemplateNodeIdentifier> {
+        self.property_recordings.recorded_nodes()
+    }
+
+    /// Adds the property values of node `uni` on a frame, reported by the engine
+    pub fn record_node_frame(&mut self, uni: &UniqueTemplateNodeIdentifier, frame: RecordedFrame) {
+        if self.property_recordings.record(uni, frame) {
+            self.property_recordings_version.update(|v| *v += 1);
+        }
+    }
+
+    /// The recent property values of node `uni`, if it's recorded
+    pub fn get_node_recording(&self, uni: &UniqueTemplateNodeIdentifier) -> Option<&NodeRecording> {
+        self.property_recordings.get(uni)
+    }
+
+    pub fn get_property_recordings_version(&self) -> Property<usize> {
+        self.property_recordings_version.clone()
+    }
+
+    pub fn get_cached_node_bounds(
+        &self,
+        uni: &UniqueTemplateNodeIdentifier,
+    ) -> Option<&Vec<NodeBounds>> {
+        self.node_bounds_cache.get(uni)
+    }
+
     pub fn handle_response(&mut self, response: DesigntimeResponseMessage) {
         match response {
             DesigntimeResponseMessage::LLMResponse(component) => {
@@ -0,0 +1,129 @@
+//! # Manifest Loading
+//!
+//! The design server sends the manifest as a header announcing the number of components, followed
+//! by batches of components, so that the designer can show real progress while a large project
+//! loads.  `ManifestLoadState` is the state the designer observes, and `ManifestAssembler` puts the
+//! manifest back together on the designtime side.
+
+use std::time::Duration;
+
+use pax_manifest::pax_runtime_api::Interpolatable;
+use pax_manifest::{ComponentDefinition, PaxManifest};
+use serde::{Deserialize, Serialize};
+
+/// Time to wait for the manifest before giving up, unless configured with
+/// `DesigntimeManager::set_manifest_load_timeout`
+pub const DEFAULT_MANIFEST_LOAD_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Time between attempts to reconnect to the design server while the manifest is loading
+pub const RECONNECT_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub enum ManifestLoadState {
+    /// Waiting for the design server to connect and announce the manifest
+    #[default]
+    Connecting,
+    /// `received` out of `total` components have arrived
+    Partial {
+        received: usize,
+        total: usize,
+    },
+    Complete,
+    /// Loading timed out, with the underlying connection error if any
+    Failed(String),
+}
+
+impl Interpolatable for ManifestLoadState {}
+
+impl ManifestLoadState {
+    pub fn is_complete(&self) -> bool {
+        matches!(self, ManifestLoadState::Complete)
+    }
+
+    pub fn is_failed(&self) -> bool {
+        matches!(self, ManifestLoadState::Failed(_))
+    }
+
+    /// Fraction of the components received, from 0 to 1
+    pub fn progress(&self) -> f64 {
+        match self {
+            ManifestLoadState::Connecting | ManifestLoadState::Failed(_) => 0.0,
+            ManifestLoadState::Partial { total: 0, .. } | ManifestLoadState::Complete => 1.0,
+            &ManifestLoadState::Partial { received, total } => received as f64 / total as f64,
+        }
+    }
+
+    /// Status line for the user, e.g. "Loading 64/210 components…"
+    pub fn message(&self) -> String {
+        match self {
+            ManifestLoadState::Connecting => "Connecting to design server…".to_string(),
+            ManifestLoadState::Partial { received, total } => {
+                format!("Loading {}/{} components…", received, total)
+            }
+            ManifestLoadState::Complete => "Loaded".to_string(),
+            ManifestLoadState::Failed(error) => format!("Couldn't load project: {}", error),
+        }
+    }
+}
+
+/// Collects a manifest sent as a `LoadManifestHeader` followed by `LoadManifestComponents`
+#[derive(Default)]
+pub struct ManifestAssembler {
+    manifest: Option<PaxManifest>,
+    total: usize,
+}
+
+impl ManifestAssembler {
+    /// Starts a new manifest, dropping any partially received one
+    pub fn begin(&mut self, manifest: PaxManifest, total_components: usize) -> ManifestLoadState {
+        self.manifest = Some(manifest);
+        self.total = total_components;
+        self.state()
+    }
+
+    /// Adds components to the manifest being received.  Components without a preceding header
+    /// (e.g. from before a reconnect) are dropped.
+    pub fn receive(&mut self, components: Vec<ComponentDefinition>) -> ManifestLoadState {
+        if let Some(manifest) = &mut self.manifest {
+            manifest.components.extend(
+                components
+                    .into_iter()
+                    .map(|component| (component.type_id.clone(), component)),
+            );
+        }
+        self.state()
+    }
+
+    /// The manifest, once all of its components have been received
+    pub fn take_complete(&mut self) -> Option<PaxManifest> {
+        let received = self.manifest.as_ref()?.components.len();
+        (received >= self.total)
+            .then(|| self.manifest.take())
+            .flatten()
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn state(&self) -> ManifestLoadState {
+        match &self.manifest {
+            Some(manifest) => ManifestLoadState::Partial {
+                received: manifest.components.len().min(self.total),
+                total: self.total,
+            },
+            None => ManifestLoadState::Connecting,
+        }
+    }
+}
+
+/// Milliseconds since page load, or 0 outside of a browser
+pub(crate) fn now_ms() -> f64 {
+    #[cfg(target_arch = "wasm32")]
+    return web_sys::window()
+        .and_then(|window| window.performance())
+        .map(|performance| performance.now())
+        .unwrap_or(0.0);
+    #[cfg(not(target_arch = "wasm32"))]
+    0.0
+}
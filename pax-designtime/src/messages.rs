@@ -1,4 +1,4 @@
-use pax_manifest::{ComponentTemplate, TypeId};
+use pax_manifest::{ComponentTemplate, TypeId, UniqueTemplateNodeIdentifier};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
@@ -9,9 +9,34 @@ pub enum AgentMessage {
     // sent from designtime to design-server
     LoadManifestRequest,
     LoadManifestResponse(LoadManifestResponse),
+    LoadManifestHeader(LoadManifestHeader),
+    LoadManifestComponents(LoadManifestComponents),
     ComponentSerializationRequest(ComponentSerializationRequest),
     UpdateTemplateRequest(Box<UpdateTemplateRequest>),
     LoadFileToStaticDirRequest(LoadFileToStaticDirRequest),
+    NodeBoundsRequest(NodeBoundsRequest),
+    NodeBoundsResponse(NodeBoundsResponse),
+    // Request to retrieve the persisted designer workspace state
+    // sent from designtime to design-server
+    LoadWorkspaceStateRequest,
+    LoadWorkspaceStateResponse(LoadWorkspaceStateResponse),
+    SaveWorkspaceStateRequest(SaveWorkspaceStateRequest),
+    // Request to retrieve the user's designer keymap
+    // sent from designtime to design-server
+    LoadKeymapRequest,
+    LoadKeymapResponse(LoadKeymapResponse),
+    SaveKeymapRequest(SaveKeymapRequest),
+    ExportSnippetRequest(ExportSnippetRequest),
+    // Request to list the snippets in the user's snippet library
+    // sent from designtime to design-server
+    ListSnippetsRequest,
+    ListSnippetsResponse(ListSnippetsResponse),
+    LoadSnippetRequest(LoadSnippetRequest),
+    ImportSnippetRequest(ImportSnippetRequest),
+    SnippetStatusNotification(SnippetStatusNotification),
+    AppendSessionLogRequest(AppendSessionLogRequest),
+    ExportBugReportRequest(ExportBugReportRequest),
+    BugReportStatusNotification(BugReportStatusNotification),
 }
 
 #[derive(Serialize, Deserialize)]
@@ -40,9 +65,29 @@ pub struct LoadManifestResponse {
     pub manifest: Vec<u8>,
 }
 
+/// The manifest without its components, and the number of components that follow in
+/// `LoadManifestComponents` messages, so that the designer can show loading progress.
+/// Sent from `pax-design-server` to `pax-designtime` in reply to a `LoadManifestRequest`.
+#[derive(Serialize, Deserialize)]
+pub struct LoadManifestHeader {
+    pub manifest: Vec<u8>,
+    pub total_components: usize,
+}
+
+/// A batch of the components (an encoded `Vec<ComponentDefinition>`) of the manifest announced
+/// by the last `LoadManifestHeader`.
+/// Sent from `pax-design-server` to `pax-designtime`.
+#[derive(Serialize, Deserialize)]
+pub struct LoadManifestComponents {
+    pub components: Vec<u8>,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct ComponentSerializationRequest {
     pub component_bytes: Vec<u8>,
+    /// Changes since the component was last saved, one per line, see `ComponentDiff::describe`
+    #[serde(default)]
+    pub changes: Vec<String>,
 }
 
 /// A request to update the template of a component.
@@ -54,3 +99,117 @@ pub struct UpdateTemplateRequest {
     /// The new template for the component.
     pub new_template: ComponentTemplate,
 }
+
+/// A request for the computed layout of a template node in the running engine.
+/// Sent from `pax-design-server` to `pax-designtime`.
+#[derive(Serialize, Deserialize)]
+pub struct NodeBoundsRequest {
+    pub uni: UniqueTemplateNodeIdentifier,
+}
+
+/// The computed layout of every expanded instance of a template node.
+/// Sent from `pax-designtime` to `pax-design-server` in reply to a `NodeBoundsRequest`.
+#[derive(Serialize, Deserialize)]
+pub struct NodeBoundsResponse {
+    pub uni: UniqueTemplateNodeIdentifier,
+    /// Empty if the node is not currently expanded (e.g. inside a falsy `if`)
+    pub bounds: Vec<NodeBounds>,
+}
+
+/// A serializable mirror of the runtime's `TransformAndBounds<NodeLocal, Window>`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NodeBounds {
+    /// Affine coefficients of the node-local to window transform, in kurbo order
+    pub transform: [f64; 6],
+    pub bounds: (f64, f64),
+}
+
+/// The persisted designer workspace state (guides, grid settings, etc.) of the project, if any has
+/// been saved.  The state is opaque to the design server, which only stores it.
+/// Sent from `pax-design-server` to `pax-designtime` in reply to a `LoadWorkspaceStateRequest`.
+#[derive(Serialize, Deserialize)]
+pub struct LoadWorkspaceStateResponse {
+    pub state: Option<String>,
+}
+
+/// A request to persist the designer workspace state for the project.
+/// Sent from `pax-designtime` to `pax-design-server`.
+#[derive(Serialize, Deserialize)]
+pub struct SaveWorkspaceStateRequest {
+    pub state: String,
+}
+
+/// The persisted designer keymap of the user, if they have customized it.  Unlike the workspace
+/// state this is stored per user rather than per project, and is likewise opaque to the design server.
+/// Sent from `pax-design-server` to `pax-designtime` in reply to a `LoadKeymapRequest`.
+#[derive(Serialize, Deserialize)]
+pub struct LoadKeymapResponse {
+    pub keymap: Option<String>,
+}
+
+/// A request to persist the designer keymap of the user.
+/// Sent from `pax-designtime` to `pax-design-server`.
+#[derive(Serialize, Deserialize)]
+pub struct SaveKeymapRequest {
+    pub keymap: String,
+}
+
+/// A request to save a snippet (an encoded `SnippetArchive`, without asset contents) to the user's
+/// snippet library.  The design server fills in the contents of the assets from the project.
+/// Sent from `pax-designtime` to `pax-design-server`.
+#[derive(Serialize, Deserialize)]
+pub struct ExportSnippetRequest {
+    pub archive: Vec<u8>,
+}
+
+/// Names of the snippets in the user's snippet library, shared between all of the user's projects.
+/// Sent from `pax-design-server` to `pax-designtime` in reply to a `ListSnippetsRequest`.
+#[derive(Serialize, Deserialize)]
+pub struct ListSnippetsResponse {
+    pub names: Vec<String>,
+}
+
+/// A request to import a snippet from the user's snippet library.
+/// Sent from `pax-designtime` to `pax-design-server`, which replies with an `ImportSnippetRequest`.
+#[derive(Serialize, Deserialize)]
+pub struct LoadSnippetRequest {
+    pub name: String,
+}
+
+/// A request to import a snippet (an encoded `SnippetArchive`) into the component being edited.
+/// Sent from `pax-design-server` to `pax-designtime`.
+#[derive(Serialize, Deserialize)]
+pub struct ImportSnippetRequest {
+    pub archive: Vec<u8>,
+}
+
+/// The outcome of a snippet operation handled by the design server, to be shown to the user.
+/// Sent from `pax-design-server` to `pax-designtime`.
+#[derive(Serialize, Deserialize)]
+pub struct SnippetStatusNotification {
+    pub message: String,
+}
+
+/// Events of the session being recorded in the designer (see `orm::session`), to append to the
+/// session log of the project: a JSON `SessionRecording` without events starting a new log, if the
+/// recording (re)started, followed by one JSON `SessionEvent` per entry.
+/// Sent from `pax-designtime` to `pax-design-server`.
+#[derive(Serialize, Deserialize)]
+pub struct AppendSessionLogRequest {
+    pub header: Option<String>,
+    pub events: Vec<String>,
+}
+
+/// A request to save a bug report (a JSON `BugReport`) to the project, to be attached to an issue.
+/// Sent from `pax-designtime` to `pax-design-server`.
+#[derive(Serialize, Deserialize)]
+pub struct ExportBugReportRequest {
+    pub report: String,
+}
+
+/// Where the design server saved a bug report, or why it couldn't, to be shown to the user.
+/// Sent from `pax-design-server` to `pax-designtime`.
+#[derive(Serialize, Deserialize)]
+pub struct BugReportStatusNotification {
+    pub message: String,
+}
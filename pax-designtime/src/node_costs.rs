@@ -0,0 +1,138 @@
+//! # Node costs
+//!
+//! Time the engine spends on each template node, for finding the parts of a tree that make
+//! frames slow. While profiling is enabled through the `DesigntimeManager`, designtime builds
+//! of the engine time the property computation, layout and rendering of every expanded node on
+//! one frame out of `SAMPLE_INTERVAL_FRAMES`, add up the times of the expanded instances of each
+//! template node (e.g. of a node in a `for`), and report them with `set_node_costs`. The times
+//! of a node don't include those of its children. Outside of designtime builds none of this is
+//! compiled in.
+
+use std::collections::HashMap;
+
+use pax_manifest::{TypeId, UniqueTemplateNodeIdentifier};
+
+/// Node costs are sampled on one frame out of this many, to keep the overhead of timing low
+pub const SAMPLE_INTERVAL_FRAMES: u64 = 30;
+
+/// Number of nodes `DesigntimeManager::get_hot_nodes` lists per component
+pub const HOT_NODES_PER_COMPONENT: usize = 10;
+
+/// Time spent on a template node during one frame, summed over its expanded instances
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct NodeCost {
+    /// Microseconds spent computing properties, including `tick` and `pre_render` handlers and
+    /// expanding children (e.g. of a `for`)
+    pub properties_us: f64,
+    /// Microseconds spent computing transforms and bounds
+    pub layout_us: f64,
+    /// Microseconds spent drawing
+    pub render_us: f64,
+    /// Number of expanded instances of the node
+    pub instances: usize,
+}
+
+impl NodeCost {
+    pub fn total_us(&self) -> f64 {
+        self.properties_us + self.layout_us + self.render_us
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HotNode {
+    pub uni: UniqueTemplateNodeIdentifier,
+    pub cost: NodeCost,
+}
+
+/// Costs of the template nodes on the most recently sampled frame
+#[derive(Default)]
+pub struct NodeCosts {
+    costs: HashMap<UniqueTemplateNodeIdentifier, NodeCost>,
+}
+
+impl NodeCosts {
+    pub fn replace(&mut self, costs: HashMap<UniqueTemplateNodeIdentifier, NodeCost>) {
+        self.costs = costs;
+    }
+
+    pub fn clear(&mut self) {
+        self.costs.clear();
+    }
+
+    pub fn get(&self, uni: &UniqueTemplateNodeIdentifier) -> Option<&NodeCost> {
+        self.costs.get(uni)
+    }
+
+    /// The `count` most expensive nodes of the template of component `type_id`, most expensive first
+    pub fn hot_nodes(&self, type_id: &TypeId, count: usize) -> Vec<HotNode> {
+        let mut hot_nodes: Vec<_> = self
+            .costs
+            .iter()
+            .filter(|(uni, _)| uni.get_containing_component_type_id() == *type_id)
+            .map(|(uni, cost)| HotNode {
+                uni: uni.clone(),
+                cost: *cost,
+            })
+            .collect();
+        hot_nodes.sort_by(|a, b| {
+            b.cost
+                .total_us()
+                .total_cmp(&a.cost.total_us())
+                .then_with(|| {
+                    let id = |node: &HotNode| node.uni.get_template_node_id().as_usize();
+                    id(a).cmp(&id(b))
+                })
+        });
+        hot_nodes.truncate(count);
+        hot_nodes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pax_manifest::TemplateNodeId;
+
+    fn uni(component: &TypeId, id: usize) -> UniqueTemplateNodeIdentifier {
+        UniqueTemplateNodeIdentifier::build(component.clone(), TemplateNodeId::build(id))
+    }
+
+    fn cost(render_us: f64, instances: usize) -> NodeCost {
+        NodeCost {
+            render_us,
+            instances,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_hot_nodes_of_component_by_cost() {
+        let main = TypeId::build_singleton("Main", None);
+        let other = TypeId::build_singleton("Other", None);
+        let mut costs = NodeCosts::default();
+        costs.replace(HashMap::from([
+            (uni(&main, 0), cost(10.0, 1)),
+            (uni(&main, 1), cost(400.0, 400)),
+            (uni(&main, 2), cost(50.0, 1)),
+            (uni(&other, 0), cost(1000.0, 1)),
+        ]));
+
+        let hot_nodes = costs.hot_nodes(&main, 2);
+        assert_eq!(
+            hot_nodes,
+            vec![
+                HotNode {
+                    uni: uni(&main, 1),
+                    cost: cost(400.0, 400)
+                },
+                HotNode {
+                    uni: uni(&main, 2),
+                    cost: cost(50.0, 1)
+                },
+            ]
+        );
+        assert_eq!(costs.hot_nodes(&other, 10).len(), 1);
+        costs.clear();
+        assert!(costs.hot_nodes(&main, 10).is_empty());
+    }
+}
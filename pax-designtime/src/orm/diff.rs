@@ -0,0 +1,392 @@
+//! # Component diffs
+//!
+//! Structured differences between two versions of a component, e.g. the version last written to
+//! disk and the one being edited, for reviewing changes before saving them. Template nodes are
+//! matched by id, which the ORM keeps stable across edits, and values are compared as they would
+//! be written in a template, so that a component that was only reformatted (e.g. parsed back from
+//! what was serialized) has an empty diff.
+
+use std::collections::BTreeMap;
+
+use pax_manifest::pax_runtime_api::Interpolatable;
+use pax_manifest::{
+    ComponentDefinition, ComponentTemplate, LiteralBlockDefinition, SettingElement,
+    SettingsBlockElement, TemplateNodeDefinition, TemplateNodeId, TypeId, ValueDefinition,
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct ComponentDiff {
+    /// Changed template nodes, in template order, removed nodes first
+    pub nodes: Vec<NodeDiff>,
+    /// Changed selector blocks of the `@settings` block, e.g. `.label`
+    pub selectors: Vec<SelectorDiff>,
+    /// Changed event handlers of the `@settings` block, e.g. `@mount`
+    pub handlers: Vec<HandlerDiff>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NodeDiff {
+    pub id: TemplateNodeId,
+    /// Type of the new version, unless the node was removed
+    pub type_id: TypeId,
+    /// Node type, or for control flow and comments their contents, e.g. `<Rectangle>` or
+    /// `if self.on`, of the new version unless the node was removed
+    pub label: String,
+    pub change: NodeChange,
+}
+
+#[derive(Debug, Clone)]
+pub enum NodeChange {
+    Added {
+        settings: Vec<(String, ValueDefinition)>,
+    },
+    Removed,
+    Modified {
+        /// Type of the old version, if the type changed
+        previous_type_id: Option<TypeId>,
+        /// Positions before and after, if the node moved
+        position: Option<(NodePosition, NodePosition)>,
+        settings: Vec<SettingChange>,
+    },
+}
+
+/// Parent and index among its siblings of a node, the parent being `None` for root nodes
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodePosition {
+    pub parent: Option<TemplateNodeId>,
+    pub index: usize,
+}
+
+/// A setting that was added (no `before`), removed (no `after`) or changed
+#[derive(Debug, Clone)]
+pub struct SettingChange {
+    pub key: String,
+    pub before: Option<ValueDefinition>,
+    pub after: Option<ValueDefinition>,
+}
+
+/// A selector block that was added (every setting without `before`), removed (every setting
+/// without `after`), or whose settings changed
+#[derive(Debug, Clone)]
+pub struct SelectorDiff {
+    pub selector: String,
+    pub settings: Vec<SettingChange>,
+}
+
+/// A handler that was added (no `before`), removed (no `after`) or bound to other functions
+#[derive(Debug, Clone)]
+pub struct HandlerDiff {
+    pub event: String,
+    pub before: Option<Vec<String>>,
+    pub after: Option<Vec<String>>,
+}
+
+impl Interpolatable for ComponentDiff {}
+
+impl ComponentDiff {
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.selectors.is_empty() && self.handlers.is_empty()
+    }
+
+    /// Number of individual changes, counting each changed setting, each added or removed
+    /// node, each move and each type change once, so adding a node counts as one change
+    pub fn change_count(&self) -> usize {
+        let node_changes: usize = self
+            .nodes
+            .iter()
+            .map(|node| match &node.change {
+                NodeChange::Added { .. } | NodeChange::Removed => 1,
+                NodeChange::Modified {
+                    previous_type_id,
+                    position,
+                    settings,
+                } => {
+                    previous_type_id.is_some() as usize
+                        + position.is_some() as usize
+                        + settings.len()
+                }
+            })
+            .sum();
+        let selector_changes: usize = self.selectors.iter().map(|s| s.settings.len()).sum();
+        node_changes + selector_changes + self.handlers.len()
+    }
+
+    /// One line per change, grouped by node, e.g. `<Rectangle> #3: fill: rgb(0, 0, 255) → RED`
+    pub fn describe(&self) -> Vec<String> {
+        self.describe_grouped()
+            .into_iter()
+            .flat_map(|(subject, changes)| {
+                changes
+                    .into_iter()
+                    .map(move |change| format!("{}: {}", subject, change))
+            })
+            .collect()
+    }
+
+    /// The changes by what they change, a node (e.g. `<Rectangle> #3`), selector (e.g. `.label`)
+    /// or handler (e.g. `@mount`), with one line per change (e.g. `fill: rgb(0, 0, 255) → RED`)
+    pub fn describe_grouped(&self) -> Vec<(String, Vec<String>)> {
+        let mut groups = vec![];
+        for node in &self.nodes {
+            let mut lines = vec![];
+            match &node.change {
+                NodeChange::Added { settings } if settings.is_empty() => {
+                    lines.push("added".to_string());
+                }
+                NodeChange::Added { settings } => {
+                    let settings: Vec<String> = settings
+                        .iter()
+                        .map(|(key, value)| format!("{}={}", key, value.to_template_string()))
+                        .collect();
+                    lines.push(format!("added ({})", settings.join(", ")));
+                }
+                NodeChange::Removed => lines.push("removed".to_string()),
+                NodeChange::Modified {
+                    previous_type_id,
+                    position,
+                    settings,
+                } => {
+                    if let Some(previous_type_id) = previous_type_id {
+                        lines.push(format!("type {} → {}", previous_type_id, node.type_id));
+                    }
+                    if let Some((before, after)) = position {
+                        lines.push(format!(
+                            "moved {} → {}",
+                            describe_position(before),
+                            describe_position(after)
+                        ));
+                    }
+                    lines.extend(settings.iter().map(describe_setting));
+                }
+            }
+            groups.push((format!("{} #{}", node.label, node.id), lines));
+        }
+        for selector in &self.selectors {
+            let lines = selector.settings.iter().map(describe_setting).collect();
+            groups.push((selector.selector.clone(), lines));
+        }
+        for handler in &self.handlers {
+            let describe = |handlers: &Option<Vec<String>>| match handlers {
+                Some(handlers) => handlers.join(", "),
+                None => "unset".to_string(),
+            };
+            let line = format!(
+                "{} → {}",
+                describe(&handler.before),
+                describe(&handler.after)
+            );
+            groups.push((format!("@{}", handler.event), vec![line]));
+        }
+        groups
+    }
+}
+
+/// Lists the template nodes, selectors and handlers that were added, removed or modified going
+/// from `old` to `new`
+pub fn diff_components(old: &ComponentDefinition, new: &ComponentDefinition) -> ComponentDiff {
+    let (old_selectors, old_handlers) = settings_block(old);
+    let (new_selectors, new_handlers) = settings_block(new);
+    ComponentDiff {
+        nodes: diff_templates(
+            old.template
+                .as_ref()
+                .unwrap_or(&ComponentTemplate::default()),
+            new.template
+                .as_ref()
+                .unwrap_or(&ComponentTemplate::default()),
+        ),
+        selectors: diff_selectors(&old_selectors, &new_selectors),
+        handlers: diff_handlers(&old_handlers, &new_handlers),
+    }
+}
+
+fn diff_templates(old: &ComponentTemplate, new: &ComponentTemplate) -> Vec<NodeDiff> {
+    let mut nodes = vec![];
+    for id in old.get_preorder_ids() {
+        if new.get_node(&id).is_some() {
+            continue;
+        }
+        if let Some(old_node) = old.get_node(&id) {
+            nodes.push(NodeDiff {
+                id,
+                type_id: old_node.type_id.clone(),
+                label: old_node.get_label(),
+                change: NodeChange::Removed,
+            });
+        }
+    }
+    for id in new.get_preorder_ids() {
+        let Some(new_node) = new.get_node(&id) else {
+            continue;
+        };
+        let label = new_node.get_label();
+        let Some(old_node) = old.get_node(&id) else {
+            let settings = node_settings(new_node)
+                .into_iter()
+                .map(|(key, (value, _))| (key, value))
+                .collect();
+            nodes.push(NodeDiff {
+                id,
+                type_id: new_node.type_id.clone(),
+                label,
+                change: NodeChange::Added { settings },
+            });
+            continue;
+        };
+        let previous_type_id =
+            (old_node.type_id != new_node.type_id).then(|| old_node.type_id.clone());
+        let (old_position, new_position) = (node_position(old, &id), node_position(new, &id));
+        let position = (old_position != new_position).then_some((old_position, new_position));
+        let settings = diff_settings(node_settings(old_node), node_settings(new_node));
+        if previous_type_id.is_some() || position.is_some() || !settings.is_empty() {
+            nodes.push(NodeDiff {
+                id,
+                type_id: new_node.type_id.clone(),
+                label,
+                change: NodeChange::Modified {
+                    previous_type_id,
+                    position,
+                    settings,
+                },
+            });
+        }
+    }
+    nodes
+}
+
+/// Values of settings by key, along with the values as written in a template
+type Settings = BTreeMap<String, (ValueDefinition, String)>;
+
+fn node_settings(node: &TemplateNodeDefinition) -> Settings {
+    let settings = node
+        .settings
+        .iter()
+        .flatten()
+        .filter_map(|setting| match setting {
+            SettingElement::Setting(key, value) => Some((&key.token_value, value)),
+            SettingElement::Comment(_) => None,
+        });
+    collect_settings(settings)
+}
+
+fn block_settings(block: &LiteralBlockDefinition) -> Settings {
+    collect_settings(
+        block
+            .get_all_settings()
+            .into_iter()
+            .map(|(key, value)| (&key.token_value, value)),
+    )
+}
+
+fn collect_settings<'a>(
+    settings: impl Iterator<Item = (&'a String, &'a ValueDefinition)>,
+) -> Settings {
+    settings
+        .map(|(key, value)| (key.clone(), (value.clone(), value.to_template_string())))
+        .collect()
+}
+
+fn diff_settings(mut old: Settings, new: Settings) -> Vec<SettingChange> {
+    let mut changes = vec![];
+    for (key, (new_value, new_string)) in new {
+        match old.remove(&key) {
+            Some((_, old_string)) if old_string == new_string => (),
+            old_value => changes.push(SettingChange {
+                key,
+                before: old_value.map(|(value, _)| value),
+                after: Some(new_value),
+            }),
+        }
+    }
+    for (key, (old_value, _)) in old {
+        changes.push(SettingChange {
+            key,
+            before: Some(old_value),
+            after: None,
+        });
+    }
+    changes
+}
+
+fn node_position(template: &ComponentTemplate, id: &TemplateNodeId) -> NodePosition {
+    let (parent, index) = template.get_position(id);
+    NodePosition { parent, index }
+}
+
+/// Selector blocks by selector, and handler function names by event, of the `@settings` block
+fn settings_block(
+    component: &ComponentDefinition,
+) -> (BTreeMap<String, Settings>, BTreeMap<String, Vec<String>>) {
+    let mut selectors = BTreeMap::new();
+    let mut handlers = BTreeMap::new();
+    for element in component.settings.iter().flatten() {
+        match element {
+            SettingsBlockElement::SelectorBlock(selector, block) => {
+                selectors.insert(selector.token_value.clone(), block_settings(block));
+            }
+            SettingsBlockElement::Handler(event, functions) => {
+                let functions = functions.iter().map(|f| f.token_value.clone()).collect();
+                handlers.insert(event.token_value.clone(), functions);
+            }
+            SettingsBlockElement::Comment(_) => (),
+        }
+    }
+    (selectors, handlers)
+}
+
+fn diff_selectors(
+    old: &BTreeMap<String, Settings>,
+    new: &BTreeMap<String, Settings>,
+) -> Vec<SelectorDiff> {
+    let mut selectors: Vec<String> = old.keys().chain(new.keys()).cloned().collect();
+    selectors.sort();
+    selectors.dedup();
+    selectors
+        .into_iter()
+        .filter_map(|selector| {
+            let settings = diff_settings(
+                old.get(&selector).cloned().unwrap_or_default(),
+                new.get(&selector).cloned().unwrap_or_default(),
+            );
+            (!settings.is_empty()).then_some(SelectorDiff { selector, settings })
+        })
+        .collect()
+}
+
+fn diff_handlers(
+    old: &BTreeMap<String, Vec<String>>,
+    new: &BTreeMap<String, Vec<String>>,
+) -> Vec<HandlerDiff> {
+    let mut events: Vec<&String> = old.keys().chain(new.keys()).collect();
+    events.sort();
+    events.dedup();
+    events
+        .into_iter()
+        .filter(|event| old.get(*event) != new.get(*event))
+        .map(|event| HandlerDiff {
+            event: event.clone(),
+            before: old.get(event).cloned(),
+            after: new.get(event).cloned(),
+        })
+        .collect()
+}
+
+fn describe_setting(change: &SettingChange) -> String {
+    let describe = |value: &Option<ValueDefinition>| match value {
+        Some(value) => value.to_template_string(),
+        None => "unset".to_string(),
+    };
+    format!(
+        "{}: {} → {}",
+        change.key,
+        describe(&change.before),
+        describe(&change.after)
+    )
+}
+
+fn describe_position(position: &NodePosition) -> String {
+    match &position.parent {
+        Some(parent) => format!("child {} of #{}", position.index, parent),
+        None => format!("root {}", position.index),
+    }
+}
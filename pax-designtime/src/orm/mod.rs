@@ -17,27 +17,51 @@
 //! - `undo`: Undo the last command. This method rolls back the last change made to the manifest.
 //! - `redo`: Redo the last undone command. This method reapplies the last change that was undone.
 //! - `undo_until`: Undo commands up to a specified command ID. This allows for targeted rollback of multiple changes.
+//! - `get_undo_history`: List the ID and description (e.g. "Add Rectangle") of each undoable command, for labeling undo history.
+//! - `get_component_history`: List snapshots of a component after each change to it, and `restore_component_to_snapshot` to restore one.
+//! - `diff_against_saved`: List the changes made to a component since it was last saved, see `diff`, and `diff_against_loaded` since the manifest was loaded.
+//! - `get_tokens` / `set_token`: Read and change the design tokens settings reference as `{$tokens.name}`, see `tokens`.
+//! - `start_recording`: Record the commands executed from then on into a replayable script for bug reports, see `session`.
+//!
+//! The same operations are available to tools not written in Rust over JSON-RPC, see `rpc`.
 //!
 //! For usage examples see the tests in `pax-designtime/src/orm/tests.rs`.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 
-use pax_manifest::pax_runtime_api::{Interpolatable, Property};
+use pax_manifest::cartridge_generation::SettingSource;
+use pax_manifest::pax_runtime_api::{Interpolatable, PaxValue, Property};
 use pax_manifest::{
     ComponentDefinition, ComponentTemplate, NodeLocation, PaxManifest, SettingElement,
-    TemplateNodeDefinition, TemplateNodeId, TypeId, UniqueTemplateNodeIdentifier, ValueDefinition,
+    TemplateNodeDefinition, TemplateNodeId, Token, TypeId, UniqueTemplateNodeIdentifier,
+    ValueDefinition,
 };
 use serde_derive::{Deserialize, Serialize};
 #[allow(unused_imports)]
 use serde_json;
 
-use self::template::{builder::NodeBuilder, ConvertToComponentRequest, RemoveTemplateNodeRequest};
+use crate::manifest_loading::ManifestLoadState;
+
+use self::session::{
+    RecordingOptions, SessionEventKind, SessionLogEntries, SessionRecorder, SessionRecording,
+    SessionState,
+};
+
+use self::template::{
+    builder::NodeBuilder, AddComponentsRequest, ConvertToComponentRequest,
+    RemoveTemplateNodeRequest,
+};
 use self::template::{GetChildrenRequest, MoveTemplateNodeRequest, PasteSubTreeRequest};
 
 use anyhow::{anyhow, Result};
+pub mod diff;
+pub mod rpc;
+pub mod selectors;
+pub mod session;
 pub mod template;
 #[cfg(test)]
 mod tests;
+pub mod tokens;
 
 pub trait Request {
     type Response: Response;
@@ -46,13 +70,27 @@ pub trait Request {
 pub trait Response {
     fn set_id(&mut self, id: usize);
     fn get_id(&self) -> usize;
+    fn set_description(&mut self, description: String);
+    /// Human-readable summary of the executed command, see `Command::description`
+    fn get_description(&self) -> &str;
     fn get_reload_type(&self) -> Option<ReloadType> {
         None
     }
+    /// Components whose definitions were changed by the command,
+    /// each of which gets a snapshot in its component history
+    fn get_affected_components(&self) -> Vec<TypeId> {
+        vec![]
+    }
 }
 
+/// Default number of snapshots kept in the history of each component
+pub const DEFAULT_COMPONENT_HISTORY_CAPACITY: usize = 50;
+
 pub trait Command<R: Request> {
     fn execute(&mut self, manifest: &mut PaxManifest) -> Result<R::Response, String>;
+    /// Human-readable summary of what the command did (e.g. "Add Rectangle"), for status
+    /// messages and undo history labels. Called after `execute`, so it can use cached state
+    fn description(&self) -> String;
     fn as_undo_redo(&mut self) -> Option<UndoRedoCommand> {
         None
     }
@@ -61,32 +99,55 @@ pub trait Command<R: Request> {
 #[derive(Serialize, Deserialize)]
 pub struct PaxManifestORM {
     manifest: PaxManifest,
-    undo_stack: Vec<(usize, UndoRedoCommand)>,
-    redo_stack: Vec<(usize, UndoRedoCommand)>,
+    // (command id, description, command)
+    undo_stack: Vec<(usize, String, UndoRedoCommand)>,
+    redo_stack: Vec<(usize, String, UndoRedoCommand)>,
     next_command_id: usize,
     // This counter increase with each command execution/undo/redo (essentially tracks each unique change to the manifest)
     manifest_version: Property<usize>,
-    next_new_component_id: usize,
     new_components: Vec<TypeId>,
     reload_queue: Vec<ReloadType>,
-    pub manifest_loaded_from_server: Property<bool>,
+    pub manifest_load_state: Property<ManifestLoadState>,
+    // Snapshots of each component after every command that changed it, oldest first
+    component_history: HashMap<TypeId, VecDeque<ComponentDefinition>>,
+    component_history_capacity: usize,
+    // Each component as it was when last written to disk, see `mark_saved`
+    saved_components: HashMap<TypeId, ComponentDefinition>,
+    // Each component as it was when the manifest was loaded
+    loaded_components: HashMap<TypeId, ComponentDefinition>,
+    #[serde(skip)]
+    recorder: Option<SessionRecorder>,
 }
 
 impl PaxManifestORM {
     pub fn new(manifest: PaxManifest) -> Self {
+        let loaded_components: HashMap<_, _> = manifest.components.clone().into_iter().collect();
         PaxManifestORM {
             manifest,
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
             next_command_id: 0,
             manifest_version: Property::new(0),
-            next_new_component_id: 1,
             new_components: Vec::new(),
             reload_queue: Vec::new(),
-            manifest_loaded_from_server: Property::new(false),
+            manifest_load_state: Property::new(ManifestLoadState::Connecting),
+            component_history: HashMap::new(),
+            component_history_capacity: DEFAULT_COMPONENT_HISTORY_CAPACITY,
+            saved_components: loaded_components.clone(),
+            loaded_components,
+            recorder: None,
         }
     }
 
+    /// Components created since the last call to `get_new_components`, which are sent along
+    /// with the next component update
+    pub fn peek_new_components(&self) -> Vec<&ComponentDefinition> {
+        self.new_components
+            .iter()
+            .filter_map(|type_id| self.manifest.components.get(type_id))
+            .collect()
+    }
+
     pub fn get_new_components(&mut self) -> Vec<ComponentDefinition> {
         let mut new_components_to_process = Vec::new();
 
@@ -104,10 +165,20 @@ impl PaxManifestORM {
     }
 
     pub fn set_manifest(&mut self, manifest: PaxManifest) {
+        let version_before = self.manifest_version.get();
+        self.loaded_components = manifest.components.clone().into_iter().collect();
+        self.saved_components = self.loaded_components.clone();
         self.manifest = manifest;
         self.increment_manifest_version();
-        self.manifest_loaded_from_server.set(true);
+        self.manifest_load_state.set(ManifestLoadState::Complete);
         self.set_reload(ReloadType::FullEdit);
+        if self.recorder.is_some() {
+            let manifest = Box::new(self.manifest.clone());
+            self.record(
+                SessionEventKind::ManifestLoaded { manifest },
+                version_before,
+            );
+        }
     }
 
     pub fn get_manifest_version(&self) -> Property<usize> {
@@ -120,6 +191,11 @@ impl PaxManifestORM {
 
     pub fn set_userland_root_component_type_id(&mut self, type_id: &TypeId) {
         self.manifest.main_component_type_id = type_id.clone();
+        let type_id = type_id.clone();
+        self.record(
+            SessionEventKind::MainComponentSet { type_id },
+            self.manifest_version.get(),
+        );
     }
 
     pub fn take_reload_queue(&mut self) -> Vec<ReloadType> {
@@ -182,6 +258,24 @@ impl PaxManifestORM {
         ))
     }
 
+    /// The top-level nodes of the template of `component`, in template order.  Empty for
+    /// components without a template
+    pub fn get_root_nodes(&self, component: &TypeId) -> Vec<UniqueTemplateNodeIdentifier> {
+        let Some(template) = self
+            .manifest
+            .components
+            .get(component)
+            .and_then(|c| c.template.as_ref())
+        else {
+            return vec![];
+        };
+        template
+            .get_root()
+            .into_iter()
+            .map(|tid| UniqueTemplateNodeIdentifier::build(component.clone(), tid))
+            .collect()
+    }
+
     pub fn move_node(
         &mut self,
         uni: UniqueTemplateNodeIdentifier,
@@ -201,6 +295,27 @@ impl PaxManifestORM {
         Ok(resp.children)
     }
 
+    /// The full subtree below `uni` in depth-first order, excluding `uni` itself
+    pub fn get_node_descendants(
+        &mut self,
+        uni: UniqueTemplateNodeIdentifier,
+    ) -> Vec<UniqueTemplateNodeIdentifier> {
+        let component = uni.get_containing_component_type_id();
+        let Some(template) = self
+            .manifest
+            .components
+            .get(&component)
+            .and_then(|c| c.template.as_ref())
+        else {
+            return vec![];
+        };
+        template
+            .get_descendants(&uni.get_template_node_id())
+            .into_iter()
+            .map(|tid| UniqueTemplateNodeIdentifier::build(component.clone(), tid))
+            .collect()
+    }
+
     pub fn swap_main_component(&mut self, component: ComponentDefinition) -> Result<(), String> {
         let command = template::SwapMainComponentRequest::new(component);
         self.execute_command(command)?;
@@ -208,27 +323,9 @@ impl PaxManifestORM {
     }
 
     pub fn copy_subtrees(&self, type_id: &TypeId, nodes: &[TemplateNodeId]) -> Option<SubTrees> {
-        let roots: Vec<_> = nodes.iter().cloned().collect();
-        let mut children = HashMap::new();
-        let mut nodes = HashMap::new();
-
         let component = self.manifest.components.get(type_id)?;
         let template = component.template.as_ref()?;
-        let mut to_visit: Vec<_> = roots.iter().cloned().collect();
-        while let Some(node) = to_visit.pop() {
-            if let Some(node_def) = template.get_node(&node) {
-                nodes.insert(node.clone(), node_def.clone());
-                let node_children = template.get_children(&node).unwrap_or_default();
-                children.insert(node.clone(), node_children.clone());
-                to_visit.extend(node_children);
-            }
-        }
-
-        Some(SubTrees {
-            roots,
-            children,
-            nodes,
-        })
+        Some(SubTrees::from_template(template, nodes))
     }
 
     pub fn paste_subtrees(
@@ -301,26 +398,36 @@ impl PaxManifestORM {
         Ok(resp.get_id())
     }
 
+    /// Moves `nodes` into a new component named after `name`, or `NewComponent1` etc. if
+    /// `None`, numbered if needed to be unique (see `template::unique_component_name`), and
+    /// returns its type id
     pub fn move_to_new_component(
         &mut self,
         nodes: &[MoveToComponentEntry],
+        name: Option<&str>,
         x: f64,
         y: f64,
         width: f64,
         height: f64,
-    ) -> Result<(), String> {
-        let new_component_number = self.next_new_component_id;
+    ) -> Result<TypeId, String> {
         let command = ConvertToComponentRequest::new(
             nodes.to_vec(),
-            new_component_number,
+            name.map(str::to_string),
             x,
             y,
             width,
             height,
         );
         let resp = self.execute_command(command)?;
-        self.new_components.push(resp.new_component_type_id);
-        self.next_new_component_id += 1;
+        self.new_components.push(resp.new_component_type_id.clone());
+        Ok(resp.new_component_type_id)
+    }
+
+    /// Adds `components` (e.g. those of an imported snippet) to the manifest,
+    /// queueing them to be written to new files
+    pub fn add_components(&mut self, components: Vec<ComponentDefinition>) -> Result<(), String> {
+        let resp = self.execute_command(AddComponentsRequest::new(components))?;
+        self.new_components.extend(resp.type_ids);
         Ok(())
     }
 
@@ -328,49 +435,258 @@ impl PaxManifestORM {
     where
         C: Command<R>,
     {
+        let version_before = self.manifest_version.get();
+        // recorded as it was before executing, as undo state is cached during execution
+        let recorded = match self.recorder {
+            Some(_) => command.as_undo_redo(),
+            None => None,
+        };
         let mut response: <R as Request>::Response = command.execute(&mut self.manifest)?;
         let command_id = self.next_command_id;
+        let description = command.description();
         if let Some(command) = command.as_undo_redo() {
-            self.undo_stack.push((command_id, command));
+            self.undo_stack
+                .push((command_id, description.clone(), command));
             self.redo_stack.clear();
         }
 
         response.set_id(command_id);
+        response.set_description(description);
+        for type_id in response.get_affected_components() {
+            self.push_component_snapshot(&type_id);
+        }
         self.next_command_id += 1;
         if let Some(reload_type) = response.get_reload_type() {
             self.set_reload(reload_type);
             self.manifest_version.update(|v| *v += 1);
         }
+        if let Some(command) = recorded {
+            let description = response.get_description().to_string();
+            self.record(
+                SessionEventKind::Command {
+                    description,
+                    command,
+                },
+                version_before,
+            );
+        }
 
         Ok(response)
     }
 
+    fn push_component_snapshot(&mut self, type_id: &TypeId) {
+        let Some(component) = self.manifest.components.get(type_id) else {
+            return;
+        };
+        let history = self.component_history.entry(type_id.clone()).or_default();
+        history.push_back(component.clone());
+        while history.len() > self.component_history_capacity {
+            history.pop_front();
+        }
+    }
+
+    /// Snapshots of the component after each command that changed it, oldest first.
+    /// At most `component_history_capacity` snapshots are kept per component
+    pub fn get_component_history(&self, type_id: &TypeId) -> Vec<&ComponentDefinition> {
+        self.component_history
+            .get(type_id)
+            .map(|history| history.iter().collect())
+            .unwrap_or_default()
+    }
+
+    /// Restores the component to the snapshot at `index` of its history (see `get_component_history`).
+    /// The restore is itself an undoable command, and adds a new snapshot to the history
+    pub fn restore_component_to_snapshot(
+        &mut self,
+        type_id: &TypeId,
+        index: usize,
+    ) -> Result<(), String> {
+        let snapshot = self
+            .component_history
+            .get(type_id)
+            .and_then(|history| history.get(index))
+            .cloned()
+            .ok_or_else(|| format!("No snapshot {} in history of component {}", index, type_id))?;
+        self.execute_command(template::RestoreComponentRequest::new(snapshot))?;
+        Ok(())
+    }
+
+    pub fn set_component_history_capacity(&mut self, capacity: usize) {
+        self.component_history_capacity = capacity;
+        for history in self.component_history.values_mut() {
+            while history.len() > capacity {
+                history.pop_front();
+            }
+        }
+    }
+
+    /// Records `component` as the version of its type last written to disk, the baseline of
+    /// `diff_against_saved`
+    pub fn mark_saved(&mut self, component: ComponentDefinition) {
+        self.saved_components
+            .insert(component.type_id.clone(), component);
+    }
+
+    /// Component `type_id` as last written to disk, if it was loaded or saved at all
+    pub fn get_saved_component(&self, type_id: &TypeId) -> Option<&ComponentDefinition> {
+        self.saved_components.get(type_id)
+    }
+
+    /// Changes made to component `type_id` since it was last saved. Components that were never
+    /// saved, e.g. components created in the designer, are compared against an empty component
+    pub fn diff_against_saved(&self, type_id: &TypeId) -> Result<diff::ComponentDiff> {
+        self.diff_against(&self.saved_components, type_id)
+    }
+
+    /// Changes made to component `type_id` since the manifest was loaded, including the ones
+    /// that were saved since
+    pub fn diff_against_loaded(&self, type_id: &TypeId) -> Result<diff::ComponentDiff> {
+        self.diff_against(&self.loaded_components, type_id)
+    }
+
+    fn diff_against(
+        &self,
+        baselines: &HashMap<TypeId, ComponentDefinition>,
+        type_id: &TypeId,
+    ) -> Result<diff::ComponentDiff> {
+        let component = self.get_component(type_id)?;
+        Ok(match baselines.get(type_id) {
+            Some(baseline) => diff::diff_components(baseline, component),
+            None => {
+                let empty = ComponentDefinition {
+                    template: None,
+                    settings: None,
+                    ..component.clone()
+                };
+                diff::diff_components(&empty, component)
+            }
+        })
+    }
+
     pub fn undo(&mut self) -> Result<(), String> {
-        if let Some((id, mut command)) = self.undo_stack.pop() {
+        if let Some((id, description, mut command)) = self.undo_stack.pop() {
+            let version_before = self.manifest_version.get();
             command.undo(&mut self.manifest)?;
-            self.redo_stack.push((id, command));
+            self.redo_stack.push((id, description.clone(), command));
             self.manifest_version.update(|v| *v += 1);
             self.set_reload(ReloadType::FullEdit);
+            self.record(SessionEventKind::Undo { description }, version_before);
         }
         Ok(())
     }
 
     pub fn redo(&mut self) -> Result<(), String> {
-        if let Some((id, mut command)) = self.redo_stack.pop() {
+        if let Some((id, description, mut command)) = self.redo_stack.pop() {
+            let version_before = self.manifest_version.get();
             command.redo(&mut self.manifest)?;
-            self.undo_stack.push((id, command));
+            self.undo_stack.push((id, description.clone(), command));
             self.manifest_version.update(|v| *v += 1);
             self.set_reload(ReloadType::FullEdit);
+            self.record(SessionEventKind::Redo { description }, version_before);
         }
         Ok(())
     }
 
+    /// Starts recording the session from the current state, replacing the current recording if
+    /// any, see `session`
+    pub fn start_recording(&mut self, options: RecordingOptions) {
+        let stack = |stack: &Vec<(usize, String, UndoRedoCommand)>| {
+            stack
+                .iter()
+                .map(|(_, description, command)| (description.clone(), command.clone()))
+                .collect()
+        };
+        let start = SessionState {
+            manifest: self.manifest.clone(),
+            undo_stack: stack(&self.undo_stack),
+            redo_stack: stack(&self.redo_stack),
+        };
+        self.recorder = Some(SessionRecorder::new(options, start));
+    }
+
+    /// Stops recording the session, returning what was recorded
+    pub fn stop_recording(&mut self) -> Option<SessionRecording> {
+        self.recorder.take().map(|recorder| recorder.recording())
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Whether the session is being recorded with string literal values redacted
+    pub fn is_recording_redacted(&self) -> bool {
+        self.recorder
+            .as_ref()
+            .is_some_and(|recorder| recorder.is_redacted())
+    }
+
+    /// The session recorded so far, within the recording window
+    pub fn get_session_recording(&self) -> Option<SessionRecording> {
+        self.recorder.as_ref().map(|recorder| recorder.recording())
+    }
+
+    /// Events recorded since the last call, to append to the session log of the design server
+    pub fn take_session_log(&mut self) -> Option<SessionLogEntries> {
+        self.recorder.as_mut()?.take_log()
+    }
+
+    /// Records that the designer performed action `name` (e.g. its type name), if recording
+    pub fn record_action(&mut self, name: &str) {
+        let name = name.to_string();
+        self.record(
+            SessionEventKind::Action { name },
+            self.manifest_version.get(),
+        );
+    }
+
+    /// Records that `key` was pressed or released in the designer, if recording
+    pub fn record_input(&mut self, key: &str, pressed: bool) {
+        let key = key.to_string();
+        self.record(
+            SessionEventKind::Input { key, pressed },
+            self.manifest_version.get(),
+        );
+    }
+
+    fn record(&mut self, kind: SessionEventKind, version_before: usize) {
+        if let Some(recorder) = &mut self.recorder {
+            let version_after = self.manifest_version.get();
+            recorder.record(kind, &self.manifest, version_before, version_after);
+        }
+    }
+
     pub fn get_last_undo_id(&self) -> Option<usize> {
         self.undo_stack.last().map(|l| l.0)
     }
 
+    /// Description of the command that `undo` would revert next
+    pub fn get_last_undo_description(&self) -> Option<&str> {
+        self.undo_stack.last().map(|l| l.1.as_str())
+    }
+
+    /// Description of the command that `redo` would re-apply next
+    pub fn get_last_redo_description(&self) -> Option<&str> {
+        self.redo_stack.last().map(|l| l.1.as_str())
+    }
+
+    /// (command id, description) of each undoable command, oldest first
+    pub fn get_undo_history(&self) -> Vec<(usize, String)> {
+        self.undo_stack
+            .iter()
+            .map(|(id, description, _)| (*id, description.clone()))
+            .collect()
+    }
+
+    /// (command id, description) of each redoable command, next to be redone last
+    pub fn get_redo_history(&self) -> Vec<(usize, String)> {
+        self.redo_stack
+            .iter()
+            .map(|(id, description, _)| (*id, description.clone()))
+            .collect()
+    }
+
     pub fn undo_until(&mut self, command_id: Option<usize>) -> Result<(), String> {
-        while let Some((id, _)) = self.undo_stack.last() {
+        while let Some((id, _, _)) = self.undo_stack.last() {
             if command_id.is_some_and(|c_id| c_id == *id) {
                 break;
             }
@@ -380,7 +696,7 @@ impl PaxManifestORM {
     }
 
     pub fn redo_including(&mut self, command_id: usize) -> Result<(), String> {
-        while let Some(&(id, _)) = self.redo_stack.last() {
+        while let Some(&(id, _, _)) = self.redo_stack.last() {
             self.redo()?;
             if id == command_id {
                 break;
@@ -399,6 +715,192 @@ impl PaxManifestORM {
         Ok(resp.get_id())
     }
 
+    /// Makes node `uni`, which its component inherited with `@extends`, an override of the
+    /// labeled node of the base: it's written back as `@override(label="..")` and can be edited
+    pub fn override_inherited_node(
+        &mut self,
+        uni: UniqueTemplateNodeIdentifier,
+    ) -> Result<usize, String> {
+        let command = template::OverrideInheritedNodeRequest::new(uni);
+        let resp = self.execute_command(command)?;
+        Ok(resp.get_id())
+    }
+
+    /// The name designers gave node `uni`, if any, see `TemplateNodeDefinition::label`
+    pub fn get_node_label(&self, uni: &UniqueTemplateNodeIdentifier) -> Option<String> {
+        self.manifest
+            .components
+            .get(&uni.get_containing_component_type_id())?
+            .template
+            .as_ref()?
+            .get_node(&uni.get_template_node_id())?
+            .label
+            .clone()
+    }
+
+    /// Names node `uni` for designers, or clears its name with `None`.  Blank labels clear it too
+    pub fn set_node_label(
+        &mut self,
+        uni: UniqueTemplateNodeIdentifier,
+        label: Option<String>,
+    ) -> Result<usize, String> {
+        let command = template::SetNodeLabelRequest::new(uni, label);
+        let resp = self.execute_command(command)?;
+        Ok(resp.get_id())
+    }
+
+    /// Makes node `uni` instantiate `type_id` instead, keeping its children and the settings the
+    /// new type has a property for.  Used to fix nodes whose component no longer exists
+    pub fn replace_node_type(
+        &mut self,
+        uni: UniqueTemplateNodeIdentifier,
+        type_id: TypeId,
+    ) -> Result<usize, String> {
+        let command = template::ReplaceNodeTypeRequest::new(uni, type_id);
+        let resp = self.execute_command(command)?;
+        Ok(resp.get_id())
+    }
+
+    /// The design tokens of the manifest, by name, see `tokens`
+    pub fn get_tokens(&self) -> &BTreeMap<String, PaxValue> {
+        &self.manifest.tokens
+    }
+
+    /// Sets design token `name` to `value`, adding it if it's new.  Every node referencing the token
+    /// takes the new value on reload
+    pub fn set_token(&mut self, name: String, value: PaxValue) -> Result<usize, String> {
+        let command = tokens::SetTokenRequest::new(name, value);
+        let resp = self.execute_command(command)?;
+        Ok(resp.get_id())
+    }
+
+    /// The selector blocks of component `type_id`, with the number of nodes matching each
+    pub fn get_selectors(&self, type_id: &TypeId) -> Vec<selectors::SelectorInfo> {
+        self.manifest
+            .components
+            .get(type_id)
+            .map(selectors::list_selectors)
+            .unwrap_or_default()
+    }
+
+    /// The settings of `selector` in component `type_id`, over all of its blocks
+    pub fn get_selector_settings(
+        &self,
+        type_id: &TypeId,
+        selector: &str,
+    ) -> Vec<(Token, ValueDefinition)> {
+        self.manifest
+            .components
+            .get(type_id)
+            .map(|component| selectors::selector_settings(component, selector))
+            .unwrap_or_default()
+    }
+
+    pub fn get_selector_property(
+        &self,
+        type_id: &TypeId,
+        selector: &str,
+        key: &str,
+    ) -> Option<ValueDefinition> {
+        self.get_selector_settings(type_id, selector)
+            .into_iter()
+            .find(|(token, _)| token.token_value == key)
+            .map(|(_, value)| value)
+    }
+
+    /// The type of property `key` of the nodes of component `type_id` matched by `selector`,
+    /// from the first of them that has it
+    pub fn get_selector_property_type(
+        &self,
+        type_id: &TypeId,
+        selector: &str,
+        key: &str,
+    ) -> Option<TypeId> {
+        let template = self.manifest.components.get(type_id)?.template.as_ref()?;
+        template
+            .get_nodes()
+            .into_iter()
+            .filter(|tnd| PaxManifest::selector_matches(&tnd.settings, selector))
+            .find_map(|tnd| {
+                self.manifest
+                    .type_table
+                    .get(&tnd.type_id)?
+                    .property_definitions
+                    .iter()
+                    .find(|v| v.name == key)
+                    .map(|v| v.type_id.clone())
+            })
+    }
+
+    /// Where the value of property `key` of node `uni` comes from, the node itself or one of the
+    /// selectors matching it, resolved as the runtime does
+    pub fn get_property_source(
+        &self,
+        uni: &UniqueTemplateNodeIdentifier,
+        key: &str,
+    ) -> Option<SettingSource> {
+        let component = self
+            .manifest
+            .components
+            .get(&uni.get_containing_component_type_id())?;
+        let tnd = self.manifest.get_template_node(uni)?;
+        PaxManifest::resolve_settings_with_provenance(&tnd.settings, &component.settings)?
+            .into_iter()
+            .find_map(|(setting, source)| match setting {
+                SettingElement::Setting(token, _) if token.token_value == key => Some(source),
+                _ => None,
+            })
+    }
+
+    /// Adds a selector block `selector`, e.g. `.card-title`, to component `type_id`
+    pub fn add_selector(
+        &mut self,
+        type_id: TypeId,
+        selector: String,
+        elements: Vec<SettingElement>,
+    ) -> Result<usize, String> {
+        let command = selectors::AddSelectorRequest::new(type_id, selector, elements);
+        let resp = self.execute_command(command)?;
+        Ok(resp.get_id())
+    }
+
+    /// Sets property `key` of `selector` to `value`, or removes it if `value` is `None`
+    pub fn set_selector_property(
+        &mut self,
+        type_id: TypeId,
+        selector: String,
+        key: String,
+        value: Option<ValueDefinition>,
+    ) -> Result<usize, String> {
+        let command = selectors::SetSelectorPropertyRequest::new(type_id, selector, key, value);
+        let resp = self.execute_command(command)?;
+        Ok(resp.get_id())
+    }
+
+    /// Renames `selector` to `new_selector`, along with the classes or ids referencing it
+    pub fn rename_selector(
+        &mut self,
+        type_id: TypeId,
+        selector: String,
+        new_selector: String,
+    ) -> Result<usize, String> {
+        let command = selectors::RenameSelectorRequest::new(type_id, selector, new_selector);
+        let resp = self.execute_command(command)?;
+        Ok(resp.get_id())
+    }
+
+    /// Deletes `selector`, see `selectors::DeleteSelectorPolicy` for what happens to its nodes
+    pub fn delete_selector(
+        &mut self,
+        type_id: TypeId,
+        selector: String,
+        policy: selectors::DeleteSelectorPolicy,
+    ) -> Result<usize, String> {
+        let command = selectors::DeleteSelectorRequest::new(type_id, selector, policy);
+        let resp = self.execute_command(command)?;
+        Ok(resp.get_id())
+    }
+
     pub fn component_has_slots(&self, type_id: &TypeId) -> bool {
         let Some(component) = self.manifest.components.get(type_id) else {
             return false;
@@ -414,7 +916,7 @@ pub trait Undo {
     fn undo(&mut self, manifest: &mut PaxManifest) -> Result<(), String>;
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub enum UndoRedoCommand {
     AddTemplateNodeRequest(Box<template::AddTemplateNodeRequest>),
     RemoveTemplateNodeRequest(Box<template::RemoveTemplateNodeRequest>),
@@ -424,6 +926,16 @@ pub enum UndoRedoCommand {
     ReplaceTemplateRequest(Box<template::ReplaceTemplateRequest>),
     ConvertToComponentRequest(Box<template::ConvertToComponentRequest>),
     SwapMainComponentRequest(Box<template::SwapMainComponentRequest>),
+    RestoreComponentRequest(Box<template::RestoreComponentRequest>),
+    AddComponentsRequest(Box<template::AddComponentsRequest>),
+    OverrideInheritedNodeRequest(Box<template::OverrideInheritedNodeRequest>),
+    SetNodeLabelRequest(Box<template::SetNodeLabelRequest>),
+    ReplaceNodeTypeRequest(Box<template::ReplaceNodeTypeRequest>),
+    SetTokenRequest(Box<tokens::SetTokenRequest>),
+    AddSelectorRequest(Box<selectors::AddSelectorRequest>),
+    SetSelectorPropertyRequest(Box<selectors::SetSelectorPropertyRequest>),
+    RenameSelectorRequest(Box<selectors::RenameSelectorRequest>),
+    DeleteSelectorRequest(Box<selectors::DeleteSelectorRequest>),
 }
 
 impl UndoRedoCommand {
@@ -437,37 +949,71 @@ impl UndoRedoCommand {
             UndoRedoCommand::ReplaceTemplateRequest(command) => command.undo(manifest),
             UndoRedoCommand::ConvertToComponentRequest(command) => command.undo(manifest),
             UndoRedoCommand::SwapMainComponentRequest(command) => command.undo(manifest),
+            UndoRedoCommand::RestoreComponentRequest(command) => command.undo(manifest),
+            UndoRedoCommand::AddComponentsRequest(command) => command.undo(manifest),
+            UndoRedoCommand::OverrideInheritedNodeRequest(command) => command.undo(manifest),
+            UndoRedoCommand::SetNodeLabelRequest(command) => command.undo(manifest),
+            UndoRedoCommand::ReplaceNodeTypeRequest(command) => command.undo(manifest),
+            UndoRedoCommand::SetTokenRequest(command) => command.undo(manifest),
+            UndoRedoCommand::AddSelectorRequest(command) => command.undo(manifest),
+            UndoRedoCommand::SetSelectorPropertyRequest(command) => command.undo(manifest),
+            UndoRedoCommand::RenameSelectorRequest(command) => command.undo(manifest),
+            UndoRedoCommand::DeleteSelectorRequest(command) => command.undo(manifest),
         }
     }
 
     fn redo(&mut self, manifest: &mut PaxManifest) -> Result<(), String> {
+        let _ = self.execute(manifest);
+        Ok(())
+    }
+
+    fn execute(&mut self, manifest: &mut PaxManifest) -> Result<(), String> {
         match self {
             UndoRedoCommand::AddTemplateNodeRequest(command) => {
-                let _ = command.execute(manifest);
+                command.execute(manifest).map(|_| ())
             }
             UndoRedoCommand::RemoveTemplateNodeRequest(command) => {
-                let _ = command.execute(manifest);
+                command.execute(manifest).map(|_| ())
             }
             UndoRedoCommand::MoveTemplateNodeRequest(command) => {
-                let _ = command.execute(manifest);
+                command.execute(manifest).map(|_| ())
             }
             UndoRedoCommand::UpdateTemplateNodeRequest(command) => {
-                let _ = command.execute(manifest);
-            }
-            UndoRedoCommand::PasteSubTreeRequest(command) => {
-                let _ = command.execute(manifest);
+                command.execute(manifest).map(|_| ())
             }
+            UndoRedoCommand::PasteSubTreeRequest(command) => command.execute(manifest).map(|_| ()),
             UndoRedoCommand::ReplaceTemplateRequest(command) => {
-                let _ = command.execute(manifest);
+                command.execute(manifest).map(|_| ())
             }
             UndoRedoCommand::ConvertToComponentRequest(command) => {
-                let _ = command.execute(manifest);
+                command.execute(manifest).map(|_| ())
             }
             UndoRedoCommand::SwapMainComponentRequest(command) => {
-                let _ = command.execute(manifest);
+                command.execute(manifest).map(|_| ())
+            }
+            UndoRedoCommand::RestoreComponentRequest(command) => {
+                command.execute(manifest).map(|_| ())
+            }
+            UndoRedoCommand::AddComponentsRequest(command) => command.execute(manifest).map(|_| ()),
+            UndoRedoCommand::OverrideInheritedNodeRequest(command) => {
+                command.execute(manifest).map(|_| ())
+            }
+            UndoRedoCommand::SetNodeLabelRequest(command) => command.execute(manifest).map(|_| ()),
+            UndoRedoCommand::ReplaceNodeTypeRequest(command) => {
+                command.execute(manifest).map(|_| ())
+            }
+            UndoRedoCommand::SetTokenRequest(command) => command.execute(manifest).map(|_| ()),
+            UndoRedoCommand::AddSelectorRequest(command) => command.execute(manifest).map(|_| ()),
+            UndoRedoCommand::SetSelectorPropertyRequest(command) => {
+                command.execute(manifest).map(|_| ())
+            }
+            UndoRedoCommand::RenameSelectorRequest(command) => {
+                command.execute(manifest).map(|_| ())
+            }
+            UndoRedoCommand::DeleteSelectorRequest(command) => {
+                command.execute(manifest).map(|_| ())
             }
         }
-        Ok(())
     }
 }
 
@@ -491,7 +1037,30 @@ impl Interpolatable for SubTrees {}
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct SubTrees {
-    roots: Vec<TemplateNodeId>,
-    children: HashMap<TemplateNodeId, Vec<TemplateNodeId>>,
-    nodes: HashMap<TemplateNodeId, TemplateNodeDefinition>,
+    pub(crate) roots: Vec<TemplateNodeId>,
+    pub(crate) children: HashMap<TemplateNodeId, Vec<TemplateNodeId>>,
+    pub(crate) nodes: HashMap<TemplateNodeId, TemplateNodeDefinition>,
+}
+
+impl SubTrees {
+    /// Copies the subtrees of `template` rooted at `roots`
+    pub fn from_template(template: &ComponentTemplate, roots: &[TemplateNodeId]) -> Self {
+        let mut children = HashMap::new();
+        let mut nodes = HashMap::new();
+        let mut to_visit: Vec<_> = roots.iter().cloned().collect();
+        while let Some(node) = to_visit.pop() {
+            if let Some(node_def) = template.get_node(&node) {
+                nodes.insert(node.clone(), node_def.clone());
+                let node_children = template.get_children(&node).unwrap_or_default();
+                children.insert(node.clone(), node_children.clone());
+                to_visit.extend(node_children);
+            }
+        }
+
+        SubTrees {
+            roots: roots.to_vec(),
+            children,
+            nodes,
+        }
+    }
 }
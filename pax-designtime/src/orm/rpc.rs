@@ -0,0 +1,202 @@
+//! # JSON-RPC interface to `PaxManifestORM`
+//!
+//! Lets tools that aren't written in Rust (e.g. editor plugins) manipulate a manifest through the
+//! ORM, by exchanging [JSON-RPC 2.0](https://www.jsonrpc.org/specification) messages, one per
+//! line, e.g. over the stdio of `pax-cli manifest-rpc`.  The params and results of the methods
+//! are the ORM's command and response structs, serialized to JSON:
+//!
+//! | method            | params                                | result                           |
+//! |-------------------|---------------------------------------|----------------------------------|
+//! | `get_manifest`    |                                       | `PaxManifest`                    |
+//! | `build_node`      | `AddTemplateNodeRequest`              | `AddTemplateNodeResponse`        |
+//! | `get_node`        | `GetTemplateNodeRequest`              | `GetTemplateNodeResponse`        |
+//! | `remove_node`     | `RemoveTemplateNodeRequest`           | `RemoveTemplateNodeResponse`     |
+//! | `move_node`       | `MoveTemplateNodeRequest`             | `MoveTemplateNodeResponse`       |
+//! | `get_tokens`      |                                       | map of token names to values     |
+//! | `set_token`       | `SetTokenRequest`                     | `SetTokenResponse`               |
+//! | `undo`, `redo`    |                                       | `UndoRedoHistory`                |
+//!
+//! Fields of the command structs starting with `_` are state cached for undo, and can be left out.
+//! Commands failing, e.g. because the node doesn't exist, are reported as errors with code
+//! [`COMMAND_FAILED`] and the message of the failure.
+
+use std::io::{BufRead, Write};
+
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::template::{
+    AddTemplateNodeRequest, GetTemplateNodeRequest, MoveTemplateNodeRequest,
+    RemoveTemplateNodeRequest,
+};
+use super::tokens::SetTokenRequest;
+use super::{Command, PaxManifestORM, Request};
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+/// The ORM rejected the command
+pub const COMMAND_FAILED: i64 = -32000;
+
+#[derive(Deserialize)]
+struct RpcRequest {
+    jsonrpc: String,
+    /// Absent for notifications, which aren't answered
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        RpcError {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// Result of `undo` and `redo`: (command id, description) of the commands left to undo and redo,
+/// as `PaxManifestORM::get_undo_history` and `get_redo_history`
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct UndoRedoHistory {
+    pub undo: Vec<(usize, String)>,
+    pub redo: Vec<(usize, String)>,
+}
+
+pub struct ManifestRpcServer {
+    orm: PaxManifestORM,
+}
+
+impl ManifestRpcServer {
+    pub fn new(orm: PaxManifestORM) -> Self {
+        ManifestRpcServer { orm }
+    }
+
+    pub fn get_orm(&self) -> &PaxManifestORM {
+        &self.orm
+    }
+
+    pub fn into_orm(self) -> PaxManifestORM {
+        self.orm
+    }
+
+    /// Answers each line of `input` with a line on `output`, until `input` ends
+    pub fn serve(&mut self, input: impl BufRead, mut output: impl Write) -> std::io::Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle_message(&line) {
+                writeln!(output, "{}", response)?;
+                output.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The serialized response to the serialized request `message`, `None` for notifications
+    pub fn handle_message(&mut self, message: &str) -> Option<String> {
+        let request = match serde_json::from_str::<Value>(message) {
+            Ok(request) => request,
+            Err(e) => {
+                return Some(error_response(
+                    Value::Null,
+                    RpcError::new(PARSE_ERROR, e.to_string()),
+                ))
+            }
+        };
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let request: RpcRequest = match serde_json::from_value(request) {
+            Ok(request) => request,
+            Err(e) => {
+                return Some(error_response(
+                    id,
+                    RpcError::new(INVALID_REQUEST, e.to_string()),
+                ))
+            }
+        };
+        if request.jsonrpc != "2.0" {
+            return Some(error_response(
+                id,
+                RpcError::new(INVALID_REQUEST, "jsonrpc must be \"2.0\""),
+            ));
+        }
+
+        let result = self.call(&request.method, request.params);
+        let id = request.id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }).to_string(),
+            Err(error) => error_response(id, error),
+        })
+    }
+
+    /// Runs `method` with `params` against the ORM
+    pub fn call(&mut self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "get_manifest" => to_value(self.orm.get_manifest()),
+            "build_node" => self.execute(parse_params::<AddTemplateNodeRequest>(params)?),
+            "get_node" => self.execute(parse_params::<GetTemplateNodeRequest>(params)?),
+            "remove_node" => self.execute(parse_params::<RemoveTemplateNodeRequest>(params)?),
+            "move_node" => self.execute(parse_params::<MoveTemplateNodeRequest>(params)?),
+            "get_tokens" => to_value(self.orm.get_tokens()),
+            "set_token" => self.execute(parse_params::<SetTokenRequest>(params)?),
+            "undo" => {
+                self.orm
+                    .undo()
+                    .map_err(|e| RpcError::new(COMMAND_FAILED, e))?;
+                to_value(&self.history())
+            }
+            "redo" => {
+                self.orm
+                    .redo()
+                    .map_err(|e| RpcError::new(COMMAND_FAILED, e))?;
+                to_value(&self.history())
+            }
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Unknown method {}", method),
+            )),
+        }
+    }
+
+    fn execute<R: Request, C: Command<R>>(&mut self, command: C) -> Result<Value, RpcError>
+    where
+        R::Response: serde::Serialize,
+    {
+        let response = self
+            .orm
+            .execute_command(command)
+            .map_err(|e| RpcError::new(COMMAND_FAILED, e))?;
+        to_value(&response)
+    }
+
+    fn history(&self) -> UndoRedoHistory {
+        UndoRedoHistory {
+            undo: self.orm.get_undo_history(),
+            redo: self.orm.get_redo_history(),
+        }
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+fn to_value(value: &impl serde::Serialize) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|e| RpcError::new(COMMAND_FAILED, e.to_string()))
+}
+
+fn error_response(id: Value, error: RpcError) -> String {
+    json!({ "jsonrpc": "2.0", "id": id, "error": error }).to_string()
+}
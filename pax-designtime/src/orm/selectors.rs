@@ -0,0 +1,495 @@
+//! Selector blocks: settings shared by the nodes of a component, e.g. `.card-title { fill: RED }`
+//! for the nodes with `class=card-title` and `#header { ... }` for the node with `id=header`.
+//! Nodes resolve them as the runtime does, see `PaxManifest::resolve_settings_with_provenance`,
+//! so editing a selector restyles every node it matches.
+
+use pax_manifest::cartridge_generation::SettingSource;
+use pax_manifest::{
+    ComponentDefinition, LiteralBlockDefinition, PaxIdentifier, PaxManifest, SettingElement,
+    SettingsBlockElement, Token, TypeId, ValueDefinition,
+};
+use serde_derive::{Deserialize, Serialize};
+
+use super::{Command, ReloadType, Request, Response, Undo, UndoRedoCommand};
+
+/// A selector block of a component, see `PaxManifestORM::get_selectors`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SelectorInfo {
+    pub selector: String,
+    /// Number of nodes of the component matched by the selector
+    pub node_count: usize,
+}
+
+/// What happens to the nodes styled by a deleted selector, see `DeleteSelectorRequest`
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeleteSelectorPolicy {
+    /// The nodes lose the settings of the selector
+    LeaveUnstyled,
+    /// The settings the nodes took from the selector are set on the nodes themselves
+    InlineValues,
+}
+
+/// The attribute of the nodes matched by `selector`, and the name they reference it by, e.g.
+/// `("class", "card-title")` for `.card-title`
+fn split_selector(selector: &str) -> Result<(&'static str, &str), String> {
+    let (attribute, name) = if let Some(name) = selector.strip_prefix('.') {
+        ("class", name)
+    } else if let Some(name) = selector.strip_prefix('#') {
+        ("id", name)
+    } else {
+        return Err(format!(
+            "\"{}\" isn't a selector, selectors start with . or #",
+            selector
+        ));
+    };
+    let valid = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        return Err(format!("\"{}\" isn't a valid selector name", name));
+    }
+    Ok((attribute, name))
+}
+
+/// Components extending another keep their own selector blocks apart from the inherited ones,
+/// see `TemplateExtension::own_settings`, so those aren't edited here
+fn editable_component<'a>(
+    manifest: &'a mut PaxManifest,
+    type_id: &TypeId,
+) -> Result<&'a mut ComponentDefinition, String> {
+    let component = manifest
+        .components
+        .get_mut(type_id)
+        .ok_or_else(|| format!("Component {} not found", type_id))?;
+    if component.extends.is_some() {
+        return Err("Selectors of components extending another can't be edited".to_string());
+    }
+    Ok(component)
+}
+
+/// The blocks of `settings` with selector `selector`, in order
+fn blocks_with_selector<'a>(
+    settings: &'a mut Option<Vec<SettingsBlockElement>>,
+    selector: &'a str,
+) -> impl Iterator<Item = &'a mut LiteralBlockDefinition> {
+    settings.iter_mut().flatten().filter_map(move |e| match e {
+        SettingsBlockElement::SelectorBlock(token, block) if token.token_value == selector => {
+            Some(block)
+        }
+        _ => None,
+    })
+}
+
+/// Whether `component` has a block with selector `selector`
+pub(crate) fn has_selector(component: &ComponentDefinition, selector: &str) -> bool {
+    component.settings.iter().flatten().any(|e| {
+        matches!(e, SettingsBlockElement::SelectorBlock(token, _) if token.token_value == selector)
+    })
+}
+
+/// The selectors of `component`, in order of appearance, with the number of nodes matching each
+pub(crate) fn list_selectors(component: &ComponentDefinition) -> Vec<SelectorInfo> {
+    let mut selectors: Vec<SelectorInfo> = vec![];
+    for e in component.settings.iter().flatten() {
+        let SettingsBlockElement::SelectorBlock(token, _) = e else {
+            continue;
+        };
+        if selectors.iter().any(|s| s.selector == token.token_value) {
+            continue;
+        }
+        let node_count = component
+            .template
+            .iter()
+            .flat_map(|t| t.get_nodes())
+            .filter(|tnd| PaxManifest::selector_matches(&tnd.settings, &token.token_value))
+            .count();
+        selectors.push(SelectorInfo {
+            selector: token.token_value.clone(),
+            node_count,
+        });
+    }
+    selectors
+}
+
+/// The settings of `selector` in `component`, merged over all its blocks as the runtime does
+pub(crate) fn selector_settings(
+    component: &ComponentDefinition,
+    selector: &str,
+) -> Vec<(Token, ValueDefinition)> {
+    let mut settings: Vec<(Token, ValueDefinition)> = vec![];
+    for e in component.settings.iter().flatten() {
+        let SettingsBlockElement::SelectorBlock(token, block) = e else {
+            continue;
+        };
+        if token.token_value != selector {
+            continue;
+        }
+        for (key, value) in block.get_all_settings() {
+            settings.retain(|(k, _)| k != key);
+            settings.push((key.clone(), value.clone()));
+        }
+    }
+    settings
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SelectorResponse {
+    command_id: Option<usize>,
+    description: Option<String>,
+    _affected_component_type_id: TypeId,
+}
+
+impl SelectorResponse {
+    fn new(type_id: &TypeId) -> Self {
+        Self {
+            command_id: None,
+            description: None,
+            _affected_component_type_id: type_id.clone(),
+        }
+    }
+}
+
+impl Response for SelectorResponse {
+    fn set_id(&mut self, id: usize) {
+        self.command_id = Some(id);
+    }
+    fn get_id(&self) -> usize {
+        self.command_id.unwrap()
+    }
+    fn set_description(&mut self, description: String) {
+        self.description = Some(description);
+    }
+    fn get_description(&self) -> &str {
+        self.description.as_deref().unwrap_or_default()
+    }
+    fn get_affected_components(&self) -> Vec<TypeId> {
+        vec![self._affected_component_type_id.clone()]
+    }
+    fn get_reload_type(&self) -> Option<ReloadType> {
+        // any number of nodes can match the selector
+        Some(ReloadType::FullEdit)
+    }
+}
+
+/// Restores the component the command was executed on as it was before
+fn restore(manifest: &mut PaxManifest, cached: &Option<ComponentDefinition>) -> Result<(), String> {
+    let cached = cached
+        .clone()
+        .ok_or_else(|| "Command wasn't executed".to_string())?;
+    manifest.components.insert(cached.type_id.clone(), cached);
+    Ok(())
+}
+
+/// Adds a selector block, see `PaxManifestORM::add_selector`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AddSelectorRequest {
+    type_id: TypeId,
+    selector: String,
+    elements: Vec<SettingElement>,
+    // Used for Undo/Redo
+    _cached_component: Option<ComponentDefinition>,
+}
+
+impl AddSelectorRequest {
+    pub fn new(type_id: TypeId, selector: String, elements: Vec<SettingElement>) -> Self {
+        Self {
+            type_id,
+            selector,
+            elements,
+            _cached_component: None,
+        }
+    }
+}
+
+impl Request for AddSelectorRequest {
+    type Response = SelectorResponse;
+}
+
+impl Command<AddSelectorRequest> for AddSelectorRequest {
+    fn execute(&mut self, manifest: &mut PaxManifest) -> Result<SelectorResponse, String> {
+        split_selector(&self.selector)?;
+        let component = editable_component(manifest, &self.type_id)?;
+        if has_selector(component, &self.selector) {
+            return Err(format!("Selector {} already exists", self.selector));
+        }
+        self._cached_component = Some(component.clone());
+        component
+            .settings
+            .get_or_insert_with(Vec::new)
+            .push(SettingsBlockElement::SelectorBlock(
+                Token::new_without_location(self.selector.clone()),
+                LiteralBlockDefinition::new(self.elements.clone()),
+            ));
+        Ok(SelectorResponse::new(&self.type_id))
+    }
+
+    fn description(&self) -> String {
+        format!("Add selector {}", self.selector)
+    }
+
+    fn as_undo_redo(&mut self) -> Option<UndoRedoCommand> {
+        Some(UndoRedoCommand::AddSelectorRequest(Box::new(self.clone())))
+    }
+}
+
+impl Undo for AddSelectorRequest {
+    fn undo(&mut self, manifest: &mut PaxManifest) -> Result<(), String> {
+        restore(manifest, &self._cached_component)
+    }
+}
+
+/// Sets or, if `value` is `None`, removes a setting of a selector, see
+/// `PaxManifestORM::set_selector_property`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SetSelectorPropertyRequest {
+    type_id: TypeId,
+    selector: String,
+    name: String,
+    value: Option<ValueDefinition>,
+    // Used for Undo/Redo
+    _cached_component: Option<ComponentDefinition>,
+}
+
+impl SetSelectorPropertyRequest {
+    pub fn new(
+        type_id: TypeId,
+        selector: String,
+        name: String,
+        value: Option<ValueDefinition>,
+    ) -> Self {
+        Self {
+            type_id,
+            selector,
+            name,
+            value,
+            _cached_component: None,
+        }
+    }
+}
+
+impl Request for SetSelectorPropertyRequest {
+    type Response = SelectorResponse;
+}
+
+impl Command<SetSelectorPropertyRequest> for SetSelectorPropertyRequest {
+    fn execute(&mut self, manifest: &mut PaxManifest) -> Result<SelectorResponse, String> {
+        let component = editable_component(manifest, &self.type_id)?;
+        if !has_selector(component, &self.selector) {
+            return Err(format!("Selector {} not found", self.selector));
+        }
+        self._cached_component = Some(component.clone());
+        // the setting is moved to the last block of the selector, where it takes precedence
+        let mut last = None;
+        for block in blocks_with_selector(&mut component.settings, &self.selector) {
+            block.elements.retain(
+                |e| !matches!(e, SettingElement::Setting(key, _) if key.token_value == self.name),
+            );
+            last = Some(block);
+        }
+        if let (Some(value), Some(last)) = (&self.value, last) {
+            last.elements.push(SettingElement::Setting(
+                Token::new_without_location(self.name.clone()),
+                value.clone(),
+            ));
+        }
+        Ok(SelectorResponse::new(&self.type_id))
+    }
+
+    fn description(&self) -> String {
+        match self.value {
+            Some(_) => format!("Set {} of {}", self.name, self.selector),
+            None => format!("Remove {} from {}", self.name, self.selector),
+        }
+    }
+
+    fn as_undo_redo(&mut self) -> Option<UndoRedoCommand> {
+        Some(UndoRedoCommand::SetSelectorPropertyRequest(Box::new(
+            self.clone(),
+        )))
+    }
+}
+
+impl Undo for SetSelectorPropertyRequest {
+    fn undo(&mut self, manifest: &mut PaxManifest) -> Result<(), String> {
+        restore(manifest, &self._cached_component)
+    }
+}
+
+/// Renames a selector, and the class or id of the nodes it matches with it, see
+/// `PaxManifestORM::rename_selector`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RenameSelectorRequest {
+    type_id: TypeId,
+    selector: String,
+    new_selector: String,
+    // Used for Undo/Redo
+    _cached_component: Option<ComponentDefinition>,
+}
+
+impl RenameSelectorRequest {
+    pub fn new(type_id: TypeId, selector: String, new_selector: String) -> Self {
+        Self {
+            type_id,
+            selector,
+            new_selector,
+            _cached_component: None,
+        }
+    }
+}
+
+impl Request for RenameSelectorRequest {
+    type Response = SelectorResponse;
+}
+
+impl Command<RenameSelectorRequest> for RenameSelectorRequest {
+    fn execute(&mut self, manifest: &mut PaxManifest) -> Result<SelectorResponse, String> {
+        let (attribute, name) = split_selector(&self.selector)?;
+        let (new_attribute, new_name) = split_selector(&self.new_selector)?;
+        if attribute != new_attribute {
+            return Err(format!(
+                "Can't rename {} to {}, the nodes are matched by {}",
+                self.selector, self.new_selector, attribute
+            ));
+        }
+        let component = editable_component(manifest, &self.type_id)?;
+        if !has_selector(component, &self.selector) {
+            return Err(format!("Selector {} not found", self.selector));
+        }
+        if has_selector(component, &self.new_selector) {
+            return Err(format!("Selector {} already exists", self.new_selector));
+        }
+        self._cached_component = Some(component.clone());
+
+        for e in component.settings.iter_mut().flatten() {
+            if let SettingsBlockElement::SelectorBlock(token, _) = e {
+                if token.token_value == self.selector {
+                    token.token_value = self.new_selector.clone();
+                }
+            }
+        }
+        for tnd in component
+            .template
+            .iter_mut()
+            .flat_map(|t| t.get_nodes_mut())
+        {
+            for setting in tnd.settings.iter_mut().flatten() {
+                if let SettingElement::Setting(key, ValueDefinition::Identifier(ident)) = setting {
+                    if key.token_value == attribute && ident.name == name {
+                        *ident = PaxIdentifier {
+                            name: new_name.to_string(),
+                        };
+                    }
+                }
+            }
+        }
+        Ok(SelectorResponse::new(&self.type_id))
+    }
+
+    fn description(&self) -> String {
+        format!("Rename {} to {}", self.selector, self.new_selector)
+    }
+
+    fn as_undo_redo(&mut self) -> Option<UndoRedoCommand> {
+        Some(UndoRedoCommand::RenameSelectorRequest(Box::new(
+            self.clone(),
+        )))
+    }
+}
+
+impl Undo for RenameSelectorRequest {
+    fn undo(&mut self, manifest: &mut PaxManifest) -> Result<(), String> {
+        restore(manifest, &self._cached_component)
+    }
+}
+
+/// Deletes all blocks of a selector, see `PaxManifestORM::delete_selector`.  The nodes it
+/// matched lose their class referencing it, ids are kept since nodes are referenced by them too
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DeleteSelectorRequest {
+    type_id: TypeId,
+    selector: String,
+    policy: DeleteSelectorPolicy,
+    // Used for Undo/Redo
+    _cached_component: Option<ComponentDefinition>,
+}
+
+impl DeleteSelectorRequest {
+    pub fn new(type_id: TypeId, selector: String, policy: DeleteSelectorPolicy) -> Self {
+        Self {
+            type_id,
+            selector,
+            policy,
+            _cached_component: None,
+        }
+    }
+}
+
+impl Request for DeleteSelectorRequest {
+    type Response = SelectorResponse;
+}
+
+impl Command<DeleteSelectorRequest> for DeleteSelectorRequest {
+    fn execute(&mut self, manifest: &mut PaxManifest) -> Result<SelectorResponse, String> {
+        let (attribute, name) = split_selector(&self.selector)?;
+        let component = editable_component(manifest, &self.type_id)?;
+        if !has_selector(component, &self.selector) {
+            return Err(format!("Selector {} not found", self.selector));
+        }
+        self._cached_component = Some(component.clone());
+
+        let settings_block = component.settings.clone();
+        for tnd in component
+            .template
+            .iter_mut()
+            .flat_map(|t| t.get_nodes_mut())
+        {
+            if !PaxManifest::selector_matches(&tnd.settings, &self.selector) {
+                continue;
+            }
+            let inlined: Vec<_> = match self.policy {
+                DeleteSelectorPolicy::LeaveUnstyled => vec![],
+                DeleteSelectorPolicy::InlineValues => {
+                    PaxManifest::resolve_settings_with_provenance(&tnd.settings, &settings_block)
+                        .into_iter()
+                        .flatten()
+                        .filter(|(_, source)| {
+                            matches!(source, SettingSource::Selector(s) if *s == self.selector)
+                        })
+                        .map(|(e, _)| e)
+                        .collect()
+                }
+            };
+            let settings = tnd.settings.get_or_insert_with(Vec::new);
+            if attribute == "class" {
+                settings.retain(|e| {
+                    !matches!(e, SettingElement::Setting(key, ValueDefinition::Identifier(ident))
+                        if key.token_value == attribute && ident.name == name)
+                });
+            }
+            settings.extend(inlined);
+        }
+        if let Some(settings) = &mut component.settings {
+            settings.retain(|e| {
+                !matches!(e, SettingsBlockElement::SelectorBlock(token, _) if token.token_value == self.selector)
+            });
+        }
+        Ok(SelectorResponse::new(&self.type_id))
+    }
+
+    fn description(&self) -> String {
+        format!("Delete selector {}", self.selector)
+    }
+
+    fn as_undo_redo(&mut self) -> Option<UndoRedoCommand> {
+        Some(UndoRedoCommand::DeleteSelectorRequest(Box::new(
+            self.clone(),
+        )))
+    }
+}
+
+impl Undo for DeleteSelectorRequest {
+    fn undo(&mut self, manifest: &mut PaxManifest) -> Result<(), String> {
+        restore(manifest, &self._cached_component)
+    }
+}
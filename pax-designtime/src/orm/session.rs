@@ -0,0 +1,535 @@
+//! # Session recording
+//!
+//! An opt-in recorder of everything that changes the manifest in the designer, for reproducing
+//! bugs like "I did something and the manifest got corrupted".  While recording (see
+//! `PaxManifestORM::start_recording`), each undoable command is recorded as it was before it was
+//! executed, along with undos and redos, manifest reloads, and for context the designer actions
+//! performed and keys pressed.  Every event changing the manifest is a checkpoint: it records the
+//! manifest version before and after, and a hash of the resulting manifest.
+//!
+//! A [`SessionRecording`] starts from a snapshot of the manifest and the undo/redo stacks, so it
+//! replays on its own: [`SessionRecording::replay`] re-executes the events against the snapshot
+//! and stops at the first event whose result doesn't hash to the recorded checkpoint, or that
+//! fails, pinpointing the first non-deterministic or buggy command.  The recorder keeps the last
+//! [`RecordingOptions::window_ms`] of events, folding older ones into the starting snapshot, so
+//! that a recording can be left running and exported into a [`BugReport`] when something goes
+//! wrong.  New events are also appended to a session log kept by the design server, see
+//! `PaxManifestORM::take_session_log`.
+//!
+//! String literal values (e.g. the text of `Text` nodes) can be redacted, see
+//! [`RecordingOptions::redact_strings`].  Hashes are then of the redacted manifests, so redacted
+//! recordings replay the same way.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::{self, Display};
+use std::hash::{Hash, Hasher};
+
+use pax_manifest::{PaxManifest, TypeId};
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{PaxManifestORM, UndoRedoCommand};
+
+/// Version of the recording format, bumped on incompatible changes
+pub const SESSION_FORMAT_VERSION: u32 = 1;
+
+/// How much of a session is kept by default, see `RecordingOptions::window_ms`
+pub const DEFAULT_RECORDING_WINDOW_MS: f64 = 10.0 * 60.0 * 1000.0;
+
+/// Replaces string literal values in redacted recordings
+pub const REDACTED: &str = "<redacted>";
+
+/// Number of actions and key presses preceding a divergence reported with it
+const DIVERGENCE_CONTEXT_LEN: usize = 10;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RecordingOptions {
+    /// Events older than this are folded into the starting snapshot, `None` to keep everything
+    pub window_ms: Option<f64>,
+    /// Whether to replace string literal values (and the characters typed) with `REDACTED`
+    pub redact_strings: bool,
+}
+
+impl Default for RecordingOptions {
+    fn default() -> Self {
+        RecordingOptions {
+            window_ms: Some(DEFAULT_RECORDING_WINDOW_MS),
+            redact_strings: false,
+        }
+    }
+}
+
+/// The state of the ORM that events are replayed against
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SessionState {
+    pub manifest: PaxManifest,
+    /// (description, command) of each undoable command, oldest first
+    pub undo_stack: Vec<(String, UndoRedoCommand)>,
+    /// (description, command) of each redoable command, next to be redone last
+    pub redo_stack: Vec<(String, UndoRedoCommand)>,
+}
+
+impl SessionState {
+    /// Applies `event` the way the ORM did when it was recorded
+    pub fn apply(&mut self, event: &SessionEventKind) -> Result<(), String> {
+        match event {
+            SessionEventKind::Command {
+                description,
+                command,
+            } => {
+                let mut command = command.clone();
+                command.execute(&mut self.manifest)?;
+                self.undo_stack.push((description.clone(), command));
+                self.redo_stack.clear();
+            }
+            SessionEventKind::Undo { description } => {
+                let (top, mut command) = self
+                    .undo_stack
+                    .pop()
+                    .ok_or_else(|| format!("Nothing to undo, expected \"{}\"", description))?;
+                if top != *description {
+                    return Err(format!("Undid \"{}\", expected \"{}\"", top, description));
+                }
+                command.undo(&mut self.manifest)?;
+                self.redo_stack.push((top, command));
+            }
+            SessionEventKind::Redo { description } => {
+                let (top, mut command) = self
+                    .redo_stack
+                    .pop()
+                    .ok_or_else(|| format!("Nothing to redo, expected \"{}\"", description))?;
+                if top != *description {
+                    return Err(format!("Redid \"{}\", expected \"{}\"", top, description));
+                }
+                command.redo(&mut self.manifest)?;
+                self.undo_stack.push((top, command));
+            }
+            SessionEventKind::ManifestLoaded { manifest } => {
+                self.manifest = (**manifest).clone();
+            }
+            SessionEventKind::MainComponentSet { type_id } => {
+                self.manifest.main_component_type_id = type_id.clone();
+            }
+            SessionEventKind::Action { .. } | SessionEventKind::Input { .. } => {}
+        }
+        Ok(())
+    }
+
+    /// An ORM in this state, e.g. to continue from the end of a replay in a test
+    pub fn into_orm(self) -> PaxManifestORM {
+        let mut orm = PaxManifestORM::new(self.manifest);
+        for (description, command) in self.undo_stack {
+            orm.undo_stack
+                .push((orm.next_command_id, description, command));
+            orm.next_command_id += 1;
+        }
+        for (description, command) in self.redo_stack {
+            orm.redo_stack
+                .push((orm.next_command_id, description, command));
+            orm.next_command_id += 1;
+        }
+        orm
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SessionEvent {
+    /// Milliseconds since the recording started
+    pub time_ms: f64,
+    pub kind: SessionEventKind,
+    /// For events changing the manifest
+    pub checkpoint: Option<Checkpoint>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub enum SessionEventKind {
+    /// An undoable command, as it was before it was executed
+    Command {
+        description: String,
+        command: UndoRedoCommand,
+    },
+    Undo {
+        description: String,
+    },
+    Redo {
+        description: String,
+    },
+    /// The manifest was replaced, e.g. reloaded after the project changed on disk
+    ManifestLoaded {
+        manifest: Box<PaxManifest>,
+    },
+    MainComponentSet {
+        type_id: TypeId,
+    },
+    /// A designer action, by type name, for context only
+    Action {
+        name: String,
+    },
+    /// A key press or release in the designer, for context only
+    Input {
+        key: String,
+        pressed: bool,
+    },
+}
+
+impl SessionEventKind {
+    fn changes_manifest(&self) -> bool {
+        !matches!(
+            self,
+            SessionEventKind::Action { .. } | SessionEventKind::Input { .. }
+        )
+    }
+}
+
+impl Display for SessionEventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionEventKind::Command { description, .. } => write!(f, "{}", description),
+            SessionEventKind::Undo { description } => write!(f, "Undo \"{}\"", description),
+            SessionEventKind::Redo { description } => write!(f, "Redo \"{}\"", description),
+            SessionEventKind::ManifestLoaded { .. } => write!(f, "Load manifest"),
+            SessionEventKind::MainComponentSet { type_id } => {
+                write!(f, "Set main component to {}", type_id)
+            }
+            SessionEventKind::Action { name } => write!(f, "Action {}", name),
+            SessionEventKind::Input { key, pressed } => {
+                write!(f, "Key {} {}", key, if *pressed { "down" } else { "up" })
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Checkpoint {
+    pub manifest_version_before: usize,
+    pub manifest_version_after: usize,
+    /// See `manifest_hash`
+    pub manifest_hash: u64,
+}
+
+/// A recorded session, replayable on its own, see the module docs
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SessionRecording {
+    pub format_version: u32,
+    pub redacted: bool,
+    pub start: SessionState,
+    pub start_hash: u64,
+    pub events: Vec<SessionEvent>,
+}
+
+impl SessionRecording {
+    /// Reads a recording from a `BugReport`, a `SessionRecording`, or a design server session
+    /// log (a `SessionRecording` without events followed by one `SessionEvent` per line)
+    pub fn from_json(json: &str) -> Result<SessionRecording, String> {
+        let recording = if let Ok(report) = serde_json::from_str::<BugReport>(json) {
+            report.session
+        } else if let Ok(recording) = serde_json::from_str::<SessionRecording>(json) {
+            recording
+        } else {
+            let mut lines = json.lines().filter(|line| !line.trim().is_empty());
+            let header = lines.next().ok_or("Empty session recording")?;
+            let mut recording: SessionRecording = serde_json::from_str(header)
+                .map_err(|e| format!("Couldn't read session recording: {}", e))?;
+            for (i, line) in lines.enumerate() {
+                let event = serde_json::from_str(line)
+                    .map_err(|e| format!("Couldn't read event {} of session log: {}", i, e))?;
+                recording.events.push(event);
+            }
+            recording
+        };
+        if recording.format_version != SESSION_FORMAT_VERSION {
+            return Err(format!(
+                "Session recording format {} isn't supported, expected {}",
+                recording.format_version, SESSION_FORMAT_VERSION
+            ));
+        }
+        Ok(recording)
+    }
+
+    /// Re-executes the recorded events against the starting snapshot, checking the manifest
+    /// against each checkpoint, until the end or the first divergence
+    pub fn replay(&self) -> ReplayOutcome {
+        let mut state = self.start.clone();
+        let actual = manifest_hash(&state.manifest, self.redacted);
+        if actual != self.start_hash {
+            return ReplayOutcome {
+                state,
+                events_replayed: 0,
+                divergence: Some(Divergence {
+                    index: None,
+                    event: "Start".to_string(),
+                    context: vec![],
+                    reason: DivergenceReason::HashMismatch {
+                        expected: self.start_hash,
+                        actual,
+                    },
+                }),
+            };
+        }
+        for (index, event) in self.events.iter().enumerate() {
+            let reason = match (state.apply(&event.kind), &event.checkpoint) {
+                (Err(e), _) => Some(DivergenceReason::Failed(e)),
+                (Ok(()), Some(checkpoint)) => {
+                    let actual = manifest_hash(&state.manifest, self.redacted);
+                    (actual != checkpoint.manifest_hash).then_some(DivergenceReason::HashMismatch {
+                        expected: checkpoint.manifest_hash,
+                        actual,
+                    })
+                }
+                (Ok(()), None) => None,
+            };
+            if let Some(reason) = reason {
+                return ReplayOutcome {
+                    state,
+                    events_replayed: index,
+                    divergence: Some(Divergence {
+                        index: Some(index),
+                        event: event.kind.to_string(),
+                        context: self.context_before(index),
+                        reason,
+                    }),
+                };
+            }
+        }
+        ReplayOutcome {
+            state,
+            events_replayed: self.events.len(),
+            divergence: None,
+        }
+    }
+
+    /// The actions and key presses since the last manifest change before event `index`
+    fn context_before(&self, index: usize) -> Vec<String> {
+        let mut context: Vec<String> = self.events[..index]
+            .iter()
+            .rev()
+            .take_while(|event| !event.kind.changes_manifest())
+            .take(DIVERGENCE_CONTEXT_LEN)
+            .map(|event| event.kind.to_string())
+            .collect();
+        context.reverse();
+        context
+    }
+}
+
+pub struct ReplayOutcome {
+    /// State after the last event replayed
+    pub state: SessionState,
+    /// Number of events replayed before the divergence, if any
+    pub events_replayed: usize,
+    pub divergence: Option<Divergence>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    /// Index of the event that diverged, `None` if the starting snapshot itself doesn't match
+    pub index: Option<usize>,
+    /// Description of the event, e.g. "Add Rectangle"
+    pub event: String,
+    /// Actions and key presses that led up to the event, oldest first
+    pub context: Vec<String>,
+    pub reason: DivergenceReason,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DivergenceReason {
+    /// The event failed, though it succeeded when it was recorded
+    Failed(String),
+    HashMismatch {
+        expected: u64,
+        actual: u64,
+    },
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.index {
+            Some(index) => write!(f, "event {} ({})", index, self.event)?,
+            None => write!(f, "starting snapshot")?,
+        }
+        match &self.reason {
+            DivergenceReason::Failed(e) => write!(f, " failed: {}", e)?,
+            DivergenceReason::HashMismatch { expected, actual } => write!(
+                f,
+                " resulted in manifest {:016x}, expected {:016x}",
+                actual, expected
+            )?,
+        }
+        if !self.context.is_empty() {
+            write!(f, ", after: {}", self.context.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// A session recording along with the manifest and environment it was exported from, for
+/// attaching to bug reports
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BugReport {
+    pub format_version: u32,
+    pub session: SessionRecording,
+    /// The manifest when the report was exported, redacted along with the recording
+    pub manifest: PaxManifest,
+    /// e.g. versions and platform, by name
+    pub environment: BTreeMap<String, String>,
+}
+
+/// Entries to append to the session log of the design server, see `PaxManifestORM::take_session_log`
+pub struct SessionLogEntries {
+    /// The recording without events, if the recording (re)started since the last entries
+    pub header: Option<SessionRecording>,
+    pub events: Vec<SessionEvent>,
+}
+
+/// Stable hash of `manifest`, of its redacted version if `redacted`
+pub fn manifest_hash(manifest: &PaxManifest, redacted: bool) -> u64 {
+    // going through `Value` sorts the keys of the manifest's hash maps, for a stable hash
+    let mut value = serde_json::to_value(manifest).unwrap();
+    if redacted {
+        redact_value(&mut value);
+    }
+    let mut hasher = DefaultHasher::new();
+    value.to_string().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// `value` with its string literal values replaced by `REDACTED`
+pub fn redact<T: serde::Serialize + DeserializeOwned>(value: &T) -> T {
+    let mut json = serde_json::to_value(value).unwrap();
+    redact_value(&mut json);
+    serde_json::from_value(json).unwrap()
+}
+
+/// Replaces `PaxValue::String`s, serialized as `{"String": ".."}`
+fn redact_value(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            if let (1, Some(Value::String(s))) = (map.len(), map.get_mut("String")) {
+                *s = REDACTED.to_string();
+                return;
+            }
+            map.values_mut().for_each(redact_value);
+        }
+        Value::Array(values) => values.iter_mut().for_each(redact_value),
+        _ => {}
+    }
+}
+
+pub(crate) struct SessionRecorder {
+    options: RecordingOptions,
+    started_ms: f64,
+    start: SessionState,
+    start_hash: u64,
+    events: VecDeque<SessionEvent>,
+    /// The recording as it started, until taken for the session log of the design server
+    unlogged_header: Option<SessionRecording>,
+    /// Events not yet taken for the session log of the design server
+    unlogged: Vec<SessionEvent>,
+}
+
+impl SessionRecorder {
+    pub(crate) fn new(options: RecordingOptions, start: SessionState) -> Self {
+        let start = if options.redact_strings {
+            redact(&start)
+        } else {
+            start
+        };
+        let start_hash = manifest_hash(&start.manifest, options.redact_strings);
+        SessionRecorder {
+            started_ms: crate::jobs::now_ms(),
+            unlogged_header: Some(SessionRecording {
+                format_version: SESSION_FORMAT_VERSION,
+                redacted: options.redact_strings,
+                start: start.clone(),
+                start_hash,
+                events: vec![],
+            }),
+            start_hash,
+            start,
+            options,
+            events: VecDeque::new(),
+            unlogged: Vec::new(),
+        }
+    }
+
+    /// Records an event, with a checkpoint of `manifest` if it changed it
+    pub(crate) fn record(
+        &mut self,
+        kind: SessionEventKind,
+        manifest: &PaxManifest,
+        manifest_version_before: usize,
+        manifest_version_after: usize,
+    ) {
+        let redacted = self.options.redact_strings;
+        let checkpoint = kind.changes_manifest().then(|| Checkpoint {
+            manifest_version_before,
+            manifest_version_after,
+            manifest_hash: manifest_hash(manifest, redacted),
+        });
+        let kind = match kind {
+            SessionEventKind::Input { key, pressed } if redacted && key.chars().count() == 1 => {
+                SessionEventKind::Input {
+                    key: REDACTED.to_string(),
+                    pressed,
+                }
+            }
+            kind if redacted && kind.changes_manifest() => redact(&kind),
+            kind => kind,
+        };
+        let now = crate::jobs::now_ms() - self.started_ms;
+        let event = SessionEvent {
+            time_ms: now,
+            kind,
+            checkpoint,
+        };
+        self.unlogged.push(event.clone());
+        self.events.push_back(event);
+
+        let Some(window_ms) = self.options.window_ms else {
+            return;
+        };
+        while let Some(event) = self.events.front() {
+            if event.time_ms >= now - window_ms {
+                break;
+            }
+            let event = self.events.pop_front().unwrap();
+            if let Err(e) = self.start.apply(&event.kind) {
+                log::warn!(
+                    "couldn't fold \"{}\" into session recording: {}",
+                    event.kind,
+                    e
+                );
+            }
+            if let Some(checkpoint) = event.checkpoint {
+                self.start_hash = checkpoint.manifest_hash;
+            }
+        }
+    }
+
+    pub(crate) fn recording(&self) -> SessionRecording {
+        SessionRecording {
+            format_version: SESSION_FORMAT_VERSION,
+            redacted: self.options.redact_strings,
+            start: self.start.clone(),
+            start_hash: self.start_hash,
+            events: self.events.iter().cloned().collect(),
+        }
+    }
+
+    pub(crate) fn is_redacted(&self) -> bool {
+        self.options.redact_strings
+    }
+
+    pub(crate) fn take_log(&mut self) -> Option<SessionLogEntries> {
+        let header = self.unlogged_header.take();
+        if header.is_none() && self.unlogged.is_empty() {
+            return None;
+        }
+        Some(SessionLogEntries {
+            header,
+            events: std::mem::take(&mut self.unlogged),
+        })
+    }
+}
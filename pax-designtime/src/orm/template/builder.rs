@@ -29,6 +29,8 @@ pub struct NodeBuilder<'a> {
 pub struct SaveData {
     pub undo_id: Option<usize>,
     pub unique_id: UniqueTemplateNodeIdentifier,
+    /// Description of the executed command, e.g. "Add Rectangle"
+    pub description: String,
 }
 
 impl<'a> NodeBuilder<'a> {
@@ -286,7 +288,7 @@ impl<'a> NodeBuilder<'a> {
     }
 
     pub fn save(mut self) -> Result<SaveData, String> {
-        let id = if let Some(uni) = &self.unique_node_identifier {
+        let (id, description) = if let Some(uni) = &self.unique_node_identifier {
             // Node already exists
             let location = self
                 .location
@@ -299,7 +301,7 @@ impl<'a> NodeBuilder<'a> {
                 Some(location),
                 self.control_flow_updates,
             ))?;
-            resp.command_id
+            (resp.command_id, resp.description.unwrap_or_default())
         } else {
             let node_data = match self.node_type_id.get_pax_type() {
                 pax_manifest::PaxType::If
@@ -330,6 +332,7 @@ impl<'a> NodeBuilder<'a> {
                     NodeType::Comment("COMMENT BUILDER WRITING NOT IMPLEMENTED".to_string())
                 }
                 pax_manifest::PaxType::BlankComponent { .. }
+                | pax_manifest::PaxType::Unresolved { .. }
                 | pax_manifest::PaxType::Singleton { .. } => {
                     let settings = self
                         .updated_property_map
@@ -353,12 +356,13 @@ impl<'a> NodeBuilder<'a> {
             ))?;
             self.location = self.orm.manifest.get_node_location(&resp.uni);
             self.unique_node_identifier = Some(resp.uni);
-            resp.command_id
+            (resp.command_id, resp.description.unwrap_or_default())
         };
 
         Ok(SaveData {
             undo_id: id,
             unique_id: self.unique_node_identifier.expect("exists after save"),
+            description,
         })
     }
 }
@@ -1,7 +1,10 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
 
 use pax_manifest::{
-    pax_runtime_api::ToPaxValue, ComponentDefinition, ComponentTemplate,
+    constants, pax_runtime_api::ToPaxValue, ComponentDefinition, ComponentTemplate,
     ControlFlowRepeatPredicateDefinition, ExpressionInfo, NodeLocation, NodeType, PaxManifest,
     SettingElement, TemplateNodeDefinition, TemplateNodeId, Token, TreeIndexPosition, TreeLocation,
     TypeId, UniqueTemplateNodeIdentifier, ValueDefinition,
@@ -11,9 +14,29 @@ use serde_derive::{Deserialize, Serialize};
 use super::{
     Command, MoveToComponentEntry, ReloadType, Request, Response, SubTrees, Undo, UndoRedoCommand,
 };
+use crate::snippet::to_snake_case;
 
 pub mod builder;
 
+/// Name of a node/component type for command descriptions, e.g. "Rectangle"
+fn type_name(type_id: &TypeId) -> String {
+    type_id
+        .get_pascal_identifier()
+        .unwrap_or_else(|| type_id.to_string())
+}
+
+/// Nodes a component inherited with `@extends` are locked until overridden,
+/// see `PaxManifestORM::override_inherited_node`
+fn check_not_inherited(component: &ComponentDefinition, id: &TemplateNodeId) -> Result<(), String> {
+    match &component.extends {
+        Some(extension) if extension.inherited_nodes.contains(id) => Err(format!(
+            "Node is inherited from {}, override it to edit it",
+            type_name(&extension.base)
+        )),
+        _ => Ok(()),
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Default, Debug)]
 pub struct NodeData {
     pub unique_node_identifier: UniqueTemplateNodeIdentifier,
@@ -59,6 +82,7 @@ impl AddTemplateNodeRequest {
 #[derive(Serialize, Deserialize, Clone)]
 pub struct AddTemplateNodeResponse {
     command_id: Option<usize>,
+    description: Option<String>,
     uni: UniqueTemplateNodeIdentifier,
 }
 
@@ -73,6 +97,15 @@ impl Response for AddTemplateNodeResponse {
     fn get_id(&self) -> usize {
         self.command_id.unwrap()
     }
+    fn set_description(&mut self, description: String) {
+        self.description = Some(description);
+    }
+    fn get_description(&self) -> &str {
+        self.description.as_deref().unwrap_or_default()
+    }
+    fn get_affected_components(&self) -> Vec<TypeId> {
+        vec![self.uni.get_containing_component_type_id()]
+    }
     fn get_reload_type(&self) -> Option<ReloadType> {
         Some(ReloadType::FullEdit)
     }
@@ -87,6 +120,11 @@ impl Command<AddTemplateNodeRequest> for AddTemplateNodeRequest {
         if component.is_primitive || component.is_struct_only_component {
             return Err("Component doesn't accept template nodes.".to_string());
         }
+        if let Some(TreeLocation::Parent(parent)) =
+            self.location.as_ref().map(|l| l.get_tree_location())
+        {
+            check_not_inherited(component, parent)?;
+        }
 
         let mut template_node = TemplateNodeDefinition {
             type_id: self.template_node_type_id.clone(),
@@ -132,10 +170,19 @@ impl Command<AddTemplateNodeRequest> for AddTemplateNodeRequest {
 
         Ok(AddTemplateNodeResponse {
             command_id: None,
+            description: None,
             uni: node_data.unique_node_identifier.clone(),
         })
     }
 
+    fn description(&self) -> String {
+        match &self.node_data {
+            NodeType::Template(_) => format!("Add {}", type_name(&self.template_node_type_id)),
+            NodeType::ControlFlow(_) => "Add control flow".to_string(),
+            NodeType::Comment(_) => "Add comment".to_string(),
+        }
+    }
+
     fn as_undo_redo(&mut self) -> Option<UndoRedoCommand> {
         Some(UndoRedoCommand::AddTemplateNodeRequest(Box::new(
             self.clone(),
@@ -164,9 +211,12 @@ impl Undo for AddTemplateNodeRequest {
     }
 }
 
+#[serde_with::serde_as]
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct UpdateTemplateNodeRequest {
     uni: UniqueTemplateNodeIdentifier,
+    // string keys, so that commands can be written as JSON, see `session`
+    #[serde_as(as = "HashMap<serde_with::json::JsonString, _>")]
     updated_properties: HashMap<Token, Option<ValueDefinition>>,
     new_type_id: Option<TypeId>,
     new_location: Option<NodeLocation>,
@@ -198,6 +248,7 @@ impl UpdateTemplateNodeRequest {
 
 pub struct UpdateTemplateNodeResponse {
     command_id: Option<usize>,
+    description: Option<String>,
     _affected_component_type_id: TypeId,
     _affected_unique_node_identifier: UniqueTemplateNodeIdentifier,
 }
@@ -213,6 +264,15 @@ impl Response for UpdateTemplateNodeResponse {
     fn get_id(&self) -> usize {
         self.command_id.unwrap()
     }
+    fn set_description(&mut self, description: String) {
+        self.description = Some(description);
+    }
+    fn get_description(&self) -> &str {
+        self.description.as_deref().unwrap_or_default()
+    }
+    fn get_affected_components(&self) -> Vec<TypeId> {
+        vec![self._affected_component_type_id.clone()]
+    }
     fn get_reload_type(&self) -> Option<ReloadType> {
         Some(ReloadType::Partial(
             self._affected_unique_node_identifier.clone(),
@@ -239,6 +299,7 @@ impl Command<UpdateTemplateNodeRequest> for UpdateTemplateNodeRequest {
         if component.is_primitive || component.is_struct_only_component {
             return Err("Component doesn't accept template nodes.".to_string());
         }
+        check_not_inherited(component, &uni.get_template_node_id())?;
 
         if let Some(template) = &mut component.template {
             template.update_node_properties(
@@ -279,11 +340,28 @@ impl Command<UpdateTemplateNodeRequest> for UpdateTemplateNodeRequest {
 
         Ok(UpdateTemplateNodeResponse {
             command_id: None,
+            description: None,
             _affected_component_type_id: uni.get_containing_component_type_id(),
             _affected_unique_node_identifier: uni,
         })
     }
 
+    fn description(&self) -> String {
+        let old_type_id = self
+            ._cached_node_data
+            .as_ref()
+            .map(|data| &data.cached_node.type_id);
+        let name = old_type_id
+            .map(type_name)
+            .unwrap_or_else(|| "node".to_string());
+        match &self.new_type_id {
+            Some(new_type_id) if old_type_id != Some(new_type_id) => {
+                format!("Change {} to {}", name, type_name(new_type_id))
+            }
+            _ => format!("Update {}", name),
+        }
+    }
+
     fn as_undo_redo(&mut self) -> Option<UndoRedoCommand> {
         Some(UndoRedoCommand::UpdateTemplateNodeRequest(Box::new(
             self.clone(),
@@ -317,6 +395,8 @@ pub struct MoveTemplateNodeRequest {
     new_location: NodeLocation,
     // Used for Undo/Redo
     _cached_old_position: Option<NodeLocation>,
+    // Used for the command description
+    _cached_type_id: Option<TypeId>,
 }
 
 impl MoveTemplateNodeRequest {
@@ -325,12 +405,15 @@ impl MoveTemplateNodeRequest {
             uni,
             new_location,
             _cached_old_position: None,
+            _cached_type_id: None,
         }
     }
 }
 
+#[derive(Serialize)]
 pub struct MoveTemplateNodeResponse {
     command_id: Option<usize>,
+    description: Option<String>,
     _affected_component_type_id: TypeId,
 }
 
@@ -345,6 +428,15 @@ impl Response for MoveTemplateNodeResponse {
     fn get_id(&self) -> usize {
         self.command_id.unwrap()
     }
+    fn set_description(&mut self, description: String) {
+        self.description = Some(description);
+    }
+    fn get_description(&self) -> &str {
+        self.description.as_deref().unwrap_or_default()
+    }
+    fn get_affected_components(&self) -> Vec<TypeId> {
+        vec![self._affected_component_type_id.clone()]
+    }
     fn get_reload_type(&self) -> Option<ReloadType> {
         Some(ReloadType::FullEdit)
     }
@@ -370,6 +462,10 @@ impl Command<MoveTemplateNodeRequest> for MoveTemplateNodeRequest {
         if current_component.template.is_none() {
             return Err("Component doesn't have a template.".to_string());
         }
+        check_not_inherited(current_component, &uni.get_template_node_id())?;
+        if let TreeLocation::Parent(parent) = self.new_location.get_tree_location() {
+            check_not_inherited(current_component, parent)?;
+        }
 
         let template = current_component.template.as_mut().unwrap();
 
@@ -378,14 +474,25 @@ impl Command<MoveTemplateNodeRequest> for MoveTemplateNodeRequest {
         }
 
         self._cached_old_position = template.get_location(&self.uni.get_template_node_id());
+        self._cached_type_id = template
+            .get_node(&self.uni.get_template_node_id())
+            .map(|node| node.type_id.clone());
         template.move_node(&uni.get_template_node_id(), self.new_location.clone());
 
         Ok(MoveTemplateNodeResponse {
             command_id: None,
+            description: None,
             _affected_component_type_id: uni.get_containing_component_type_id(),
         })
     }
 
+    fn description(&self) -> String {
+        match &self._cached_type_id {
+            Some(type_id) => format!("Move {}", type_name(type_id)),
+            None => "Move node".to_string(),
+        }
+    }
+
     fn as_undo_redo(&mut self) -> Option<UndoRedoCommand> {
         Some(UndoRedoCommand::MoveTemplateNodeRequest(Box::new(
             self.clone(),
@@ -428,6 +535,7 @@ impl PasteSubTreeRequest {
 
 pub struct PasteSubTreeResponse {
     command_id: Option<usize>,
+    description: Option<String>,
     root_ids: Vec<TemplateNodeId>,
     _affected_component_type_id: TypeId,
 }
@@ -449,6 +557,15 @@ impl Response for PasteSubTreeResponse {
     fn get_id(&self) -> usize {
         self.command_id.unwrap()
     }
+    fn set_description(&mut self, description: String) {
+        self.description = Some(description);
+    }
+    fn get_description(&self) -> &str {
+        self.description.as_deref().unwrap_or_default()
+    }
+    fn get_affected_components(&self) -> Vec<TypeId> {
+        vec![self._affected_component_type_id.clone()]
+    }
     fn get_reload_type(&self) -> Option<ReloadType> {
         Some(ReloadType::FullEdit)
     }
@@ -505,11 +622,19 @@ impl Command<PasteSubTreeRequest> for PasteSubTreeRequest {
 
         Ok(PasteSubTreeResponse {
             command_id: None,
+            description: None,
             root_ids,
             _affected_component_type_id: type_id.clone(),
         })
     }
 
+    fn description(&self) -> String {
+        match self.subtrees.roots.len() {
+            1 => "Paste 1 node".to_string(),
+            n => format!("Paste {} nodes", n),
+        }
+    }
+
     fn as_undo_redo(&mut self) -> Option<UndoRedoCommand> {
         Some(UndoRedoCommand::PasteSubTreeRequest(Box::new(self.clone())))
     }
@@ -542,8 +667,10 @@ impl RemoveTemplateNodeRequest {
     }
 }
 
+#[derive(Serialize)]
 pub struct RemoveTemplateNodeResponse {
     command_id: Option<usize>,
+    description: Option<String>,
     _affected_component_type_id: TypeId,
 }
 
@@ -558,6 +685,15 @@ impl Response for RemoveTemplateNodeResponse {
     fn get_id(&self) -> usize {
         self.command_id.unwrap()
     }
+    fn set_description(&mut self, description: String) {
+        self.description = Some(description);
+    }
+    fn get_description(&self) -> &str {
+        self.description.as_deref().unwrap_or_default()
+    }
+    fn get_affected_components(&self) -> Vec<TypeId> {
+        vec![self._affected_component_type_id.clone()]
+    }
     fn get_reload_type(&self) -> Option<ReloadType> {
         Some(ReloadType::FullEdit)
     }
@@ -580,6 +716,7 @@ impl Command<RemoveTemplateNodeRequest> for RemoveTemplateNodeRequest {
         if component.is_primitive || component.is_struct_only_component {
             unreachable!("Component doesn't accept template nodes.");
         }
+        check_not_inherited(component, &self.uni.get_template_node_id())?;
 
         if let Some(template) = &mut component.template {
             self._cached_template = Some(template.clone());
@@ -588,10 +725,23 @@ impl Command<RemoveTemplateNodeRequest> for RemoveTemplateNodeRequest {
 
         Ok(RemoveTemplateNodeResponse {
             command_id: None,
+            description: None,
             _affected_component_type_id: self.uni.get_containing_component_type_id(),
         })
     }
 
+    fn description(&self) -> String {
+        let removed_type_id = self
+            ._cached_template
+            .as_ref()
+            .and_then(|t| t.get_node(&self.uni.get_template_node_id()))
+            .map(|node| &node.type_id);
+        match removed_type_id {
+            Some(type_id) => format!("Remove {}", type_name(type_id)),
+            None => "Remove node".to_string(),
+        }
+    }
+
     fn as_undo_redo(&mut self) -> Option<UndoRedoCommand> {
         Some(UndoRedoCommand::RemoveTemplateNodeRequest(Box::new(
             self.clone(),
@@ -615,13 +765,15 @@ impl Undo for RemoveTemplateNodeRequest {
     }
 }
 
+#[derive(Deserialize)]
 pub struct GetTemplateNodeRequest {
     uni: UniqueTemplateNodeIdentifier,
 }
 
+#[derive(Serialize)]
 pub struct GetTemplateNodeResponse {
     command_id: Option<usize>,
-    #[allow(unused)]
+    description: Option<String>,
     node: Option<TemplateNodeDefinition>,
 }
 
@@ -636,6 +788,12 @@ impl Response for GetTemplateNodeResponse {
     fn get_id(&self) -> usize {
         self.command_id.unwrap()
     }
+    fn set_description(&mut self, description: String) {
+        self.description = Some(description);
+    }
+    fn get_description(&self) -> &str {
+        self.description.as_deref().unwrap_or_default()
+    }
 }
 
 impl Command<GetTemplateNodeRequest> for GetTemplateNodeRequest {
@@ -662,9 +820,14 @@ impl Command<GetTemplateNodeRequest> for GetTemplateNodeRequest {
 
         Ok(GetTemplateNodeResponse {
             command_id: None,
+            description: None,
             node,
         })
     }
+
+    fn description(&self) -> String {
+        "Get node".to_string()
+    }
 }
 
 pub struct GetChildrenRequest {
@@ -673,6 +836,7 @@ pub struct GetChildrenRequest {
 
 pub struct GetChildrenResponse {
     command_id: Option<usize>,
+    description: Option<String>,
     #[allow(unused)]
     pub children: Vec<UniqueTemplateNodeIdentifier>,
 }
@@ -688,6 +852,12 @@ impl Response for GetChildrenResponse {
     fn get_id(&self) -> usize {
         self.command_id.unwrap()
     }
+    fn set_description(&mut self, description: String) {
+        self.description = Some(description);
+    }
+    fn get_description(&self) -> &str {
+        self.description.as_deref().unwrap_or_default()
+    }
 }
 
 impl Command<GetChildrenRequest> for GetChildrenRequest {
@@ -717,9 +887,14 @@ impl Command<GetChildrenRequest> for GetChildrenRequest {
 
         Ok(GetChildrenResponse {
             command_id: None,
+            description: None,
             children,
         })
     }
+
+    fn description(&self) -> String {
+        "Get children".to_string()
+    }
 }
 
 pub struct GetAllTemplateNodeRequest {
@@ -728,6 +903,7 @@ pub struct GetAllTemplateNodeRequest {
 
 pub struct GetAllTemplateNodeResponse {
     command_id: Option<usize>,
+    description: Option<String>,
     #[allow(unused)]
     nodes: Option<Vec<TemplateNodeDefinition>>,
 }
@@ -743,6 +919,12 @@ impl Response for GetAllTemplateNodeResponse {
     fn get_id(&self) -> usize {
         self.command_id.unwrap()
     }
+    fn set_description(&mut self, description: String) {
+        self.description = Some(description);
+    }
+    fn get_description(&self) -> &str {
+        self.description.as_deref().unwrap_or_default()
+    }
 }
 
 impl Command<GetAllTemplateNodeRequest> for GetAllTemplateNodeRequest {
@@ -762,9 +944,14 @@ impl Command<GetAllTemplateNodeRequest> for GetAllTemplateNodeRequest {
 
         Ok(GetAllTemplateNodeResponse {
             command_id: None,
+            description: None,
             nodes,
         })
     }
+
+    fn description(&self) -> String {
+        format!("Get nodes of {}", type_name(&self.component_type_id))
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -786,6 +973,7 @@ impl ReplaceTemplateRequest {
 
 pub struct ReplaceTemplateResponse {
     command_id: Option<usize>,
+    description: Option<String>,
     _affected_component_type_id: TypeId,
 }
 
@@ -796,6 +984,15 @@ impl Response for ReplaceTemplateResponse {
     fn get_id(&self) -> usize {
         self.command_id.unwrap()
     }
+    fn set_description(&mut self, description: String) {
+        self.description = Some(description);
+    }
+    fn get_description(&self) -> &str {
+        self.description.as_deref().unwrap_or_default()
+    }
+    fn get_affected_components(&self) -> Vec<TypeId> {
+        vec![self._affected_component_type_id.clone()]
+    }
     fn get_reload_type(&self) -> Option<ReloadType> {
         Some(ReloadType::FullEdit)
     }
@@ -818,10 +1015,15 @@ impl Command<ReplaceTemplateRequest> for ReplaceTemplateRequest {
 
         Ok(ReplaceTemplateResponse {
             command_id: None,
+            description: None,
             _affected_component_type_id: self.component_type_id.clone(),
         })
     }
 
+    fn description(&self) -> String {
+        format!("Replace template of {}", type_name(&self.component_type_id))
+    }
+
     fn as_undo_redo(&mut self) -> Option<UndoRedoCommand> {
         Some(UndoRedoCommand::ReplaceTemplateRequest(Box::new(
             self.clone(),
@@ -840,11 +1042,68 @@ impl Undo for ReplaceTemplateRequest {
     }
 }
 
+/// Pascal identifier of components created by `ConvertToComponentRequest` without a name,
+/// followed by a number making it unique, e.g. `NewComponent3`
+pub const DEFAULT_NEW_COMPONENT_NAME: &str = "NewComponent";
+
+/// Pascal identifier for a new component based on `name`, e.g. `ProfileCard` for
+/// "profile card", or `NewComponent1` without a usable name, numbered if needed so that
+/// neither the identifier nor its file name, e.g. `profile_card.pax`, is taken in `manifest`
+pub fn unique_component_name(manifest: &PaxManifest, name: Option<&str>) -> String {
+    let taken_names: HashSet<String> = manifest
+        .components
+        .keys()
+        .filter_map(|type_id| type_id.get_pascal_identifier())
+        .collect();
+    let taken_files: HashSet<String> = manifest
+        .components
+        .values()
+        .filter_map(|component| component.template.as_ref()?.get_file_path())
+        .filter_map(|path| Some(PathBuf::from(path).file_name()?.to_str()?.to_string()))
+        .collect();
+    let is_taken = |name: &String| {
+        taken_names.contains(name) || taken_files.contains(&format!("{}.pax", to_snake_case(name)))
+    };
+
+    let base = name.map(to_pascal_case).unwrap_or_default();
+    if base.is_empty() {
+        return (1..)
+            .map(|n| format!("{}{}", DEFAULT_NEW_COMPONENT_NAME, n))
+            .find(|name| !is_taken(name))
+            .unwrap();
+    }
+    std::iter::once(base.clone())
+        .chain((2..).map(|n| format!("{}{}", base, n)))
+        .find(|name| !is_taken(name))
+        .unwrap()
+}
+
+/// `name` as a pascal case identifier, e.g. `ProfileCard` for "profile card" or "profile_card".
+/// Empty if `name` has no alphanumeric characters
+fn to_pascal_case(name: &str) -> String {
+    let mut pascal: String = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+        })
+        .collect();
+    if pascal.starts_with(|c: char| c.is_ascii_digit()) {
+        pascal.insert_str(0, DEFAULT_NEW_COMPONENT_NAME);
+    }
+    pascal
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ConvertToComponentRequest {
     // These subtrees (roots) must be at the same TreeLocation
     subtrees_roots: Vec<MoveToComponentEntry>,
-    new_component_number: usize,
+    /// Name of the new component, made unique and pascal case, see `unique_component_name`
+    name: Option<String>,
     x: f64,
     y: f64,
     width: f64,
@@ -859,7 +1118,7 @@ pub struct ConvertToComponentRequest {
 impl ConvertToComponentRequest {
     pub fn new(
         subtrees_roots: Vec<MoveToComponentEntry>,
-        new_component_number: usize,
+        name: Option<String>,
         x: f64,
         y: f64,
         width: f64,
@@ -867,7 +1126,7 @@ impl ConvertToComponentRequest {
     ) -> Self {
         Self {
             subtrees_roots,
-            new_component_number,
+            name,
             x,
             y,
             width,
@@ -886,6 +1145,7 @@ impl Request for ConvertToComponentRequest {
 #[derive(Serialize, Deserialize, Clone)]
 pub struct ConvertToComponentResponse {
     command_id: Option<usize>,
+    description: Option<String>,
     pub uni: UniqueTemplateNodeIdentifier,
     pub new_component_type_id: TypeId,
 }
@@ -897,6 +1157,18 @@ impl Response for ConvertToComponentResponse {
     fn get_id(&self) -> usize {
         self.command_id.unwrap()
     }
+    fn set_description(&mut self, description: String) {
+        self.description = Some(description);
+    }
+    fn get_description(&self) -> &str {
+        self.description.as_deref().unwrap_or_default()
+    }
+    fn get_affected_components(&self) -> Vec<TypeId> {
+        vec![
+            self.uni.get_containing_component_type_id(),
+            self.new_component_type_id.clone(),
+        ]
+    }
     fn get_reload_type(&self) -> Option<ReloadType> {
         Some(ReloadType::FullEdit)
     }
@@ -911,8 +1183,12 @@ impl Command<ConvertToComponentRequest> for ConvertToComponentRequest {
             return Err("No subtrees provided".to_string());
         }
 
-        let new_component_identifier = format!("NewComponent{}", self.new_component_number);
-        let new_component_file_name = format!("new_component_{}.pax", self.new_component_number);
+        // kept on redo, when the name is free again
+        let new_component_identifier = match &self._cached_new_component_type_id {
+            Some(type_id) => type_name(type_id),
+            None => unique_component_name(manifest, self.name.as_deref()),
+        };
+        let new_component_file_name = format!("{}.pax", to_snake_case(&new_component_identifier));
         let new_component_type_id = TypeId::build_blank_component(&new_component_identifier);
 
         let (module_path, ul_path) = {
@@ -1050,6 +1326,7 @@ impl Command<ConvertToComponentRequest> for ConvertToComponentRequest {
             primitive_instance_import_path: None,
             template: Some(new_template),
             settings: None,
+            extends: None,
         };
 
         manifest
@@ -1091,11 +1368,24 @@ impl Command<ConvertToComponentRequest> for ConvertToComponentRequest {
 
         Ok(ConvertToComponentResponse {
             command_id: None,
+            description: None,
             uni: response.uni,
             new_component_type_id,
         })
     }
 
+    fn description(&self) -> String {
+        let component = self
+            ._cached_new_component_type_id
+            .as_ref()
+            .map(type_name)
+            .unwrap_or_else(|| "component".to_string());
+        match self.subtrees_roots.len() {
+            1 => format!("Convert 1 node to {}", component),
+            n => format!("Convert {} nodes to {}", n, component),
+        }
+    }
+
     fn as_undo_redo(&mut self) -> Option<UndoRedoCommand> {
         Some(UndoRedoCommand::ConvertToComponentRequest(Box::new(
             self.clone(),
@@ -1143,6 +1433,8 @@ impl SwapMainComponentRequest {
 
 pub struct SwapMainComponentResponse {
     command_id: Option<usize>,
+    description: Option<String>,
+    _affected_component_type_id: TypeId,
 }
 
 impl Request for SwapMainComponentRequest {
@@ -1156,6 +1448,15 @@ impl Response for SwapMainComponentResponse {
     fn get_id(&self) -> usize {
         self.command_id.unwrap()
     }
+    fn set_description(&mut self, description: String) {
+        self.description = Some(description);
+    }
+    fn get_description(&self) -> &str {
+        self.description.as_deref().unwrap_or_default()
+    }
+    fn get_affected_components(&self) -> Vec<TypeId> {
+        vec![self._affected_component_type_id.clone()]
+    }
 
     fn get_reload_type(&self) -> Option<ReloadType> {
         Some(ReloadType::FullEdit)
@@ -1176,7 +1477,18 @@ impl Command<SwapMainComponentRequest> for SwapMainComponentRequest {
             self.new_component.clone(),
         );
         manifest.main_component_type_id = self.new_component.type_id.clone();
-        Ok(SwapMainComponentResponse { command_id: None })
+        Ok(SwapMainComponentResponse {
+            command_id: None,
+            description: None,
+            _affected_component_type_id: self.new_component.type_id.clone(),
+        })
+    }
+
+    fn description(&self) -> String {
+        format!(
+            "Swap main component to {}",
+            type_name(&self.new_component.type_id)
+        )
     }
 }
 
@@ -1221,3 +1533,555 @@ pub fn update_position_if_exists(new_x: f64, new_y: f64, settings: &mut [Setting
         }
     }
 }
+
+/// Replaces a component's definition with a snapshot from its history,
+/// see `PaxManifestORM::restore_component_to_snapshot`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RestoreComponentRequest {
+    snapshot: ComponentDefinition,
+    // Used for Undo/Redo
+    _cached_component: Option<ComponentDefinition>,
+}
+
+impl RestoreComponentRequest {
+    pub fn new(snapshot: ComponentDefinition) -> Self {
+        Self {
+            snapshot,
+            _cached_component: None,
+        }
+    }
+}
+
+pub struct RestoreComponentResponse {
+    command_id: Option<usize>,
+    description: Option<String>,
+    _affected_component_type_id: TypeId,
+}
+
+impl Request for RestoreComponentRequest {
+    type Response = RestoreComponentResponse;
+}
+
+impl Response for RestoreComponentResponse {
+    fn set_id(&mut self, id: usize) {
+        self.command_id = Some(id);
+    }
+    fn get_id(&self) -> usize {
+        self.command_id.unwrap()
+    }
+    fn set_description(&mut self, description: String) {
+        self.description = Some(description);
+    }
+    fn get_description(&self) -> &str {
+        self.description.as_deref().unwrap_or_default()
+    }
+    fn get_affected_components(&self) -> Vec<TypeId> {
+        vec![self._affected_component_type_id.clone()]
+    }
+    fn get_reload_type(&self) -> Option<ReloadType> {
+        Some(ReloadType::FullEdit)
+    }
+}
+
+impl Command<RestoreComponentRequest> for RestoreComponentRequest {
+    fn execute(&mut self, manifest: &mut PaxManifest) -> Result<RestoreComponentResponse, String> {
+        let type_id = &self.snapshot.type_id;
+        let component = manifest
+            .components
+            .get_mut(type_id)
+            .ok_or_else(|| format!("Component {} not found", type_id))?;
+        self._cached_component = Some(component.clone());
+        *component = self.snapshot.clone();
+
+        Ok(RestoreComponentResponse {
+            command_id: None,
+            description: None,
+            _affected_component_type_id: type_id.clone(),
+        })
+    }
+
+    fn description(&self) -> String {
+        format!("Restore {}", type_name(&self.snapshot.type_id))
+    }
+
+    fn as_undo_redo(&mut self) -> Option<UndoRedoCommand> {
+        Some(UndoRedoCommand::RestoreComponentRequest(Box::new(
+            self.clone(),
+        )))
+    }
+}
+
+impl Undo for RestoreComponentRequest {
+    fn undo(&mut self, manifest: &mut PaxManifest) -> Result<(), String> {
+        if let Some(component) = &self._cached_component {
+            manifest
+                .components
+                .insert(component.type_id.clone(), component.clone());
+        }
+        Ok(())
+    }
+}
+
+/// Turns a node a component inherited with `@extends` into an override of the labeled node
+/// of the base, so that it and its descendants can be edited, see
+/// `PaxManifestORM::override_inherited_node`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct OverrideInheritedNodeRequest {
+    uni: UniqueTemplateNodeIdentifier,
+    // Used for Undo/Redo
+    _cached_inherited_nodes: Option<HashSet<TemplateNodeId>>,
+}
+
+impl OverrideInheritedNodeRequest {
+    pub fn new(uni: UniqueTemplateNodeIdentifier) -> Self {
+        Self {
+            uni,
+            _cached_inherited_nodes: None,
+        }
+    }
+}
+
+pub struct OverrideInheritedNodeResponse {
+    command_id: Option<usize>,
+    description: Option<String>,
+    _affected_component_type_id: TypeId,
+}
+
+impl Request for OverrideInheritedNodeRequest {
+    type Response = OverrideInheritedNodeResponse;
+}
+
+impl Response for OverrideInheritedNodeResponse {
+    fn set_id(&mut self, id: usize) {
+        self.command_id = Some(id);
+    }
+    fn get_id(&self) -> usize {
+        self.command_id.unwrap()
+    }
+    fn set_description(&mut self, description: String) {
+        self.description = Some(description);
+    }
+    fn get_description(&self) -> &str {
+        self.description.as_deref().unwrap_or_default()
+    }
+    fn get_affected_components(&self) -> Vec<TypeId> {
+        vec![self._affected_component_type_id.clone()]
+    }
+    fn get_reload_type(&self) -> Option<ReloadType> {
+        Some(ReloadType::FullEdit)
+    }
+}
+
+impl Command<OverrideInheritedNodeRequest> for OverrideInheritedNodeRequest {
+    fn execute(
+        &mut self,
+        manifest: &mut PaxManifest,
+    ) -> Result<OverrideInheritedNodeResponse, String> {
+        let type_id = self.uni.get_containing_component_type_id();
+        let component = manifest
+            .components
+            .get_mut(&type_id)
+            .ok_or_else(|| format!("Component {} not found", type_id))?;
+        let id = self.uni.get_template_node_id();
+        let (Some(template), Some(extension)) = (&component.template, &mut component.extends)
+        else {
+            return Err("Component doesn't extend another component".to_string());
+        };
+        if !extension.inherited_nodes.contains(&id) {
+            return Err("Node isn't inherited".to_string());
+        }
+        let node = template
+            .get_node(&id)
+            .ok_or_else(|| format!("Node {} not found", id))?;
+        if node.get_template_label().is_none() {
+            return Err(format!(
+                "Only nodes with a {} can be overridden",
+                constants::TEMPLATE_LABEL_ATTRIBUTE
+            ));
+        }
+
+        self._cached_inherited_nodes = Some(extension.inherited_nodes.clone());
+        let mut subtree = vec![id];
+        while let Some(id) = subtree.pop() {
+            subtree.extend(template.get_children(&id).unwrap_or_default());
+            extension.inherited_nodes.remove(&id);
+        }
+
+        Ok(OverrideInheritedNodeResponse {
+            command_id: None,
+            description: None,
+            _affected_component_type_id: type_id,
+        })
+    }
+
+    fn description(&self) -> String {
+        "Override inherited node".to_string()
+    }
+
+    fn as_undo_redo(&mut self) -> Option<UndoRedoCommand> {
+        Some(UndoRedoCommand::OverrideInheritedNodeRequest(Box::new(
+            self.clone(),
+        )))
+    }
+}
+
+impl Undo for OverrideInheritedNodeRequest {
+    fn undo(&mut self, manifest: &mut PaxManifest) -> Result<(), String> {
+        let component = manifest
+            .components
+            .get_mut(&self.uni.get_containing_component_type_id());
+        if let (Some(extension), Some(inherited_nodes)) = (
+            component.and_then(|component| component.extends.as_mut()),
+            &self._cached_inherited_nodes,
+        ) {
+            extension.inherited_nodes.clone_from(inherited_nodes);
+        }
+        Ok(())
+    }
+}
+
+/// Names a node for designers, or clears its name, see `PaxManifestORM::set_node_label`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SetNodeLabelRequest {
+    uni: UniqueTemplateNodeIdentifier,
+    label: Option<String>,
+    // Used for Undo/Redo
+    _cached_label: Option<String>,
+}
+
+impl SetNodeLabelRequest {
+    pub fn new(uni: UniqueTemplateNodeIdentifier, label: Option<String>) -> Self {
+        Self {
+            uni,
+            label,
+            _cached_label: None,
+        }
+    }
+}
+
+pub struct SetNodeLabelResponse {
+    command_id: Option<usize>,
+    description: Option<String>,
+    _affected_unique_node_identifier: UniqueTemplateNodeIdentifier,
+}
+
+impl Request for SetNodeLabelRequest {
+    type Response = SetNodeLabelResponse;
+}
+
+impl Response for SetNodeLabelResponse {
+    fn set_id(&mut self, id: usize) {
+        self.command_id = Some(id);
+    }
+    fn get_id(&self) -> usize {
+        self.command_id.unwrap()
+    }
+    fn set_description(&mut self, description: String) {
+        self.description = Some(description);
+    }
+    fn get_description(&self) -> &str {
+        self.description.as_deref().unwrap_or_default()
+    }
+    fn get_affected_components(&self) -> Vec<TypeId> {
+        vec![self
+            ._affected_unique_node_identifier
+            .get_containing_component_type_id()]
+    }
+    fn get_reload_type(&self) -> Option<ReloadType> {
+        Some(ReloadType::Partial(
+            self._affected_unique_node_identifier.clone(),
+        ))
+    }
+}
+
+impl Command<SetNodeLabelRequest> for SetNodeLabelRequest {
+    fn execute(&mut self, manifest: &mut PaxManifest) -> Result<SetNodeLabelResponse, String> {
+        let type_id = self.uni.get_containing_component_type_id();
+        let component = manifest
+            .components
+            .get_mut(&type_id)
+            .ok_or_else(|| format!("Component {} not found", type_id))?;
+        let id = self.uni.get_template_node_id();
+        check_not_inherited(component, &id)?;
+        let template = component
+            .template
+            .as_mut()
+            .ok_or_else(|| format!("Node {} not found", id))?;
+        let node = template
+            .get_node(&id)
+            .ok_or_else(|| format!("Node {} not found", id))?;
+        // the label is written back as an attribute, which only elements have
+        if node.settings.is_none() {
+            return Err("Only elements can be labeled".to_string());
+        }
+
+        self._cached_label = node.label.clone();
+        let label = self.label.clone().filter(|label| !label.trim().is_empty());
+        template.update_node_label(&id, label);
+
+        Ok(SetNodeLabelResponse {
+            command_id: None,
+            description: None,
+            _affected_unique_node_identifier: self.uni.clone(),
+        })
+    }
+
+    fn description(&self) -> String {
+        match &self.label {
+            Some(label) => format!("Label node \"{}\"", label),
+            None => "Remove node label".to_string(),
+        }
+    }
+
+    fn as_undo_redo(&mut self) -> Option<UndoRedoCommand> {
+        Some(UndoRedoCommand::SetNodeLabelRequest(Box::new(self.clone())))
+    }
+}
+
+impl Undo for SetNodeLabelRequest {
+    fn undo(&mut self, manifest: &mut PaxManifest) -> Result<(), String> {
+        let template = manifest
+            .components
+            .get_mut(&self.uni.get_containing_component_type_id())
+            .and_then(|component| component.template.as_mut());
+        if let Some(template) = template {
+            template
+                .update_node_label(&self.uni.get_template_node_id(), self._cached_label.clone());
+        }
+        Ok(())
+    }
+}
+
+/// Changes the component a node instantiates, e.g. to fix a node of an unresolved type, see
+/// `PaxManifestORM::replace_node_type`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ReplaceNodeTypeRequest {
+    uni: UniqueTemplateNodeIdentifier,
+    type_id: TypeId,
+    // Used for Undo/Redo
+    _cached_node: Option<TemplateNodeDefinition>,
+}
+
+impl ReplaceNodeTypeRequest {
+    pub fn new(uni: UniqueTemplateNodeIdentifier, type_id: TypeId) -> Self {
+        Self {
+            uni,
+            type_id,
+            _cached_node: None,
+        }
+    }
+}
+
+pub struct ReplaceNodeTypeResponse {
+    command_id: Option<usize>,
+    description: Option<String>,
+    _affected_unique_node_identifier: UniqueTemplateNodeIdentifier,
+}
+
+impl Request for ReplaceNodeTypeRequest {
+    type Response = ReplaceNodeTypeResponse;
+}
+
+impl Response for ReplaceNodeTypeResponse {
+    fn set_id(&mut self, id: usize) {
+        self.command_id = Some(id);
+    }
+    fn get_id(&self) -> usize {
+        self.command_id.unwrap()
+    }
+    fn set_description(&mut self, description: String) {
+        self.description = Some(description);
+    }
+    fn get_description(&self) -> &str {
+        self.description.as_deref().unwrap_or_default()
+    }
+    fn get_affected_components(&self) -> Vec<TypeId> {
+        vec![self
+            ._affected_unique_node_identifier
+            .get_containing_component_type_id()]
+    }
+    fn get_reload_type(&self) -> Option<ReloadType> {
+        Some(ReloadType::Partial(
+            self._affected_unique_node_identifier.clone(),
+        ))
+    }
+}
+
+impl Command<ReplaceNodeTypeRequest> for ReplaceNodeTypeRequest {
+    fn execute(&mut self, manifest: &mut PaxManifest) -> Result<ReplaceNodeTypeResponse, String> {
+        if manifest.is_unresolved_type(&self.type_id) {
+            return Err(format!(
+                "`{}` isn't a known component",
+                type_name(&self.type_id)
+            ));
+        }
+        // settings are kept by name where the new type has them, along with the ones every node has
+        let mut kept_keys = manifest.get_all_property_names(&self.type_id);
+        kept_keys.extend(
+            [
+                "id",
+                "class",
+                constants::TEMPLATE_LABEL_ATTRIBUTE,
+                constants::CFG_ATTRIBUTE,
+            ]
+            .map(String::from),
+        );
+
+        let containing_type_id = self.uni.get_containing_component_type_id();
+        let component = manifest
+            .components
+            .get_mut(&containing_type_id)
+            .ok_or_else(|| format!("Component {} not found", containing_type_id))?;
+        let id = self.uni.get_template_node_id();
+        check_not_inherited(component, &id)?;
+        let template = component
+            .template
+            .as_mut()
+            .ok_or_else(|| format!("Node {} not found", id))?;
+        let node = template
+            .get_node(&id)
+            .ok_or_else(|| format!("Node {} not found", id))?;
+        if node.settings.is_none() {
+            return Err("Only elements can change type".to_string());
+        }
+
+        let mut replacement = node.clone();
+        replacement.type_id = self.type_id.clone();
+        replacement.settings = node.settings.as_ref().map(|settings| {
+            settings
+                .iter()
+                .filter(|element| match element {
+                    SettingElement::Setting(key, value) => {
+                        matches!(value, ValueDefinition::EventBindingTarget(_))
+                            || kept_keys.contains(&key.token_value)
+                    }
+                    SettingElement::Comment(_) => true,
+                })
+                .cloned()
+                .collect()
+        });
+        self._cached_node = Some(node.clone());
+        template.set_node(id, replacement);
+
+        Ok(ReplaceNodeTypeResponse {
+            command_id: None,
+            description: None,
+            _affected_unique_node_identifier: self.uni.clone(),
+        })
+    }
+
+    fn description(&self) -> String {
+        format!("Replace node with {}", type_name(&self.type_id))
+    }
+
+    fn as_undo_redo(&mut self) -> Option<UndoRedoCommand> {
+        Some(UndoRedoCommand::ReplaceNodeTypeRequest(Box::new(
+            self.clone(),
+        )))
+    }
+}
+
+impl Undo for ReplaceNodeTypeRequest {
+    fn undo(&mut self, manifest: &mut PaxManifest) -> Result<(), String> {
+        let template = manifest
+            .components
+            .get_mut(&self.uni.get_containing_component_type_id())
+            .and_then(|component| component.template.as_mut());
+        if let (Some(template), Some(node)) = (template, self._cached_node.clone()) {
+            template.set_node(self.uni.get_template_node_id(), node);
+        }
+        Ok(())
+    }
+}
+
+/// Adds new components to the manifest, e.g. those of an imported snippet,
+/// see `PaxManifestORM::add_components`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AddComponentsRequest {
+    components: Vec<ComponentDefinition>,
+}
+
+impl AddComponentsRequest {
+    pub fn new(components: Vec<ComponentDefinition>) -> Self {
+        Self { components }
+    }
+}
+
+pub struct AddComponentsResponse {
+    command_id: Option<usize>,
+    description: Option<String>,
+    pub type_ids: Vec<TypeId>,
+}
+
+impl Request for AddComponentsRequest {
+    type Response = AddComponentsResponse;
+}
+
+impl Response for AddComponentsResponse {
+    fn set_id(&mut self, id: usize) {
+        self.command_id = Some(id);
+    }
+    fn get_id(&self) -> usize {
+        self.command_id.unwrap()
+    }
+    fn set_description(&mut self, description: String) {
+        self.description = Some(description);
+    }
+    fn get_description(&self) -> &str {
+        self.description.as_deref().unwrap_or_default()
+    }
+    fn get_affected_components(&self) -> Vec<TypeId> {
+        self.type_ids.clone()
+    }
+    fn get_reload_type(&self) -> Option<ReloadType> {
+        Some(ReloadType::FullEdit)
+    }
+}
+
+impl Command<AddComponentsRequest> for AddComponentsRequest {
+    fn execute(&mut self, manifest: &mut PaxManifest) -> Result<AddComponentsResponse, String> {
+        if let Some(existing) = self
+            .components
+            .iter()
+            .find(|c| manifest.components.contains_key(&c.type_id))
+        {
+            return Err(format!(
+                "Component {} already exists",
+                type_name(&existing.type_id)
+            ));
+        }
+        for component in &self.components {
+            manifest
+                .components
+                .insert(component.type_id.clone(), component.clone());
+        }
+
+        Ok(AddComponentsResponse {
+            command_id: None,
+            description: None,
+            type_ids: self.components.iter().map(|c| c.type_id.clone()).collect(),
+        })
+    }
+
+    fn description(&self) -> String {
+        match self.components.as_slice() {
+            [component] => format!("Add component {}", type_name(&component.type_id)),
+            components => format!("Add {} components", components.len()),
+        }
+    }
+
+    fn as_undo_redo(&mut self) -> Option<UndoRedoCommand> {
+        Some(UndoRedoCommand::AddComponentsRequest(Box::new(
+            self.clone(),
+        )))
+    }
+}
+
+impl Undo for AddComponentsRequest {
+    fn undo(&mut self, manifest: &mut PaxManifest) -> Result<(), String> {
+        for component in &self.components {
+            manifest.components.remove(&component.type_id);
+        }
+        Ok(())
+    }
+}
@@ -1,9 +1,18 @@
 #[cfg(test)]
 mod tests {
-    use crate::orm::PaxManifestORM;
+    use crate::orm::diff::diff_components;
+    use crate::orm::selectors::DeleteSelectorPolicy;
+    use crate::orm::session::{
+        manifest_hash, DivergenceReason, RecordingOptions, SessionRecording, REDACTED,
+    };
+    use crate::orm::{MoveToComponentEntry, PaxManifestORM};
+    use pax_manifest::cartridge_generation::SettingSource;
+    use pax_manifest::pax_runtime_api::PaxValue;
     use pax_manifest::{
-        ComponentDefinition, LiteralBlockDefinition, PaxManifest, SettingsBlockElement, Token,
-        TypeId,
+        utils, ComponentDefinition, ComponentTemplate, LiteralBlockDefinition, NodeLocation,
+        PaxIdentifier, PaxManifest, PropertyDefinition, SettingElement, SettingsBlockElement,
+        TemplateNodeDefinition, Token, TypeDefinition, TypeId, UniqueTemplateNodeIdentifier,
+        ValueDefinition,
     };
     use std::collections::{BTreeMap, HashMap};
 
@@ -24,6 +33,7 @@ mod tests {
                     Token::new_without_location("existing_selector".to_string()),
                     LiteralBlockDefinition::new(vec![]),
                 )]),
+                extends: None,
             },
         );
 
@@ -33,6 +43,9 @@ mod tests {
             type_table: HashMap::new(),
             assets_dirs: vec![],
             engine_import_path: "".to_string(),
+            defines: BTreeMap::new(),
+            tokens: BTreeMap::new(),
+            build_cfg: None,
         }
     }
 
@@ -67,4 +80,764 @@ mod tests {
             .template
             .is_some());
     }
+
+    #[test]
+    fn test_command_descriptions() {
+        let mut orm = PaxManifestORM::new(create_basic_manifest());
+        let type_id: TypeId = TypeId::build_singleton("Component1", Some("Component1"));
+        let rectangle_type_id: TypeId = TypeId::build_singleton("Rectangle", Some("Rectangle"));
+
+        let mut node_builder = orm.build_new_node(type_id.clone(), rectangle_type_id);
+        node_builder.set_property("x", "10px").unwrap();
+        let save_data = node_builder.save().unwrap();
+        assert_eq!(save_data.description, "Add Rectangle");
+        assert_eq!(orm.get_last_undo_description(), Some("Add Rectangle"));
+
+        let mut node_builder = orm.get_node(save_data.unique_id, false).unwrap();
+        node_builder.set_property("x", "20px").unwrap();
+        let save_data = node_builder.save().unwrap();
+        assert_eq!(save_data.description, "Update Rectangle");
+
+        let history: Vec<_> = orm
+            .get_undo_history()
+            .into_iter()
+            .map(|(_, description)| description)
+            .collect();
+        assert_eq!(history, vec!["Add Rectangle", "Update Rectangle"]);
+
+        orm.undo().unwrap();
+        assert_eq!(orm.get_last_undo_description(), Some("Add Rectangle"));
+        assert_eq!(orm.get_last_redo_description(), Some("Update Rectangle"));
+    }
+
+    #[test]
+    fn test_get_root_nodes() {
+        let mut orm = PaxManifestORM::new(create_basic_manifest());
+        let type_id: TypeId = TypeId::build_singleton("Component1", Some("Component1"));
+        let group_type_id: TypeId = TypeId::build_singleton("Group", Some("Group"));
+        let rectangle_type_id: TypeId = TypeId::build_singleton("Rectangle", Some("Rectangle"));
+        assert!(orm.get_root_nodes(&type_id).is_empty());
+
+        let group = orm
+            .build_new_node(type_id.clone(), group_type_id)
+            .save()
+            .unwrap()
+            .unique_id;
+        let rectangle = orm
+            .build_new_node(type_id.clone(), rectangle_type_id.clone())
+            .save()
+            .unwrap()
+            .unique_id;
+        let child = orm
+            .build_new_node(type_id.clone(), rectangle_type_id)
+            .save()
+            .unwrap()
+            .unique_id;
+        orm.move_node(
+            child.clone(),
+            NodeLocation::parent(type_id.clone(), group.get_template_node_id()),
+        )
+        .unwrap();
+
+        // new nodes are added to the top of the template
+        assert_eq!(orm.get_root_nodes(&type_id), vec![rectangle, group.clone()]);
+        assert_eq!(orm.get_parent(&child), Some(group));
+    }
+
+    #[test]
+    fn test_get_node_descendants() {
+        let mut orm = PaxManifestORM::new(create_basic_manifest());
+        let type_id: TypeId = TypeId::build_singleton("Component1", Some("Component1"));
+        let group_type_id: TypeId = TypeId::build_singleton("Group", Some("Group"));
+        let rectangle_type_id: TypeId = TypeId::build_singleton("Rectangle", Some("Rectangle"));
+
+        let mut add_node =
+            |type_id_to_add: &TypeId, parent: Option<&UniqueTemplateNodeIdentifier>| {
+                let uni = orm
+                    .build_new_node(type_id.clone(), type_id_to_add.clone())
+                    .save()
+                    .unwrap()
+                    .unique_id;
+                if let Some(parent) = parent {
+                    orm.move_node(
+                        uni.clone(),
+                        NodeLocation::parent(type_id.clone(), parent.get_template_node_id()),
+                    )
+                    .unwrap();
+                }
+                uni
+            };
+        let outer = add_node(&group_type_id, None);
+        let inner = add_node(&group_type_id, Some(&outer));
+        let leaf = add_node(&rectangle_type_id, Some(&inner));
+        let sibling = add_node(&rectangle_type_id, Some(&outer));
+
+        assert_eq!(
+            orm.get_node_descendants(outer.clone()),
+            vec![sibling, inner.clone(), leaf.clone()]
+        );
+        assert_eq!(orm.get_node_descendants(inner), vec![leaf.clone()]);
+        assert!(orm.get_node_descendants(leaf).is_empty());
+    }
+
+    #[test]
+    fn test_component_history() {
+        let mut orm = PaxManifestORM::new(create_basic_manifest());
+        let type_id: TypeId = TypeId::build_singleton("Component1", Some("Component1"));
+        let rectangle_type_id: TypeId = TypeId::build_singleton("Rectangle", Some("Rectangle"));
+        let node_count = |orm: &PaxManifestORM| {
+            orm.get_manifest().components[&type_id]
+                .template
+                .as_ref()
+                .map(|t| t.get_nodes().len())
+                .unwrap_or_default()
+        };
+        assert!(orm.get_component_history(&type_id).is_empty());
+
+        let node_builder = orm.build_new_node(type_id.clone(), rectangle_type_id.clone());
+        node_builder.save().unwrap();
+        let node_builder = orm.build_new_node(type_id.clone(), rectangle_type_id);
+        node_builder.save().unwrap();
+        assert_eq!(orm.get_component_history(&type_id).len(), 2);
+        assert_eq!(node_count(&orm), 2);
+
+        orm.restore_component_to_snapshot(&type_id, 0).unwrap();
+        assert_eq!(node_count(&orm), 1);
+        assert_eq!(orm.get_component_history(&type_id).len(), 3);
+        assert!(orm.restore_component_to_snapshot(&type_id, 10).is_err());
+
+        // restoring is undoable
+        orm.undo().unwrap();
+        assert_eq!(node_count(&orm), 2);
+
+        orm.set_component_history_capacity(1);
+        assert_eq!(orm.get_component_history(&type_id).len(), 1);
+    }
+
+    #[test]
+    fn test_diff_against_saved() {
+        let mut orm = PaxManifestORM::new(create_basic_manifest());
+        let type_id: TypeId = TypeId::build_singleton("Component1", Some("Component1"));
+        let rectangle_type_id: TypeId = TypeId::build_singleton("Rectangle", Some("Rectangle"));
+        assert!(orm.diff_against_saved(&type_id).unwrap().is_empty());
+
+        let mut node_builder = orm.build_new_node(type_id.clone(), rectangle_type_id.clone());
+        node_builder.set_property("x", "10px").unwrap();
+        let rectangle = node_builder.save().unwrap().unique_id;
+        let diff = orm.diff_against_saved(&type_id).unwrap();
+        assert_eq!(diff.change_count(), 1);
+        assert_eq!(diff.describe(), vec!["<Rectangle> #0: added (x=10px)"]);
+
+        orm.mark_saved(orm.get_component(&type_id).unwrap().clone());
+        assert!(orm.diff_against_saved(&type_id).unwrap().is_empty());
+
+        // setting a property to its current value doesn't show up
+        let mut node_builder = orm.get_node(rectangle.clone(), false).unwrap();
+        node_builder.set_property("x", "10px").unwrap();
+        node_builder.save().unwrap();
+        assert!(orm.diff_against_saved(&type_id).unwrap().is_empty());
+
+        let mut node_builder = orm.get_node(rectangle, false).unwrap();
+        node_builder.set_property("x", "20px").unwrap();
+        node_builder.set_property("y", "5px").unwrap();
+        node_builder.save().unwrap();
+        let diff = orm.diff_against_saved(&type_id).unwrap();
+        assert_eq!(diff.change_count(), 2);
+        assert_eq!(
+            diff.describe(),
+            vec![
+                "<Rectangle> #0: x: 10px → 20px",
+                "<Rectangle> #0: y: unset → 5px",
+            ]
+        );
+
+        // the session diff still includes the saved changes
+        let diff = orm.diff_against_loaded(&type_id).unwrap();
+        assert_eq!(
+            diff.describe(),
+            vec!["<Rectangle> #0: added (x=20px, y=5px)"]
+        );
+
+        let old = orm.get_component(&type_id).unwrap().clone();
+        let mut new = old.clone();
+        new.settings
+            .get_or_insert_with(Vec::new)
+            .push(SettingsBlockElement::Handler(
+                Token::new_without_location("mount".to_string()),
+                vec![Token::new_without_location("on_mount".to_string())],
+            ));
+        let diff = diff_components(&old, &new);
+        assert!(diff.selectors.is_empty());
+        assert_eq!(diff.describe(), vec!["@mount: unset → on_mount"]);
+    }
+
+    #[test]
+    fn test_move_to_new_component_names() {
+        let mut manifest = create_basic_manifest();
+        let type_id = TypeId::build_singleton("designer_project::Example", None);
+        manifest.components.insert(
+            type_id.clone(),
+            ComponentDefinition {
+                type_id: type_id.clone(),
+                template: Some(ComponentTemplate::new(
+                    type_id.clone(),
+                    Some("/project/src/example.pax".to_string()),
+                )),
+                settings: None,
+                ..manifest.components[&manifest.main_component_type_id].clone()
+            },
+        );
+        let mut orm = PaxManifestORM::new(manifest);
+        let rectangle_type_id: TypeId = TypeId::build_singleton("Rectangle", Some("Rectangle"));
+        let move_new_rectangle = |orm: &mut PaxManifestORM, name: Option<&str>| {
+            let id = orm
+                .build_new_node(type_id.clone(), rectangle_type_id.clone())
+                .save()
+                .unwrap()
+                .unique_id;
+            let entry = MoveToComponentEntry {
+                x: 0.0,
+                y: 0.0,
+                width: 10.0,
+                height: 10.0,
+                id,
+            };
+            orm.move_to_new_component(&[entry], name, 0.0, 0.0, 10.0, 10.0)
+                .unwrap()
+        };
+        let file_path = |orm: &PaxManifestORM, type_id: &TypeId| {
+            orm.get_component(type_id)
+                .unwrap()
+                .template
+                .as_ref()
+                .unwrap()
+                .get_file_path()
+                .unwrap()
+                .clone()
+        };
+
+        let card = move_new_rectangle(&mut orm, Some("profile card"));
+        assert_eq!(card.get_pascal_identifier().unwrap(), "ProfileCard");
+        assert_eq!(file_path(&orm, &card), "/project/src/profile_card.pax");
+        let card = move_new_rectangle(&mut orm, Some("ProfileCard"));
+        assert_eq!(card.get_pascal_identifier().unwrap(), "ProfileCard2");
+        assert_eq!(file_path(&orm, &card), "/project/src/profile_card2.pax");
+        // taken by a component of another kind
+        let component = move_new_rectangle(&mut orm, Some("component1"));
+        assert_eq!(component.get_pascal_identifier().unwrap(), "Component12");
+
+        let unnamed = move_new_rectangle(&mut orm, None);
+        assert_eq!(unnamed.get_pascal_identifier().unwrap(), "NewComponent1");
+        let unnamed = move_new_rectangle(&mut orm, Some("  "));
+        assert_eq!(unnamed.get_pascal_identifier().unwrap(), "NewComponent2");
+
+        // redo recreates the component under the same name
+        orm.undo().unwrap();
+        assert!(orm.get_component(&unnamed).is_err());
+        orm.redo().unwrap();
+        assert_eq!(file_path(&orm, &unnamed), "/project/src/new_component2.pax");
+    }
+
+    #[test]
+    fn test_add_components() {
+        let mut orm = PaxManifestORM::new(create_basic_manifest());
+        let type_id = TypeId::build_blank_component("Imported");
+        let component = ComponentDefinition {
+            type_id: type_id.clone(),
+            is_main_component: false,
+            is_primitive: false,
+            is_struct_only_component: false,
+            module_path: "module_path1".to_string(),
+            primitive_instance_import_path: None,
+            template: None,
+            settings: None,
+            extends: None,
+        };
+
+        orm.add_components(vec![component.clone()]).unwrap();
+        assert!(orm.get_manifest().components.contains_key(&type_id));
+        assert_eq!(
+            orm.get_last_undo_description(),
+            Some("Add component Imported")
+        );
+        assert_eq!(orm.get_new_components().len(), 1);
+
+        // components can't be added twice
+        assert!(orm.add_components(vec![component]).is_err());
+
+        orm.undo().unwrap();
+        assert!(!orm.get_manifest().components.contains_key(&type_id));
+    }
+
+    #[test]
+    fn test_node_labels() {
+        let mut orm = PaxManifestORM::new(create_basic_manifest());
+        let type_id: TypeId = TypeId::build_singleton("Component1", Some("Component1"));
+        let rectangle_type_id: TypeId = TypeId::build_singleton("Rectangle", Some("Rectangle"));
+        let rectangle = orm
+            .build_new_node(type_id.clone(), rectangle_type_id)
+            .save()
+            .unwrap()
+            .unique_id;
+        assert_eq!(orm.get_node_label(&rectangle), None);
+
+        orm.set_node_label(rectangle.clone(), Some("CTA Button".to_string()))
+            .unwrap();
+        assert_eq!(
+            orm.get_node_label(&rectangle).as_deref(),
+            Some("CTA Button")
+        );
+        assert_eq!(
+            orm.get_last_undo_description(),
+            Some("Label node \"CTA Button\"")
+        );
+
+        // blank labels clear the label
+        orm.set_node_label(rectangle.clone(), Some(" ".to_string()))
+            .unwrap();
+        assert_eq!(orm.get_node_label(&rectangle), None);
+
+        orm.undo().unwrap();
+        assert_eq!(
+            orm.get_node_label(&rectangle).as_deref(),
+            Some("CTA Button")
+        );
+        orm.undo().unwrap();
+        assert_eq!(orm.get_node_label(&rectangle), None);
+        orm.redo().unwrap();
+        assert_eq!(
+            orm.get_node_label(&rectangle).as_deref(),
+            Some("CTA Button")
+        );
+    }
+
+    #[test]
+    fn test_replace_node_type() {
+        let mut manifest = create_basic_manifest();
+        let type_id: TypeId = TypeId::build_singleton("Component1", Some("Component1"));
+        let card_type_id = TypeId::build_singleton("crate::Card", Some("Card"));
+        let mut card = manifest.components[&type_id].clone();
+        card.type_id = card_type_id.clone();
+        manifest.components.insert(card_type_id.clone(), card);
+        manifest.type_table.insert(
+            card_type_id.clone(),
+            TypeDefinition {
+                type_id: card_type_id.clone(),
+                inner_iterable_type_id: None,
+                property_definitions: vec![PropertyDefinition {
+                    name: "title".to_string(),
+                    ..Default::default()
+                }],
+            },
+        );
+
+        // a node of a component since renamed from `OldCard` to `Card`
+        let setting = |key: &str, value: ValueDefinition| {
+            SettingElement::Setting(Token::new_without_location(key.to_string()), value)
+        };
+        let literal = |value: &str| ValueDefinition::LiteralValue(PaxValue::String(value.into()));
+        let mut template = ComponentTemplate::new(type_id.clone(), None);
+        let old_card = template.add_root_node_back(TemplateNodeDefinition {
+            type_id: TypeId::build_unresolved("OldCard"),
+            control_flow_settings: None,
+            settings: Some(vec![
+                setting("x", literal("10px")),
+                setting("title", literal("Hello")),
+                setting("subtitle", literal("World")),
+                setting(
+                    "click",
+                    ValueDefinition::EventBindingTarget(PaxIdentifier::new("self.on_click")),
+                ),
+            ]),
+            raw_comment_string: None,
+            label: None,
+        });
+        manifest.components.get_mut(&type_id).unwrap().template = Some(template);
+        let mut orm = PaxManifestORM::new(manifest);
+        let setting_keys = |orm: &PaxManifestORM| -> Vec<String> {
+            let node = orm.get_manifest().get_template_node(&old_card).unwrap();
+            node.settings
+                .iter()
+                .flatten()
+                .filter_map(|s| match s {
+                    SettingElement::Setting(key, _) => Some(key.token_value.clone()),
+                    SettingElement::Comment(_) => None,
+                })
+                .collect()
+        };
+
+        // only known components can replace a node
+        assert!(orm
+            .replace_node_type(old_card.clone(), TypeId::build_unresolved("Card"))
+            .is_err());
+
+        // settings are kept by name, along with the handlers
+        orm.replace_node_type(old_card.clone(), card_type_id.clone())
+            .unwrap();
+        let node = orm.get_manifest().get_template_node(&old_card).unwrap();
+        assert_eq!(node.type_id, card_type_id);
+        assert_eq!(setting_keys(&orm), vec!["x", "title", "click"]);
+        assert_eq!(
+            orm.get_last_undo_description(),
+            Some("Replace node with Card")
+        );
+
+        orm.undo().unwrap();
+        let node = orm.get_manifest().get_template_node(&old_card).unwrap();
+        assert!(node.type_id.is_unresolved());
+        assert_eq!(setting_keys(&orm), vec!["x", "title", "subtitle", "click"]);
+    }
+
+    #[test]
+    fn test_tokens() {
+        use crate::orm::ReloadType;
+        use pax_manifest::pax_runtime_api::PaxValue;
+
+        let mut orm = PaxManifestORM::new(create_basic_manifest());
+        let type_id: TypeId = TypeId::build_singleton("Component1", Some("Component1"));
+        let rectangle_type_id: TypeId = TypeId::build_singleton("Rectangle", Some("Rectangle"));
+        let mut node_builder = orm.build_new_node(type_id.clone(), rectangle_type_id);
+        node_builder
+            .set_property("fill", "{$tokens.primary}")
+            .unwrap();
+        node_builder.save().unwrap();
+        let history_len = orm.get_component_history(&type_id).len();
+        orm.take_reload_queue();
+
+        let red = PaxValue::String("red".to_string());
+        let blue = PaxValue::String("blue".to_string());
+        orm.set_token("primary".to_string(), red.clone()).unwrap();
+        orm.set_token("primary".to_string(), blue.clone()).unwrap();
+        assert_eq!(orm.get_tokens().get("primary"), Some(&blue));
+        assert_eq!(
+            orm.get_last_undo_description(),
+            Some("Set token \"primary\"")
+        );
+        // the referencing component is reloaded and snapshotted
+        assert!(orm.take_reload_queue().contains(&ReloadType::FullEdit));
+        assert_eq!(orm.get_component_history(&type_id).len(), history_len + 2);
+
+        orm.undo().unwrap();
+        assert_eq!(orm.get_tokens().get("primary"), Some(&red));
+        orm.undo().unwrap();
+        assert!(orm.get_tokens().is_empty());
+        orm.redo().unwrap();
+        assert_eq!(orm.get_tokens().get("primary"), Some(&red));
+
+        assert!(orm.set_token(" ".to_string(), red).is_err());
+    }
+
+    #[test]
+    fn test_rpc() {
+        use crate::orm::rpc::{ManifestRpcServer, METHOD_NOT_FOUND, PARSE_ERROR};
+        use crate::orm::template::AddTemplateNodeRequest;
+        use pax_manifest::{NodeType, UniqueTemplateNodeIdentifier};
+        use serde_json::{json, Value};
+
+        let mut server = ManifestRpcServer::new(PaxManifestORM::new(create_basic_manifest()));
+        let type_id: TypeId = TypeId::build_singleton("Component1", Some("Component1"));
+        let rectangle_type_id: TypeId = TypeId::build_singleton("Rectangle", Some("Rectangle"));
+        let mut call = |id: usize, method: &str, params: Value| -> Value {
+            let request = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+            let response = server.handle_message(&request.to_string()).unwrap();
+            let response: Value = serde_json::from_str(&response).unwrap();
+            assert_eq!(response["id"], id);
+            response
+        };
+
+        let add = AddTemplateNodeRequest::new(
+            type_id.clone(),
+            rectangle_type_id,
+            NodeType::Template(vec![]),
+            None,
+        );
+        let response = call(1, "build_node", serde_json::to_value(add).unwrap());
+        assert_eq!(response["result"]["description"], "Add Rectangle");
+        let uni: UniqueTemplateNodeIdentifier =
+            serde_json::from_value(response["result"]["uni"].clone()).unwrap();
+
+        let response = call(2, "get_node", json!({ "uni": uni }));
+        assert!(response["result"]["node"].is_object());
+
+        let response = call(3, "undo", Value::Null);
+        assert_eq!(response["result"]["redo"][0][1], "Add Rectangle");
+        let response = call(4, "get_node", json!({ "uni": uni }));
+        assert!(response["result"]["node"].is_null());
+        let response = call(5, "redo", Value::Null);
+        assert_eq!(response["result"]["undo"][0][1], "Add Rectangle");
+
+        let response = call(6, "remove_node", json!({ "uni": uni }));
+        assert_eq!(response["result"]["description"], "Remove Rectangle");
+
+        let response = call(7, "unknown", Value::Null);
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        // invalid params and failing commands are errors, not panics
+        let response = call(8, "move_node", json!({ "uni": uni }));
+        assert!(response["error"].is_object());
+
+        // notifications aren't answered
+        let notification = json!({ "jsonrpc": "2.0", "method": "get_manifest" });
+        assert!(server.handle_message(&notification.to_string()).is_none());
+
+        let response: Value = serde_json::from_str(&server.handle_message("{").unwrap()).unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+        assert!(server.get_orm().get_manifest().components[&type_id]
+            .template
+            .as_ref()
+            .unwrap()
+            .get_nodes()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_session_recording_replays() {
+        let mut orm = PaxManifestORM::new(create_basic_manifest());
+        let type_id: TypeId = TypeId::build_singleton("Component1", Some("Component1"));
+        let rectangle_type_id: TypeId = TypeId::build_singleton("Rectangle", Some("Rectangle"));
+        orm.start_recording(RecordingOptions {
+            window_ms: None,
+            redact_strings: false,
+        });
+
+        let mut node_builder = orm.build_new_node(type_id.clone(), rectangle_type_id.clone());
+        node_builder.set_property("x", "10px").unwrap();
+        let rectangle = node_builder.save().unwrap().unique_id;
+        orm.record_action("SelectNodes");
+        let mut node_builder = orm.get_node(rectangle.clone(), false).unwrap();
+        node_builder.set_property("x", "20px").unwrap();
+        node_builder.save().unwrap();
+        orm.record_input("Backspace", true);
+        orm.remove_node(rectangle.clone()).unwrap();
+        orm.undo().unwrap();
+        orm.undo().unwrap();
+        orm.redo().unwrap();
+
+        // bug reports are exchanged as JSON
+        let recording = orm.stop_recording().unwrap();
+        let json = serde_json::to_string(&recording).unwrap();
+        let recording = SessionRecording::from_json(&json).unwrap();
+        assert_eq!(recording.events.len(), 8);
+
+        let outcome = recording.replay();
+        assert_eq!(outcome.divergence, None);
+        assert_eq!(outcome.events_replayed, 8);
+        assert_eq!(
+            manifest_hash(&outcome.state.manifest, false),
+            manifest_hash(orm.get_manifest(), false)
+        );
+        // the replayed session can be continued where it ended
+        let mut replayed = outcome.state.into_orm();
+        assert_eq!(
+            replayed.get_last_redo_description(),
+            Some("Remove Rectangle")
+        );
+        replayed.redo().unwrap();
+        assert!(replayed.get_node(rectangle, false).is_none());
+    }
+
+    #[test]
+    fn test_session_replay_divergence() {
+        let mut orm = PaxManifestORM::new(create_basic_manifest());
+        let type_id: TypeId = TypeId::build_singleton("Component1", Some("Component1"));
+        let rectangle_type_id: TypeId = TypeId::build_singleton("Rectangle", Some("Rectangle"));
+        orm.start_recording(RecordingOptions::default());
+
+        let rectangle = orm
+            .build_new_node(type_id.clone(), rectangle_type_id)
+            .save()
+            .unwrap()
+            .unique_id;
+        orm.record_action("SetNodeLabel");
+        orm.set_node_label(rectangle, Some("CTA Button".to_string()))
+            .unwrap();
+        let mut recording = orm.get_session_recording().unwrap();
+
+        // the second checkpoint doesn't match what was actually recorded
+        recording.events[2]
+            .checkpoint
+            .as_mut()
+            .unwrap()
+            .manifest_hash ^= 1;
+        let divergence = recording.replay().divergence.unwrap();
+        assert_eq!(divergence.index, Some(2));
+        assert_eq!(divergence.event, "Label node \"CTA Button\"");
+        assert_eq!(divergence.context, vec!["Action SetNodeLabel"]);
+        assert!(matches!(
+            divergence.reason,
+            DivergenceReason::HashMismatch { .. }
+        ));
+
+        // the recorded command fails against a manifest it wasn't recorded on
+        let mut recording = orm.get_session_recording().unwrap();
+        recording.start.manifest.components.clear();
+        recording.start_hash = manifest_hash(&recording.start.manifest, false);
+        let divergence = recording.replay().divergence.unwrap();
+        assert_eq!(divergence.index, Some(0));
+        assert!(matches!(divergence.reason, DivergenceReason::Failed(_)));
+    }
+
+    #[test]
+    fn test_session_recording_window_and_redaction() {
+        let mut orm = PaxManifestORM::new(create_basic_manifest());
+        let type_id: TypeId = TypeId::build_singleton("Component1", Some("Component1"));
+        let text_type_id: TypeId = TypeId::build_singleton("Text", Some("Text"));
+        let mut node_builder = orm.build_new_node(type_id.clone(), text_type_id.clone());
+        node_builder.set_property("text", "\"Secret\"").unwrap();
+        let text = node_builder.save().unwrap().unique_id;
+
+        // keeps only the latest event, folding the older ones into the start of the recording
+        orm.start_recording(RecordingOptions {
+            window_ms: Some(0.0),
+            redact_strings: true,
+        });
+        for i in 0..3 {
+            let mut node_builder = orm.get_node(text.clone(), false).unwrap();
+            node_builder
+                .set_property("text", &format!("\"Secret {i}\""))
+                .unwrap();
+            node_builder.save().unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        orm.undo().unwrap();
+        orm.record_input("s", true);
+
+        let recording = orm.get_session_recording().unwrap();
+        assert!(recording.events.len() < 5);
+        let json = serde_json::to_string(&recording).unwrap();
+        assert!(!json.contains("Secret"));
+        assert!(json.contains(REDACTED));
+        assert_eq!(recording.replay().divergence, None);
+
+        // the session log gets every event, after the recording as it started
+        let log = orm.take_session_log().unwrap();
+        let header = log.header.unwrap();
+        assert!(header.events.is_empty());
+        assert_eq!(log.events.len(), 5);
+        assert!(orm.take_session_log().is_none());
+
+        // which replays from the start of the recording, one JSON object per line
+        let lines: Vec<String> = std::iter::once(serde_json::to_string(&header).unwrap())
+            .chain(log.events.iter().map(|e| serde_json::to_string(e).unwrap()))
+            .collect();
+        let recording = SessionRecording::from_json(&lines.join("\n")).unwrap();
+        assert_eq!(recording.events.len(), 5);
+        assert_eq!(recording.replay().divergence, None);
+    }
+
+    #[test]
+    fn test_selectors() {
+        let mut orm = PaxManifestORM::new(create_basic_manifest());
+        let type_id: TypeId = TypeId::build_singleton("Component1", Some("Component1"));
+        let text_type_id: TypeId = TypeId::build_singleton("Text", Some("Text"));
+        let mut texts = vec![];
+        for class in ["muted", "muted", ""] {
+            let mut node_builder = orm.build_new_node(type_id.clone(), text_type_id.clone());
+            node_builder.set_property("class", class).unwrap();
+            texts.push(node_builder.save().unwrap().unique_id);
+        }
+        let width = |value: &str| Some(utils::parse_value(value).unwrap());
+        let debug = |value: Option<ValueDefinition>| format!("{:?}", value);
+        let selector = |s: &str| SettingSource::Selector(s.to_string());
+
+        orm.add_selector(
+            type_id.clone(),
+            ".muted".to_string(),
+            vec![SettingElement::Setting(
+                Token::new_without_location("width".to_string()),
+                width("10px").unwrap(),
+            )],
+        )
+        .unwrap();
+        assert!(orm
+            .add_selector(type_id.clone(), ".muted".to_string(), vec![])
+            .is_err());
+        assert!(orm
+            .add_selector(type_id.clone(), "muted".to_string(), vec![])
+            .is_err());
+        assert_eq!(
+            orm.get_selectors(&type_id)
+                .into_iter()
+                .map(|s| (s.selector, s.node_count))
+                .collect::<Vec<_>>(),
+            vec![
+                ("existing_selector".to_string(), 0),
+                (".muted".to_string(), 2)
+            ]
+        );
+        assert_eq!(
+            orm.get_property_source(&texts[0], "width"),
+            Some(selector(".muted"))
+        );
+        assert_eq!(orm.get_property_source(&texts[2], "width"), None);
+
+        orm.set_selector_property(
+            type_id.clone(),
+            ".muted".to_string(),
+            "width".to_string(),
+            width("20px"),
+        )
+        .unwrap();
+        assert_eq!(
+            orm.get_selector_settings(&type_id, ".muted").len(),
+            1,
+            "the setting is replaced"
+        );
+        assert_eq!(
+            debug(orm.get_selector_property(&type_id, ".muted", "width")),
+            debug(width("20px"))
+        );
+
+        // the classes of the nodes are renamed along
+        assert!(orm
+            .rename_selector(type_id.clone(), ".muted".to_string(), "#muted".to_string())
+            .is_err());
+        orm.rename_selector(type_id.clone(), ".muted".to_string(), ".subtle".to_string())
+            .unwrap();
+        assert_eq!(
+            orm.get_property_source(&texts[1], "width"),
+            Some(selector(".subtle"))
+        );
+        assert_eq!(orm.get_selectors(&type_id)[1].node_count, 2);
+
+        orm.delete_selector(
+            type_id.clone(),
+            ".subtle".to_string(),
+            DeleteSelectorPolicy::InlineValues,
+        )
+        .unwrap();
+        assert_eq!(orm.get_selectors(&type_id).len(), 1);
+        assert!(orm.get_property(&texts[0], "class").is_none());
+        assert_eq!(
+            debug(orm.get_property(&texts[0], "width")),
+            debug(width("20px"))
+        );
+        assert_eq!(
+            orm.get_property_source(&texts[0], "width"),
+            Some(SettingSource::Inline)
+        );
+
+        orm.undo().unwrap();
+        assert_eq!(
+            orm.get_property_source(&texts[0], "width"),
+            Some(selector(".subtle"))
+        );
+        orm.delete_selector(
+            type_id.clone(),
+            ".subtle".to_string(),
+            DeleteSelectorPolicy::LeaveUnstyled,
+        )
+        .unwrap();
+        assert_eq!(orm.get_property_source(&texts[0], "width"), None);
+
+        orm.undo().unwrap();
+        orm.undo().unwrap();
+        orm.undo().unwrap();
+        orm.undo().unwrap();
+        assert_eq!(orm.get_selectors(&type_id).len(), 1);
+        assert!(matches!(
+            orm.get_property(&texts[0], "class"),
+            Some(ValueDefinition::Identifier(class)) if class.name == "muted"
+        ));
+    }
 }
@@ -0,0 +1,127 @@
+//! Design tokens: named values, e.g. colors and spacings, listed in `PaxManifest::tokens` and
+//! referenced by settings as `{$tokens.name}`, see `ValueDefinition::Token`.  Settings resolve
+//! tokens when their nodes are instantiated, so setting a token changes every node referencing it.
+
+use pax_manifest::pax_runtime_api::PaxValue;
+use pax_manifest::{PaxManifest, SettingElement, TypeId, ValueDefinition};
+use serde_derive::{Deserialize, Serialize};
+
+use super::{Command, ReloadType, Request, Response, Undo, UndoRedoCommand};
+
+/// Whether `value` references token `name`, directly or within a block
+fn references_token(value: &ValueDefinition, name: &str) -> bool {
+    match value {
+        ValueDefinition::Token(token) => token == name,
+        ValueDefinition::Block(block) => block
+            .get_all_settings()
+            .into_iter()
+            .any(|(_, value)| references_token(value, name)),
+        _ => false,
+    }
+}
+
+/// Components with settings referencing token `name`
+fn components_referencing_token(manifest: &PaxManifest, name: &str) -> Vec<TypeId> {
+    manifest
+        .components
+        .iter()
+        .filter(|(_, component)| {
+            let Some(template) = &component.template else {
+                return false;
+            };
+            template.get_nodes().into_iter().any(|tnd| {
+                tnd.settings.iter().flatten().any(|setting| match setting {
+                    SettingElement::Setting(_, value) => references_token(value, name),
+                    SettingElement::Comment(_) => false,
+                })
+            })
+        })
+        .map(|(type_id, _)| type_id.clone())
+        .collect()
+}
+
+/// Sets the value of a design token, adding the token if it's new, see `PaxManifestORM::set_token`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SetTokenRequest {
+    name: String,
+    value: PaxValue,
+    // Used for Undo/Redo
+    _cached_value: Option<PaxValue>,
+}
+
+impl SetTokenRequest {
+    pub fn new(name: String, value: PaxValue) -> Self {
+        Self {
+            name,
+            value,
+            _cached_value: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SetTokenResponse {
+    command_id: Option<usize>,
+    description: Option<String>,
+    _affected_component_type_ids: Vec<TypeId>,
+}
+
+impl Request for SetTokenRequest {
+    type Response = SetTokenResponse;
+}
+
+impl Response for SetTokenResponse {
+    fn set_id(&mut self, id: usize) {
+        self.command_id = Some(id);
+    }
+    fn get_id(&self) -> usize {
+        self.command_id.unwrap()
+    }
+    fn set_description(&mut self, description: String) {
+        self.description = Some(description);
+    }
+    fn get_description(&self) -> &str {
+        self.description.as_deref().unwrap_or_default()
+    }
+    fn get_affected_components(&self) -> Vec<TypeId> {
+        self._affected_component_type_ids.clone()
+    }
+    fn get_reload_type(&self) -> Option<ReloadType> {
+        // the nodes referencing the token can be anywhere
+        Some(ReloadType::FullEdit)
+    }
+}
+
+impl Command<SetTokenRequest> for SetTokenRequest {
+    fn execute(&mut self, manifest: &mut PaxManifest) -> Result<SetTokenResponse, String> {
+        if self.name.trim().is_empty() {
+            return Err("Tokens need a name".to_string());
+        }
+        self._cached_value = manifest
+            .tokens
+            .insert(self.name.clone(), self.value.clone());
+        Ok(SetTokenResponse {
+            command_id: None,
+            description: None,
+            _affected_component_type_ids: components_referencing_token(manifest, &self.name),
+        })
+    }
+
+    fn description(&self) -> String {
+        format!("Set token \"{}\"", self.name)
+    }
+
+    fn as_undo_redo(&mut self) -> Option<UndoRedoCommand> {
+        Some(UndoRedoCommand::SetTokenRequest(Box::new(self.clone())))
+    }
+}
+
+impl Undo for SetTokenRequest {
+    fn undo(&mut self, manifest: &mut PaxManifest) -> Result<(), String> {
+        match self._cached_value.clone() {
+            Some(value) => manifest.tokens.insert(self.name.clone(), value),
+            None => manifest.tokens.remove(&self.name),
+        };
+        Ok(())
+    }
+}
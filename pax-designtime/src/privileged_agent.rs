@@ -1,17 +1,43 @@
 use std::net::SocketAddr;
 
 use crate::{
-    messages::{AgentMessage, ComponentSerializationRequest, LoadFileToStaticDirRequest},
+    manifest_loading::{ManifestAssembler, ManifestLoadState},
+    messages::{
+        AgentMessage, AppendSessionLogRequest, ComponentSerializationRequest,
+        ExportBugReportRequest, ExportSnippetRequest, LoadFileToStaticDirRequest,
+        LoadSnippetRequest, NodeBounds, NodeBoundsResponse, SaveKeymapRequest,
+        SaveWorkspaceStateRequest,
+    },
     orm::PaxManifestORM,
+    snippet::SnippetInbox,
 };
 use anyhow::{anyhow, Result};
 use ewebsock::{WsEvent, WsMessage};
-use pax_manifest::{ComponentDefinition, PaxManifest};
+use pax_manifest::{ComponentDefinition, PaxManifest, UniqueTemplateNodeIdentifier};
+
+/// Serializes the message asking the design server to write `component` back to its file,
+/// which is slow for large components, see `DesigntimeManager::send_component_update`.
+/// `changes` describe what changed since the component was last saved, for the server to log
+pub fn serialize_component_update(
+    component: &ComponentDefinition,
+    changes: Vec<String>,
+) -> Result<Vec<u8>> {
+    let component_bytes = rmp_serde::to_vec(component)?;
+    Ok(rmp_serde::to_vec(
+        &AgentMessage::ComponentSerializationRequest(ComponentSerializationRequest {
+            component_bytes,
+            changes,
+        }),
+    )?)
+}
 
 pub struct PrivilegedAgentConnection {
     sender: ewebsock::WsSender,
     recver: ewebsock::WsReceiver,
     pub alive: bool,
+    /// Most recent error reported by the socket, shown if loading the manifest fails
+    pub last_error: Option<String>,
+    manifest_assembler: ManifestAssembler,
 }
 
 impl PrivilegedAgentConnection {
@@ -23,6 +49,8 @@ impl PrivilegedAgentConnection {
             sender,
             recver,
             alive: true,
+            last_error: None,
+            manifest_assembler: ManifestAssembler::default(),
         })
     }
 
@@ -32,14 +60,122 @@ impl PrivilegedAgentConnection {
         Ok(())
     }
 
-    pub fn send_component_update(&mut self, component: &ComponentDefinition) -> Result<()> {
+    pub fn send_workspace_state_load_request(&mut self) -> Result<()> {
+        let msg_bytes = rmp_serde::to_vec(&AgentMessage::LoadWorkspaceStateRequest)?;
+        self.sender.send(ewebsock::WsMessage::Binary(msg_bytes));
+        Ok(())
+    }
+
+    pub fn send_workspace_state(&mut self, state: String) -> Result<()> {
+        if self.alive {
+            let msg_bytes = rmp_serde::to_vec(&AgentMessage::SaveWorkspaceStateRequest(
+                SaveWorkspaceStateRequest { state },
+            ))?;
+            self.sender.send(ewebsock::WsMessage::Binary(msg_bytes));
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "couldn't save workspace state: connection to design-server was lost"
+            ))
+        }
+    }
+
+    pub fn send_keymap_load_request(&mut self) -> Result<()> {
+        let msg_bytes = rmp_serde::to_vec(&AgentMessage::LoadKeymapRequest)?;
+        self.sender.send(ewebsock::WsMessage::Binary(msg_bytes));
+        Ok(())
+    }
+
+    pub fn send_keymap(&mut self, keymap: String) -> Result<()> {
+        if self.alive {
+            let msg_bytes =
+                rmp_serde::to_vec(&AgentMessage::SaveKeymapRequest(SaveKeymapRequest {
+                    keymap,
+                }))?;
+            self.sender.send(ewebsock::WsMessage::Binary(msg_bytes));
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "couldn't save keymap: connection to design-server was lost"
+            ))
+        }
+    }
+
+    pub fn send_snippet_export(&mut self, archive: Vec<u8>) -> Result<()> {
+        if self.alive {
+            let msg_bytes =
+                rmp_serde::to_vec(&AgentMessage::ExportSnippetRequest(ExportSnippetRequest {
+                    archive,
+                }))?;
+            self.sender.send(ewebsock::WsMessage::Binary(msg_bytes));
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "couldn't export snippet: connection to design-server was lost"
+            ))
+        }
+    }
+
+    pub fn send_snippet_list_request(&mut self) -> Result<()> {
+        if self.alive {
+            let msg_bytes = rmp_serde::to_vec(&AgentMessage::ListSnippetsRequest)?;
+            self.sender.send(ewebsock::WsMessage::Binary(msg_bytes));
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "couldn't list snippets: connection to design-server was lost"
+            ))
+        }
+    }
+
+    pub fn send_snippet_load_request(&mut self, name: String) -> Result<()> {
+        if self.alive {
+            let msg_bytes =
+                rmp_serde::to_vec(&AgentMessage::LoadSnippetRequest(LoadSnippetRequest {
+                    name,
+                }))?;
+            self.sender.send(ewebsock::WsMessage::Binary(msg_bytes));
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "couldn't load snippet: connection to design-server was lost"
+            ))
+        }
+    }
+
+    pub fn send_session_log(&mut self, header: Option<String>, events: Vec<String>) -> Result<()> {
+        if self.alive {
+            let msg_bytes = rmp_serde::to_vec(&AgentMessage::AppendSessionLogRequest(
+                AppendSessionLogRequest { header, events },
+            ))?;
+            self.sender.send(ewebsock::WsMessage::Binary(msg_bytes));
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "couldn't append to session log: connection to design-server was lost"
+            ))
+        }
+    }
+
+    pub fn send_bug_report(&mut self, report: String) -> Result<()> {
         if self.alive {
-            let component_bytes = rmp_serde::to_vec(&component)?;
-            let msg_bytes = rmp_serde::to_vec(&AgentMessage::ComponentSerializationRequest(
-                ComponentSerializationRequest { component_bytes },
+            let msg_bytes = rmp_serde::to_vec(&AgentMessage::ExportBugReportRequest(
+                ExportBugReportRequest { report },
             ))?;
             self.sender.send(ewebsock::WsMessage::Binary(msg_bytes));
             Ok(())
+        } else {
+            Err(anyhow!(
+                "couldn't export bug report: connection to design-server was lost"
+            ))
+        }
+    }
+
+    /// Sends a component update serialized with `serialize_component_update`
+    pub fn send_component_update(&mut self, msg_bytes: Vec<u8>) -> Result<()> {
+        if self.alive {
+            self.sender.send(ewebsock::WsMessage::Binary(msg_bytes));
+            Ok(())
         } else {
             Err(anyhow!(
                 "couldn't send component update: connection to design-server was lost"
@@ -64,11 +200,48 @@ impl PrivilegedAgentConnection {
         }
     }
 
-    pub fn handle_recv(&mut self, manager: &mut PaxManifestORM) -> Result<()> {
+    pub fn send_node_bounds_response(
+        &mut self,
+        uni: UniqueTemplateNodeIdentifier,
+        bounds: Vec<NodeBounds>,
+    ) -> Result<()> {
+        if self.alive {
+            let msg_bytes =
+                rmp_serde::to_vec(&AgentMessage::NodeBoundsResponse(NodeBoundsResponse {
+                    uni,
+                    bounds,
+                }))?;
+            self.sender.send(ewebsock::WsMessage::Binary(msg_bytes));
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "couldn't send node bounds: connection to design-server was lost"
+            ))
+        }
+    }
+
+    pub fn handle_recv(
+        &mut self,
+        manager: &mut PaxManifestORM,
+        bounds_requests: &mut Vec<UniqueTemplateNodeIdentifier>,
+        workspace_state: &mut Option<String>,
+        keymap: &mut Option<Option<String>>,
+        snippets: &mut SnippetInbox,
+        bug_report_messages: &mut Vec<String>,
+    ) -> Result<()> {
         while let Some(event) = self.recver.try_recv() {
             match event {
                 WsEvent::Opened => {
+                    self.alive = true;
+                    self.manifest_assembler.reset();
+                    if !manager.manifest_load_state.get().is_complete() {
+                        manager
+                            .manifest_load_state
+                            .set(ManifestLoadState::Connecting);
+                    }
                     self.send_manifest_load_request()?;
+                    self.send_workspace_state_load_request()?;
+                    self.send_keymap_load_request()?;
                 }
                 WsEvent::Message(message) => {
                     if let WsMessage::Binary(msg_bytes) = message {
@@ -78,19 +251,72 @@ impl PrivilegedAgentConnection {
                                 let manifest: PaxManifest = rmp_serde::from_slice(&resp.manifest)?;
                                 manager.set_manifest(manifest);
                             }
+                            AgentMessage::LoadManifestHeader(header) => {
+                                let manifest: PaxManifest =
+                                    rmp_serde::from_slice(&header.manifest)?;
+                                let state = self
+                                    .manifest_assembler
+                                    .begin(manifest, header.total_components);
+                                manager.manifest_load_state.set(state);
+                                if let Some(manifest) = self.manifest_assembler.take_complete() {
+                                    manager.set_manifest(manifest);
+                                }
+                            }
+                            AgentMessage::LoadManifestComponents(batch) => {
+                                let components: Vec<ComponentDefinition> =
+                                    rmp_serde::from_slice(&batch.components)?;
+                                let state = self.manifest_assembler.receive(components);
+                                manager.manifest_load_state.set(state);
+                                if let Some(manifest) = self.manifest_assembler.take_complete() {
+                                    manager.set_manifest(manifest);
+                                }
+                            }
                             AgentMessage::UpdateTemplateRequest(resp) => {
                                 manager
                                     .replace_template(resp.type_id, resp.new_template)
                                     .map_err(|e| anyhow!(e))?;
                             }
+                            AgentMessage::NodeBoundsRequest(req) => {
+                                if !bounds_requests.contains(&req.uni) {
+                                    bounds_requests.push(req.uni);
+                                }
+                            }
+                            AgentMessage::LoadWorkspaceStateResponse(resp) => {
+                                *workspace_state = resp.state;
+                            }
+                            AgentMessage::LoadKeymapResponse(resp) => {
+                                *keymap = Some(resp.keymap);
+                            }
+                            AgentMessage::ListSnippetsResponse(resp) => {
+                                snippets.library = Some(resp.names);
+                            }
+                            AgentMessage::ImportSnippetRequest(req) => {
+                                snippets.imports.push(req.archive);
+                            }
+                            AgentMessage::SnippetStatusNotification(notification) => {
+                                snippets.messages.push(notification.message);
+                            }
+                            AgentMessage::BugReportStatusNotification(notification) => {
+                                bug_report_messages.push(notification.message);
+                            }
                             _ => {}
                         }
                     }
                 }
-                WsEvent::Error(e) => log::warn!("web socket error: {e}"),
+                WsEvent::Error(e) => {
+                    log::warn!("web socket error: {e}");
+                    self.last_error = Some(e);
+                }
                 WsEvent::Closed => {
                     self.alive = false;
-                    log::warn!("web socket was closed")
+                    log::warn!("web socket was closed");
+                    // the server went away mid-load (e.g. restarted): start over once reconnected
+                    self.manifest_assembler.reset();
+                    if let ManifestLoadState::Partial { .. } = manager.manifest_load_state.get() {
+                        manager
+                            .manifest_load_state
+                            .set(ManifestLoadState::Connecting);
+                    }
                 }
             }
         }
@@ -0,0 +1,354 @@
+//! # Property recorder
+//!
+//! Recent property values of individual template nodes, for finding the frame on which a value
+//! jumped while an animation or binding plays out. While recording is enabled through the
+//! `DesigntimeManager`, designtime builds of the engine observe the selected node and the pinned
+//! ones each frame and report the resolved value of every property of their first expanded
+//! instance, together with its computed layout, with `record_node_frame`. Each node keeps the
+//! most recent `capacity` frames. Outside of designtime builds none of this is compiled in.
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+use pax_manifest::pax_runtime_api::{PaxValue, Size};
+use pax_manifest::UniqueTemplateNodeIdentifier;
+
+use crate::messages::NodeBounds;
+
+/// Number of frames recorded per node unless configured otherwise, ten seconds at 60 fps
+pub const DEFAULT_CAPACITY_FRAMES: usize = 600;
+
+/// The values of a node on one frame
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedFrame {
+    /// Number of the frame, see `Globals::frames_elapsed`
+    pub frame: u64,
+    pub properties: BTreeMap<String, PaxValue>,
+    pub bounds: NodeBounds,
+}
+
+/// The most recent frames recorded of a node, oldest first
+#[derive(Default)]
+pub struct NodeRecording {
+    frames: VecDeque<RecordedFrame>,
+}
+
+impl NodeRecording {
+    pub fn frames(&self) -> &VecDeque<RecordedFrame> {
+        &self.frames
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Names of the properties recorded on any frame
+    pub fn property_names(&self) -> Vec<String> {
+        let names: HashSet<_> = self
+            .frames
+            .iter()
+            .flat_map(|frame| frame.properties.keys())
+            .collect();
+        let mut names: Vec<_> = names.into_iter().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Value of `property` on each recorded frame as a number, `None` on frames where it's
+    /// missing or not numeric, see `numeric_value`
+    pub fn numeric_series(&self, property: &str) -> Vec<Option<f64>> {
+        self.frames
+            .iter()
+            .map(|frame| frame.properties.get(property).and_then(numeric_value))
+            .collect()
+    }
+
+    /// Indices of the recorded frames on which the value of `property` differs from the
+    /// frame before
+    pub fn discontinuities(&self, property: &str) -> Vec<usize> {
+        let values: Vec<_> = self
+            .frames
+            .iter()
+            .map(|frame| frame.properties.get(property))
+            .collect();
+        (1..values.len())
+            .filter(|&i| !same_value(values[i - 1], values[i]))
+            .collect()
+    }
+
+    fn push(&mut self, frame: RecordedFrame, capacity: usize) {
+        // a node can be reported more than once per frame, e.g. if it's observed twice
+        if self
+            .frames
+            .back()
+            .is_some_and(|last| last.frame == frame.frame)
+        {
+            self.frames.pop_back();
+        }
+        self.frames.push_back(frame);
+        self.truncate(capacity);
+    }
+
+    fn truncate(&mut self, capacity: usize) {
+        while self.frames.len() > capacity {
+            self.frames.pop_front();
+        }
+    }
+}
+
+/// A property value as a number, for the values that have a natural one: numbers, sizes in
+/// either pixels or percent, percentages and rotations in degrees
+pub fn numeric_value(value: &PaxValue) -> Option<f64> {
+    match value {
+        PaxValue::Numeric(n) => Some(n.to_float()),
+        PaxValue::Size(Size::Pixels(n) | Size::Percent(n)) => Some(n.to_float()),
+        PaxValue::Percent(p) => Some(p.0.to_float()),
+        PaxValue::Rotation(r) => Some(r.get_as_degrees()),
+        _ => None,
+    }
+}
+
+/// `PaxValue`'s `PartialEq` warns when comparing values of different kinds, which a property
+/// can take on from one frame to the next (e.g. a size from pixels to a `Combined` one)
+fn same_value(a: Option<&PaxValue>, b: Option<&PaxValue>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => std::mem::discriminant(a) == std::mem::discriminant(b) && a == b,
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Which nodes are recorded, and what has been recorded of them
+pub struct PropertyRecordings {
+    enabled: bool,
+    /// Set while the project isn't playing, values only change during play
+    paused: bool,
+    capacity: usize,
+    selected: Option<UniqueTemplateNodeIdentifier>,
+    /// Nodes recorded even when not selected
+    pinned: Vec<UniqueTemplateNodeIdentifier>,
+    recordings: HashMap<UniqueTemplateNodeIdentifier, NodeRecording>,
+}
+
+impl Default for PropertyRecordings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            paused: false,
+            capacity: DEFAULT_CAPACITY_FRAMES,
+            selected: None,
+            pinned: Vec::new(),
+            recordings: HashMap::new(),
+        }
+    }
+}
+
+impl PropertyRecordings {
+    /// Starts or stops recording. Stopping drops everything recorded so far
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.recordings.clear();
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Pauses or resumes recording, keeping what has been recorded so far
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Sets the number of frames kept per node, dropping the oldest ones of longer recordings
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        for recording in self.recordings.values_mut() {
+            recording.truncate(self.capacity);
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Sets the selected node, dropping the recording of the previously selected one unless
+    /// it's pinned
+    pub fn set_selected(&mut self, selected: Option<UniqueTemplateNodeIdentifier>) {
+        self.selected = selected;
+        self.retain_recorded();
+    }
+
+    pub fn pin(&mut self, uni: UniqueTemplateNodeIdentifier) {
+        if !self.pinned.contains(&uni) {
+            self.pinned.push(uni);
+        }
+    }
+
+    /// Unpins a node, dropping its recording unless it's selected
+    pub fn unpin(&mut self, uni: &UniqueTemplateNodeIdentifier) {
+        self.pinned.retain(|pinned| pinned != uni);
+        self.retain_recorded();
+    }
+
+    pub fn is_pinned(&self, uni: &UniqueTemplateNodeIdentifier) -> bool {
+        self.pinned.contains(uni)
+    }
+
+    /// Nodes whose values the engine should report this frame
+    pub fn recorded_nodes(&self) -> Vec<UniqueTemplateNodeIdentifier> {
+        if !self.enabled || self.paused {
+            return vec![];
+        }
+        let mut nodes = self.pinned.clone();
+        if let Some(selected) = &self.selected {
+            if !nodes.contains(selected) {
+                nodes.push(selected.clone());
+            }
+        }
+        nodes
+    }
+
+    /// Adds the values of node `uni` on a frame, if it's recorded
+    pub fn record(&mut self, uni: &UniqueTemplateNodeIdentifier, frame: RecordedFrame) -> bool {
+        if !self.recorded_nodes().contains(uni) {
+            return false;
+        }
+        self.recordings
+            .entry(uni.clone())
+            .or_default()
+            .push(frame, self.capacity);
+        true
+    }
+
+    pub fn get(&self, uni: &UniqueTemplateNodeIdentifier) -> Option<&NodeRecording> {
+        self.recordings.get(uni)
+    }
+
+    fn retain_recorded(&mut self) {
+        let Self {
+            selected,
+            pinned,
+            recordings,
+            ..
+        } = self;
+        recordings.retain(|uni, _| selected.as_ref() == Some(uni) || pinned.contains(uni));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pax_manifest::pax_runtime_api::Numeric;
+    use pax_manifest::{TemplateNodeId, TypeId};
+
+    fn uni(id: usize) -> UniqueTemplateNodeIdentifier {
+        UniqueTemplateNodeIdentifier::build(
+            TypeId::build_singleton("Main", None),
+            TemplateNodeId::build(id),
+        )
+    }
+
+    fn frame(frame: u64, y: f64) -> RecordedFrame {
+        RecordedFrame {
+            frame,
+            properties: BTreeMap::from([
+                (
+                    "y".to_string(),
+                    PaxValue::Size(Size::Pixels(Numeric::F64(y))),
+                ),
+                ("label".to_string(), PaxValue::String("a".to_string())),
+            ]),
+            bounds: NodeBounds {
+                transform: [1.0, 0.0, 0.0, 1.0, 0.0, y],
+                bounds: (100.0, 100.0),
+            },
+        }
+    }
+
+    fn enabled() -> PropertyRecordings {
+        let mut recordings = PropertyRecordings::default();
+        recordings.set_enabled(true);
+        recordings
+    }
+
+    #[test]
+    fn test_records_selected_and_pinned_nodes() {
+        let mut recordings = enabled();
+        recordings.set_selected(Some(uni(1)));
+        recordings.pin(uni(2));
+        assert_eq!(recordings.recorded_nodes(), vec![uni(2), uni(1)]);
+        assert!(recordings.record(&uni(1), frame(0, 0.0)));
+        assert!(recordings.record(&uni(2), frame(0, 0.0)));
+        assert!(!recordings.record(&uni(3), frame(0, 0.0)));
+
+        // deselecting drops the recording of the selected node, but not of the pinned one
+        recordings.set_selected(None);
+        assert!(recordings.get(&uni(1)).is_none());
+        assert_eq!(recordings.get(&uni(2)).map(NodeRecording::len), Some(1));
+        recordings.unpin(&uni(2));
+        assert!(recordings.get(&uni(2)).is_none());
+        assert!(recordings.recorded_nodes().is_empty());
+    }
+
+    #[test]
+    fn test_pausing_keeps_recording() {
+        let mut recordings = enabled();
+        recordings.set_selected(Some(uni(1)));
+        recordings.record(&uni(1), frame(0, 0.0));
+        recordings.set_paused(true);
+        assert!(recordings.recorded_nodes().is_empty());
+        assert!(!recordings.record(&uni(1), frame(1, 0.0)));
+        assert_eq!(recordings.get(&uni(1)).map(NodeRecording::len), Some(1));
+
+        recordings.set_paused(false);
+        recordings.set_enabled(false);
+        assert!(recordings.get(&uni(1)).is_none());
+    }
+
+    #[test]
+    fn test_ring_buffer_keeps_most_recent_frames() {
+        let mut recordings = enabled();
+        recordings.set_selected(Some(uni(1)));
+        recordings.set_capacity(3);
+        for i in 0..5 {
+            recordings.record(&uni(1), frame(i, i as f64));
+        }
+        // reporting a frame again replaces it
+        recordings.record(&uni(1), frame(4, 10.0));
+        let recording = recordings.get(&uni(1)).unwrap();
+        let frames: Vec<_> = recording.frames().iter().map(|f| f.frame).collect();
+        assert_eq!(frames, vec![2, 3, 4]);
+        assert_eq!(
+            recording.numeric_series("y"),
+            vec![Some(2.0), Some(3.0), Some(10.0)]
+        );
+
+        recordings.set_capacity(1);
+        assert_eq!(recordings.get(&uni(1)).map(NodeRecording::len), Some(1));
+    }
+
+    #[test]
+    fn test_discontinuities() {
+        let mut recording = NodeRecording::default();
+        for (i, y) in [0.0, 0.0, 5.0, 5.0, -3.0].into_iter().enumerate() {
+            recording.push(frame(i as u64, y), DEFAULT_CAPACITY_FRAMES);
+        }
+        recording.frames.back_mut().unwrap().properties.insert(
+            "y".to_string(),
+            PaxValue::Size(Size::Percent(Numeric::F64(-3.0))),
+        );
+        assert_eq!(recording.discontinuities("y"), vec![2, 4]);
+        assert!(recording.discontinuities("label").is_empty());
+        assert_eq!(recording.property_names(), vec!["label", "y"]);
+        assert_eq!(recording.numeric_series("label"), vec![None; 5]);
+    }
+}
@@ -0,0 +1,728 @@
+//! # Snippets
+//!
+//! A snippet packages a piece of UI for reuse in another project: a fragment of a template, the
+//! definitions of the userland components it uses (transitively), and the assets they reference.
+//! Snippets are exported from the designer into `.paxsnip` archives by the design server, and
+//! imported either through the designer or with `pax-cli import-snippet`.
+//!
+//! Imported components become template-only components of the importing project, so anything
+//! backed by Rust code of the exporting project (event handlers, bindings to properties) can't be
+//! carried over. These are skipped on import, and listed in the import report.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Result};
+use pax_manifest::constants::COMMON_PROPERTIES;
+use pax_manifest::pax_runtime_api::{CoercionRules, ToPaxValue};
+use pax_manifest::{
+    ComponentDefinition, ComponentTemplate, PaxManifest, PaxType, SettingElement,
+    SettingsBlockElement, TemplateNodeDefinition, TemplateNodeId, Token, TypeId, ValueDefinition,
+};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::orm::SubTrees;
+
+pub const SNIPPET_FILE_EXTENSION: &str = "paxsnip";
+
+/// Version of the archive layout, increased on incompatible changes
+pub const SNIPPET_FORMAT_VERSION: u32 = 1;
+
+/// Start of every archive, followed by the format version (little-endian u32)
+/// and the MessagePack encoded `SnippetArchive`
+const SNIPPET_MAGIC: &[u8; 8] = b"PAXSNIP\0";
+
+/// Prefix of asset paths, relative to the project root
+const ASSETS_DIR: &str = "assets/";
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SnippetArchive {
+    pub name: String,
+    /// The exported nodes, with the roots positioned relative to the top left of their bounds
+    fragment: SubTrees,
+    /// Size of the bounds of the fragment roots, in px
+    size: (f64, f64),
+    components: Vec<ComponentDefinition>,
+    pub assets: Vec<SnippetAsset>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SnippetAsset {
+    /// Path relative to the project root, e.g. `assets/logo.png`
+    pub path: String,
+    /// Contents of the asset, filled in by the design server on export. Empty if it couldn't be read
+    pub data: Vec<u8>,
+}
+
+/// Snippet messages received from the design server, not yet picked up by the designer
+#[derive(Default)]
+pub struct SnippetInbox {
+    /// Encoded archives to import into the component being edited
+    pub imports: Vec<Vec<u8>>,
+    /// Response to the last request for the names of the snippets in the user's library
+    pub library: Option<Vec<String>>,
+    /// Status messages to show to the user
+    pub messages: Vec<String>,
+}
+
+/// A snippet prepared for import into a specific component, see `SnippetArchive::into_import`
+pub struct SnippetImport {
+    /// Components to add to the project
+    pub components: Vec<ComponentDefinition>,
+    /// Nodes to paste into the target component
+    pub fragment: SubTrees,
+    /// Assets to copy into the project, with paths rewritten to match the fragment and components
+    pub assets: Vec<SnippetAsset>,
+    /// Everything that was renamed or skipped, one line each
+    pub report: Vec<String>,
+    size: (f64, f64),
+}
+
+impl SnippetArchive {
+    /// Bundles `fragment`, copied from a component of `manifest`, with the userland components it uses
+    /// and the paths of the assets they reference. `root_bounds` are the (x, y, width, height) of each
+    /// fragment root, relative to the top left of their combined bounds of the given `size`.
+    pub fn new(
+        manifest: &PaxManifest,
+        name: &str,
+        mut fragment: SubTrees,
+        root_bounds: &[(f64, f64, f64, f64)],
+        size: (f64, f64),
+    ) -> Self {
+        for (root, &(x, y, width, height)) in fragment.roots.iter().zip(root_bounds) {
+            if let Some(settings) = fragment
+                .nodes
+                .get_mut(root)
+                .and_then(|node| node.settings.as_mut())
+            {
+                set_literal(settings, "x", x);
+                set_literal(settings, "y", y);
+                set_literal(settings, "width", width);
+                set_literal(settings, "height", height);
+            }
+        }
+
+        let components = used_components(manifest, fragment.nodes.values());
+        let mut asset_paths = BTreeSet::new();
+        for node in fragment
+            .nodes
+            .values()
+            .chain(components.iter().flat_map(template_nodes))
+        {
+            for (_, value) in node_settings(node) {
+                visit_asset_paths(&mut value.clone(), &mut |path| {
+                    asset_paths.insert(path.clone());
+                });
+            }
+        }
+        for (_, value) in components.iter().flat_map(selector_settings) {
+            visit_asset_paths(&mut value.clone(), &mut |path| {
+                asset_paths.insert(path.clone());
+            });
+        }
+
+        Self {
+            name: name.to_string(),
+            fragment,
+            size,
+            components,
+            assets: asset_paths
+                .into_iter()
+                .map(|path| SnippetAsset { path, data: vec![] })
+                .collect(),
+        }
+    }
+
+    /// Exports a whole component, as a single instance of it of the given size
+    pub fn for_component(manifest: &PaxManifest, type_id: &TypeId, size: (f64, f64)) -> Self {
+        let root = TemplateNodeId::build(0);
+        let node = TemplateNodeDefinition {
+            type_id: type_id.clone(),
+            control_flow_settings: None,
+            settings: Some(vec![]),
+            raw_comment_string: None,
+            label: None,
+        };
+        let fragment = SubTrees {
+            roots: vec![root.clone()],
+            children: HashMap::new(),
+            nodes: HashMap::from([(root, node)]),
+        };
+        let name = type_id
+            .get_pascal_identifier()
+            .unwrap_or_else(|| "Snippet".to_string());
+        Self::new(
+            manifest,
+            &name,
+            fragment,
+            &[(0.0, 0.0, size.0, size.1)],
+            size,
+        )
+    }
+
+    pub fn file_name(&self) -> String {
+        format!("{}.{}", to_snake_case(&self.name), SNIPPET_FILE_EXTENSION)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = SNIPPET_MAGIC.to_vec();
+        bytes.extend(SNIPPET_FORMAT_VERSION.to_le_bytes());
+        bytes.extend(rmp_serde::to_vec(self)?);
+        Ok(bytes)
+    }
+
+    /// Reads and validates an archive written by `to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let rest = bytes
+            .strip_prefix(SNIPPET_MAGIC.as_slice())
+            .ok_or_else(|| anyhow!("not a .{} file", SNIPPET_FILE_EXTENSION))?;
+        if rest.len() < 4 {
+            bail!("snippet is truncated");
+        }
+        let (version, payload) = rest.split_at(4);
+        let version = u32::from_le_bytes(version.try_into().unwrap());
+        if version != SNIPPET_FORMAT_VERSION {
+            bail!(
+                "snippet has format version {}, but only version {} is supported",
+                version,
+                SNIPPET_FORMAT_VERSION
+            );
+        }
+        let archive: Self =
+            rmp_serde::from_slice(payload).map_err(|e| anyhow!("snippet is corrupted: {}", e))?;
+        archive.validate()?;
+        Ok(archive)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.fragment.roots.is_empty() {
+            bail!("snippet contains no nodes");
+        }
+        let referenced = self
+            .fragment
+            .roots
+            .iter()
+            .chain(self.fragment.children.values().flatten());
+        for id in referenced {
+            if !self.fragment.nodes.contains_key(id) {
+                bail!("snippet is missing template node {}", id.as_usize());
+            }
+        }
+        for component in &self.components {
+            if component.type_id.get_pascal_identifier().is_none() || component.template.is_none() {
+                bail!(
+                    "snippet contains an invalid component {}",
+                    component.type_id
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Names of the snippet's components that are already taken in `manifest`
+    pub fn collisions(&self, manifest: &PaxManifest) -> Vec<String> {
+        let taken = pascal_identifiers(manifest);
+        self.components
+            .iter()
+            .filter_map(|c| c.type_id.get_pascal_identifier())
+            .filter(|name| taken.contains(name))
+            .collect()
+    }
+
+    /// Prepares the snippet for import into the component `target` of `manifest`:
+    /// components whose names are taken are renamed, assets are moved to their own
+    /// directory, and everything that can't be imported is stripped.
+    pub fn into_import(self, manifest: &PaxManifest, target: &TypeId) -> Result<SnippetImport> {
+        let target_component = manifest
+            .components
+            .get(target)
+            .ok_or_else(|| anyhow!("component {} not found", target))?;
+        let target_file = target_component
+            .template
+            .as_ref()
+            .and_then(|t| t.get_file_path())
+            .ok_or_else(|| anyhow!("can't import into {}: it has no template file", target))?;
+        let target_dir = Path::new(&target_file)
+            .parent()
+            .ok_or_else(|| anyhow!("can't import into {}: invalid template file", target))?;
+
+        let mut report = vec![];
+        let mut taken = pascal_identifiers(manifest);
+        let mut type_ids = HashMap::new();
+        for component in &self.components {
+            let name = component
+                .type_id
+                .get_pascal_identifier()
+                .unwrap_or_default();
+            let mut new_name = name.clone();
+            let mut suffix = 2;
+            while taken.contains(&new_name) {
+                new_name = format!("{}{}", name, suffix);
+                suffix += 1;
+            }
+            if new_name != name {
+                report.push(format!("Renamed component {} to {}", name, new_name));
+            }
+            type_ids.insert(
+                component.type_id.clone(),
+                TypeId::build_blank_component(&new_name),
+            );
+            taken.insert(new_name);
+        }
+
+        let snippet_assets_dir = format!("{}{}/", ASSETS_DIR, to_snake_case(&self.name));
+        let mut asset_paths = HashMap::new();
+        let mut assets = vec![];
+        for asset in self.assets {
+            if asset.data.is_empty() {
+                report.push(format!(
+                    "Skipped asset {}: it couldn't be read when the snippet was exported",
+                    asset.path
+                ));
+                continue;
+            }
+            let path = format!(
+                "{}{}",
+                snippet_assets_dir,
+                asset.path.trim_start_matches(ASSETS_DIR)
+            );
+            asset_paths.insert(asset.path, path.clone());
+            assets.push(SnippetAsset {
+                path,
+                data: asset.data,
+            });
+        }
+
+        let mut sanitizer = Sanitizer {
+            manifest,
+            type_ids: &type_ids,
+            asset_paths: &asset_paths,
+            report: &mut report,
+        };
+        let mut components = vec![];
+        for component in self.components {
+            let type_id = type_ids[&component.type_id].clone();
+            let name = type_id.get_pascal_identifier().unwrap_or_default();
+            let Some(template) = component.template else {
+                continue;
+            };
+            let subtrees = SubTrees::from_template(&template, &template.get_root());
+            let subtrees = sanitizer.sanitize(&subtrees, &name);
+
+            let file_path = target_dir.join(format!("{}.pax", to_snake_case(&name)));
+            let mut new_template =
+                ComponentTemplate::new(type_id.clone(), file_path.to_str().map(String::from));
+            write_subtrees(&subtrees, &mut new_template);
+
+            let settings = component
+                .settings
+                .map(|settings| sanitizer.sanitize_settings_block(settings, &name));
+            components.push(ComponentDefinition {
+                type_id,
+                is_main_component: false,
+                is_primitive: false,
+                is_struct_only_component: false,
+                module_path: target_component.module_path.clone(),
+                primitive_instance_import_path: None,
+                template: Some(new_template),
+                settings,
+                extends: None,
+            });
+        }
+        let fragment = sanitizer.sanitize(&self.fragment, &self.name);
+        if fragment.roots.is_empty() {
+            bail!("none of the snippet's nodes can be imported");
+        }
+
+        Ok(SnippetImport {
+            components,
+            fragment,
+            assets,
+            report,
+            size: self.size,
+        })
+    }
+}
+
+impl SnippetImport {
+    /// Positions the fragment so that its bounds are centered on `center`
+    /// (in the coordinate space of the component it's pasted into)
+    pub fn place_at(&mut self, center: (f64, f64)) {
+        let left = center.0 - self.size.0 / 2.0;
+        let top = center.1 - self.size.1 / 2.0;
+        for root in &self.fragment.roots {
+            let Some(settings) = self
+                .fragment
+                .nodes
+                .get_mut(root)
+                .and_then(|node| node.settings.as_mut())
+            else {
+                continue;
+            };
+            let offset = |settings: &[SettingElement], key: &str| {
+                settings.iter().find_map(|setting| match setting {
+                    SettingElement::Setting(token, ValueDefinition::LiteralValue(value))
+                        if token.token_value == key =>
+                    {
+                        f64::try_coerce(value.clone()).ok()
+                    }
+                    _ => None,
+                })
+            };
+            let x = offset(settings, "x").unwrap_or_default();
+            let y = offset(settings, "y").unwrap_or_default();
+            set_literal(settings, "x", left + x);
+            set_literal(settings, "y", top + y);
+        }
+    }
+}
+
+/// Rewrites a snippet's nodes for the importing project, recording everything that's skipped
+struct Sanitizer<'a> {
+    manifest: &'a PaxManifest,
+    type_ids: &'a HashMap<TypeId, TypeId>,
+    asset_paths: &'a HashMap<String, String>,
+    report: &'a mut Vec<String>,
+}
+
+impl Sanitizer<'_> {
+    /// Copies `subtrees`, where `context` names the component or snippet they belong to in the report
+    fn sanitize(&mut self, subtrees: &SubTrees, context: &str) -> SubTrees {
+        let local_symbols: HashSet<String> = subtrees
+            .nodes
+            .values()
+            .filter_map(|node| node.control_flow_settings.as_ref())
+            .filter_map(|cfs| cfs.repeat_predicate_definition.as_ref())
+            .flat_map(|predicate| predicate.get_symbols())
+            .collect();
+        let mut sanitized = SubTrees::default();
+        for root in &subtrees.roots {
+            if let Some(id) =
+                self.sanitize_node(subtrees, root, &local_symbols, context, &mut sanitized)
+            {
+                sanitized.roots.push(id);
+            }
+        }
+        sanitized
+    }
+
+    fn sanitize_node(
+        &mut self,
+        src: &SubTrees,
+        id: &TemplateNodeId,
+        local_symbols: &HashSet<String>,
+        context: &str,
+        dst: &mut SubTrees,
+    ) -> Option<TemplateNodeId> {
+        let mut node = src.nodes.get(id)?.clone();
+        let node_name = type_name(&node.type_id);
+        let is_control_flow = matches!(
+            node.type_id.get_pax_type(),
+            PaxType::If | PaxType::Slot | PaxType::Repeat | PaxType::Comment
+        );
+        let imported_type_id = self.type_ids.get(&node.type_id).cloned();
+        if !is_control_flow
+            && imported_type_id.is_none()
+            && !self.manifest.components.contains_key(&node.type_id)
+        {
+            self.report.push(format!(
+                "Skipped {} in {}: the component isn't available in this project",
+                node_name, context
+            ));
+            return None;
+        }
+
+        if let Some(cfs) = &node.control_flow_settings {
+            let depends_on_properties = [
+                &cfs.condition_expression,
+                &cfs.slot_index_expression,
+                &cfs.repeat_source_expression,
+            ]
+            .into_iter()
+            .flatten()
+            .any(|info| !info.dependencies.iter().all(|d| is_local(d, local_symbols)));
+            if depends_on_properties {
+                self.report.push(format!(
+                    "Skipped {} in {}: it depends on properties of the exporting project",
+                    node_name, context
+                ));
+                return None;
+            }
+        }
+
+        if let Some(settings) = node.settings.take() {
+            let mut kept = vec![];
+            for setting in settings {
+                let SettingElement::Setting(key, mut value) = setting else {
+                    kept.push(setting);
+                    continue;
+                };
+                let is_property = !COMMON_PROPERTIES.contains(&key.token_value.as_str())
+                    && key.token_value != "class";
+                let skip_reason = if imported_type_id.is_some() && is_property {
+                    Some("imported components have no properties")
+                } else {
+                    unsupported_value(&value, local_symbols)
+                };
+                if let Some(reason) = skip_reason {
+                    self.report.push(format!(
+                        "Skipped {} of {} in {}: {}",
+                        key.token_value, node_name, context, reason
+                    ));
+                    continue;
+                }
+                self.rewrite_asset_paths(&mut value);
+                kept.push(SettingElement::Setting(key, value));
+            }
+            node.settings = Some(kept);
+        }
+        if let Some(type_id) = imported_type_id {
+            node.type_id = type_id;
+        }
+
+        // ids only need to be unique within the copy, they are reassigned when pasted
+        let new_id = TemplateNodeId::build(dst.nodes.len());
+        dst.nodes.insert(new_id.clone(), node);
+        let children: Vec<_> = src
+            .children
+            .get(id)
+            .into_iter()
+            .flatten()
+            .filter_map(|child| self.sanitize_node(src, child, local_symbols, context, dst))
+            .collect();
+        dst.children.insert(new_id.clone(), children);
+        Some(new_id)
+    }
+
+    fn sanitize_settings_block(
+        &mut self,
+        settings: Vec<SettingsBlockElement>,
+        context: &str,
+    ) -> Vec<SettingsBlockElement> {
+        settings
+            .into_iter()
+            .filter_map(|element| match element {
+                SettingsBlockElement::Handler(event, _) => {
+                    self.report.push(format!(
+                        "Skipped handler @{} in {}: handlers are implemented in Rust",
+                        event.token_value, context
+                    ));
+                    None
+                }
+                SettingsBlockElement::SelectorBlock(selector, mut block) => {
+                    for element in &mut block.elements {
+                        if let SettingElement::Setting(_, value) = element {
+                            self.rewrite_asset_paths(value);
+                        }
+                    }
+                    Some(SettingsBlockElement::SelectorBlock(selector, block))
+                }
+                comment => Some(comment),
+            })
+            .collect()
+    }
+
+    fn rewrite_asset_paths(&self, value: &mut ValueDefinition) {
+        visit_asset_paths(value, &mut |path| {
+            if let Some(new_path) = self.asset_paths.get(path.as_str()) {
+                *path = new_path.clone();
+            }
+        });
+    }
+}
+
+/// Why `value` can't be imported, if it can't
+fn unsupported_value(
+    value: &ValueDefinition,
+    local_symbols: &HashSet<String>,
+) -> Option<&'static str> {
+    match value {
+        ValueDefinition::EventBindingTarget(_) => Some("handlers are implemented in Rust"),
+        ValueDefinition::Identifier(identifier) | ValueDefinition::DoubleBinding(identifier)
+            if !is_local(&identifier.name, local_symbols) =>
+        {
+            Some("it's bound to a property of the exporting project")
+        }
+        ValueDefinition::Expression(info)
+            if !info.dependencies.iter().all(|d| is_local(d, local_symbols)) =>
+        {
+            Some("it's bound to a property of the exporting project")
+        }
+        _ => None,
+    }
+}
+
+/// Whether `dependency` of an expression is defined within the snippet (by a `for`)
+fn is_local(dependency: &str, local_symbols: &HashSet<String>) -> bool {
+    let root = dependency.split('.').next().unwrap_or_default();
+    local_symbols.contains(root)
+}
+
+/// The userland components used by `nodes`, including those used by their templates
+fn used_components<'a>(
+    manifest: &PaxManifest,
+    nodes: impl Iterator<Item = &'a TemplateNodeDefinition>,
+) -> Vec<ComponentDefinition> {
+    let mut components: Vec<ComponentDefinition> = vec![];
+    let mut to_visit: Vec<TypeId> = nodes.map(|node| node.type_id.clone()).collect();
+    while let Some(type_id) = to_visit.pop() {
+        if components.iter().any(|c| c.type_id == type_id) {
+            continue;
+        }
+        let Some(component) = manifest.components.get(&type_id) else {
+            continue;
+        };
+        if !is_userland_component(component) {
+            continue;
+        }
+        to_visit.extend(template_nodes(component).map(|node| node.type_id.clone()));
+        components.push(component.clone());
+    }
+    components
+}
+
+fn is_userland_component(component: &ComponentDefinition) -> bool {
+    let is_library = component.type_id.import_path().is_some_and(|path| {
+        ["pax_std", "pax_designer", "pax_engine"]
+            .iter()
+            .any(|library| path.starts_with(library))
+    });
+    let is_component_type = matches!(
+        component.type_id.get_pax_type(),
+        PaxType::Singleton { .. } | PaxType::BlankComponent { .. }
+    );
+    is_component_type
+        && !is_library
+        && !component.is_primitive
+        && !component.is_struct_only_component
+        && component.template.is_some()
+}
+
+fn template_nodes(
+    component: &ComponentDefinition,
+) -> impl Iterator<Item = &TemplateNodeDefinition> {
+    component.template.iter().flat_map(|t| t.get_nodes())
+}
+
+fn node_settings(
+    node: &TemplateNodeDefinition,
+) -> impl Iterator<Item = (&Token, &ValueDefinition)> {
+    node.settings
+        .iter()
+        .flatten()
+        .filter_map(|setting| match setting {
+            SettingElement::Setting(key, value) => Some((key, value)),
+            SettingElement::Comment(_) => None,
+        })
+}
+
+fn selector_settings(
+    component: &ComponentDefinition,
+) -> impl Iterator<Item = (&Token, &ValueDefinition)> {
+    component
+        .settings
+        .iter()
+        .flatten()
+        .filter_map(|element| match element {
+            SettingsBlockElement::SelectorBlock(_, block) => Some(block),
+            _ => None,
+        })
+        .flat_map(|block| &block.elements)
+        .filter_map(|setting| match setting {
+            SettingElement::Setting(key, value) => Some((key, value)),
+            SettingElement::Comment(_) => None,
+        })
+}
+
+/// Calls `f` with every asset path (string starting with `assets/`) in `value`,
+/// writing back any changes it makes
+fn visit_asset_paths(value: &mut ValueDefinition, f: &mut dyn FnMut(&mut String)) {
+    fn visit(json: &mut serde_json::Value, f: &mut dyn FnMut(&mut String)) {
+        match json {
+            serde_json::Value::String(s) if s.starts_with(ASSETS_DIR) => f(s),
+            serde_json::Value::Array(values) => values.iter_mut().for_each(|v| visit(v, f)),
+            serde_json::Value::Object(map) => map.values_mut().for_each(|v| visit(v, f)),
+            _ => (),
+        }
+    }
+
+    let Ok(mut json) = serde_json::to_value(&*value) else {
+        return;
+    };
+    let original = json.clone();
+    visit(&mut json, f);
+    if json != original {
+        if let Ok(rewritten) = serde_json::from_value(json) {
+            *value = rewritten;
+        }
+    }
+}
+
+/// Adds `subtrees` to the root of `template`
+fn write_subtrees(subtrees: &SubTrees, template: &mut ComponentTemplate) {
+    fn write_children(
+        subtrees: &SubTrees,
+        id: &TemplateNodeId,
+        parent: TemplateNodeId,
+        template: &mut ComponentTemplate,
+    ) {
+        for child in subtrees.children.get(id).into_iter().flatten() {
+            let new_id = template
+                .add_child_back(parent.clone(), subtrees.nodes[child].clone())
+                .get_template_node_id();
+            write_children(subtrees, child, new_id, template);
+        }
+    }
+
+    for root in &subtrees.roots {
+        let new_id = template
+            .add_root_node_back(subtrees.nodes[root].clone())
+            .get_template_node_id();
+        write_children(subtrees, root, new_id, template);
+    }
+}
+
+fn set_literal(settings: &mut Vec<SettingElement>, key: &str, value: f64) {
+    let value = ValueDefinition::LiteralValue(value.to_pax_value());
+    for setting in settings.iter_mut() {
+        if let SettingElement::Setting(token, existing) = setting {
+            if token.token_value == key {
+                *existing = value;
+                return;
+            }
+        }
+    }
+    settings.push(SettingElement::Setting(
+        Token::new_without_location(key.to_string()),
+        value,
+    ));
+}
+
+fn pascal_identifiers(manifest: &PaxManifest) -> HashSet<String> {
+    manifest
+        .components
+        .keys()
+        .filter_map(|type_id| type_id.get_pascal_identifier())
+        .collect()
+}
+
+fn type_name(type_id: &TypeId) -> String {
+    type_id
+        .get_pascal_identifier()
+        .unwrap_or_else(|| type_id.to_string())
+}
+
+pub(crate) fn to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            snake.push('_');
+        }
+        if c.is_alphanumeric() {
+            snake.extend(c.to_lowercase());
+        } else if !snake.ends_with('_') {
+            snake.push('_');
+        }
+    }
+    snake
+}
@@ -0,0 +1,41 @@
+//! # Thumbnails
+//!
+//! Small previews of userland components, shown by the designer's component library. The
+//! designer requests a thumbnail for a component through the `DesigntimeManager`, the chassis
+//! renders a detached instance of the component to an offscreen surface on its next tick, and
+//! hands the pixels back. Thumbnails are cached by a hash of the component's definition, so a
+//! component is only rendered again once it changed.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use pax_manifest::{ComponentDefinition, TypeId};
+
+/// Size in pixels thumbnails are rendered at
+pub const THUMBNAIL_SIZE: (usize, usize) = (96, 64);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComponentThumbnail {
+    /// `component_content_hash` of the definition this was rendered from
+    pub content_hash: u64,
+    pub width: usize,
+    pub height: usize,
+    /// `width * height * 4` bytes, one for each of r, g, b and a
+    pub rgba: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThumbnailRequest {
+    pub type_id: TypeId,
+    pub content_hash: u64,
+}
+
+/// Hash of everything that affects how `component` renders on its own
+pub fn component_content_hash(component: &ComponentDefinition) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    component.type_id.hash(&mut hasher);
+    if let Ok(bytes) = rmp_serde::to_vec(component) {
+        bytes.hash(&mut hasher);
+    }
+    hasher.finish()
+}
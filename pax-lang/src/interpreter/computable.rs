@@ -1,4 +1,8 @@
-use std::{collections::HashMap, rc::Rc};
+use std::{
+    collections::HashMap,
+    rc::Rc,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 use pax_runtime_api::{
     functions::call_function, CoercionRules, Functions, Numeric, PaxValue, Percent, Rotation, Size,
@@ -32,35 +36,62 @@ impl Computable for PaxPrimary {
             PaxPrimary::Identifier(i, accessors) => {
                 let mut value = i.compute(idr.clone())?;
                 for accessor in accessors {
-                    match accessor {
+                    let next = match accessor {
                         PaxAccessor::Tuple(index) => {
                             if let PaxValue::Vec(v) = value {
-                                value = v[*index].clone();
+                                Some(v.get(*index).cloned().ok_or_else(|| {
+                                    format!(
+                                        "Tuple index {} out of bounds for a tuple of length {}",
+                                        index,
+                                        v.len()
+                                    )
+                                })?)
                             } else {
                                 return Err("Tuple access must be performed on a tuple".to_string());
                             }
                         }
                         PaxAccessor::List(index) => {
                             if let PaxValue::Vec(v) = value {
-                                let index = Numeric::try_coerce(index.compute(idr.clone())?)?
-                                    .to_int() as usize;
-                                value = v[index].clone();
+                                let index =
+                                    Numeric::try_coerce(index.compute(idr.clone())?)?.to_int();
+                                Some(index_list(v, index)?)
                             } else {
                                 return Err("List access must be performed on a list".to_string());
                             }
                         }
                         PaxAccessor::Struct(field) => {
                             if let PaxValue::Object(obj) = value {
-                                value = obj
-                                    .get(field)
-                                    .map(|v| v.clone())
-                                    .ok_or(format!("Field not found: {}", field))?;
+                                Some(
+                                    obj.get(field)
+                                        .map(|v| v.clone())
+                                        .ok_or(format!("Field not found: {}", field))?,
+                                )
                             } else {
                                 return Err(
                                     "Struct access must be performed on an object".to_string()
                                 );
                             }
                         }
+                        PaxAccessor::OptionalList(index) => match unwrap_option(value) {
+                            Some(PaxValue::Vec(v)) => {
+                                let index =
+                                    Numeric::try_coerce(index.compute(idr.clone())?)?.to_int();
+                                usize::try_from(index)
+                                    .ok()
+                                    .and_then(|index| v.get(index).cloned())
+                            }
+                            _ => None,
+                        },
+                        PaxAccessor::OptionalStruct(field) => match unwrap_option(value) {
+                            Some(PaxValue::Object(obj)) => obj.get(field).cloned(),
+                            _ => None,
+                        },
+                    };
+                    match next {
+                        Some(next) => value = next,
+                        // like `?.` in Javascript, a missing value short-circuits the rest
+                        // of the chain
+                        None => return Ok(PaxValue::Option(Box::new(None))),
                     }
                 }
                 Ok(value)
@@ -144,6 +175,13 @@ impl Computable for PaxPostfix {
 impl Computable for PaxInfix {
     fn compute(&self, idr: Rc<dyn IdentifierResolver>) -> Result<PaxValue, String> {
         let lhs = self.lhs.compute(idr.clone())?;
+        if self.operator.name == "??" {
+            // the fallback is only computed if it's needed
+            return match unwrap_option(lhs) {
+                Some(value) => Ok(value),
+                None => self.rhs.compute(idr),
+            };
+        }
         let rhs = self.rhs.compute(idr)?;
         let operator = &self.operator.name;
         call_function("Math".to_string(), operator.to_string(), vec![lhs, rhs])
@@ -155,3 +193,39 @@ impl Computable for PaxIdentifier {
         idr.resolve(self.name.clone())
     }
 }
+
+/// `Some(v)` for `v` or `Option(Some(v))`, `None` for `Option(None)`
+fn unwrap_option(value: PaxValue) -> Option<PaxValue> {
+    match value {
+        PaxValue::Option(o) => *o,
+        v => Some(v),
+    }
+}
+
+static LOGGED_OUT_OF_BOUNDS: AtomicBool = AtomicBool::new(false);
+
+/// `list[index]`.  An out of bounds index is a bug in the expression: debug builds panic to
+/// surface it, while release builds log it (once, since expressions are recomputed every time
+/// their dependencies change) and clamp `index` to the list rather than taking the app down.
+/// `list[?index]` is the way to access an index that's expected to be out of bounds at times.
+fn index_list(list: Vec<PaxValue>, index: i64) -> Result<PaxValue, String> {
+    let len = list.len();
+    if let Some(value) = usize::try_from(index).ok().and_then(|i| list.get(i)) {
+        return Ok(value.clone());
+    }
+    let message = format!(
+        "List index {} out of bounds for a list of length {}; use `[?{}]` if the index may be out of bounds",
+        index, len, index
+    );
+    if cfg!(debug_assertions) {
+        panic!("{}", message);
+    }
+    if !LOGGED_OUT_OF_BOUNDS.swap(true, Ordering::Relaxed) {
+        log::warn!("{}", message);
+    }
+    if len == 0 {
+        return Err(message);
+    }
+    let clamped = index.clamp(0, len as i64 - 1) as usize;
+    Ok(list[clamped].clone())
+}
@@ -41,6 +41,37 @@ impl Display for PaxExpression {
     }
 }
 
+impl PaxExpression {
+    /// Whether this expression is built purely from literals combined with operators,
+    /// i.e. it references no identifiers and calls no functions, so it always computes
+    /// to the same value
+    pub fn is_constant(&self) -> bool {
+        match self {
+            PaxExpression::Primary(p) => p.is_constant(),
+            PaxExpression::Prefix(p) => p.rhs.is_constant(),
+            PaxExpression::Infix(i) => i.lhs.is_constant() && i.rhs.is_constant(),
+            PaxExpression::Postfix(p) => p.lhs.is_constant(),
+        }
+    }
+
+    /// Replaces every occurrence of identifier `name`, along with the accessors following it,
+    /// by the literal `replace` returns given these accessors.  Returns whether anything was
+    /// replaced.
+    pub fn replace_identifier(
+        &mut self,
+        name: &str,
+        replace: &mut dyn FnMut(&[PaxAccessor]) -> Result<PaxValue, String>,
+    ) -> Result<bool, String> {
+        match self {
+            PaxExpression::Primary(p) => p.replace_identifier(name, replace),
+            PaxExpression::Prefix(p) => p.rhs.replace_identifier(name, replace),
+            PaxExpression::Infix(i) => Ok(i.lhs.replace_identifier(name, replace)?
+                | i.rhs.replace_identifier(name, replace)?),
+            PaxExpression::Postfix(p) => p.lhs.replace_identifier(name, replace),
+        }
+    }
+}
+
 #[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
 pub enum PaxPrimary {
     Literal(PaxValue),
@@ -81,6 +112,8 @@ impl Display for PaxPrimary {
                         PaxAccessor::Tuple(i) => write!(f, ".{}", i)?,
                         PaxAccessor::List(e) => write!(f, "[{}]", e)?,
                         PaxAccessor::Struct(s) => write!(f, ".{}", s)?,
+                        PaxAccessor::OptionalList(e) => write!(f, "[?{}]", e)?,
+                        PaxAccessor::OptionalStruct(s) => write!(f, "?.{}", s)?,
                     }
                 }
                 Ok(())
@@ -143,6 +176,64 @@ impl Display for PaxPrimary {
     }
 }
 
+impl PaxPrimary {
+    pub fn is_constant(&self) -> bool {
+        match self {
+            PaxPrimary::Literal(_) => true,
+            PaxPrimary::Grouped(e, _) => e.is_constant(),
+            PaxPrimary::Tuple(t) => t.iter().all(|e| e.is_constant()),
+            PaxPrimary::List(l) => l.iter().all(|e| e.is_constant()),
+            PaxPrimary::Identifier(_, _)
+            | PaxPrimary::Object(_)
+            | PaxPrimary::FunctionOrEnum(_, _, _)
+            | PaxPrimary::Range(_, _) => false,
+        }
+    }
+
+    fn replace_identifier(
+        &mut self,
+        name: &str,
+        replace: &mut dyn FnMut(&[PaxAccessor]) -> Result<PaxValue, String>,
+    ) -> Result<bool, String> {
+        let mut replaced = false;
+        match self {
+            PaxPrimary::Literal(_) => {}
+            PaxPrimary::Grouped(e, _) => replaced = e.replace_identifier(name, replace)?,
+            PaxPrimary::Identifier(i, accessors) => {
+                if i.name == name {
+                    *self = PaxPrimary::Literal(replace(accessors)?);
+                    return Ok(true);
+                }
+                for accessor in accessors {
+                    if let PaxAccessor::List(index) | PaxAccessor::OptionalList(index) = accessor {
+                        replaced |= index.replace_identifier(name, replace)?;
+                    }
+                }
+            }
+            PaxPrimary::Object(o) => {
+                for e in o.values_mut() {
+                    replaced |= e.replace_identifier(name, replace)?;
+                }
+            }
+            PaxPrimary::FunctionOrEnum(_, _, args) => {
+                for e in args {
+                    replaced |= e.replace_identifier(name, replace)?;
+                }
+            }
+            PaxPrimary::Range(start, end) => {
+                replaced |= start.replace_identifier(name, replace)?;
+                replaced |= end.replace_identifier(name, replace)?;
+            }
+            PaxPrimary::Tuple(elements) | PaxPrimary::List(elements) => {
+                for e in elements {
+                    replaced |= e.replace_identifier(name, replace)?;
+                }
+            }
+        }
+        Ok(replaced)
+    }
+}
+
 impl Default for PaxPrimary {
     fn default() -> Self {
         Self::Literal(PaxValue::default())
@@ -162,6 +253,10 @@ pub enum PaxAccessor {
     Tuple(usize),
     List(PaxExpression),
     Struct(String),
+    /// `[?index]`: `None` instead of an error if `index` is out of bounds
+    OptionalList(PaxExpression),
+    /// `?.field`: `None` instead of an error if the value is `None` or has no `field`
+    OptionalStruct(String),
 }
 
 #[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
@@ -418,6 +513,17 @@ fn recurse_pratt_parse(
                             let field = symbol.as_str().trim().to_string();
                             PaxAccessor::Struct(field)
                         }
+                        // optional list access [?index]
+                        Rule::xo_optional_index => {
+                            let body = symbol.into_inner().next().unwrap();
+                            let expr = recurse_pratt_parse(body.into_inner(), pratt_parser)?;
+                            PaxAccessor::OptionalList(expr)
+                        }
+                        // optional field access ?.field
+                        Rule::xo_optional_field => {
+                            let field = symbol.into_inner().next().unwrap();
+                            PaxAccessor::OptionalStruct(field.as_str().trim().to_string())
+                        }
                         _ => {
                             return Err(format!("Unexpected rule: {:?}", symbol.as_rule()));
                         }
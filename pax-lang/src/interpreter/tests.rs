@@ -53,6 +53,15 @@ fn initialize_test_resolver() -> Rc<HashMap<String, PaxValue>> {
             .collect(),
         ),
     );
+    idr.insert(
+        "user".to_string(),
+        PaxValue::Option(Box::new(Some(PaxValue::Object(
+            vec![("name".to_string(), PaxValue::String("Ada".to_string()))]
+                .into_iter()
+                .collect(),
+        )))),
+    );
+    idr.insert("no_user".to_string(), PaxValue::Option(Box::new(None)));
     Rc::new(idr)
 }
 
@@ -128,6 +137,61 @@ fn test_modulus() {
     assert_eq!(expected, result);
 }
 
+#[test]
+fn test_modulus_float() {
+    let idr = initialize_test_resolver();
+    let expr = "10.5 %% 4";
+    let expected = PaxValue::Numeric(Numeric::F64(2.5));
+    let result = compute_paxel(expr, idr).unwrap();
+    assert_eq!(expected, result);
+}
+
+#[test]
+fn test_modulus_by_zero() {
+    let idr = initialize_test_resolver();
+    let expr = "a %% 0";
+    let result = compute_paxel(expr, idr).unwrap();
+    let PaxValue::Numeric(n) = result else {
+        panic!("expected numeric, got {:?}", result);
+    };
+    assert!(n.to_float().is_nan());
+}
+
+#[test]
+fn test_integer_overflow() {
+    let idr = initialize_test_resolver();
+    for (expr, expected) in [
+        ("9223372036854775807 + 1", 9223372036854775808.0),
+        ("-9223372036854775807 - 2", -9223372036854775809.0),
+        ("9223372036854775807 * 2", 18446744073709551614.0),
+        ("2 ^ 64", 18446744073709551616.0),
+        ("2 ^ -1", 0.5),
+    ] {
+        let result = compute_paxel(expr, idr.clone()).unwrap();
+        assert_eq!(
+            result,
+            PaxValue::Numeric(Numeric::F64(expected)),
+            "{}",
+            expr
+        );
+    }
+}
+
+#[test]
+fn test_euclidean_division() {
+    let idr = initialize_test_resolver();
+    let expr = "Math::div_euclid(-7, 2)";
+    let expected = PaxValue::Numeric(Numeric::I64(-4));
+    let result = compute_paxel(expr, idr.clone()).unwrap();
+    assert_eq!(expected, result);
+    assert!(matches!(result, PaxValue::Numeric(Numeric::I64(_))));
+
+    let expr = "Math::rem_euclid(-7, 2)";
+    let expected = PaxValue::Numeric(Numeric::I64(1));
+    let result = compute_paxel(expr, idr).unwrap();
+    assert_eq!(expected, result);
+}
+
 #[test]
 fn test_rel_eq() {
     let idr = initialize_test_resolver();
@@ -359,6 +423,76 @@ fn test_list_access() {
     assert_eq!(expected, result);
 }
 
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn test_list_access_out_of_bounds_panics_in_debug() {
+    let idr = initialize_test_resolver();
+    let _ = compute_paxel("c[2]", idr);
+}
+
+#[test]
+fn test_tuple_access_out_of_bounds() {
+    let idr = initialize_test_resolver();
+    assert!(compute_paxel("c.2", idr).is_err());
+}
+
+#[test]
+fn test_optional_list_access() {
+    let idr = initialize_test_resolver();
+    let none = PaxValue::Option(Box::new(None));
+    assert_eq!(
+        compute_paxel("c[?1]", idr.clone()).unwrap(),
+        PaxValue::Numeric(Numeric::I64(2))
+    );
+    assert_eq!(compute_paxel("c[?2]", idr.clone()).unwrap(), none);
+    assert_eq!(compute_paxel("c[?b - 5]", idr.clone()).unwrap(), none);
+    assert_eq!(compute_paxel("no_user[?0]", idr).unwrap(), none);
+}
+
+#[test]
+fn test_optional_struct_access() {
+    let idr = initialize_test_resolver();
+    let none = PaxValue::Option(Box::new(None));
+    assert_eq!(
+        compute_paxel("user?.name", idr.clone()).unwrap(),
+        PaxValue::String("Ada".to_string())
+    );
+    assert_eq!(compute_paxel("user?.age", idr.clone()).unwrap(), none);
+    assert_eq!(compute_paxel("d?.c", idr.clone()).unwrap(), none);
+    // the rest of the chain is skipped once a value is missing
+    assert_eq!(compute_paxel("no_user?.name.first", idr).unwrap(), none);
+}
+
+#[test]
+fn test_null_coalesce() {
+    let idr = initialize_test_resolver();
+    assert_eq!(
+        compute_paxel("no_user?.name ?? \"guest\"", idr.clone()).unwrap(),
+        PaxValue::String("guest".to_string())
+    );
+    assert_eq!(
+        compute_paxel("user?.name ?? \"guest\"", idr.clone()).unwrap(),
+        PaxValue::String("Ada".to_string())
+    );
+    assert_eq!(
+        compute_paxel("c[?5] ?? a + b", idr.clone()).unwrap(),
+        PaxValue::Numeric(Numeric::I64(14))
+    );
+    // the fallback isn't computed if the value is present
+    assert_eq!(
+        compute_paxel("c[?0] ?? c.5", idr).unwrap(),
+        PaxValue::Numeric(Numeric::I64(1))
+    );
+}
+
+#[test]
+fn test_display_optional_access() {
+    let expr = "user?.name ?? c[?a+1]";
+    let expected = "user?.name ?? c[?a + 1]";
+    let result = format!("{}", parse_pax_expression(expr).unwrap());
+    assert_eq!(expected, result);
+}
+
 #[test]
 fn test_color_expression() {
     let idr = initialize_test_resolver();
@@ -431,3 +565,49 @@ fn test_display_object() {
     let result = format!("{}", parse_pax_expression(expr).unwrap());
     assert_eq!(expected, result);
 }
+
+#[test]
+fn test_is_constant() {
+    assert!(parse_pax_expression("1 + 2").unwrap().is_constant());
+    assert!(parse_pax_expression("(100.0 * 0.5)px")
+        .unwrap()
+        .is_constant());
+    assert!(parse_pax_expression("true && !false")
+        .unwrap()
+        .is_constant());
+    assert!(parse_pax_expression("\"a\" + \"b\"").unwrap().is_constant());
+    assert!(!parse_pax_expression("a + 2").unwrap().is_constant());
+    assert!(!parse_pax_expression("Math::min(1, 2)")
+        .unwrap()
+        .is_constant());
+    assert!(!parse_pax_expression("0..5").unwrap().is_constant());
+}
+
+#[test]
+fn test_replace_identifier() {
+    let mut expr = parse_pax_expression("$defines.new_nav && a > c[$defines.index]").unwrap();
+    let replaced = expr
+        .replace_identifier("$defines", &mut |accessors| match accessors {
+            [super::PaxAccessor::Struct(key)] if key == "new_nav" => Ok(PaxValue::Bool(true)),
+            [super::PaxAccessor::Struct(key)] if key == "index" => {
+                Ok(PaxValue::Numeric(Numeric::I64(1)))
+            }
+            _ => Err("unknown define".to_string()),
+        })
+        .unwrap();
+    assert!(replaced);
+    assert_eq!(format!("{}", expr), "true && a > c[1]");
+    let mut deps = expr.collect_dependencies();
+    deps.sort();
+    assert_eq!(deps, vec!["a", "c"]);
+
+    let mut expr = parse_pax_expression("$defines.missing").unwrap();
+    assert!(expr
+        .replace_identifier("$defines", &mut |_| Err("unknown define".to_string()))
+        .is_err());
+
+    let mut expr = parse_pax_expression("a + 1").unwrap();
+    assert!(!expr
+        .replace_identifier("$defines", &mut |_| Ok(PaxValue::Bool(true)))
+        .unwrap());
+}
@@ -27,6 +27,9 @@ fn renamed_rules(rule: &Rule) -> String {
         Rule::comment => "comment".to_string(),
         Rule::pax_component_definition =>  "component".to_string(),
         Rule::root_tag_pair => "component tag, comment".to_string(),
+        Rule::extends_declaration => "@extends(COMPONENT_NAME)".to_string(),
+        Rule::override_tag_pair => "overriding component tag".to_string(),
+        Rule::override_directive => "@override(label=\"LABEL\")".to_string(),
         Rule::any_tag_pair => "component tag, comment".to_string(),
         Rule::open_tag => "opening component tag".to_string(),
         Rule::closing_tag => "closing component tag".to_string(),
@@ -78,13 +81,13 @@ fn renamed_rules(rule: &Rule) -> String {
         Rule::xo_prefix => "- , !".to_string(),
         Rule::xo_neg => "-".to_string(),
         Rule::xo_bool_not => "!".to_string(),
-        Rule::xo_infix => "+, -, *, /, %, ^, ==, !=, <, <=, >, >=, &&, ||".to_string(),
+        Rule::xo_infix => "+, -, *, /, %%, ^, ==, !=, <, <=, >, >=, &&, ||, ??".to_string(),
         Rule::xo_add => "+".to_string(),
         Rule::xo_bool_and => "&&".to_string(),
         Rule::xo_bool_or => "||".to_string(),
         Rule::xo_div => "/".to_string(),
         Rule::xo_exp => "^".to_string(),
-        Rule::xo_mod => "%".to_string(),
+        Rule::xo_mod => "%%".to_string(),
         Rule::xo_mul => "*".to_string(),
         Rule::xo_rel_eq => "==".to_string(),
         Rule::xo_rel_gt => ">".to_string(),
@@ -93,6 +96,7 @@ fn renamed_rules(rule: &Rule) -> String {
         Rule::xo_rel_lte => "<=".to_string(),
         Rule::xo_rel_neq => "!=".to_string(),
         Rule::xo_sub => "-".to_string(),
+        Rule::xo_null_coalesce => "??".to_string(),
         Rule::xo_tern_then => "then".to_string(),
         Rule::xo_tern_else => "else".to_string(),
         Rule::xo_range => "range (e.g. 0..5 or i..j)".to_string(),
@@ -101,6 +105,8 @@ fn renamed_rules(rule: &Rule) -> String {
         Rule::xo_object => "literal object".to_string(),
         Rule::xo_object_settings_key_value_pair => "setting key-value pair".to_string(),
         Rule::xo_symbol => "identifier".to_string(),
+        Rule::xo_optional_field => "optional field access (e.g. ?.name)".to_string(),
+        Rule::xo_optional_index => "optional index (e.g. [?i])".to_string(),
         Rule::xo_tuple => "tuple (e.g. (1,2) )".to_string(),
         Rule::xo_list => "list (e.g. [1,2] )".to_string(),
         Rule::xo_enum_or_function_call => "enum, function call".to_string(),
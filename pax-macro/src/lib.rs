@@ -217,6 +217,25 @@ fn recurse_get_scoped_resolvable_types(t: &Type, accum: &mut Vec<String>) {
     }
 }
 
+/// Whether `attrs` contain `#[pax_allow(<lint>)]`
+fn has_pax_allow(attrs: &[syn::Attribute], lint: &str) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path.is_ident("pax_allow")
+            && matches!(attr.parse_meta(), Ok(Meta::List(meta_list)) if meta_list.nested.iter().any(|nested| {
+                matches!(nested, syn::NestedMeta::Meta(Meta::Path(path)) if path.is_ident(lint))
+            }))
+    })
+}
+
+/// Removes `#[pax_allow(...)]` from fields, since it is only read by `#[pax]`
+fn strip_pax_allow_attributes(input: &mut DeriveInput) {
+    if let Data::Struct(data) = &mut input.data {
+        for field in data.fields.iter_mut() {
+            field.attrs.retain(|attr| !attr.path.is_ident("pax_allow"));
+        }
+    }
+}
+
 fn index_to_ascii_lowercase(index: usize) -> char {
     (b'a' + (index as u8)) as char
 }
@@ -246,6 +265,7 @@ fn get_internal_definitions_from_tokens(data: &Data) -> InternalDefinitions {
                                     pascal_identifier,
                                     is_property_wrapped: ty.1,
                                     is_enum: false,
+                                    allow_unused: has_pax_allow(&f.attrs, "unused_property"),
                                 })
                             }
                         };
@@ -277,6 +297,7 @@ fn get_internal_definitions_from_tokens(data: &Data) -> InternalDefinitions {
                             pascal_identifier,
                             is_property_wrapped: ty.1,
                             is_enum: true,
+                            allow_unused: false,
                         })
                     }
                 }
@@ -354,10 +375,14 @@ fn pax_full_component(
             error_message = Some(err);
         }
     }
+    let extends_dependency =
+        parsing::parse_extends_from_component_definition_string(&raw_pax).unwrap_or_default();
 
-    // Add BlankComponent to template_dependencies so it's guaranteed to be included in the PaxManifest
+    // Add BlankComponent and UnresolvedPlaceholder to template_dependencies so they're guaranteed
+    // to be included in the PaxManifest
     if is_main_component {
         template_dependencies.push("BlankComponent".to_string());
+        template_dependencies.push("UnresolvedPlaceholder".to_string());
     }
 
     let pax_dir: Option<PathBuf> = option_env!("PAX_DIR")
@@ -385,6 +410,7 @@ fn pax_full_component(
             is_main_component,
             raw_pax,
             template_dependencies,
+            extends_dependency,
             cartridge_snippet,
             associated_pax_file_path,
             error_message,
@@ -557,6 +583,11 @@ pub fn pax(
     _args: proc_macro::TokenStream,
     input: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
+    if let Ok(item_impl) = syn::parse::<ItemImpl>(input.clone()) {
+        return pax_public_methods(item_impl)
+            .unwrap_or_else(syn::Error::into_compile_error)
+            .into();
+    }
     let mut input = parse_macro_input!(input as DeriveInput);
 
     let pascal_identifier = input.ident.to_string();
@@ -648,6 +679,8 @@ pub fn pax(
         })
         .collect();
 
+    strip_pax_allow_attributes(&mut input);
+
     let ident = &input.ident;
     let helper_functions_impl = if !config.has_helpers {
         quote! {
@@ -675,6 +708,144 @@ pub fn pax(
     output.into()
 }
 
+/// `#[pax]` on an `impl` block of a component: generates a `<Component>Handle` trait with the
+/// methods of the block marked `#[pax(public)]`, implemented for `ComponentHandle<Component>` by
+/// queueing calls onto the instance.  Public methods take `&mut self`, optionally followed by the
+/// `&NodeContext` of the instance, and return nothing, as they run on a later tick
+fn pax_public_methods(mut input: ItemImpl) -> syn::Result<TokenStream> {
+    let self_ty = input.self_ty.clone();
+    if let Some((_, path, _)) = &input.trait_ {
+        return Err(syn::Error::new_spanned(
+            path,
+            "#[pax] impl blocks declare the public methods of a component, not trait impls",
+        ));
+    }
+    let type_name = match &*self_ty {
+        Type::Path(path) if input.generics.params.is_empty() => path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.clone())
+            .ok_or_else(|| syn::Error::new_spanned(&self_ty, "expected a component type"))?,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &self_ty,
+                "#[pax] impl blocks are only supported for non-generic components",
+            ))
+        }
+    };
+    let trait_name = format_ident!("{}Handle", type_name);
+
+    let mut trait_methods = vec![];
+    let mut impl_methods = vec![];
+    for item in input.items.iter_mut() {
+        let ImplItem::Method(method) = item else {
+            continue;
+        };
+        let attr_count = method.attrs.len();
+        method.attrs.retain(|attr| !is_pax_public_attribute(attr));
+        if method.attrs.len() == attr_count {
+            continue;
+        }
+
+        let sig = &method.sig;
+        if !sig.generics.params.is_empty() || sig.asyncness.is_some() {
+            return Err(syn::Error::new_spanned(
+                sig,
+                "#[pax(public)] methods can't be generic or async",
+            ));
+        }
+        if !matches!(sig.output, syn::ReturnType::Default) {
+            return Err(syn::Error::new_spanned(
+                &sig.output,
+                "#[pax(public)] methods run on a later tick, so they can't return values",
+            ));
+        }
+        let mut inputs = sig.inputs.iter();
+        match inputs.next() {
+            Some(FnArg::Receiver(receiver)) if receiver.reference.is_some() => {}
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    sig,
+                    "#[pax(public)] methods need to take `&mut self` or `&self`",
+                ))
+            }
+        }
+        let mut inputs = inputs.peekable();
+        let takes_ctx = matches!(
+            inputs.peek(),
+            Some(FnArg::Typed(PatType { ty, .. })) if is_node_context_ref(ty)
+        );
+        if takes_ctx {
+            inputs.next();
+        }
+        let (arg_names, arg_types): (Vec<_>, Vec<_>) = inputs
+            .enumerate()
+            .filter_map(|(i, arg)| match arg {
+                FnArg::Typed(PatType { ty, .. }) => Some((format_ident!("arg_{}", i), ty.clone())),
+                FnArg::Receiver(_) => None,
+            })
+            .unzip();
+
+        let method_name = &sig.ident;
+        let (ctx_param, ctx_arg) = match takes_ctx {
+            true => (quote! { ctx }, Some(quote! { ctx, })),
+            false => (quote! { _ctx }, None),
+        };
+        trait_methods.push(quote! {
+            fn #method_name(&self, #(#arg_names: #arg_types),*);
+        });
+        impl_methods.push(quote! {
+            fn #method_name(&self, #(#arg_names: #arg_types),*) {
+                self.call(move |component, #ctx_param| {
+                    component.#method_name(#ctx_arg #(#arg_names),*)
+                });
+            }
+        });
+    }
+
+    let doc = format!(
+        "The `#[pax(public)]` methods of [`{}`], callable on its instances through their handles",
+        type_name
+    );
+    Ok(quote! {
+        #input
+
+        #[doc = #doc]
+        pub trait #trait_name {
+            #(#trait_methods)*
+        }
+
+        impl #trait_name for pax_engine::api::ComponentHandle<#self_ty> {
+            #(#impl_methods)*
+        }
+    })
+}
+
+/// Whether `attr` is `#[pax(public)]`
+fn is_pax_public_attribute(attr: &syn::Attribute) -> bool {
+    if !attr.path.is_ident("pax") {
+        return false;
+    }
+    match attr.parse_meta() {
+        Ok(Meta::List(list)) => list.nested.iter().any(|nested| {
+            matches!(nested, syn::NestedMeta::Meta(Meta::Path(path)) if path.is_ident("public"))
+        }),
+        _ => false,
+    }
+}
+
+/// Whether `ty` is a reference to a `NodeContext`
+fn is_node_context_ref(ty: &Type) -> bool {
+    let Type::Reference(reference) = ty else {
+        return false;
+    };
+    matches!(
+        &*reference.elem,
+        Type::Path(path) if path.path.segments.last().is_some_and(|s| s.ident == "NodeContext")
+    )
+}
+
 // Needed because Cargo wouldn't otherwise watch for changes in pax files.
 // By include_str!ing the file contents,
 // (Trick borrowed from Pest: github.com/pest-parser/pest)
@@ -19,12 +19,35 @@ pub fn parse_pascal_identifiers_from_component_definition_string(
                     Rc::clone(&pascal_identifiers),
                 );
             }
+            Rule::override_tag_pair => {
+                //override_tag_pair => override_directive, any_tag_pair
+                recurse_visit_tag_pairs_for_pascal_identifiers(
+                    pair.into_inner().nth(1).unwrap(),
+                    Rc::clone(&pascal_identifiers),
+                );
+            }
             _ => {}
         });
     let unwrapped_hashmap = Rc::try_unwrap(pascal_identifiers).unwrap().into_inner();
     Ok(unwrapped_hashmap.into_iter().collect())
 }
 
+/// The component named by the `@extends(..)` declaration of a component definition, if any
+pub fn parse_extends_from_component_definition_string(pax: &str) -> Result<Option<String>, String> {
+    let pax_component_definition = parse_pax_str(Rule::pax_component_definition, pax)?;
+    Ok(pax_component_definition
+        .into_inner()
+        .find(|pair| pair.as_rule() == Rule::extends_declaration)
+        .map(|extends_declaration| {
+            extends_declaration
+                .into_inner()
+                .next()
+                .unwrap()
+                .as_str()
+                .to_string()
+        }))
+}
+
 fn recurse_visit_tag_pairs_for_pascal_identifiers(
     any_tag_pair: Pair<Rule>,
     pascal_identifiers: Rc<RefCell<HashSet<String>>>,
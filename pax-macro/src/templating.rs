@@ -13,6 +13,8 @@ pub struct StaticPropertyDefinition {
     pub pascal_identifier: String,
     pub is_property_wrapped: bool,
     pub is_enum: bool,
+    /// Whether the field is marked `#[pax_allow(unused_property)]`
+    pub allow_unused: bool,
 }
 
 #[derive(Serialize, Debug)]
@@ -40,6 +42,8 @@ pub struct ArgsFullComponent {
     pub raw_pax: String,
     pub is_main_component: bool,
     pub template_dependencies: Vec<String>,
+    /// Component named by `@extends(..)`, parsed before this one
+    pub extends_dependency: Option<String>,
     pub cartridge_snippet: String,
     pub associated_pax_file_path: Option<PathBuf>,
     pub error_message: Option<String>,
@@ -64,6 +64,7 @@ impl PaxManifest {
         add("key_press", "KeyPress");
         add("checkbox_change", "CheckboxChange");
         add("button_click", "ButtonClick");
+        add("ended", "VideoEnded");
         add("textbox_change", "TextboxChange");
         add("text_input", "TextInput");
         add("textbox_input", "TextboxInput");
@@ -172,15 +173,18 @@ impl PaxManifest {
         if let Some(settings) = &settings {
             for setting in settings {
                 if let SettingElement::Setting(key, value) = setting {
+                    let value = self.resolve_tokens(value);
                     match value {
                         ValueDefinition::LiteralValue(_)
                         | ValueDefinition::Block(_)
                         | ValueDefinition::Expression(_)
                         | ValueDefinition::Identifier(_)
                         | ValueDefinition::DoubleBinding(_) => {
-                            map.insert(key.token_value.clone(), value.clone());
+                            map.insert(key.token_value.clone(), value);
                         }
-                        ValueDefinition::EventBindingTarget(_) | ValueDefinition::Undefined => {}
+                        ValueDefinition::EventBindingTarget(_)
+                        | ValueDefinition::Token(_)
+                        | ValueDefinition::Undefined => {}
                     }
                 }
             }
@@ -200,13 +204,14 @@ impl PaxManifest {
         if let Some(settings) = &settings {
             for setting in settings {
                 if let SettingElement::Setting(key, value) = setting {
+                    let value = self.resolve_tokens(value);
                     match value {
                         ValueDefinition::LiteralValue(_)
                         | ValueDefinition::Block(_)
                         | ValueDefinition::Expression(_)
                         | ValueDefinition::Identifier(_) => {
                             if CommonProperty::get_common_properties().contains(&key.token_value) {
-                                map.insert(key.token_value.clone(), value.clone());
+                                map.insert(key.token_value.clone(), value);
                             }
                         }
                         _ => {}
@@ -261,7 +266,7 @@ impl PaxManifest {
 
     fn pull_settings_with_selector(
         settings: &Option<Vec<SettingsBlockElement>>,
-        selector: String,
+        selector: &str,
     ) -> Option<Vec<SettingElement>> {
         settings.as_ref().and_then(|val| {
             let mut merged_setting = Vec::new();
@@ -279,19 +284,50 @@ impl PaxManifest {
         })
     }
 
+    /// Whether a node with settings `inline_settings` is matched by `selector`, e.g.
+    /// `.card-title` by nodes with `class=card-title` and `#header` by the node with `id=header`
+    pub fn selector_matches(inline_settings: &Option<Vec<SettingElement>>, selector: &str) -> bool {
+        if let Some(class) = selector.strip_prefix('.') {
+            Self::pull_matched_identifiers_from_inline(inline_settings, "class".to_string())
+                .iter()
+                .any(|c| c == class)
+        } else if let Some(id) = selector.strip_prefix('#') {
+            Self::pull_matched_identifiers_from_inline(inline_settings, "id".to_string())
+                .iter()
+                .any(|i| i == id)
+        } else {
+            false
+        }
+    }
+
     pub fn merge_inline_settings_with_settings_block(
         inline_settings: &Option<Vec<SettingElement>>,
         settings_block: &Option<Vec<SettingsBlockElement>>,
     ) -> Option<Vec<SettingElement>> {
+        Self::resolve_settings_with_provenance(inline_settings, settings_block)
+            .map(|settings| settings.into_iter().map(|(e, _)| e).collect())
+    }
+
+    /// The settings of a node after applying the selector blocks matching it, as used by the
+    /// runtime: inline settings first, then those of the id selector, then those of the class
+    /// selectors, each setting only taken from the first of these that has it (later class
+    /// selectors winning over earlier ones). Each setting comes with where it was taken from
+    pub fn resolve_settings_with_provenance(
+        inline_settings: &Option<Vec<SettingElement>>,
+        settings_block: &Option<Vec<SettingsBlockElement>>,
+    ) -> Option<Vec<(SettingElement, SettingSource)>> {
         // collect id settings
-        let ids = Self::pull_matched_identifiers_from_inline(&inline_settings, "id".to_string());
+        let ids = Self::pull_matched_identifiers_from_inline(inline_settings, "id".to_string());
 
         let mut id_settings = Vec::new();
         if ids.len() == 1 {
-            if let Some(settings) =
-                Self::pull_settings_with_selector(&settings_block, format!("#{}", ids[0]))
-            {
-                id_settings.extend(settings.clone());
+            let selector = format!("#{}", ids[0]);
+            if let Some(settings) = Self::pull_settings_with_selector(settings_block, &selector) {
+                id_settings.extend(
+                    settings
+                        .into_iter()
+                        .map(|e| (e, SettingSource::Selector(selector.clone()))),
+                );
             }
         } else if ids.len() > 1 {
             panic!("Specified more than one id inline!");
@@ -299,29 +335,26 @@ impl PaxManifest {
 
         // collect all class settings
         let classes =
-            Self::pull_matched_identifiers_from_inline(&inline_settings, "class".to_string());
+            Self::pull_matched_identifiers_from_inline(inline_settings, "class".to_string());
 
         let mut class_settings = Vec::new();
         for class in classes {
-            if let Some(settings) =
-                Self::pull_settings_with_selector(&settings_block, format!(".{}", class))
-            {
-                class_settings.extend(settings.clone());
+            let selector = format!(".{}", class);
+            if let Some(settings) = Self::pull_settings_with_selector(settings_block, &selector) {
+                class_settings.extend(
+                    settings
+                        .into_iter()
+                        .map(|e| (e, SettingSource::Selector(selector.clone()))),
+                );
             }
         }
 
         let mut map = BTreeMap::new();
 
         // Iterate in reverse order of priority (class, then id, then inline)
-        for e in class_settings.into_iter() {
+        for (e, source) in class_settings.into_iter().chain(id_settings) {
             if let SettingElement::Setting(key, _) = e.clone() {
-                map.insert(key, e);
-            }
-        }
-
-        for e in id_settings.into_iter() {
-            if let SettingElement::Setting(key, _) = e.clone() {
-                map.insert(key, e);
+                map.insert(key, (e, source));
             }
         }
 
@@ -332,20 +365,24 @@ impl PaxManifest {
                     map.remove(&key);
                 }
             }
-            let unique_setting_block_settings: Vec<SettingElement> =
-                map.values().cloned().collect();
-            merged.extend(inline);
+            let unique_setting_block_settings: Vec<_> = map.into_values().collect();
+            merged.extend(inline.into_iter().map(|e| (e, SettingSource::Inline)));
             merged.extend(unique_setting_block_settings);
         }
 
-        if merged.len() > 0 {
-            Some(merged)
-        } else {
-            None
-        }
+        (!merged.is_empty()).then_some(merged)
     }
 }
 
+/// Where a setting of a node comes from, see `PaxManifest::resolve_settings_with_provenance`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum SettingSource {
+    /// Set on the node itself
+    Inline,
+    /// Set in the selector block with this selector, e.g. `.card-title`
+    Selector(String),
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct CommonProperty {
     name: String,
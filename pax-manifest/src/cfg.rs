@@ -0,0 +1,272 @@
+//! # Conditional template nodes
+//!
+//! A template node with a `cfg` attribute is only part of builds whose target and features satisfy
+//! the condition, so that one `.pax` file can hold platform-specific or per-variant layouts:
+//!
+//! ```pax
+//! <WebNav cfg="target == web"/>
+//! <NativeNav cfg="target != web"/>
+//! <Upsell cfg="!premium && (target == ios || target == macos)"/>
+//! ```
+//!
+//! `target == <target>` and `target != <target>` test the platform the build is for, one of
+//! [`CFG_TARGETS`], and any other name tests whether the app crate's cargo feature of that name is
+//! enabled.  Conditions combine with `!`, `&&`, `||` and parentheses.
+
+use std::collections::BTreeSet;
+use std::fmt::Display;
+
+use pax_message::serde::{Deserialize, Serialize};
+
+/// Names of the targets `cfg` conditions can test for, as passed to `pax-cli build --target`
+pub const CFG_TARGETS: [&str; 3] = ["web", "macos", "ios"];
+
+/// The target and enabled features of a build, which `cfg` conditions are evaluated against
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(crate = "pax_message::serde")]
+pub struct BuildCfg {
+    /// One of [`CFG_TARGETS`]
+    pub target: String,
+    pub features: BTreeSet<String>,
+}
+
+/// A parsed `cfg` condition
+#[derive(Debug, Clone, PartialEq)]
+pub enum CfgCondition {
+    /// `target == <target>`, or `target != <target>` if not `equal`
+    Target {
+        target: String,
+        equal: bool,
+    },
+    /// Whether the cargo feature of this name is enabled
+    Feature(String),
+    Not(Box<CfgCondition>),
+    All(Vec<CfgCondition>),
+    Any(Vec<CfgCondition>),
+}
+
+impl CfgCondition {
+    pub fn parse(condition: &str) -> Result<Self, String> {
+        let tokens = tokenize(condition)?;
+        let mut parser = Parser { tokens, next: 0 };
+        let parsed = parser.parse_any()?;
+        match parser.tokens.get(parser.next) {
+            None => Ok(parsed),
+            Some(token) => Err(format!(
+                "Unexpected `{}` in cfg condition `{}`",
+                token, condition
+            )),
+        }
+    }
+
+    pub fn evaluate(&self, cfg: &BuildCfg) -> bool {
+        match self {
+            CfgCondition::Target { target, equal } => (&cfg.target == target) == *equal,
+            CfgCondition::Feature(feature) => cfg.features.contains(feature),
+            CfgCondition::Not(condition) => !condition.evaluate(cfg),
+            CfgCondition::All(conditions) => conditions.iter().all(|c| c.evaluate(cfg)),
+            CfgCondition::Any(conditions) => conditions.iter().any(|c| c.evaluate(cfg)),
+        }
+    }
+
+    /// Names of the features the condition tests
+    pub fn features(&self) -> BTreeSet<&str> {
+        match self {
+            CfgCondition::Target { .. } => BTreeSet::new(),
+            CfgCondition::Feature(feature) => BTreeSet::from([feature.as_str()]),
+            CfgCondition::Not(condition) => condition.features(),
+            CfgCondition::All(conditions) | CfgCondition::Any(conditions) => {
+                conditions.iter().flat_map(|c| c.features()).collect()
+            }
+        }
+    }
+
+    fn fmt_operand(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CfgCondition::All(_) | CfgCondition::Any(_) => write!(f, "({})", self),
+            _ => write!(f, "{}", self),
+        }
+    }
+}
+
+impl Display for CfgCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CfgCondition::Target { target, equal } => {
+                write!(f, "target {} {}", if *equal { "==" } else { "!=" }, target)
+            }
+            CfgCondition::Feature(feature) => write!(f, "{}", feature),
+            CfgCondition::Not(condition) => {
+                write!(f, "!")?;
+                match **condition {
+                    CfgCondition::Target { .. } => write!(f, "({})", condition),
+                    _ => condition.fmt_operand(f),
+                }
+            }
+            CfgCondition::All(conditions) | CfgCondition::Any(conditions) => {
+                let separator = if matches!(self, CfgCondition::All(_)) {
+                    " && "
+                } else {
+                    " || "
+                };
+                for (i, condition) in conditions.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "{}", separator)?;
+                    }
+                    condition.fmt_operand(f)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum CfgToken {
+    Name(String),
+    Equal,
+    NotEqual,
+    Not,
+    And,
+    Or,
+    Open,
+    Close,
+}
+
+impl Display for CfgToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CfgToken::Name(name) => write!(f, "{}", name),
+            CfgToken::Equal => write!(f, "=="),
+            CfgToken::NotEqual => write!(f, "!="),
+            CfgToken::Not => write!(f, "!"),
+            CfgToken::And => write!(f, "&&"),
+            CfgToken::Or => write!(f, "||"),
+            CfgToken::Open => write!(f, "("),
+            CfgToken::Close => write!(f, ")"),
+        }
+    }
+}
+
+fn tokenize(condition: &str) -> Result<Vec<CfgToken>, String> {
+    let mut tokens = vec![];
+    let mut chars = condition.chars().peekable();
+    while let Some(c) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => CfgToken::Open,
+            ')' => CfgToken::Close,
+            '=' if chars.next_if_eq(&'=').is_some() => CfgToken::Equal,
+            '!' if chars.next_if_eq(&'=').is_some() => CfgToken::NotEqual,
+            '!' => CfgToken::Not,
+            '&' if chars.next_if_eq(&'&').is_some() => CfgToken::And,
+            '|' if chars.next_if_eq(&'|').is_some() => CfgToken::Or,
+            '"' | '\'' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some(q) if q == c => break,
+                        Some(ch) => name.push(ch),
+                        None => {
+                            return Err(format!("Unclosed quote in cfg condition `{}`", condition))
+                        }
+                    }
+                }
+                CfgToken::Name(name)
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '-' => {
+                let mut name = c.to_string();
+                while let Some(ch) =
+                    chars.next_if(|ch| ch.is_alphanumeric() || *ch == '_' || *ch == '-')
+                {
+                    name.push(ch);
+                }
+                CfgToken::Name(name)
+            }
+            c => {
+                return Err(format!(
+                    "Unexpected `{}` in cfg condition `{}`",
+                    c, condition
+                ))
+            }
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<CfgToken>,
+    next: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&CfgToken> {
+        self.tokens.get(self.next)
+    }
+
+    fn advance(&mut self) -> Option<CfgToken> {
+        let token = self.tokens.get(self.next).cloned();
+        self.next += 1;
+        token
+    }
+
+    fn parse_any(&mut self) -> Result<CfgCondition, String> {
+        let mut conditions = vec![self.parse_all()?];
+        while self.peek() == Some(&CfgToken::Or) {
+            self.advance();
+            conditions.push(self.parse_all()?);
+        }
+        Ok(match conditions.len() {
+            1 => conditions.remove(0),
+            _ => CfgCondition::Any(conditions),
+        })
+    }
+
+    fn parse_all(&mut self) -> Result<CfgCondition, String> {
+        let mut conditions = vec![self.parse_unary()?];
+        while self.peek() == Some(&CfgToken::And) {
+            self.advance();
+            conditions.push(self.parse_unary()?);
+        }
+        Ok(match conditions.len() {
+            1 => conditions.remove(0),
+            _ => CfgCondition::All(conditions),
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<CfgCondition, String> {
+        match self.advance() {
+            Some(CfgToken::Not) => Ok(CfgCondition::Not(Box::new(self.parse_unary()?))),
+            Some(CfgToken::Open) => {
+                let condition = self.parse_any()?;
+                match self.advance() {
+                    Some(CfgToken::Close) => Ok(condition),
+                    _ => Err("Expected `)` in cfg condition".to_string()),
+                }
+            }
+            Some(CfgToken::Name(name)) if name == "target" => {
+                let equal = match self.advance() {
+                    Some(CfgToken::Equal) => true,
+                    Some(CfgToken::NotEqual) => false,
+                    _ => return Err("Expected `==` or `!=` after `target`".to_string()),
+                };
+                let Some(CfgToken::Name(target)) = self.advance() else {
+                    return Err("Expected a target after `target ==`".to_string());
+                };
+                let target = target.to_lowercase();
+                if !CFG_TARGETS.contains(&target.as_str()) {
+                    return Err(format!(
+                        "Unknown target `{}` in cfg condition, expected one of {}",
+                        target,
+                        CFG_TARGETS.join(", ")
+                    ));
+                }
+                Ok(CfgCondition::Target { target, equal })
+            }
+            Some(CfgToken::Name(feature)) => Ok(CfgCondition::Feature(feature)),
+            Some(token) => Err(format!("Unexpected `{}` in cfg condition", token)),
+            None => Err("Incomplete cfg condition".to_string()),
+        }
+    }
+}
@@ -2,3 +2,23 @@ pub use pax_runtime_api::constants::*;
 pub const CARTRIDGE_PARTIAL_STRUCT_ID: &'static str = "Cartridge";
 pub const DEFINITION_TO_INSTANCE_TRAVERSER_PARTIAL_STRUCT_ID: &'static str =
     "DefinitionToInstanceTraverser";
+/// Roles of the manifests printed by the parser binary, see [`crate::TaggedManifest`]
+pub const USERLAND_MANIFEST_ROLE: &'static str = "userland";
+pub const DESIGNER_MANIFEST_ROLE: &'static str = "designer";
+/// Attribute naming a template node, so that components extending the template with
+/// `@extends(..)` can replace it with `@override(label="..")`
+pub const TEMPLATE_LABEL_ATTRIBUTE: &'static str = "template_label";
+/// Attribute carrying the name a designer gave a template node, e.g. "Header".  Moved into
+/// [`crate::TemplateNodeDefinition::label`] when parsing, so it never reaches codegen
+pub const DESIGNER_LABEL_ATTRIBUTE: &'static str = "designer_label";
+/// Attribute conditioning a template node on the target and features of the build, see
+/// [`crate::cfg`]
+pub const CFG_ATTRIBUTE: &'static str = "cfg";
+/// Symbol under which settings reference design tokens, see [`crate::ValueDefinition::Token`]
+pub const TOKENS_SYMBOL: &'static str = "$tokens";
+/// Component the cartridge constructs for every [`crate::PaxType::BlankComponent`], e.g. wrappers
+/// the designer inserts.  Added to the manifest of every main component
+pub const BLANK_COMPONENT: &'static str = "BlankComponent";
+/// Component rendered in place of nodes of unresolved types, see [`crate::PaxType::Unresolved`].
+/// Added to the manifest of every main component
+pub const UNRESOLVED_PLACEHOLDER: &'static str = "UnresolvedPlaceholder";
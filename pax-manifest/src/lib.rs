@@ -11,12 +11,15 @@ pub use pax_runtime_api;
 use pax_runtime_api::{CoercionRules, HelperFunctions, Interpolatable, PaxValue, ToPaxValue};
 pub mod parsing;
 pub mod server;
+mod text_diff;
 
 #[cfg(feature = "parsing")]
 pub mod utils;
 
 pub mod cartridge_generation;
+pub mod cfg;
 pub mod constants;
+pub mod validation;
 
 /// Definition container for an entire Pax cartridge
 #[serde_with::serde_as]
@@ -34,6 +37,76 @@ pub struct PaxManifest {
     /// Compiler metadata: the import prefix for the engine module, `pax_kit::pax_engine` by default
     /// but parameterizable for integrating with pax_engine directly, e.g. pax_std and pax_designer
     pub engine_import_path: String,
+    /// Compiler metadata: the build-time defines (`--define key=value`) referenced by templates
+    /// as `$defines.key`, with their values
+    #[serde(default)]
+    pub defines: BTreeMap<String, PaxValue>,
+    /// Compiler metadata: the design tokens listed under `[package.metadata.pax.tokens]`, e.g. named
+    /// colors and spacings, which settings reference as `{$tokens.name}`, see [`ValueDefinition::Token`]
+    #[serde(default)]
+    pub tokens: BTreeMap<String, PaxValue>,
+    /// Compiler metadata: the target and features of the build, which the `cfg` conditions of
+    /// template nodes are evaluated against at runtime.  `None` if nodes aren't filtered at runtime
+    #[serde(default)]
+    pub build_cfg: Option<cfg::BuildCfg>,
+}
+
+/// A manifest labeled with its role, e.g. `userland` or `designer`.  The parser binary prints a
+/// list of these, so that consumers can select manifests by role rather than by position.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "pax_message::serde")]
+pub struct TaggedManifest<M = PaxManifest> {
+    pub role: String,
+    pub manifest: M,
+}
+
+impl<M> TaggedManifest<M> {
+    pub fn new(role: &str, manifest: M) -> Self {
+        Self {
+            role: role.to_string(),
+            manifest,
+        }
+    }
+}
+
+/// The manifests of a parser run, selected by role from a list of [`TaggedManifest`]s.
+/// `designer` is `None` when the project was parsed without the designer.
+pub struct ManifestSet<M = PaxManifest> {
+    pub userland: M,
+    pub designer: Option<M>,
+    /// Roles this version doesn't know about, which were skipped
+    pub unknown_roles: Vec<String>,
+}
+
+impl<M> ManifestSet<M> {
+    /// Errors if the userland role is missing or if any role appears more than once
+    pub fn from_tagged(tagged: Vec<TaggedManifest<M>>) -> Result<Self, String> {
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut userland = None;
+        let mut designer = None;
+        let mut unknown_roles = vec![];
+        for TaggedManifest { role, manifest } in tagged {
+            if !seen.insert(role.clone()) {
+                return Err(format!("manifest role `{}` appears more than once", role));
+            }
+            match role.as_str() {
+                constants::USERLAND_MANIFEST_ROLE => userland = Some(manifest),
+                constants::DESIGNER_MANIFEST_ROLE => designer = Some(manifest),
+                _ => unknown_roles.push(role),
+            }
+        }
+        let userland = userland.ok_or_else(|| {
+            format!(
+                "no manifest with role `{}`",
+                constants::USERLAND_MANIFEST_ROLE
+            )
+        })?;
+        Ok(Self {
+            userland,
+            designer,
+            unknown_roles,
+        })
+    }
 }
 
 impl PaxManifest {
@@ -56,6 +129,28 @@ impl PaxManifest {
             .get(&uni.template_node_id)
     }
 
+    /// Whether nodes of `type_id` instantiate a component missing from this manifest, either
+    /// because their tag was never resolved (see [`PaxType::Unresolved`]) or because the
+    /// component has since been removed
+    pub fn is_unresolved_type(&self, type_id: &TypeId) -> bool {
+        match type_id.get_pax_type() {
+            PaxType::If | PaxType::Slot | PaxType::Repeat | PaxType::Comment => false,
+            PaxType::Unresolved { .. } => true,
+            _ => !self.components.contains_key(type_id),
+        }
+    }
+
+    /// The component rendered in place of nodes of unresolved types, if in this manifest
+    pub fn get_unresolved_placeholder_type_id(&self) -> Option<TypeId> {
+        self.components
+            .keys()
+            .find(|type_id| {
+                type_id.get_pascal_identifier().as_deref()
+                    == Some(constants::UNRESOLVED_PLACEHOLDER)
+            })
+            .cloned()
+    }
+
     pub fn get_all_component_properties(&self, type_id: &TypeId) -> Vec<PropertyDefinition> {
         if let None = self.components.get(type_id) {
             return Vec::default();
@@ -114,6 +209,33 @@ impl PaxManifest {
         self.components.extend(other.components.clone());
         self.type_table.extend(other.type_table.clone());
         self.assets_dirs.extend(other.assets_dirs.clone());
+        self.defines.extend(other.defines.clone());
+        self.tokens.extend(other.tokens.clone());
+    }
+
+    /// `value` with the design tokens it references, directly or within a block, replaced by their
+    /// values from [`PaxManifest::tokens`].  References to unknown tokens become `Undefined`
+    pub fn resolve_tokens(&self, value: &ValueDefinition) -> ValueDefinition {
+        match value {
+            ValueDefinition::Token(name) => match self.tokens.get(name) {
+                Some(token) => ValueDefinition::LiteralValue(token.clone()),
+                None => ValueDefinition::Undefined,
+            },
+            ValueDefinition::Block(block) => ValueDefinition::Block(LiteralBlockDefinition {
+                explicit_type_pascal_identifier: block.explicit_type_pascal_identifier.clone(),
+                elements: block
+                    .elements
+                    .iter()
+                    .map(|element| match element {
+                        SettingElement::Setting(key, value) => {
+                            SettingElement::Setting(key.clone(), self.resolve_tokens(value))
+                        }
+                        other => other.clone(),
+                    })
+                    .collect(),
+            }),
+            other => other.clone(),
+        }
     }
 }
 
@@ -181,12 +303,38 @@ pub struct ComponentDefinition {
     pub primitive_instance_import_path: Option<String>,
     pub template: Option<ComponentTemplate>,
     pub settings: Option<Vec<SettingsBlockElement>>,
+
+    /// Set if this component's template extends the template of another component with
+    /// `@extends(..)`.  `template` and `settings` are then flattened: they contain the nodes and
+    /// settings of the base along with the overrides of this component.
+    #[serde(default)]
+    pub extends: Option<TemplateExtension>,
 }
 
 impl ComponentDefinition {
     pub fn get_property_definitions<'a>(&self, tt: &'a TypeTable) -> &'a Vec<PropertyDefinition> {
         &tt.get(&self.type_id).unwrap().property_definitions
     }
+
+    /// Whether template node `id` was copied from the template this component extends,
+    /// rather than declared or overridden by the component itself
+    pub fn is_inherited_node(&self, id: &TemplateNodeId) -> bool {
+        self.extends
+            .as_ref()
+            .is_some_and(|extension| extension.inherited_nodes.contains(id))
+    }
+}
+
+/// What's needed to write a component that extends another one's template back to its
+/// source, since its template and settings are flattened into the component definition
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "pax_message::serde")]
+pub struct TemplateExtension {
+    pub base: TypeId,
+    /// Nodes of the flattened template that were copied from the base
+    pub inherited_nodes: HashSet<TemplateNodeId>,
+    /// Settings blocks declared by the extending component, layered over the ones of the base
+    pub own_settings: Vec<SettingsBlockElement>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -244,6 +392,11 @@ pub enum PaxType {
     BlankComponent {
         pascal_identifier: String,
     },
+    /// A tag naming a component that doesn't exist, e.g. after the component was renamed in Rust.
+    /// The node is kept, so that its template can still be edited and written back unchanged
+    Unresolved {
+        pascal_identifier: String,
+    },
     Primitive {
         pascal_identifier: String,
     },
@@ -281,6 +434,10 @@ impl CoercionRules for PaxType {
                     let pascal_identifier = String::try_coerce(args[0].clone())?;
                     Ok(PaxType::BlankComponent { pascal_identifier })
                 }
+                "Unresolved" => {
+                    let pascal_identifier = String::try_coerce(args[0].clone())?;
+                    Ok(PaxType::Unresolved { pascal_identifier })
+                }
                 "Primitive" => {
                     let pascal_identifier = String::try_coerce(args[0].clone())?;
                     Ok(PaxType::Primitive { pascal_identifier })
@@ -330,6 +487,11 @@ impl ToPaxValue for PaxType {
                 "BlankComponent".to_string(),
                 vec![pascal_identifier.to_pax_value()],
             ),
+            PaxType::Unresolved { pascal_identifier } => PaxValue::Enum(
+                "PaxType".to_string(),
+                "Unresolved".to_string(),
+                vec![pascal_identifier.to_pax_value()],
+            ),
             PaxType::Primitive { pascal_identifier } => PaxValue::Enum(
                 "PaxType".to_string(),
                 "Primitive".to_string(),
@@ -381,6 +543,7 @@ impl Display for PaxType {
             PaxType::Repeat => write!(f, "Repeat"),
             PaxType::Comment => write!(f, "Comment"),
             PaxType::BlankComponent { pascal_identifier } => write!(f, "{}", pascal_identifier),
+            PaxType::Unresolved { pascal_identifier } => write!(f, "{}", pascal_identifier),
             PaxType::Primitive { pascal_identifier } => write!(f, "{}", pascal_identifier),
             PaxType::Singleton { pascal_identifier } => write!(f, "{}", pascal_identifier),
             PaxType::Range { identifier } => write!(f, "std::ops::Range<{}>", identifier),
@@ -539,6 +702,20 @@ impl TypeId {
         }
     }
 
+    /// Build a TypeId for a tag naming a component that doesn't exist, see [`PaxType::Unresolved`]
+    pub fn build_unresolved(pascal_identifier: &str) -> Self {
+        let _id = format!("unresolved::{}", pascal_identifier);
+        TypeId {
+            pax_type: PaxType::Unresolved {
+                pascal_identifier: pascal_identifier.to_owned(),
+            },
+            import_path: None,
+            is_intoable_downstream_type: false,
+            _type_id: _id.clone(),
+            _type_id_escaped: escape_identifier(_id),
+        }
+    }
+
     /// Build a TypeId for a most types, like `Stacker` or `SpecialComponent`
     pub fn build_singleton(import_path: &str, pascal_identifier: Option<&str>) -> Self {
         let pascal_identifier = if let Some(p) = pascal_identifier {
@@ -641,7 +818,8 @@ impl TypeId {
         match &self.pax_type {
             PaxType::Primitive { pascal_identifier }
             | PaxType::Singleton { pascal_identifier }
-            | PaxType::BlankComponent { pascal_identifier } => Some(pascal_identifier.clone()),
+            | PaxType::BlankComponent { pascal_identifier }
+            | PaxType::Unresolved { pascal_identifier } => Some(pascal_identifier.clone()),
             PaxType::If | PaxType::Slot | PaxType::Repeat | PaxType::Comment => {
                 Some(self.pax_type.to_string())
             }
@@ -667,6 +845,10 @@ impl TypeId {
             .replace(".", "_")
     }
 
+    pub fn is_unresolved(&self) -> bool {
+        matches!(self.pax_type, PaxType::Unresolved { .. })
+    }
+
     pub fn is_blank_component(&self) -> bool {
         if let PaxType::BlankComponent { .. } = self.pax_type {
             true
@@ -1045,7 +1227,7 @@ impl ComponentTemplate {
     pub fn remove_node(&mut self, id: TemplateNodeId) -> TemplateNodeDefinition {
         if let Some(tnd) = self.nodes.get(&id) {
             let node = tnd.clone();
-            let subtree = self.get_subtree(&id);
+            let subtree = self.get_descendants(&id);
             for node in subtree {
                 self.nodes.remove(&node);
                 self.children.remove(&node);
@@ -1065,12 +1247,13 @@ impl ComponentTemplate {
         }
     }
 
-    fn get_subtree(&self, id: &TemplateNodeId) -> Vec<TemplateNodeId> {
+    /// All nodes below `id` in depth-first order, excluding `id` itself
+    pub fn get_descendants(&self, id: &TemplateNodeId) -> Vec<TemplateNodeId> {
         let mut ret = vec![];
         if let Some(children) = self.children.get(&id) {
             for child in children {
                 ret.push(child.clone());
-                ret.extend(self.get_subtree(child));
+                ret.extend(self.get_descendants(child));
             }
         }
         ret
@@ -1110,6 +1293,12 @@ impl ComponentTemplate {
         }
     }
 
+    pub fn update_node_label(&mut self, id: &TemplateNodeId, label: Option<String>) {
+        if let Some(node) = self.nodes.get_mut(id) {
+            node.label = label;
+        }
+    }
+
     pub fn update_node_properties(
         &mut self,
         id: &TemplateNodeId,
@@ -1200,6 +1389,30 @@ impl ComponentTemplate {
         None
     }
 
+    /// The node with a `template_label` attribute of `label`, see
+    /// [`constants::TEMPLATE_LABEL_ATTRIBUTE`]
+    pub fn find_node_with_label(&self, label: &str) -> Option<TemplateNodeId> {
+        self.nodes
+            .iter()
+            .find(|(_, node)| node.get_template_label().as_deref() == Some(label))
+            .map(|(id, _)| id.clone())
+    }
+
+    /// Copy of this template used as the starting point of the template of
+    /// `containing_component`, which extends it.  Node ids are kept, so nodes
+    /// added afterward have ids of at least `self.get_next_id()`.
+    pub fn extended_by(
+        &self,
+        containing_component: TypeId,
+        template_source_file_path: Option<String>,
+    ) -> Self {
+        Self {
+            containing_component,
+            template_source_file_path,
+            ..self.clone()
+        }
+    }
+
     pub fn get_nodes(&self) -> Vec<&TemplateNodeDefinition> {
         self.nodes.values().collect()
     }
@@ -1309,9 +1522,58 @@ pub struct TemplateNodeDefinition {
     pub settings: Option<Vec<SettingElement>>,
     /// IFF this TND is a comment node: raw comment string
     pub raw_comment_string: Option<String>,
+    /// Name given to this node by a designer, e.g. "Header", shown instead of its type when
+    /// authoring.  Written back as a [`constants::DESIGNER_LABEL_ATTRIBUTE`] attribute, and
+    /// ignored by codegen
+    #[serde(default)]
+    pub label: Option<String>,
 }
 
 impl TemplateNodeDefinition {
+    /// Raw `cfg` condition of this node, see [`constants::CFG_ATTRIBUTE`]
+    pub fn get_cfg(&self) -> Option<String> {
+        self.settings
+            .iter()
+            .flatten()
+            .find_map(|setting| match setting {
+                SettingElement::Setting(
+                    key,
+                    ValueDefinition::LiteralValue(PaxValue::String(cfg)),
+                ) if key.token_value == constants::CFG_ATTRIBUTE => Some(cfg.clone()),
+                _ => None,
+            })
+    }
+
+    /// Whether this node is part of a build with `build_cfg`, per its `cfg` condition.  Nodes
+    /// with a malformed condition are left out
+    pub fn is_enabled_for(&self, build_cfg: &cfg::BuildCfg) -> bool {
+        self.get_cfg().map_or(true, |condition| {
+            cfg::CfgCondition::parse(&condition).is_ok_and(|c| c.evaluate(build_cfg))
+        })
+    }
+
+    /// Value of the `template_label` attribute of this node, see
+    /// [`constants::TEMPLATE_LABEL_ATTRIBUTE`]
+    pub fn get_template_label(&self) -> Option<String> {
+        self.settings
+            .iter()
+            .flatten()
+            .find_map(|setting| match setting {
+                SettingElement::Setting(key, value)
+                    if key.token_value == constants::TEMPLATE_LABEL_ATTRIBUTE =>
+                {
+                    match value {
+                        ValueDefinition::LiteralValue(PaxValue::String(label)) => {
+                            Some(label.clone())
+                        }
+                        ValueDefinition::Identifier(identifier) => Some(identifier.name.clone()),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            })
+    }
+
     pub fn get_node_type(&self) -> NodeType {
         if let Some(cfsd) = &self.control_flow_settings {
             NodeType::ControlFlow(Box::new(cfsd.clone()))
@@ -1415,6 +1677,10 @@ pub struct PropertyDefinitionFlags {
 
     /// Describes whether this property is an enum variant property
     pub is_enum: bool,
+
+    /// Set by `#[pax_allow(unused_property)]`, for properties only set from Rust,
+    /// which would otherwise be reported as unused
+    pub allow_unused: bool,
 }
 
 /// Describes static metadata surrounding a property, for example
@@ -1496,6 +1762,10 @@ pub enum ValueDefinition {
     /// (Expression contents, vtable id binding)
     DoubleBinding(PaxIdentifier),
     EventBindingTarget(PaxIdentifier),
+    /// A design token, written `{$tokens.name}`, standing for the value of `name` in
+    /// [`PaxManifest::tokens`].  Resolved by [`PaxManifest::resolve_tokens`] when the runtime
+    /// instantiates the node, so that changing the token changes every setting referencing it
+    Token(String),
 }
 
 /// Container for holding metadata about original Location in Pax Template
@@ -1,17 +1,18 @@
+use crate::constants::TOKENS_SYMBOL;
 use crate::*;
-use pax_lang::interpreter::parse_pax_expression_from_pair;
+use pax_lang::interpreter::{parse_pax_expression_from_pair, PaxAccessor};
 use pax_lang::{from_pax, parse_pax_expression, parse_pax_str, Pair, Pairs, Rule, Span};
-use pax_runtime_api::{Color, Fill, Size, Stroke};
+use pax_runtime_api::{Color, Fill, PaxValue, Size, Stroke};
 use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 
+/// Errors if an `@override` names a label that no node of the extended template has
 pub fn parse_template_from_component_definition_string(
     ctx: &mut TemplateNodeParseContext,
     pax: &str,
     pax_component_definition: Pair<Rule>,
-) {
-    pax_component_definition
-        .into_inner()
-        .for_each(|pair| match pair.as_rule() {
+) -> Result<(), ParsingError> {
+    for pair in pax_component_definition.into_inner() {
+        match pair.as_rule() {
             Rule::root_tag_pair => {
                 recurse_visit_tag_pairs_for_template(
                     ctx,
@@ -20,8 +21,83 @@ pub fn parse_template_from_component_definition_string(
                     TreeLocation::Root,
                 );
             }
+            Rule::override_tag_pair => {
+                //override_tag_pair => override_directive, any_tag_pair
+                let mut override_tag_pair = pair.into_inner();
+                let override_directive = override_tag_pair.next().unwrap();
+                let span = override_directive.as_span();
+                let label = parse_override_label(override_directive);
+                let Some(overridden) = ctx.template.find_node_with_label(&label) else {
+                    let start = span.start_pos().line_col();
+                    return Err(ParsingError {
+                        error_name: "@override".to_string(),
+                        error_message: format!(
+                            "{}:{}:{}: no node labeled \"{}\" in the extended template",
+                            ctx.template.get_file_path().unwrap_or_default(),
+                            start.0,
+                            start.1,
+                            label
+                        ),
+                        matched_string: span.as_str().to_string(),
+                        start,
+                        end: span.end_pos().line_col(),
+                    });
+                };
+                let location = ctx.template.get_location(&overridden).unwrap();
+                ctx.template.remove_node(overridden);
+                recurse_visit_tag_pairs_for_template(
+                    ctx,
+                    override_tag_pair.next().unwrap(),
+                    pax,
+                    TreeLocation::Root,
+                );
+                let id = ctx.template.get_root().pop().unwrap();
+                ctx.template.move_node(&id, location);
+
+                // keep the label, so that the override can itself be overridden
+                let mut node = ctx.template.get_node(&id).unwrap().clone();
+                if let (None, Some(settings)) = (node.get_template_label(), &mut node.settings) {
+                    settings.push(SettingElement::Setting(
+                        Token::new_without_location(
+                            constants::TEMPLATE_LABEL_ATTRIBUTE.to_string(),
+                        ),
+                        ValueDefinition::LiteralValue(PaxValue::String(label)),
+                    ));
+                    ctx.template.set_node(id, node);
+                }
+            }
             _ => {}
-        });
+        }
+    }
+    Ok(())
+}
+
+/// The pascal identifier of the component named by the `@extends(..)` declaration of a
+/// component definition, if any
+pub fn parse_extends_from_component_definition_string(
+    pax_component_definition: Pair<Rule>,
+) -> Option<String> {
+    pax_component_definition
+        .into_inner()
+        .find(|pair| pair.as_rule() == Rule::extends_declaration)
+        .map(|extends_declaration| {
+            extends_declaration
+                .into_inner()
+                .next()
+                .unwrap()
+                .as_str()
+                .to_string()
+        })
+}
+
+fn parse_override_label(override_directive: Pair<Rule>) -> String {
+    //override_directive => string => inner
+    let string = override_directive.into_inner().next().unwrap();
+    string
+        .into_inner()
+        .next()
+        .map(|inner| inner.as_str().to_string())
+        .unwrap_or_default()
 }
 
 pub struct TemplateNodeParseContext {
@@ -47,15 +123,17 @@ fn recurse_visit_tag_pairs_for_template(
                 .into_inner();
             let pascal_identifier = open_tag.next().unwrap().as_str();
 
+            let mut settings = parse_inline_attribute_from_final_pairs_of_tag(open_tag);
+            let type_id = match ctx.pascal_identifier_to_type_id_map.get(pascal_identifier) {
+                Some(type_id) => {
+                    TypeId::build_singleton(&type_id.to_string(), Some(pascal_identifier))
+                }
+                None => TypeId::build_unresolved(pascal_identifier),
+            };
             let template_node = TemplateNodeDefinition {
-                type_id: TypeId::build_singleton(
-                    &ctx.pascal_identifier_to_type_id_map
-                        .get(pascal_identifier)
-                        .expect(&format!("Template key not found {}", &pascal_identifier))
-                        .to_string(),
-                    Some(&pascal_identifier.to_string()),
-                ),
-                settings: parse_inline_attribute_from_final_pairs_of_tag(open_tag),
+                type_id,
+                label: take_designer_label(&mut settings),
+                settings,
                 raw_comment_string: None,
                 control_flow_settings: None,
             };
@@ -88,16 +166,16 @@ fn recurse_visit_tag_pairs_for_template(
             let mut tag_pairs = any_tag_pair.into_inner();
             let pascal_identifier = tag_pairs.next().unwrap().as_str();
 
-            let type_id = if let Some(type_id) =
-                ctx.pascal_identifier_to_type_id_map.get(pascal_identifier)
-            {
-                type_id.clone()
-            } else {
-                TypeId::build_blank_component(pascal_identifier)
+            // tags not naming a known component are kept, see `PaxType::Unresolved`
+            let type_id = match ctx.pascal_identifier_to_type_id_map.get(pascal_identifier) {
+                Some(type_id) => type_id.clone(),
+                None => TypeId::build_unresolved(pascal_identifier),
             };
+            let mut settings = parse_inline_attribute_from_final_pairs_of_tag(tag_pairs);
             let template_node = TemplateNodeDefinition {
                 type_id,
-                settings: parse_inline_attribute_from_final_pairs_of_tag(tag_pairs),
+                label: take_designer_label(&mut settings),
+                settings,
                 raw_comment_string: None,
                 control_flow_settings: None,
             };
@@ -129,6 +207,7 @@ fn recurse_visit_tag_pairs_for_template(
                         type_id: TypeId::build_if(),
                         settings: None,
                         raw_comment_string: None,
+                        label: None,
                     };
 
                     let id = match location {
@@ -186,6 +265,7 @@ fn recurse_visit_tag_pairs_for_template(
                         control_flow_settings: Some(cfavd),
                         settings: None,
                         raw_comment_string: None,
+                        label: None,
                     };
 
                     let id = match location {
@@ -220,6 +300,7 @@ fn recurse_visit_tag_pairs_for_template(
                         type_id: TypeId::build_slot(),
                         settings: None,
                         raw_comment_string: None,
+                        label: None,
                     };
 
                     let _ = match location {
@@ -238,6 +319,7 @@ fn recurse_visit_tag_pairs_for_template(
                 type_id: TypeId::build_comment(),
                 settings: None,
                 raw_comment_string: Some(any_tag_pair.as_str().to_string()),
+                label: None,
             };
             let _ = match location {
                 TreeLocation::Root => ctx.template.add_root_node_back(template_node),
@@ -270,6 +352,26 @@ fn parse_event_id(event_id_full: Pair<Rule>) -> Token {
     event_id_token
 }
 
+/// Removes the [`constants::DESIGNER_LABEL_ATTRIBUTE`] attribute from `settings`, returning its
+/// value: the label is authoring metadata, not a property of the node
+fn take_designer_label(settings: &mut Option<Vec<SettingElement>>) -> Option<String> {
+    let settings = settings.as_mut()?;
+    let index = settings.iter().position(|setting| {
+        matches!(setting, SettingElement::Setting(key, _)
+            if key.token_value == constants::DESIGNER_LABEL_ATTRIBUTE)
+    })?;
+    match settings.remove(index) {
+        SettingElement::Setting(_, ValueDefinition::LiteralValue(PaxValue::String(label))) => {
+            Some(label)
+        }
+        _ => panic!(
+            "{} must be a string, e.g. {}=\"Header\"",
+            constants::DESIGNER_LABEL_ATTRIBUTE,
+            constants::DESIGNER_LABEL_ATTRIBUTE
+        ),
+    }
+}
+
 fn parse_inline_attribute_from_final_pairs_of_tag(
     final_pairs_of_tag: Pairs<Rule>,
 ) -> Option<Vec<SettingElement>> {
@@ -356,7 +458,10 @@ pub fn parse_value_definition(value: Pair<Rule>) -> ValueDefinition {
         Rule::expression_body => {
             let expression =
                 parse_pax_expression_from_pair(value).expect("Unable to parse expression");
-            ValueDefinition::Expression(ExpressionInfo::new(expression))
+            match token_reference(&expression) {
+                Some(token) => ValueDefinition::Token(token),
+                None => ValueDefinition::Expression(ExpressionInfo::new(expression)),
+            }
         }
         Rule::identifier => {
             let identifier = PaxIdentifier::new(value.as_str());
@@ -371,6 +476,22 @@ pub fn parse_value_definition(value: Pair<Rule>) -> ValueDefinition {
     }
 }
 
+/// The name of the design token `expression` references, if it's exactly `$tokens.name`
+fn token_reference(expression: &PaxExpression) -> Option<String> {
+    let PaxExpression::Primary(primary) = expression else {
+        return None;
+    };
+    match primary.as_ref() {
+        PaxPrimary::Identifier(identifier, accessors) if identifier.name == TOKENS_SYMBOL => {
+            match accessors.as_slice() {
+                [PaxAccessor::Struct(name)] => Some(name.clone()),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
 fn derive_value_definition_from_literal_object_pair(
     literal_object: Pair<Rule>,
 ) -> LiteralBlockDefinition {
@@ -516,6 +637,58 @@ pub struct ParsingContext {
     pub type_table: TypeTable,
 
     pub assets_dirs: Vec<String>,
+
+    /// Components waiting for the component they extend to be parsed, used to
+    /// detect cycles in `@extends` chains
+    pub extends_chain: Vec<String>,
+}
+
+impl ParsingContext {
+    /// Marks `component` as waiting for the component it extends to be parsed.  Panics if it
+    /// already is, as the chain of `@extends` then loops back to it.
+    pub fn enter_extends(&mut self, component: &str) {
+        if let Some(start) = self.extends_chain.iter().position(|c| c == component) {
+            let mut cycle = self.extends_chain[start..].to_vec();
+            cycle.push(component.to_string());
+            panic!("Cyclic @extends: {}", cycle.join(" -> "));
+        }
+        self.extends_chain.push(component.to_string());
+    }
+
+    pub fn exit_extends(&mut self) {
+        self.extends_chain.pop();
+    }
+
+    /// The manifest of everything parsed so far.  The compiler metadata that isn't known at parse
+    /// time (defines, tokens and build cfg) is left empty for the compiler to fill in
+    pub fn into_manifest(self, engine_import_path: &str) -> PaxManifest {
+        PaxManifest {
+            components: self.component_definitions,
+            main_component_type_id: self.main_component_type_id,
+            type_table: self.type_table,
+            assets_dirs: self.assets_dirs,
+            engine_import_path: engine_import_path.to_string(),
+            defines: BTreeMap::new(),
+            tokens: BTreeMap::new(),
+            build_cfg: None,
+        }
+    }
+}
+
+/// Parses `R` and every component and type in its render tree into a manifest with
+/// `main_component_type_id` as its main component, as the parser binary does for the `#[main]`
+/// component of a project.  Lets a crate check its own templates without building the parser
+/// binary, e.g. from its tests
+pub fn parse_manifest<R: Reflectable>(
+    main_component_type_id: TypeId,
+    engine_import_path: &str,
+) -> PaxManifest {
+    let ctx = ParsingContext {
+        main_component_type_id,
+        ..Default::default()
+    };
+    let (ctx, _) = R::parse_to_manifest(ctx);
+    ctx.into_manifest(engine_import_path)
 }
 
 impl Default for ParsingContext {
@@ -528,6 +701,7 @@ impl Default for ParsingContext {
             type_table: get_primitive_type_table(),
             template_node_definitions: ComponentTemplate::default(),
             assets_dirs: vec![],
+            extends_chain: vec![],
         }
     }
 }
@@ -541,6 +715,12 @@ pub struct ParsingError {
     pub end: (usize, usize),
 }
 
+impl std::fmt::Display for ParsingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.error_name, self.error_message)
+    }
+}
+
 /// From a raw string of Pax representing a single component, parse a complete ComponentDefinition
 pub fn assemble_component_definition(
     mut ctx: ParsingContext,
@@ -551,17 +731,40 @@ pub fn assemble_component_definition(
     self_type_id: TypeId,
     component_source_file_path: &str,
 ) -> (ParsingContext, ComponentDefinition) {
-    let mut tpc = TemplateNodeParseContext {
-        pascal_identifier_to_type_id_map: template_map,
-        template: ComponentTemplate::new(
+    let ast = parse_pax_str(Rule::pax_component_definition, pax).expect("Unsuccessful parse");
+
+    // the extended component has been parsed before this one, see `ParsingContext::enter_extends`
+    let base = parse_extends_from_component_definition_string(ast.clone()).map(|base| {
+        template_map
+            .get(&base)
+            .and_then(|base_type_id| ctx.component_definitions.get(base_type_id))
+            .filter(|base_definition| base_definition.template.is_some())
+            .unwrap_or_else(|| {
+                panic!(
+                    "@extends({}): only components with a template can be extended",
+                    base
+                )
+            })
+            .clone()
+    });
+
+    let template = match &base {
+        Some(base) => base.template.as_ref().unwrap().extended_by(
+            self_type_id.clone(),
+            Some(component_source_file_path.to_owned()),
+        ),
+        None => ComponentTemplate::new(
             self_type_id.clone(),
             Some(component_source_file_path.to_owned()),
         ),
     };
+    let mut tpc = TemplateNodeParseContext {
+        pascal_identifier_to_type_id_map: template_map,
+        template,
+    };
 
-    let ast = parse_pax_str(Rule::pax_component_definition, pax).expect("Unsuccessful parse");
-
-    parse_template_from_component_definition_string(&mut tpc, pax, ast.clone());
+    parse_template_from_component_definition_string(&mut tpc, pax, ast.clone())
+        .unwrap_or_else(|e| panic!("{}", e));
     let modified_module_path = if module_path.starts_with("parser") {
         module_path.replacen("parser", "crate", 1)
     } else {
@@ -572,6 +775,10 @@ pub fn assemble_component_definition(
     ctx.template_node_definitions = tpc.template.clone();
 
     let settings = parse_settings_from_component_definition_string(ast);
+    let (settings, extends) = match base {
+        Some(base) => flatten_extension(base, &tpc.template, settings),
+        None => (settings, None),
+    };
 
     let new_def = ComponentDefinition {
         is_primitive: false,
@@ -582,11 +789,44 @@ pub fn assemble_component_definition(
         template: Some(tpc.template),
         settings: Some(settings),
         module_path: modified_module_path,
+        extends,
     };
 
     (ctx, new_def)
 }
 
+/// Settings of a component extending `base`, with the selector blocks of `base` followed by
+/// `own_settings`, which take precedence when merged into the nodes.  Handlers aren't
+/// inherited, since they refer to methods of the base.
+fn flatten_extension(
+    base: ComponentDefinition,
+    template: &ComponentTemplate,
+    own_settings: Vec<SettingsBlockElement>,
+) -> (Vec<SettingsBlockElement>, Option<TemplateExtension>) {
+    let base_next_id = base.template.as_ref().unwrap().get_next_id();
+    let inherited_nodes = template
+        .get_ids()
+        .into_iter()
+        .filter(|id| id.as_usize() < base_next_id)
+        .cloned()
+        .collect();
+    let mut settings: Vec<SettingsBlockElement> = base
+        .settings
+        .into_iter()
+        .flatten()
+        .filter(|element| matches!(element, SettingsBlockElement::SelectorBlock(..)))
+        .collect();
+    settings.extend(own_settings.iter().cloned());
+    (
+        settings,
+        Some(TemplateExtension {
+            base: base.type_id,
+            inherited_nodes,
+            own_settings,
+        }),
+    )
+}
+
 pub fn clean_module_path(module_path: &str) -> String {
     if module_path.starts_with("parser") {
         module_path.replacen("parser", "crate", 1)
@@ -611,16 +851,52 @@ pub fn assemble_struct_only_component_definition(
         primitive_instance_import_path: None,
         template: None,
         settings: None,
+        extends: None,
     };
     (ctx, new_def)
 }
 
+/// Resolves the instance path of a `#[primitive("...")]`, relative to the module of the struct
+/// declaring it (as returned by `clean_module_path`), into a path that resolves from the
+/// cartridge: `crate::`, `self::` and `super::` paths and bare names are taken like `use`
+/// paths of that module, so that primitives declared in dependencies don't need to spell out
+/// their crate name.  Other paths are kept as they are
+pub fn resolve_primitive_instance_path(module_path: &str, instance_path: &str) -> String {
+    let instance_path = instance_path.trim();
+    let mut module: Vec<&str> = module_path.split("::").collect();
+    let mut segments = instance_path.split("::").peekable();
+    match segments.peek() {
+        Some(&"crate") => {
+            segments.next();
+            module.truncate(1);
+        }
+        Some(&"self") => {
+            segments.next();
+        }
+        Some(&"super") => {
+            while segments.peek() == Some(&"super") && module.len() > 1 {
+                segments.next();
+                module.pop();
+            }
+        }
+        _ if !instance_path.contains("::") => {}
+        _ => return instance_path.to_string(),
+    }
+    module
+        .into_iter()
+        .chain(segments)
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
 pub fn assemble_primitive_definition(
     module_path: &str,
     primitive_instance_import_path: String,
     self_type_id: TypeId,
 ) -> ComponentDefinition {
     let modified_module_path = clean_module_path(module_path);
+    let primitive_instance_import_path =
+        resolve_primitive_instance_path(&modified_module_path, &primitive_instance_import_path);
 
     ComponentDefinition {
         is_primitive: true,
@@ -631,6 +907,7 @@ pub fn assemble_primitive_definition(
         template: None,
         settings: None,
         module_path: modified_module_path,
+        extends: None,
     }
 }
 
@@ -958,9 +1235,14 @@ impl Reflectable for Stroke {
                 },
                 PropertyDefinition {
                     name: "width".to_string(),
-                    flags: flags,
+                    flags: flags.clone(),
                     type_id: Size::get_type_id(),
                 },
+                PropertyDefinition {
+                    name: "hairline".to_string(),
+                    flags: flags,
+                    type_id: bool::get_type_id(),
+                },
             ],
         };
 
@@ -1111,6 +1393,22 @@ impl Reflectable for pax_runtime_api::Numeric {
     }
 }
 
+impl Reflectable for PaxValue {
+    fn get_import_path() -> String {
+        "pax_engine::api::PaxValue".to_string()
+    }
+
+    fn get_self_pascal_identifier() -> String {
+        "PaxValue".to_string()
+    }
+    fn get_type_id() -> TypeId {
+        TypeId::build_singleton(
+            &Self::get_import_path(),
+            Some(&Self::get_self_pascal_identifier()),
+        )
+    }
+}
+
 impl Reflectable for kurbo::Point {
     fn get_import_path() -> String {
         "kurbo::Point".to_string()
@@ -0,0 +1,270 @@
+//! Human-readable diff of two manifests, grouped by component, e.g. to review
+//! the changes made by a batch operation:
+//!
+//! ```text
+//! ~ component crate::Main
+//!     + <Rectangle> #4
+//!         x=20
+//!     ~ <Text> #1
+//!         ~ text: "Hello" -> "Hi"
+//!         - width=10
+//!     - <Ellipse> #2
+//! ```
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write;
+
+use crate::constants::TOKENS_SYMBOL;
+use crate::{
+    ComponentDefinition, ComponentTemplate, PaxManifest, SettingElement, TemplateNodeDefinition,
+    TemplateNodeId, TypeId, ValueDefinition,
+};
+
+impl PaxManifest {
+    /// Lists the components, template nodes and settings that were added, removed or
+    /// modified going from `self` to `other`.  Template nodes are matched by id, which the
+    /// ORM keeps stable across edits.  Returns an empty string if nothing changed.
+    pub fn text_diff(&self, other: &PaxManifest) -> String {
+        let mut out = String::new();
+        for (type_id, old) in &self.components {
+            match other.components.get(type_id) {
+                Some(new) => diff_component(&mut out, type_id, old, new),
+                None => {
+                    let _ = writeln!(out, "- component {}", type_id);
+                }
+            }
+        }
+        for (type_id, new) in &other.components {
+            if self.components.contains_key(type_id) {
+                continue;
+            }
+            let _ = writeln!(out, "+ component {}", type_id);
+            if let Some(template) = &new.template {
+                for id in preorder(template) {
+                    write_node(&mut out, "+", template, &id, true);
+                }
+            }
+        }
+        out
+    }
+}
+
+fn diff_component(
+    out: &mut String,
+    type_id: &TypeId,
+    old: &ComponentDefinition,
+    new: &ComponentDefinition,
+) {
+    let empty = ComponentTemplate::default();
+    let old_template = old.template.as_ref().unwrap_or(&empty);
+    let new_template = new.template.as_ref().unwrap_or(&empty);
+
+    let mut changes = String::new();
+    for id in preorder(old_template) {
+        if new_template.get_node(&id).is_none() {
+            write_node(&mut changes, "-", old_template, &id, false);
+        }
+    }
+    for id in preorder(new_template) {
+        let Some(new_node) = new_template.get_node(&id) else {
+            continue;
+        };
+        let Some(old_node) = old_template.get_node(&id) else {
+            write_node(&mut changes, "+", new_template, &id, true);
+            continue;
+        };
+        let mut node_changes = String::new();
+        if old_node.type_id != new_node.type_id {
+            let _ = writeln!(
+                node_changes,
+                "        type: {} -> {}",
+                node_label(old_node),
+                node_label(new_node)
+            );
+        }
+        let (old_position, new_position) =
+            (position(old_template, &id), position(new_template, &id));
+        if old_position != new_position {
+            let _ = writeln!(
+                node_changes,
+                "        moved: {} -> {}",
+                describe_position(&old_position),
+                describe_position(&new_position)
+            );
+        }
+        diff_settings(&mut node_changes, &settings(old_node), &settings(new_node));
+        if !node_changes.is_empty() {
+            let _ = writeln!(changes, "    ~ {} #{}", node_label(new_node), id);
+            changes.push_str(&node_changes);
+        }
+    }
+
+    if !changes.is_empty() {
+        let _ = writeln!(out, "~ component {}", type_id);
+        out.push_str(&changes);
+    }
+}
+
+fn diff_settings(out: &mut String, old: &BTreeMap<String, String>, new: &BTreeMap<String, String>) {
+    for (key, old_value) in old {
+        match new.get(key) {
+            Some(new_value) if new_value != old_value => {
+                let _ = writeln!(out, "        ~ {}: {} -> {}", key, old_value, new_value);
+            }
+            Some(_) => (),
+            None => {
+                let _ = writeln!(out, "        - {}={}", key, old_value);
+            }
+        }
+    }
+    for (key, new_value) in new {
+        if !old.contains_key(key) {
+            let _ = writeln!(out, "        + {}={}", key, new_value);
+        }
+    }
+}
+
+/// Writes an added or removed node, with its settings if `with_settings`
+fn write_node(
+    out: &mut String,
+    sign: &str,
+    template: &ComponentTemplate,
+    id: &TemplateNodeId,
+    with_settings: bool,
+) {
+    let Some(node) = template.get_node(id) else {
+        return;
+    };
+    let _ = writeln!(out, "    {} {} #{}", sign, node_label(node), id);
+    if with_settings {
+        for (key, value) in settings(node) {
+            let _ = writeln!(out, "        {}={}", key, value);
+        }
+    }
+}
+
+impl TemplateNodeDefinition {
+    /// Short description of the node for listing changes, e.g. `<Rectangle>`, `if self.on` or
+    /// `// comment`
+    pub fn get_label(&self) -> String {
+        node_label(self)
+    }
+}
+
+impl ValueDefinition {
+    /// The value as it would be written in a template, e.g. `{self.x + 1}`, which doesn't depend
+    /// on how the value was formatted in the source
+    pub fn to_template_string(&self) -> String {
+        value_to_string(self)
+    }
+}
+
+impl ComponentTemplate {
+    /// Ids of the nodes, parents before children, siblings in order
+    pub fn get_preorder_ids(&self) -> Vec<TemplateNodeId> {
+        preorder(self)
+    }
+
+    /// Parent and index among its siblings of node `id`
+    pub fn get_position(&self, id: &TemplateNodeId) -> (Option<TemplateNodeId>, usize) {
+        position(self, id)
+    }
+}
+
+fn node_label(node: &TemplateNodeDefinition) -> String {
+    if let Some(comment) = &node.raw_comment_string {
+        return format!("// {}", comment.trim());
+    }
+    if let Some(cfsd) = &node.control_flow_settings {
+        if let Some(condition) = &cfsd.condition_expression {
+            return format!("if {}", condition);
+        }
+        if let Some(source) = &cfsd.repeat_source_expression {
+            return format!("for ... in {}", source);
+        }
+        if let Some(index) = &cfsd.slot_index_expression {
+            return format!("slot({})", index);
+        }
+    }
+    format!(
+        "<{}>",
+        node.type_id
+            .get_pascal_identifier()
+            .unwrap_or_else(|| node.type_id.to_string())
+    )
+}
+
+/// The settings of a node by key, with values as they would be written in a template
+fn settings(node: &TemplateNodeDefinition) -> BTreeMap<String, String> {
+    node.settings
+        .iter()
+        .flatten()
+        .filter_map(|setting| match setting {
+            SettingElement::Setting(key, value) => {
+                Some((key.token_value.clone(), value_to_string(value)))
+            }
+            SettingElement::Comment(_) => None,
+        })
+        .collect()
+}
+
+fn value_to_string(value: &ValueDefinition) -> String {
+    match value {
+        ValueDefinition::Undefined => "undefined".to_string(),
+        ValueDefinition::LiteralValue(value) => value.to_string(),
+        ValueDefinition::Block(block) => {
+            let elements: Vec<String> = block
+                .get_all_settings()
+                .into_iter()
+                .map(|(key, value)| format!("{}: {}", key.token_value, value_to_string(value)))
+                .collect();
+            let prefix = block
+                .explicit_type_pascal_identifier
+                .as_ref()
+                .map(|t| format!("{} ", t.token_value))
+                .unwrap_or_default();
+            format!("{}{{{}}}", prefix, elements.join(", "))
+        }
+        ValueDefinition::Expression(info) => format!("{{{}}}", info),
+        ValueDefinition::Identifier(ident) => ident.to_string(),
+        ValueDefinition::DoubleBinding(ident) => format!("bind:{}", ident),
+        ValueDefinition::EventBindingTarget(ident) => format!("self.{}", ident),
+        ValueDefinition::Token(name) => format!("{{{}.{}}}", TOKENS_SYMBOL, name),
+    }
+}
+
+/// Parent and index among its siblings of node `id`
+fn position(template: &ComponentTemplate, id: &TemplateNodeId) -> (Option<TemplateNodeId>, usize) {
+    let parent = template.get_parent(id);
+    let siblings = match &parent {
+        Some(parent) => template.get_children(parent).unwrap_or_default(),
+        None => template.get_root(),
+    };
+    let index = siblings.iter().position(|s| s == id).unwrap_or_default();
+    (parent, index)
+}
+
+fn describe_position((parent, index): &(Option<TemplateNodeId>, usize)) -> String {
+    match parent {
+        Some(parent) => format!("child {} of #{}", index, parent),
+        None => format!("root {}", index),
+    }
+}
+
+/// Ids of the nodes of `template`, parents before children
+fn preorder(template: &ComponentTemplate) -> Vec<TemplateNodeId> {
+    let mut ids = vec![];
+    let mut queue: VecDeque<TemplateNodeId> = template.get_root().into();
+    while let Some(id) = queue.pop_front() {
+        for child in template
+            .get_children(&id)
+            .unwrap_or_default()
+            .into_iter()
+            .rev()
+        {
+            queue.push_front(child);
+        }
+        ids.push(id);
+    }
+    ids
+}
@@ -0,0 +1,231 @@
+//! # Validation
+//!
+//! Checks template nodes against the rest of the manifest, so that mistakes are reported at the
+//! node that makes them instead of in the generated cartridge or at runtime:
+//!
+//! - settings are checked against the properties of the component (or primitive) the node
+//!   instantiates.  Literal settings are checked against the type of their property with
+//!   [`check_coercion`], the same coercion rules the runtime applies, as are the values of the
+//!   design tokens settings reference, which must be in [`PaxManifest::tokens`]; expressions,
+//!   identifiers and blocks are only checked for the property existing.
+//! - tags the parser couldn't resolve to a component, e.g. after the component was renamed, are
+//!   reported as [`UNRESOLVED_TYPE`].  With [`validate_component`], the type of each node is also
+//!   checked to still be a component of the manifest.
+//!
+//! The compiler fails builds on these issues, and the designtime checks components with them
+//! before sending them to the design server, which would otherwise fail to rebuild them.  Until
+//! then, nodes of unresolved types are kept in the manifest and rendered as placeholders, so that
+//! they can be replaced or removed in the designer.
+//!
+//! Settings of `@settings` selector blocks aren't checked, since the nodes they apply to are only
+//! known once they are merged into the template.
+
+use pax_runtime_api::{check_coercion, Coercibility, PaxValue};
+
+use crate::constants::{CFG_ATTRIBUTE, TEMPLATE_LABEL_ATTRIBUTE, TOKENS_SYMBOL};
+use crate::{
+    ComponentDefinition, LocationInfo, PaxManifest, PropertyDefinition, SettingElement,
+    TemplateNodeDefinition, TemplateNodeId, Token, ValueDefinition,
+};
+
+/// Setting keys handled by the runtime rather than by a property of the node
+const RESERVED_SETTING_KEYS: [&str; 3] = ["class", TEMPLATE_LABEL_ATTRIBUTE, CFG_ATTRIBUTE];
+
+/// A setting whose key isn't a property of the node's component
+pub const UNKNOWN_PROPERTY: &str = "unknown_property";
+/// A literal setting that can't be coerced into the type of its property
+pub const MISMATCHED_TYPE: &str = "mismatched_type";
+/// A literal setting coerced into the type of its property with loss, e.g. `2.5` into a `u32`
+pub const LOSSY_COERCION: &str = "lossy_coercion";
+/// A node instantiating a type that isn't a component of the manifest
+pub const UNRESOLVED_TYPE: &str = "unresolved_type";
+/// A setting referencing a design token that isn't in the manifest's tokens
+pub const UNKNOWN_TOKEN: &str = "unknown_token";
+
+/// An issue with a node of a template
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateIssue {
+    /// One of the rules of this module, e.g. [`UNKNOWN_PROPERTY`]
+    pub rule: &'static str,
+    /// Whether the issue fails builds, as opposed to being a warning
+    pub is_error: bool,
+    pub message: String,
+    pub node_id: TemplateNodeId,
+    /// Name of the node's type, e.g. `Rectangle`
+    pub node_name: String,
+    /// Where in the template the issue is, if known
+    pub location: Option<LocationInfo>,
+}
+
+/// Checks the settings of the nodes of `component`'s template, and that none of their tags were
+/// left unresolved by the parser, in node order
+pub fn validate_settings(
+    manifest: &PaxManifest,
+    component: &ComponentDefinition,
+) -> Vec<TemplateIssue> {
+    let mut issues = vec![];
+    for (id, tnd) in template_nodes(component) {
+        if tnd.type_id.is_unresolved() {
+            issues.push(unresolved_type_issue(id, tnd));
+            continue;
+        }
+        validate_node_settings(manifest, id, tnd, &mut issues);
+    }
+    issues
+}
+
+/// Checks the settings of the nodes of `component`'s template, and that their types resolve to
+/// components of `manifest`, in node order
+pub fn validate_component(
+    manifest: &PaxManifest,
+    component: &ComponentDefinition,
+) -> Vec<TemplateIssue> {
+    let mut issues = vec![];
+    for (id, tnd) in template_nodes(component) {
+        if manifest.is_unresolved_type(&tnd.type_id) {
+            issues.push(unresolved_type_issue(id, tnd));
+            continue;
+        }
+        validate_node_settings(manifest, id, tnd, &mut issues);
+    }
+    issues
+}
+
+fn unresolved_type_issue(id: &TemplateNodeId, tnd: &TemplateNodeDefinition) -> TemplateIssue {
+    TemplateIssue {
+        rule: UNRESOLVED_TYPE,
+        is_error: true,
+        message: format!("`{}` isn't a known component", node_name(tnd)),
+        node_id: id.clone(),
+        node_name: node_name(tnd),
+        location: None,
+    }
+}
+
+fn template_nodes(
+    component: &ComponentDefinition,
+) -> Vec<(&TemplateNodeId, &TemplateNodeDefinition)> {
+    let Some(template) = &component.template else {
+        return vec![];
+    };
+    let mut ids = template.get_ids();
+    ids.sort_by_key(|id| id.as_usize());
+    ids.into_iter()
+        .filter_map(|id| Some((id, template.get_node(id)?)))
+        .collect()
+}
+
+fn node_name(tnd: &TemplateNodeDefinition) -> String {
+    tnd.type_id
+        .get_pascal_identifier()
+        .unwrap_or_else(|| tnd.type_id.to_string())
+}
+
+fn validate_node_settings(
+    manifest: &PaxManifest,
+    id: &TemplateNodeId,
+    tnd: &TemplateNodeDefinition,
+    issues: &mut Vec<TemplateIssue>,
+) {
+    // control flow nodes, and types the parser didn't reflect, have no known properties
+    if !manifest.type_table.contains_key(&tnd.type_id) {
+        return;
+    }
+    let properties = manifest.get_all_component_properties(&tnd.type_id);
+    if properties.is_empty() {
+        return;
+    }
+    let node_name = node_name(tnd);
+
+    for element in tnd.settings.iter().flatten() {
+        let SettingElement::Setting(key, value) = element else {
+            continue;
+        };
+        if matches!(value, ValueDefinition::EventBindingTarget(_))
+            || RESERVED_SETTING_KEYS.contains(&key.token_value.as_str())
+            || key.token_value.starts_with('_')
+        {
+            continue;
+        }
+        let mut push = |rule: &'static str, is_error: bool, message: String| {
+            issues.push(TemplateIssue {
+                rule,
+                is_error,
+                message,
+                node_id: id.clone(),
+                node_name: node_name.clone(),
+                location: key.token_location.clone(),
+            })
+        };
+
+        let Some(property) = properties.iter().find(|p| p.name == key.token_value) else {
+            push(
+                UNKNOWN_PROPERTY,
+                true,
+                format!("{} has no property `{}`", node_name, key.token_value),
+            );
+            continue;
+        };
+        let literal = match value {
+            ValueDefinition::LiteralValue(literal) => Some(literal),
+            ValueDefinition::Token(name) => {
+                let token = manifest.tokens.get(name);
+                if token.is_none() {
+                    push(
+                        UNKNOWN_TOKEN,
+                        true,
+                        format!(
+                            "{} references the unknown token `{}.{}`",
+                            qualified_name(&node_name, key),
+                            TOKENS_SYMBOL,
+                            name
+                        ),
+                    );
+                }
+                token
+            }
+            _ => None,
+        };
+        if let Some(literal) = literal {
+            match check_literal(property, literal) {
+                Coercibility::NotCoercible(reason) => push(
+                    MISMATCHED_TYPE,
+                    true,
+                    format!(
+                        "expected {} for {}, found `{}` ({})",
+                        type_name(property),
+                        qualified_name(&node_name, key),
+                        literal,
+                        reason
+                    ),
+                ),
+                Coercibility::Lossy(reason) => push(
+                    LOSSY_COERCION,
+                    false,
+                    format!(
+                        "lossy coercion into {} for {}: {}",
+                        type_name(property),
+                        qualified_name(&node_name, key),
+                        reason
+                    ),
+                ),
+                Coercibility::Coercible | Coercibility::Unknown => {}
+            }
+        }
+    }
+}
+
+fn check_literal(property: &PropertyDefinition, literal: &PaxValue) -> Coercibility {
+    check_coercion(&property.type_id.get_unique_identifier(), literal)
+}
+
+fn type_name(property: &PropertyDefinition) -> String {
+    property
+        .type_id
+        .get_pascal_identifier()
+        .unwrap_or_else(|| property.type_id.to_string())
+}
+
+fn qualified_name(node_name: &str, key: &Token) -> String {
+    format!("{}::{}", node_name, key.token_value)
+}
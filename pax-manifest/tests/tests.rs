@@ -2,7 +2,19 @@
 #[cfg(feature = "parsing")]
 mod tests {
 
-    use pax_manifest::{utils, ValueDefinition};
+    use std::collections::{BTreeMap, HashMap};
+
+    use pax_manifest::{
+        cartridge_generation::SettingSource,
+        cfg::{BuildCfg, CfgCondition},
+        parsing::{
+            assemble_component_definition, parse_template_from_component_definition_string,
+            resolve_primitive_instance_path, ParsingContext, TemplateNodeParseContext,
+        },
+        utils, ComponentDefinition, ComponentTemplate, PaxManifest, SettingElement,
+        SettingsBlockElement, TemplateNodeDefinition, Token, TypeId, ValueDefinition,
+    };
+    use pax_runtime_api::{Numeric, PaxValue};
 
     #[test]
     fn test_parse_empty() {
@@ -44,4 +56,495 @@ mod tests {
         let res = utils::parse_value("{5 + 3}this_shouldn't succeed");
         assert!(matches!(res, Err(_)));
     }
+
+    #[test]
+    fn test_parse_token() {
+        let res = utils::parse_value("{$tokens.primary}");
+        assert!(
+            matches!(&res, Ok(ValueDefinition::Token(name)) if name == "primary"),
+            "{:?}",
+            res
+        );
+        // any other use of a token is an expression
+        let res = utils::parse_value("{$tokens.gutter * 2}");
+        assert!(
+            matches!(res, Ok(ValueDefinition::Expression(_))),
+            "{:?}",
+            res
+        );
+    }
+
+    #[test]
+    fn test_resolve_tokens() {
+        let type_id = TypeId::build_singleton("crate::Main", None);
+        let mut template = ComponentTemplate::new(type_id.clone(), None);
+        let rect = node(
+            "Rectangle",
+            &[
+                ("fill", "{$tokens.primary}"),
+                (
+                    "corner_radii",
+                    "{top_left: {$tokens.radius}, top_right: 2.0}",
+                ),
+                ("width", "{$tokens.missing}"),
+            ],
+        );
+        template.add_root_node_back(rect.clone());
+        let mut manifest = manifest(template);
+        manifest
+            .tokens
+            .insert("primary".to_string(), PaxValue::String("red".to_string()));
+        manifest
+            .tokens
+            .insert("radius".to_string(), PaxValue::Numeric(Numeric::F64(4.0)));
+
+        let properties = manifest.get_inline_properties(&type_id, &rect);
+        assert!(matches!(
+            &properties["fill"],
+            ValueDefinition::LiteralValue(PaxValue::String(fill)) if fill == "red"
+        ));
+        let ValueDefinition::Block(radii) = &properties["corner_radii"] else {
+            panic!("unexpected value: {:?}", properties["corner_radii"]);
+        };
+        assert!(matches!(
+            radii.get_all_settings()[0].1,
+            ValueDefinition::LiteralValue(PaxValue::Numeric(Numeric::F64(radius))) if *radius == 4.0
+        ));
+        // unknown tokens leave the property unset
+        assert!(!properties.contains_key("width"));
+
+        // changing a token changes every setting referencing it
+        manifest
+            .tokens
+            .insert("primary".to_string(), PaxValue::String("blue".to_string()));
+        let properties = manifest.get_inline_properties(&type_id, &rect);
+        assert!(matches!(
+            &properties["fill"],
+            ValueDefinition::LiteralValue(PaxValue::String(fill)) if fill == "blue"
+        ));
+    }
+
+    fn node(type_name: &str, settings: &[(&str, &str)]) -> TemplateNodeDefinition {
+        TemplateNodeDefinition {
+            type_id: TypeId::build_singleton(&format!("pax_std::{}", type_name), None),
+            control_flow_settings: None,
+            settings: Some(
+                settings
+                    .iter()
+                    .map(|(key, value)| {
+                        SettingElement::Setting(
+                            Token::new_without_location(key.to_string()),
+                            utils::parse_value(value).unwrap(),
+                        )
+                    })
+                    .collect(),
+            ),
+            raw_comment_string: None,
+            label: None,
+        }
+    }
+
+    fn manifest(template: ComponentTemplate) -> PaxManifest {
+        let type_id = template.get_containing_component_type_id();
+        let component = ComponentDefinition {
+            type_id: type_id.clone(),
+            is_main_component: true,
+            is_primitive: false,
+            is_struct_only_component: false,
+            module_path: "crate".to_string(),
+            primitive_instance_import_path: None,
+            template: Some(template),
+            settings: None,
+            extends: None,
+        };
+        PaxManifest {
+            components: BTreeMap::from([(type_id.clone(), component)]),
+            main_component_type_id: type_id,
+            type_table: HashMap::new(),
+            assets_dirs: vec![],
+            engine_import_path: "pax_engine".to_string(),
+            defines: BTreeMap::new(),
+            tokens: BTreeMap::new(),
+            build_cfg: None,
+        }
+    }
+
+    #[test]
+    fn test_text_diff() {
+        let type_id = TypeId::build_singleton("crate::Main", None);
+        let mut template = ComponentTemplate::new(type_id, None);
+        let text = template
+            .add_root_node_back(node("Text", &[("text", "\"Hello\""), ("width", "10")]))
+            .get_template_node_id();
+        template.add_root_node_back(node("Ellipse", &[]));
+        let old = manifest(template.clone());
+        assert_eq!(old.text_diff(&old), "");
+
+        template.update_node_properties(
+            &text,
+            vec![
+                (
+                    Token::new_without_location("text".to_string()),
+                    Some(utils::parse_value("\"Hi\"").unwrap()),
+                ),
+                (Token::new_without_location("width".to_string()), None),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        let ellipse = template.get_root()[1].clone();
+        template.remove_node(ellipse);
+        template.add_root_node_back(node("Rectangle", &[("x", "20")]));
+        let new = manifest(template);
+
+        let diff = old.text_diff(&new);
+        assert!(diff.starts_with("~ component crate::Main\n"), "{}", diff);
+        assert!(diff.contains("    - <Ellipse> #1\n"), "{}", diff);
+        assert!(diff.contains("    ~ <Text> #0\n"), "{}", diff);
+        assert!(
+            diff.contains("        ~ text: \"Hello\" -> \"Hi\"\n"),
+            "{}",
+            diff
+        );
+        assert!(diff.contains("        - width=10\n"), "{}", diff);
+        assert!(
+            diff.contains("    + <Rectangle> #2\n        x=20\n"),
+            "{}",
+            diff
+        );
+    }
+
+    #[test]
+    fn test_template_extension() {
+        let base_type_id = TypeId::build_singleton("crate::BaseButton", Some("BaseButton"));
+        let type_id = TypeId::build_singleton("crate::SecondaryButton", Some("SecondaryButton"));
+        let template_map: HashMap<String, TypeId> = ["Group", "Image", "Text"]
+            .into_iter()
+            .map(|name| {
+                let import_path = format!("pax_std::{}", name);
+                (
+                    name.to_string(),
+                    TypeId::build_singleton(&import_path, Some(name)),
+                )
+            })
+            .chain([("BaseButton".to_string(), base_type_id.clone())])
+            .collect();
+
+        let (mut ctx, base) = assemble_component_definition(
+            ParsingContext::default(),
+            r#"
+                <Group id=root>
+                    <Image template_label="icon"/>
+                    <Text text="Base"/>
+                </Group>
+                @settings {
+                    #root { width: 100px }
+                }
+            "#,
+            false,
+            template_map.clone(),
+            "crate",
+            base_type_id.clone(),
+            "base_button.pax",
+        );
+        assert!(base.extends.is_none());
+        ctx.component_definitions.insert(base_type_id.clone(), base);
+
+        let (_, extending) = assemble_component_definition(
+            ctx,
+            r#"
+                @extends(BaseButton)
+                @override(label="icon") <Text text="icon"/>
+                @settings {
+                    #root { width: 200px }
+                }
+            "#,
+            false,
+            template_map,
+            "crate",
+            type_id.clone(),
+            "secondary_button.pax",
+        );
+
+        let template = extending.template.as_ref().unwrap();
+        assert_eq!(template.get_containing_component_type_id(), type_id);
+        let root = template.get_root();
+        assert_eq!(root.len(), 1);
+        let children = template.get_children(&root[0]).unwrap();
+        assert_eq!(children.len(), 2);
+
+        // the override replaces the labeled node in place, and keeps its label
+        let icon = template.get_node(&children[0]).unwrap();
+        assert_eq!(icon.type_id.get_pascal_identifier().unwrap(), "Text");
+        assert_eq!(icon.get_template_label().as_deref(), Some("icon"));
+        assert_eq!(
+            template.find_node_with_label("icon"),
+            Some(children[0].clone())
+        );
+
+        assert!(extending.is_inherited_node(&root[0]));
+        assert!(!extending.is_inherited_node(&children[0]));
+        assert!(extending.is_inherited_node(&children[1]));
+
+        // own settings come after the ones of the base, and take precedence
+        let extension = extending.extends.as_ref().unwrap();
+        assert_eq!(extension.base, base_type_id);
+        assert_eq!(extension.own_settings.len(), 1);
+        let settings = extending.settings.as_ref().unwrap();
+        assert_eq!(settings.len(), 2);
+        assert!(matches!(
+            &settings[1],
+            SettingsBlockElement::SelectorBlock(selector, _) if selector.token_value == "#root"
+        ));
+    }
+
+    #[test]
+    fn test_override_of_missing_label_is_an_error() {
+        let base_type_id = TypeId::build_singleton("crate::BaseButton", Some("BaseButton"));
+        let type_id = TypeId::build_singleton("crate::SecondaryButton", Some("SecondaryButton"));
+        let template_map: HashMap<String, TypeId> = ["Group", "Image", "Text"]
+            .into_iter()
+            .map(|name| {
+                let import_path = format!("pax_std::{}", name);
+                (
+                    name.to_string(),
+                    TypeId::build_singleton(&import_path, Some(name)),
+                )
+            })
+            .collect();
+        let (_, base) = assemble_component_definition(
+            ParsingContext::default(),
+            r#"<Group><Image template_label="icon"/></Group>"#,
+            false,
+            template_map.clone(),
+            "crate",
+            base_type_id,
+            "base_button.pax",
+        );
+
+        let pax = "@extends(BaseButton)\n@override(label=\"icno\") <Text text=\"icon\"/>\n";
+        let ast = pax_lang::parse_pax_str(pax_lang::Rule::pax_component_definition, pax).unwrap();
+        let mut ctx = TemplateNodeParseContext {
+            pascal_identifier_to_type_id_map: template_map,
+            template: base
+                .template
+                .unwrap()
+                .extended_by(type_id, Some("secondary_button.pax".to_string())),
+        };
+        let error =
+            parse_template_from_component_definition_string(&mut ctx, pax, ast).unwrap_err();
+        assert_eq!(error.start, (2, 1));
+        assert_eq!(error.matched_string, "@override(label=\"icno\")");
+        assert_eq!(
+            error.to_string(),
+            "@override: secondary_button.pax:2:1: no node labeled \"icno\" in the extended template"
+        );
+    }
+
+    fn build_cfg(target: &str, features: &[&str]) -> BuildCfg {
+        BuildCfg {
+            target: target.to_string(),
+            features: features.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_cfg_condition_evaluation() {
+        let condition =
+            CfgCondition::parse("!premium && (target == ios || target == macos)").unwrap();
+        assert!(condition.evaluate(&build_cfg("ios", &[])));
+        assert!(condition.evaluate(&build_cfg("macos", &["beta"])));
+        assert!(!condition.evaluate(&build_cfg("ios", &["premium"])));
+        assert!(!condition.evaluate(&build_cfg("web", &[])));
+        assert_eq!(
+            condition.features().into_iter().collect::<Vec<_>>(),
+            vec!["premium"]
+        );
+
+        let condition = CfgCondition::parse("target != 'web'").unwrap();
+        assert!(!condition.evaluate(&build_cfg("web", &[])));
+        assert!(condition.evaluate(&build_cfg("macos", &[])));
+    }
+
+    #[test]
+    fn test_cfg_condition_display_round_trips() {
+        // nested `&&`/`||` are parenthesized, so precedence doesn't need to be known to read them
+        for (condition, displayed) in [
+            ("target == web", "target == web"),
+            ("!(target == web)", "!(target == web)"),
+            ("a && b || c", "(a && b) || c"),
+            ("a && (b || c)", "a && (b || c)"),
+            ("!(a || b) && target != ios", "!(a || b) && target != ios"),
+        ] {
+            let parsed = CfgCondition::parse(condition).unwrap();
+            assert_eq!(parsed.to_string(), displayed);
+            assert_eq!(CfgCondition::parse(displayed).unwrap(), parsed);
+        }
+    }
+
+    #[test]
+    fn test_cfg_condition_errors() {
+        for condition in [
+            "",
+            "target == linux",
+            "target web",
+            "a &&",
+            "(a || b",
+            "a b",
+            "a & b",
+            "'a",
+        ] {
+            assert!(
+                CfgCondition::parse(condition).is_err(),
+                "`{}` should not parse",
+                condition
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_primitive_instance_path() {
+        let resolve = |path| resolve_primitive_instance_path("my_charts::particles", path);
+        assert_eq!(
+            resolve("crate::particles::ParticlesInstance"),
+            "my_charts::particles::ParticlesInstance"
+        );
+        assert_eq!(
+            resolve("self::ParticlesInstance"),
+            "my_charts::particles::ParticlesInstance"
+        );
+        assert_eq!(
+            resolve("ParticlesInstance"),
+            "my_charts::particles::ParticlesInstance"
+        );
+        assert_eq!(resolve("super::Instance"), "my_charts::Instance");
+        assert_eq!(
+            resolve("pax_std::core::group::GroupInstance"),
+            "pax_std::core::group::GroupInstance"
+        );
+        // the userland crate is parsed as `crate`, so its paths are kept relative to it
+        assert_eq!(
+            resolve_primitive_instance_path("crate::particles", "crate::particles::Instance"),
+            "crate::particles::Instance"
+        );
+    }
+
+    #[test]
+    fn test_designer_label() {
+        let type_id = TypeId::build_singleton("crate::Page", Some("Page"));
+        let template_map: HashMap<String, TypeId> = ["Group", "Text"]
+            .into_iter()
+            .map(|name| {
+                let import_path = format!("pax_std::{}", name);
+                (
+                    name.to_string(),
+                    TypeId::build_singleton(&import_path, Some(name)),
+                )
+            })
+            .collect();
+
+        let (_, page) = assemble_component_definition(
+            ParsingContext::default(),
+            r#"
+                <Group designer_label="Header">
+                    <Text designer_label="Title" text="Hello"/>
+                </Group>
+            "#,
+            false,
+            template_map,
+            "crate",
+            type_id,
+            "page.pax",
+        );
+
+        let template = page.template.as_ref().unwrap();
+        let header = template.get_node(&template.get_root()[0]).unwrap();
+        assert_eq!(header.label.as_deref(), Some("Header"));
+        assert!(header.settings.as_ref().unwrap().is_empty());
+
+        // the label is moved out of the settings, so codegen never sees it
+        let children = template.get_children(&template.get_root()[0]).unwrap();
+        let title = template.get_node(&children[0]).unwrap();
+        assert_eq!(title.label.as_deref(), Some("Title"));
+        let keys: Vec<_> = title
+            .settings
+            .iter()
+            .flatten()
+            .filter_map(|setting| match setting {
+                SettingElement::Setting(key, _) => Some(key.token_value.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(keys, vec!["text"]);
+    }
+
+    #[test]
+    fn test_resolve_settings_with_provenance() {
+        let type_id = TypeId::build_singleton("crate::Card", Some("Card"));
+        let template_map: HashMap<String, TypeId> = [(
+            "Text".to_string(),
+            TypeId::build_singleton("pax_std::Text", Some("Text")),
+        )]
+        .into_iter()
+        .collect();
+
+        let (_, card) = assemble_component_definition(
+            ParsingContext::default(),
+            r#"
+                <Text id=title class=muted class=large text="Hello" height=5px/>
+                <Text text="Plain"/>
+
+                @settings {
+                    .muted { fill: RED, width: 10px, height: 10px }
+                    .large { width: 15px }
+                    #title { x: 20px }
+                }
+            "#,
+            false,
+            template_map,
+            "crate",
+            type_id,
+            "card.pax",
+        );
+
+        let template = card.template.as_ref().unwrap();
+        let title = template.get_node(&template.get_root()[0]).unwrap();
+        assert!(PaxManifest::selector_matches(&title.settings, ".muted"));
+        assert!(PaxManifest::selector_matches(&title.settings, "#title"));
+        assert!(!PaxManifest::selector_matches(&title.settings, ".title"));
+
+        let resolved =
+            PaxManifest::resolve_settings_with_provenance(&title.settings, &card.settings).unwrap();
+        let source = |name: &str| {
+            resolved
+                .iter()
+                .find_map(|(setting, source)| match setting {
+                    SettingElement::Setting(key, _) if key.token_value == name => {
+                        Some(source.clone())
+                    }
+                    _ => None,
+                })
+                .unwrap()
+        };
+        let selector = |s: &str| SettingSource::Selector(s.to_string());
+        assert_eq!(source("text"), SettingSource::Inline);
+        assert_eq!(source("height"), SettingSource::Inline);
+        assert_eq!(source("fill"), selector(".muted"));
+        // later classes override earlier ones
+        assert_eq!(source("width"), selector(".large"));
+        assert_eq!(source("x"), selector("#title"));
+
+        // the merged settings used by the runtime are the same, without provenance
+        let merged =
+            PaxManifest::merge_inline_settings_with_settings_block(&title.settings, &card.settings)
+                .unwrap();
+        assert_eq!(merged.len(), resolved.len());
+
+        let plain = template.get_node(&template.get_root()[1]).unwrap();
+        let resolved =
+            PaxManifest::resolve_settings_with_provenance(&plain.settings, &card.settings).unwrap();
+        assert!(resolved
+            .iter()
+            .all(|(_, source)| *source == SettingSource::Inline));
+    }
 }
@@ -9,6 +9,9 @@ pub use serde;
 
 use serde::{Deserialize, Serialize};
 
+/// Messages from the engine to the chassis. Positions and sizes in patches are in logical
+/// pixels (CSS pixels on web, points on Apple platforms), regardless of the device pixel ratio
+/// reported with [`NativeInterrupt::ViewportDprChange`]; chassis scale them for display.
 #[cfg_attr(debug_assertions, derive(Debug))]
 #[derive(Serialize)]
 pub enum NativeMessage {
@@ -45,11 +48,20 @@ pub enum NativeMessage {
     ScrollerCreate(AnyCreatePatch),
     ScrollerUpdate(ScrollerPatch),
     ScrollerDelete(u32),
+    VideoCreate(AnyCreatePatch),
+    VideoUpdate(VideoPatch),
+    VideoDelete(u32),
     ImageLoad(ImagePatch),
     LayerAdd(LayerAddPatch), //FUTURE: native form controls
     ShrinkLayersTo(u32),
     OcclusionUpdate(OcclusionPatch),
+    ElementReorder(ElementReorderPatch),
     Navigate(NavigationPatch),
+    PrintRequest(PrintPatch),
+    ClipboardWrite(String),
+    /// Asks the chassis for the text on the system clipboard, answered with a
+    /// [`NativeInterrupt::ClipboardRead`]
+    ClipboardReadRequest(ClipboardReadRequestPatch),
 }
 
 #[derive(Deserialize)]
@@ -82,8 +94,33 @@ pub enum NativeInterrupt {
     FormTextboxChange(FormTextboxChangeArgs),
     FormTextboxInput(FormTextboxInputArgs),
     FormButtonClick(FormButtonClickArgs),
+    VideoTimeUpdate(VideoTimeUpdateArgs),
+    VideoPlayStateChange(VideoPlayStateChangeArgs),
+    VideoMetadata(VideoMetadataArgs),
+    VideoEnded(VideoEndedArgs),
+    VideoUnsupported(VideoUnsupportedArgs),
     Scrollbar(ScrollbarInterruptArgs),
     DropFile(DropFileArgs),
+    ViewportDprChange(ViewportDprChangeArgs),
+    MessageChunksConsumed(MessageChunksConsumedArgs),
+    /// The text on the system clipboard, one for each [`NativeMessage::ClipboardReadRequest`]
+    /// in the order they were sent. Empty if the clipboard can't be read, e.g. when the user
+    /// denies access
+    ClipboardRead(String),
+}
+
+/// Optional features a chassis supports, declared by the chassis on startup.
+/// Chassis that don't declare capabilities get the default (nothing enabled).
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct ChassisCapabilities {
+    /// Reuse of the native elements of unmounted nodes by newly mounted nodes
+    /// of the same kind, signaled with an update patch marked as `recycled`
+    /// instead of a delete followed by a create
+    pub native_element_recycling: bool,
+    /// Explicit stacking of native elements within their occlusion layer,
+    /// signaled with `ElementReorder` messages
+    pub native_element_ordering: bool,
 }
 
 #[derive(Deserialize)]
@@ -153,6 +190,46 @@ pub struct FormButtonClickArgs {
     pub id: u32,
 }
 
+/// Sent by the chassis periodically (throttled) during video playback
+#[derive(Deserialize)]
+#[repr(C)]
+pub struct VideoTimeUpdateArgs {
+    pub id: u32,
+    pub current_time: f64,
+}
+
+/// Sent by the chassis when playback starts or stops for reasons other than
+/// a `VideoPatch` (native controls, buffering, reaching the end)
+#[derive(Deserialize)]
+#[repr(C)]
+pub struct VideoPlayStateChangeArgs {
+    pub id: u32,
+    pub playing: bool,
+}
+
+#[derive(Deserialize)]
+#[repr(C)]
+pub struct VideoMetadataArgs {
+    pub id: u32,
+    pub natural_width: f64,
+    pub natural_height: f64,
+    pub duration: f64,
+}
+
+#[derive(Deserialize)]
+#[repr(C)]
+pub struct VideoEndedArgs {
+    pub id: u32,
+}
+
+/// Sent by the chassis in response to a `VideoCreate` (or a later source change)
+/// if it can't play the video natively, so the engine can render a fallback
+#[derive(Deserialize)]
+#[repr(C)]
+pub struct VideoUnsupportedArgs {
+    pub id: u32,
+}
+
 #[derive(Deserialize)]
 #[repr(C)]
 pub struct ClapInterruptArgs {
@@ -231,12 +308,25 @@ pub enum ModifierKeyMessage {
     Command,
 }
 
+/// Where on the keyboard a key is, for keys that exist more than once, e.g. left and right shift
+#[derive(Deserialize, Default)]
+#[repr(C)]
+pub enum KeyLocationMessage {
+    #[default]
+    Standard,
+    Left,
+    Right,
+    Numpad,
+}
+
 #[derive(Deserialize)]
 #[repr(C)]
 pub struct KeyDownInterruptArgs {
     pub key: String,
     pub modifiers: Vec<ModifierKeyMessage>,
     pub is_repeat: bool,
+    #[serde(default)]
+    pub location: KeyLocationMessage,
 }
 
 #[derive(Deserialize)]
@@ -245,6 +335,8 @@ pub struct KeyUpInterruptArgs {
     pub key: String,
     pub modifiers: Vec<ModifierKeyMessage>,
     pub is_repeat: bool,
+    #[serde(default)]
+    pub location: KeyLocationMessage,
 }
 
 #[derive(Deserialize)]
@@ -253,6 +345,8 @@ pub struct KeyPressInterruptArgs {
     pub key: String,
     pub modifiers: Vec<ModifierKeyMessage>,
     pub is_repeat: bool,
+    #[serde(default)]
+    pub location: KeyLocationMessage,
 }
 
 #[derive(Deserialize)]
@@ -380,12 +474,39 @@ pub struct MessageQueue {
     pub messages: Vec<NativeMessage>,
 }
 
+/// Part of the messages of a frame, sent to chassis that receive them in bounded-size
+/// chunks (the web chassis). Messages are independent of each other, so each chunk can be
+/// applied as soon as it's received.
+#[derive(Serialize)]
+pub struct NativeMessageChunk<'a, M> {
+    pub frame_id: u64,
+    pub chunk_index: usize,
+    pub chunk_count: usize,
+    pub messages: &'a [M],
+}
+
+/// Sent by the chassis once it has applied the last chunk of frame `frame_id`, see
+/// [`NativeMessageChunk`]. The messages of following frames are held back until then.
+#[derive(Deserialize)]
+#[repr(C)]
+pub struct MessageChunksConsumedArgs {
+    pub frame_id: u64,
+}
+
 #[derive(Deserialize)]
 #[repr(C)]
 pub struct AddedLayerArgs {
     pub num_layers_added: u32,
 }
 
+/// Sent by the chassis on startup and whenever the device pixel ratio (physical pixels per
+/// logical pixel) changes, e.g. when a window moves to a monitor with another resolution
+#[derive(Deserialize)]
+#[repr(C)]
+pub struct ViewportDprChangeArgs {
+    pub dpr: f64,
+}
+
 #[cfg_attr(debug_assertions, derive(Debug))]
 #[derive(Default, Serialize)]
 #[repr(C)]
@@ -435,6 +556,24 @@ pub struct NativeImagePatch {
     pub fit: Option<String>,
 }
 
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Default, Serialize, Clone)]
+#[repr(C)]
+pub struct VideoPatch {
+    pub id: u32,
+    pub transform: Option<Vec<f64>>,
+    pub size_x: Option<f64>,
+    pub size_y: Option<f64>,
+    pub source: Option<String>,
+    pub playing: Option<bool>,
+    pub looping: Option<bool>,
+    pub muted: Option<bool>,
+    pub volume: Option<f64>,
+    /// Seek target, only sent when `current_time` was set from userland
+    pub current_time: Option<f64>,
+    pub fit: Option<String>,
+}
+
 #[cfg_attr(debug_assertions, derive(Debug))]
 #[derive(Default, Serialize, Clone)]
 #[repr(C)]
@@ -484,6 +623,16 @@ pub struct SliderPatch {
     pub accent: Option<ColorMessage>,
     pub background: Option<ColorMessage>,
     pub border_radius: Option<f64>,
+    pub orientation: Option<OrientationMessage>,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Default, Serialize, Clone, PartialEq)]
+#[repr(C)]
+pub enum OrientationMessage {
+    #[default]
+    Horizontal,
+    Vertical,
 }
 
 #[cfg_attr(debug_assertions, derive(Debug))]
@@ -536,6 +685,17 @@ pub struct OcclusionPatch {
     pub parent_frame: Option<u32>,
 }
 
+/// New stacking index of a native element within its occlusion layer, higher
+/// indices are stacked above lower ones
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Default, Serialize)]
+#[repr(C)]
+pub struct ElementReorderPatch {
+    pub id: u32,
+    pub occlusion_layer_id: u32,
+    pub z_index: i32,
+}
+
 #[cfg_attr(debug_assertions, derive(Debug))]
 #[derive(Default, Serialize)]
 #[repr(C)]
@@ -544,6 +704,18 @@ pub struct NavigationPatch {
     pub target: String,
 }
 
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Default, Serialize)]
+#[repr(C)]
+pub struct PrintPatch {
+    pub target_id: Option<String>,
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Default, Serialize)]
+#[repr(C)]
+pub struct ClipboardReadRequestPatch {}
+
 #[cfg_attr(debug_assertions, derive(Debug))]
 #[derive(Default, Serialize, Clone)]
 #[repr(C)]
@@ -557,6 +729,9 @@ pub struct TextPatch {
     pub size_y: Option<f64>,
     pub style: Option<TextStyleMessage>,
     pub style_link: Option<TextStyleMessage>,
+    /// Set on the first patch of a node reusing the element of an unmounted
+    /// node. The patch carries the full state of the element.
+    pub recycled: Option<bool>,
 }
 
 #[cfg_attr(debug_assertions, derive(Debug))]
@@ -578,6 +753,9 @@ pub struct TextStyleMessage {
 pub struct ImagePatch {
     pub id: u32,
     pub path: Option<String>,
+    /// Scale of the asset variant to load if available, e.g. 2 for `logo@2x.png` in place of
+    /// `logo.png`, picked from the device pixel ratio
+    pub scale: Option<u32>,
 }
 
 #[cfg_attr(debug_assertions, derive(Debug))]
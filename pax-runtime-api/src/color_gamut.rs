@@ -0,0 +1,184 @@
+//! Range checks for the channels of `Color`, see `Color::clamped` and `Color::try_rgba`
+
+use crate::{Color, ColorChannel, Numeric, Rotation};
+
+impl ColorChannel {
+    /// Restricts this channel to its valid range: `0..=255` for integers, `0%..=100%` for
+    /// percentages and `0..=1` turn for rotations. Channels that aren't numbers become `0`
+    pub fn clamped(&self) -> ColorChannel {
+        if self.is_in_range() {
+            return self.clone();
+        }
+        match self {
+            Self::Integer(int) => {
+                Self::Integer(Numeric::F64(sanitize(int.to_float()).clamp(0.0, 255.0)))
+            }
+            Self::Percent(per) => {
+                Self::Percent(Numeric::F64(sanitize(per.to_float()).clamp(0.0, 100.0)))
+            }
+            Self::Rotation(rot) => Self::Percent(Numeric::F64(
+                sanitize(rot.to_float_0_1()).clamp(0.0, 1.0) * 100.0,
+            )),
+        }
+    }
+
+    fn is_in_range(&self) -> bool {
+        match self {
+            Self::Integer(int) => (0.0..=255.0).contains(&int.to_float()),
+            Self::Percent(per) => (0.0..=100.0).contains(&per.to_float()),
+            Self::Rotation(rot) => (0.0..=1.0).contains(&rot.to_float_0_1()),
+        }
+    }
+
+    /// Errors, naming `channel`, if this channel is outside of its valid range (see `clamped`)
+    fn check_range(&self, channel: &str) -> Result<(), String> {
+        if self.is_in_range() {
+            return Ok(());
+        }
+        let expected = match self {
+            Self::Integer(_) => "0 to 255",
+            Self::Percent(_) => "0% to 100%",
+            Self::Rotation(_) => "0 to 1 turn",
+        };
+        Err(format!(
+            "color channel {} is out of range: {} (expected {})",
+            channel, self, expected
+        ))
+    }
+}
+
+impl Rotation {
+    /// The equivalent hue in `0deg..360deg`. Hues that aren't numbers become `0deg`
+    pub fn wrapped_hue(&self) -> Rotation {
+        let turns = self.to_float_0_1();
+        if (0.0..1.0).contains(&turns) {
+            *self
+        } else {
+            Rotation::Degrees(Numeric::F64(sanitize(turns).rem_euclid(1.0) * 360.0))
+        }
+    }
+}
+
+impl Color {
+    /// Restricts the channels of this color to their valid ranges, so that it renders the same
+    /// on every backend: rgb and alpha channels are clamped (see `ColorChannel::clamped`) and
+    /// hues are wrapped into `0deg..360deg`. Named colors are returned as they are
+    pub fn clamped(&self) -> Color {
+        match self {
+            Self::rgb(r, g, b) => Self::rgb(r.clamped(), g.clamped(), b.clamped()),
+            Self::rgba(r, g, b, a) => {
+                Self::rgba(r.clamped(), g.clamped(), b.clamped(), a.clamped())
+            }
+            Self::hsl(h, s, l) => Self::hsl(h.wrapped_hue(), s.clamped(), l.clamped()),
+            Self::hsla(h, s, l, a) => {
+                Self::hsla(h.wrapped_hue(), s.clamped(), l.clamped(), a.clamped())
+            }
+            named => named.clone(),
+        }
+    }
+
+    /// An rgb color, erroring instead of clamping if a channel is out of range
+    pub fn try_rgb(r: ColorChannel, g: ColorChannel, b: ColorChannel) -> Result<Self, String> {
+        for (channel, name) in [(&r, "r"), (&g, "g"), (&b, "b")] {
+            channel.check_range(name)?;
+        }
+        Ok(Self::rgb(r, g, b))
+    }
+
+    /// An rgba color, erroring instead of clamping if a channel is out of range
+    pub fn try_rgba(
+        r: ColorChannel,
+        g: ColorChannel,
+        b: ColorChannel,
+        a: ColorChannel,
+    ) -> Result<Self, String> {
+        for (channel, name) in [(&r, "r"), (&g, "g"), (&b, "b"), (&a, "a")] {
+            channel.check_range(name)?;
+        }
+        Ok(Self::rgba(r, g, b, a))
+    }
+
+    /// An hsl color, erroring instead of clamping if a channel is out of range. Any hue that is
+    /// a number is valid, since hues wrap around
+    pub fn try_hsl(h: Rotation, s: ColorChannel, l: ColorChannel) -> Result<Self, String> {
+        if !h.to_float_0_1().is_finite() {
+            return Err(format!("color hue is out of range: {}", h));
+        }
+        for (channel, name) in [(&s, "s"), (&l, "l")] {
+            channel.check_range(name)?;
+        }
+        Ok(Self::hsl(h, s, l))
+    }
+
+    /// An hsla color, erroring instead of clamping if a channel is out of range
+    pub fn try_hsla(
+        h: Rotation,
+        s: ColorChannel,
+        l: ColorChannel,
+        a: ColorChannel,
+    ) -> Result<Self, String> {
+        if !h.to_float_0_1().is_finite() {
+            return Err(format!("color hue is out of range: {}", h));
+        }
+        for (channel, name) in [(&s, "s"), (&l, "l"), (&a, "a")] {
+            channel.check_range(name)?;
+        }
+        Ok(Self::hsla(h, s, l, a))
+    }
+}
+
+/// Maps values that aren't numbers to 0
+pub(crate) fn sanitize(value: f64) -> f64 {
+    if value.is_nan() {
+        0.0
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Color, ColorChannel, Numeric, Rotation};
+
+    fn int(value: f64) -> ColorChannel {
+        ColorChannel::Integer(Numeric::F64(value))
+    }
+
+    fn percent(value: f64) -> ColorChannel {
+        ColorChannel::Percent(Numeric::F64(value))
+    }
+
+    #[test]
+    fn out_of_range_channels_are_clamped() {
+        let color = Color::rgba(int(300.0), int(-1.0), percent(50.0), percent(250.0)).clamped();
+        assert_eq!(
+            color,
+            Color::rgba(int(255.0), int(0.0), percent(50.0), percent(100.0))
+        );
+        assert_eq!(color.to_rgba_0_1(), [1.0, 0.0, 0.5, 1.0]);
+        assert_eq!(int(f64::NAN).clamped(), int(0.0));
+    }
+
+    #[test]
+    fn hues_wrap_around() {
+        let hue = |deg: f64| Rotation::Degrees(Numeric::F64(deg));
+        assert_eq!(hue(420.0).wrapped_hue(), hue(60.0));
+        assert_eq!(hue(-90.0).wrapped_hue(), hue(270.0));
+        let wrapped = Color::hsl(hue(480.0), percent(100.0), percent(50.0));
+        let color = Color::hsl(hue(120.0), percent(100.0), percent(50.0));
+        assert_eq!(wrapped.to_hex_string(), color.to_hex_string());
+    }
+
+    #[test]
+    fn try_constructors_reject_out_of_range_channels() {
+        assert!(Color::try_rgba(int(10.0), int(20.0), int(30.0), percent(50.0)).is_ok());
+        let err = Color::try_rgba(int(10.0), int(20.0), int(30.0), percent(300.0)).unwrap_err();
+        assert_eq!(
+            err,
+            "color channel a is out of range: 300.00% (expected 0% to 100%)"
+        );
+        assert!(Color::try_rgb(int(256.0), int(0.0), int(0.0)).is_err());
+        let hue = Rotation::Degrees(Numeric::F64(f64::INFINITY));
+        assert!(Color::try_hsla(hue, percent(50.0), percent(50.0), percent(100.0)).is_err());
+    }
+}
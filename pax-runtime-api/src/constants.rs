@@ -64,5 +64,7 @@ pub const COMMON_PROPERTIES_TYPE: &[(&str, &str)] = &[
     ("transform", "pax_engine::api::Transform2D"),
     ("width", "pax_engine::api::Size"),
     ("height", "pax_engine::api::Size"),
+    ("unclippable", "bool"),
+    ("text_style", "pax_std::core::text::TextStyle"),
     ("_raycastable", "bool"),
 ];
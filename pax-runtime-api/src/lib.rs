@@ -1,13 +1,15 @@
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::Display;
-use std::ops::{Add, Deref, Mul, Neg, Sub};
+use std::ops::{Add, Deref, Div, Mul, Neg, Sub};
 use std::time::Instant;
 
 use crate::math::Space;
 use kurbo::BezPath;
 pub use pax_message::*;
 pub use pax_value::numeric::Numeric;
-pub use pax_value::{CoercionRules, ImplToFromPaxAny, PaxValue, ToPaxValue};
+pub use pax_value::{
+    check_coercion, Coercibility, CoercionRules, ImplToFromPaxAny, PaxValue, ToPaxValue,
+};
 use piet::{PaintBrush, UnitPoint};
 use properties::{PropertyValue, UntypedProperty};
 
@@ -21,8 +23,10 @@ pub trait Store: 'static {}
 use std::cell::Cell;
 use std::rc::{Rc, Weak};
 
+mod color_gamut;
 pub mod constants;
 pub mod math;
+mod named_colors;
 pub mod pax_value;
 pub mod properties;
 
@@ -241,7 +245,9 @@ pub struct TouchEnd {
 pub struct KeyboardEventArgs {
     pub key: String,
     pub modifiers: Vec<ModifierKey>,
+    /// Whether the event is an automatic repeat of a key being held down
     pub is_repeat: bool,
+    pub location: KeyLocation,
 }
 
 /// User is pressing a key.
@@ -315,6 +321,27 @@ impl From<&ModifierKeyMessage> for ModifierKey {
     }
 }
 
+/// Where on the keyboard a key is, distinguishing e.g. left and right shift, or the digits of
+/// the numpad from those above the letters
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyLocation {
+    Standard,
+    Left,
+    Right,
+    Numpad,
+}
+
+impl From<&KeyLocationMessage> for KeyLocation {
+    fn from(value: &KeyLocationMessage) -> Self {
+        match value {
+            KeyLocationMessage::Standard => KeyLocation::Standard,
+            KeyLocationMessage::Left => KeyLocation::Left,
+            KeyLocationMessage::Right => KeyLocation::Right,
+            KeyLocationMessage::Numpad => KeyLocation::Numpad,
+        }
+    }
+}
+
 /// User clicks a mouse button over an element.
 #[derive(Clone)]
 pub struct Click {
@@ -376,6 +403,10 @@ pub struct TextboxInput {
 #[derive(Clone)]
 pub struct ButtonClick {}
 
+/// A video played to its end (and isn't looping).
+#[derive(Clone)]
+pub struct VideoEnded {}
+
 /// User presses a mouse button over an element.
 #[derive(Clone)]
 pub struct MouseDown {
@@ -437,7 +468,13 @@ impl Neg for Size {
 
 impl Add for Size {
     type Output = Size;
+    /// Sizes of the same unit add up to that unit, others to a `Size::Combined`
     fn add(self, rhs: Self) -> Self::Output {
+        match (self, rhs) {
+            (Size::Pixels(a), Size::Pixels(b)) => return Size::Pixels(a + b),
+            (Size::Percent(a), Size::Percent(b)) => return Size::Percent(a + b),
+            _ => (),
+        }
         let mut pixel_component: Numeric = Default::default();
         let mut percent_component: Numeric = Default::default();
 
@@ -484,7 +521,13 @@ impl Sub<Size> for Percent {
 
 impl Sub for Size {
     type Output = Size;
+    /// Like `add`, sizes of the same unit keep it
     fn sub(self, rhs: Self) -> Self::Output {
+        match (self, rhs) {
+            (Size::Pixels(a), Size::Pixels(b)) => return Size::Pixels(a - b),
+            (Size::Percent(a), Size::Percent(b)) => return Size::Percent(a - b),
+            _ => (),
+        }
         let mut pixel_component: Numeric = Default::default();
         let mut percent_component: Numeric = Default::default();
 
@@ -508,12 +551,30 @@ impl Sub for Size {
     }
 }
 
+impl Mul<f64> for Size {
+    type Output = Size;
+    fn mul(self, rhs: f64) -> Self::Output {
+        let rhs = Numeric::F64(rhs);
+        match self {
+            Size::Pixels(pix) => Size::Pixels(pix * rhs),
+            Size::Percent(per) => Size::Percent(per * rhs),
+            Size::Combined(pix, per) => Size::Combined(pix * rhs, per * rhs),
+        }
+    }
+}
+
 impl Size {
     #[allow(non_snake_case)]
     pub fn ZERO() -> Self {
         Size::Pixels(Numeric::F64(0.0))
     }
 
+    /// `pixels` plus `percent` of the parent's bounds, e.g. `Size::combined(-10, Percent(100.into()))`
+    /// for `100% - 10px`
+    pub fn combined(pixels: impl Into<Numeric>, percent: Percent) -> Self {
+        Size::Combined(pixels.into(), percent.0)
+    }
+
     /// Returns the wrapped percent value normalized as a float, such that 100% => 1.0.
     /// Panics if wrapped type is not a percentage.
     pub fn expect_percent(&self) -> f64 {
@@ -600,6 +661,10 @@ pub struct CommonProperties {
     pub rotate: Property<Option<Rotation>>,
     pub transform: Property<Option<Transform2D>>,
     pub unclippable: Property<Option<bool>>,
+    /// Defaults for the text styles of descendant text nodes, as an object with some of the
+    /// fields of a text style (e.g. `{font_size: 14px, fill: RED}`). Kept untyped since the
+    /// text types live outside the engine, see `ExpandedNode::resolved_text_style`.
+    pub text_style: Property<Option<PaxValue>>,
     pub _raycastable: Property<Option<bool>>,
     pub _suspended: Property<Option<bool>>,
 }
@@ -655,6 +720,7 @@ impl CommonProperties {
             rotate,
             transform,
             unclippable,
+            text_style,
             _raycastable,
             _suspended,
             // NOTE: remember to add an entry to the hashmap bellow as well
@@ -717,6 +783,10 @@ impl CommonProperties {
                 "unclippable".to_string(),
                 Variable::new_from_typed_property(unclippable.clone()),
             ),
+            (
+                "text_style".to_string(),
+                Variable::new_from_typed_property(text_style.clone()),
+            ),
             (
                 "_raycastable".to_string(),
                 Variable::new_from_typed_property(_raycastable.clone()),
@@ -747,6 +817,8 @@ pub struct TransitionManager<T> {
     transition_checkpoint_value: T,
     /// The time the current transition started
     origin_frames_elapsed: u64,
+    /// The time the value was last eased to
+    last_frames_elapsed: u64,
 }
 
 #[cfg(debug_assertions)]
@@ -765,6 +837,7 @@ impl<T: Interpolatable> TransitionManager<T> {
             queue: VecDeque::new(),
             transition_checkpoint_value: value,
             origin_frames_elapsed: current_time,
+            last_frames_elapsed: current_time,
         }
     }
 
@@ -779,8 +852,31 @@ impl<T: Interpolatable> TransitionManager<T> {
         self.origin_frames_elapsed = current_time;
     }
 
+    /// Aborts all queued transitions, holding the value last eased to as the checkpoint value
+    pub fn cancel(&mut self) {
+        let last_fe = self.last_frames_elapsed;
+        if let Some(eased_value) = self.compute_eased_value(last_fe) {
+            self.transition_checkpoint_value = eased_value;
+        }
+        self.queue.clear();
+        self.origin_frames_elapsed = last_fe;
+    }
+
+    /// Jumps to the ending value of the last queued transition and clears the queue
+    pub fn skip_to_end(&mut self) {
+        if let Some(last) = self.queue.pop_back() {
+            self.transition_checkpoint_value = last.ending_value;
+        }
+        self.queue.clear();
+    }
+
+    pub fn get_checkpoint_value(&self) -> &T {
+        &self.transition_checkpoint_value
+    }
+
     pub fn compute_eased_value(&mut self, frames_elapsed: u64) -> Option<T> {
         let global_fe = frames_elapsed;
+        self.last_frames_elapsed = global_fe;
         let origin_fe = &mut self.origin_frames_elapsed;
 
         // Fast-forward transitions that have already passed
@@ -1040,6 +1136,51 @@ impl Interpolatable for Percent {
     }
 }
 
+impl Percent {
+    pub fn to_f64(&self) -> f64 {
+        self.0.to_float()
+    }
+
+    /// Restricts this percentage to `min..=max` (in percent, e.g. `0.0` and `100.0`).
+    /// Panics if `min > max`, like `f64::clamp`
+    pub fn clamp(&self, min: f64, max: f64) -> Percent {
+        let value = self.to_f64();
+        if value.clamp(min, max) == value {
+            self.clone()
+        } else {
+            Percent(Numeric::F64(value.clamp(min, max)))
+        }
+    }
+}
+
+impl Add for Percent {
+    type Output = Percent;
+    fn add(self, rhs: Percent) -> Self::Output {
+        Percent(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Percent {
+    type Output = Percent;
+    fn sub(self, rhs: Percent) -> Self::Output {
+        Percent(self.0 - rhs.0)
+    }
+}
+
+impl Mul<f64> for Percent {
+    type Output = Percent;
+    fn mul(self, rhs: f64) -> Self::Output {
+        Percent(self.0 * Numeric::F64(rhs))
+    }
+}
+
+impl Div<f64> for Percent {
+    type Output = Percent;
+    fn div(self, rhs: f64) -> Self::Output {
+        Percent(self.0 / Numeric::F64(rhs))
+    }
+}
+
 impl From<f64> for ColorChannel {
     fn from(value: f64) -> Self {
         Numeric::F64(value).into()
@@ -1110,17 +1251,25 @@ impl From<Numeric> for ColorChannel {
 impl ColorChannel {
     ///Normalizes this ColorChannel as a float [0.0, 1.0]
     pub fn to_float_0_1(&self) -> f64 {
-        match self {
-            Self::Percent(per) => (per.to_float() / 100.0).clamp(0_f64, 1_f64),
+        let value = match self {
+            Self::Percent(per) => per.to_float() / 100.0,
             Self::Integer(zero_to_255) => {
                 let f_zero: f64 = (*zero_to_255).to_float();
-                (f_zero / 255.0_f64).clamp(0_f64, 1_f64)
+                f_zero / 255.0_f64
             }
             Self::Rotation(rot) => rot.to_float_0_1(),
-        }
+        };
+        color_gamut::sanitize(value).clamp(0_f64, 1_f64)
     }
 }
 
+/// A color, either named or built from channels.
+///
+/// Channels are only meaningful within their ranges: `0..=255` for integers, `0%..=100%` for
+/// percentages, and any hue, which wraps around `0deg..360deg`. Colors are rendered with their
+/// channels clamped to these ranges on every backend; the `rgb`, `rgba`, `hsl` and `hsla`
+/// functions of pax expressions clamp them on construction (see `Color::clamped`), and
+/// `Color::try_rgba` and friends error on out of range channels instead.
 #[allow(non_camel_case_types)]
 #[derive(Default, Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub enum Color {
@@ -1256,11 +1405,13 @@ impl Color {
     pub fn to_rgba_0_1(&self) -> [f64; 4] {
         match self {
             Self::hsla(h, s, l, a) => {
-                let rgb = hsl_to_rgb(h.to_float_0_1(), s.to_float_0_1(), l.to_float_0_1());
+                let h = h.wrapped_hue().to_float_0_1();
+                let rgb = hsl_to_rgb(h, s.to_float_0_1(), l.to_float_0_1());
                 [rgb[0], rgb[1], rgb[2], a.to_float_0_1()]
             }
             Self::hsl(h, s, l) => {
-                let rgb = hsl_to_rgb(h.to_float_0_1(), s.to_float_0_1(), l.to_float_0_1());
+                let h = h.wrapped_hue().to_float_0_1();
+                let rgb = hsl_to_rgb(h, s.to_float_0_1(), l.to_float_0_1());
                 [rgb[0], rgb[1], rgb[2], 1.0]
             }
             Self::rgba(r, g, b, a) => [
@@ -1667,6 +1818,11 @@ impl HelperFunctions for PathElement {}
 pub struct Stroke {
     pub color: Property<Color>,
     pub width: Property<Size>,
+    /// Draw the stroke exactly one physical pixel wide, aligned to the device
+    /// pixel grid, ignoring `width`. Keeps dividers and outlines crisp on
+    /// high-DPI screens, where a 1px stroke spans several physical pixels.
+    #[serde(default)]
+    pub hairline: Property<bool>,
 }
 
 impl Default for Stroke {
@@ -1674,13 +1830,27 @@ impl Default for Stroke {
         Self {
             color: Default::default(),
             width: Property::new(Size::Pixels(Numeric::F64(0.0))),
+            hairline: Default::default(),
+        }
+    }
+}
+
+impl Stroke {
+    /// Width of the stroke in logical pixels, given the device pixel ratio.
+    pub fn resolve_width(&self, device_pixel_ratio: f64) -> f64 {
+        if self.hairline.get() {
+            1.0 / device_pixel_ratio.max(f64::EPSILON)
+        } else {
+            self.width.get().expect_pixels().to_float()
         }
     }
 }
 
 impl PartialEq for Stroke {
     fn eq(&self, other: &Self) -> bool {
-        self.color.get() == other.color.get() && self.width.get() == other.width.get()
+        self.color.get() == other.color.get()
+            && self.width.get() == other.width.get()
+            && self.hairline.get() == other.hairline.get()
     }
 }
 
@@ -1990,3 +2160,104 @@ impl Variable {
         (self.convert_to_pax_value)(self.untyped_property.clone())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_to_f64() {
+        assert_eq!(Percent(Numeric::I64(50)).to_f64(), 50.0);
+        assert_eq!(Percent(Numeric::F64(12.5)).to_f64(), 12.5);
+    }
+
+    #[test]
+    fn percent_clamp() {
+        assert_eq!(
+            Percent(Numeric::F64(-10.0)).clamp(0.0, 100.0),
+            Percent(Numeric::F64(0.0))
+        );
+        assert_eq!(
+            Percent(Numeric::F64(150.0)).clamp(0.0, 100.0),
+            Percent(Numeric::F64(100.0))
+        );
+        assert_eq!(
+            Percent(Numeric::I64(0)).clamp(0.0, 100.0),
+            Percent(Numeric::I64(0))
+        );
+        assert_eq!(
+            Percent(Numeric::I64(100)).clamp(0.0, 100.0),
+            Percent(Numeric::I64(100))
+        );
+        assert_eq!(
+            Percent(Numeric::I64(42)).clamp(0.0, 100.0),
+            Percent(Numeric::I64(42))
+        );
+    }
+
+    #[test]
+    fn percent_arithmetic() {
+        let a = Percent(Numeric::I64(30));
+        let b = Percent(Numeric::F64(12.5));
+        assert_eq!(a.clone() + b.clone(), Percent(Numeric::F64(42.5)));
+        assert_eq!(a.clone() - b.clone(), Percent(Numeric::F64(17.5)));
+        assert_eq!(a.clone() * 2.0, Percent(Numeric::F64(60.0)));
+        assert_eq!(a / 4.0, Percent(Numeric::F64(7.5)));
+    }
+
+    #[test]
+    fn percent_division_by_zero() {
+        assert!((Percent(Numeric::I64(50)) / 0.0).to_f64().is_infinite());
+        assert!((Percent(Numeric::F64(0.0)) / 0.0).to_f64().is_nan());
+    }
+
+    #[test]
+    fn size_arithmetic_keeps_matching_units() {
+        let px = |v: i64| Size::Pixels(Numeric::I64(v));
+        let pct = |v: i64| Size::Percent(Numeric::I64(v));
+        assert_eq!(px(10) + px(5), px(15));
+        assert_eq!(px(10) - px(5), px(5));
+        assert_eq!(pct(40) + pct(20), pct(60));
+        assert_eq!(pct(40) - pct(20), pct(20));
+        assert_eq!(px(10) * 1.5, Size::Pixels(Numeric::F64(15.0)));
+        assert_eq!(pct(40) * 0.5, Size::Percent(Numeric::F64(20.0)));
+    }
+
+    #[test]
+    fn size_arithmetic_combines_pixels_and_percent() {
+        let combined = Size::Pixels(Numeric::I64(10)) + Size::Percent(Numeric::I64(50));
+        assert_eq!(combined, Size::combined(10, Percent(Numeric::I64(50))));
+        assert_eq!(
+            Size::Percent(Numeric::I64(100)) - Size::Pixels(Numeric::I64(20)),
+            Size::combined(-20, Percent(Numeric::I64(100)))
+        );
+        assert_eq!(
+            Size::Pixels(Numeric::I64(5)) + Percent(Numeric::I64(25)),
+            Size::combined(5, Percent(Numeric::I64(25)))
+        );
+        assert_eq!(
+            combined * 2.0,
+            Size::combined(20.0, Percent(Numeric::F64(100.0)))
+        );
+    }
+
+    #[test]
+    fn size_evaluates_against_bounds() {
+        let bounds = (200.0, 50.0);
+        assert_eq!(
+            Size::Pixels(Numeric::I64(30)).evaluate(bounds, Axis::X),
+            30.0
+        );
+        assert_eq!(
+            Size::Percent(Numeric::I64(50)).evaluate(bounds, Axis::X),
+            100.0
+        );
+        assert_eq!(
+            Size::Percent(Numeric::I64(50)).evaluate(bounds, Axis::Y),
+            25.0
+        );
+        let combined = Size::combined(-10, Percent(Numeric::I64(100)));
+        assert_eq!(combined.evaluate(bounds, Axis::X), 190.0);
+        assert_eq!(combined.evaluate(bounds, Axis::Y), 40.0);
+    }
+}
@@ -252,6 +252,26 @@ impl PaxValue {
         }
     }
 
+    pub fn div_euclid(self, rhs: Self) -> Self {
+        match (self, rhs) {
+            (PaxValue::Numeric(a), PaxValue::Numeric(b)) => a.div_euclid(b).to_pax_value(),
+            (a, b) => {
+                log::warn!("euclidean division not valid between {:?} and {:?}", a, b);
+                PaxValue::default()
+            }
+        }
+    }
+
+    pub fn rem_euclid(self, rhs: Self) -> Self {
+        match (self, rhs) {
+            (PaxValue::Numeric(a), PaxValue::Numeric(b)) => a.rem_euclid(b).to_pax_value(),
+            (a, b) => {
+                log::warn!("euclidean remainder not valid between {:?} and {:?}", a, b);
+                PaxValue::default()
+            }
+        }
+    }
+
     pub fn min(self, rhs: Self) -> Self {
         match (self, rhs) {
             (PaxValue::Numeric(a), PaxValue::Numeric(b)) => a.min(b).to_pax_value(),
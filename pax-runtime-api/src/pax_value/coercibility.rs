@@ -0,0 +1,225 @@
+// ------------------------------- Coercibility ----------------------------------
+// compile-time counterpart of the coercion rules: whether a literal in a template can be
+// coerced into a property of a given type, answered by running the runtime's own rules
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::{
+    CoercionRules, Color, ColorChannel, Fill, GradientStop, LinearGradient, Numeric, PathElement,
+    PaxValue, Percent, RadialGradient, Rotation, Size, Stroke, Transform2D,
+};
+
+/// Outcome of [`check_coercion`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Coercibility {
+    Coercible,
+    /// Coercible, but information is lost on the way, e.g. a float literal into a `u32`
+    Lossy(String),
+    /// Rejected by the target type's `CoercionRules`, with its error
+    NotCoercible(String),
+    /// The target type has no coercion rules known here, e.g. a userland struct
+    Unknown,
+}
+
+/// Whether `value` can be coerced into a property of type `type_path`, e.g.
+/// `pax_engine::api::Size` or `std::vec::Vec<pax_engine::api::Color>`.  Dispatches to the
+/// [`CoercionRules`] of the type the runtime would use, so compile-time checks and runtime
+/// coercion can't disagree
+pub fn check_coercion(type_path: &str, value: &PaxValue) -> Coercibility {
+    let (name, argument) = match type_path.trim().split_once('<') {
+        Some((outer, inner)) => (last_segment(outer), inner.strip_suffix('>')),
+        None => (last_segment(type_path), None),
+    };
+    match (name, argument) {
+        ("Vec", Some(element_type)) => match Vec::<PaxValue>::try_coerce(value.clone()) {
+            Ok(elements) => elements
+                .iter()
+                .map(|element| check_coercion(element_type, element))
+                .fold(Coercibility::Coercible, combine),
+            Err(e) => Coercibility::NotCoercible(e),
+        },
+        ("Option", Some(inner_type)) => match Option::<PaxValue>::try_coerce(value.clone()) {
+            Ok(Some(inner)) => check_coercion(inner_type, &inner),
+            Ok(None) => Coercibility::Coercible,
+            Err(e) => Coercibility::NotCoercible(e),
+        },
+        (_, Some(_)) => Coercibility::Unknown,
+        ("u8", None) => check_integer::<u8>(value),
+        ("u16", None) => check_integer::<u16>(value),
+        ("u32", None) => check_integer::<u32>(value),
+        ("u64", None) => check_integer::<u64>(value),
+        ("usize", None) => check_integer::<usize>(value),
+        ("i8", None) => check_integer::<i8>(value),
+        ("i16", None) => check_integer::<i16>(value),
+        ("i32", None) => check_integer::<i32>(value),
+        ("i64", None) => check_integer::<i64>(value),
+        ("isize", None) => check_integer::<isize>(value),
+        ("bool", None) => check_with_rules_of::<bool>(value),
+        ("f32", None) => check_with_rules_of::<f32>(value),
+        ("f64", None) => check_with_rules_of::<f64>(value),
+        ("String", None) => check_with_rules_of::<String>(value),
+        ("Numeric", None) => check_with_rules_of::<Numeric>(value),
+        ("Size", None) => check_with_rules_of::<Size>(value),
+        ("Percent", None) => check_with_rules_of::<Percent>(value),
+        ("Rotation", None) => check_with_rules_of::<Rotation>(value),
+        ("Color", None) => check_with_rules_of::<Color>(value),
+        ("ColorChannel", None) => check_with_rules_of::<ColorChannel>(value),
+        ("Fill", None) => check_with_rules_of::<Fill>(value),
+        ("Stroke", None) => check_with_rules_of::<Stroke>(value),
+        ("LinearGradient", None) => check_with_rules_of::<LinearGradient>(value),
+        ("RadialGradient", None) => check_with_rules_of::<RadialGradient>(value),
+        ("GradientStop", None) => check_with_rules_of::<GradientStop>(value),
+        ("PathElement", None) => check_with_rules_of::<PathElement>(value),
+        ("Transform2D", None) => check_with_rules_of::<Transform2D>(value),
+        _ => Coercibility::Unknown,
+    }
+}
+
+fn last_segment(type_path: &str) -> &str {
+    type_path.trim().rsplit("::").next().unwrap_or_default()
+}
+
+fn check_with_rules_of<T: CoercionRules>(value: &PaxValue) -> Coercibility {
+    // some rules unwrap the fields of object literals, which a template may leave out
+    match catch_unwind(AssertUnwindSafe(|| T::try_coerce(value.clone()))) {
+        Ok(Ok(_)) => Coercibility::Coercible,
+        Ok(Err(e)) => Coercibility::NotCoercible(e),
+        Err(_) => Coercibility::NotCoercible(format!(
+            "{} is missing fields of {}",
+            value,
+            std::any::type_name::<T>()
+        )),
+    }
+}
+
+/// Integers truncate float literals coerced into them
+fn check_integer<T: CoercionRules>(value: &PaxValue) -> Coercibility {
+    match check_with_rules_of::<T>(value) {
+        Coercibility::Coercible if is_float_literal(value) => Coercibility::Lossy(format!(
+            "{} is truncated when coerced into a {}",
+            value,
+            std::any::type_name::<T>()
+        )),
+        other => other,
+    }
+}
+
+fn is_float_literal(value: &PaxValue) -> bool {
+    match value {
+        PaxValue::Numeric(n) => n.is_float(),
+        PaxValue::Option(opt) => opt.as_ref().as_ref().is_some_and(is_float_literal),
+        _ => false,
+    }
+}
+
+/// Combines the outcomes for the elements of a collection, the worst one winning
+fn combine(acc: Coercibility, next: Coercibility) -> Coercibility {
+    match (acc, next) {
+        (e @ Coercibility::NotCoercible(_), _) | (_, e @ Coercibility::NotCoercible(_)) => e,
+        (Coercibility::Unknown, _) | (_, Coercibility::Unknown) => Coercibility::Unknown,
+        (l @ Coercibility::Lossy(_), _) | (_, l @ Coercibility::Lossy(_)) => l,
+        _ => Coercibility::Coercible,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn int(i: i64) -> PaxValue {
+        PaxValue::Numeric(Numeric::I64(i))
+    }
+
+    fn float(f: f64) -> PaxValue {
+        PaxValue::Numeric(Numeric::F64(f))
+    }
+
+    /// `check_coercion` must accept exactly what the `CoercionRules` of the type accept
+    fn assert_agrees<T: CoercionRules>(type_path: &str, value: PaxValue) {
+        let coercible = !matches!(
+            check_coercion(type_path, &value),
+            Coercibility::NotCoercible(_) | Coercibility::Unknown
+        );
+        assert_eq!(
+            coercible,
+            T::try_coerce(value.clone()).is_ok(),
+            "{} into {}",
+            value,
+            type_path
+        );
+    }
+
+    #[test]
+    fn agrees_with_coercion_rules() {
+        let values = [
+            int(3),
+            float(2.5),
+            PaxValue::Bool(true),
+            PaxValue::String("lots".to_string()),
+            PaxValue::Size(Size::Pixels(Numeric::I64(10))),
+            PaxValue::Percent(Percent(Numeric::F64(50.0))),
+            PaxValue::Rotation(Rotation::Degrees(Numeric::I64(90))),
+            PaxValue::Color(Box::new(Color::RED)),
+            PaxValue::Vec(vec![int(1), int(2)]),
+            PaxValue::Option(Box::new(Some(int(1)))),
+            PaxValue::Option(Box::new(None)),
+        ];
+        for value in values {
+            assert_agrees::<bool>("bool", value.clone());
+            assert_agrees::<u32>("u32", value.clone());
+            assert_agrees::<f64>("f64", value.clone());
+            assert_agrees::<String>("std::string::String", value.clone());
+            assert_agrees::<Numeric>("pax_engine::api::Numeric", value.clone());
+            assert_agrees::<Size>("pax_engine::api::Size", value.clone());
+            assert_agrees::<Percent>("pax_engine::api::Percent", value.clone());
+            assert_agrees::<Rotation>("pax_engine::api::Rotation", value.clone());
+            assert_agrees::<Color>("pax_engine::api::Color", value.clone());
+            assert_agrees::<ColorChannel>("pax_engine::api::ColorChannel", value.clone());
+            assert_agrees::<Fill>("pax_engine::api::Fill", value.clone());
+            assert_agrees::<Stroke>("pax_engine::api::Stroke", value.clone());
+            assert_agrees::<Vec<Size>>("std::vec::Vec<pax_engine::api::Size>", value.clone());
+            assert_agrees::<Option<Numeric>>(
+                "std::option::Option<pax_engine::api::Numeric>",
+                value.clone(),
+            );
+        }
+    }
+
+    #[test]
+    fn float_into_integer_is_lossy() {
+        assert!(matches!(
+            check_coercion("i32", &float(2.5)),
+            Coercibility::Lossy(_)
+        ));
+        assert!(matches!(
+            check_coercion(
+                "std::vec::Vec<usize>",
+                &PaxValue::Vec(vec![int(1), float(1.5)])
+            ),
+            Coercibility::Lossy(_)
+        ));
+        assert_eq!(check_coercion("i32", &int(2)), Coercibility::Coercible);
+        assert_eq!(check_coercion("f64", &float(2.5)), Coercibility::Coercible);
+    }
+
+    #[test]
+    fn unknown_types_are_not_checked() {
+        assert_eq!(
+            check_coercion("crate::CardStyle", &int(1)),
+            Coercibility::Unknown
+        );
+        assert_eq!(
+            check_coercion("std::collections::HashMap<String><u8>", &int(1)),
+            Coercibility::Unknown
+        );
+    }
+
+    #[test]
+    fn malformed_object_literals_are_not_coercible() {
+        let stroke = PaxValue::Object([("width".to_string(), int(2))].into_iter().collect());
+        assert!(matches!(
+            check_coercion("pax_engine::api::Stroke", &stroke),
+            Coercibility::NotCoercible(_)
+        ));
+    }
+}
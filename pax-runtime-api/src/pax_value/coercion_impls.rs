@@ -2,6 +2,7 @@
 // default coercion only allows a single type: the type expected
 // custom coercion rules can be implemented by a type
 
+use std::collections::HashMap;
 use std::ops::Range;
 
 use crate::{
@@ -11,6 +12,29 @@ use crate::{
     Percent, Property, RadialGradient, Rotation, Size, Stroke, Transform2D,
 };
 
+/// Field `key` of an object being coerced into `type_name`, or an error if it's missing
+fn field<'a>(
+    map: &'a HashMap<String, PaxValue>,
+    key: &str,
+    type_name: &str,
+) -> Result<&'a PaxValue, String> {
+    map.get(key)
+        .ok_or_else(|| format!("{} is missing a {}", type_name, key))
+}
+
+/// Argument `index` of an enum variant being coerced into `type_name`, or an error if there
+/// are too few
+fn arg(args: &[PaxValue], index: usize, type_name: &str) -> Result<PaxValue, String> {
+    args.get(index).cloned().ok_or_else(|| {
+        format!(
+            "{} expects at least {} arguments, got {}",
+            type_name,
+            index + 1,
+            args.len()
+        )
+    })
+}
+
 // Default coercion rules:
 // call Into::<first param>::into() on contents of second enum variant
 impl_default_coercion_rule!(bool, PaxValue::Bool);
@@ -84,6 +108,15 @@ impl CoercionRules for Color {
     fn try_coerce(value: PaxValue) -> Result<Self, String> {
         match value {
             PaxValue::Color(color) => Ok(*color),
+            // CSS color names, e.g. "cornflowerblue", or hex codes, e.g. "#6495ED"
+            PaxValue::String(ref s) => Color::from_named(s)
+                .or_else(|| {
+                    let hex = s.strip_prefix('#').unwrap_or(s);
+                    ((hex.len() == 6 || hex.len() == 8)
+                        && hex.chars().all(|c| c.is_ascii_hexdigit()))
+                    .then(|| Color::from_hex(hex))
+                })
+                .ok_or_else(|| format!("{:?} can't be coerced into a Color", value)),
             _ => return Err(format!("{:?} can't be coerced into a Color", value)),
         }
     }
@@ -98,23 +131,28 @@ impl CoercionRules for PathElement {
             PaxValue::Enum(enum_name, enum_variant, values) => {
                 if enum_name == "PathElement" {
                     let mut values_itr = values.into_iter();
+                    let mut next = || {
+                        values_itr.next().ok_or_else(|| {
+                            format!("PathElement::{} is missing arguments", enum_variant)
+                        })
+                    };
                     match enum_variant.as_str() {
                         "Line" => Ok(PathElement::Line),
                         "Close" => Ok(PathElement::Close),
                         "Empty" => Ok(PathElement::Empty),
                         "Point" => Ok(PathElement::Point(
-                            Size::try_coerce(values_itr.next().unwrap())?,
-                            Size::try_coerce(values_itr.next().unwrap())?,
+                            Size::try_coerce(next()?)?,
+                            Size::try_coerce(next()?)?,
                         )),
                         "Quadratic" => Ok(PathElement::Quadratic(
-                            Size::try_coerce(values_itr.next().unwrap())?,
-                            Size::try_coerce(values_itr.next().unwrap())?,
+                            Size::try_coerce(next()?)?,
+                            Size::try_coerce(next()?)?,
                         )),
                         "Cubic" => Ok(PathElement::Cubic(Box::new((
-                            Size::try_coerce(values_itr.next().unwrap())?,
-                            Size::try_coerce(values_itr.next().unwrap())?,
-                            Size::try_coerce(values_itr.next().unwrap())?,
-                            Size::try_coerce(values_itr.next().unwrap())?,
+                            Size::try_coerce(next()?)?,
+                            Size::try_coerce(next()?)?,
+                            Size::try_coerce(next()?)?,
+                            Size::try_coerce(next()?)?,
                         )))),
                         _ => return Err(err),
                     }
@@ -136,15 +174,15 @@ impl CoercionRules for Fill {
             PaxValue::Color(color) => Fill::Solid(*color),
             PaxValue::Enum(_, variant, args) => match variant.as_str() {
                 "Solid" => {
-                    let color = Color::try_coerce(args[0].clone())?;
+                    let color = Color::try_coerce(arg(&args, 0, "Fill")?)?;
                     Fill::Solid(color)
                 }
                 "LinearGradient" => {
-                    let gradient = LinearGradient::try_coerce(args[0].clone())?;
+                    let gradient = LinearGradient::try_coerce(arg(&args, 0, "Fill")?)?;
                     Fill::LinearGradient(gradient)
                 }
                 "RadialGradient" => {
-                    let gradient = RadialGradient::try_coerce(args[0].clone())?;
+                    let gradient = RadialGradient::try_coerce(arg(&args, 0, "Fill")?)?;
                     Fill::RadialGradient(gradient)
                 }
                 _ => return Err(format!("{:?} can't be coerced into a Fill", pax_value)),
@@ -165,9 +203,9 @@ impl CoercionRules for LinearGradient {
     fn try_coerce(pax_value: PaxValue) -> Result<Self, String> {
         Ok(match pax_value.clone() {
             PaxValue::Object(map) => {
-                let start = map.get("start").unwrap().clone();
+                let start = field(&map, "start", "LinearGradient")?.clone();
                 let (s1, s2) = match start {
-                    PaxValue::Vec(vec) => {
+                    PaxValue::Vec(vec) if vec.len() == 2 => {
                         let s1 = Size::try_coerce(vec[0].clone())?;
                         let s2 = Size::try_coerce(vec[1].clone())?;
                         (s1, s2)
@@ -180,9 +218,9 @@ impl CoercionRules for LinearGradient {
                     }
                 };
 
-                let end = map.get("end").unwrap().clone();
+                let end = field(&map, "end", "LinearGradient")?.clone();
                 let (e1, e2) = match end {
-                    PaxValue::Vec(vec) => {
+                    PaxValue::Vec(vec) if vec.len() == 2 => {
                         let e1 = Size::try_coerce(vec[0].clone())?;
                         let e2 = Size::try_coerce(vec[1].clone())?;
                         (e1, e2)
@@ -194,7 +232,9 @@ impl CoercionRules for LinearGradient {
                         ))
                     }
                 };
-                let stops = Vec::<GradientStop>::try_coerce(map.get("stops").unwrap().clone())?;
+                let stops = Vec::<GradientStop>::try_coerce(
+                    field(&map, "stops", "LinearGradient")?.clone(),
+                )?;
                 LinearGradient {
                     start: (s1, s2),
                     end: (e1, e2),
@@ -225,9 +265,9 @@ impl CoercionRules for RadialGradient {
     fn try_coerce(pax_value: PaxValue) -> Result<Self, String> {
         Ok(match pax_value.clone() {
             PaxValue::Object(map) => {
-                let start = map.get("start").unwrap().clone();
+                let start = field(&map, "start", "RadialGradient")?.clone();
                 let (s1, s2) = match start {
-                    PaxValue::Vec(vec) => {
+                    PaxValue::Vec(vec) if vec.len() == 2 => {
                         let s1 = Size::try_coerce(vec[0].clone())?;
                         let s2 = Size::try_coerce(vec[1].clone())?;
                         (s1, s2)
@@ -240,9 +280,9 @@ impl CoercionRules for RadialGradient {
                     }
                 };
 
-                let end = map.get("end").unwrap().clone();
+                let end = field(&map, "end", "RadialGradient")?.clone();
                 let (e1, e2) = match end {
-                    PaxValue::Vec(vec) => {
+                    PaxValue::Vec(vec) if vec.len() == 2 => {
                         let e1 = Size::try_coerce(vec[0].clone())?;
                         let e2 = Size::try_coerce(vec[1].clone())?;
                         (e1, e2)
@@ -254,7 +294,7 @@ impl CoercionRules for RadialGradient {
                         ))
                     }
                 };
-                let radius = match map.get("radius").unwrap().clone() {
+                let radius = match field(&map, "radius", "RadialGradient")?.clone() {
                     PaxValue::Numeric(n) => n.to_float(),
                     _ => {
                         return Err(format!(
@@ -263,7 +303,9 @@ impl CoercionRules for RadialGradient {
                         ))
                     }
                 };
-                let stops = Vec::<GradientStop>::try_coerce(map.get("stops").unwrap().clone())?;
+                let stops = Vec::<GradientStop>::try_coerce(
+                    field(&map, "stops", "RadialGradient")?.clone(),
+                )?;
                 RadialGradient {
                     start: (s1, s2),
                     end: (e1, e2),
@@ -295,8 +337,8 @@ impl CoercionRules for GradientStop {
     fn try_coerce(pax_value: PaxValue) -> Result<Self, String> {
         Ok(match pax_value {
             PaxValue::Object(map) => {
-                let position = Size::try_coerce(map.get("position").unwrap().clone())?;
-                let color = Color::try_coerce(map.get("color").unwrap().clone())?;
+                let position = Size::try_coerce(field(&map, "position", "GradientStop")?.clone())?;
+                let color = Color::try_coerce(field(&map, "color", "GradientStop")?.clone())?;
                 GradientStop { position, color }
             }
             PaxValue::Option(mut o) => {
@@ -322,11 +364,26 @@ impl CoercionRules for Stroke {
             PaxValue::Color(color) => Stroke {
                 color: Property::new(*color),
                 width: Property::new(Size::Pixels(1.into())),
+                hairline: Default::default(),
             },
             PaxValue::Object(map) => {
-                let color = Property::new(Color::try_coerce(map.get("color").unwrap().clone())?);
-                let width = Property::new(Size::try_coerce(map.get("width").unwrap().clone())?);
-                Stroke { color, width }
+                let color =
+                    Property::new(Color::try_coerce(field(&map, "color", "Stroke")?.clone())?);
+                let hairline = match map.get("hairline") {
+                    Some(hairline) => bool::try_coerce(hairline.clone())?,
+                    None => false,
+                };
+                // width is ignored for hairlines, so it can be left out
+                let width = match map.get("width") {
+                    Some(width) => Size::try_coerce(width.clone())?,
+                    None if hairline => Size::Pixels(1.into()),
+                    None => return Err("Stroke is missing a width".to_string()),
+                };
+                Stroke {
+                    color,
+                    width: Property::new(width),
+                    hairline: Property::new(hairline),
+                }
             }
             PaxValue::Option(mut o) => {
                 if let Some(o) = o.take() {
@@ -348,15 +405,15 @@ impl CoercionRules for ColorChannel {
             PaxValue::Numeric(num) => ColorChannel::Integer(num),
             PaxValue::Enum(_, variant, args) => match variant.as_str() {
                 "Rotation" => {
-                    let rot = Rotation::try_coerce(args[0].clone())?;
+                    let rot = Rotation::try_coerce(arg(&args, 0, "ColorChannel")?)?;
                     ColorChannel::Rotation(rot)
                 }
                 "Integer" => {
-                    let num = Numeric::try_coerce(args[0].clone())?;
+                    let num = Numeric::try_coerce(arg(&args, 0, "ColorChannel")?)?;
                     ColorChannel::Integer(num)
                 }
                 "Percent" => {
-                    let num = Numeric::try_coerce(args[0].clone())?;
+                    let num = Numeric::try_coerce(arg(&args, 0, "ColorChannel")?)?;
                     ColorChannel::Percent(num)
                 }
                 _ => return Err(format!("{:?} can't be coerced into a ColorChannel", value)),
@@ -454,6 +511,9 @@ impl CoercionRules for Numeric {
         Ok(match pax_value {
             PaxValue::Bool(b) => (b as i32).into(),
             PaxValue::Numeric(n) => n.into(),
+            PaxValue::Size(Size::Combined(..)) => {
+                return Err(format!("{:?} can't be coerced into a Numeric", pax_value))
+            }
             PaxValue::Size(n) => n.into(),
             PaxValue::Option(mut opt) => {
                 if let Some(p) = opt.take() {
@@ -493,7 +553,7 @@ impl<T: CoercionRules> CoercionRules for Vec<T> {
 impl<T1: CoercionRules, T2: CoercionRules> CoercionRules for (T1, T2) {
     fn try_coerce(value: PaxValue) -> Result<Self, String> {
         match value {
-            PaxValue::Vec(vec) => {
+            PaxValue::Vec(vec) if vec.len() == 2 => {
                 let res: Result<T1, _> = T1::try_coerce(vec[0].clone());
                 let res2: Result<T2, _> = T2::try_coerce(vec[1].clone());
                 res.and_then(|v1| res2.map(|v2| (v1, v2)))
@@ -706,7 +766,7 @@ impl CoercionRules for Transform2 {
                 }
             }
             PaxValue::Object(map) => {
-                let m = Vec::<f64>::try_coerce(map.get("m").unwrap().clone())?;
+                let m = Vec::<f64>::try_coerce(field(&map, "m", "Transform2")?.clone())?;
                 if m.len() != 6 {
                     return Err(format!("expected 6 elements in coeffs, got {:?}", m.len()));
                 }
@@ -728,11 +788,52 @@ impl CoercionRules for Vector2 {
                 }
             }
             PaxValue::Object(map) => {
-                let x = f64::try_coerce(map.get("x").unwrap().clone())?;
-                let y = f64::try_coerce(map.get("y").unwrap().clone())?;
+                let x = f64::try_coerce(field(&map, "x", "Vector2")?.clone())?;
+                let y = f64::try_coerce(field(&map, "y", "Vector2")?.clone())?;
                 Vector2::new(x, y)
             }
             _ => return Err(format!("{:?} can't be coerced into a Vector2", value)),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ToPaxValue;
+
+    #[test]
+    fn stroke_hairline_round_trips() {
+        let object = |fields: Vec<(&str, PaxValue)>| {
+            PaxValue::Object(
+                fields
+                    .into_iter()
+                    .map(|(k, v)| (k.to_string(), v))
+                    .collect(),
+            )
+        };
+        let color = PaxValue::Color(Box::new(Color::RED));
+
+        let stroke = Stroke::try_coerce(object(vec![
+            ("color", color.clone()),
+            ("width", PaxValue::Size(Size::Pixels(3.into()))),
+        ]))
+        .unwrap();
+        assert!(!stroke.hairline.get());
+        assert_eq!(stroke.resolve_width(2.0), 3.0);
+
+        let stroke = Stroke::try_coerce(object(vec![
+            ("color", color.clone()),
+            ("hairline", PaxValue::Bool(true)),
+        ]))
+        .unwrap();
+        assert!(stroke.hairline.get());
+        assert_eq!(stroke.resolve_width(2.0), 0.5);
+        assert_eq!(
+            Stroke::try_coerce(stroke.clone().to_pax_value()).unwrap(),
+            stroke
+        );
+
+        assert!(Stroke::try_coerce(object(vec![("color", color)])).is_err());
+    }
+}
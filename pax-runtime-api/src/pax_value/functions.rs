@@ -91,6 +91,20 @@ fn mod_(args: Vec<PaxValue>) -> Result<PaxValue, String> {
     Ok(args[0].clone() % args[1].clone())
 }
 
+fn div_euclid(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 2 {
+        return Err("Expected 2 arguments for function div_euclid".to_string());
+    }
+    Ok(args[0].clone().div_euclid(args[1].clone()))
+}
+
+fn rem_euclid(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 2 {
+        return Err("Expected 2 arguments for function rem_euclid".to_string());
+    }
+    Ok(args[0].clone().rem_euclid(args[1].clone()))
+}
+
 fn rel_eq(args: Vec<PaxValue>) -> Result<PaxValue, String> {
     if args.len() != 2 {
         return Err("Expected 2 arguments for function rel_eq".to_string());
@@ -185,7 +199,7 @@ fn rgb(args: Vec<PaxValue>) -> Result<PaxValue, String> {
     let r = ColorChannel::try_coerce(args[0].clone())?;
     let g = ColorChannel::try_coerce(args[1].clone())?;
     let b = ColorChannel::try_coerce(args[2].clone())?;
-    Ok(Color::rgb(r, g, b).to_pax_value())
+    Ok(Color::rgb(r, g, b).clamped().to_pax_value())
 }
 
 fn rgba(args: Vec<PaxValue>) -> Result<PaxValue, String> {
@@ -196,7 +210,7 @@ fn rgba(args: Vec<PaxValue>) -> Result<PaxValue, String> {
     let g = ColorChannel::try_coerce(args[1].clone())?;
     let b = ColorChannel::try_coerce(args[2].clone())?;
     let a = ColorChannel::try_coerce(args[3].clone())?;
-    Ok(Color::rgba(r, g, b, a).to_pax_value())
+    Ok(Color::rgba(r, g, b, a).clamped().to_pax_value())
 }
 
 fn hsl(args: Vec<PaxValue>) -> Result<PaxValue, String> {
@@ -206,7 +220,7 @@ fn hsl(args: Vec<PaxValue>) -> Result<PaxValue, String> {
     let h = Rotation::try_coerce(args[0].clone())?;
     let s = ColorChannel::try_coerce(args[1].clone())?;
     let l = ColorChannel::try_coerce(args[2].clone())?;
-    Ok(Color::hsl(h, s, l).to_pax_value())
+    Ok(Color::hsl(h, s, l).clamped().to_pax_value())
 }
 
 fn hsla(args: Vec<PaxValue>) -> Result<PaxValue, String> {
@@ -217,7 +231,7 @@ fn hsla(args: Vec<PaxValue>) -> Result<PaxValue, String> {
     let s = ColorChannel::try_coerce(args[1].clone())?;
     let l = ColorChannel::try_coerce(args[2].clone())?;
     let a = ColorChannel::try_coerce(args[3].clone())?;
-    Ok(Color::hsla(h, s, l, a).to_pax_value())
+    Ok(Color::hsla(h, s, l, a).clamped().to_pax_value())
 }
 
 fn hex(args: Vec<PaxValue>) -> Result<PaxValue, String> {
@@ -255,6 +269,16 @@ impl Functions {
         register_function("Math".to_string(), "min".to_string(), Arc::new(min));
         register_function("Math".to_string(), "max".to_string(), Arc::new(max));
         register_function("Math".to_string(), "len".to_string(), Arc::new(len));
+        register_function(
+            "Math".to_string(),
+            "div_euclid".to_string(),
+            Arc::new(div_euclid),
+        );
+        register_function(
+            "Math".to_string(),
+            "rem_euclid".to_string(),
+            Arc::new(rem_euclid),
+        );
         // Colors
         register_function("Color".to_string(), "rgb".to_string(), Arc::new(rgb));
         register_function("Color".to_string(), "rgba".to_string(), Arc::new(rgba));
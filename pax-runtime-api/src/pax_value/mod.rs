@@ -2,16 +2,21 @@ use crate::{Color, Interpolatable, PathElement, Percent, Rotation, Size};
 use std::{any::Any, collections::HashMap, fmt::Display};
 
 use self::numeric::Numeric;
+pub use coercibility::{check_coercion, Coercibility};
 pub use coercion_impls::CoercionRules;
 use serde::{Deserialize, Serialize};
 
 mod arithmetic;
+mod coercibility;
 mod coercion_impls;
 pub mod functions;
 mod macros;
 pub mod numeric;
 mod to_from_impls;
 
+#[cfg(test)]
+mod tests;
+
 /// Container for all internal pax types
 /// Two important traits are related to this type:
 /// ToFromPaxValue - responsible for converting to and from specific types (u8,
@@ -50,7 +50,7 @@ impl PartialEq for Numeric {
     fn eq(&self, rhs: &Self) -> bool {
         match (self.is_float(), rhs.is_float()) {
             (false, false) => self.to_int() == rhs.to_int(),
-            _ => (self.to_float() - rhs.to_float()) < 1e-6,
+            _ => (self.to_float() - rhs.to_float()).abs() < 1e-6,
         }
     }
 }
@@ -61,16 +61,20 @@ impl Default for Numeric {
     }
 }
 
+// integer arithmetic panics on overflow (with overflow checks, as in debug builds), and division
+// and remainder on a zero divisor, fall back to float semantics (inf/NaN) in those cases instead
 macro_rules! impl_numeric_arith {
-    ($trait:ident, $method:ident, $op:tt) => {
+    ($trait:ident, $method:ident, $op:tt, $checked:ident) => {
         impl std::ops::$trait for &Numeric {
             type Output = Numeric;
 
             fn $method(self, rhs: Self) -> Self::Output {
-
                 // TBD: might want to be more granular here at some point
                 match (self.is_float(), rhs.is_float()) {
-                    (false, false) => Numeric::I64(self.to_int() $op rhs.to_int()),
+                    (false, false) => match self.to_int().$checked(rhs.to_int()) {
+                        Some(v) => Numeric::I64(v),
+                        None => Numeric::F64(self.to_float() $op rhs.to_float()),
+                    },
                     _ => Numeric::F64(self.to_float() $op rhs.to_float()),
                 }
             }
@@ -85,25 +89,26 @@ macro_rules! impl_numeric_arith {
     };
 }
 
-impl_numeric_arith!(Add, add, +);
-impl_numeric_arith!(Sub, sub, -);
-impl_numeric_arith!(Mul, mul, *);
-impl_numeric_arith!(Div, div, /);
-impl_numeric_arith!(Rem, rem, %);
+impl_numeric_arith!(Add, add, +, checked_add);
+impl_numeric_arith!(Sub, sub, -, checked_sub);
+impl_numeric_arith!(Mul, mul, *, checked_mul);
+impl_numeric_arith!(Div, div, /, checked_div);
+impl_numeric_arith!(Rem, rem, %, checked_rem);
 
 impl std::ops::Neg for Numeric {
     type Output = Self;
 
     fn neg(self) -> Self::Output {
         use Numeric::*;
+        // only the minimum of each type overflows
         match self {
-            I8(a) => I8(-a),
-            I16(a) => I16(-a),
-            I32(a) => I32(-a),
-            I64(a) => I64(-a),
+            I8(a) => a.checked_neg().map_or(F64(-(a as f64)), I8),
+            I16(a) => a.checked_neg().map_or(F64(-(a as f64)), I16),
+            I32(a) => a.checked_neg().map_or(F64(-(a as f64)), I32),
+            I64(a) => a.checked_neg().map_or(F64(-(a as f64)), I64),
             F32(a) => F32(-a),
             F64(a) => F64(-a),
-            ISize(a) => ISize(-a),
+            ISize(a) => a.checked_neg().map_or(F64(-(a as f64)), ISize),
             _ => panic!("tried to negate numeric that is unsigned"),
         }
     }
@@ -190,11 +195,42 @@ impl Numeric {
 
     pub fn pow(self, exp: Self) -> Self {
         match (self.is_float(), exp.is_float()) {
-            (false, false) => Numeric::I64(self.to_int().pow(exp.into())),
+            (false, false) => match u32::try_from(exp.to_int())
+                .ok()
+                .and_then(|exp| self.to_int().checked_pow(exp))
+            {
+                Some(v) => Numeric::I64(v),
+                // negative exponents and overflows
+                None => Numeric::F64(self.to_float().powf(exp.to_float())),
+            },
             _ => Numeric::F64(self.to_float().powf(exp.to_float())),
         }
     }
 
+    /// Euclidean division, rounding the quotient so that the remainder is
+    /// never negative. Stays an integer if both sides are integers.
+    pub fn div_euclid(self, rhs: Self) -> Self {
+        match (self.is_float(), rhs.is_float()) {
+            (false, false) => match self.to_int().checked_div_euclid(rhs.to_int()) {
+                Some(v) => Numeric::I64(v),
+                None => Numeric::F64(self.to_float() / rhs.to_float()),
+            },
+            _ => Numeric::F64(self.to_float().div_euclid(rhs.to_float())),
+        }
+    }
+
+    /// Non-negative remainder of [`Numeric::div_euclid`], useful for wrapping
+    /// indices (`-1` wraps to `n - 1`). Stays an integer if both sides are integers.
+    pub fn rem_euclid(self, rhs: Self) -> Self {
+        match (self.is_float(), rhs.is_float()) {
+            (false, false) => match self.to_int().checked_rem_euclid(rhs.to_int()) {
+                Some(v) => Numeric::I64(v),
+                None => Numeric::F64(self.to_float() % rhs.to_float()),
+            },
+            _ => Numeric::F64(self.to_float().rem_euclid(rhs.to_float())),
+        }
+    }
+
     pub fn min(self, other: Self) -> Self {
         match (self.is_float(), other.is_float()) {
             (false, false) => Numeric::I64(self.to_int().min(other.to_int())),
@@ -0,0 +1,283 @@
+// Test matrix for the coercion rules: every target type is coerced from a
+// sample of every PaxValue variant, including malformed nested values. A
+// coercion must either succeed or return a descriptive Err, never panic.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+
+use super::{CoercionRules, Numeric, PaxValue, ToPaxValue};
+use crate::math::{Transform2, Vector2};
+use crate::{
+    Color, ColorChannel, Fill, GradientStop, LinearGradient, PathElement, Percent, RadialGradient,
+    Rotation, Size, Stroke, Transform2D,
+};
+
+fn int(i: i64) -> PaxValue {
+    PaxValue::Numeric(Numeric::I64(i))
+}
+
+fn float(f: f64) -> PaxValue {
+    PaxValue::Numeric(Numeric::F64(f))
+}
+
+fn px(i: i64) -> PaxValue {
+    PaxValue::Size(Size::Pixels(Numeric::I64(i)))
+}
+
+fn object(fields: Vec<(&str, PaxValue)>) -> PaxValue {
+    PaxValue::Object(
+        fields
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect(),
+    )
+}
+
+fn enum_value(name: &str, variant: &str, args: Vec<PaxValue>) -> PaxValue {
+    PaxValue::Enum(name.to_string(), variant.to_string(), args)
+}
+
+fn some(value: PaxValue) -> PaxValue {
+    PaxValue::Option(Box::new(Some(value)))
+}
+
+/// At least one value of each variant, plus the shapes that the object and
+/// enum coercions look into, with fields missing or of the wrong type
+fn samples() -> Vec<PaxValue> {
+    vec![
+        PaxValue::Bool(false),
+        int(-3),
+        int(300),
+        float(2.5),
+        PaxValue::String("".to_string()),
+        PaxValue::String("red".to_string()),
+        PaxValue::String("#00ff00".to_string()),
+        PaxValue::String("12".to_string()),
+        px(10),
+        PaxValue::Size(Size::Percent(Numeric::F64(50.0))),
+        PaxValue::Size(Size::Combined(Numeric::I64(10), Numeric::F64(50.0))),
+        PaxValue::Percent(Percent(Numeric::F64(50.0))),
+        PaxValue::Color(Box::new(Color::RED)),
+        PaxValue::Rotation(Rotation::Degrees(Numeric::I64(90))),
+        PaxValue::PathElement(PathElement::Line),
+        PaxValue::Option(Box::new(None)),
+        some(int(1)),
+        some(PaxValue::Option(Box::new(None))),
+        PaxValue::Vec(vec![]),
+        PaxValue::Vec(vec![int(1)]),
+        PaxValue::Vec(vec![int(1), int(2)]),
+        PaxValue::Vec(vec![px(1), px(2), px(3), px(4), px(5), px(6)]),
+        PaxValue::Range(Box::new(int(0)), Box::new(int(5))),
+        PaxValue::Range(Box::new(int(0)), Box::new(PaxValue::Bool(true))),
+        PaxValue::Object(HashMap::new()),
+        object(vec![("color", PaxValue::Bool(true)), ("width", px(1))]),
+        object(vec![("start", int(0)), ("end", int(0)), ("stops", int(0))]),
+        object(vec![
+            ("start", PaxValue::Vec(vec![])),
+            ("end", PaxValue::Vec(vec![])),
+            ("radius", int(1)),
+            ("stops", PaxValue::Vec(vec![])),
+        ]),
+        object(vec![("position", px(0))]),
+        object(vec![("m", PaxValue::Vec(vec![float(1.0)]))]),
+        object(vec![("x", float(1.0))]),
+        object(vec![("translate", int(0)), ("scale", some(int(0)))]),
+        enum_value("Fill", "Solid", vec![]),
+        enum_value("Fill", "LinearGradient", vec![]),
+        enum_value("Fill", "Unknown", vec![]),
+        enum_value("ColorChannel", "Integer", vec![]),
+        enum_value("PathElement", "Point", vec![px(1)]),
+        enum_value("PathElement", "Cubic", vec![px(1), px(2)]),
+        enum_value("Unknown", "Unknown", vec![int(1)]),
+    ]
+}
+
+/// Coerces every sample, as well as the values that must be accepted, into
+/// `T`, and returns a description of each coercion that panicked, returned an
+/// empty error, or rejected one of `accepted`
+fn check_coercions<T: CoercionRules>(accepted: &[PaxValue]) -> Vec<String> {
+    let type_name = std::any::type_name::<T>();
+    let mut failures = vec![];
+    for value in samples().into_iter().chain(accepted.iter().cloned()) {
+        let must_accept = accepted.iter().any(|a| a.to_string() == value.to_string());
+        let result = panic::catch_unwind(AssertUnwindSafe(|| T::try_coerce(value.clone())));
+        match result {
+            Err(_) => failures.push(format!("{} into {} panicked", value, type_name)),
+            Ok(Err(e)) if e.trim().is_empty() => failures.push(format!(
+                "{} into {} failed without a message",
+                value, type_name
+            )),
+            Ok(Err(e)) if must_accept => failures.push(format!(
+                "{} into {} should succeed, failed with: {}",
+                value, type_name, e
+            )),
+            Ok(_) => (),
+        }
+    }
+    failures
+}
+
+/// Every value that coerces into `T` must coerce to the same `T` again after
+/// converting it back into a PaxValue
+fn check_round_trips<T>(accepted: &[PaxValue]) -> Vec<String>
+where
+    T: CoercionRules + ToPaxValue + PartialEq + Clone + Debug,
+{
+    let type_name = std::any::type_name::<T>();
+    let mut failures = vec![];
+    for value in samples().into_iter().chain(accepted.iter().cloned()) {
+        let Ok(Ok(coerced)) =
+            panic::catch_unwind(AssertUnwindSafe(|| T::try_coerce(value.clone())))
+        else {
+            continue;
+        };
+        let round_tripped = panic::catch_unwind(AssertUnwindSafe(|| {
+            T::try_coerce(coerced.clone().to_pax_value())
+        }));
+        match round_tripped {
+            Ok(Ok(again)) if again == coerced => (),
+            Ok(Ok(again)) => failures.push(format!(
+                "{} into {} gave {:?}, which round trips to {:?}",
+                value, type_name, coerced, again
+            )),
+            Ok(Err(e)) => failures.push(format!(
+                "{} into {} gave {:?}, which doesn't round trip: {}",
+                value, type_name, coerced, e
+            )),
+            Err(_) => failures.push(format!(
+                "{} into {} gave {:?}, which panics when round tripped",
+                value, type_name, coerced
+            )),
+        }
+    }
+    failures
+}
+
+/// Runs `$check` for each type with the values it must accept, and fails
+/// with every failure across the matrix
+macro_rules! coercion_matrix {
+    ($check:ident, $($type:ty => [$($accepted:expr),* $(,)?]),* $(,)?) => {{
+        let mut failures: Vec<String> = vec![];
+        $(failures.extend($check::<$type>(&[$($accepted),*]));)*
+        assert!(
+            failures.is_empty(),
+            "{} coercion failures:\n{}",
+            failures.len(),
+            failures.join("\n")
+        );
+    }};
+}
+
+fn stroke() -> PaxValue {
+    object(vec![
+        ("color", PaxValue::Color(Box::new(Color::BLUE))),
+        ("width", px(2)),
+    ])
+}
+
+fn gradient_stop() -> PaxValue {
+    object(vec![
+        ("position", PaxValue::Percent(Percent(Numeric::F64(50.0)))),
+        ("color", PaxValue::Color(Box::new(Color::RED))),
+    ])
+}
+
+fn linear_gradient() -> PaxValue {
+    object(vec![
+        ("start", PaxValue::Vec(vec![px(0), px(0)])),
+        ("end", PaxValue::Vec(vec![px(10), px(10)])),
+        ("stops", PaxValue::Vec(vec![gradient_stop()])),
+    ])
+}
+
+fn radial_gradient() -> PaxValue {
+    object(vec![
+        ("start", PaxValue::Vec(vec![px(0), px(0)])),
+        ("end", PaxValue::Vec(vec![px(10), px(10)])),
+        ("radius", float(5.0)),
+        ("stops", PaxValue::Vec(vec![gradient_stop()])),
+    ])
+}
+
+#[test]
+fn coercions_never_panic() {
+    coercion_matrix!(
+        check_coercions,
+        bool => [PaxValue::Bool(true)],
+        u8 => [int(3)],
+        u16 => [int(3)],
+        u32 => [int(3)],
+        u64 => [int(3)],
+        i8 => [int(-3)],
+        i16 => [int(-3)],
+        i32 => [int(-3)],
+        i64 => [int(-3)],
+        f32 => [float(2.5)],
+        f64 => [float(2.5)],
+        isize => [int(-3)],
+        usize => [int(3)],
+        String => [PaxValue::String("text".to_string()), int(3)],
+        Numeric => [int(3), PaxValue::Bool(true), px(10)],
+        Size => [px(10), int(10), PaxValue::Percent(Percent(Numeric::I64(50)))],
+        Percent => [PaxValue::Percent(Percent(Numeric::I64(50))), int(50)],
+        Rotation => [PaxValue::Rotation(Rotation::Radians(Numeric::F64(1.0))), int(90)],
+        Color => [
+            PaxValue::Color(Box::new(Color::GREEN)),
+            PaxValue::String("cornflowerblue".to_string()),
+        ],
+        ColorChannel => [int(255), PaxValue::Percent(Percent(Numeric::I64(50)))],
+        PathElement => [
+            PaxValue::PathElement(PathElement::Close),
+            enum_value("PathElement", "Point", vec![px(1), px(2)]),
+        ],
+        Fill => [
+            PaxValue::Color(Box::new(Color::RED)),
+            enum_value("Fill", "LinearGradient", vec![linear_gradient()]),
+        ],
+        GradientStop => [gradient_stop()],
+        LinearGradient => [linear_gradient()],
+        RadialGradient => [radial_gradient()],
+        Stroke => [stroke(), PaxValue::Color(Box::new(Color::RED))],
+        Transform2D => [object(vec![])],
+        Transform2 => [object(vec![(
+            "m",
+            PaxValue::Vec(vec![float(1.0), float(0.0), float(0.0), float(1.0), float(0.0), float(0.0)]),
+        )])],
+        Vector2 => [object(vec![("x", float(1.0)), ("y", float(2.0))])],
+        Vec<Size> => [PaxValue::Vec(vec![px(1), int(2)])],
+        (Size, Size) => [PaxValue::Vec(vec![px(1), px(2)])],
+        Option<Color> => [PaxValue::Option(Box::new(None))],
+        Range<isize> => [PaxValue::Range(Box::new(int(0)), Box::new(int(5)))],
+        Box<Size> => [px(1)],
+        PaxValue => [],
+    );
+}
+
+#[test]
+fn coercions_round_trip() {
+    coercion_matrix!(
+        check_round_trips,
+        bool => [],
+        u8 => [],
+        i64 => [],
+        f64 => [],
+        usize => [],
+        String => [],
+        Numeric => [],
+        Size => [],
+        Percent => [],
+        Rotation => [],
+        Color => [PaxValue::String("cornflowerblue".to_string())],
+        ColorChannel => [int(255)],
+        Fill => [enum_value("Fill", "LinearGradient", vec![linear_gradient()])],
+        GradientStop => [gradient_stop()],
+        LinearGradient => [linear_gradient()],
+        RadialGradient => [radial_gradient()],
+        Stroke => [stroke()],
+        Vec<Size> => [PaxValue::Vec(vec![px(1), int(2)])],
+        Option<Color> => [],
+        Range<isize> => [PaxValue::Range(Box::new(int(0)), Box::new(int(5)))],
+    );
+}
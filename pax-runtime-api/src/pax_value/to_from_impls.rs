@@ -159,14 +159,14 @@ impl ToPaxValue for ColorChannel {
 
 impl ToPaxValue for Stroke {
     fn to_pax_value(self) -> PaxValue {
-        PaxValue::Object(
-            vec![
-                ("color".to_string(), self.color.get().to_pax_value()),
-                ("width".to_string(), self.width.to_pax_value()),
-            ]
-            .into_iter()
-            .collect(),
-        )
+        let mut fields = vec![
+            ("color".to_string(), self.color.get().to_pax_value()),
+            ("width".to_string(), self.width.to_pax_value()),
+        ];
+        if self.hairline.get() {
+            fields.push(("hairline".to_string(), true.to_pax_value()));
+        }
+        PaxValue::Object(fields.into_iter().collect())
     }
 }
 
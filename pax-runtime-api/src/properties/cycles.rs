@@ -0,0 +1,155 @@
+use std::collections::VecDeque;
+use std::fmt::{self, Display};
+
+use super::{private::PropertyId, properties_table::PropertyTable};
+
+/// Whether the properties being evaluated are tracked, to diagnose property cycles.  Off in
+/// release builds, where the tracking would cost every computed property evaluation
+pub(crate) const TRACK_EVALUATION: bool = cfg!(any(debug_assertions, feature = "designtime"));
+
+/// A property that (transitively) depends on itself, or that is read or set while it's being
+/// evaluated.  `chain` holds the debug labels of the properties involved, in the order they
+/// reach each other, starting and ending with the same property
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyCycle {
+    pub chain: Vec<String>,
+}
+
+impl Display for PropertyCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "property cycle: {}", self.chain.join(" → "))
+    }
+}
+
+impl PropertyCycle {
+    /// Logs the cycle, then panics in debug builds.  In release builds the caller skips the
+    /// operation that closed the cycle instead
+    pub(crate) fn report(self) {
+        if cfg!(debug_assertions) {
+            log::error!("{}", self);
+            panic!("{}", self);
+        } else {
+            log::warn!("{} (skipped)", self);
+        }
+    }
+}
+
+impl PropertyTable {
+    /// Runs `f` with `id` on the stack of properties being evaluated
+    pub(crate) fn evaluating<V>(&self, id: PropertyId, f: impl FnOnce() -> V) -> V {
+        if !TRACK_EVALUATION {
+            return f();
+        }
+        self.evaluating.borrow_mut().push(id);
+        let res = f();
+        self.evaluating.borrow_mut().pop();
+        res
+    }
+
+    /// The cycle closed by getting `id`, if it's already being evaluated
+    pub(crate) fn reentrant_get_cycle(&self, id: PropertyId) -> Option<PropertyCycle> {
+        if !TRACK_EVALUATION {
+            return None;
+        }
+        let evaluating = self.evaluating.borrow();
+        let start = evaluating.iter().position(|e| *e == id)?;
+        let mut chain: Vec<_> = evaluating[start..]
+            .iter()
+            .map(|e| self.debug_name(*e))
+            .collect();
+        chain.push(self.debug_name(id));
+        Some(PropertyCycle { chain })
+    }
+
+    /// The cycle closed by setting `id`, if that invalidates a property being evaluated: one
+    /// that depends on `id` through properties that are up to date
+    pub(crate) fn set_during_get_cycle(&self, id: PropertyId) -> Option<PropertyCycle> {
+        if !TRACK_EVALUATION || self.evaluating.borrow().is_empty() {
+            return None;
+        }
+        let evaluating = self.evaluating.borrow().clone();
+        // the property each visited property was reached from, to recover the path
+        let mut reached_from = vec![(id, id)];
+        let mut to_visit = vec![id];
+        while let Some(current) = to_visit.pop() {
+            let outbound = self.with_property_data(current, |data| data.outbound.clone());
+            for dep in outbound {
+                if reached_from.iter().any(|(p, _)| *p == dep) {
+                    continue;
+                }
+                reached_from.push((dep, current));
+                if let Some(start) = evaluating.iter().position(|e| *e == dep) {
+                    // `id` invalidates `dep`, whose evaluation led to the set of `id`
+                    let chain = path_from(&reached_from, id, current)
+                        .into_iter()
+                        .chain(evaluating[start..].iter().copied())
+                        .chain([id])
+                        .map(|p| self.debug_name(p))
+                        .collect();
+                    return Some(PropertyCycle { chain });
+                }
+                if !self.with_property_data(dep, |data| data.dirty) {
+                    to_visit.push(dep);
+                }
+            }
+        }
+        None
+    }
+
+    /// The cycle of dependencies from `id` back to itself, which the graph can't contain
+    pub(crate) fn dependency_cycle(&self, id: PropertyId) -> PropertyCycle {
+        let mut reached_from = vec![(id, id)];
+        let mut to_visit = VecDeque::from([id]);
+        while let Some(current) = to_visit.pop_front() {
+            let outbound = self.with_property_data(current, |data| data.outbound.clone());
+            for dep in outbound {
+                if dep == id {
+                    let chain = path_from(&reached_from, id, current)
+                        .into_iter()
+                        .chain([id])
+                        .map(|p| self.debug_name(p))
+                        .collect();
+                    return PropertyCycle { chain };
+                }
+                if !reached_from.iter().any(|(p, _)| *p == dep) {
+                    reached_from.push((dep, current));
+                    to_visit.push_back(dep);
+                }
+            }
+        }
+        PropertyCycle {
+            chain: vec![self.debug_name(id), self.debug_name(id)],
+        }
+    }
+
+    /// The labels of the properties currently being evaluated, outermost first, to name them in
+    /// diagnostics
+    pub(crate) fn evaluation_chain(&self) -> String {
+        let evaluating = self.evaluating.borrow();
+        if evaluating.is_empty() {
+            return String::new();
+        }
+        let names: Vec<_> = evaluating.iter().map(|e| self.debug_name(*e)).collect();
+        format!(" (while evaluating {})", names.join(" → "))
+    }
+}
+
+/// The path from `start` to `end`, given the property each visited property was reached from
+fn path_from(
+    reached_from: &[(PropertyId, PropertyId)],
+    start: PropertyId,
+    end: PropertyId,
+) -> Vec<PropertyId> {
+    let mut path = vec![end];
+    let mut step = end;
+    while step != start {
+        step = reached_from
+            .iter()
+            .find(|(p, _)| *p == step)
+            .map(|(_, from)| *from)
+            .unwrap_or(start);
+        path.push(step);
+    }
+    path.reverse();
+    path
+}
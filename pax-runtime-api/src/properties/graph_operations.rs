@@ -7,10 +7,11 @@ impl PropertyTable {
             self.with_property_data_mut(id, |property_data| property_data.outbound.clone());
 
         while let Some(dep_id) = to_dirtify.pop() {
+            if dep_id == id {
+                self.dependency_cycle(id).report();
+                return;
+            }
             self.with_property_data_mut(dep_id, |dep_data| {
-                if dep_id == id {
-                    unreachable!("property cycle");
-                }
                 if !dep_data.dirty {
                     dep_data.dirty = true;
                     to_dirtify.extend_from_slice(&dep_data.outbound);
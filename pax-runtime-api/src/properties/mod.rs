@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::{marker::PhantomData, rc::Rc};
 
+mod cycles;
 mod graph_operations;
 mod properties_table;
 #[cfg(test)]
@@ -10,6 +11,7 @@ mod untyped_property;
 use crate::{EasingCurve, Interpolatable, TransitionQueueEntry};
 
 use self::properties_table::{PropertyType, PROPERTY_TIME};
+pub use cycles::PropertyCycle;
 use properties_table::PROPERTY_TABLE;
 pub use untyped_property::UntypedProperty;
 
@@ -126,6 +128,16 @@ impl<T: PropertyValue> Property<T> {
         })
     }
 
+    /// Aborts any in-progress `ease_to` transitions, leaving this property at its current value
+    pub fn cancel_transitions(&self) {
+        PROPERTY_TABLE.with(|t| t.cancel_transitions::<T>(self.untyped.id));
+    }
+
+    /// Completes any in-progress `ease_to` transitions immediately, jumping to the final value
+    pub fn skip_transitions_to_end(&self) {
+        PROPERTY_TABLE.with(|t| t.skip_transitions_to_end::<T>(self.untyped.id));
+    }
+
     /// Gets the currently stored value. Might be computationally
     /// expensive in a large reactivity network since this triggers
     /// re-evaluation of dirty property chains
@@ -170,6 +182,12 @@ impl<T: PropertyValue> Property<T> {
         })
     }
 
+    /// Labels this property in diagnostics, such as property cycles. Only kept in debug and
+    /// designtime builds, where the properties being evaluated are tracked
+    pub fn set_debug_label(&self, label: &str) {
+        PROPERTY_TABLE.with(|t| t.set_debug_name(self.untyped.id, label));
+    }
+
     /// Casts this property to its untyped version
     pub fn untyped(&self) -> UntypedProperty {
         self.untyped.clone()
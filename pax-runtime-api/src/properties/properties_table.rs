@@ -4,7 +4,7 @@ use slotmap::{SlotMap, SparseSecondaryMap};
 
 use crate::{Property, TransitionManager, TransitionQueueEntry};
 
-use super::{private::PropertyId, PropertyValue};
+use super::{cycles::TRACK_EVALUATION, private::PropertyId, PropertyValue};
 
 thread_local! {
     /// Global property table used to store data backing dirty-dag
@@ -61,6 +61,8 @@ pub(crate) struct PropertyTable {
     // Box<dyn Any> is of type Box<Entry<T>> where T is the proptype
     pub(crate) property_map: RefCell<SlotMap<PropertyId, Entry>>,
     debug_names: RefCell<SparseSecondaryMap<PropertyId, String>>,
+    // Stack of the properties currently being evaluated, when tracked (see cycles.rs)
+    pub(crate) evaluating: RefCell<Vec<PropertyId>>,
 }
 
 pub struct Entry {
@@ -73,23 +75,37 @@ impl PropertyTable {
     /// Makes sure the value is up to date before returning in the case
     /// of computed properties.
     pub fn get_value<T: PropertyValue>(&self, id: PropertyId) -> T {
-        self.update_value::<T>(id);
+        self.update_value_unless_evaluating::<T>(id);
         self.with_property_data_mut(id, |property_data| {
             property_data.typed_data::<T>().value.clone()
         })
     }
 
     pub fn read_value<T: PropertyValue, V>(&self, id: PropertyId, f: impl FnOnce(&T) -> V) -> V {
-        self.update_value::<T>(id);
+        self.update_value_unless_evaluating::<T>(id);
         self.with_property_data_mut(id, |property_data| {
-            f(&property_data.typed_data::<T>().value)
+            self.evaluating(id, || f(&property_data.typed_data::<T>().value))
         })
     }
 
+    /// Updates the value, unless getting it is re-entrant: a cycle, after which the
+    /// value from before the evaluation is used
+    fn update_value_unless_evaluating<T: PropertyValue>(&self, id: PropertyId) {
+        if let Some(cycle) = self.reentrant_get_cycle(id) {
+            cycle.report();
+            return;
+        }
+        self.update_value::<T>(id);
+    }
+
     // Main function to set a value of a property.
     // NOTE: This always assumes the underlying data was changed, and marks
     // it and its dependents as dirty irrespective of actual modification
     pub fn set_value<T: PropertyValue>(&self, id: PropertyId, new_val: T) {
+        if let Some(cycle) = self.set_during_get_cycle(id) {
+            cycle.report();
+            return;
+        }
         self.with_property_data_mut(id, |property_data: &mut PropertyData| {
             let typed_data = property_data.typed_data();
             typed_data.value = new_val;
@@ -165,13 +181,63 @@ impl PropertyTable {
         }
     }
 
+    /// Aborts any in-progress transitions, leaving the property at the value eased to at the
+    /// current time
+    pub fn cancel_transitions<T: PropertyValue>(&self, id: PropertyId) {
+        let curr_time = PROPERTY_TIME.with_borrow(|time| time.get());
+        self.end_transitions::<T>(id, |transition_manager| {
+            // ease up to now in case the property wasn't read since time last changed
+            transition_manager.compute_eased_value(curr_time);
+            transition_manager.cancel();
+        });
+    }
+
+    /// Completes any in-progress transitions immediately, setting the property to the final
+    /// ending value of its transition queue
+    pub fn skip_transitions_to_end<T: PropertyValue>(&self, id: PropertyId) {
+        self.end_transitions::<T>(id, TransitionManager::skip_to_end);
+    }
+
+    /// Stops the transitions of `id` with `end`, settling the property on the checkpoint value
+    /// `end` leaves the transition manager at
+    fn end_transitions<T: PropertyValue>(
+        &self,
+        id: PropertyId,
+        end: impl FnOnce(&mut TransitionManager<T>),
+    ) {
+        let mut had_transitions = false;
+        self.with_property_data_mut(id, |property_data: &mut PropertyData| {
+            let typed_data = property_data.typed_data::<T>();
+            if let Some(mut transition_manager) = typed_data.transition_manager.take() {
+                end(&mut transition_manager);
+                typed_data.value = transition_manager.get_checkpoint_value().clone();
+                property_data.dirty = false;
+                had_transitions = true;
+            }
+        });
+        if had_transitions {
+            // the transition is over, so this literal no longer depends on time
+            self.disconnect_inbound(id);
+            self.with_property_data_mut(id, |property_data| {
+                property_data.inbound.clear();
+            });
+            self.dirtify_outbound(id);
+        }
+    }
+
     /// Gives mutable access to a entry in the property table
     /// WARNING: this function is dangerous, f can not drop, create, set, get
     /// or in any other way modify the global property table or this will panic
     /// with multiple mutable borrows. Letting f contain any form of userland
     /// code is NOT a good idea.
     fn with_entry_mut<V>(&self, id: PropertyId, f: impl FnOnce(&mut Entry) -> V) -> V {
-        let mut sm = self.property_map.borrow_mut();
+        let Ok(mut sm) = self.property_map.try_borrow_mut() else {
+            panic!(
+                "couldn't access property \"{}\" - table already borrowed{}",
+                self.debug_name(id),
+                self.evaluation_chain()
+            );
+        };
         let data = sm.get_mut(id).unwrap();
         let return_value = f(data);
         return_value
@@ -188,10 +254,13 @@ impl PropertyTable {
     ) -> V {
         // take the value out of the table
         let mut property_data = self.with_entry_mut(id, |entry| {
-            entry
-                .data
-                .take()
-                .expect("property data should not have already been taken")
+            entry.data.take().unwrap_or_else(|| {
+                panic!(
+                    "property \"{}\" accessed while already in use, e.g. set from its own read{}",
+                    self.debug_name(id),
+                    self.evaluation_chain()
+                )
+            })
         });
 
         // run f, without table being borrowed
@@ -311,7 +380,7 @@ impl PropertyTable {
             // an invalid state (borrowed, in with_property_data closure, etc.)
             // as this function is provided by a user of the property system and
             // can do arbitrary sets/ gets/drops etc (that need the prop data)
-            let new_value = { evaluator() };
+            let new_value = self.evaluating(id, || evaluator());
             self.with_property_data_mut(id, |property_data| {
                 let typed_data = property_data.typed_data();
                 typed_data.value = new_value;
@@ -335,6 +404,12 @@ impl PropertyTable {
         drop(res);
     }
 
+    pub fn set_debug_name(&self, id: PropertyId, name: &str) {
+        if TRACK_EVALUATION {
+            self.debug_names.borrow_mut().insert(id, name.to_owned());
+        }
+    }
+
     pub fn debug_name(&self, id: PropertyId) -> String {
         self.debug_names
             .borrow()
@@ -85,3 +85,126 @@ fn test_recursive_props() {
     }
     assert!(PROPERTY_TABLE.with(|t| t.property_map.borrow().is_empty()));
 }
+
+#[test]
+fn test_cancel_transitions() {
+    let time = Property::new(0u64);
+    register_time(&time);
+    let prop = Property::new(0.0);
+    prop.ease_to(10.0, 10, EasingCurve::Linear);
+    time.set(5);
+    assert_eq!(prop.get(), 5.0);
+    prop.cancel_transitions();
+    time.set(8);
+    assert_eq!(prop.get(), 5.0);
+}
+
+#[test]
+fn test_cancel_transitions_holds_the_value_eased_to_when_cancelled() {
+    let time = Property::new(0u64);
+    register_time(&time);
+    let prop = Property::new(0.0);
+    let doubled = {
+        let prop = prop.clone();
+        let dependents = [prop.untyped()];
+        Property::computed(move || prop.get() * 2.0, &dependents)
+    };
+    prop.ease_to(10.0, 10, EasingCurve::Linear);
+    time.set(3);
+    assert_eq!(prop.get(), 3.0);
+    // not read again before cancelling, so the eased value is computed when cancelling
+    time.set(6);
+    prop.cancel_transitions();
+    assert_eq!(prop.get(), 6.0);
+    assert_eq!(doubled.get(), 12.0);
+    time.set(9);
+    assert_eq!(prop.get(), 6.0);
+    // new transitions start from the held value
+    prop.ease_to(16.0, 10, EasingCurve::Linear);
+    time.set(14);
+    assert_eq!(prop.get(), 11.0);
+}
+
+#[test]
+fn test_skip_transitions_to_end() {
+    let time = Property::new(0u64);
+    register_time(&time);
+    let prop = Property::new(0.0);
+    prop.ease_to(10.0, 10, EasingCurve::Linear);
+    prop.ease_to_later(20.0, 10, EasingCurve::Linear);
+    time.set(5);
+    assert_eq!(prop.get(), 5.0);
+    prop.skip_transitions_to_end();
+    assert_eq!(prop.get(), 20.0);
+    time.set(8);
+    assert_eq!(prop.get(), 20.0);
+}
+
+#[test]
+#[should_panic(
+    expected = "property cycle: FileAndComponentPicker::library_active → FileAndComponentPicker::toggle_image → FileAndComponentPicker::library_active"
+)]
+fn test_set_during_get_cycle_names_properties() {
+    let library_active = Property::new(false);
+    library_active.set_debug_label("FileAndComponentPicker::library_active");
+    let active = library_active.clone();
+    let toggle_image = Property::computed(
+        move || {
+            let image = if active.get() { "open" } else { "closed" };
+            active.set(true);
+            image.to_owned()
+        },
+        &[library_active.untyped()],
+    );
+    toggle_image.set_debug_label("FileAndComponentPicker::toggle_image");
+    toggle_image.get();
+}
+
+#[test]
+#[should_panic(expected = "property cycle: a → b → a")]
+fn test_reentrant_get_cycle() {
+    let a_handle: Rc<std::cell::RefCell<Option<Property<i32>>>> = Default::default();
+    let a_for_b = a_handle.clone();
+    let b = Property::computed_with_name(
+        move || a_for_b.borrow().as_ref().map(|a| a.get()).unwrap_or(0) + 1,
+        &[],
+        "b",
+    );
+    let b_for_a = b.clone();
+    let a = Property::computed_with_name(move || b_for_a.get() + 1, &[b.untyped()], "a");
+    *a_handle.borrow_mut() = Some(a.clone());
+    a.get();
+}
+
+#[test]
+#[should_panic(expected = "property cycle: a → b → a")]
+fn test_dependency_cycle() {
+    let a = Property::new_with_name(1, "a");
+    let a_for_b = a.clone();
+    let b = Property::computed_with_name(move || a_for_b.get() + 1, &[a.untyped()], "b");
+    let b_for_c = b.clone();
+    let c = Property::computed_with_name(move || b_for_c.get() + 1, &[b.untyped()], "c");
+    assert_eq!(c.get(), 3);
+    // a now computes from b, which computes from a
+    a.replace_with(c);
+}
+
+#[test]
+fn test_set_during_get_without_cycle() {
+    let source = Property::new(1);
+    let log = Property::new(0);
+    let s = source.clone();
+    let l = log.clone();
+    let doubled = Property::computed(
+        move || {
+            let value = s.get() * 2;
+            l.set(value);
+            value
+        },
+        &[source.untyped()],
+    );
+    assert_eq!(doubled.get(), 2);
+    source.set(4);
+    assert_eq!(doubled.get(), 8);
+    assert_eq!(log.get(), 8);
+}
@@ -1,4 +1,5 @@
 use std::{
+    marker::PhantomData,
     rc::{Rc, Weak},
     time::Instant,
 };
@@ -10,6 +11,7 @@ use crate::{
 };
 
 use pax_runtime_api::math::Point2;
+use pax_runtime_api::pax_value::ToFromPaxAny;
 pub use pax_runtime_api::*;
 
 #[cfg(feature = "designtime")]
@@ -38,6 +40,8 @@ pub struct NodeContext {
     pub os: OS,
     /// The number of slot children provided to this component template
     pub slot_children_count: Property<usize>,
+    /// The slot children provided to this component template, flattened
+    pub(crate) slot_children: Property<Vec<Rc<ExpandedNode>>>,
     /// Borrow of the RuntimeContext, used at least for exposing raycasting to userland
     pub(crate) runtime_context: Rc<RuntimeContext>,
 
@@ -57,10 +61,29 @@ impl NodeContext {
         self.local_stack_frame.peek_stack_local_store(f)
     }
 
+    /// Size of each slot child if laid out in a container of size `available`, see
+    /// [`ExpandedNode::measure`].  Measured in a `(0.0, 0.0)` container, children that fill
+    /// their container report 0, so only explicit pixel sizes and rendered sizes (e.g. of auto
+    /// sized text) are left
+    pub fn measure_slot_children(&self, available: (f64, f64)) -> Vec<(f64, f64)> {
+        self.slot_children.read(|children| {
+            children
+                .iter()
+                .map(|child| child.measure(available))
+                .collect()
+        })
+    }
+
     pub fn local_point(&self, p: Point2<Window>) -> Point2<NodeLocal> {
         self.node_transform_and_bounds.as_transform().inverse() * p
     }
 
+    /// Physical pixels per logical pixel, e.g. 2.0 on most high-DPI screens. Sizes in Pax are
+    /// logical pixels, so this is only needed to align content to physical pixels
+    pub fn device_pixel_ratio(&self) -> f64 {
+        self.runtime_context.device_pixel_ratio()
+    }
+
     /// Get std::time::Instant::now()
     pub fn elapsed_time_millis(&self) -> u128 {
         (self.get_elapsed_millis)()
@@ -78,6 +101,71 @@ impl NodeContext {
             }))
     }
 
+    /// Opens the platform's print dialog, for the element with target_id
+    /// if given or otherwise for the whole app
+    pub fn print(&self, target_id: Option<String>) {
+        self.runtime_context
+            .enqueue_native_message(NativeMessage::PrintRequest(PrintPatch { target_id }))
+    }
+
+    /// Puts text on the system clipboard
+    pub fn write_clipboard(&self, text: &str) {
+        self.runtime_context
+            .enqueue_native_message(NativeMessage::ClipboardWrite(text.to_string()))
+    }
+
+    /// Reads the text on the system clipboard. The platform answers asynchronously, so
+    /// `callback` is called with the text on a later tick, or with an empty string if the
+    /// clipboard can't be read
+    pub fn read_clipboard(&self, callback: impl FnOnce(String) + 'static) {
+        self.runtime_context.request_clipboard_read(callback)
+    }
+
+    /// A handle to the mounted instance of component `T` with `id=` `id`, through which the
+    /// methods `T` marks `#[pax(public)]` can be called, see [`ComponentHandle`].  Fails if no
+    /// instance is mounted, or if several are (e.g. in a `for`), see
+    /// [`NodeContext::get_component_handles`]
+    pub fn get_component_handle<T: ToFromPaxAny>(
+        &self,
+        id: &str,
+    ) -> Result<ComponentHandle<T>, String> {
+        let mut handles = self.get_component_handles(id);
+        match handles.len() {
+            1 => Ok(handles.remove(0)),
+            0 => Err(format!(
+                "no mounted {} with id \"{}\"",
+                std::any::type_name::<T>(),
+                id
+            )),
+            n => Err(format!(
+                "{} mounted instances of {} with id \"{}\", use get_component_handles",
+                n,
+                std::any::type_name::<T>(),
+                id
+            )),
+        }
+    }
+
+    /// Handles to every mounted instance of component `T` with `id=` `id`, in the order they
+    /// were created
+    pub fn get_component_handles<T: ToFromPaxAny>(&self, id: &str) -> Vec<ComponentHandle<T>> {
+        let mut nodes = self.runtime_context.get_expanded_nodes_by_id(id);
+        nodes.retain(|node| {
+            let properties = Rc::clone(&*borrow!(node.properties));
+            let is_instance_of_t = T::ref_from_pax_any(&borrow!(properties)).is_ok();
+            is_instance_of_t
+        });
+        nodes.sort_by_key(|node| node.id);
+        nodes
+            .into_iter()
+            .map(|node| ComponentHandle {
+                node: Rc::downgrade(&node),
+                runtime_context: Rc::clone(&self.runtime_context),
+                _component: PhantomData,
+            })
+            .collect()
+    }
+
     pub fn dispatch_event(&self, identifier: &'static str) -> Result<(), String> {
         let component_origin = self
             .containing_component
@@ -105,6 +193,65 @@ impl NodeContext {
     }
 }
 
+/// A handle to an instance of component `T`, from [`NodeContext::get_component_handle`], for
+/// parents to call methods on their children imperatively, e.g. to focus an input or reset a form.
+///
+/// `#[pax]` on an `impl` block of `T` generates a `<T>Handle` trait, implemented for
+/// `ComponentHandle<T>`, with the methods of the block marked `#[pax(public)]`:
+///
+/// ```ignore
+/// #[pax]
+/// impl SignupForm {
+///     #[pax(public)]
+///     pub fn reset(&mut self, ctx: &NodeContext) { ... }
+/// }
+///
+/// // in a handler of the parent, with `SignupFormHandle` in scope
+/// ctx.get_component_handle::<SignupForm>("signup_form")?.reset();
+/// ```
+///
+/// Calls don't run right away: they're queued and run at the start of the next tick, before its
+/// properties are computed, with the instance's own properties and context.  Calls on an instance
+/// that's unmounted by then are dropped with a warning.
+pub struct ComponentHandle<T> {
+    node: Weak<ExpandedNode>,
+    runtime_context: Rc<RuntimeContext>,
+    _component: PhantomData<T>,
+}
+
+impl<T> Clone for ComponentHandle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            node: Weak::clone(&self.node),
+            runtime_context: Rc::clone(&self.runtime_context),
+            _component: PhantomData,
+        }
+    }
+}
+
+impl<T: ToFromPaxAny> ComponentHandle<T> {
+    /// Whether the instance is still mounted, i.e. whether calls on it will run
+    pub fn is_mounted(&self) -> bool {
+        self.node
+            .upgrade()
+            .is_some_and(|node| node.attached.get() > 0)
+    }
+
+    /// Queues `f` to run on the instance at the start of the next tick.  Used by the handle traits
+    /// `#[pax]` generates, which call the instance's public methods with it
+    pub fn call(&self, f: impl FnOnce(&mut T, &NodeContext) + 'static) {
+        self.runtime_context.queue_component_call(
+            Weak::clone(&self.node),
+            Box::new(
+                move |properties, ctx| match T::mut_from_pax_any(properties) {
+                    Ok(component) => f(component, ctx),
+                    Err(e) => log::warn!("failed to call a component through its handle: {}", e),
+                },
+            ),
+        );
+    }
+}
+
 #[cfg(feature = "designtime")]
 impl NodeContext {
     pub fn raycast(&self, point: Point2<Window>, hit_invisible: bool) -> Vec<NodeInterface> {
@@ -9,7 +9,7 @@ use pax_manifest::{TypeId, ValueDefinition};
 use pax_message::borrow;
 use pax_runtime_api::pax_value::{CoercionRules, PaxAny, ToFromPaxAny};
 use pax_runtime_api::properties::PropertyValue;
-use pax_runtime_api::{use_RefCell, CommonProperties, Numeric, Property, Variable};
+use pax_runtime_api::{use_RefCell, CommonProperties, Numeric, PaxValue, Property, Variable};
 use serde::de::DeserializeOwned;
 use std::borrow::Borrow;
 use std::collections::{BTreeMap, HashMap};
@@ -34,10 +34,18 @@ pub trait DefinitionToInstanceTraverser {
             let manifest = self.get_manifest();
             manifest.main_component_type_id.clone()
         };
+        self.get_component_root(&main_component_type_id, id)
+    }
 
+    /// Instantiates component `type_id` as the root of a tree, e.g. to render a preview of it
+    fn get_component_root(
+        &self,
+        type_id: &pax_manifest::TypeId,
+        id: &str,
+    ) -> std::rc::Rc<crate::ComponentInstance> {
         let wrapper_type_id = TypeId::build_singleton(id, Some("RootComponent"));
 
-        let mut args = self.build_component_args(&main_component_type_id);
+        let mut args = self.build_component_args(type_id);
         args.template_node_identifier = Some(pax_manifest::UniqueTemplateNodeIdentifier::build(
             wrapper_type_id,
             pax_manifest::TemplateNodeId::build(0),
@@ -87,6 +95,9 @@ pub trait DefinitionToInstanceTraverser {
             let mut instances = Vec::new();
             for node_id in root {
                 let node = template.get_node(&node_id).unwrap();
+                if !is_enabled(&manifest, node) {
+                    continue;
+                }
                 match node.type_id.get_pax_type() {
                     pax_manifest::PaxType::If
                     | pax_manifest::PaxType::Slot
@@ -199,7 +210,12 @@ pub trait DefinitionToInstanceTraverser {
                         let mut properties = crate::ConditionalProperties::default();
                         properties.boolean_expression = Property::computed_with_name(
                             move || {
-                                let new_value = expr_ast.compute(cloned_stack.clone()).unwrap();
+                                let new_value = expr_ast
+                                    .compute(cloned_stack.clone())
+                                    .unwrap_or_else(|err| {
+                                        log::warn!("Failed to compute expression: {:?}", err);
+                                        Default::default()
+                                    });
                                 let coerced = bool::try_coerce(new_value).unwrap_or_else(|_e| {
                                     log::warn!("Failed to parse boolean expression: {}", expr_ast);
                                     Default::default()
@@ -450,6 +466,9 @@ pub trait DefinitionToInstanceTraverser {
         let mut children_instances = Vec::new();
         for child_id in &children.unwrap_or_default() {
             let child = containing_template.get_node(&child_id).unwrap();
+            if !is_enabled(&manifest, child) {
+                continue;
+            }
             match child.type_id.get_pax_type() {
                 pax_manifest::PaxType::If
                 | pax_manifest::PaxType::Slot
@@ -484,8 +503,19 @@ pub trait DefinitionToInstanceTraverser {
             .get_component_factory(containing_component_type_id)
             .unwrap();
 
-        let mut args = self.build_component_args(&node.type_id);
-        let node_component_factory = self.get_component_factory(&node.type_id).unwrap();
+        // nodes of components missing from the manifest, e.g. renamed since the template was
+        // written, are rendered as a placeholder at their bounds, see `PaxType::Unresolved`
+        let is_unresolved = manifest.is_unresolved_type(&node.type_id);
+        let node_type_id = if is_unresolved {
+            manifest
+                .get_unresolved_placeholder_type_id()
+                .unwrap_or_else(|| panic!("`{}` isn't a known component", node.type_id))
+        } else {
+            node.type_id.clone()
+        };
+
+        let mut args = self.build_component_args(&node_type_id);
+        let node_component_factory = self.get_component_factory(&node_type_id).unwrap();
 
         // update handlers from tnd
         let handlers_from_tnd = manifest.get_inline_event_handlers(node);
@@ -501,7 +531,15 @@ pub trait DefinitionToInstanceTraverser {
         args.handler_registry = Some(updated_registry);
 
         // update properties from tnd
-        let inline_properties = manifest.get_inline_properties(containing_component_type_id, node);
+        let mut inline_properties =
+            manifest.get_inline_properties(containing_component_type_id, node);
+        if is_unresolved {
+            let type_name = node.type_id.get_pascal_identifier().unwrap_or_default();
+            inline_properties.insert(
+                "type_name".to_string(),
+                ValueDefinition::LiteralValue(PaxValue::String(type_name)),
+            );
+        }
         let updated_properties =
             node_component_factory.build_inline_properties(inline_properties.clone());
         args.prototypical_properties_factory = updated_properties;
@@ -594,6 +632,18 @@ pub trait DefinitionToInstanceTraverser {
     }
 }
 
+/// Whether `node` is part of this build, per its `cfg` condition.  The compiler leaves out the
+/// nodes that aren't, except in designer builds, which record their target and features instead
+fn is_enabled(
+    manifest: &pax_manifest::PaxManifest,
+    node: &pax_manifest::TemplateNodeDefinition,
+) -> bool {
+    manifest
+        .build_cfg
+        .as_ref()
+        .map_or(true, |build_cfg| node.is_enabled_for(build_cfg))
+}
+
 fn resolve_property<T: CoercionRules + PropertyValue + DeserializeOwned>(
     name: &str,
     defined_properties: &BTreeMap<String, ValueDefinition>,
@@ -803,6 +853,7 @@ fn create_new_common_properties(
         anchor_x: resolve_property("anchor_x", defined_properties, stack_frame),
         anchor_y: resolve_property("anchor_y", defined_properties, stack_frame),
         unclippable: resolve_property("unclippable", defined_properties, stack_frame),
+        text_style: resolve_property("text_style", defined_properties, stack_frame),
         _raycastable: resolve_property("_raycastable", defined_properties, stack_frame),
         _suspended: resolve_property("_suspended", defined_properties, stack_frame),
     }))
@@ -850,6 +901,11 @@ fn update_common_properties(
         defined_properties,
         stack_frame,
     ));
+    cp.text_style.replace_with(resolve_property(
+        "text_style",
+        defined_properties,
+        stack_frame,
+    ));
     cp._raycastable.replace_with(resolve_property(
         "_raycastable",
         defined_properties,
@@ -74,6 +74,9 @@ impl InstanceNode for ConditionalInstance {
         let dep = cond_expr.untyped();
 
         let old_val = RefCell::new(false);
+        // returned while the condition holds its value, as reading `children` from
+        // its own evaluator is a property cycle
+        let old_children = RefCell::new(Vec::new());
         expanded_node
             .children
             .replace_with(Property::computed_with_name(
@@ -83,10 +86,10 @@ impl InstanceNode for ConditionalInstance {
                     };
                     let val = cond_expr.get();
                     if val == *borrow!(old_val) {
-                        return cloned_expanded_node.children.get();
+                        return borrow!(old_children).clone();
                     }
                     *borrow_mut!(old_val) = val;
-                    if val {
+                    let children = if val {
                         let env = Rc::clone(&cloned_expanded_node.stack);
                         let children = borrow!(cloned_self.base().get_instance_children());
                         let children_with_envs = children.iter().cloned().zip(iter::repeat(env));
@@ -102,7 +105,9 @@ impl InstanceNode for ConditionalInstance {
                             &cloned_context,
                             &cloned_expanded_node.parent_frame,
                         )
-                    }
+                    };
+                    *borrow_mut!(old_children) = children.clone();
+                    children
                 },
                 &[dep],
                 &format!("conditional_children (node id: {})", expanded_node.id.0),
@@ -9,6 +9,7 @@ pub const KEY_UP_HANDLERS: &str = "key_up";
 pub const KEY_PRESS_HANDLERS: &str = "key_press";
 pub const CHECKBOX_CHANGE_HANDLERS: &str = "checkbox_change";
 pub const BUTTON_CLICK_HANDLERS: &str = "button_click";
+pub const VIDEO_ENDED_HANDLERS: &str = "ended";
 pub const TEXTBOX_CHANGE_HANDLERS: &str = "textbox_change";
 pub const TEXT_INPUT_HANDLERS: &str = "text_input";
 pub const TEXTBOX_INPUT_HANDLERS: &str = "textbox_input";
@@ -0,0 +1,96 @@
+//! Debug overlay drawn over the normal render when enabled with
+//! [`PaxEngine::set_debug_overlay`](super::PaxEngine::set_debug_overlay): the bounds of every
+//! expanded node, labeled with its z-index and expanded node id, e.g. `12 345`.
+//! [`RenderContext`] has no text support, so labels are stroked as seven-segment digits.
+
+use std::rc::Rc;
+
+use kurbo::{Affine, BezPath, Point};
+use piet::Color;
+
+use crate::api::RenderContext;
+use crate::{ExpandedNode, RuntimeContext};
+
+const STROKE_WIDTH: f64 = 1.0;
+const GLYPH_WIDTH: f64 = 4.0;
+const GLYPH_HEIGHT: f64 = 7.0;
+const GLYPH_SPACING: f64 = 2.0;
+const LABEL_MARGIN: f64 = 2.0;
+
+/// Outline colors, picked by z-index so that overlapping neighbours are told apart
+const PALETTE: [(u8, u8, u8); 4] = [(255, 0, 170), (0, 190, 255), (255, 140, 0), (60, 210, 60)];
+
+/// Segment endpoints, in glyph units from the top left corner of a 1x2 glyph
+const SEGMENTS: [((f64, f64), (f64, f64)); 7] = [
+    ((0.0, 0.0), (1.0, 0.0)), // top
+    ((1.0, 0.0), (1.0, 1.0)), // top right
+    ((1.0, 1.0), (1.0, 2.0)), // bottom right
+    ((0.0, 2.0), (1.0, 2.0)), // bottom
+    ((0.0, 1.0), (0.0, 2.0)), // bottom left
+    ((0.0, 0.0), (0.0, 1.0)), // top left
+    ((0.0, 1.0), (1.0, 1.0)), // middle
+];
+
+/// Lit segments of each digit, bit `i` standing for `SEGMENTS[i]`
+const DIGITS: [u8; 10] = [
+    0b0111111, 0b0000110, 0b1011011, 0b1001111, 0b1100110, 0b1101101, 0b1111101, 0b0000111,
+    0b1111111, 0b1101111,
+];
+
+const MINUS: u8 = 0b1000000;
+
+pub fn render_debug_overlay(
+    root: &Rc<ExpandedNode>,
+    ctx: &Rc<RuntimeContext>,
+    rcs: &mut dyn RenderContext,
+) {
+    // drawn to the topmost canvas layer, so that outlines aren't covered by other canvas content
+    let layer = format!("{}", ctx.layer_count.get().saturating_sub(1));
+    render_node_overlay(root, &layer, rcs);
+}
+
+fn render_node_overlay(node: &Rc<ExpandedNode>, layer: &str, rcs: &mut dyn RenderContext) {
+    let tab = node.transform_and_bounds.get();
+    let occlusion = node.occlusion.get();
+    let (r, g, b) = PALETTE[occlusion.z_index.rem_euclid(PALETTE.len() as i32) as usize];
+    let brush = Color::rgb8(r, g, b).into();
+
+    let corners = tab.corners();
+    let mut outline = BezPath::new();
+    outline.move_to((corners[0].x, corners[0].y));
+    for corner in &corners[1..] {
+        outline.line_to((corner.x, corner.y));
+    }
+    outline.close_path();
+    rcs.stroke(layer, outline, &brush, STROKE_WIDTH);
+
+    let label = format!("{} {}", occlusion.z_index, node.id.to_u32());
+    let origin = Affine::translate((corners[0].x + LABEL_MARGIN, corners[0].y + LABEL_MARGIN));
+    rcs.stroke(layer, origin * label_path(&label), &brush, STROKE_WIDTH);
+
+    for child in node.children.get().iter() {
+        render_node_overlay(child, layer, rcs);
+    }
+}
+
+/// Path of `label` drawn with seven-segment glyphs, supporting digits, `-` and spaces
+fn label_path(label: &str) -> BezPath {
+    let mut path = BezPath::new();
+    let scale = (GLYPH_WIDTH, GLYPH_HEIGHT / 2.0);
+    for (i, c) in label.chars().enumerate() {
+        let segments = match c {
+            '0'..='9' => DIGITS[c as usize - '0' as usize],
+            '-' => MINUS,
+            _ => continue,
+        };
+        let x = i as f64 * (GLYPH_WIDTH + GLYPH_SPACING);
+        let at = |(sx, sy): (f64, f64)| Point::new(x + sx * scale.0, sy * scale.1);
+        for (bit, (from, to)) in SEGMENTS.iter().enumerate() {
+            if segments & (1 << bit) != 0 {
+                path.move_to(at(*from));
+                path.line_to(at(*to));
+            }
+        }
+    }
+    path
+}
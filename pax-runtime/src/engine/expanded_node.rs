@@ -2,20 +2,23 @@ use crate::api::TextInput;
 use crate::node_interface::NodeLocal;
 use pax_runtime_api::pax_value::{ImplToFromPaxAny, PaxAny, ToFromPaxAny};
 use pax_runtime_api::{
-    borrow, borrow_mut, use_RefCell, Focus, Interpolatable, Percent, Property, Variable, Viewport,
+    borrow, borrow_mut, use_RefCell, Focus, Interpolatable, PaxValue, Percent, Property, Variable,
+    Viewport,
 };
 
-use crate::api::math::Point2;
+use crate::api::math::{Point2, Transform2};
 use crate::constants::{
     BUTTON_CLICK_HANDLERS, CHECKBOX_CHANGE_HANDLERS, CLAP_HANDLERS, CLICK_HANDLERS,
     CONTEXT_MENU_HANDLERS, DOUBLE_CLICK_HANDLERS, DROP_HANDLERS, FOCUSED_HANDLERS,
     KEY_DOWN_HANDLERS, KEY_PRESS_HANDLERS, KEY_UP_HANDLERS, MOUSE_DOWN_HANDLERS,
     MOUSE_MOVE_HANDLERS, MOUSE_OUT_HANDLERS, MOUSE_OVER_HANDLERS, MOUSE_UP_HANDLERS,
     SCROLL_HANDLERS, TEXTBOX_CHANGE_HANDLERS, TEXTBOX_INPUT_HANDLERS, TEXT_INPUT_HANDLERS,
-    TOUCH_END_HANDLERS, TOUCH_MOVE_HANDLERS, TOUCH_START_HANDLERS, WHEEL_HANDLERS,
+    TOUCH_END_HANDLERS, TOUCH_MOVE_HANDLERS, TOUCH_START_HANDLERS, VIDEO_ENDED_HANDLERS,
+    WHEEL_HANDLERS,
 };
 use_RefCell!();
 use crate::{ExpandedNodeIdentifier, Globals, LayoutProperties, TransformAndBounds};
+use pax_manifest::UniqueTemplateNodeIdentifier;
 use core::fmt;
 use std::cell::Cell;
 use std::collections::HashMap;
@@ -25,12 +28,13 @@ use crate::api::{
     ButtonClick, CheckboxChange, Clap, Click, CommonProperties, ContextMenu, DoubleClick, Drop,
     Event, KeyDown, KeyPress, KeyUp, MouseDown, MouseMove, MouseOut, MouseOver, MouseUp,
     NodeContext, RenderContext, Scroll, Size, TextboxChange, TextboxInput, TouchEnd, TouchMove,
-    TouchStart, Wheel, Window,
+    TouchStart, VideoEnded, Wheel, Window,
 };
 
+use super::text_style_cascade::layer_text_style;
 use crate::{
-    compute_tab, ComponentInstance, HandlerLocation, InstanceNode, InstanceNodePtr, RuntimeContext,
-    RuntimePropertiesStackFrame,
+    calculate_transform_and_bounds, compute_tab, node_costs::NodeCostPhase, ComponentInstance,
+    HandlerLocation, InstanceNode, InstanceNodePtr, RuntimeContext, RuntimePropertiesStackFrame,
 };
 
 #[derive(Clone)]
@@ -87,6 +91,13 @@ pub struct ExpandedNode {
     /// computed property based on parent bounds + common properties
     pub transform_and_bounds: Property<TransformAndBounds<NodeLocal, Window>>,
 
+    /// The global transform this node was last rendered with, written during the
+    /// render pass. Unlike `transform_and_bounds`, reading this doesn't evaluate
+    /// (or subscribe to) the layout of the node, so it's safe to use outside of
+    /// the render loop, e.g. for selection feedback once a tick has completed.
+    /// `None` until the node has been rendered once.
+    pub computed_global_transform: Cell<Option<Transform2<NodeLocal, Window>>>,
+
     /// For component instances only, tracks the expanded slot_children in its
     /// non-collapsed form (repeat and conditionals still present). This allows
     /// repeat/conditionals to update their children (handled in component.rs
@@ -126,6 +137,11 @@ pub struct ExpandedNode {
 
     /// used by native elements to trigger sending of native messages
     pub native_message_listener: Property<()>,
+
+    /// The text style defaults of this node and its descendants: the `text_style` common
+    /// property of this node layered over the one resolved by its render parent, see
+    /// [`layer_text_style`]. Text primitives layer their own style over this one.
+    pub resolved_text_style: Property<Option<PaxValue>>,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
@@ -246,6 +262,7 @@ impl ExpandedNode {
             ),
             mounted_children: RefCell::new(Vec::new()),
             transform_and_bounds: Property::new(TransformAndBounds::default()),
+            computed_global_transform: Cell::new(None),
             expanded_slot_children: Default::default(),
             expanded_and_flattened_slot_children: Default::default(),
             flattened_slot_children_count: Property::new(0),
@@ -254,7 +271,9 @@ impl ExpandedNode {
             slot_index: Property::default(),
             suspended: Property::new(false),
             native_message_listener: Property::default(),
+            resolved_text_style: Property::default(),
         });
+        res.bind_to_parent_text_style();
         res
     }
 
@@ -297,10 +316,22 @@ impl ExpandedNode {
         self.occlusion.set(Default::default());
 
         self.bind_to_parent_bounds(context);
+        self.bind_to_parent_text_style();
         Rc::clone(self).recurse_mount(context);
         Rc::clone(self).recurse_update(context);
     }
 
+    /// The template node this node was expanded from, that is the node to edit in the manifest.
+    /// Every node instantiated from a component template has one, the nodes of the instances of
+    /// a node in a `for` sharing it.  `None` for nodes built outside of a template, e.g. through
+    /// `DefinitionToInstanceTraverser::get_component`
+    pub fn global_id(&self) -> Option<UniqueTemplateNodeIdentifier> {
+        borrow!(self.instance_node)
+            .base()
+            .template_node_identifier
+            .clone()
+    }
+
     /// Returns whether this node is a descendant of the ExpandedNode described by `other_expanded_node_id` (id)
     /// Currently requires traversing linked list of ancestry, incurring a O(log(n)) cost for a tree of `n` elements.
     /// This could be mitigated with caching/memoization, perhaps by storing a HashSet on each ExpandedNode describing its ancestry chain.
@@ -364,6 +395,7 @@ impl ExpandedNode {
 
             // suspension is used in the designer to turn of/on tick/update
             child.inherit_suspend(self);
+            child.bind_to_parent_text_style();
         }
         if self.attached.get() > 0 {
             for child in curr_children.iter() {
@@ -394,6 +426,20 @@ impl ExpandedNode {
         self.transform_and_bounds.replace_with(transform_and_bounds);
     }
 
+    fn bind_to_parent_text_style(self: &Rc<Self>) {
+        let cp = self.get_common_properties();
+        let own = borrow!(cp).text_style.clone();
+        let inherited = borrow!(self.render_parent)
+            .upgrade()
+            .map(|parent| parent.resolved_text_style.clone())
+            .unwrap_or_default();
+        let deps = [inherited.untyped(), own.untyped()];
+        self.resolved_text_style.replace_with(Property::computed(
+            move || layer_text_style(inherited.get(), own.get()),
+            &deps,
+        ));
+    }
+
     pub fn inherit_suspend(self: &Rc<Self>, node: &Rc<Self>) {
         let cp = self.get_common_properties();
         let self_suspended = borrow!(cp)._suspended.clone();
@@ -423,6 +469,16 @@ impl ExpandedNode {
     /// This method recursively updates all node properties. When dirty-dag exists, this won't
     /// need to be here since all property dependencies can be set up and removed during mount/unmount
     pub fn recurse_update(self: &Rc<Self>, context: &Rc<RuntimeContext>) {
+        self.measure_cost(context, NodeCostPhase::Properties, || {
+            self.update_self(context)
+        });
+        for child in self.children.get().iter() {
+            child.recurse_update(context);
+        }
+    }
+
+    /// Fires the `tick` and `pre_render` handlers of this node, and updates its properties
+    fn update_self(self: &Rc<Self>, context: &Rc<RuntimeContext>) {
         if let Some(ref registry) = borrow!(self.instance_node).base().handler_registry {
             if !self.suspended.get() {
                 for handler in borrow!(registry)
@@ -457,15 +513,36 @@ impl ExpandedNode {
                 }
             }
         }
-        for child in self.children.get().iter() {
-            child.recurse_update(context);
-        }
+    }
+
+    /// Runs `f`, attributing the time it takes to `phase` of this node while node costs are
+    /// sampled, see `pax_designtime::node_costs`
+    #[cfg(feature = "designtime")]
+    fn measure_cost<R>(
+        &self,
+        context: &Rc<RuntimeContext>,
+        phase: NodeCostPhase,
+        f: impl FnOnce() -> R,
+    ) -> R {
+        context.node_costs().measure(self, phase, f)
+    }
+
+    #[cfg(not(feature = "designtime"))]
+    #[inline(always)]
+    fn measure_cost<R>(
+        &self,
+        _context: &Rc<RuntimeContext>,
+        _phase: NodeCostPhase,
+        f: impl FnOnce() -> R,
+    ) -> R {
+        f()
     }
 
     pub fn recurse_mount(self: &Rc<Self>, context: &Rc<RuntimeContext>) {
         if self.attached.get() == 0 {
             self.attached.set(self.attached.get() + 1);
             context.add_to_cache(&self);
+            context.record_mount(&self);
             if let Some(ref registry) = borrow!(self.instance_node).base().handler_registry {
                 for handler in borrow!(registry)
                     .handlers
@@ -501,6 +578,7 @@ impl ExpandedNode {
         if self.attached.get() == 1 {
             self.attached.set(self.attached.get() - 1);
             context.remove_from_cache(&self);
+            context.record_unmount(&self);
             for child in borrow!(self.mounted_children).iter() {
                 Rc::clone(child).recurse_unmount(context);
             }
@@ -538,12 +616,21 @@ impl ExpandedNode {
     }
 
     pub fn recurse_render(self: &Rc<Self>, ctx: &Rc<RuntimeContext>, rcs: &mut dyn RenderContext) {
-        borrow!(self.instance_node).handle_pre_render(&self, ctx, rcs);
+        let transform_and_bounds = self.measure_cost(ctx, NodeCostPhase::Layout, || {
+            self.transform_and_bounds.get()
+        });
+        self.computed_global_transform
+            .set(Some(transform_and_bounds.transform));
+        self.measure_cost(ctx, NodeCostPhase::Render, || {
+            borrow!(self.instance_node).handle_pre_render(&self, ctx, rcs)
+        });
         for child in self.children.get().iter().rev() {
             child.recurse_render_queue(ctx, rcs);
         }
-        borrow!(self.instance_node).render(&self, ctx, rcs);
-        borrow!(self.instance_node).handle_post_render(&self, ctx, rcs);
+        self.measure_cost(ctx, NodeCostPhase::Render, || {
+            borrow!(self.instance_node).render(&self, ctx, rcs);
+            borrow!(self.instance_node).handle_post_render(&self, ctx, rcs);
+        });
     }
 
     /// Manages unpacking an Rc<RefCell<PaxValue>>, downcasting into
@@ -596,14 +683,23 @@ impl ExpandedNode {
         let deps = [t_and_b_parent.untyped()];
         let bounds_parent = Property::computed(move || t_and_b_parent.get().bounds, &deps);
 
-        let slot_children_count = if borrow!(self.instance_node).base().flags().is_component {
-            self.flattened_slot_children_count.clone()
-        } else {
-            self.containing_component
-                .upgrade()
-                .map(|v| v.flattened_slot_children_count.clone())
-                .unwrap_or_default()
-        };
+        let (slot_children_count, slot_children) =
+            if borrow!(self.instance_node).base().flags().is_component {
+                (
+                    self.flattened_slot_children_count.clone(),
+                    self.expanded_and_flattened_slot_children.clone(),
+                )
+            } else {
+                self.containing_component
+                    .upgrade()
+                    .map(|v| {
+                        (
+                            v.flattened_slot_children_count.clone(),
+                            v.expanded_and_flattened_slot_children.clone(),
+                        )
+                    })
+                    .unwrap_or_default()
+            };
 
         let last_frame = Rc::new(RefCell::new(globals.frames_elapsed.get()));
         let suspended = self.suspended.clone();
@@ -636,6 +732,7 @@ impl ExpandedNode {
             os: globals.os.clone(),
             get_elapsed_millis: globals.get_elapsed_millis,
             slot_children_count,
+            slot_children,
             node_transform_and_bounds: self.transform_and_bounds.get(),
             #[cfg(feature = "designtime")]
             designtime: globals.designtime.clone(),
@@ -665,7 +762,7 @@ impl ExpandedNode {
             && transformed_ray.y > 0.0
             && transformed_ray.x < width
             && transformed_ray.y < height;
-        res
+        res && borrow!(self.instance_node).hit_test(self, transformed_ray)
     }
 
     pub fn compute_flattened_slot_children(&self) {
@@ -734,6 +831,12 @@ impl ExpandedNode {
         BUTTON_CLICK_HANDLERS,
         true
     );
+    dispatch_event_handler!(
+        dispatch_video_ended,
+        VideoEnded,
+        VIDEO_ENDED_HANDLERS,
+        false
+    );
     dispatch_event_handler!(dispatch_mouse_down, MouseDown, MOUSE_DOWN_HANDLERS, true);
     dispatch_event_handler!(dispatch_mouse_up, MouseUp, MOUSE_UP_HANDLERS, true);
     dispatch_event_handler!(dispatch_mouse_move, MouseMove, MOUSE_MOVE_HANDLERS, true);
@@ -791,6 +894,18 @@ impl ExpandedNode {
         self.rendered_size.set(Some((width, height)));
     }
 
+    /// Size this node would have if laid out in a container of size `available`, without
+    /// committing it to the layout.  Nodes without an explicit width/height report their
+    /// rendered size if the chassis reported one (e.g. auto sized text), and fill `available`
+    /// otherwise.
+    pub fn measure(self: &Rc<ExpandedNode>, available: (f64, f64)) -> (f64, f64) {
+        let container = TransformAndBounds {
+            transform: Transform2::identity(),
+            bounds: available,
+        };
+        calculate_transform_and_bounds(&self.layout_properties().get(), container).bounds
+    }
+
     /// Helper method that returns a collection of common properties
     /// related to layout (position, size, scale, anchor, etc),
     pub fn layout_properties(self: &Rc<ExpandedNode>) -> Property<LayoutProperties> {
@@ -0,0 +1,531 @@
+use std::collections::HashMap;
+
+use pax_message::{NativeMessage, NativeMessageChunk};
+use serde::{Serialize, Serializer};
+use serde_json::{Map, Value};
+
+/// Default upper bound of the serialized size of a chunk, see [`MessageChunks`]
+pub const DEFAULT_MESSAGE_CHUNK_BYTES: usize = 256 * 1024;
+
+/// Room left in each chunk's buffer for its envelope, see [`NativeMessageChunk`]
+const ENVELOPE_BYTES: usize = 128;
+
+/// Transfer of the native messages of each frame to a chassis in bounded-size chunks,
+/// with backpressure.
+///
+/// Serializing the messages of a large frame (the first paint of a big app, a full
+/// reload in the designer, a repeat expanding thousands of nodes) to a single buffer
+/// can take megabytes of contiguous memory, and stalls the boundary with the chassis
+/// while it's parsed in one go. Instead the messages of a frame are split into chunks
+/// of at most `chunk_bytes` (a single message larger than that gets a chunk of its
+/// own), serialized one at a time as the chassis pulls them with [`Self::next_chunk`],
+/// each carrying the frame id, its index and the number of chunks of the frame.
+/// Messages are independent of each other, so chassis can apply each chunk as it
+/// comes in.
+///
+/// Once it has applied the last chunk of a frame, the chassis acknowledges the frame
+/// with [`Self::acknowledge`]. Until then, the messages of following ticks are held
+/// back and coalesced instead of being queued up as frames of their own: an update
+/// patch superseded by a later one for the same element is merged into it, so that
+/// a slow consumer receives the latest state of each element once it catches up.
+/// Creates, deletes and all other messages are always kept, in order.
+pub struct MessageChunks {
+    chunk_bytes: usize,
+    next_frame_id: u64,
+    /// Frame being transferred, until the chassis acknowledges it
+    frame: Option<ChunkedFrame>,
+    backlog: Backlog,
+    stats: MessageChunkStats,
+}
+
+/// Counters of the chunked transfer since startup
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct MessageChunkStats {
+    /// Frames with messages handed to the chassis
+    pub frames: u64,
+    pub chunks: u64,
+    /// Chunks of the last frame
+    pub last_frame_chunks: usize,
+    /// Ticks whose messages were held back because the previous frame wasn't consumed yet
+    pub coalesced_ticks: u64,
+    /// Update patches dropped because a later patch for the same element superseded them
+    pub coalesced_drops: u64,
+}
+
+struct ChunkedFrame {
+    id: u64,
+    messages: Vec<QueuedMessage>,
+    /// End of the messages of each chunk, exclusive
+    chunk_ends: Vec<usize>,
+    /// Serialized size of the messages of each chunk, without the envelope
+    chunk_sizes: Vec<usize>,
+    next_chunk: usize,
+}
+
+// as large as the native messages the engine produces, boxing them would only add allocations
+#[allow(clippy::large_enum_variant)]
+enum QueuedMessage {
+    Native(NativeMessage),
+    /// A message held back in the backlog, possibly merged with the ones it superseded
+    Coalesced(Value),
+}
+
+impl Serialize for QueuedMessage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            QueuedMessage::Native(message) => message.serialize(serializer),
+            QueuedMessage::Coalesced(message) => message.serialize(serializer),
+        }
+    }
+}
+
+/// Messages of the ticks held back while waiting for the chassis, in JSON form so that
+/// update patches can be merged
+#[derive(Default)]
+struct Backlog {
+    /// `None` for the patches merged into later ones
+    messages: Vec<Option<Value>>,
+    /// Per native element id, the index of the latest update patch of each kind
+    latest_updates: HashMap<u64, HashMap<String, usize>>,
+    /// Patches merged into later ones, left as `None` in `messages` until compacted
+    holes: usize,
+    dropped: u64,
+}
+
+impl Backlog {
+    fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    fn push(&mut self, message: &NativeMessage) {
+        let message = serde_json::to_value(message).expect("native messages serialize to JSON");
+        let Some((kind, payload)) = message.as_object().and_then(|m| m.iter().next()) else {
+            self.messages.push(Some(message));
+            return;
+        };
+        match element_id(kind, payload) {
+            Some((id, true)) => {
+                let kind = kind.clone();
+                let index = self.messages.len();
+                let older = self
+                    .latest_updates
+                    .entry(id)
+                    .or_default()
+                    .insert(kind.clone(), index)
+                    .and_then(|older| self.messages[older].take());
+                let message = match older {
+                    Some(older) => {
+                        self.dropped += 1;
+                        self.holes += 1;
+                        merge_update(&kind, older, message)
+                    }
+                    None => message,
+                };
+                self.messages.push(Some(message));
+                if self.holes > self.messages.len() / 2 {
+                    self.compact();
+                }
+                return;
+            }
+            // an update on either side of a create or delete doesn't supersede the other,
+            // the element they apply to being a different one (ids are reused)
+            Some((id, false)) => {
+                self.latest_updates.remove(&id);
+            }
+            None => {}
+        }
+        self.messages.push(Some(message));
+    }
+
+    /// Removes the holes left by merged patches, so that the backlog of a consumer that
+    /// doesn't catch up stays proportional to the number of elements updated
+    fn compact(&mut self) {
+        self.messages.retain(Option::is_some);
+        self.holes = 0;
+        self.latest_updates.clear();
+        for (index, message) in self.messages.iter().enumerate() {
+            let Some((kind, payload)) = message
+                .as_ref()
+                .and_then(Value::as_object)
+                .and_then(|m| m.iter().next())
+            else {
+                continue;
+            };
+            match element_id(kind, payload) {
+                Some((id, true)) => {
+                    self.latest_updates
+                        .entry(id)
+                        .or_default()
+                        .insert(kind.clone(), index);
+                }
+                Some((id, false)) => {
+                    self.latest_updates.remove(&id);
+                }
+                None => {}
+            }
+        }
+    }
+
+    fn take(&mut self) -> Vec<QueuedMessage> {
+        self.latest_updates.clear();
+        self.holes = 0;
+        std::mem::take(&mut self.messages)
+            .into_iter()
+            .flatten()
+            .map(QueuedMessage::Coalesced)
+            .collect()
+    }
+}
+
+/// Id of the native element a message of kind `kind` applies to, and whether it's an
+/// update patch (as opposed to a create or delete)
+fn element_id(kind: &str, payload: &Value) -> Option<(u64, bool)> {
+    if kind.ends_with("Update") {
+        Some((payload.get("id")?.as_u64()?, true))
+    } else if kind.ends_with("Create") || kind.ends_with("Delete") {
+        Some((payload.get("id").unwrap_or(payload).as_u64()?, false))
+    } else {
+        None
+    }
+}
+
+/// `newer` with the fields `older` set and `newer` leaves unchanged (`null`). Occlusion
+/// updates carry the full state of an element, and replace older ones outright.
+fn merge_update(kind: &str, older: Value, newer: Value) -> Value {
+    if kind == "OcclusionUpdate" {
+        return newer;
+    }
+    let (Value::Object(mut older), Value::Object(newer)) = (older, newer) else {
+        unreachable!("native messages serialize to single-key objects")
+    };
+    let (Some(Value::Object(older_patch)), Some(Value::Object(newer_patch))) =
+        (older.remove(kind), newer.get(kind))
+    else {
+        return Value::Object(newer);
+    };
+    let mut merged = older_patch;
+    for (field, value) in newer_patch {
+        if !value.is_null() {
+            merged.insert(field.clone(), value.clone());
+        }
+    }
+    let mut message = Map::new();
+    message.insert(kind.to_string(), Value::Object(merged));
+    Value::Object(message)
+}
+
+/// Counts the bytes written to it, to size chunks without allocating their buffers
+#[derive(Default)]
+struct ByteCounter(usize);
+
+impl std::io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn serialized_len(message: &QueuedMessage) -> usize {
+    let mut counter = ByteCounter::default();
+    serde_json::to_writer(&mut counter, message).expect("native messages serialize to JSON");
+    counter.0
+}
+
+impl Default for MessageChunks {
+    fn default() -> Self {
+        Self::new(DEFAULT_MESSAGE_CHUNK_BYTES)
+    }
+}
+
+impl MessageChunks {
+    pub fn new(chunk_bytes: usize) -> Self {
+        Self {
+            chunk_bytes,
+            next_frame_id: 0,
+            frame: None,
+            backlog: Backlog::default(),
+            stats: MessageChunkStats::default(),
+        }
+    }
+
+    /// Sets the upper bound of the serialized size of chunks, from the next frame on
+    pub fn set_chunk_bytes(&mut self, chunk_bytes: usize) {
+        self.chunk_bytes = chunk_bytes;
+    }
+
+    pub fn stats(&self) -> &MessageChunkStats {
+        &self.stats
+    }
+
+    /// Whether chunks of a frame are waiting to be acknowledged
+    pub fn is_waiting(&self) -> bool {
+        self.frame.is_some()
+    }
+
+    /// Queues the messages of a tick: as a new frame if the chassis consumed the previous
+    /// one, along with the messages held back since, or into the backlog otherwise.
+    /// Ticks without messages don't make a frame.
+    pub fn push_tick(&mut self, messages: Vec<NativeMessage>) {
+        if self.frame.is_some() || !self.backlog.is_empty() {
+            if self.frame.is_some() && !messages.is_empty() {
+                self.stats.coalesced_ticks += 1;
+            }
+            for message in &messages {
+                self.backlog.push(message);
+            }
+            self.stats.coalesced_drops += std::mem::take(&mut self.backlog.dropped);
+            if self.frame.is_some() {
+                return;
+            }
+        }
+        let messages = if self.backlog.is_empty() {
+            messages.into_iter().map(QueuedMessage::Native).collect()
+        } else {
+            self.backlog.take()
+        };
+        if messages.is_empty() {
+            return;
+        }
+
+        let mut chunk_ends = vec![];
+        let mut chunk_sizes = vec![];
+        let mut size = 0;
+        for (i, message) in messages.iter().enumerate() {
+            let len = serialized_len(message) + 1; // separating comma
+            if size > 0 && size + len > self.chunk_bytes.saturating_sub(ENVELOPE_BYTES) {
+                chunk_ends.push(i);
+                chunk_sizes.push(size);
+                size = 0;
+            }
+            size += len;
+        }
+        chunk_ends.push(messages.len());
+        chunk_sizes.push(size);
+
+        self.stats.frames += 1;
+        self.stats.last_frame_chunks = chunk_ends.len();
+        self.frame = Some(ChunkedFrame {
+            id: self.next_frame_id,
+            messages,
+            chunk_ends,
+            chunk_sizes,
+            next_chunk: 0,
+        });
+        self.next_frame_id += 1;
+    }
+
+    /// The next chunk of the frame being transferred, serialized as JSON, see
+    /// [`NativeMessageChunk`]
+    pub fn next_chunk(&mut self) -> Option<Vec<u8>> {
+        let frame = self.frame.as_mut()?;
+        let index = frame.next_chunk;
+        let end = *frame.chunk_ends.get(index)?;
+        let start = index.checked_sub(1).map_or(0, |i| frame.chunk_ends[i]);
+        let chunk = NativeMessageChunk {
+            frame_id: frame.id,
+            chunk_index: index,
+            chunk_count: frame.chunk_ends.len(),
+            messages: &frame.messages[start..end],
+        };
+        let mut bytes = Vec::with_capacity(frame.chunk_sizes[index] + ENVELOPE_BYTES);
+        serde_json::to_writer(&mut bytes, &chunk).expect("native messages serialize to JSON");
+        frame.next_chunk += 1;
+        self.stats.chunks += 1;
+        Some(bytes)
+    }
+
+    /// Called by the chassis once it has applied the last chunk of frame `frame_id`
+    pub fn acknowledge(&mut self, frame_id: u64) {
+        if self
+            .frame
+            .as_ref()
+            .is_some_and(|frame| frame.id == frame_id)
+        {
+            self.frame = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pax_message::{AnyCreatePatch, OcclusionPatch, TextPatch};
+
+    use super::*;
+
+    fn text_update(id: u32, content: Option<&str>, size_x: Option<f64>) -> NativeMessage {
+        NativeMessage::TextUpdate(TextPatch {
+            id,
+            content: content.map(str::to_string),
+            size_x,
+            ..Default::default()
+        })
+    }
+
+    fn text_create(id: u32) -> NativeMessage {
+        NativeMessage::TextCreate(AnyCreatePatch {
+            id,
+            parent_frame: None,
+            occlusion_layer_id: 0,
+        })
+    }
+
+    fn chunk_json(chunks: &mut MessageChunks) -> Option<Value> {
+        chunks
+            .next_chunk()
+            .map(|bytes| serde_json::from_slice(&bytes).unwrap())
+    }
+
+    /// Messages of all remaining chunks of the current frame, acknowledging it
+    fn drain(chunks: &mut MessageChunks) -> Vec<Value> {
+        let mut messages = vec![];
+        let mut frame_id = None;
+        while let Some(chunk) = chunk_json(chunks) {
+            frame_id = chunk["frame_id"].as_u64();
+            messages.extend(chunk["messages"].as_array().unwrap().iter().cloned());
+        }
+        if let Some(frame_id) = frame_id {
+            chunks.acknowledge(frame_id);
+        }
+        messages
+    }
+
+    #[test]
+    fn frames_are_split_into_bounded_chunks() {
+        let mut chunks = MessageChunks::new(1024);
+        let messages: Vec<_> = (0..100)
+            .map(|id| text_update(id, Some("some text content"), Some(100.0)))
+            .collect();
+        chunks.push_tick(messages);
+
+        let mut ids = vec![];
+        let mut index = 0;
+        while let Some(bytes) = chunks.next_chunk() {
+            assert!(bytes.len() <= 1024, "chunk of {} bytes", bytes.len());
+            let chunk: Value = serde_json::from_slice(&bytes).unwrap();
+            assert_eq!(chunk["frame_id"], 0);
+            assert_eq!(chunk["chunk_index"], index);
+            assert_eq!(chunk["chunk_count"], chunks.stats().last_frame_chunks);
+            ids.extend(
+                chunk["messages"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|m| m["TextUpdate"]["id"].as_u64().unwrap()),
+            );
+            index += 1;
+        }
+        assert!(index > 1);
+        assert_eq!(ids, (0..100).collect::<Vec<_>>());
+        assert_eq!(chunks.stats().chunks, index as u64);
+    }
+
+    #[test]
+    fn oversized_messages_get_a_chunk_of_their_own() {
+        let mut chunks = MessageChunks::new(256);
+        let long = "x".repeat(1000);
+        chunks.push_tick(vec![
+            text_update(1, Some("a"), None),
+            text_update(2, Some(&long), None),
+            text_update(3, Some("b"), None),
+        ]);
+        assert_eq!(chunks.stats().last_frame_chunks, 3);
+        assert_eq!(drain(&mut chunks).len(), 3);
+    }
+
+    #[test]
+    fn ticks_without_messages_make_no_frame() {
+        let mut chunks = MessageChunks::default();
+        chunks.push_tick(vec![]);
+        assert!(!chunks.is_waiting());
+        assert!(chunks.next_chunk().is_none());
+        assert_eq!(chunks.stats().frames, 0);
+    }
+
+    #[test]
+    fn unconsumed_frames_hold_back_and_coalesce_later_ticks() {
+        let mut chunks = MessageChunks::default();
+        chunks.push_tick(vec![text_create(1), text_update(1, Some("a"), Some(10.0))]);
+        // the chassis pulls the frame but doesn't get to acknowledge it before the next ticks
+        chunk_json(&mut chunks).unwrap();
+        chunks.push_tick(vec![text_update(1, Some("b"), None)]);
+        chunks.push_tick(vec![
+            text_update(1, None, Some(20.0)),
+            text_update(2, Some("c"), None),
+        ]);
+        assert!(chunks.next_chunk().is_none());
+        assert_eq!(chunks.stats().coalesced_ticks, 2);
+        assert_eq!(chunks.stats().coalesced_drops, 1);
+
+        chunks.acknowledge(0);
+        chunks.push_tick(vec![]);
+        let messages = drain(&mut chunks);
+        assert_eq!(messages.len(), 2);
+        // fields left unchanged by the later patch are kept from the earlier one
+        assert_eq!(messages[0]["TextUpdate"]["id"], 1);
+        assert_eq!(messages[0]["TextUpdate"]["content"], "b");
+        assert_eq!(messages[0]["TextUpdate"]["size_x"], 20.0);
+        assert_eq!(messages[1]["TextUpdate"]["id"], 2);
+        assert_eq!(chunks.stats().frames, 2);
+    }
+
+    #[test]
+    fn creates_and_deletes_are_never_coalesced() {
+        let mut chunks = MessageChunks::default();
+        chunks.push_tick(vec![text_create(9)]);
+        chunks.push_tick(vec![
+            text_update(1, Some("a"), None),
+            NativeMessage::TextDelete(1),
+            text_create(1),
+            text_update(1, Some("b"), None),
+            NativeMessage::OcclusionUpdate(OcclusionPatch {
+                id: 1,
+                occlusion_layer_id: 1,
+                z_index: 0,
+                parent_frame: Some(4),
+            }),
+            NativeMessage::OcclusionUpdate(OcclusionPatch {
+                id: 1,
+                occlusion_layer_id: 2,
+                z_index: 0,
+                parent_frame: None,
+            }),
+        ]);
+        drain(&mut chunks);
+        chunks.push_tick(vec![]);
+        let messages = drain(&mut chunks);
+        let kinds: Vec<_> = messages
+            .iter()
+            .map(|m| m.as_object().unwrap().keys().next().unwrap().clone())
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                "TextUpdate",
+                "TextDelete",
+                "TextCreate",
+                "TextUpdate",
+                "OcclusionUpdate"
+            ]
+        );
+        // occlusion updates carry the full state, `null` included
+        assert_eq!(messages[4]["OcclusionUpdate"]["occlusion_layer_id"], 2);
+        assert!(messages[4]["OcclusionUpdate"]["parent_frame"].is_null());
+        assert_eq!(chunks.stats().coalesced_drops, 1);
+    }
+
+    #[test]
+    fn stale_acknowledgments_are_ignored() {
+        let mut chunks = MessageChunks::default();
+        chunks.push_tick(vec![text_create(1)]);
+        drain(&mut chunks);
+        chunks.push_tick(vec![text_create(2)]);
+        chunks.acknowledge(0);
+        assert!(chunks.is_waiting());
+        chunks.acknowledge(1);
+        assert!(!chunks.is_waiting());
+    }
+}
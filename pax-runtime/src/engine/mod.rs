@@ -16,8 +16,19 @@ use crate::{ComponentInstance, RuntimeContext};
 use pax_runtime_api::Platform;
 use std::time::Instant;
 
+mod debug_overlay;
+pub mod message_chunks;
+pub mod mount_tracking;
+pub mod native_ordering;
+pub mod native_recycling;
+pub mod node_costs;
 pub mod node_interface;
+#[cfg(feature = "designtime")]
+pub mod node_observers;
 pub mod occlusion;
+#[cfg(feature = "designtime")]
+pub mod property_recorder;
+pub mod text_style_cascade;
 
 /// The atomic unit of rendering; also the container for each unique tuple of computed properties.
 /// Represents an expanded node, that is "expanded" in the context of computed properties and repeat expansion.
@@ -34,6 +45,7 @@ use self::node_interface::NodeLocal;
 use {
     crate::InstanceNode,
     pax_designtime::DesigntimeManager,
+    pax_manifest::UniqueTemplateNodeIdentifier,
     pax_runtime_api::{borrow, borrow_mut},
 };
 
@@ -41,6 +53,9 @@ use {
 pub struct Globals {
     pub frames_elapsed: Property<u64>,
     pub viewport: Property<TransformAndBounds<NodeLocal, Window>>,
+    /// Physical pixels per logical pixel of the viewport, reported by the chassis, see
+    /// [`PaxEngine::set_device_pixel_ratio`]. Layout and native patches are in logical pixels
+    pub device_pixel_ratio: Property<f64>,
     pub platform: Platform,
     pub os: OS,
     #[cfg(feature = "designtime")]
@@ -71,7 +86,8 @@ impl Globals {
         let viewport_var = Variable::new_from_typed_property(viewport);
         let frames_elapsed_var = Variable::new_from_typed_property(self.frames_elapsed.clone());
 
-        let global_scope = vec![
+        #[allow(unused_mut)]
+        let mut global_scope: HashMap<_, _> = vec![
             ("$mobile".to_string(), mobile_var),
             ("$desktop".to_string(), desktop_var),
             ("$viewport".to_string(), viewport_var),
@@ -80,6 +96,23 @@ impl Globals {
         .into_iter()
         .collect();
 
+        // Outside of designtime, the compiler has already replaced `$defines` by their values
+        #[cfg(feature = "designtime")]
+        {
+            let defines = borrow!(self.designtime)
+                .get_manifest()
+                .defines
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect();
+            global_scope.insert(
+                "$defines".to_string(),
+                Variable::new_from_typed_property(Property::new(
+                    pax_runtime_api::PaxValue::Object(defines),
+                )),
+            );
+        }
+
         let root_env = RuntimePropertiesStackFrame::new(global_scope);
         root_env
     }
@@ -90,6 +123,7 @@ impl std::fmt::Debug for Globals {
         f.debug_struct("Globals")
             .field("frames_elapsed", &self.frames_elapsed)
             .field("viewport", &self.viewport)
+            .field("device_pixel_ratio", &self.device_pixel_ratio)
             .finish_non_exhaustive()
     }
 }
@@ -98,6 +132,8 @@ impl std::fmt::Debug for Globals {
 pub struct PaxEngine {
     pub runtime_context: Rc<RuntimeContext>,
     pub root_expanded_node: Rc<ExpandedNode>,
+    /// Whether node bounds are drawn over the render, see [`PaxEngine::set_debug_overlay`]
+    debug_overlay: bool,
 }
 
 pub enum HandlerLocation {
@@ -150,6 +186,8 @@ struct ImgData<R: piet::RenderContext> {
 pub struct Renderer<R: piet::RenderContext> {
     backends: HashMap<String, R>,
     image_map: HashMap<String, ImgData<R>>,
+    /// Whether every layer is drawn to the single backend, see `Renderer::flattened`
+    flattened: bool,
 }
 
 impl<R: piet::RenderContext> Renderer<R> {
@@ -157,9 +195,34 @@ impl<R: piet::RenderContext> Renderer<R> {
         Self {
             backends: HashMap::new(),
             image_map: HashMap::new(),
+            flattened: false,
+        }
+    }
+
+    /// A renderer drawing every layer to `backend`, in the order they're drawn in, e.g. to
+    /// render to an offscreen surface
+    pub fn flattened(backend: R) -> Self {
+        let mut renderer = Self::new();
+        renderer.backends.insert("flattened".to_owned(), backend);
+        renderer.flattened = true;
+        renderer
+    }
+
+    fn backend(&mut self, layer: &str) -> Option<&mut R> {
+        if self.flattened {
+            self.backends.values_mut().next()
+        } else {
+            self.backends.get_mut(layer)
         }
     }
 
+    /// The backend of a renderer created with `Renderer::flattened`
+    pub fn flattened_backend(&mut self) -> Option<&mut R> {
+        self.flattened
+            .then(|| self.backends.values_mut().next())
+            .flatten()
+    }
+
     pub fn add_context(&mut self, id: &str, context: R) {
         self.backends.insert(id.to_owned(), context);
     }
@@ -175,7 +238,7 @@ impl<R: piet::RenderContext> Renderer<R> {
 
 impl<R: piet::RenderContext> crate::api::RenderContext for Renderer<R> {
     fn fill(&mut self, layer: &str, path: kurbo::BezPath, brush: &piet_common::PaintBrush) {
-        if let Some(layer) = self.backends.get_mut(layer) {
+        if let Some(layer) = self.backend(layer) {
             layer.fill(path, brush);
         }
     }
@@ -187,31 +250,31 @@ impl<R: piet::RenderContext> crate::api::RenderContext for Renderer<R> {
         brush: &piet_common::PaintBrush,
         width: f64,
     ) {
-        if let Some(layer) = self.backends.get_mut(layer) {
+        if let Some(layer) = self.backend(layer) {
             layer.stroke(path, brush, width);
         }
     }
 
     fn save(&mut self, layer: &str) {
-        if let Some(layer) = self.backends.get_mut(layer) {
+        if let Some(layer) = self.backend(layer) {
             let _ = layer.save();
         }
     }
 
     fn transform(&mut self, layer: &str, affine: Affine) {
-        if let Some(layer) = self.backends.get_mut(layer) {
+        if let Some(layer) = self.backend(layer) {
             layer.transform(affine);
         }
     }
 
     fn clip(&mut self, layer: &str, path: kurbo::BezPath) {
-        if let Some(layer) = self.backends.get_mut(layer) {
+        if let Some(layer) = self.backend(layer) {
             layer.clip(path);
         }
     }
 
     fn restore(&mut self, layer: &str) {
-        if let Some(layer) = self.backends.get_mut(layer) {
+        if let Some(layer) = self.backend(layer) {
             let _ = layer.restore();
         }
     }
@@ -239,7 +302,13 @@ impl<R: piet::RenderContext> crate::api::RenderContext for Renderer<R> {
         let Some(data) = self.image_map.get(image_path) else {
             return;
         };
-        if let Some(layer) = self.backends.get_mut(layer) {
+        // not `self.backend`, which would borrow `image_map` as well
+        let backend = if self.flattened {
+            self.backends.values_mut().next()
+        } else {
+            self.backends.get_mut(layer)
+        };
+        if let Some(layer) = backend {
             layer.draw_image(&data.img, rect, InterpolationMode::Bilinear);
         }
     }
@@ -273,6 +342,7 @@ impl PaxEngine {
                 transform: Transform2::identity(),
                 bounds: viewport_size,
             }),
+            device_pixel_ratio: Property::new(1.0),
             platform,
             os,
             get_elapsed_millis: Rc::from(get_elapsed_millis),
@@ -285,6 +355,7 @@ impl PaxEngine {
         PaxEngine {
             runtime_context,
             root_expanded_node: root_node,
+            debug_overlay: false,
         }
     }
 
@@ -309,6 +380,7 @@ impl PaxEngine {
                 transform: Transform2::identity(),
                 bounds: viewport_size,
             }),
+            device_pixel_ratio: Property::new(1.0),
             platform,
             os,
             designtime: designtime.clone(),
@@ -329,6 +401,7 @@ impl PaxEngine {
         PaxEngine {
             runtime_context,
             root_expanded_node,
+            debug_overlay: false,
         }
     }
 
@@ -349,6 +422,48 @@ impl PaxEngine {
         }
     }
 
+    /// Returns the computed layout of every expanded instance of the template node `uni`
+    #[cfg(feature = "designtime")]
+    pub fn get_transform_and_bounds_by_global_id(
+        &self,
+        uni: &UniqueTemplateNodeIdentifier,
+    ) -> Vec<TransformAndBounds<NodeLocal, Window>> {
+        self.runtime_context
+            .get_expanded_nodes_by_global_ids(uni)
+            .into_iter()
+            .map(|node| node.transform_and_bounds.get())
+            .collect()
+    }
+
+    /// Renders a standalone instance of `component` to `rcs`, laid out in the viewport and
+    /// scaled down to fit `size`.  The instance is unmounted right after, so native elements
+    /// (e.g. `Text`) aren't part of the render.
+    #[cfg(feature = "designtime")]
+    pub fn render_detached(
+        &mut self,
+        component: Rc<ComponentInstance>,
+        size: (f64, f64),
+        rcs: &mut dyn RenderContext,
+    ) {
+        let root = ExpandedNode::initialize_root(component, &self.runtime_context);
+        root.recurse_update(&self.runtime_context);
+
+        let (viewport_width, viewport_height) =
+            self.runtime_context.globals().viewport.get().bounds;
+        let scale = (size.0 / viewport_width).min(size.1 / viewport_height);
+        let layers: Vec<String> = rcs.layers().into_iter().map(str::to_owned).collect();
+        for layer in &layers {
+            rcs.save(layer);
+            rcs.transform(layer, Affine::scale(scale));
+        }
+        root.recurse_render_queue(&self.runtime_context, rcs);
+        self.runtime_context.recurse_flush_queued_renders(rcs);
+        for layer in &layers {
+            rcs.restore(layer);
+        }
+        root.recurse_unmount(&self.runtime_context);
+    }
+
     #[cfg(feature = "designtime")]
     pub fn full_reload_userland(&mut self, new_userland_instance: Rc<dyn InstanceNode>) {
         let node = borrow!(self.runtime_context.userland_root_expanded_node)
@@ -358,6 +473,7 @@ impl PaxEngine {
         *borrow_mut!(self.runtime_context.userland_frame_instance_node) =
             Rc::clone(&new_userland_instance);
         node.fully_recreate_with_new_data(new_userland_instance.clone(), &self.runtime_context);
+        self.runtime_context.unmount_orphaned_nodes();
     }
 
     // NOTES: this is the order of different things being computed in recurse-expand-nodes
@@ -373,6 +489,21 @@ impl PaxEngine {
     ///     a. find lowest node (last child of last node)
     ///     b. start rendering, from lowest node on-up, throughout tree
     pub fn tick(&mut self) -> Vec<NativeMessage> {
+        #[cfg(feature = "designtime")]
+        {
+            let globals = self.runtime_context.globals();
+            self.runtime_context
+                .node_costs()
+                .begin_frame(globals.frames_elapsed.get(), &globals.designtime);
+            self.runtime_context
+                .property_recorder()
+                .begin_frame(self.runtime_context.node_observers(), &globals.designtime);
+        }
+
+        // Calls on component instances made through their handles since the last tick, so that
+        // they take effect in this tick's properties
+        self.runtime_context.flush_component_calls();
+
         //
         // 1. UPDATE NODES (properties, etc.). This part we should be able to
         // completely remove once reactive properties dirty-dag is a thing.
@@ -382,10 +513,15 @@ impl PaxEngine {
 
         let ctx = &self.runtime_context;
         occlusion::update_node_occlusion(&self.root_expanded_node, ctx);
+        ctx.flush_native_element_ordering();
+        #[cfg(feature = "designtime")]
+        ctx.node_observers()
+            .notify(ctx.globals().frames_elapsed.get(), ctx);
         let time = &ctx.globals().frames_elapsed;
         time.set(time.get() + 1);
 
         ctx.flush_custom_events().unwrap();
+        ctx.flush_native_element_pool();
         let native_messages = ctx.take_native_messages();
         native_messages
     }
@@ -396,11 +532,26 @@ impl PaxEngine {
         self.root_expanded_node
             .recurse_render_queue(&mut self.runtime_context, rcs);
         self.runtime_context.recurse_flush_queued_renders(rcs);
+        if self.debug_overlay {
+            debug_overlay::render_debug_overlay(
+                &self.root_expanded_node,
+                &self.runtime_context,
+                rcs,
+            );
+        }
     }
 
+    /// Draws the bounds of every expanded node over the normal render, labeled with its
+    /// z-index and expanded node id, to help diagnose layout issues
+    pub fn set_debug_overlay(&mut self, enabled: bool) {
+        self.debug_overlay = enabled;
+    }
+
+    /// Finds the node an interrupt from the chassis is targeted at, `id` being the
+    /// id of a native element (see [`RuntimeContext::native_id`])
     pub fn get_expanded_node(&self, id: ExpandedNodeIdentifier) -> Option<Rc<ExpandedNode>> {
-        let val = self.runtime_context.get_expanded_node_by_eid(id).clone();
-        val.map(|v| (v.clone()))
+        self.runtime_context
+            .get_expanded_node_by_native_id(id.to_u32())
     }
 
     /// Called by chassis when viewport size changes, e.g. with native window resizes
@@ -412,6 +563,21 @@ impl PaxEngine {
         });
     }
 
+    /// Called by chassis on startup and when the device pixel ratio changes, e.g. when the
+    /// window moves to a monitor with another resolution. Ratios that aren't positive, which
+    /// some platforms report for windows that aren't on screen, are ignored
+    pub fn set_device_pixel_ratio(&self, dpr: f64) {
+        if !(dpr.is_finite() && dpr > 0.0) {
+            log::debug!("ignoring device pixel ratio {}", dpr);
+            return;
+        }
+        self.runtime_context.edit_globals(|globals| {
+            if globals.device_pixel_ratio.get() != dpr {
+                globals.device_pixel_ratio.set(dpr);
+            }
+        });
+    }
+
     pub fn global_dispatch_focus(&self, args: Focus) -> bool {
         let mut prevent_default = false;
         self.root_expanded_node
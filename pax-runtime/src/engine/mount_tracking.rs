@@ -0,0 +1,104 @@
+use std::collections::HashSet;
+
+use crate::ExpandedNodeIdentifier;
+
+/// Bookkeeping of the expanded nodes that are currently mounted.
+///
+/// Nodes are unmounted by walking the tree from the node being removed, so a
+/// node that has been detached from the tree without going through
+/// `recurse_unmount` (for example when the tree is replaced wholesale) would
+/// never get its `unmount` handlers fired, leaking whatever its component set
+/// up on mount (timers, listeners, ...). After such a replacement, the nodes
+/// still recorded as mounted but no longer reachable from the root are
+/// reported as orphans, to be unmounted.
+#[derive(Default)]
+pub struct MountedNodes {
+    mounted: HashSet<ExpandedNodeIdentifier>,
+    mount_count: u64,
+    unmount_count: u64,
+}
+
+impl MountedNodes {
+    pub fn record_mount(&mut self, id: ExpandedNodeIdentifier) {
+        if self.mounted.insert(id) {
+            self.mount_count += 1;
+        }
+    }
+
+    pub fn record_unmount(&mut self, id: ExpandedNodeIdentifier) {
+        if self.mounted.remove(&id) {
+            self.unmount_count += 1;
+        }
+    }
+
+    pub fn is_mounted(&self, id: &ExpandedNodeIdentifier) -> bool {
+        self.mounted.contains(id)
+    }
+
+    /// Number of `mount` and `unmount` lifecycle events fired so far
+    pub fn counts(&self) -> (u64, u64) {
+        (self.mount_count, self.unmount_count)
+    }
+
+    /// Mounted nodes that aren't in `reachable`, oldest first
+    pub fn orphans(
+        &self,
+        reachable: &HashSet<ExpandedNodeIdentifier>,
+    ) -> Vec<ExpandedNodeIdentifier> {
+        let mut orphans: Vec<_> = self.mounted.difference(reachable).copied().collect();
+        orphans.sort();
+        orphans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(range: std::ops::Range<u32>) -> Vec<ExpandedNodeIdentifier> {
+        range.map(ExpandedNodeIdentifier).collect()
+    }
+
+    #[test]
+    fn test_mount_and_unmount_are_counted_once() {
+        let mut nodes = MountedNodes::default();
+        let id = ExpandedNodeIdentifier(0);
+        nodes.record_mount(id);
+        nodes.record_mount(id);
+        assert!(nodes.is_mounted(&id));
+        nodes.record_unmount(id);
+        nodes.record_unmount(id);
+        assert!(!nodes.is_mounted(&id));
+        assert_eq!(nodes.counts(), (1, 1));
+    }
+
+    #[test]
+    fn test_orphans_of_replaced_tree_balance_counts() {
+        let mut nodes = MountedNodes::default();
+        for id in ids(0..6) {
+            nodes.record_mount(id);
+        }
+        // the tree is replaced: the walk from the root only unmounts 1 and 2,
+        // 3 to 5 were detached from it beforehand
+        for id in ids(1..3) {
+            nodes.record_unmount(id);
+        }
+        for id in ids(6..8) {
+            nodes.record_mount(id);
+        }
+        let reachable = [0, 6, 7].into_iter().map(ExpandedNodeIdentifier).collect();
+        let orphans = nodes.orphans(&reachable);
+        assert_eq!(orphans, ids(3..6));
+        for id in orphans {
+            nodes.record_unmount(id);
+        }
+        assert!(nodes.orphans(&reachable).is_empty());
+
+        for id in ids(0..1).into_iter().chain(ids(6..8)) {
+            nodes.record_unmount(id);
+        }
+        let (mounts, unmounts) = nodes.counts();
+        assert_eq!(mounts, 8);
+        assert_eq!(mounts, unmounts);
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use pax_message::{ElementReorderPatch, NativeMessage};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct NativeOrder {
+    occlusion_layer_id: u32,
+    z_index: i32,
+}
+
+struct VisitedElement {
+    native_id: u32,
+    occlusion_layer_id: u32,
+    overlay: bool,
+}
+
+/// Stacking order of native elements within their occlusion layer.
+///
+/// Chassis stack native elements of a layer in creation order, which is wrong
+/// as soon as an element is created after one that's drawn above it (for example
+/// a Textbox mounted after a popover containing a Dropdown was opened). Native
+/// elements are visited in render order each frame, and each element gets an
+/// index within its layer: elements in overlays (unclippable subtrees, which are
+/// drawn after everything else) are placed above all other content of the layer.
+/// Reorder messages are only sent for the elements whose index changed since the
+/// last frame.
+///
+/// Only used if the chassis declared support for it, see
+/// [`pax_message::ChassisCapabilities`].
+#[derive(Default)]
+pub struct NativeElementOrdering {
+    enabled: bool,
+    visited: Vec<VisitedElement>,
+    last_frame: HashMap<u32, NativeOrder>,
+}
+
+impl NativeElementOrdering {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.visited.clear();
+        self.last_frame.clear();
+    }
+
+    /// Records the native element `native_id` as the next one in render order
+    pub fn visit(&mut self, native_id: u32, occlusion_layer_id: u32, overlay: bool) {
+        if self.enabled {
+            self.visited.push(VisitedElement {
+                native_id,
+                occlusion_layer_id,
+                overlay,
+            });
+        }
+    }
+
+    /// Computes the order of the elements visited this frame, returning
+    /// reorder messages for the ones that moved
+    pub fn finish_frame(&mut self) -> Vec<NativeMessage> {
+        let mut visited = std::mem::take(&mut self.visited);
+        // stable, keeps render order among overlay and non-overlay elements
+        visited.sort_by_key(|element| element.overlay);
+
+        let mut next_index: HashMap<u32, i32> = HashMap::new();
+        let mut current = HashMap::with_capacity(visited.len());
+        let mut messages = vec![];
+        for element in visited {
+            let index = next_index.entry(element.occlusion_layer_id).or_default();
+            let order = NativeOrder {
+                occlusion_layer_id: element.occlusion_layer_id,
+                z_index: *index,
+            };
+            *index += 1;
+            if self.last_frame.get(&element.native_id) != Some(&order) {
+                messages.push(NativeMessage::ElementReorder(ElementReorderPatch {
+                    id: element.native_id,
+                    occlusion_layer_id: order.occlusion_layer_id,
+                    z_index: order.z_index,
+                }));
+            }
+            current.insert(element.native_id, order);
+        }
+        self.last_frame = current;
+        messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(ordering: &mut NativeElementOrdering, elements: &[(u32, u32, bool)]) -> Vec<u32> {
+        for &(id, layer, overlay) in elements {
+            ordering.visit(id, layer, overlay);
+        }
+        ordering
+            .finish_frame()
+            .into_iter()
+            .map(|message| match message {
+                NativeMessage::ElementReorder(patch) => patch.id,
+                _ => panic!("expected only reorder messages"),
+            })
+            .collect()
+    }
+
+    fn enabled() -> NativeElementOrdering {
+        let mut ordering = NativeElementOrdering::default();
+        ordering.set_enabled(true);
+        ordering
+    }
+
+    #[test]
+    fn test_stable_frames_send_nothing() {
+        let mut ordering = enabled();
+        let elements = [(0, 0, false), (1, 0, false), (2, 1, false)];
+        assert_eq!(frame(&mut ordering, &elements), vec![0, 1, 2]);
+        for _ in 0..5 {
+            assert!(frame(&mut ordering, &elements).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_reorder_sent_when_relative_order_changes() {
+        let mut ordering = enabled();
+        frame(
+            &mut ordering,
+            &[(0, 0, false), (1, 0, false), (2, 0, false)],
+        );
+        assert_eq!(
+            frame(
+                &mut ordering,
+                &[(0, 0, false), (2, 0, false), (1, 0, false)]
+            ),
+            vec![2, 1]
+        );
+        // elements in other layers aren't affected
+        frame(&mut ordering, &[(0, 0, false), (1, 1, false)]);
+        assert_eq!(
+            frame(
+                &mut ordering,
+                &[(3, 0, false), (0, 0, false), (1, 1, false)]
+            ),
+            vec![3, 0]
+        );
+    }
+
+    #[test]
+    fn test_overlays_above_later_elements() {
+        let mut ordering = enabled();
+        // dropdown (1) in a popover, textbox (2) created afterward elsewhere
+        frame(&mut ordering, &[(0, 0, false), (1, 0, true)]);
+        ordering.visit(0, 0, false);
+        ordering.visit(1, 0, true);
+        ordering.visit(2, 0, false);
+        let messages = ordering.finish_frame();
+        let orders: Vec<_> = messages
+            .iter()
+            .map(|message| match message {
+                NativeMessage::ElementReorder(patch) => (patch.id, patch.z_index),
+                _ => panic!("expected only reorder messages"),
+            })
+            .collect();
+        assert_eq!(orders, vec![(2, 1), (1, 2)]);
+        assert!(frame(&mut ordering, &[(0, 0, false), (1, 0, true), (2, 0, false)]).is_empty());
+    }
+
+    #[test]
+    fn test_reappearing_elements_are_resent() {
+        let mut ordering = enabled();
+        frame(&mut ordering, &[(0, 0, false)]);
+        assert!(frame(&mut ordering, &[]).is_empty());
+        assert_eq!(frame(&mut ordering, &[(0, 0, false)]), vec![0]);
+    }
+
+    #[test]
+    fn test_disabled_sends_nothing() {
+        let mut ordering = NativeElementOrdering::default();
+        assert!(frame(&mut ordering, &[(0, 0, false), (1, 0, true)]).is_empty());
+    }
+}
@@ -0,0 +1,289 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use pax_message::NativeMessage;
+
+use crate::ExpandedNodeIdentifier;
+
+/// Max number of elements parked per kind of native element and parent frame,
+/// the oldest parked element is deleted when a new one would go over budget
+pub const MAX_PARKED_ELEMENTS: usize = 256;
+
+/// Number of frames an element can stay parked before it is deleted
+pub const MAX_PARKED_FRAMES: u64 = 120;
+
+/// Parked elements are only handed out to nodes of the same kind that live in
+/// the same frame, since the chassis places native elements in their frame on
+/// creation
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+struct PoolKey {
+    kind: &'static str,
+    parent_frame: Option<u32>,
+}
+
+struct ParkedElement {
+    native_id: u32,
+    parked_at: u64,
+    delete: fn(u32) -> NativeMessage,
+}
+
+/// Keeps native elements of unmounted nodes around for a while, to be reused by
+/// nodes of the same kind mounted later instead of deleting and recreating the
+/// platform element. This avoids native element churn when for example scrolling
+/// a Repeat of Text rows.
+///
+/// Only `Text` recycles its element for now. Form controls still create and
+/// delete theirs on mount/unmount, as their native state (focus, selection,
+/// checked, ...) would have to be reset when parked.
+///
+/// A node that claims a parked element talks to the chassis with the id of the
+/// claimed element (its "native id") instead of its own [`ExpandedNodeIdentifier`].
+/// Only used if the chassis declared support for it, see
+/// [`pax_message::ChassisCapabilities`].
+#[derive(Default)]
+pub struct NativeElementPool {
+    enabled: bool,
+    frame: u64,
+    parked: HashMap<PoolKey, VecDeque<ParkedElement>>,
+    parked_ids: HashSet<u32>,
+    native_ids: HashMap<ExpandedNodeIdentifier, u32>,
+    claimed_by: HashMap<u32, ExpandedNodeIdentifier>,
+}
+
+impl NativeElementPool {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enables/disables recycling, disabling it deletes all parked elements
+    pub fn set_enabled(&mut self, enabled: bool) -> Vec<NativeMessage> {
+        self.enabled = enabled;
+        if enabled {
+            return vec![];
+        }
+        self.parked_ids.clear();
+        self.parked
+            .drain()
+            .flat_map(|(_, parked)| parked)
+            .map(|p| (p.delete)(p.native_id))
+            .collect()
+    }
+
+    /// The id the chassis knows the native element of this node by
+    pub fn native_id(&self, id: ExpandedNodeIdentifier) -> u32 {
+        self.native_ids
+            .get(&id)
+            .copied()
+            .unwrap_or_else(|| id.to_u32())
+    }
+
+    /// The node currently using the native element with id `native_id`,
+    /// `None` if the element is parked
+    pub fn node_id(&self, native_id: u32) -> Option<ExpandedNodeIdentifier> {
+        if self.parked_ids.contains(&native_id) {
+            return None;
+        }
+        Some(
+            self.claimed_by
+                .get(&native_id)
+                .copied()
+                .unwrap_or(ExpandedNodeIdentifier(native_id)),
+        )
+    }
+
+    /// Hands the most recently parked element of this kind to node `id`,
+    /// returning its native id
+    pub fn claim(
+        &mut self,
+        kind: &'static str,
+        parent_frame: Option<u32>,
+        id: ExpandedNodeIdentifier,
+    ) -> Option<u32> {
+        if !self.enabled {
+            return None;
+        }
+        let parked = self
+            .parked
+            .get_mut(&PoolKey { kind, parent_frame })?
+            .pop_back()?;
+        self.parked_ids.remove(&parked.native_id);
+        self.native_ids.insert(id, parked.native_id);
+        self.claimed_by.insert(parked.native_id, id);
+        Some(parked.native_id)
+    }
+
+    /// Forgets the native element of node `id`, returning its native id
+    pub fn release(&mut self, id: ExpandedNodeIdentifier) -> u32 {
+        match self.native_ids.remove(&id) {
+            Some(native_id) => {
+                self.claimed_by.remove(&native_id);
+                native_id
+            }
+            None => id.to_u32(),
+        }
+    }
+
+    /// Parks a released native element for reuse. Returns the messages to send
+    /// to the chassis: a delete for this element if recycling is disabled,
+    /// otherwise deletes for the elements that went over budget.
+    pub fn park(
+        &mut self,
+        kind: &'static str,
+        parent_frame: Option<u32>,
+        native_id: u32,
+        delete: fn(u32) -> NativeMessage,
+    ) -> Vec<NativeMessage> {
+        if !self.enabled {
+            return vec![delete(native_id)];
+        }
+        let parked = self
+            .parked
+            .entry(PoolKey { kind, parent_frame })
+            .or_default();
+        parked.push_back(ParkedElement {
+            native_id,
+            parked_at: self.frame,
+            delete,
+        });
+        self.parked_ids.insert(native_id);
+        let mut messages = vec![];
+        while parked.len() > MAX_PARKED_ELEMENTS {
+            let evicted = parked.pop_front().unwrap();
+            self.parked_ids.remove(&evicted.native_id);
+            messages.push((evicted.delete)(evicted.native_id));
+        }
+        messages
+    }
+
+    /// Advances the pool one frame, returning deletes for the elements that
+    /// have been parked for too long
+    pub fn tick(&mut self) -> Vec<NativeMessage> {
+        self.frame += 1;
+        let frame = self.frame;
+        let mut messages = vec![];
+        for parked in self.parked.values_mut() {
+            while parked
+                .front()
+                .is_some_and(|p| frame - p.parked_at > MAX_PARKED_FRAMES)
+            {
+                let expired = parked.pop_front().unwrap();
+                self.parked_ids.remove(&expired.native_id);
+                messages.push((expired.delete)(expired.native_id));
+            }
+        }
+        self.parked.retain(|_, parked| !parked.is_empty());
+        messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "Text";
+
+    fn is_create_or_delete(message: &NativeMessage) -> bool {
+        matches!(
+            message,
+            NativeMessage::TextCreate(_) | NativeMessage::TextDelete(_)
+        )
+    }
+
+    /// Mounts `count` text nodes, claiming parked elements when possible,
+    /// returning the ids of the nodes and the messages sent
+    fn mount(
+        pool: &mut NativeElementPool,
+        next_id: &mut u32,
+        count: usize,
+    ) -> (Vec<ExpandedNodeIdentifier>, Vec<NativeMessage>) {
+        let mut ids = vec![];
+        let mut messages = vec![];
+        for _ in 0..count {
+            let id = ExpandedNodeIdentifier(*next_id);
+            *next_id += 1;
+            if pool.claim(TEXT, None, id).is_none() {
+                messages.push(NativeMessage::TextCreate(pax_message::AnyCreatePatch {
+                    id: id.to_u32(),
+                    parent_frame: None,
+                    occlusion_layer_id: 0,
+                }));
+            }
+            ids.push(id);
+        }
+        (ids, messages)
+    }
+
+    fn unmount(pool: &mut NativeElementPool, ids: &[ExpandedNodeIdentifier]) -> Vec<NativeMessage> {
+        let mut messages = vec![];
+        for &id in ids {
+            let native_id = pool.release(id);
+            messages.extend(pool.park(TEXT, None, native_id, NativeMessage::TextDelete));
+        }
+        messages
+    }
+
+    #[test]
+    fn test_recycling_steady_state() {
+        let mut pool = NativeElementPool::default();
+        pool.set_enabled(true);
+        let mut next_id = 0;
+        let (mut ids, messages) = mount(&mut pool, &mut next_id, 200);
+        assert_eq!(
+            messages.iter().filter(|m| is_create_or_delete(m)).count(),
+            200
+        );
+
+        // re-expanding the list every frame should reuse all native elements
+        for _ in 0..10 {
+            let mut messages = unmount(&mut pool, &ids);
+            let (new_ids, mount_messages) = mount(&mut pool, &mut next_id, 200);
+            messages.extend(mount_messages);
+            messages.extend(pool.tick());
+            assert_eq!(
+                messages.iter().filter(|m| is_create_or_delete(m)).count(),
+                0
+            );
+            ids = new_ids;
+        }
+
+        // the native ids are the ones of the first nodes
+        let mut native_ids: Vec<_> = ids.iter().map(|&id| pool.native_id(id)).collect();
+        native_ids.sort();
+        assert_eq!(native_ids, (0..200).collect::<Vec<_>>());
+        for (i, &id) in ids.iter().enumerate() {
+            assert_eq!(pool.node_id(pool.native_id(id)), Some(id), "node {}", i);
+        }
+    }
+
+    #[test]
+    fn test_parked_elements_are_deleted() {
+        let mut pool = NativeElementPool::default();
+        pool.set_enabled(true);
+        let mut next_id = 0;
+        let (ids, _) = mount(&mut pool, &mut next_id, MAX_PARKED_ELEMENTS + 10);
+        // over budget
+        let messages = unmount(&mut pool, &ids);
+        assert_eq!(messages.len(), 10);
+        // interrupts for parked elements have no target
+        assert_eq!(pool.node_id(ids[20].to_u32()), None);
+        // expired
+        let mut deleted = 0;
+        for _ in 0..=MAX_PARKED_FRAMES {
+            deleted += pool.tick().len();
+        }
+        assert_eq!(deleted, MAX_PARKED_ELEMENTS);
+        let (_, messages) = mount(&mut pool, &mut next_id, 1);
+        assert_eq!(messages.len(), 1);
+    }
+
+    #[test]
+    fn test_disabled_pool_deletes() {
+        let mut pool = NativeElementPool::default();
+        let mut next_id = 0;
+        let (ids, messages) = mount(&mut pool, &mut next_id, 5);
+        assert_eq!(messages.len(), 5);
+        let messages = unmount(&mut pool, &ids);
+        assert_eq!(messages.len(), 5);
+        let (_, messages) = mount(&mut pool, &mut next_id, 5);
+        assert_eq!(messages.len(), 5);
+    }
+}
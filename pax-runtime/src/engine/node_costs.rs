@@ -0,0 +1,80 @@
+/// The parts of the work on a node per frame that are timed separately,
+/// see `pax_designtime::node_costs`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeCostPhase {
+    Properties,
+    Layout,
+    Render,
+}
+
+#[cfg(feature = "designtime")]
+pub use sampler::NodeCostSampler;
+
+#[cfg(feature = "designtime")]
+mod sampler {
+    use std::cell::Cell;
+    use std::collections::HashMap;
+
+    use pax_designtime::node_costs::{NodeCost, SAMPLE_INTERVAL_FRAMES};
+    use pax_designtime::DesigntimeManager;
+    use pax_manifest::UniqueTemplateNodeIdentifier;
+    use pax_runtime_api::{borrow, borrow_mut, use_RefCell};
+    use web_time::Instant;
+    use_RefCell!();
+
+    use super::NodeCostPhase;
+    use crate::ExpandedNode;
+
+    /// Times the work on each node on the frames that are sampled, summed up per template node
+    #[derive(Default)]
+    pub struct NodeCostSampler {
+        sampling: Cell<bool>,
+        costs: RefCell<HashMap<UniqueTemplateNodeIdentifier, NodeCost>>,
+    }
+
+    impl NodeCostSampler {
+        /// Reports the costs of the previous frame if it was sampled, and decides whether to
+        /// sample frame `frame`, which begins with this tick and ends with its render
+        pub fn begin_frame(&self, frame: u64, designtime: &RefCell<DesigntimeManager>) {
+            if self.sampling.get() {
+                let costs = std::mem::take(&mut *borrow_mut!(self.costs));
+                borrow_mut!(designtime).set_node_costs(costs);
+            }
+            self.sampling.set(
+                frame % SAMPLE_INTERVAL_FRAMES == 0 && borrow!(designtime).is_node_cost_profiling(),
+            );
+        }
+
+        /// Runs `f`, attributing the time it takes to `phase` of the template node of `node`
+        /// if this frame is sampled
+        pub fn measure<R>(
+            &self,
+            node: &ExpandedNode,
+            phase: NodeCostPhase,
+            f: impl FnOnce() -> R,
+        ) -> R {
+            if !self.sampling.get() {
+                return f();
+            }
+            let Some(uni) = node.global_id() else {
+                return f();
+            };
+            let start = Instant::now();
+            let result = f();
+            let elapsed_us = start.elapsed().as_secs_f64() * 1_000_000.0;
+
+            let mut costs = borrow_mut!(self.costs);
+            let cost = costs.entry(uni).or_default();
+            match phase {
+                NodeCostPhase::Properties => {
+                    cost.properties_us += elapsed_us;
+                    // properties are computed once per expanded node and frame
+                    cost.instances += 1;
+                }
+                NodeCostPhase::Layout => cost.layout_us += elapsed_us,
+                NodeCostPhase::Render => cost.render_us += elapsed_us,
+            }
+            result
+        }
+    }
+}
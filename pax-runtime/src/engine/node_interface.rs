@@ -1,11 +1,14 @@
 use std::rc::Rc;
 
-use pax_manifest::UniqueTemplateNodeIdentifier;
-use pax_runtime_api::Property;
+use pax_manifest::{TypeId, UniqueTemplateNodeIdentifier};
 use pax_runtime_api::{borrow, pax_value::ToFromPaxAny, Interpolatable};
+use pax_runtime_api::{PaxValue, Property};
 
 use crate::{
-    api::{math::Space, Window},
+    api::{
+        math::{Space, Transform2},
+        Window,
+    },
     ExpandedNode, LayoutProperties, TransformAndBounds,
 };
 
@@ -56,9 +59,7 @@ impl Space for NodeLocal {}
 
 impl NodeInterface {
     pub fn global_id(&self) -> Option<UniqueTemplateNodeIdentifier> {
-        let instance_node = borrow!(self.inner.instance_node);
-        let base = instance_node.base();
-        base.template_node_identifier.clone()
+        self.inner.global_id()
     }
 
     pub fn layout_properties(&self) -> LayoutProperties {
@@ -83,6 +84,23 @@ impl NodeInterface {
         self.inner.transform_and_bounds.clone()
     }
 
+    /// The global transform this node was last rendered with, if it has been rendered
+    pub fn computed_global_transform(&self) -> Option<Transform2<NodeLocal, Window>> {
+        self.inner.computed_global_transform.get()
+    }
+
+    /// The `text_style` common property of this node, if set
+    pub fn text_style(&self) -> Option<PaxValue> {
+        let cp = self.inner.get_common_properties();
+        let text_style = borrow!(cp).text_style.get();
+        text_style
+    }
+
+    /// The text style defaults this node resolves to, inherited from its ancestors
+    pub fn resolved_text_style(&self) -> Property<Option<PaxValue>> {
+        self.inner.resolved_text_style.clone()
+    }
+
     pub fn render_parent(&self) -> Option<NodeInterface> {
         let parent = borrow!(self.inner.render_parent);
         Some(parent.upgrade()?.into())
@@ -96,6 +114,24 @@ impl NodeInterface {
         Some(self.inner.template_parent.upgrade()?.into())
     }
 
+    /// The node this one was expanded from as seen in the template of `component`: this node
+    /// if it's declared in that template, otherwise the closest template ancestor that is, for
+    /// example the instance of the component whose internals this node is part of. All
+    /// expansions of a repeated node map to the repeated node itself, which they share a
+    /// global id with. `None` if the node isn't inside an instance of `component`.
+    pub fn template_node_in_component(&self, component: &TypeId) -> Option<NodeInterface> {
+        let mut node = self.clone();
+        loop {
+            if node
+                .global_id()
+                .is_some_and(|uid| &uid.get_containing_component_type_id() == component)
+            {
+                return Some(node);
+            }
+            node = node.template_parent()?;
+        }
+    }
+
     pub fn is_descendant_of(&self, node: &NodeInterface) -> bool {
         self.inner.is_descendant_of(&node.inner.id)
     }
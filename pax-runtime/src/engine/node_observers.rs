@@ -0,0 +1,67 @@
+//! Designtime hook for tooling that follows individual template nodes from frame to frame, e.g.
+//! the property recorder (see `pax_designtime::property_recorder`). An observer registered for a
+//! template node is shown its expanded instances at the end of every tick, once their
+//! properties are up to date.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use pax_manifest::UniqueTemplateNodeIdentifier;
+use pax_runtime_api::{borrow, borrow_mut, use_RefCell};
+use_RefCell!();
+
+use crate::{ExpandedNode, RuntimeContext};
+
+pub trait NodeObserver {
+    /// Called at the end of tick `frame` with the expanded instances of the observed template
+    /// node, in the order they were mounted. Not called on frames the node isn't expanded on
+    /// (e.g. inside a falsy `if`)
+    fn observe(&self, frame: u64, nodes: &[Rc<ExpandedNode>]);
+}
+
+/// Identifies a registration of a `NodeObserver`, for removing it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NodeObserverId(usize);
+
+type Registration = (
+    NodeObserverId,
+    UniqueTemplateNodeIdentifier,
+    Rc<dyn NodeObserver>,
+);
+
+/// The node observers registered with a `RuntimeContext`
+#[derive(Default)]
+pub struct NodeObservers {
+    next_id: Cell<usize>,
+    observers: RefCell<Vec<Registration>>,
+}
+
+impl NodeObservers {
+    /// Shows `observer` the expanded instances of template node `uni` every frame, until
+    /// removed with `remove`
+    pub fn add(
+        &self,
+        uni: UniqueTemplateNodeIdentifier,
+        observer: Rc<dyn NodeObserver>,
+    ) -> NodeObserverId {
+        let id = NodeObserverId(self.next_id.get());
+        self.next_id.set(id.0 + 1);
+        borrow_mut!(self.observers).push((id, uni, observer));
+        id
+    }
+
+    pub fn remove(&self, id: NodeObserverId) {
+        borrow_mut!(self.observers).retain(|(observer_id, _, _)| *observer_id != id);
+    }
+
+    pub(crate) fn notify(&self, frame: u64, ctx: &RuntimeContext) {
+        // observers can add or remove observers while being notified
+        let observers = borrow!(self.observers).clone();
+        for (_, uni, observer) in observers {
+            let nodes = ctx.get_expanded_nodes_by_global_ids(&uni);
+            if !nodes.is_empty() {
+                observer.observe(frame, &nodes);
+            }
+        }
+    }
+}
@@ -45,7 +45,14 @@ impl OcclusionBox {
 pub fn update_node_occlusion(root_node: &Rc<ExpandedNode>, ctx: &RuntimeContext) {
     let mut occlusion_stack = vec![];
     let mut z_index = 0;
-    update_node_occlusion_recursive(root_node, &mut occlusion_stack, ctx, false, &mut z_index);
+    update_node_occlusion_recursive(
+        root_node,
+        &mut occlusion_stack,
+        ctx,
+        false,
+        false,
+        &mut z_index,
+    );
     let max_layer = occlusion_stack.len();
     if ctx.layer_count.get() != max_layer {
         ctx.layer_count.set(max_layer);
@@ -60,8 +67,24 @@ fn update_node_occlusion_recursive(
     occlusion_stack: &mut Vec<(Vec<OcclusionBox>, Vec<OcclusionBox>)>,
     ctx: &RuntimeContext,
     clipping: bool,
+    overlay: bool,
     z_index: &mut i32,
 ) {
+    // a background is drawn beneath the children, so it needs to be
+    // placed before them
+    let draws_background = borrow!(node.instance_node).draws_background(&node);
+    if draws_background {
+        update_single_node_occlusion(
+            node,
+            Layer::Canvas,
+            occlusion_stack,
+            ctx,
+            clipping,
+            overlay,
+            z_index,
+        );
+    }
+
     for child in node.children.get().iter().rev() {
         let cp = child.get_common_properties();
         let cp = borrow!(cp);
@@ -72,65 +95,94 @@ fn update_node_occlusion_recursive(
             occlusion_stack,
             ctx,
             (clipping | clips) & !unclippable,
+            // unclippable subtrees are rendered after everything else
+            overlay | unclippable,
             z_index,
         );
     }
 
     let layer = borrow!(node.instance_node).base().flags().layer;
-    if layer != Layer::DontCare || borrow!(node.instance_node).clips_content(&node) {
-        let occlusion_box =
-            OcclusionBox::new_from_transform_and_bounds(node.transform_and_bounds.get());
-        let mut occlusion_index = 0;
-
-        for (index, stack) in occlusion_stack.iter().enumerate().rev() {
-            if stack.0.iter().any(|box_| occlusion_box.intersects(box_)) {
-                occlusion_index = match layer {
-                    Layer::Canvas => index + 1,
-                    _ => index,
-                };
-                break;
-            }
-            if stack.1.iter().any(|box_| occlusion_box.intersects(box_)) {
-                occlusion_index = index;
-                break;
-            }
-        }
-
-        if occlusion_stack.len() <= occlusion_index {
-            occlusion_stack.push(Default::default());
-        }
+    if !draws_background
+        && (layer != Layer::DontCare || borrow!(node.instance_node).clips_content(&node))
+    {
+        update_single_node_occlusion(
+            node,
+            layer,
+            occlusion_stack,
+            ctx,
+            clipping,
+            overlay,
+            z_index,
+        );
+    }
+}
 
-        let occl_layer = &mut occlusion_stack[occlusion_index];
-        let set = match layer {
-            Layer::Native => &mut occl_layer.0,
-            _ => &mut occl_layer.1,
-        };
-        set.push(occlusion_box);
-
-        let new_occlusion = Occlusion {
-            occlusion_layer_id: occlusion_index as u32,
-            z_index: *z_index,
-            parent_frame: node
-                .parent_frame
-                .get()
-                .filter(|_| clipping)
-                .map(|v| v.to_u32()),
-        };
+fn update_single_node_occlusion(
+    node: &Rc<ExpandedNode>,
+    layer: Layer,
+    occlusion_stack: &mut Vec<(Vec<OcclusionBox>, Vec<OcclusionBox>)>,
+    ctx: &RuntimeContext,
+    clipping: bool,
+    overlay: bool,
+    z_index: &mut i32,
+) {
+    let occlusion_box =
+        OcclusionBox::new_from_transform_and_bounds(node.transform_and_bounds.get());
+    let mut occlusion_index = 0;
 
-        if (layer == Layer::Native || borrow!(node.instance_node).clips_content(&node))
-            && node.occlusion.get() != new_occlusion
-        {
-            let occlusion_patch = OcclusionPatch {
-                id: node.id.to_u32(),
-                z_index: new_occlusion.z_index,
-                occlusion_layer_id: new_occlusion.occlusion_layer_id,
-                parent_frame: new_occlusion.parent_frame,
+    for (index, stack) in occlusion_stack.iter().enumerate().rev() {
+        if stack.0.iter().any(|box_| occlusion_box.intersects(box_)) {
+            occlusion_index = match layer {
+                Layer::Canvas => index + 1,
+                _ => index,
             };
-            ctx.enqueue_native_message(pax_message::NativeMessage::OcclusionUpdate(
-                occlusion_patch,
-            ));
+            break;
         }
-        node.occlusion.set(new_occlusion);
-        *z_index += 1;
+        if stack.1.iter().any(|box_| occlusion_box.intersects(box_)) {
+            occlusion_index = index;
+            break;
+        }
+    }
+
+    if occlusion_stack.len() <= occlusion_index {
+        occlusion_stack.push(Default::default());
+    }
+
+    let occl_layer = &mut occlusion_stack[occlusion_index];
+    let set = match layer {
+        Layer::Native => &mut occl_layer.0,
+        _ => &mut occl_layer.1,
+    };
+    set.push(occlusion_box);
+
+    let new_occlusion = Occlusion {
+        occlusion_layer_id: occlusion_index as u32,
+        z_index: *z_index,
+        parent_frame: node
+            .parent_frame
+            .get()
+            .filter(|_| clipping)
+            .map(|v| v.to_u32()),
+    };
+
+    if (layer == Layer::Native || borrow!(node.instance_node).clips_content(&node))
+        && node.occlusion.get() != new_occlusion
+    {
+        let occlusion_patch = OcclusionPatch {
+            id: ctx.native_id(node.id),
+            z_index: new_occlusion.z_index,
+            occlusion_layer_id: new_occlusion.occlusion_layer_id,
+            parent_frame: new_occlusion.parent_frame,
+        };
+        ctx.enqueue_native_message(pax_message::NativeMessage::OcclusionUpdate(occlusion_patch));
+    }
+    if layer == Layer::Native {
+        ctx.visit_native_element(
+            ctx.native_id(node.id),
+            new_occlusion.occlusion_layer_id,
+            overlay,
+        );
     }
+    node.occlusion.set(new_occlusion);
+    *z_index += 1;
 }
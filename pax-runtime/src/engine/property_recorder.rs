@@ -0,0 +1,77 @@
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+use pax_designtime::messages::NodeBounds;
+use pax_designtime::property_recorder::RecordedFrame;
+use pax_designtime::DesigntimeManager;
+use pax_manifest::UniqueTemplateNodeIdentifier;
+use pax_runtime_api::{borrow, borrow_mut, use_RefCell};
+use_RefCell!();
+
+use super::node_observers::{NodeObserver, NodeObserverId, NodeObservers};
+use crate::ExpandedNode;
+
+/// Keeps a node observer registered for each node the `DesigntimeManager` records the property
+/// values of, see `pax_designtime::property_recorder`
+#[derive(Default)]
+pub struct PropertyRecorder {
+    registered: RefCell<HashMap<UniqueTemplateNodeIdentifier, NodeObserverId>>,
+}
+
+impl PropertyRecorder {
+    /// Registers observers for the nodes that started being recorded since the last frame, and
+    /// removes those of the nodes that stopped
+    pub fn begin_frame(
+        &self,
+        observers: &NodeObservers,
+        designtime: &Rc<RefCell<DesigntimeManager>>,
+    ) {
+        let recorded = borrow!(designtime).get_recorded_nodes();
+        let mut registered = borrow_mut!(self.registered);
+        registered.retain(|uni, id| {
+            let keep = recorded.contains(uni);
+            if !keep {
+                observers.remove(*id);
+            }
+            keep
+        });
+        for uni in recorded {
+            if registered.contains_key(&uni) {
+                continue;
+            }
+            let observer = Rc::new(RecordingObserver {
+                uni: uni.clone(),
+                designtime: Rc::clone(designtime),
+            });
+            registered.insert(uni.clone(), observers.add(uni, observer));
+        }
+    }
+}
+
+struct RecordingObserver {
+    uni: UniqueTemplateNodeIdentifier,
+    designtime: Rc<RefCell<DesigntimeManager>>,
+}
+
+impl NodeObserver for RecordingObserver {
+    fn observe(&self, frame: u64, nodes: &[Rc<ExpandedNode>]) {
+        // instances other than the first (e.g. of a node in a `for`) aren't recorded
+        let Some(node) = nodes.first() else {
+            return;
+        };
+        let properties: BTreeMap<_, _> = borrow!(node.properties_scope)
+            .iter()
+            .map(|(name, variable)| (name.clone(), variable.get_as_pax_value()))
+            .collect();
+        let tab = node.transform_and_bounds.get();
+        let frame = RecordedFrame {
+            frame,
+            properties,
+            bounds: NodeBounds {
+                transform: tab.transform.coeffs(),
+                bounds: tab.bounds,
+            },
+        };
+        borrow_mut!(self.designtime).record_node_frame(&self.uni, frame);
+    }
+}
@@ -0,0 +1,66 @@
+use pax_runtime_api::PaxValue;
+
+/// Layers the `text_style` common property of a node over the text style it inherits from its
+/// render parent, field by field: fields set by the node override the inherited ones, the other
+/// inherited fields are passed on. Text styles are objects with some of the fields of a text
+/// style, which the text primitives layer their own style over in turn.
+pub fn layer_text_style(inherited: Option<PaxValue>, own: Option<PaxValue>) -> Option<PaxValue> {
+    match (inherited, own) {
+        (Some(PaxValue::Object(mut fields)), Some(PaxValue::Object(own_fields))) => {
+            fields.extend(own_fields);
+            Some(PaxValue::Object(fields))
+        }
+        (inherited, None) => inherited,
+        (_, own) => own,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use pax_runtime_api::{Color, Numeric, Size};
+
+    use super::*;
+
+    fn style(fields: &[(&str, PaxValue)]) -> Option<PaxValue> {
+        Some(PaxValue::Object(
+            fields
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect(),
+        ))
+    }
+
+    fn size(px: f64) -> PaxValue {
+        PaxValue::Size(Size::Pixels(Numeric::F64(px)))
+    }
+
+    fn color(color: Color) -> PaxValue {
+        PaxValue::Color(Box::new(color))
+    }
+
+    #[test]
+    fn own_fields_override_inherited_ones() {
+        let root = style(&[("font_size", size(14.0)), ("fill", color(Color::BLACK))]);
+        let child = style(&[("fill", color(Color::RED))]);
+        let Some(PaxValue::Object(fields)) = layer_text_style(root, child) else {
+            panic!("expected an object");
+        };
+        assert_eq!(
+            fields,
+            HashMap::from([
+                ("font_size".to_string(), size(14.0)),
+                ("fill".to_string(), color(Color::RED)),
+            ])
+        );
+    }
+
+    #[test]
+    fn missing_styles_pass_through() {
+        let root = style(&[("font_size", size(14.0))]);
+        assert_eq!(layer_text_style(root.clone(), None), root);
+        assert_eq!(layer_text_style(None, root.clone()), root);
+        assert_eq!(layer_text_style(None, None), None);
+    }
+}
@@ -63,17 +63,27 @@ pub fn calculate_transform_and_bounds(
         bounds: container_bounds,
     }: TransformAndBounds<NodeLocal, Window>,
 ) -> TransformAndBounds<NodeLocal, Window> {
+    let container_bounds = (
+        clamp_size(container_bounds.0, "container width"),
+        clamp_size(container_bounds.1, "container height"),
+    );
     let x = x.unwrap_or(Size::ZERO());
     let y = y.unwrap_or(Size::ZERO());
-    let width = width
-        .map(|v| v.evaluate(container_bounds, Axis::X))
-        .unwrap_or(container_bounds.0);
-    let height = height
-        .map(|v| v.evaluate(container_bounds, Axis::Y))
-        .unwrap_or(container_bounds.1);
+    let width = clamp_size(
+        width
+            .map(|v| v.evaluate(container_bounds, Axis::X))
+            .unwrap_or(container_bounds.0),
+        "width",
+    );
+    let height = clamp_size(
+        height
+            .map(|v| v.evaluate(container_bounds, Axis::Y))
+            .unwrap_or(container_bounds.1),
+        "height",
+    );
     let origin = Vector2::new(
-        x.evaluate(container_bounds, Axis::X),
-        y.evaluate(container_bounds, Axis::Y),
+        finite_or_zero(x.evaluate(container_bounds, Axis::X)),
+        finite_or_zero(y.evaluate(container_bounds, Axis::Y)),
     );
 
     let bounds = (width, height);
@@ -108,8 +118,8 @@ pub fn calculate_transform_and_bounds(
     });
 
     let anchor_transform = Transform2::translate(Vector2::new(
-        -anchor_x.evaluate(bounds, Axis::X),
-        -anchor_y.evaluate(bounds, Axis::Y),
+        -finite_or_zero(anchor_x.evaluate(bounds, Axis::X)),
+        -finite_or_zero(anchor_y.evaluate(bounds, Axis::Y)),
     ));
 
     let scale = Vector2::new(
@@ -145,6 +155,26 @@ pub fn calculate_transform_and_bounds(
     }
 }
 
+/// Clamps a computed size to be finite and non-negative, so that a broken layout (e.g. a negative
+/// combined size, or a percent of a degenerate container) degrades to an empty node instead of
+/// corrupting the transforms of the whole subtree
+fn clamp_size(size: f64, what: &str) -> f64 {
+    if size.is_finite() && size >= 0.0 {
+        return size;
+    }
+    log::debug!("clamped computed {} of {} to 0", what, size);
+    0.0
+}
+
+/// A computed offset, with non-finite values (e.g. from an infinite container) treated as 0
+fn finite_or_zero(offset: f64) -> f64 {
+    if offset.is_finite() {
+        offset
+    } else {
+        0.0
+    }
+}
+
 /// Properties that are currently re-computed each frame before rendering.
 
 impl<F: Space, T: Space> Interpolatable for TransformAndBounds<F, T> {
@@ -451,3 +481,284 @@ impl ComputableTransform<NodeLocal, Window> for Transform2D {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pax_runtime_api::Numeric;
+
+    use super::*;
+
+    // element size used throughout: non-square, so that mixing up the axes shows
+    const ELEMENT: (f64, f64) = (200.0, 100.0);
+    const CONTAINER: (f64, f64) = (400.0, 300.0);
+
+    fn px(v: f64) -> Size {
+        Size::Pixels(Numeric::F64(v))
+    }
+
+    fn percent(v: f64) -> Size {
+        Size::Percent(Numeric::F64(v))
+    }
+
+    fn combined(pixels: f64, percent: f64) -> Size {
+        Size::Combined(Numeric::F64(pixels), Numeric::F64(percent))
+    }
+
+    fn container() -> TransformAndBounds<NodeLocal, Window> {
+        TransformAndBounds {
+            transform: Transform2::identity(),
+            bounds: CONTAINER,
+        }
+    }
+
+    /// Layout properties of an `ELEMENT` sized node at (0, 0), with zero anchor
+    fn element() -> LayoutProperties {
+        LayoutProperties {
+            width: Some(px(ELEMENT.0)),
+            height: Some(px(ELEMENT.1)),
+            anchor_x: Some(Size::ZERO()),
+            anchor_y: Some(Size::ZERO()),
+            ..Default::default()
+        }
+    }
+
+    fn assert_coeffs(actual: Transform2<NodeLocal, Window>, expected: [f64; 6]) {
+        let diff = actual
+            .coeffs()
+            .iter()
+            .zip(expected)
+            .map(|(a, e)| (a - e).abs())
+            .sum::<f64>();
+        assert!(
+            diff < 1e-9,
+            "expected {:?}, got {:?}",
+            expected,
+            actual.coeffs()
+        );
+    }
+
+    fn translation(layout_properties: &LayoutProperties) -> (f64, f64) {
+        let tab = calculate_transform_and_bounds(layout_properties, container());
+        let [.., x, y] = tab.transform.coeffs();
+        (x, y)
+    }
+
+    #[test]
+    fn anchor_x_all_size_variants() {
+        for (anchor, expected) in [
+            (px(10.0), -10.0),
+            (percent(50.0), -100.0),
+            (combined(10.0, 50.0), -110.0),
+        ] {
+            let props = LayoutProperties {
+                anchor_x: Some(anchor),
+                ..element()
+            };
+            assert_eq!(translation(&props), (expected, 0.0));
+        }
+    }
+
+    #[test]
+    fn anchor_y_all_size_variants() {
+        // percentages of the y anchor are relative to the element height
+        for (anchor, expected) in [
+            (px(10.0), -10.0),
+            (percent(50.0), -50.0),
+            (combined(10.0, 50.0), -60.0),
+        ] {
+            let props = LayoutProperties {
+                anchor_y: Some(anchor),
+                ..element()
+            };
+            assert_eq!(translation(&props), (0.0, expected));
+        }
+    }
+
+    #[test]
+    fn translate_all_size_variants() {
+        // percentages of x/y are relative to the container bounds
+        for (x, y, expected) in [
+            (px(10.0), px(20.0), (10.0, 20.0)),
+            (percent(50.0), percent(50.0), (200.0, 150.0)),
+            (combined(10.0, 25.0), combined(10.0, 25.0), (110.0, 85.0)),
+        ] {
+            let props = LayoutProperties {
+                x: Some(x),
+                y: Some(y),
+                ..element()
+            };
+            assert_eq!(translation(&props), expected);
+        }
+    }
+
+    #[test]
+    fn implicit_anchor_follows_percent_component_of_position() {
+        let props = |x: Size, y: Size| LayoutProperties {
+            x: Some(x),
+            y: Some(y),
+            anchor_x: None,
+            anchor_y: None,
+            ..element()
+        };
+        // pixel positions don't anchor
+        assert_eq!(translation(&props(px(10.0), px(20.0))), (10.0, 20.0));
+        // 50% positions anchor at 50% of the element
+        assert_eq!(
+            translation(&props(percent(50.0), percent(50.0))),
+            (200.0 - 100.0, 150.0 - 50.0)
+        );
+        // the percent component of a combined position is used as anchor
+        assert_eq!(
+            translation(&props(combined(10.0, 50.0), combined(10.0, 50.0))),
+            (210.0 - 100.0, 160.0 - 50.0)
+        );
+        // implicit anchors are clamped to [0%, 100%]
+        assert_eq!(
+            translation(&props(percent(150.0), percent(-50.0))),
+            (600.0 - 200.0, -150.0)
+        );
+    }
+
+    #[test]
+    fn negative_and_degenerate_sizes_are_clamped() {
+        let props = LayoutProperties {
+            width: Some(combined(-300.0, 50.0)),
+            height: Some(percent(50.0)),
+            x: Some(percent(50.0)),
+            anchor_x: Some(percent(50.0)),
+            ..element()
+        };
+        let tab = calculate_transform_and_bounds(&props, container());
+        assert_eq!(tab.bounds, (0.0, 150.0));
+        assert_coeffs(tab.transform, [1.0, 0.0, 0.0, 1.0, 200.0, 0.0]);
+
+        // percents of a zero-size or broken container produce zero sizes and offsets, not NaN
+        for bounds in [(0.0, 0.0), (f64::NAN, -10.0), (f64::INFINITY, 0.0)] {
+            let container = TransformAndBounds {
+                transform: Transform2::identity(),
+                bounds,
+            };
+            let tab = calculate_transform_and_bounds(&props, container);
+            assert_eq!(tab.bounds, (0.0, 0.0));
+            assert_coeffs(tab.transform, [1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+        }
+    }
+
+    #[test]
+    fn size_percent_and_combined() {
+        let props = LayoutProperties {
+            width: Some(percent(50.0)),
+            height: Some(combined(10.0, 50.0)),
+            ..element()
+        };
+        let tab = calculate_transform_and_bounds(&props, container());
+        assert_eq!(tab.bounds, (200.0, 160.0));
+        // unset width/height fill the container
+        let tab = calculate_transform_and_bounds(&LayoutProperties::default(), container());
+        assert_eq!(tab.bounds, CONTAINER);
+    }
+
+    #[test]
+    fn rotation_around_anchor() {
+        let props = LayoutProperties {
+            x: Some(px(100.0)),
+            y: Some(px(50.0)),
+            anchor_x: Some(percent(50.0)),
+            anchor_y: Some(percent(50.0)),
+            rotate: Some(Rotation::Degrees(Numeric::F64(90.0))),
+            ..element()
+        };
+        let tab = calculate_transform_and_bounds(&props, container());
+        // translate(100, 50) * rotate(90°) * translate(-100, -50)
+        assert_coeffs(tab.transform, [0.0, 1.0, -1.0, 0.0, 150.0, -50.0]);
+        // the anchor point ends up at (x, y)
+        let center = tab.transform * Point2::<NodeLocal>::new(100.0, 50.0);
+        assert!((center.x - 100.0).abs() < 1e-9 && (center.y - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn scale_and_skew() {
+        let props = LayoutProperties {
+            scale_x: Some(Percent(Numeric::F64(200.0))),
+            scale_y: Some(Percent(Numeric::F64(50.0))),
+            ..element()
+        };
+        let tab = calculate_transform_and_bounds(&props, container());
+        assert_coeffs(tab.transform, [2.0, 0.0, 0.0, 0.5, 0.0, 0.0]);
+
+        let props = LayoutProperties {
+            skew_x: Some(Rotation::Radians(Numeric::F64(0.5))),
+            skew_y: Some(Rotation::Radians(Numeric::F64(0.25))),
+            ..element()
+        };
+        let tab = calculate_transform_and_bounds(&props, container());
+        assert_coeffs(tab.transform, [1.0, 0.25, 0.5, 1.0, 0.0, 0.0]);
+
+        // skew is applied before scale
+        let props = LayoutProperties {
+            scale_x: Some(Percent(Numeric::F64(200.0))),
+            skew_x: Some(Rotation::Radians(Numeric::F64(0.5))),
+            ..element()
+        };
+        let tab = calculate_transform_and_bounds(&props, container());
+        assert_coeffs(tab.transform, [2.0, 0.0, 1.0, 1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn container_transform_is_applied_last() {
+        // translate(5, 7) * rotate(90°)
+        let container = TransformAndBounds {
+            transform: Transform2::new([0.0, 1.0, -1.0, 0.0, 5.0, 7.0]),
+            bounds: CONTAINER,
+        };
+        let props = LayoutProperties {
+            x: Some(px(10.0)),
+            anchor_y: Some(combined(10.0, 50.0)),
+            ..element()
+        };
+        let tab = calculate_transform_and_bounds(&props, container);
+        // container * translate(10, 0) * translate(0, -60)
+        assert_coeffs(tab.transform, [0.0, 1.0, -1.0, 0.0, 65.0, 17.0]);
+    }
+
+    #[test]
+    fn transform2d_apply() {
+        let node = TransformAndBounds {
+            transform: Transform2::identity(),
+            bounds: ELEMENT,
+        };
+
+        let anchored = Transform2D::anchor(percent(50.0), combined(10.0, 50.0));
+        let tab = anchored.apply(node);
+        assert_coeffs(tab.transform, [1.0, 0.0, 0.0, 1.0, -100.0, -60.0]);
+        assert_eq!(tab.bounds, ELEMENT);
+
+        // translate percentages are relative to the node's own bounds
+        let translated = Transform2D::translate(percent(50.0), combined(10.0, 50.0));
+        let tab = translated.apply(node);
+        assert_coeffs(
+            tab.transform,
+            [1.0, 0.0, 0.0, 1.0, 100.0 - 100.0, 60.0 - 50.0],
+        );
+    }
+
+    #[test]
+    fn transform2d_previous_chaining() {
+        let node = TransformAndBounds {
+            transform: Transform2::identity(),
+            bounds: ELEMENT,
+        };
+        let chained = Transform2D::translate(px(10.0), px(20.0))
+            * Transform2D::scale(percent(200.0), percent(200.0));
+        assert!(chained.previous.is_some());
+        let tab = chained.apply(node);
+        // the last transform in the chain is applied first, previous ones
+        // are applied within its resulting coordinate space
+        assert_coeffs(tab.transform, [2.0, 0.0, 0.0, 2.0, 20.0, 40.0]);
+
+        let chained = Transform2D::scale(percent(200.0), percent(200.0))
+            * Transform2D::translate(px(10.0), px(20.0));
+        let tab = chained.apply(node);
+        assert_coeffs(tab.transform, [2.0, 0.0, 0.0, 2.0, 10.0, 20.0]);
+    }
+}
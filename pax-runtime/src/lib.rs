@@ -12,6 +12,8 @@ pub mod properties;
 pub mod rendering;
 pub mod repeat;
 pub mod slot;
+#[cfg(feature = "svg-export")]
+pub mod svg_export;
 
 pub use crate::cartridge::*;
 pub use crate::component::*;
@@ -1,8 +1,8 @@
 use crate::api::math::Point2;
-use crate::api::Window;
+use crate::api::{NodeContext, Window};
 use pax_lang::interpreter::property_resolution::IdentifierResolver;
 use pax_manifest::UniqueTemplateNodeIdentifier;
-use pax_message::NativeMessage;
+use pax_message::{ClipboardReadRequestPatch, NativeMessage, OcclusionPatch};
 use pax_runtime_api::pax_value::PaxAny;
 use pax_runtime_api::properties::UntypedProperty;
 use pax_runtime_api::{
@@ -12,13 +12,19 @@ use pax_runtime_api::{
 use_RefCell!();
 use std::any::{Any, TypeId};
 use std::cell::Cell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::rc::{Rc, Weak};
 
+use crate::mount_tracking::MountedNodes;
+use crate::native_ordering::NativeElementOrdering;
+use crate::native_recycling::NativeElementPool;
 use crate::{ExpandedNode, Globals};
 
 #[cfg(feature = "designtime")]
-use crate::{ComponentInstance, InstanceNode};
+use crate::{
+    node_costs::NodeCostSampler, node_observers::NodeObservers,
+    property_recorder::PropertyRecorder, ComponentInstance, InstanceNode,
+};
 
 impl Interpolatable for ExpandedNodeIdentifier {}
 
@@ -32,6 +38,10 @@ impl ExpandedNodeIdentifier {
     }
 }
 
+type ClipboardReadCallback = Box<dyn FnOnce(String)>;
+/// A call of a public method of a component instance, see `ComponentHandle`
+pub(crate) type ComponentCall = Box<dyn FnOnce(&mut PaxAny, &NodeContext)>;
+
 /// Shared context for properties pass recursion
 pub struct RuntimeContext {
     next_uid: Cell<ExpandedNodeIdentifier>,
@@ -42,10 +52,21 @@ pub struct RuntimeContext {
     pub userland_frame_instance_node: RefCell<Rc<dyn InstanceNode>>,
     #[cfg(feature = "designtime")]
     pub userland_root_expanded_node: RefCell<Option<Rc<ExpandedNode>>>,
+    #[cfg(feature = "designtime")]
+    node_costs: NodeCostSampler,
+    #[cfg(feature = "designtime")]
+    node_observers: NodeObservers,
+    #[cfg(feature = "designtime")]
+    property_recorder: PropertyRecorder,
     node_cache: RefCell<NodeCache>,
+    mounted_nodes: RefCell<MountedNodes>,
     last_topmost_element: RefCell<Weak<ExpandedNode>>,
     queued_custom_events: RefCell<Vec<(Rc<ExpandedNode>, &'static str)>>,
+    queued_component_calls: RefCell<Vec<(Weak<ExpandedNode>, ComponentCall)>>,
     queued_renders: RefCell<Vec<Rc<ExpandedNode>>>,
+    native_element_pool: RefCell<NativeElementPool>,
+    native_element_ordering: RefCell<NativeElementOrdering>,
+    pending_clipboard_reads: RefCell<VecDeque<ClipboardReadCallback>>,
     pub layer_count: Cell<usize>,
 }
 
@@ -65,11 +86,7 @@ impl NodeCache {
     // Add this node to all relevant constant lookup cache structures
     fn add_to_cache(&mut self, node: &Rc<ExpandedNode>) {
         self.eid_to_node.insert(node.id, Rc::clone(&node));
-        let uni = borrow!(node.instance_node)
-            .base()
-            .template_node_identifier
-            .clone();
-        if let Some(uni) = uni {
+        if let Some(uni) = node.global_id() {
             self.uni_to_eid.entry(uni).or_default().push(node.id);
         }
     }
@@ -77,9 +94,9 @@ impl NodeCache {
     // Remove this node from all relevant constant lookup cache structures
     fn remove_from_cache(&mut self, node: &Rc<ExpandedNode>) {
         self.eid_to_node.remove(&node.id);
-        if let Some(uni) = &borrow!(node.instance_node).base().template_node_identifier {
+        if let Some(uni) = node.global_id() {
             self.uni_to_eid
-                .entry(uni.clone())
+                .entry(uni)
                 .or_default()
                 .retain(|&n| n != node.id);
         }
@@ -95,8 +112,13 @@ impl RuntimeContext {
             globals: RefCell::new(globals),
             root_expanded_node: RefCell::new(Weak::new()),
             node_cache: RefCell::new(NodeCache::new()),
+            mounted_nodes: Default::default(),
             queued_custom_events: Default::default(),
+            queued_component_calls: Default::default(),
             queued_renders: Default::default(),
+            native_element_pool: Default::default(),
+            native_element_ordering: Default::default(),
+            pending_clipboard_reads: Default::default(),
             layer_count: Cell::default(),
             last_topmost_element: Default::default(),
         }
@@ -111,9 +133,17 @@ impl RuntimeContext {
             root_expanded_node: RefCell::new(Weak::new()),
             userland_frame_instance_node: RefCell::new(userland),
             userland_root_expanded_node: Default::default(),
+            node_costs: Default::default(),
+            node_observers: Default::default(),
+            property_recorder: Default::default(),
             node_cache: RefCell::new(NodeCache::new()),
+            mounted_nodes: Default::default(),
             queued_custom_events: Default::default(),
+            queued_component_calls: Default::default(),
             queued_renders: Default::default(),
+            native_element_pool: Default::default(),
+            native_element_ordering: Default::default(),
+            pending_clipboard_reads: Default::default(),
             layer_count: Cell::default(),
             last_topmost_element: Default::default(),
         }
@@ -131,6 +161,50 @@ impl RuntimeContext {
         borrow_mut!(self.node_cache).remove_from_cache(node);
     }
 
+    /// Records that the `mount` handlers of `node` fired
+    pub fn record_mount(&self, node: &Rc<ExpandedNode>) {
+        borrow_mut!(self.mounted_nodes).record_mount(node.id);
+    }
+
+    /// Records that the `unmount` handlers of `node` fired
+    pub fn record_unmount(&self, node: &Rc<ExpandedNode>) {
+        borrow_mut!(self.mounted_nodes).record_unmount(node.id);
+    }
+
+    /// Number of `mount` and `unmount` lifecycle events fired so far, which
+    /// balance out once every node that was mounted has been unmounted
+    pub fn mount_counts(&self) -> (u64, u64) {
+        borrow!(self.mounted_nodes).counts()
+    }
+
+    /// Unmounts the nodes that are still mounted but can't be reached from the
+    /// root anymore, to be called after replacing (part of) the tree so that
+    /// the `unmount` handlers of every node that disappeared fire
+    pub fn unmount_orphaned_nodes(self: &Rc<Self>) {
+        let Some(root) = self.get_root_expanded_node() else {
+            return;
+        };
+        let mut reachable = HashSet::new();
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            if node.attached.get() == 0 || !reachable.insert(node.id) {
+                continue;
+            }
+            stack.extend(borrow!(node.mounted_children).iter().cloned());
+            if let Some(slot_children) = borrow!(node.expanded_slot_children).as_ref() {
+                stack.extend(slot_children.iter().cloned());
+            }
+        }
+        let orphans: Vec<_> = borrow!(self.mounted_nodes)
+            .orphans(&reachable)
+            .into_iter()
+            .filter_map(|id| self.get_expanded_node_by_eid(id))
+            .collect();
+        for orphan in orphans {
+            orphan.recurse_unmount(self);
+        }
+    }
+
     pub fn get_expanded_node_by_eid(&self, id: ExpandedNodeIdentifier) -> Option<Rc<ExpandedNode>> {
         borrow!(self.node_cache).eid_to_node.get(&id).cloned()
     }
@@ -273,15 +347,121 @@ impl RuntimeContext {
         borrow_mut!(self.messages).push(message)
     }
 
+    /// Asks the chassis for the text on the system clipboard, calling `callback` with it
+    /// once the chassis answers
+    pub fn request_clipboard_read(&self, callback: impl FnOnce(String) + 'static) {
+        borrow_mut!(self.pending_clipboard_reads).push_back(Box::new(callback));
+        self.enqueue_native_message(NativeMessage::ClipboardReadRequest(
+            ClipboardReadRequestPatch {},
+        ));
+    }
+
+    /// Hands the text of a `ClipboardRead` interrupt to the oldest pending clipboard read
+    pub fn resolve_clipboard_read(&self, text: String) {
+        let callback = borrow_mut!(self.pending_clipboard_reads).pop_front();
+        match callback {
+            Some(callback) => callback(text),
+            None => log::warn!("received a clipboard read without a pending request"),
+        }
+    }
+
     pub fn take_native_messages(&self) -> Vec<NativeMessage> {
         let mut messages = borrow_mut!(self.messages);
         std::mem::take(&mut *messages)
     }
 
+    /// Enables reuse of the native elements of unmounted nodes, if the chassis supports it
+    pub fn set_native_element_recycling(&self, enabled: bool) {
+        let messages = borrow_mut!(self.native_element_pool).set_enabled(enabled);
+        borrow_mut!(self.messages).extend(messages);
+    }
+
+    /// The id the chassis knows the native element of node `id` by. Differs
+    /// from `id` if the node reuses the native element of an unmounted node.
+    pub fn native_id(&self, id: ExpandedNodeIdentifier) -> u32 {
+        borrow!(self.native_element_pool).native_id(id)
+    }
+
+    /// Finds the node using the native element with id `native_id`. Returns
+    /// `None` for elements parked for reuse, dropping interrupts sent to them.
+    pub fn get_expanded_node_by_native_id(&self, native_id: u32) -> Option<Rc<ExpandedNode>> {
+        let id = borrow!(self.native_element_pool).node_id(native_id)?;
+        self.get_expanded_node_by_eid(id)
+    }
+
+    /// Claims a parked native element of `kind` for this node, returning its
+    /// id. The node should send a full update patch for the element instead
+    /// of a create message. Returns `None` if there is nothing to reuse, in
+    /// which case the node creates its element as usual, using its own id.
+    pub fn claim_native_element(
+        &self,
+        kind: &'static str,
+        expanded_node: &ExpandedNode,
+    ) -> Option<u32> {
+        let parent_frame = expanded_node.parent_frame.get().map(|v| v.to_u32());
+        borrow_mut!(self.native_element_pool).claim(kind, parent_frame, expanded_node.id)
+    }
+
+    /// Releases the native element of an unmounting node, either parking it
+    /// for reuse (hiding it with the message created by `hide`), or deleting
+    /// it using `delete` if recycling is disabled.
+    pub fn release_native_element(
+        &self,
+        kind: &'static str,
+        expanded_node: &ExpandedNode,
+        delete: fn(u32) -> NativeMessage,
+        hide: impl FnOnce(u32) -> NativeMessage,
+    ) {
+        let parent_frame = expanded_node.parent_frame.get().map(|v| v.to_u32());
+        let mut pool = borrow_mut!(self.native_element_pool);
+        let native_id = pool.release(expanded_node.id);
+        if pool.is_enabled() {
+            self.enqueue_native_message(hide(native_id));
+            // put the element back in the state the chassis creates elements
+            // in, as occlusion updates are only sent on diffs
+            self.enqueue_native_message(NativeMessage::OcclusionUpdate(OcclusionPatch {
+                id: native_id,
+                occlusion_layer_id: 0,
+                z_index: 0,
+                parent_frame,
+            }));
+        }
+        let messages = pool.park(kind, parent_frame, native_id, delete);
+        borrow_mut!(self.messages).extend(messages);
+    }
+
+    /// Enables explicit stacking of native elements, if the chassis supports it
+    pub fn set_native_element_ordering(&self, enabled: bool) {
+        borrow_mut!(self.native_element_ordering).set_enabled(enabled);
+    }
+
+    /// Records the native element `native_id` as the next one in render order,
+    /// `overlay` if it's part of a subtree drawn above all other content
+    pub fn visit_native_element(&self, native_id: u32, occlusion_layer_id: u32, overlay: bool) {
+        borrow_mut!(self.native_element_ordering).visit(native_id, occlusion_layer_id, overlay);
+    }
+
+    /// Sends reorder messages for the native elements whose stacking changed this frame
+    pub fn flush_native_element_ordering(&self) {
+        let messages = borrow_mut!(self.native_element_ordering).finish_frame();
+        borrow_mut!(self.messages).extend(messages);
+    }
+
+    /// Deletes the native elements that have been parked for too long
+    pub fn flush_native_element_pool(&self) {
+        let messages = borrow_mut!(self.native_element_pool).tick();
+        borrow_mut!(self.messages).extend(messages);
+    }
+
     pub fn globals(&self) -> Globals {
         borrow!(self.globals).clone()
     }
 
+    /// See [`Globals::device_pixel_ratio`]
+    pub fn device_pixel_ratio(&self) -> f64 {
+        borrow!(self.globals).device_pixel_ratio.get()
+    }
+
     pub fn edit_globals(&self, f: impl Fn(&mut Globals)) {
         let mut globals = borrow_mut!(self.globals);
         f(&mut globals);
@@ -292,6 +472,29 @@ impl RuntimeContext {
         queued_custom_events.push((source_expanded_node, name));
     }
 
+    /// Queues `call` to run on the properties of component instance `target`, see
+    /// [`RuntimeContext::flush_component_calls`]
+    pub(crate) fn queue_component_call(&self, target: Weak<ExpandedNode>, call: ComponentCall) {
+        borrow_mut!(self.queued_component_calls).push((target, call));
+    }
+
+    /// Runs the component calls queued since the last flush, in order, on the instances that are
+    /// still mounted.  Calls queued by these calls run on the next flush
+    pub fn flush_component_calls(self: &Rc<Self>) {
+        let to_flush = std::mem::take(&mut *borrow_mut!(self.queued_component_calls));
+        for (target, call) in to_flush {
+            let Some(target) = target.upgrade().filter(|node| node.attached.get() > 0) else {
+                log::warn!("dropped a call on a component instance that was unmounted");
+                continue;
+            };
+            let properties = Rc::clone(&*borrow!(target.properties));
+            call(
+                &mut *borrow_mut!(properties),
+                &target.get_node_context(self),
+            );
+        }
+    }
+
     pub fn flush_custom_events(self: &Rc<Self>) -> Result<(), String> {
         let mut queued_custom_event = borrow_mut!(self.queued_custom_events);
         let to_flush: Vec<_> = std::mem::take(queued_custom_event.as_mut());
@@ -301,6 +504,24 @@ impl RuntimeContext {
         Ok(())
     }
 
+    /// Timing of the work on each node, see `pax_designtime::node_costs`
+    #[cfg(feature = "designtime")]
+    pub fn node_costs(&self) -> &NodeCostSampler {
+        &self.node_costs
+    }
+
+    /// Observers following individual template nodes, see `node_observers`
+    #[cfg(feature = "designtime")]
+    pub fn node_observers(&self) -> &NodeObservers {
+        &self.node_observers
+    }
+
+    /// Recording of the property values of nodes, see `pax_designtime::property_recorder`
+    #[cfg(feature = "designtime")]
+    pub fn property_recorder(&self) -> &PropertyRecorder {
+        &self.property_recorder
+    }
+
     #[cfg(feature = "designtime")]
     pub fn get_userland_root_expanded_node(&self) -> Option<Rc<ExpandedNode>> {
         borrow!(self.userland_root_expanded_node).clone()
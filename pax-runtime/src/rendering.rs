@@ -10,7 +10,9 @@ use pax_runtime_api::pax_value::PaxAny;
 use pax_runtime_api::{borrow, use_RefCell, Variable};
 use piet::{Color, StrokeStyle};
 
+use crate::api::math::Point2;
 use crate::api::{Layer, Scroll};
+use crate::node_interface::NodeLocal;
 
 use crate::{ExpandedNode, HandlerRegistry, RuntimeContext, RuntimePropertiesStackFrame};
 
@@ -68,6 +70,24 @@ impl std::fmt::Debug for dyn InstanceNode {
 /// [`ExpandedNode`]s are "type-blind".  The latter store polymorphic data but cannot operate on it without the type-aware assistance of their linked `InstanceNode`.
 ///
 /// (See [`RepeatInstance#expand_node`] where we visit a singular `InstanceNode` several times, producing multiple [`ExpandedNode`]s.)
+///
+/// # Custom primitives
+///
+/// Primitives are components whose behavior is implemented in Rust by an `InstanceNode`, rather
+/// than by a template.  Any crate can declare one, not only `pax-std`:
+///
+/// - declare the properties struct with `#[pax]` and `#[primitive("path::to::SomeInstance")]`.
+///   The path is resolved like a `use` path from the struct's module: `crate::`, `self::` and
+///   `super::` paths, and bare names, work from the crate the struct is declared in.
+/// - implement `InstanceNode` for `SomeInstance`, building its [`BaseInstance`] from the
+///   [`InstantiationArgs`] in `instantiate`.  Read the properties of a node with
+///   [`ExpandedNode::with_properties_unwrapped`], e.g. in `render`, which draws onto the
+///   `RenderContext` in window coordinates (see `transform_and_bounds`).
+/// - optionally narrow `hit_test` for shapes that don't fill their bounds.
+///
+/// The generated cartridge instantiates the primitive with
+/// `<path::to::SomeInstance as InstanceNode>::instantiate`; if the path doesn't resolve, or
+/// doesn't implement `InstanceNode`, the build error names the primitive.
 pub trait InstanceNode {
     ///Retrieves the base instance, containing common functionality that all instances share
     fn base(&self) -> &BaseInstance;
@@ -174,6 +194,20 @@ pub trait InstanceNode {
         false
     }
 
+    /// Whether a ray hitting the bounds of this node at `point`, in the node's own coordinates
+    /// (`(0, 0)` being its top-left corner), hits its content.  Lets primitives that don't fill
+    /// their bounds, e.g. a circle, ignore clicks on their empty corners
+    fn hit_test(&self, _expanded_node: &ExpandedNode, _point: Point2<NodeLocal>) -> bool {
+        true
+    }
+
+    /// Used by nodes that otherwise don't render anything themselves (`Layer::DontCare`)
+    /// to draw a background beneath their children, e.g. `Group` with a fill.
+    /// Such nodes are treated as canvas content placed below their children during occlusion.
+    fn draws_background(&self, _expanded_node: &ExpandedNode) -> bool {
+        false
+    }
+
     fn handle_native_interrupt(
         &self,
         _expanded_node: &Rc<ExpandedNode>,
@@ -181,6 +215,14 @@ pub trait InstanceNode {
     ) {
         // no-op for many
     }
+
+    /// SVG element approximating the content of a native element, used by
+    /// `PaxEngine::serialize_frame_as_svg`.  Native elements without one are exported as an
+    /// empty `<foreignObject>` covering their bounds
+    #[cfg(feature = "svg-export")]
+    fn svg_element(&self, _expanded_node: &ExpandedNode) -> Option<String> {
+        None
+    }
 }
 
 pub struct BaseInstance {
@@ -266,3 +308,54 @@ pub struct StrokeInstance {
     pub width: f64,
     pub style: StrokeStyle,
 }
+
+/// Moves every point of a path in window coordinates to the center of the
+/// physical pixel it falls in, so that a stroke one physical pixel wide
+/// covers a single row or column of pixels instead of blurring across two.
+pub fn snap_to_device_pixels(path: kurbo::BezPath, device_pixel_ratio: f64) -> kurbo::BezPath {
+    let dpr = device_pixel_ratio.max(f64::EPSILON);
+    let snap = |v: f64| ((v * dpr - 0.5).round() + 0.5) / dpr;
+    let snap_point = |p: kurbo::Point| kurbo::Point::new(snap(p.x), snap(p.y));
+    path.elements()
+        .iter()
+        .map(|el| match *el {
+            kurbo::PathEl::MoveTo(p) => kurbo::PathEl::MoveTo(snap_point(p)),
+            kurbo::PathEl::LineTo(p) => kurbo::PathEl::LineTo(snap_point(p)),
+            kurbo::PathEl::QuadTo(p1, p2) => kurbo::PathEl::QuadTo(snap_point(p1), snap_point(p2)),
+            kurbo::PathEl::CurveTo(p1, p2, p3) => {
+                kurbo::PathEl::CurveTo(snap_point(p1), snap_point(p2), snap_point(p3))
+            }
+            kurbo::PathEl::ClosePath => kurbo::PathEl::ClosePath,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snaps_to_physical_pixel_centers() {
+        let mut path = kurbo::BezPath::new();
+        path.move_to((10.0, 3.1));
+        path.line_to((20.2, 3.1));
+        let points = |path: kurbo::BezPath| {
+            path.elements()
+                .iter()
+                .filter_map(|el| match *el {
+                    kurbo::PathEl::MoveTo(p) | kurbo::PathEl::LineTo(p) => Some((p.x, p.y)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            points(snap_to_device_pixels(path.clone(), 1.0)),
+            vec![(10.5, 3.5), (20.5, 3.5)]
+        );
+        assert_eq!(
+            points(snap_to_device_pixels(path, 2.0)),
+            vec![(10.25, 3.25), (20.25, 3.25)]
+        );
+    }
+}
@@ -138,6 +138,9 @@ impl InstanceNode for RepeatInstance {
         let last_length = Rc::new(RefCell::new(0));
         let last_elem_sym = Rc::new(RefCell::new(None));
         let last_i_sym = Rc::new(RefCell::new(None));
+        // the children are kept here rather than read back from the property being
+        // evaluated, which would be a property cycle
+        let last_children = Rc::new(RefCell::new(Vec::new()));
 
         expanded_node
             .children
@@ -160,7 +163,7 @@ impl InstanceNode for RepeatInstance {
                         && i_symbol.read(|i| i == &*borrow!(last_i_sym))
                         && elem_symbol.read(|e| e == &*borrow!(last_elem_sym))
                     {
-                        return cloned_expanded_node.children.get();
+                        return borrow!(last_children).clone();
                     }
                     *borrow_mut!(last_length) = source_len;
                     *borrow_mut!(last_i_sym) = i_symbol.get();
@@ -216,6 +219,7 @@ impl InstanceNode for RepeatInstance {
                         &cloned_context,
                         &cloned_expanded_node.parent_frame,
                     );
+                    *borrow_mut!(last_children) = ret.clone();
                     ret
                 },
                 &deps,
@@ -0,0 +1,401 @@
+//! # SVG export
+//!
+//! Vector export of a rendered frame, see [`PaxEngine::serialize_frame_as_svg`]. Canvas content is
+//! recorded by [`SvgRenderContext`], which writes every fill and stroke as a `<path>` in window
+//! coordinates, one `<g>` per occlusion layer. Native elements are placed above the canvas content
+//! of their layer, as the chassis does, each serialized by [`InstanceNode::svg_element`] or, by
+//! default, as an empty `<foreignObject>` covering its bounds.
+//!
+//! Two things can't be exported: images, as the engine doesn't have access to the data of images
+//! loaded by the chassis, and gradients given relative to the shape they fill (piet's
+//! `LinearGradient` and `RadialGradient`), as piet doesn't expose their parameters. The latter are
+//! exported as a grey fill.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::rc::Rc;
+
+use kurbo::{Affine, BezPath, Rect};
+use pax_runtime_api::{borrow, Layer};
+use piet::{Color, FixedGradient, GradientStop, PaintBrush};
+
+use crate::api::RenderContext;
+use crate::{ExpandedNode, PaxEngine};
+
+/// Fill of shapes using gradients whose parameters piet doesn't expose
+const UNKNOWN_PAINT: Color = Color::grey8(128);
+
+#[derive(Clone, Default)]
+struct SvgState {
+    transform: Affine,
+    /// Id of the `<clipPath>` clipping everything drawn, the intersection of all clips so far
+    clip: Option<String>,
+}
+
+#[derive(Default)]
+struct SvgLayer {
+    body: String,
+    state: SvgState,
+    saved: Vec<SvgState>,
+}
+
+/// A [`RenderContext`] recording what's drawn as SVG elements
+pub struct SvgRenderContext {
+    layers: BTreeMap<usize, SvgLayer>,
+    /// Names of the layers, as returned by `layers`
+    layer_names: Vec<String>,
+    defs: String,
+    next_def_id: usize,
+}
+
+impl SvgRenderContext {
+    pub fn new(layer_count: usize) -> Self {
+        let layer_count = layer_count.max(1);
+        SvgRenderContext {
+            layers: (0..layer_count).map(|i| (i, SvgLayer::default())).collect(),
+            layer_names: (0..layer_count).map(|i| i.to_string()).collect(),
+            defs: String::new(),
+            next_def_id: 0,
+        }
+    }
+
+    fn layer(&mut self, layer: &str) -> Option<&mut SvgLayer> {
+        self.layers.get_mut(&layer.parse().ok()?)
+    }
+
+    fn next_id(&mut self, prefix: &str) -> String {
+        self.next_def_id += 1;
+        format!("{}{}", prefix, self.next_def_id)
+    }
+
+    /// Writes `element` to `layer`, clipped by the layer's current clip
+    fn push_element(&mut self, layer: &str, element: &str) {
+        let Some(layer) = self.layer(layer) else {
+            return;
+        };
+        match &layer.state.clip {
+            Some(clip) => {
+                let _ = writeln!(
+                    layer.body,
+                    r#"<g clip-path="url(#{})">{}</g>"#,
+                    clip, element
+                );
+            }
+            None => {
+                let _ = writeln!(layer.body, "{}", element);
+            }
+        }
+    }
+
+    /// `attribute` (`fill` or `stroke`) and its opacity painted with `brush`, adding the gradient
+    /// it refers to to the defs if any.  Gradients are given in the coordinates `transform` maps
+    /// to the window
+    fn paint(&mut self, attribute: &str, brush: &PaintBrush, transform: Affine) -> String {
+        let gradient = match brush {
+            PaintBrush::Color(color) => return paint_attributes(attribute, *color),
+            PaintBrush::Fixed(gradient) => gradient,
+            PaintBrush::Linear(_) | PaintBrush::Radial(_) => {
+                return paint_attributes(attribute, UNKNOWN_PAINT)
+            }
+        };
+        let id = self.next_id("gradient");
+        let (element, stops) = match gradient {
+            FixedGradient::Linear(linear) => (
+                format!(
+                    r#"<linearGradient id="{}" gradientUnits="userSpaceOnUse" gradientTransform="{}" x1="{}" y1="{}" x2="{}" y2="{}">"#,
+                    id,
+                    svg_matrix(transform),
+                    linear.start.x,
+                    linear.start.y,
+                    linear.end.x,
+                    linear.end.y
+                ),
+                &linear.stops,
+            ),
+            FixedGradient::Radial(radial) => {
+                let focus = radial.center + radial.origin_offset;
+                (
+                    format!(
+                        r#"<radialGradient id="{}" gradientUnits="userSpaceOnUse" gradientTransform="{}" cx="{}" cy="{}" r="{}" fx="{}" fy="{}">"#,
+                        id,
+                        svg_matrix(transform),
+                        radial.center.x,
+                        radial.center.y,
+                        radial.radius,
+                        focus.x,
+                        focus.y
+                    ),
+                    &radial.stops,
+                )
+            }
+        };
+        let _ = write!(self.defs, "{}", element);
+        for GradientStop { pos, color } in stops {
+            let (r, g, b, a) = color.as_rgba8();
+            let _ = write!(
+                self.defs,
+                r#"<stop offset="{}" stop-color="rgb({},{},{})" stop-opacity="{}"/>"#,
+                pos,
+                r,
+                g,
+                b,
+                a as f64 / 255.0
+            );
+        }
+        let closing = match gradient {
+            FixedGradient::Linear(_) => "</linearGradient>",
+            FixedGradient::Radial(_) => "</radialGradient>",
+        };
+        let _ = writeln!(self.defs, "{}", closing);
+        format!(r#"{}="url(#{})""#, attribute, id)
+    }
+
+    /// The SVG document, `width` by `height` pixels, with the `native` elements placed above the
+    /// canvas content of their layer
+    pub fn finish(self, (width, height): (f64, f64), native: &BTreeMap<usize, String>) -> String {
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = width,
+            h = height
+        );
+        svg.push('\n');
+        if !self.defs.is_empty() {
+            let _ = write!(svg, "<defs>\n{}</defs>\n", self.defs);
+        }
+        for (id, layer) in &self.layers {
+            let _ = write!(
+                svg,
+                "<g data-pax-layer=\"{}\">\n{}{}</g>\n",
+                id,
+                layer.body,
+                native.get(id).map(String::as_str).unwrap_or_default()
+            );
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+impl RenderContext for SvgRenderContext {
+    fn fill(&mut self, layer: &str, path: BezPath, brush: &PaintBrush) {
+        let Some(transform) = self.layer(layer).map(|l| l.state.transform) else {
+            return;
+        };
+        let path = transform * path;
+        let paint = self.paint("fill", brush, transform);
+        let element = format!(r#"<path d="{}" {}/>"#, path.to_svg(), paint);
+        self.push_element(layer, &element);
+    }
+
+    fn stroke(&mut self, layer: &str, path: BezPath, brush: &PaintBrush, width: f64) {
+        let Some(transform) = self.layer(layer).map(|l| l.state.transform) else {
+            return;
+        };
+        let path = transform * path;
+        let paint = self.paint("stroke", brush, transform);
+        // stroke width scales with the transform, like on the canvas
+        let width = width * transform.as_coeffs()[0].hypot(transform.as_coeffs()[1]);
+        let element = format!(
+            r#"<path d="{}" fill="none" {} stroke-width="{}"/>"#,
+            path.to_svg(),
+            paint,
+            width
+        );
+        self.push_element(layer, &element);
+    }
+
+    fn save(&mut self, layer: &str) {
+        if let Some(layer) = self.layer(layer) {
+            layer.saved.push(layer.state.clone());
+        }
+    }
+
+    fn restore(&mut self, layer: &str) {
+        if let Some(layer) = self.layer(layer) {
+            if let Some(state) = layer.saved.pop() {
+                layer.state = state;
+            }
+        }
+    }
+
+    fn clip(&mut self, layer: &str, path: BezPath) {
+        let Some(state) = self.layer(layer).map(|l| l.state.clone()) else {
+            return;
+        };
+        let id = self.next_id("clip");
+        // a clip path clipped by the current one is the intersection of both
+        let parent = state
+            .clip
+            .map(|clip| format!(r#" clip-path="url(#{})""#, clip))
+            .unwrap_or_default();
+        let _ = writeln!(
+            self.defs,
+            r#"<clipPath id="{}"{}><path d="{}"/></clipPath>"#,
+            id,
+            parent,
+            (state.transform * path).to_svg()
+        );
+        if let Some(layer) = self.layer(layer) {
+            layer.state.clip = Some(id);
+        }
+    }
+
+    fn load_image(&mut self, _path: &str, _image: &[u8], _width: usize, _height: usize) {}
+
+    fn draw_image(&mut self, _layer: &str, _image_path: &str, _rect: Rect) {}
+
+    fn get_image_size(&mut self, _image_path: &str) -> Option<(usize, usize)> {
+        None
+    }
+
+    fn transform(&mut self, layer: &str, affine: Affine) {
+        if let Some(layer) = self.layer(layer) {
+            layer.state.transform *= affine;
+        }
+    }
+
+    fn layers(&self) -> Vec<&str> {
+        self.layer_names.iter().map(String::as_str).collect()
+    }
+}
+
+/// `attribute` (e.g. `fill`) set to the opaque part of `color`, and its opacity if not opaque
+pub fn paint_attributes(attribute: &str, color: Color) -> String {
+    let (r, g, b, a) = color.as_rgba8();
+    let mut attributes = format!(r#"{}="rgb({},{},{})""#, attribute, r, g, b);
+    if a != 255 {
+        let _ = write!(
+            attributes,
+            r#" {}-opacity="{}""#,
+            attribute,
+            a as f64 / 255.0
+        );
+    }
+    attributes
+}
+
+/// `transform` attribute value for `affine`
+pub fn svg_matrix(affine: Affine) -> String {
+    let [a, b, c, d, e, f] = affine.as_coeffs();
+    format!("matrix({} {} {} {} {} {})", a, b, c, d, e, f)
+}
+
+/// `text` escaped for use in XML content and attribute values
+pub fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Default SVG representation of a native element: an empty `<foreignObject>` covering its bounds
+pub fn foreign_object(expanded_node: &ExpandedNode) -> String {
+    let tab = expanded_node.transform_and_bounds.get();
+    format!(
+        r#"<foreignObject data-pax-node="{}" width="{}" height="{}" transform="{}"/>"#,
+        expanded_node.id.to_u32(),
+        tab.bounds.0,
+        tab.bounds.1,
+        svg_matrix(tab.transform.into())
+    )
+}
+
+/// The SVG elements of the native elements under `node`, per occlusion layer, in z-order
+fn native_elements(node: &Rc<ExpandedNode>) -> BTreeMap<usize, String> {
+    fn collect(node: &Rc<ExpandedNode>, natives: &mut Vec<(u32, i32, String)>) {
+        let instance_node = borrow!(node.instance_node);
+        if instance_node.base().flags().layer == Layer::Native {
+            let occlusion = node.occlusion.get();
+            let element = instance_node
+                .svg_element(node)
+                .unwrap_or_else(|| foreign_object(node));
+            natives.push((occlusion.occlusion_layer_id, occlusion.z_index, element));
+        }
+        drop(instance_node);
+        for child in node.children.get().iter() {
+            collect(child, natives);
+        }
+    }
+
+    let mut natives = vec![];
+    collect(node, &mut natives);
+    natives.sort_by_key(|(layer, z_index, _)| (*layer, *z_index));
+    let mut layers = BTreeMap::<usize, String>::new();
+    for (layer, _, element) in natives {
+        let body = layers.entry(layer as usize).or_default();
+        body.push_str(&element);
+        body.push('\n');
+    }
+    layers
+}
+
+impl PaxEngine {
+    /// Ticks the engine and serializes the resulting frame as an SVG document the size of the
+    /// viewport, see the [`svg_export`](crate::svg_export) module.  Native messages produced by
+    /// the tick are queued again, to be handed to the chassis by the next `tick`.
+    pub fn serialize_frame_as_svg(&mut self) -> String {
+        let native_messages = self.tick();
+        for message in native_messages {
+            self.runtime_context.enqueue_native_message(message);
+        }
+
+        let mut rcs = SvgRenderContext::new(self.runtime_context.layer_count.get());
+        self.render(&mut rcs);
+        let natives = native_elements(&self.root_expanded_node);
+        let viewport = self.runtime_context.globals().viewport.get().bounds;
+        rcs.finish(viewport, &natives)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(size: f64) -> BezPath {
+        kurbo::Shape::into_path(Rect::new(0.0, 0.0, size, size), 0.1)
+    }
+
+    #[test]
+    fn test_fills_are_transformed_and_clipped() {
+        let mut rcs = SvgRenderContext::new(1);
+        rcs.save("0");
+        rcs.transform("0", Affine::translate((10.0, 20.0)));
+        rcs.clip("0", square(5.0));
+        rcs.fill("0", square(2.0), &Color::rgba8(255, 0, 0, 128).into());
+        rcs.restore("0");
+        rcs.fill("0", square(2.0), &Color::BLACK.into());
+
+        let svg = rcs.finish((100.0, 50.0), &BTreeMap::new());
+        assert!(svg.contains(r#"viewBox="0 0 100 50""#));
+        assert!(svg.contains(
+            r#"<clipPath id="clip1"><path d="M10,20 L15,20 L15,25 L10,25 Z"/></clipPath>"#
+        ));
+        assert!(svg.contains(
+            r#"<g clip-path="url(#clip1)"><path d="M10,20 L12,20 L12,22 L10,22 Z" fill="rgb(255,0,0)" fill-opacity="0.5019607843137255"/></g>"#
+        ));
+        assert!(svg.contains(r#"<path d="M0,0 L2,0 L2,2 L0,2 Z" fill="rgb(0,0,0)"/>"#));
+    }
+
+    #[test]
+    fn test_native_elements_are_placed_above_their_layer() {
+        let mut rcs = SvgRenderContext::new(2);
+        rcs.fill("0", square(1.0), &Color::BLACK.into());
+        rcs.fill("1", square(1.0), &Color::WHITE.into());
+        let natives = BTreeMap::from([(0, "<text>a &amp; b</text>\n".to_string())]);
+
+        let svg = rcs.finish((10.0, 10.0), &natives);
+        let black = svg.find("rgb(0,0,0)").unwrap();
+        let text = svg.find("<text>").unwrap();
+        let white = svg.find("rgb(255,255,255)").unwrap();
+        assert!(black < text && text < white);
+        assert_eq!(escape_xml("a & <b>"), "a &amp; &lt;b&gt;");
+    }
+}
@@ -0,0 +1,111 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use pax_message::{NativeMessage, TextPatch};
+use pax_runtime::message_chunks::{MessageChunks, DEFAULT_MESSAGE_CHUNK_BYTES};
+
+/// Tracks the bytes allocated, and the peak since the last reset
+struct PeakAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+        PEAK.fetch_max(allocated, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: PeakAllocator = PeakAllocator;
+
+/// Held by each test, so that the counters aren't thrown off by tests running in parallel
+static MEASURING: Mutex<()> = Mutex::new(());
+
+const FRAME_BYTES: usize = 10 * 1024 * 1024;
+
+#[test]
+fn test_large_frame_is_transferred_with_bounded_peak_memory() {
+    let _measuring = MEASURING.lock().unwrap();
+    let content = "x".repeat(1000);
+    let messages: Vec<NativeMessage> = (0..(FRAME_BYTES / content.len()) as u32)
+        .map(|id| {
+            NativeMessage::TextUpdate(TextPatch {
+                id,
+                content: Some(content.clone()),
+                size_x: Some(100.0),
+                size_y: Some(20.0),
+                ..Default::default()
+            })
+        })
+        .collect();
+    let message_count = messages.len();
+
+    let mut chunks = MessageChunks::default();
+    chunks.push_tick(messages);
+
+    // the messages are already allocated, the transfer should only add about one chunk at a time
+    let baseline = ALLOCATED.load(Ordering::SeqCst);
+    PEAK.store(baseline, Ordering::SeqCst);
+    let mut transferred = 0;
+    let mut total_bytes = 0;
+    while let Some(chunk) = chunks.next_chunk() {
+        assert!(chunk.len() <= DEFAULT_MESSAGE_CHUNK_BYTES);
+        transferred += chunk.windows(12).filter(|w| w == b"\"TextUpdate\"").count();
+        total_bytes += chunk.len();
+    }
+    let peak = PEAK.load(Ordering::SeqCst) - baseline;
+
+    assert_eq!(transferred, message_count);
+    assert!(total_bytes > FRAME_BYTES);
+    assert!(
+        peak <= 2 * DEFAULT_MESSAGE_CHUNK_BYTES,
+        "transferring a {} byte frame peaked at {} extra bytes",
+        total_bytes,
+        peak
+    );
+    assert!(chunks.stats().last_frame_chunks >= FRAME_BYTES / DEFAULT_MESSAGE_CHUNK_BYTES);
+}
+
+#[test]
+fn test_slow_consumer_backlog_stays_bounded() {
+    let _measuring = MEASURING.lock().unwrap();
+    let mut chunks = MessageChunks::default();
+    let tick = |frame: usize| -> Vec<NativeMessage> {
+        (0..1000)
+            .map(|id| {
+                NativeMessage::TextUpdate(TextPatch {
+                    id,
+                    content: Some(format!("frame {}", frame)),
+                    ..Default::default()
+                })
+            })
+            .collect()
+    };
+    chunks.push_tick(tick(0));
+
+    // the chassis never consumes the first frame, while every element changes on each tick
+    chunks.push_tick(tick(1));
+    let after_one_tick = ALLOCATED.load(Ordering::SeqCst);
+    for frame in 2..200 {
+        chunks.push_tick(tick(frame));
+    }
+    let after_many_ticks = ALLOCATED.load(Ordering::SeqCst);
+
+    assert!(
+        after_many_ticks <= 2 * after_one_tick,
+        "backlog grew from {} to {} bytes",
+        after_one_tick,
+        after_many_ticks
+    );
+    assert_eq!(chunks.stats().coalesced_ticks, 199);
+    assert_eq!(chunks.stats().coalesced_drops, 198 * 1000);
+}
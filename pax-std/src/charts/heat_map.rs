@@ -0,0 +1,253 @@
+#[allow(unused)]
+use crate::*;
+use pax_engine::api::*;
+use pax_engine::math::Point2;
+use pax_engine::*;
+
+/// Number of frames a cell takes to ease to its new color when `data` changes
+const CELL_TRANSITION_FRAMES: u64 = 20;
+
+/// Visualizes a grid of values as colored cells. `data` is indexed by row, then
+/// column, and each value is colored by linearly interpolating through
+/// `color_scale`, from the smallest value in `data` to the largest. Hovering a
+/// cell shows its value.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[custom(Default)]
+#[inlined(
+    <Group @mouse_move=self.mouse_move @mouse_out=self.mouse_out>
+        if self._tooltip_visible {
+            <Group x={(self._tooltip_x)px} y={(self._tooltip_y - 6)px} anchor_x=50% anchor_y=100% width=80px height=24px _raycastable=false>
+                <Text x=5px width={100% - 10px} height=100% id=tooltip_text text={self._tooltip_text}/>
+                <Rectangle corner_radii={RectangleCornerRadii::radii(5.00, 5.00, 5.00, 5.00)} fill=rgb(12.5%, 12.5%, 12.5%)/>
+            </Group>
+        }
+        for cell in self._cells {
+            <HeatMapCell
+                x={(cell.x)px}
+                y={(cell.y)px}
+                width={(cell.width)px}
+                height={(cell.height)px}
+                anchor_x=0%
+                anchor_y=0%
+                color={cell.color}
+            />
+        }
+        <Rectangle fill=TRANSPARENT/>
+    </Group>
+
+    @settings {
+        @mount: on_mount
+        #tooltip_text {
+            selectable: false,
+            style: {
+                font: {Font::Web(
+                    "ff-real-headline-pro",
+                    "https://use.typekit.net/ivu7epf.css",
+                    FontStyle::Normal,
+                    FontWeight::Light,
+                )},
+                font_size: 13px,
+                fill: WHITE,
+                align_vertical: TextAlignVertical::Center,
+                align_horizontal: TextAlignHorizontal::Center,
+            }
+        }
+    }
+)]
+pub struct HeatMap {
+    pub data: Property<Vec<Vec<f64>>>,
+    pub color_scale: Property<Vec<Color>>,
+    /// Space between neighboring cells, in pixels
+    pub cell_gap: Property<f64>,
+
+    // private
+    pub _cells: Property<Vec<HeatMapCellSpec>>,
+    pub _tooltip_visible: Property<bool>,
+    pub _tooltip_text: Property<String>,
+    pub _tooltip_x: Property<f64>,
+    pub _tooltip_y: Property<f64>,
+}
+
+impl Default for HeatMap {
+    fn default() -> Self {
+        Self {
+            data: Default::default(),
+            color_scale: Property::new(vec![
+                Color::rgb(49.into(), 54.into(), 149.into()),
+                Color::rgb(255.into(), 255.into(), 191.into()),
+                Color::rgb(165.into(), 0.into(), 38.into()),
+            ]),
+            cell_gap: Property::new(1.0),
+            _cells: Default::default(),
+            _tooltip_visible: Default::default(),
+            _tooltip_text: Default::default(),
+            _tooltip_x: Default::default(),
+            _tooltip_y: Default::default(),
+        }
+    }
+}
+
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct HeatMapCellSpec {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub color: Color,
+}
+
+impl HeatMap {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        let data = self.data.clone();
+        let color_scale = self.color_scale.clone();
+        let cell_gap = self.cell_gap.clone();
+        let bounds = ctx.bounds_self.clone();
+        let deps = [
+            data.untyped(),
+            color_scale.untyped(),
+            cell_gap.untyped(),
+            bounds.untyped(),
+        ];
+        self._cells.replace_with(Property::computed(
+            move || {
+                let data = data.get();
+                let color_scale = color_scale.get();
+                let gap = cell_gap.get().max(0.0);
+                let Some((cell_width, cell_height)) = cell_size(&data, bounds.get()) else {
+                    return vec![];
+                };
+                let (min, max) = value_range(&data);
+                let mut cells = vec![];
+                for (row, values) in data.iter().enumerate() {
+                    for (column, &value) in values.iter().enumerate() {
+                        let t = if max > min {
+                            (value - min) / (max - min)
+                        } else {
+                            0.0
+                        };
+                        cells.push(HeatMapCellSpec {
+                            x: column as f64 * cell_width + gap / 2.0,
+                            y: row as f64 * cell_height + gap / 2.0,
+                            width: (cell_width - gap).max(0.0),
+                            height: (cell_height - gap).max(0.0),
+                            color: color_at(&color_scale, t),
+                        });
+                    }
+                }
+                cells
+            },
+            &deps,
+        ));
+    }
+
+    pub fn mouse_move(&mut self, ctx: &NodeContext, event: Event<MouseMove>) {
+        let point = ctx.local_point(Point2::new(event.mouse.x, event.mouse.y));
+        let data = self.data.get();
+        let hovered = cell_size(&data, ctx.bounds_self.get()).and_then(|(width, height)| {
+            if point.x < 0.0 || point.y < 0.0 {
+                return None;
+            }
+            let row = (point.y / height) as usize;
+            let column = (point.x / width) as usize;
+            let value = *data.get(row)?.get(column)?;
+            Some((row, column, value, width, height))
+        });
+        match hovered {
+            Some((row, column, value, width, height)) => {
+                self._tooltip_text.set(format_value(value));
+                self._tooltip_x.set((column as f64 + 0.5) * width);
+                self._tooltip_y.set(row as f64 * height);
+                self._tooltip_visible.set(true);
+            }
+            None => self._tooltip_visible.set(false),
+        }
+    }
+
+    pub fn mouse_out(&mut self, _ctx: &NodeContext, _event: Event<MouseOut>) {
+        self._tooltip_visible.set(false);
+    }
+}
+
+/// A single heat map cell, easing to its new color whenever `color` changes
+#[pax]
+#[engine_import_path("pax_engine")]
+#[inlined(
+    <Rectangle fill={self._fill}/>
+
+    @settings {
+        @mount: on_mount
+        @pre_render: pre_render
+    }
+)]
+pub struct HeatMapCell {
+    pub color: Property<Color>,
+
+    // private
+    pub _fill: Property<Color>,
+    pub _on_color_change: Property<bool>,
+}
+
+impl HeatMapCell {
+    pub fn on_mount(&mut self, _ctx: &NodeContext) {
+        self._fill.set(self.color.get());
+        let color = self.color.clone();
+        let fill = self._fill.clone();
+        let deps = [color.untyped()];
+        self._on_color_change.replace_with(Property::computed(
+            move || {
+                fill.ease_to(color.get(), CELL_TRANSITION_FRAMES, EasingCurve::OutQuad);
+                false
+            },
+            &deps,
+        ));
+    }
+
+    pub fn pre_render(&mut self, _ctx: &NodeContext) {
+        // fire lazy prop if dirty every tick
+        self._on_color_change.get();
+    }
+}
+
+/// Size of each cell if the grid of data is spread over bounds,
+/// `None` if there is no data
+fn cell_size(data: &[Vec<f64>], bounds: (f64, f64)) -> Option<(f64, f64)> {
+    let rows = data.len();
+    let columns = data.iter().map(Vec::len).max().unwrap_or_default();
+    if rows == 0 || columns == 0 {
+        return None;
+    }
+    Some((bounds.0 / columns as f64, bounds.1 / rows as f64))
+}
+
+/// Smallest and largest finite value in data
+fn value_range(data: &[Vec<f64>]) -> (f64, f64) {
+    data.iter()
+        .flatten()
+        .filter(|v| v.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &v| {
+            (min.min(v), max.max(v))
+        })
+}
+
+/// Color at t (0.0 to 1.0) of the gradient through the colors of scale
+fn color_at(scale: &[Color], t: f64) -> Color {
+    match scale {
+        [] => Color::TRANSPARENT,
+        [color] => color.clone(),
+        _ => {
+            let position = t.clamp(0.0, 1.0) * (scale.len() - 1) as f64;
+            let index = (position.floor() as usize).min(scale.len() - 2);
+            scale[index].interpolate(&scale[index + 1], position - index as f64)
+        }
+    }
+}
+
+fn format_value(value: f64) -> String {
+    let formatted = format!("{:.3}", value);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
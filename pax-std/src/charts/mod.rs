@@ -0,0 +1,5 @@
+pub mod heat_map;
+pub mod spark_line;
+
+pub use heat_map::*;
+pub use spark_line::*;
@@ -0,0 +1,147 @@
+#[allow(unused)]
+use crate::*;
+use pax_engine::api::*;
+use pax_engine::*;
+
+/// A minimal line chart without axes or labels, for showing a trend inline
+/// (in table cells, tooltips, stat cards). The line is scaled to span the
+/// bounds of the node, from the smallest value in `data` at the bottom to the
+/// largest at the top. If `fill_opacity` is above zero, the area under the
+/// line is filled with `color` at that opacity.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[custom(Default)]
+#[inlined(
+    <Path elements={self._line} stroke={self._stroke} fill=TRANSPARENT/>
+    <Path elements={self._area} fill={self._area_fill}/>
+
+    @settings {
+        @mount: on_mount
+    }
+)]
+pub struct SparkLine {
+    pub data: Property<Vec<f64>>,
+    pub color: Property<Color>,
+    /// Opacity (0.0 to 1.0) of the area under the line, 0.0 for no fill
+    pub fill_opacity: Property<f64>,
+    /// Width of the line, in pixels
+    pub stroke_width: Property<f64>,
+
+    // private
+    pub _line: Property<Vec<PathElement>>,
+    pub _area: Property<Vec<PathElement>>,
+    pub _stroke: Property<Stroke>,
+    pub _area_fill: Property<Color>,
+}
+
+impl Default for SparkLine {
+    fn default() -> Self {
+        Self {
+            data: Default::default(),
+            color: Property::new(Color::rgb(16.into(), 196.into(), 187.into())),
+            fill_opacity: Property::new(0.0),
+            stroke_width: Property::new(1.5),
+            _line: Default::default(),
+            _area: Default::default(),
+            _stroke: Default::default(),
+            _area_fill: Default::default(),
+        }
+    }
+}
+
+impl SparkLine {
+    pub fn on_mount(&mut self, _ctx: &NodeContext) {
+        let data = self.data.clone();
+        let deps = [data.untyped()];
+        self._line.replace_with(Property::computed(
+            move || {
+                let points = line_points(&data.get());
+                let mut elements = vec![];
+                for (i, &(x, y)) in points.iter().enumerate() {
+                    if i > 0 {
+                        elements.push(PathElement::Line);
+                    }
+                    elements.push(PathElement::Point(x, y));
+                }
+                elements
+            },
+            &deps,
+        ));
+
+        let data = self.data.clone();
+        let fill_opacity = self.fill_opacity.clone();
+        let deps = [data.untyped(), fill_opacity.untyped()];
+        self._area.replace_with(Property::computed(
+            move || {
+                let points = line_points(&data.get());
+                if fill_opacity.get() <= 0.0 || points.len() < 2 {
+                    return vec![];
+                }
+                let bottom = Size::Percent(100.into());
+                let (first_x, _) = points[0];
+                let (last_x, _) = points[points.len() - 1];
+                let mut elements = vec![PathElement::Point(first_x, bottom)];
+                for &(x, y) in &points {
+                    elements.push(PathElement::Line);
+                    elements.push(PathElement::Point(x, y));
+                }
+                elements.push(PathElement::Line);
+                elements.push(PathElement::Point(last_x, bottom));
+                elements.push(PathElement::Close);
+                elements
+            },
+            &deps,
+        ));
+
+        let color = self.color.clone();
+        let stroke_width = self.stroke_width.clone();
+        let deps = [color.untyped(), stroke_width.untyped()];
+        self._stroke.replace_with(Property::computed(
+            move || Stroke {
+                color: Property::new(color.get()),
+                width: Property::new(Size::Pixels(stroke_width.get().max(0.0).into())),
+                ..Default::default()
+            },
+            &deps,
+        ));
+
+        let color = self.color.clone();
+        let fill_opacity = self.fill_opacity.clone();
+        let deps = [color.untyped(), fill_opacity.untyped()];
+        self._area_fill.replace_with(Property::computed(
+            move || {
+                let [r, g, b, a] = color.get().to_rgba_0_1();
+                Color::from_rgba_0_1([r, g, b, a * fill_opacity.get().clamp(0.0, 1.0)])
+            },
+            &deps,
+        ));
+    }
+}
+
+/// Points of the line through data, as percentages of the bounds so that
+/// the line scales with the node. Non-finite values are skipped, and a flat
+/// series is drawn across the vertical center.
+fn line_points(data: &[f64]) -> Vec<(Size, Size)> {
+    let (min, max) = data
+        .iter()
+        .filter(|v| v.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &v| {
+            (min.min(v), max.max(v))
+        });
+    let last_index = data.len().saturating_sub(1).max(1) as f64;
+    data.iter()
+        .enumerate()
+        .filter(|(_, v)| v.is_finite())
+        .map(|(i, &v)| {
+            let t = if max > min {
+                (v - min) / (max - min)
+            } else {
+                0.5
+            };
+            (
+                Size::Percent((i as f64 / last_index * 100.0).into()),
+                Size::Percent(((1.0 - t) * 100.0).into()),
+            )
+        })
+        .collect()
+}
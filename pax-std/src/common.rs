@@ -1,5 +1,8 @@
 pub use pax_engine::api::Size;
+use pax_engine::api::{properties::UntypedProperty, Property};
 use pax_engine::*;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
 
 #[pax]
 #[engine_import_path("pax_engine")]
@@ -34,3 +37,77 @@ pub fn patch_if_needed<T: PartialEq + Clone>(
         false
     }
 }
+
+/// Builds the `native_message_listener` of a native control out of one listener per
+/// patch field, so that a property change only re-reads and diffs the fields that
+/// depend on it, instead of every property of the control.  Changed fields are
+/// collected into a shared patch, sent once per frame when the listener is read.
+pub struct PatchAccumulator<P> {
+    empty: P,
+    last_sent: Rc<RefCell<P>>,
+    pending: Rc<RefCell<P>>,
+    changed: Rc<Cell<bool>>,
+    fields: Vec<Property<()>>,
+}
+
+impl<P: Clone + 'static> PatchAccumulator<P> {
+    /// `empty` is the patch with only its id set, that fields are accumulated into
+    pub fn new(empty: P) -> Self {
+        Self {
+            last_sent: Rc::new(RefCell::new(empty.clone())),
+            pending: Rc::new(RefCell::new(empty.clone())),
+            empty,
+            changed: Default::default(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Adds a patch field, set to the result of `value` whenever it differs from
+    /// the last value sent.  `value` is only re-evaluated when one of `deps` changes
+    pub fn field<T: PartialEq + Clone + 'static>(
+        mut self,
+        deps: &[UntypedProperty],
+        value: impl Fn() -> T + 'static,
+        field: impl Fn(&mut P) -> &mut Option<T> + 'static,
+    ) -> Self {
+        let last_sent = Rc::clone(&self.last_sent);
+        let pending = Rc::clone(&self.pending);
+        let changed = Rc::clone(&self.changed);
+        self.fields.push(Property::computed(
+            move || {
+                let new_value = value();
+                let mut last_sent = last_sent.borrow_mut();
+                let mut pending = pending.borrow_mut();
+                if patch_if_needed(field(&mut last_sent), field(&mut pending), new_value) {
+                    changed.set(true);
+                }
+            },
+            deps,
+        ));
+        self
+    }
+
+    /// The listener to set as `native_message_listener`, calling `send` with the
+    /// accumulated patch if any field changed
+    pub fn build(self, send: impl Fn(P) + 'static) -> Property<()> {
+        let deps: Vec<_> = self.fields.iter().map(|f| f.untyped()).collect();
+        let Self {
+            empty,
+            pending,
+            changed,
+            fields,
+            ..
+        } = self;
+        Property::computed(
+            move || {
+                for field in &fields {
+                    field.get();
+                }
+                if changed.replace(false) {
+                    send(pending.replace(empty.clone()));
+                }
+            },
+            &deps,
+        )
+    }
+}
@@ -1,15 +1,23 @@
-use pax_engine::pax;
-use pax_runtime::{BaseInstance, InstanceFlags, InstanceNode, InstantiationArgs};
+use kurbo::Shape;
+use pax_engine::{pax, Property};
+use pax_runtime::{
+    BaseInstance, ExpandedNode, InstanceFlags, InstanceNode, InstantiationArgs, RuntimeContext,
+};
+use piet::{LinearGradient, RadialGradient};
 use std::rc::Rc;
 
-use pax_runtime::api::Layer;
+use pax_runtime::api::{Fill, Layer, RenderContext};
 
 /// Gathers a set of children underneath a single render node:
 /// useful for composing transforms and simplifying render trees.
+/// If `fill` is set, a background matching the group's bounds
+/// is drawn beneath its children.
 #[pax]
 #[engine_import_path("pax_engine")]
 #[primitive("pax_std::core::group::GroupInstance")]
-pub struct Group {}
+pub struct Group {
+    pub fill: Property<Option<Fill>>,
+}
 
 pub struct GroupInstance {
     base: BaseInstance,
@@ -33,14 +41,68 @@ impl InstanceNode for GroupInstance {
         })
     }
 
+    // the background is drawn before the children render (in render
+    // it would be drawn on top of them)
+    fn handle_pre_render(
+        &self,
+        expanded_node: &ExpandedNode,
+        _context: &Rc<RuntimeContext>,
+        rc: &mut dyn RenderContext,
+    ) {
+        let Some(fill) = expanded_node.with_properties_unwrapped(|g: &mut Group| g.fill.get())
+        else {
+            return;
+        };
+        let tab = expanded_node.transform_and_bounds.get();
+        let (width, height) = tab.bounds;
+        let layer_id = format!("{}", expanded_node.occlusion.get().occlusion_layer_id);
+
+        let bez_path = kurbo::Rect::new(0.0, 0.0, width, height).to_path(0.1);
+        let transformed_bez_path = Into::<kurbo::Affine>::into(tab.transform) * bez_path;
+
+        match fill {
+            Fill::Solid(color) => {
+                rc.fill(
+                    &layer_id,
+                    transformed_bez_path,
+                    &color.to_piet_color().into(),
+                );
+            }
+            Fill::LinearGradient(linear) => {
+                let linear_gradient = LinearGradient::new(
+                    Fill::to_unit_point(linear.start, (width, height)),
+                    Fill::to_unit_point(linear.end, (width, height)),
+                    Fill::to_piet_gradient_stops(linear.stops.clone()),
+                );
+                rc.fill(&layer_id, transformed_bez_path, &linear_gradient.into())
+            }
+            Fill::RadialGradient(radial) => {
+                let origin = Fill::to_unit_point(radial.start, (width, height));
+                let center = Fill::to_unit_point(radial.end, (width, height));
+                let gradient_stops = Fill::to_piet_gradient_stops(radial.stops.clone());
+                let radial_gradient = RadialGradient::new(radial.radius, gradient_stops)
+                    .with_center(center)
+                    .with_origin(origin);
+                rc.fill(&layer_id, transformed_bez_path, &radial_gradient.into());
+            }
+        }
+    }
+
+    fn draws_background(&self, expanded_node: &ExpandedNode) -> bool {
+        expanded_node.with_properties_unwrapped(|g: &mut Group| g.fill.get().is_some())
+    }
+
     fn resolve_debug(
         &self,
         f: &mut std::fmt::Formatter,
-        expanded_node: Option<&pax_runtime::ExpandedNode>,
+        expanded_node: Option<&ExpandedNode>,
     ) -> std::fmt::Result {
         match expanded_node {
-            Some(expanded_node) => expanded_node
-                .with_properties_unwrapped(|_g: &mut Group| f.debug_struct("Group").finish()),
+            Some(expanded_node) => expanded_node.with_properties_unwrapped(|g: &mut Group| {
+                f.debug_struct("Group")
+                    .field("fill", &g.fill.get())
+                    .finish()
+            }),
             None => f.debug_struct("Group").finish_non_exhaustive(),
         }
     }
@@ -72,8 +72,12 @@ impl InstanceNode for ImageInstance {
             ..Default::default()
         }));
 
-        let deps =
-            [expanded_node.with_properties_unwrapped(|props: &mut Image| props.source.untyped())];
+        // load higher resolution variants of the image on high-DPI screens
+        let dpr = context.globals().device_pixel_ratio;
+        let deps = [
+            expanded_node.with_properties_unwrapped(|props: &mut Image| props.source.untyped()),
+            dpr.untyped(),
+        ];
         let needs_to_load_data = Rc::clone(&self.needs_to_load_data);
         expanded_node
             .native_message_listener
@@ -93,10 +97,19 @@ impl InstanceNode for ImageInstance {
                         match source {
                             ImageSource::Empty => (),
                             ImageSource::Url(url) => {
-                                let update =
-                                    patch_if_needed(&mut old_state.path, &mut patch.path, url);
-
-                                if update {
+                                let scale = (dpr.get().ceil() as u32).clamp(1, 3);
+                                let path_changed = patch_if_needed(
+                                    &mut old_state.path,
+                                    &mut patch.path,
+                                    url.clone(),
+                                );
+                                let scale_changed =
+                                    patch_if_needed(&mut old_state.scale, &mut patch.scale, scale);
+
+                                if path_changed || scale_changed {
+                                    // the chassis needs both to resolve the asset variant
+                                    patch.path = Some(url);
+                                    patch.scale = Some(scale);
                                     context.enqueue_native_message(
                                         pax_message::NativeMessage::ImageLoad(patch),
                                     );
@@ -0,0 +1,184 @@
+#[allow(unused)]
+use crate::*;
+use pax_engine::api::*;
+use pax_engine::*;
+
+/// Approximate advance of a glyph in ems, used to size the text since its layout is native
+const AVERAGE_GLYPH_WIDTH: f64 = 0.6;
+
+/// Height of a line of text in ems
+const LINE_HEIGHT: f64 = 1.2;
+
+/// News-ticker style text, scrolling through the `Marquee` and starting over once it has
+/// scrolled out of view:
+///
+/// ```pax
+/// <Marquee width=100% height=30px text="Breaking news" speed_px_per_frame=2.0 pause_on_hover=true/>
+/// ```
+///
+/// `direction` is the direction the text travels in.  The size of the text is estimated from
+/// its length and `style.font_size`, as text is laid out natively.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[inlined(
+    <Frame>
+        <Text
+            text={self.text}
+            style={self.style}
+            selectable=false
+            width={(self._text_width)px}
+            height={(self._text_height)px}
+            transform={Transform2D::translate((self._x)px, (self._y)px)}
+        />
+    </Frame>
+    <Rectangle fill=TRANSPARENT/>
+    @settings {
+        @mount: on_mount
+        @pre_render: pre_render
+        @mouse_over: mouse_over
+        @mouse_out: mouse_out
+    }
+)]
+#[custom(Default)]
+pub struct Marquee {
+    pub text: Property<String>,
+    pub speed_px_per_frame: Property<f64>,
+    pub direction: Property<MarqueeDirection>,
+    pub style: Property<TextStyle>,
+    pub pause_on_hover: Property<bool>,
+    /// Distance the text has travelled since it last started over
+    pub _offset: Property<f64>,
+    pub _paused: Property<bool>,
+    pub _text_width: Property<f64>,
+    pub _text_height: Property<f64>,
+    pub _x: Property<f64>,
+    pub _y: Property<f64>,
+}
+
+impl Default for Marquee {
+    fn default() -> Self {
+        Self {
+            text: Property::new(String::new()),
+            speed_px_per_frame: Property::new(1.0),
+            direction: Property::new(MarqueeDirection::Left),
+            style: Property::new(TextStyle::default()),
+            pause_on_hover: Property::new(false),
+            _offset: Property::new(0.0),
+            _paused: Property::new(false),
+            _text_width: Property::new(0.0),
+            _text_height: Property::new(0.0),
+            _x: Property::new(0.0),
+            _y: Property::new(0.0),
+        }
+    }
+}
+
+#[pax]
+#[engine_import_path("pax_engine")]
+pub enum MarqueeDirection {
+    #[default]
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Marquee {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        let bounds = ctx.bounds_self.clone();
+        let text = self.text.clone();
+        let style = self.style.clone();
+        let direction = self.direction.clone();
+        let deps = [
+            bounds.untyped(),
+            text.untyped(),
+            style.untyped(),
+            direction.untyped(),
+        ];
+        let text_size = Property::computed(
+            move || {
+                let (width, height) = bounds.get();
+                let font_size = style.get().font_size.get().get_pixels(height);
+                text.read(|text| match direction.get() {
+                    // a single line as wide as the text, as high as the marquee
+                    MarqueeDirection::Left | MarqueeDirection::Right => {
+                        let chars = text.lines().map(|l| l.chars().count()).max();
+                        let chars = chars.unwrap_or_default() as f64;
+                        (chars * font_size * AVERAGE_GLYPH_WIDTH, height)
+                    }
+                    // as wide as the marquee, as high as its lines
+                    MarqueeDirection::Up | MarqueeDirection::Down => {
+                        let lines = text.lines().count().max(1) as f64;
+                        (width, lines * font_size * LINE_HEIGHT)
+                    }
+                })
+            },
+            &deps,
+        );
+
+        let size = text_size.clone();
+        let deps = [size.untyped()];
+        self._text_width
+            .replace_with(Property::computed(move || size.get().0, &deps));
+        let size = text_size.clone();
+        let deps = [size.untyped()];
+        self._text_height
+            .replace_with(Property::computed(move || size.get().1, &deps));
+
+        // the text enters on the side opposite to `direction`, right outside the marquee
+        let bounds = ctx.bounds_self.clone();
+        let offset = self._offset.clone();
+        let direction = self.direction.clone();
+        let deps = [
+            bounds.untyped(),
+            offset.untyped(),
+            direction.untyped(),
+            text_size.untyped(),
+        ];
+        let position = Property::computed(
+            move || {
+                let (width, height) = bounds.get();
+                let (text_width, text_height) = text_size.get();
+                let offset = offset.get();
+                match direction.get() {
+                    MarqueeDirection::Left => (width - offset, 0.0),
+                    MarqueeDirection::Right => (offset - text_width, 0.0),
+                    MarqueeDirection::Up => (0.0, height - offset),
+                    MarqueeDirection::Down => (0.0, offset - text_height),
+                }
+            },
+            &deps,
+        );
+        let pos = position.clone();
+        let deps = [pos.untyped()];
+        self._x
+            .replace_with(Property::computed(move || pos.get().0, &deps));
+        let deps = [position.untyped()];
+        self._y
+            .replace_with(Property::computed(move || position.get().1, &deps));
+    }
+
+    pub fn pre_render(&mut self, ctx: &NodeContext) {
+        if self._paused.get() {
+            return;
+        }
+        let (width, height) = ctx.bounds_self.get();
+        let travel = match self.direction.get() {
+            MarqueeDirection::Left | MarqueeDirection::Right => width + self._text_width.get(),
+            MarqueeDirection::Up | MarqueeDirection::Down => height + self._text_height.get(),
+        };
+        let offset = self._offset.get() + self.speed_px_per_frame.get().max(0.0);
+        // once out of view, the text starts over from the other side
+        self._offset.set(if offset > travel { 0.0 } else { offset });
+    }
+
+    pub fn mouse_over(&mut self, _ctx: &NodeContext, _event: Event<MouseOver>) {
+        if self.pause_on_hover.get() {
+            self._paused.set(true);
+        }
+    }
+
+    pub fn mouse_out(&mut self, _ctx: &NodeContext, _event: Event<MouseOut>) {
+        self._paused.set(false);
+    }
+}
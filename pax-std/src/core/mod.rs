@@ -4,11 +4,23 @@ pub mod frame;
 pub mod group;
 pub mod image;
 pub mod link;
+#[cfg(all(feature = "drawing", feature = "text"))]
+pub mod marquee;
 pub mod native_image;
+#[cfg(feature = "scroller")]
 pub mod scrollbar;
+#[cfg(feature = "scroller")]
 pub mod scroller;
+#[cfg(feature = "drawing")]
+pub mod skeleton;
+#[cfg(feature = "text")]
 pub mod text;
+pub mod timeline;
+#[cfg(all(feature = "drawing", feature = "text"))]
 pub mod tooltip;
+#[cfg(all(feature = "drawing", feature = "text"))]
+pub mod unresolved;
+pub mod video;
 
 //Only exposing inline_frame when designtime feature is enabled,
 //mostly as a safety measure to prevent it from being used in userland
@@ -22,8 +34,20 @@ pub use frame::*;
 pub use group::*;
 pub use image::*;
 pub use link::*;
+#[cfg(all(feature = "drawing", feature = "text"))]
+pub use marquee::*;
 pub use native_image::*;
+#[cfg(feature = "scroller")]
 pub use scrollbar::*;
+#[cfg(feature = "scroller")]
 pub use scroller::*;
+#[cfg(feature = "drawing")]
+pub use skeleton::*;
+#[cfg(feature = "text")]
 pub use text::*;
+pub use timeline::*;
+#[cfg(all(feature = "drawing", feature = "text"))]
 pub use tooltip::*;
+#[cfg(all(feature = "drawing", feature = "text"))]
+pub use unresolved::*;
+pub use video::*;
@@ -51,6 +51,9 @@ pub struct Scroller {
     pub scroll_pos_y: Property<f64>,
     pub scroll_width: Property<Size>,
     pub scroll_height: Property<Size>,
+    /// Whether wheel scrolling that reaches the extent of this scroller
+    /// carries on to the scrollers (and page) containing it
+    pub overscroll_behavior: Property<OverscrollBehavior>,
 
     // used by pax create (might want to just make public at some point)
     pub _clip_content: Property<bool>,
@@ -70,6 +73,7 @@ impl Default for Scroller {
             scroll_pos_y: Default::default(),
             scroll_width: Default::default(),
             scroll_height: Default::default(),
+            overscroll_behavior: Default::default(),
             _clip_content: Property::new(true),
             _platform_params: Default::default(),
             _momentum_x: Default::default(),
@@ -91,6 +95,19 @@ pub struct PlatformSpecificScrollParams {
     pub fling: bool,
 }
 
+/// Mirrors the CSS `overscroll-behavior` property
+#[pax]
+#[engine_import_path("pax_engine")]
+pub enum OverscrollBehavior {
+    /// Once this scroller can't scroll further, scrolling chains to its parent
+    #[default]
+    Auto,
+    /// Scrolling never chains past this scroller
+    Contain,
+    /// Same as `Contain`: the scroller has no overscroll effects of its own to disable
+    None,
+}
+
 pub struct TouchInfo {
     x: f64,
     y: f64,
@@ -221,7 +238,10 @@ impl Scroller {
         let delta_x = args.delta_x;
         let delta_y = args.delta_y;
         let (done_x, done_y) = self.moving_passed_bounds(ctx, delta_x, delta_y);
-        if !done_x || !done_y {
+        // scrollers further up the tree skip cancelled wheel events, so
+        // cancelling while scrolling at the extent stops the chaining
+        let chains = matches!(self.overscroll_behavior.get(), OverscrollBehavior::Auto);
+        if !done_x || !done_y || !chains {
             args.prevent_default();
         }
         self.add_position(ctx, args.delta_x, args.delta_y);
@@ -0,0 +1,274 @@
+use std::collections::HashSet;
+
+#[allow(unused)]
+use crate::*;
+use pax_engine::api::{Color, Property};
+use pax_engine::*;
+use pax_manifest::{
+    get_common_properties_as_property_definitions, ComponentTemplate, NodeLocation, PaxManifest,
+    PaxType, SettingElement, TemplateNodeDefinition, TemplateNodeId, Token, TreeIndexPosition,
+    TypeId, ValueDefinition,
+};
+use pax_runtime::api::{NodeContext, PaxValue};
+
+/// A placeholder shape shown in place of content that is still loading.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[inlined(
+    if self._is_circle {
+        <Ellipse fill={self.fill}/>
+    }
+    if !self._is_circle {
+        <Rectangle
+            y=50%
+            anchor_y=50%
+            height={(self._height_percent)%}
+            fill={self.fill}
+            corner_radii={RectangleCornerRadii::radii(self._radius, self._radius, self._radius, self._radius)}
+        />
+    }
+    @settings {
+        @mount: on_mount
+    }
+)]
+#[custom(Default)]
+pub struct Skeleton {
+    pub variant: Property<SkeletonVariant>,
+    pub fill: Property<Color>,
+    pub _is_circle: Property<bool>,
+    pub _height_percent: Property<f64>,
+    pub _radius: Property<f64>,
+}
+
+impl Default for Skeleton {
+    fn default() -> Self {
+        Self {
+            variant: Property::new(SkeletonVariant::Rectangle),
+            fill: Property::new(Color::rgb(229.into(), 231.into(), 235.into())),
+            _is_circle: Property::new(false),
+            _height_percent: Property::new(100.0),
+            _radius: Property::new(6.0),
+        }
+    }
+}
+
+#[pax]
+#[engine_import_path("pax_engine")]
+pub enum SkeletonVariant {
+    /// A thin rounded bar standing in for a line of text
+    Text,
+    #[default]
+    Rectangle,
+    Circle,
+}
+
+impl Skeleton {
+    pub fn on_mount(&mut self, _ctx: &NodeContext) {
+        let variant = self.variant.clone();
+        let deps = [variant.untyped()];
+        self._is_circle.replace_with(Property::computed(
+            move || matches!(variant.get(), SkeletonVariant::Circle),
+            &deps,
+        ));
+        let variant = self.variant.clone();
+        self._height_percent.replace_with(Property::computed(
+            move || match variant.get() {
+                SkeletonVariant::Text => 60.0,
+                _ => 100.0,
+            },
+            &deps,
+        ));
+        let variant = self.variant.clone();
+        self._radius.replace_with(Property::computed(
+            move || match variant.get() {
+                SkeletonVariant::Text => 4.0,
+                _ => 6.0,
+            },
+            &deps,
+        ));
+    }
+}
+
+const SKELETON_IMPORT_PATH: &str = "pax_std::core::skeleton::Skeleton";
+const GROUP_IMPORT_PATH: &str = "pax_std::core::group::Group";
+
+/// Primitives that contain other nodes and are kept as-is (with their literal settings) so that
+/// their children are laid out the same way in the skeleton
+const CONTAINER_IMPORT_PATHS: [&str; 5] = [
+    GROUP_IMPORT_PATH,
+    "pax_std::core::frame::Frame",
+    "pax_std::core::scroller::Scroller",
+    "pax_std::layout::stacker::Stacker",
+    "pax_std::core::link::Link",
+];
+
+/// A loading placeholder with the structure of a component's template: text becomes
+/// `Skeleton { variant: Text }` bars, ellipses become circles, and images, shapes and form
+/// controls become rectangles, while containers and the layout of every node are kept.  Nested
+/// components are expanded in place, control flow is flattened, rendering the body of each `if`
+/// and `for` once, and slots are left empty.
+pub struct SkeletonScreen {
+    /// Template of the skeleton, with `Group`, container and `Skeleton` nodes only
+    pub template: ComponentTemplate,
+}
+
+impl SkeletonScreen {
+    pub fn from_component(type_id: &TypeId, manifest: &PaxManifest) -> SkeletonScreen {
+        let mut template = ComponentTemplate::new(type_id.clone(), None);
+        let layout_keys: HashSet<String> = get_common_properties_as_property_definitions()
+            .into_iter()
+            .map(|property| property.name)
+            .collect();
+        let mut generator = SkeletonGenerator {
+            manifest,
+            layout_keys,
+            expanding: vec![type_id.clone()],
+        };
+        if let Some(source) = component_template(manifest, type_id) {
+            generator.add_nodes(
+                &mut template,
+                source,
+                &source.get_root(),
+                &NodeLocation::root(type_id.clone()),
+            );
+        }
+        SkeletonScreen { template }
+    }
+
+    /// Root nodes of the skeleton, e.g. to copy it into another template
+    pub fn roots(&self) -> Vec<TemplateNodeId> {
+        self.template.get_root()
+    }
+}
+
+struct SkeletonGenerator<'a> {
+    manifest: &'a PaxManifest,
+    layout_keys: HashSet<String>,
+    /// Components being expanded, to stop at recursive components
+    expanding: Vec<TypeId>,
+}
+
+impl SkeletonGenerator<'_> {
+    fn add_nodes(
+        &mut self,
+        template: &mut ComponentTemplate,
+        source: &ComponentTemplate,
+        ids: &[TemplateNodeId],
+        location: &NodeLocation,
+    ) {
+        for id in ids {
+            let Some(node) = source.get_node(id) else {
+                continue;
+            };
+            let children = source.get_children(id).unwrap_or_default();
+            match node.type_id.get_pax_type() {
+                PaxType::If | PaxType::Repeat => {
+                    self.add_nodes(template, source, &children, location);
+                }
+                PaxType::Slot | PaxType::Comment => {}
+                _ => self.add_node(template, source, node, &children, location),
+            }
+        }
+    }
+
+    fn add_node(
+        &mut self,
+        template: &mut ComponentTemplate,
+        source: &ComponentTemplate,
+        node: &TemplateNodeDefinition,
+        children: &[TemplateNodeId],
+        location: &NodeLocation,
+    ) {
+        let import_path = node.type_id.import_path().unwrap_or_default();
+        let nested = component_template(self.manifest, &node.type_id)
+            .filter(|_| !self.expanding.contains(&node.type_id));
+
+        if let Some(nested) = nested {
+            let group = self.insert(template, location, GROUP_IMPORT_PATH, node, None);
+            self.expanding.push(node.type_id.clone());
+            self.add_nodes(template, nested, &nested.get_root(), &group);
+            self.expanding.pop();
+        } else if CONTAINER_IMPORT_PATHS.contains(&import_path.as_str()) || !children.is_empty() {
+            let container_path = if CONTAINER_IMPORT_PATHS.contains(&import_path.as_str()) {
+                import_path.as_str()
+            } else {
+                GROUP_IMPORT_PATH
+            };
+            let container = self.insert(template, location, container_path, node, None);
+            self.add_nodes(template, source, children, &container);
+        } else {
+            let variant = match import_path.trim_start_matches("pax_std::") {
+                "core::text::Text" => "Text",
+                "drawing::ellipse::Ellipse" => "Circle",
+                _ => "Rectangle",
+            };
+            self.insert(
+                template,
+                location,
+                SKELETON_IMPORT_PATH,
+                node,
+                Some(variant),
+            );
+        }
+    }
+
+    /// Adds a node of type `import_path` at `location`, with the literal layout settings of
+    /// `source_node` (and all of its literal settings if it's kept as-is), and returns the
+    /// location of its children
+    fn insert(
+        &self,
+        template: &mut ComponentTemplate,
+        location: &NodeLocation,
+        import_path: &str,
+        source_node: &TemplateNodeDefinition,
+        variant: Option<&str>,
+    ) -> NodeLocation {
+        let keep_all = source_node.type_id.import_path().as_deref() == Some(import_path);
+        let mut settings: Vec<SettingElement> = source_node
+            .settings
+            .iter()
+            .flatten()
+            .filter(|setting| match setting {
+                SettingElement::Setting(key, ValueDefinition::LiteralValue(_)) => {
+                    keep_all || self.layout_keys.contains(&key.token_value)
+                }
+                _ => false,
+            })
+            .cloned()
+            .collect();
+        if let Some(variant) = variant {
+            settings.push(SettingElement::Setting(
+                Token::new_without_location("variant".to_string()),
+                ValueDefinition::LiteralValue(PaxValue::Enum(
+                    "SkeletonVariant".to_string(),
+                    variant.to_string(),
+                    vec![],
+                )),
+            ));
+        }
+        let node = TemplateNodeDefinition {
+            type_id: TypeId::build_singleton(import_path, None),
+            control_flow_settings: None,
+            settings: Some(settings),
+            raw_comment_string: None,
+            label: None,
+        };
+        let mut location = location.clone();
+        location.set_index(TreeIndexPosition::Bottom);
+        let id = template.add_at(node, location).get_template_node_id();
+        let mut children = NodeLocation::parent(template.get_containing_component_type_id(), id);
+        children.set_index(TreeIndexPosition::Bottom);
+        children
+    }
+}
+
+fn component_template<'a>(
+    manifest: &'a PaxManifest,
+    type_id: &TypeId,
+) -> Option<&'a ComponentTemplate> {
+    let component = manifest.components.get(type_id)?;
+    if component.is_primitive || component.is_struct_only_component {
+        return None;
+    }
+    component.template.as_ref()
+}
@@ -14,6 +14,7 @@ use pax_message::{
 use pax_runtime::api::{borrow, borrow_mut, use_RefCell};
 
 use_RefCell!();
+use std::cell::Cell;
 use std::rc::Rc;
 #[cfg(feature = "designtime")]
 use {
@@ -23,6 +24,11 @@ use {
 
 use crate::common::patch_if_needed;
 
+/// Kind of native element used to recycle the elements of unmounted Texts.  Text is the only
+/// primitive recycling its element, see
+/// [`NativeElementPool`](pax_runtime::engine::native_recycling::NativeElementPool)
+const NATIVE_ELEMENT_KIND: &str = "Text";
+
 /// Renders text in a platform-native way
 #[pax]
 #[engine_import_path("pax_engine")]
@@ -100,13 +106,20 @@ impl InstanceNode for TextInstance {
         expanded_node: &Rc<ExpandedNode>,
         context: &Rc<RuntimeContext>,
     ) {
-        // Send creation message
-        let id = expanded_node.id.to_u32();
-        context.enqueue_native_message(pax_message::NativeMessage::TextCreate(AnyCreatePatch {
-            id,
-            parent_frame: expanded_node.parent_frame.get().map(|v| v.to_u32()),
-            occlusion_layer_id: 0,
-        }));
+        // Reuse the native element of an unmounted Text if possible,
+        // otherwise send creation message
+        let recycled_id = context.claim_native_element(NATIVE_ELEMENT_KIND, expanded_node);
+        let id = recycled_id.unwrap_or_else(|| expanded_node.id.to_u32());
+        if recycled_id.is_none() {
+            context.enqueue_native_message(pax_message::NativeMessage::TextCreate(
+                AnyCreatePatch {
+                    id,
+                    parent_frame: expanded_node.parent_frame.get().map(|v| v.to_u32()),
+                    occlusion_layer_id: 0,
+                },
+            ));
+        }
+        let recycled = Cell::new(recycled_id.is_some());
 
         // send update message when relevant properties change
         let weak_self_ref = Rc::downgrade(&expanded_node);
@@ -120,7 +133,10 @@ impl InstanceNode for TextInstance {
             .values()
             .cloned()
             .map(|v| v.get_untyped_property().clone())
-            .chain([expanded_node.transform_and_bounds.untyped()])
+            .chain([
+                expanded_node.transform_and_bounds.untyped(),
+                expanded_node.resolved_text_style.untyped(),
+            ])
             .collect();
 
         expanded_node
@@ -160,7 +176,11 @@ impl InstanceNode for TextInstance {
                             patch_if_needed(
                                 &mut old_state.style,
                                 &mut patch.style,
-                                (&properties.style.get()).into(),
+                                (&properties.style.get().layered_over(
+                                    expanded_node.resolved_text_style.get(),
+                                    &TextStyle::default(),
+                                ))
+                                    .into(),
                             ),
                             patch_if_needed(
                                 &mut old_state.style_link,
@@ -186,6 +206,11 @@ impl InstanceNode for TextInstance {
                                 computed_tab.transform.coeffs().to_vec(),
                             ),
                         ];
+                        // the first patch of a recycled element is a full patch,
+                        // since last_patch starts out empty
+                        if recycled.take() {
+                            patch.recycled = Some(true);
+                        }
                         if updates.into_iter().any(|v| v == true) {
                             context.enqueue_native_message(pax_message::NativeMessage::TextUpdate(
                                 patch,
@@ -199,11 +224,25 @@ impl InstanceNode for TextInstance {
     }
 
     fn handle_unmount(&self, expanded_node: &Rc<ExpandedNode>, context: &Rc<RuntimeContext>) {
-        let id = expanded_node.id.to_u32();
         expanded_node
             .native_message_listener
             .replace_with(Property::default());
-        context.enqueue_native_message(pax_message::NativeMessage::TextDelete(id));
+        context.release_native_element(
+            NATIVE_ELEMENT_KIND,
+            expanded_node,
+            pax_message::NativeMessage::TextDelete,
+            |id| {
+                // hide the element while parked
+                pax_message::NativeMessage::TextUpdate(TextPatch {
+                    id,
+                    content: Some(String::new()),
+                    editable: Some(false),
+                    size_x: Some(0.0),
+                    size_y: Some(0.0),
+                    ..Default::default()
+                })
+            },
+        );
     }
 
     fn resolve_debug(
@@ -236,6 +275,85 @@ impl InstanceNode for TextInstance {
             log::warn!("text element was handed interrupt it doesn't use");
         }
     }
+
+    /// Approximates the text with an SVG `<text>`, one `<tspan>` per line.  Line breaks the
+    /// chassis makes to fit the text to its width aren't reproduced
+    #[cfg(feature = "svg-export")]
+    fn svg_element(&self, expanded_node: &ExpandedNode) -> Option<String> {
+        use pax_runtime::svg_export::{escape_xml, paint_attributes, svg_matrix};
+
+        const LINE_HEIGHT: f64 = 1.2;
+
+        let tab = expanded_node.transform_and_bounds.get();
+        let (width, height) = tab.bounds;
+        expanded_node.with_properties_unwrapped(|properties: &mut Text| {
+            let text = properties.text.get();
+            let style = properties
+                .style
+                .get()
+                .layered_over(expanded_node.resolved_text_style.get(), &TextStyle::default());
+            let font_size = style.font_size.get().expect_pixels().to_float();
+            let Font::Web(family, _, font_style, font_weight) = style.font.get();
+            let (x, anchor) = match style.align_horizontal.get() {
+                TextAlignHorizontal::Left => (0.0, "start"),
+                TextAlignHorizontal::Center => (width / 2.0, "middle"),
+                TextAlignHorizontal::Right => (width, "end"),
+            };
+            let lines: Vec<&str> = text.lines().collect();
+            let text_height = lines.len() as f64 * font_size * LINE_HEIGHT;
+            let top = match style.align_vertical.get() {
+                TextAlignVertical::Top => 0.0,
+                TextAlignVertical::Center => (height - text_height) / 2.0,
+                TextAlignVertical::Bottom => height - text_height,
+            };
+            let font_style = match font_style {
+                FontStyle::Normal => "normal",
+                FontStyle::Italic => "italic",
+                FontStyle::Oblique => "oblique",
+            };
+            let font_weight = match font_weight {
+                FontWeight::Thin => 100,
+                FontWeight::ExtraLight => 200,
+                FontWeight::Light => 300,
+                FontWeight::Normal => 400,
+                FontWeight::Medium => 500,
+                FontWeight::SemiBold => 600,
+                FontWeight::Bold => 700,
+                FontWeight::ExtraBold => 800,
+                FontWeight::Black => 900,
+            };
+            let underline = if style.underline.get() {
+                r#" text-decoration="underline""#
+            } else {
+                ""
+            };
+            let tspans: String = lines
+                .iter()
+                .enumerate()
+                .map(|(i, line)| {
+                    format!(
+                        r#"<tspan x="{}" y="{}">{}</tspan>"#,
+                        x,
+                        top + i as f64 * font_size * LINE_HEIGHT,
+                        escape_xml(line)
+                    )
+                })
+                .collect();
+            Some(format!(
+                r#"<text data-pax-node="{}" transform="{}" font-family="{}" font-size="{}" font-style="{}" font-weight="{}" text-anchor="{}" dominant-baseline="text-before-edge" {}{}>{}</text>"#,
+                expanded_node.id.to_u32(),
+                svg_matrix(tab.transform.into()),
+                escape_xml(&family),
+                font_size,
+                font_style,
+                font_weight,
+                anchor,
+                paint_attributes("fill", style.fill.get().to_piet_color()),
+                underline,
+                tspans
+            ))
+        })
+    }
 }
 
 #[pax]
@@ -272,6 +390,48 @@ impl Default for TextStyle {
     }
 }
 
+impl TextStyle {
+    /// Names of the fields that differ from `defaults`, the style of the element when its
+    /// style is left unset. Only these fields override the text style an element inherits.
+    pub fn set_fields(&self, defaults: &TextStyle) -> Vec<String> {
+        let (PaxValue::Object(fields), PaxValue::Object(defaults)) =
+            (self.clone().to_pax_value(), defaults.clone().to_pax_value())
+        else {
+            return vec![];
+        };
+        let mut set: Vec<String> = fields
+            .into_iter()
+            .filter(|(name, value)| defaults.get(name) != Some(value))
+            .map(|(name, _)| name)
+            .collect();
+        set.sort();
+        set
+    }
+
+    /// The style an element renders with: the fields of this style that are set (see
+    /// [`TextStyle::set_fields`]), layered over the text style the element inherits from its
+    /// ancestors (see `ExpandedNode::resolved_text_style`), layered over `defaults`. A field
+    /// set to its default value counts as unset, and takes the inherited value.
+    pub fn layered_over(&self, inherited: Option<PaxValue>, defaults: &TextStyle) -> TextStyle {
+        let Some(PaxValue::Object(inherited)) = inherited else {
+            return self.clone();
+        };
+        let set = self.set_fields(defaults);
+        let PaxValue::Object(mut fields) = self.clone().to_pax_value() else {
+            return self.clone();
+        };
+        for (name, value) in inherited {
+            if fields.contains_key(&name) && !set.contains(&name) {
+                fields.insert(name, value);
+            }
+        }
+        TextStyle::try_coerce(PaxValue::Object(fields)).unwrap_or_else(|e| {
+            log::warn!("failed to inherit text style: {e}");
+            self.clone()
+        })
+    }
+}
+
 impl<'a> Into<TextStyleMessage> for &'a TextStyle {
     fn into(self) -> TextStyleMessage {
         TextStyleMessage {
@@ -0,0 +1,294 @@
+#[allow(unused)]
+use crate::*;
+use pax_engine::api::*;
+use pax_engine::*;
+
+/// Plays the keyframe animation described by its `Keyframe` children on the rest of its
+/// content.  Keyframes are placed at a fraction (`at`, from 0.0 to 1.0) of `duration_frames`,
+/// and the values of consecutive keyframes for the same property are eased between linearly:
+///
+/// ```pax
+/// <Timeline duration_frames=120 looping=true>
+///     <Keyframe at=0.0 properties={[KeyframeProperty { name: "x", value: 0px }]}/>
+///     <Keyframe at=1.0 properties={[KeyframeProperty { name: "x", value: 200px }]}/>
+///     <Rectangle width=50px height=50px/>
+/// </Timeline>
+/// ```
+///
+/// The animatable properties are `x`, `y`, `rotate`, `scale_x` and `scale_y` of the content.
+/// The animation starts when `auto_play` is (or becomes) true, and restarts from the first
+/// keyframe at the end of `duration_frames` if `looping` is set (`loop` is a reserved word).
+#[pax]
+#[engine_import_path("pax_engine")]
+#[inlined(
+    <Group
+        x={self._x}
+        y={self._y}
+        rotate={self._rotate}
+        scale_x={self._scale_x}
+        scale_y={self._scale_y}
+    >
+        for i in 0..self._slot_children_count {
+            slot(i)
+        }
+    </Group>
+    @settings {
+        @mount: on_mount
+        @pre_render: pre_render
+    }
+)]
+#[custom(Default)]
+pub struct Timeline {
+    pub duration_frames: Property<usize>,
+    pub looping: Property<bool>,
+    pub auto_play: Property<bool>,
+    pub _slot_children_count: Property<usize>,
+    pub _keyframes: Property<Vec<Option<KeyframeEntry>>>,
+    pub _x: Property<Size>,
+    pub _y: Property<Size>,
+    pub _rotate: Property<Rotation>,
+    pub _scale_x: Property<Size>,
+    pub _scale_y: Property<Size>,
+    pub _on_change: Property<bool>,
+    pub _started_at: Property<Option<u64>>,
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Self {
+            duration_frames: Property::new(60),
+            looping: Property::new(false),
+            auto_play: Property::new(true),
+            _slot_children_count: Property::new(0),
+            _keyframes: Property::new(vec![]),
+            _x: Property::new(Size::ZERO()),
+            _y: Property::new(Size::ZERO()),
+            _rotate: Property::new(Rotation::default()),
+            _scale_x: Property::new(Size::Percent(100.into())),
+            _scale_y: Property::new(Size::Percent(100.into())),
+            _on_change: Property::new(false),
+            _started_at: Property::new(None),
+        }
+    }
+}
+
+/// A value of a property at one point of a `Timeline`, e.g. `KeyframeProperty { name: "x",
+/// value: 100px }`
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct KeyframeProperty {
+    pub name: String,
+    pub value: PaxValue,
+}
+
+/// A registered keyframe, at the slot index of its `Keyframe` node
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct KeyframeEntry {
+    pub at: f64,
+    pub properties: Vec<KeyframeProperty>,
+}
+
+pub struct TimelineContext {
+    pub keyframes: Property<Vec<Option<KeyframeEntry>>>,
+}
+
+impl Store for TimelineContext {}
+
+impl Timeline {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        ctx.push_local_store(TimelineContext {
+            keyframes: self._keyframes.clone(),
+        });
+        let slot_children_count = ctx.slot_children_count.clone();
+        let deps = [slot_children_count.untyped()];
+        self._slot_children_count
+            .replace_with(Property::computed(move || slot_children_count.get(), &deps));
+
+        let keyframes = self._keyframes.clone();
+        let duration = self.duration_frames.clone();
+        let auto_play = self.auto_play.clone();
+        let started_at = self._started_at.clone();
+        let frames_elapsed = ctx.frames_elapsed.clone();
+        let targets = self.targets();
+        let deps = [keyframes.untyped(), duration.untyped(), auto_play.untyped()];
+        self._on_change.replace_with(Property::computed(
+            move || {
+                let keyframes = keyframes.get();
+                if auto_play.get() {
+                    targets.play(&keyframes, duration.get());
+                    started_at.set(Some(frames_elapsed.get()));
+                } else {
+                    targets.show_first(&keyframes);
+                    started_at.set(None);
+                }
+                false
+            },
+            &deps,
+        ));
+    }
+
+    pub fn pre_render(&mut self, ctx: &NodeContext) {
+        // trigger dirty prop to fire closure
+        self._on_change.get();
+
+        let Some(started_at) = self._started_at.get() else {
+            return;
+        };
+        let now = ctx.frames_elapsed.get();
+        let duration = self.duration_frames.get() as u64;
+        if self.looping.get() && now.saturating_sub(started_at) >= duration.max(1) {
+            self.targets()
+                .play(&self._keyframes.get(), self.duration_frames.get());
+            self._started_at.set(Some(now));
+        }
+    }
+
+    fn targets(&self) -> Targets {
+        Targets {
+            x: self._x.clone(),
+            y: self._y.clone(),
+            rotate: self._rotate.clone(),
+            scale_x: self._scale_x.clone(),
+            scale_y: self._scale_y.clone(),
+        }
+    }
+}
+
+/// The animatable properties of the content of a `Timeline`
+struct Targets {
+    x: Property<Size>,
+    y: Property<Size>,
+    rotate: Property<Rotation>,
+    scale_x: Property<Size>,
+    scale_y: Property<Size>,
+}
+
+impl Targets {
+    fn play(&self, keyframes: &[Option<KeyframeEntry>], duration_frames: usize) {
+        let keyframes = sorted(keyframes);
+        animate(&self.x, &values(&keyframes, "x"), duration_frames);
+        animate(&self.y, &values(&keyframes, "y"), duration_frames);
+        animate(&self.rotate, &values(&keyframes, "rotate"), duration_frames);
+        animate(
+            &self.scale_x,
+            &values(&keyframes, "scale_x"),
+            duration_frames,
+        );
+        animate(
+            &self.scale_y,
+            &values(&keyframes, "scale_y"),
+            duration_frames,
+        );
+    }
+
+    fn show_first(&self, keyframes: &[Option<KeyframeEntry>]) {
+        self.play(keyframes, 0);
+    }
+}
+
+fn sorted(keyframes: &[Option<KeyframeEntry>]) -> Vec<KeyframeEntry> {
+    let mut keyframes: Vec<KeyframeEntry> = keyframes.iter().flatten().cloned().collect();
+    keyframes.sort_by(|a, b| a.at.total_cmp(&b.at));
+    keyframes
+}
+
+/// The values of property `name` in `keyframes`, with the time they're at
+fn values(keyframes: &[KeyframeEntry], name: &str) -> Vec<(f64, PaxValue)> {
+    keyframes
+        .iter()
+        .flat_map(|keyframe| {
+            keyframe
+                .properties
+                .iter()
+                .filter(|property| property.name == name)
+                .map(|property| (keyframe.at.clamp(0.0, 1.0), property.value.clone()))
+        })
+        .collect()
+}
+
+/// Sets `property` to the first of `values`, and queues transitions to the following ones.
+/// A `duration_frames` of zero only sets the first value.
+fn animate<T: CoercionRules + Default + Interpolatable + Clone + 'static>(
+    property: &Property<T>,
+    values: &[(f64, PaxValue)],
+    duration_frames: usize,
+) {
+    let values: Vec<(f64, T)> = values
+        .iter()
+        .filter_map(|(at, value)| Some((*at, T::try_coerce(value.clone()).ok()?)))
+        .collect();
+    let Some((first_at, first)) = values.first() else {
+        return;
+    };
+    let frames =
+        |from: f64, to: f64| (((to - from) * duration_frames as f64).round() as u64).max(1);
+    // jump to the first value, dropping any running transitions, and hold it until its keyframe
+    property.set(first.clone());
+    property.ease_to(first.clone(), frames(0.0, *first_at), EasingCurve::Linear);
+    if duration_frames == 0 {
+        return;
+    }
+    let mut previous_at = *first_at;
+    for (at, value) in values.iter().skip(1) {
+        property.ease_to_later(value.clone(), frames(previous_at, *at), EasingCurve::Linear);
+        previous_at = *at;
+    }
+}
+
+/// A keyframe of the enclosing `Timeline`: the values of some of the animated properties
+/// at fraction `at` of the timeline's duration.  Keyframes don't render anything.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[inlined( @settings { @mount: on_mount @pre_render: pre_render @unmount: on_unmount })]
+pub struct Keyframe {
+    pub at: Property<f64>,
+    pub properties: Property<Vec<KeyframeProperty>>,
+    pub on_change: Property<bool>,
+}
+
+impl Keyframe {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        let keyframes = ctx
+            .peek_local_store(|timeline_ctx: &mut TimelineContext| timeline_ctx.keyframes.clone())
+            .expect("keyframe can only exist in <Timeline> tag");
+
+        let at = self.at.clone();
+        let properties = self.properties.clone();
+        let id = ctx.slot_index.clone();
+        let deps = [at.untyped(), properties.untyped(), id.untyped()];
+        self.on_change.replace_with(Property::computed(
+            move || {
+                keyframes.update(|keyframes| {
+                    let id = id.get().unwrap();
+                    while keyframes.len() < id + 1 {
+                        keyframes.push(None)
+                    }
+                    keyframes[id] = Some(KeyframeEntry {
+                        at: at.get(),
+                        properties: properties.get(),
+                    });
+                });
+                false
+            },
+            &deps,
+        ));
+    }
+
+    pub fn on_unmount(&mut self, ctx: &NodeContext) {
+        let keyframes = ctx
+            .peek_local_store(|timeline_ctx: &mut TimelineContext| timeline_ctx.keyframes.clone())
+            .expect("keyframe can only exist in <Timeline> tag");
+        let id = ctx.slot_index.get().unwrap();
+        keyframes.update(|keyframes| {
+            if let Some(keyframe) = keyframes.get_mut(id) {
+                *keyframe = None;
+            }
+        });
+    }
+
+    pub fn pre_render(&mut self, _ctx: &NodeContext) {
+        // trigger dirty prop to fire closure
+        self.on_change.get();
+    }
+}
@@ -0,0 +1,47 @@
+#[allow(unused)]
+use crate::*;
+use pax_engine::api::Property;
+use pax_engine::*;
+use pax_runtime::api::NodeContext;
+
+/// Rendered in place of a template node whose component doesn't exist, e.g. after the component
+/// was renamed in Rust, at the node's own bounds so that the surrounding layout stays intact.
+/// Used by the runtime at designtime, see `PaxType::Unresolved`, rather than in templates.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[inlined(
+    <Text x=50% y=50% width={100% - 10px} height=100% id=label text={self._label}/>
+    <Rectangle
+        fill=rgba(230, 60, 60, 30)
+        stroke={color: rgb(230, 60, 60), width: 2px}
+    />
+    @settings {
+        @mount: on_mount
+        #label {
+            selectable: false,
+            style: {
+                font_size: 12px,
+                fill: rgb(200, 40, 40),
+                align_vertical: TextAlignVertical::Center,
+                align_horizontal: TextAlignHorizontal::Center,
+                align_multiline: TextAlignHorizontal::Center
+            }
+        }
+    }
+)]
+pub struct UnresolvedPlaceholder {
+    /// Name of the missing component, as written in the template
+    pub type_name: Property<String>,
+    pub _label: Property<String>,
+}
+
+impl UnresolvedPlaceholder {
+    pub fn on_mount(&mut self, _ctx: &NodeContext) {
+        let type_name = self.type_name.clone();
+        let deps = [type_name.untyped()];
+        self._label.replace_with(Property::computed(
+            move || format!("Missing component <{}>", type_name.get()),
+            &deps,
+        ));
+    }
+}
@@ -0,0 +1,349 @@
+use kurbo::{BezPath, Shape};
+use pax_engine::*;
+use pax_message::{AnyCreatePatch, ImagePatch, NativeInterrupt, VideoPatch};
+use pax_runtime::api::{borrow, borrow_mut, use_RefCell, Color, Layer, Property, RenderContext};
+use pax_runtime::{
+    BaseInstance, ExpandedNode, ExpandedNodeIdentifier, InstanceFlags, InstanceNode,
+    InstantiationArgs, RuntimeContext,
+};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use_RefCell!();
+
+use crate::common::patch_if_needed;
+use crate::ImageFit;
+
+/// A platform-native video player. `source` is an asset path or URL.
+///
+/// `playing` and `current_time` are kept in sync with playback: setting them
+/// plays/pauses or seeks, and the chassis writes back the playback position
+/// (at a throttled rate) and play state. `duration`, `natural_width` and
+/// `natural_height` are read-only, filled in once the video's metadata is loaded.
+/// If the chassis can't play the video, `poster` (or a placeholder) is drawn instead.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[primitive("pax_std::core::video::VideoInstance")]
+#[custom(Default)]
+pub struct Video {
+    pub source: Property<String>,
+    pub playing: Property<bool>,
+    pub looping: Property<bool>,
+    pub muted: Property<bool>,
+    pub volume: Property<f64>,
+    pub current_time: Property<f64>,
+    pub fit: Property<ImageFit>,
+    pub poster: Property<String>,
+    pub duration: Property<f64>,
+    pub natural_width: Property<f64>,
+    pub natural_height: Property<f64>,
+}
+
+impl Default for Video {
+    fn default() -> Self {
+        Self {
+            source: Default::default(),
+            playing: Default::default(),
+            looping: Default::default(),
+            muted: Default::default(),
+            volume: Property::new(1.0),
+            current_time: Default::default(),
+            fit: Default::default(),
+            poster: Default::default(),
+            duration: Default::default(),
+            natural_width: Default::default(),
+            natural_height: Default::default(),
+        }
+    }
+}
+
+pub struct VideoInstance {
+    base: BaseInstance,
+    /// Last state sent to the chassis for each mounted video. Values reported by the
+    /// chassis are written here before being set on the properties, so that they
+    /// aren't echoed back (for example as a seek on every time update).
+    last_patches: RefCell<HashMap<ExpandedNodeIdentifier, Rc<RefCell<VideoPatch>>>>,
+    /// Videos the chassis reported it can't play, rendered on canvas instead
+    unsupported: Rc<RefCell<HashSet<ExpandedNodeIdentifier>>>,
+}
+
+impl InstanceNode for VideoInstance {
+    fn instantiate(args: InstantiationArgs) -> Rc<Self>
+    where
+        Self: Sized,
+    {
+        Rc::new(Self {
+            base: BaseInstance::new(
+                args,
+                InstanceFlags {
+                    invisible_to_slot: false,
+                    invisible_to_raycasting: false,
+                    layer: Layer::Native,
+                    is_component: false,
+                },
+            ),
+            last_patches: Default::default(),
+            unsupported: Default::default(),
+        })
+    }
+
+    fn handle_mount(
+        self: Rc<Self>,
+        expanded_node: &Rc<ExpandedNode>,
+        context: &Rc<RuntimeContext>,
+    ) {
+        let id = expanded_node.id.to_u32();
+        context.enqueue_native_message(pax_message::NativeMessage::VideoCreate(AnyCreatePatch {
+            id,
+            parent_frame: expanded_node.parent_frame.get().map(|v| v.to_u32()),
+            occlusion_layer_id: 0,
+        }));
+
+        // send update message when relevant properties change
+        let weak_self_ref = Rc::downgrade(&expanded_node);
+        let context = Rc::clone(context);
+        let last_patch = Rc::new(RefCell::new(VideoPatch {
+            id,
+            ..Default::default()
+        }));
+        borrow_mut!(self.last_patches).insert(expanded_node.id, Rc::clone(&last_patch));
+        let last_poster = Rc::new(RefCell::new(ImagePatch {
+            id,
+            ..Default::default()
+        }));
+        let unsupported = Rc::clone(&self.unsupported);
+
+        let deps: Vec<_> = borrow!(expanded_node.properties_scope)
+            .values()
+            .cloned()
+            .map(|v| v.get_untyped_property().clone())
+            .chain([expanded_node.transform_and_bounds.untyped()])
+            .collect();
+        expanded_node
+            .native_message_listener
+            .replace_with(Property::computed(
+                move || {
+                    let Some(expanded_node) = weak_self_ref.upgrade() else {
+                        unreachable!()
+                    };
+                    let mut old_state = borrow_mut!(last_patch);
+
+                    let mut patch = VideoPatch {
+                        id,
+                        ..Default::default()
+                    };
+                    expanded_node.with_properties_unwrapped(|properties: &mut Video| {
+                        let computed_tab = expanded_node.transform_and_bounds.get();
+                        let (width, height) = computed_tab.bounds;
+                        let updates = [
+                            patch_if_needed(&mut old_state.size_x, &mut patch.size_x, width),
+                            patch_if_needed(&mut old_state.size_y, &mut patch.size_y, height),
+                            patch_if_needed(
+                                &mut old_state.transform,
+                                &mut patch.transform,
+                                computed_tab.transform.coeffs().to_vec(),
+                            ),
+                            patch_if_needed(
+                                &mut old_state.source,
+                                &mut patch.source,
+                                properties.source.get(),
+                            ),
+                            patch_if_needed(
+                                &mut old_state.playing,
+                                &mut patch.playing,
+                                properties.playing.get(),
+                            ),
+                            patch_if_needed(
+                                &mut old_state.looping,
+                                &mut patch.looping,
+                                properties.looping.get(),
+                            ),
+                            patch_if_needed(
+                                &mut old_state.muted,
+                                &mut patch.muted,
+                                properties.muted.get(),
+                            ),
+                            patch_if_needed(
+                                &mut old_state.volume,
+                                &mut patch.volume,
+                                properties.volume.get(),
+                            ),
+                            patch_if_needed(
+                                &mut old_state.current_time,
+                                &mut patch.current_time,
+                                properties.current_time.get(),
+                            ),
+                            patch_if_needed(
+                                &mut old_state.fit,
+                                &mut patch.fit,
+                                match properties.fit.get() {
+                                    ImageFit::Fill => "cover",
+                                    ImageFit::Fit => "contain",
+                                    ImageFit::Stretch => "fill",
+                                }
+                                .to_string(),
+                            ),
+                        ];
+                        if patch.source.is_some() {
+                            // the chassis reports again if it can't play the new source
+                            borrow_mut!(unsupported).remove(&expanded_node.id);
+                        }
+                        if updates.into_iter().any(|v| v == true) {
+                            context.enqueue_native_message(
+                                pax_message::NativeMessage::VideoUpdate(patch),
+                            );
+                        }
+
+                        // the poster is only drawn if the chassis can't play the video,
+                        // but is loaded up front so that it's ready if that happens
+                        let poster = properties.poster.get();
+                        let mut last_poster = borrow_mut!(last_poster);
+                        let mut poster_patch = ImagePatch {
+                            id,
+                            ..Default::default()
+                        };
+                        if !poster.is_empty()
+                            && patch_if_needed(
+                                &mut last_poster.path,
+                                &mut poster_patch.path,
+                                poster,
+                            )
+                        {
+                            context.enqueue_native_message(pax_message::NativeMessage::ImageLoad(
+                                poster_patch,
+                            ));
+                        }
+                    });
+                    ()
+                },
+                &deps,
+            ));
+    }
+
+    fn handle_unmount(&self, expanded_node: &Rc<ExpandedNode>, context: &Rc<RuntimeContext>) {
+        let id = expanded_node.id.clone();
+        expanded_node
+            .native_message_listener
+            .replace_with(Property::default());
+        borrow_mut!(self.last_patches).remove(&id);
+        borrow_mut!(self.unsupported).remove(&id);
+        context.enqueue_native_message(pax_message::NativeMessage::VideoDelete(id.to_u32()));
+    }
+
+    fn render(
+        &self,
+        expanded_node: &ExpandedNode,
+        _rtc: &Rc<RuntimeContext>,
+        rc: &mut dyn RenderContext,
+    ) {
+        if !borrow!(self.unsupported).contains(&expanded_node.id) {
+            return;
+        }
+        let t_and_b = expanded_node.transform_and_bounds.get();
+        let (container_width, container_height) = t_and_b.bounds;
+        let clip_path = kurbo::Rect::new(0.0, 0.0, container_width, container_height);
+        let layer_id = format!("{}", expanded_node.occlusion.get().occlusion_layer_id);
+
+        rc.save(&layer_id);
+        rc.transform(&layer_id, t_and_b.transform.into());
+        rc.clip(&layer_id, clip_path.into_path(0.01));
+        expanded_node.with_properties_unwrapped(|props: &mut Video| {
+            let poster = props.poster.get();
+            match rc.get_image_size(&poster).filter(|_| !poster.is_empty()) {
+                Some((image_width, image_height)) => {
+                    let (image_width, image_height) = (image_width as f64, image_height as f64);
+                    let stretch_w = container_width / image_width;
+                    let stretch_h = container_height / image_height;
+                    let (width, height) = match props.fit.get() {
+                        ImageFit::Fill => {
+                            let stretch = stretch_h.max(stretch_w);
+                            (image_width * stretch, image_height * stretch)
+                        }
+                        ImageFit::Fit => {
+                            let stretch = stretch_h.min(stretch_w);
+                            (image_width * stretch, image_height * stretch)
+                        }
+                        ImageFit::Stretch => (container_width, container_height),
+                    };
+                    let x = (container_width - width) / 2.0;
+                    let y = (container_height - height) / 2.0;
+                    let bounds = kurbo::Rect::new(x, y, x + width, y + height);
+                    rc.draw_image(&layer_id, &poster, bounds);
+                }
+                None => {
+                    // placeholder: dark background with a "play" triangle
+                    rc.fill(
+                        &layer_id,
+                        clip_path.into_path(0.01),
+                        &Color::rgb(30.into(), 30.into(), 30.into())
+                            .to_piet_color()
+                            .into(),
+                    );
+                    let size = container_width.min(container_height) * 0.2;
+                    let (cx, cy) = (container_width / 2.0, container_height / 2.0);
+                    let mut triangle = BezPath::new();
+                    triangle.move_to((cx - size * 0.4, cy - size * 0.5));
+                    triangle.line_to((cx + size * 0.6, cy));
+                    triangle.line_to((cx - size * 0.4, cy + size * 0.5));
+                    triangle.close_path();
+                    rc.fill(
+                        &layer_id,
+                        triangle,
+                        &Color::rgb(200.into(), 200.into(), 200.into())
+                            .to_piet_color()
+                            .into(),
+                    );
+                }
+            }
+        });
+        rc.restore(&layer_id);
+    }
+
+    fn base(&self) -> &BaseInstance {
+        &self.base
+    }
+
+    fn resolve_debug(
+        &self,
+        f: &mut std::fmt::Formatter,
+        _expanded_node: Option<&ExpandedNode>,
+    ) -> std::fmt::Result {
+        f.debug_struct("Video").finish_non_exhaustive()
+    }
+
+    fn handle_native_interrupt(
+        &self,
+        expanded_node: &Rc<ExpandedNode>,
+        interrupt: &NativeInterrupt,
+    ) {
+        let last_patch = borrow!(self.last_patches).get(&expanded_node.id).cloned();
+        match interrupt {
+            NativeInterrupt::VideoTimeUpdate(args) => {
+                if let Some(last_patch) = last_patch {
+                    borrow_mut!(last_patch).current_time = Some(args.current_time);
+                }
+                expanded_node.with_properties_unwrapped(|props: &mut Video| {
+                    props.current_time.set(args.current_time)
+                });
+            }
+            NativeInterrupt::VideoPlayStateChange(args) => {
+                if let Some(last_patch) = last_patch {
+                    borrow_mut!(last_patch).playing = Some(args.playing);
+                }
+                expanded_node
+                    .with_properties_unwrapped(|props: &mut Video| props.playing.set(args.playing));
+            }
+            NativeInterrupt::VideoMetadata(args) => {
+                expanded_node.with_properties_unwrapped(|props: &mut Video| {
+                    props.duration.set(args.duration);
+                    props.natural_width.set(args.natural_width);
+                    props.natural_height.set(args.natural_height);
+                });
+            }
+            NativeInterrupt::VideoUnsupported(_) => {
+                borrow_mut!(self.unsupported).insert(expanded_node.id);
+            }
+            _ => log::warn!("video element was handed interrupt it doesn't use"),
+        }
+    }
+}
@@ -41,7 +41,7 @@ impl InstanceNode for EllipseInstance {
     fn render(
         &self,
         expanded_node: &ExpandedNode,
-        _context: &Rc<RuntimeContext>,
+        context: &Rc<RuntimeContext>,
         rc: &mut dyn RenderContext,
     ) {
         let tab = expanded_node.transform_and_bounds.get();
@@ -65,19 +65,14 @@ impl InstanceNode for EllipseInstance {
             rc.fill(&layer_id, transformed_bez_path, &color.into());
 
             //hack to address "phantom stroke" bug on Web
-            let width: f64 = properties
-                .stroke
-                .get()
-                .width
-                .get()
-                .expect_pixels()
-                .to_float();
+            let stroke = properties.stroke.get();
+            let width = stroke.resolve_width(context.device_pixel_ratio());
 
             if width > f64::EPSILON {
                 rc.stroke(
                     &layer_id,
                     duplicate_transformed_bez_path,
-                    &properties.stroke.get().color.get().to_piet_color().into(),
+                    &stroke.color.get().to_piet_color().into(),
                     width,
                 );
             }
@@ -1,7 +1,9 @@
 pub mod ellipse;
+pub mod morphing_shape;
 pub mod path;
 pub mod rectangle;
 
 pub use ellipse::*;
+pub use morphing_shape::*;
 pub use path::*;
 pub use rectangle::*;
@@ -0,0 +1,330 @@
+#[allow(unused)]
+use crate::*;
+use pax_engine::api::*;
+use pax_engine::*;
+
+/// A `Path` tweened between two shapes: `from_path` at `progress` 0.0 and `to_path` at 1.0.
+/// Corresponding segments of the two paths are interpolated point by point, so paths drawn
+/// with the same structure (e.g. the three lines of a hamburger icon and the two lines plus a
+/// zero-length line of an X) morph smoothly. If one path has fewer segments, it's padded with
+/// zero-length lines at its end point. Segments of different kinds are morphed as cubic
+/// curves, and segments that can't be matched (e.g. a line with a `Close`) switch over at 0.5.
+///
+/// ```pax
+/// <MorphingShape
+///     from_path={self.hamburger_icon}
+///     to_path={self.close_icon}
+///     progress={self.menu_open_progress}
+///     fill=TRANSPARENT
+///     stroke={color: BLACK, width: 2px}
+/// />
+/// ```
+///
+/// `progress` can be animated with `ease_to`, and may overshoot 0.0 and 1.0 for springy
+/// easing curves.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[inlined(
+    <Path elements={self._elements} fill={self.fill} stroke={self.stroke}/>
+
+    @settings {
+        @mount: on_mount
+    }
+)]
+pub struct MorphingShape {
+    pub from_path: Property<Vec<PathElement>>,
+    pub to_path: Property<Vec<PathElement>>,
+    pub progress: Property<f64>,
+    pub fill: Property<Color>,
+    pub stroke: Property<Stroke>,
+
+    // private
+    pub _elements: Property<Vec<PathElement>>,
+}
+
+impl MorphingShape {
+    pub fn on_mount(&mut self, _ctx: &NodeContext) {
+        let from_path = self.from_path.clone();
+        let to_path = self.to_path.clone();
+        let progress = self.progress.clone();
+        let deps = [from_path.untyped(), to_path.untyped(), progress.untyped()];
+        self._elements.replace_with(Property::computed(
+            move || {
+                from_path.read(|from| {
+                    to_path.read(|to| MorphingShape::interpolate(from, to, progress.get()))
+                })
+            },
+            &deps,
+        ));
+    }
+
+    /// The path between `from` (at `t` 0.0) and `to` (at `t` 1.0)
+    pub fn interpolate(from: &[PathElement], to: &[PathElement], t: f64) -> Vec<PathElement> {
+        let mut from = segments(from);
+        let mut to = segments(to);
+        let len = from.len().max(to.len());
+        for segments in [&mut from, &mut to] {
+            let end = *current_points(segments).last().unwrap();
+            segments.resize(len, Segment::Line(end));
+        }
+
+        let from_starts = current_points(&from);
+        let to_starts = current_points(&to);
+        let mut elements = vec![];
+        for i in 0..len {
+            let segment = match (&from[i], &to[i]) {
+                (Segment::Move(a), Segment::Move(b)) => Segment::Move(lerp_point(*a, *b, t)),
+                (Segment::Line(a), Segment::Line(b)) => Segment::Line(lerp_point(*a, *b, t)),
+                (Segment::Quadratic(a_h, a), Segment::Quadratic(b_h, b)) => {
+                    Segment::Quadratic(lerp_point(*a_h, *b_h, t), lerp_point(*a, *b, t))
+                }
+                (Segment::Close, Segment::Close) => Segment::Close,
+                (a, b) => match (a.as_cubic(from_starts[i]), b.as_cubic(to_starts[i])) {
+                    (Some([a_h1, a_h2, a]), Some([b_h1, b_h2, b])) => Segment::Cubic(
+                        lerp_point(a_h1, b_h1, t),
+                        lerp_point(a_h2, b_h2, t),
+                        lerp_point(a, b, t),
+                    ),
+                    _ if t < 0.5 => a.clone(),
+                    _ => b.clone(),
+                },
+            };
+            segment.push_elements(&mut elements);
+        }
+        elements
+    }
+}
+
+type SizePoint = (Size, Size);
+
+const ORIGIN: SizePoint = (
+    Size::Pixels(Numeric::F64(0.0)),
+    Size::Pixels(Numeric::F64(0.0)),
+);
+
+#[derive(Clone)]
+enum Segment {
+    Move(SizePoint),
+    Line(SizePoint),
+    Quadratic(SizePoint, SizePoint),
+    Cubic(SizePoint, SizePoint, SizePoint),
+    Close,
+}
+
+impl Segment {
+    /// The control points and end point of the segment as a cubic curve starting at `start`,
+    /// `None` for segments that don't draw a curve
+    fn as_cubic(&self, start: SizePoint) -> Option<[SizePoint; 3]> {
+        match self {
+            Segment::Line(p) => Some([
+                lerp_point(start, *p, 1.0 / 3.0),
+                lerp_point(start, *p, 2.0 / 3.0),
+                *p,
+            ]),
+            Segment::Quadratic(h, p) => Some([
+                lerp_point(start, *h, 2.0 / 3.0),
+                lerp_point(*p, *h, 2.0 / 3.0),
+                *p,
+            ]),
+            Segment::Cubic(h1, h2, p) => Some([*h1, *h2, *p]),
+            Segment::Move(_) | Segment::Close => None,
+        }
+    }
+
+    fn push_elements(&self, elements: &mut Vec<PathElement>) {
+        let end = match self {
+            Segment::Move(p) => p,
+            Segment::Line(p) => {
+                elements.push(PathElement::Line);
+                p
+            }
+            Segment::Quadratic(h, p) => {
+                elements.push(PathElement::Quadratic(h.0, h.1));
+                p
+            }
+            Segment::Cubic(h1, h2, p) => {
+                elements.push(PathElement::Cubic(Box::new((h1.0, h1.1, h2.0, h2.1))));
+                p
+            }
+            Segment::Close => {
+                elements.push(PathElement::Close);
+                return;
+            }
+        };
+        elements.push(PathElement::Point(end.0, end.1));
+    }
+}
+
+/// Splits `elements` up into segments, stopping at the first malformed one, like `Path` does
+fn segments(elements: &[PathElement]) -> Vec<Segment> {
+    let mut segments = vec![];
+    let mut elements = elements.iter();
+    while let Some(element) = elements.next() {
+        let segment = match element {
+            &PathElement::Point(x, y) => Segment::Move((x, y)),
+            PathElement::Close => Segment::Close,
+            PathElement::Empty => continue,
+            curve => {
+                let Some(&PathElement::Point(x, y)) = elements.next() else {
+                    log::warn!("line and curve expect to be followed by a point");
+                    break;
+                };
+                match curve {
+                    &PathElement::Quadratic(h_x, h_y) => Segment::Quadratic((h_x, h_y), (x, y)),
+                    PathElement::Cubic(vals) => {
+                        Segment::Cubic((vals.0, vals.1), (vals.2, vals.3), (x, y))
+                    }
+                    _ => Segment::Line((x, y)),
+                }
+            }
+        };
+        segments.push(segment);
+    }
+    segments
+}
+
+/// The point each segment starts at, followed by the point the last one ends at
+fn current_points(segments: &[Segment]) -> Vec<SizePoint> {
+    let mut current = ORIGIN;
+    let mut subpath_start = ORIGIN;
+    let mut points = Vec::with_capacity(segments.len() + 1);
+    points.push(current);
+    for segment in segments {
+        current = match segment {
+            Segment::Move(p) => {
+                subpath_start = *p;
+                *p
+            }
+            Segment::Line(p) | Segment::Quadratic(_, p) | Segment::Cubic(_, _, p) => *p,
+            Segment::Close => subpath_start,
+        };
+        points.push(current);
+    }
+    points
+}
+
+fn lerp_point(a: SizePoint, b: SizePoint, t: f64) -> SizePoint {
+    (lerp_size(a.0, b.0, t), lerp_size(a.1, b.1, t))
+}
+
+/// Interpolates pixels and percentages separately, so that sizes in different units can be mixed
+fn lerp_size(a: Size, b: Size, t: f64) -> Size {
+    let lerp = |a: f64, b: f64| Numeric::F64(a + (b - a) * t);
+    match (a, b) {
+        (Size::Pixels(a), Size::Pixels(b)) => Size::Pixels(lerp(a.to_float(), b.to_float())),
+        (Size::Percent(a), Size::Percent(b)) => Size::Percent(lerp(a.to_float(), b.to_float())),
+        (a, b) => {
+            let (a_px, a_percent) = size_parts(a);
+            let (b_px, b_percent) = size_parts(b);
+            Size::Combined(lerp(a_px, b_px), lerp(a_percent, b_percent))
+        }
+    }
+}
+
+fn size_parts(size: Size) -> (f64, f64) {
+    match size {
+        Size::Pixels(px) => (px.to_float(), 0.0),
+        Size::Percent(percent) => (0.0, percent.to_float()),
+        Size::Combined(px, percent) => (px.to_float(), percent.to_float()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn px(x: f64, y: f64) -> PathElement {
+        PathElement::Point(Size::Pixels(x.into()), Size::Pixels(y.into()))
+    }
+
+    /// The kind and evaluated coordinates of each element, for comparing paths
+    fn describe(elements: &[PathElement]) -> Vec<(&'static str, Vec<f64>)> {
+        let bounds = (100.0, 100.0);
+        let x = |s: &Size| s.evaluate(bounds, Axis::X);
+        let y = |s: &Size| s.evaluate(bounds, Axis::Y);
+        elements
+            .iter()
+            .map(|element| match element {
+                PathElement::Point(px, py) => ("point", vec![x(px), y(py)]),
+                PathElement::Line => ("line", vec![]),
+                PathElement::Quadratic(hx, hy) => ("quadratic", vec![x(hx), y(hy)]),
+                PathElement::Cubic(vals) => (
+                    "cubic",
+                    vec![x(&vals.0), y(&vals.1), x(&vals.2), y(&vals.3)],
+                ),
+                PathElement::Close => ("close", vec![]),
+                PathElement::Empty => ("empty", vec![]),
+            })
+            .collect()
+    }
+
+    fn polyline(points: &[(f64, f64)]) -> Vec<PathElement> {
+        let mut elements = vec![px(points[0].0, points[0].1)];
+        for &(x, y) in &points[1..] {
+            elements.push(PathElement::Line);
+            elements.push(px(x, y));
+        }
+        elements
+    }
+
+    #[test]
+    fn test_interpolate_endpoints() {
+        let from = polyline(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)]);
+        let to = polyline(&[(50.0, 0.0), (20.0, 30.0), (0.0, 40.0)]);
+        assert_eq!(
+            describe(&MorphingShape::interpolate(&from, &to, 0.0)),
+            describe(&from)
+        );
+        assert_eq!(
+            describe(&MorphingShape::interpolate(&from, &to, 1.0)),
+            describe(&to)
+        );
+        assert_eq!(
+            describe(&MorphingShape::interpolate(&from, &to, 0.5)),
+            describe(&polyline(&[(25.0, 0.0), (15.0, 15.0), (5.0, 25.0)]))
+        );
+    }
+
+    #[test]
+    fn test_interpolate_pads_shorter_path() {
+        let long = polyline(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)]);
+        let short = polyline(&[(0.0, 0.0), (20.0, 0.0)]);
+        // the short path gets a zero-length line at its end point
+        let padded = polyline(&[(0.0, 0.0), (20.0, 0.0), (20.0, 0.0)]);
+
+        assert_eq!(
+            describe(&MorphingShape::interpolate(&long, &short, 0.0)),
+            describe(&long)
+        );
+        assert_eq!(
+            describe(&MorphingShape::interpolate(&long, &short, 1.0)),
+            describe(&padded)
+        );
+        assert_eq!(
+            describe(&MorphingShape::interpolate(&short, &long, 0.0)),
+            describe(&padded)
+        );
+        assert_eq!(
+            describe(&MorphingShape::interpolate(&short, &long, 1.0)),
+            describe(&long)
+        );
+        assert_eq!(
+            describe(&MorphingShape::interpolate(&long, &short, 0.5)),
+            describe(&polyline(&[(0.0, 0.0), (15.0, 0.0), (15.0, 5.0)]))
+        );
+    }
+
+    #[test]
+    fn test_interpolate_unmatched_segments_switch_halfway() {
+        let from = polyline(&[(0.0, 0.0), (10.0, 0.0)]);
+        let to = vec![px(0.0, 0.0), PathElement::Close];
+        assert_eq!(
+            describe(&MorphingShape::interpolate(&from, &to, 0.25)),
+            describe(&from)
+        );
+        assert_eq!(
+            describe(&MorphingShape::interpolate(&from, &to, 0.75)),
+            describe(&to)
+        );
+    }
+}
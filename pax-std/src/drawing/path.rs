@@ -4,7 +4,8 @@ use pax_engine::api::PathElement;
 use pax_runtime::api::{borrow, borrow_mut, use_RefCell};
 use pax_runtime::api::{Color, Layer, RenderContext, Stroke};
 use pax_runtime::{
-    BaseInstance, ExpandedNode, InstanceFlags, InstanceNode, InstantiationArgs, RuntimeContext,
+    snap_to_device_pixels, BaseInstance, ExpandedNode, InstanceFlags, InstanceNode,
+    InstantiationArgs, RuntimeContext,
 };
 
 use crate::common::Point;
@@ -108,7 +109,7 @@ impl InstanceNode for PathInstance {
     fn render(
         &self,
         expanded_node: &ExpandedNode,
-        _rtc: &Rc<RuntimeContext>,
+        rtc: &Rc<RuntimeContext>,
         rc: &mut dyn RenderContext,
     ) {
         let layer_id = format!("{}", expanded_node.occlusion.get().occlusion_layer_id);
@@ -198,26 +199,20 @@ impl InstanceNode for PathInstance {
             rc.save(&layer_id);
             rc.clip(&layer_id, transformed_clip_path.clone());
             rc.fill(&layer_id, transformed_bez_path, &color.into());
-            if properties
-                .stroke
-                .get()
-                .width
-                .get()
-                .expect_pixels()
-                .to_float()
-                > f64::EPSILON
-            {
+            let stroke = properties.stroke.get();
+            let dpr = rtc.device_pixel_ratio();
+            let width = stroke.resolve_width(dpr);
+            if width > f64::EPSILON {
+                let stroke_path = if stroke.hairline.get() {
+                    snap_to_device_pixels(duplicate_transformed_bez_path, dpr)
+                } else {
+                    duplicate_transformed_bez_path
+                };
                 rc.stroke(
                     &layer_id,
-                    duplicate_transformed_bez_path,
-                    &properties.stroke.get().color.get().to_piet_color().into(),
-                    properties
-                        .stroke
-                        .get()
-                        .width
-                        .get()
-                        .expect_pixels()
-                        .to_float(),
+                    stroke_path,
+                    &stroke.color.get().to_piet_color().into(),
+                    width,
                 );
             }
             rc.restore(&layer_id);
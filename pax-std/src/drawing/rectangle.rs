@@ -3,7 +3,10 @@ use pax_runtime::{api::Fill, BaseInstance};
 use pax_runtime_api::use_RefCell;
 use piet::{LinearGradient, RadialGradient};
 
-use pax_runtime::{ExpandedNode, InstanceFlags, InstanceNode, InstantiationArgs, RuntimeContext};
+use pax_runtime::{
+    snap_to_device_pixels, ExpandedNode, InstanceFlags, InstanceNode, InstantiationArgs,
+    RuntimeContext,
+};
 
 use pax_runtime::api as pax_runtime_api;
 use pax_runtime::api::{Layer, RenderContext, Stroke};
@@ -44,7 +47,7 @@ impl InstanceNode for RectangleInstance {
     fn render(
         &self,
         expanded_node: &ExpandedNode,
-        _rtc: &Rc<RuntimeContext>,
+        rtc: &Rc<RuntimeContext>,
         rc: &mut dyn RenderContext,
     ) {
         let tab = expanded_node.transform_and_bounds.get();
@@ -87,18 +90,19 @@ impl InstanceNode for RectangleInstance {
             }
 
             //hack to address "phantom stroke" bug on Web
-            let width: f64 = properties
-                .stroke
-                .get()
-                .width
-                .get()
-                .expect_pixels()
-                .to_float();
+            let stroke = properties.stroke.get();
+            let dpr = rtc.device_pixel_ratio();
+            let width = stroke.resolve_width(dpr);
             if width > f64::EPSILON {
+                let stroke_path = if stroke.hairline.get() {
+                    snap_to_device_pixels(duplicate_transformed_bez_path, dpr)
+                } else {
+                    duplicate_transformed_bez_path
+                };
                 rc.stroke(
                     &layer_id,
-                    duplicate_transformed_bez_path,
-                    &properties.stroke.get().color.get().to_piet_color().into(),
+                    stroke_path,
+                    &stroke.color.get().to_piet_color().into(),
                     width,
                 );
             }
@@ -0,0 +1,3 @@
+pub mod shimmer;
+
+pub use shimmer::*;
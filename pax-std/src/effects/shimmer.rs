@@ -0,0 +1,128 @@
+#[allow(unused)]
+use crate::*;
+use pax_engine::api::*;
+use pax_engine::*;
+
+/// Frames one sweep of the stripe takes at a `speed` of 1.0, i.e. a second at 60fps
+const FRAMES_PER_SWEEP: f64 = 60.0;
+
+/// A bright stripe sweeping across its content, e.g. to highlight a new item or one that
+/// is loading:
+///
+/// ```pax
+/// <Shimmer width=200px height=40px speed=0.5>
+///     <Rectangle fill=rgb(30, 30, 30)/>
+/// </Shimmer>
+/// ```
+///
+/// `angle` is the direction the stripe travels in, in degrees clockwise from left to right,
+/// `stripe_width` the width of the stripe in percent of the content (`width` being the width of
+/// the `Shimmer` itself), and `speed` the number of sweeps per 60 frames.  The stripe is drawn on
+/// top of the content and doesn't receive events.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[inlined(
+    <Rectangle fill={self._fill} _raycastable=false/>
+    for i in 0..self._slot_children_count {
+        slot(i)
+    }
+    @settings {
+        @mount: on_mount
+    }
+)]
+#[custom(Default)]
+pub struct Shimmer {
+    pub color: Property<Color>,
+    pub speed: Property<f64>,
+    pub angle: Property<f64>,
+    pub stripe_width: Property<f64>,
+    pub _slot_children_count: Property<usize>,
+    pub _fill: Property<Fill>,
+}
+
+impl Default for Shimmer {
+    fn default() -> Self {
+        Self {
+            color: Property::new(Color::rgba(
+                255.into(),
+                255.into(),
+                255.into(),
+                ColorChannel::Percent(60.into()),
+            )),
+            speed: Property::new(0.5),
+            angle: Property::new(20.0),
+            stripe_width: Property::new(30.0),
+            _slot_children_count: Property::new(0),
+            _fill: Property::new(Fill::Solid(Color::TRANSPARENT)),
+        }
+    }
+}
+
+impl Shimmer {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        let slot_children_count = ctx.slot_children_count.clone();
+        let deps = [slot_children_count.untyped()];
+        self._slot_children_count
+            .replace_with(Property::computed(move || slot_children_count.get(), &deps));
+
+        let frames_elapsed = ctx.frames_elapsed.clone();
+        let color = self.color.clone();
+        let speed = self.speed.clone();
+        let angle = self.angle.clone();
+        let stripe_width = self.stripe_width.clone();
+        let deps = [
+            frames_elapsed.untyped(),
+            color.untyped(),
+            speed.untyped(),
+            angle.untyped(),
+            stripe_width.untyped(),
+        ];
+        self._fill.replace_with(Property::computed(
+            move || {
+                let speed = speed.get();
+                if speed <= 0.0 {
+                    return Fill::Solid(Color::TRANSPARENT);
+                }
+                let period = (FRAMES_PER_SWEEP / speed).max(1.0);
+                let progress = (frames_elapsed.get() as f64 % period) / period;
+                shimmer_fill(&color.get(), angle.get(), stripe_width.get(), progress)
+            },
+            &deps,
+        ));
+    }
+}
+
+/// The gradient drawing the stripe `progress` (0.0 to 1.0) of the way through a sweep.  The
+/// stripe starts and ends just outside the content, and fades out from its center to its edges
+fn shimmer_fill(color: &Color, angle: f64, stripe_width: f64, progress: f64) -> Fill {
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let point = |t: f64| {
+        (
+            Size::Percent((50.0 + t * cos * 50.0).into()),
+            Size::Percent((50.0 + t * sin * 50.0).into()),
+        )
+    };
+
+    let half_width = (stripe_width / 2.0).max(f64::EPSILON);
+    let center = -half_width + progress * (100.0 + 2.0 * half_width);
+    let [r, g, b, a] = color.to_rgba_0_1();
+    // stops must lie on the gradient line, so the ones outside it are clamped to its ends, with
+    // the color the stripe has there
+    let stops = [0.0, center - half_width, center, center + half_width, 100.0]
+        .into_iter()
+        .map(|position: f64| {
+            let position = position.clamp(0.0, 100.0);
+            let intensity = (1.0 - (position - center).abs() / half_width).max(0.0);
+            GradientStop::get(
+                Color::from_rgba_0_1([r, g, b, a * intensity]),
+                Size::Percent(position.into()),
+            )
+        })
+        .collect();
+
+    Fill::LinearGradient(LinearGradient {
+        start: point(-1.0),
+        end: point(1.0),
+        stops,
+    })
+}
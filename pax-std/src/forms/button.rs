@@ -93,7 +93,10 @@ impl InstanceNode for ButtonInstance {
             .values()
             .cloned()
             .map(|v| v.get_untyped_property().clone())
-            .chain([expanded_node.transform_and_bounds.untyped()])
+            .chain([
+                expanded_node.transform_and_bounds.untyped(),
+                expanded_node.resolved_text_style.untyped(),
+            ])
             .collect();
         expanded_node
             .native_message_listener
@@ -151,7 +154,11 @@ impl InstanceNode for ButtonInstance {
                             patch_if_needed(
                                 &mut old_state.style,
                                 &mut patch.style,
-                                (&properties.style.get()).into(),
+                                (&properties.style.get().layered_over(
+                                    expanded_node.resolved_text_style.get(),
+                                    &Button::default().style.get(),
+                                ))
+                                    .into(),
                             ),
                             patch_if_needed(&mut old_state.size_x, &mut patch.size_x, width),
                             patch_if_needed(&mut old_state.size_y, &mut patch.size_y, height),
@@ -1,17 +1,16 @@
 use crate::*;
 use pax_message::{AnyCreatePatch, DropdownPatch, NativeInterrupt};
 use pax_runtime::api as pax_runtime_api;
-use pax_runtime::api::{use_RefCell, Layer, Property};
+use pax_runtime::api::{Layer, Property};
 use pax_runtime::{
     BaseInstance, ExpandedNode, InstanceFlags, InstanceNode, InstantiationArgs, RuntimeContext,
 };
-use_RefCell!();
 use pax_runtime_api::*;
 
 use pax_engine::pax;
 use std::rc::Rc;
 
-use crate::common::patch_if_needed;
+use crate::common::PatchAccumulator;
 
 /// A platform-native dropdown list
 #[pax]
@@ -36,6 +35,7 @@ impl Default for Dropdown {
             stroke: Property::new(Stroke {
                 color: Property::new(Color::rgb(209.into(), 213.into(), 219.into())),
                 width: Property::new(Size::Pixels(1.into())),
+                ..Default::default()
             }),
             border_radius: Property::new(8.0.into()),
             style: Property::new(TextStyle {
@@ -88,91 +88,97 @@ impl InstanceNode for DropdownInstance {
             },
         ));
 
-        // send update message when relevant properties change
-        let weak_self_ref = Rc::downgrade(&expanded_node);
+        // send update message when relevant properties change, diffing only the
+        // fields whose properties changed
         let context = Rc::clone(context);
-        let last_patch = Rc::new(RefCell::new(DropdownPatch {
+        let tab = expanded_node.transform_and_bounds.clone();
+        let inherited_text_style = expanded_node.resolved_text_style.clone();
+        let (style, stroke, background, selected_id, border_radius, options) = expanded_node
+            .with_properties_unwrapped(|properties: &mut Dropdown| {
+                (
+                    properties.style.clone(),
+                    properties.stroke.clone(),
+                    properties.background.clone(),
+                    properties.selected_id.clone(),
+                    properties.border_radius.clone(),
+                    properties.options.clone(),
+                )
+            });
+        let listener = PatchAccumulator::new(DropdownPatch {
             id: id.to_u32(),
             ..Default::default()
-        }));
-
-        let deps: Vec<_> = borrow_mut!(expanded_node.properties_scope)
-            .values()
-            .cloned()
-            .map(|v| v.get_untyped_property().clone())
-            .chain([expanded_node.transform_and_bounds.untyped()])
-            .collect();
-        expanded_node
-            .native_message_listener
-            .replace_with(Property::computed(
-                move || {
-                    let Some(expanded_node) = weak_self_ref.upgrade() else {
-                        unreachable!()
-                    };
-                    let id = expanded_node.id.clone();
-                    let mut old_state = borrow_mut!(last_patch);
-
-                    let mut patch = DropdownPatch {
-                        id: id.to_u32(),
-                        ..Default::default()
-                    };
-                    expanded_node.with_properties_unwrapped(|properties: &mut Dropdown| {
-                        let computed_tab = expanded_node.transform_and_bounds.get();
-                        let (width, height) = computed_tab.bounds;
-                        let updates = [
-                            patch_if_needed(&mut old_state.size_x, &mut patch.size_x, width),
-                            patch_if_needed(&mut old_state.size_y, &mut patch.size_y, height),
-                            patch_if_needed(
-                                &mut old_state.transform,
-                                &mut patch.transform,
-                                computed_tab.transform.coeffs().to_vec(),
-                            ),
-                            patch_if_needed(
-                                &mut old_state.style,
-                                &mut patch.style,
-                                (&properties.style.get()).into(),
-                            ),
-                            patch_if_needed(
-                                &mut old_state.stroke_color,
-                                &mut patch.stroke_color,
-                                (&properties.stroke.get().color.get()).into(),
-                            ),
-                            patch_if_needed(
-                                &mut old_state.stroke_width,
-                                &mut patch.stroke_width,
-                                properties.stroke.get().width.get().get_pixels(width),
-                            ),
-                            patch_if_needed(
-                                &mut old_state.background,
-                                &mut patch.background,
-                                (&properties.background.get()).into(),
-                            ),
-                            patch_if_needed(
-                                &mut old_state.selected_id,
-                                &mut patch.selected_id,
-                                properties.selected_id.get(),
-                            ),
-                            patch_if_needed(
-                                &mut old_state.border_radius,
-                                &mut patch.border_radius,
-                                properties.border_radius.get(),
-                            ),
-                            patch_if_needed(
-                                &mut old_state.options,
-                                &mut patch.options,
-                                properties.options.get(),
-                            ),
-                        ];
-                        if updates.into_iter().any(|v| v == true) {
-                            context.enqueue_native_message(
-                                pax_message::NativeMessage::DropdownUpdate(patch),
-                            );
-                        }
-                    });
-                    ()
-                },
-                &deps,
-            ));
+        })
+        .field(
+            &[tab.untyped()],
+            {
+                let tab = tab.clone();
+                move || tab.get().bounds.0
+            },
+            |patch| &mut patch.size_x,
+        )
+        .field(
+            &[tab.untyped()],
+            {
+                let tab = tab.clone();
+                move || tab.get().bounds.1
+            },
+            |patch| &mut patch.size_y,
+        )
+        .field(
+            &[tab.untyped()],
+            {
+                let tab = tab.clone();
+                move || tab.get().transform.coeffs().to_vec()
+            },
+            |patch| &mut patch.transform,
+        )
+        .field(
+            &[style.untyped(), inherited_text_style.untyped()],
+            move || {
+                (&style
+                    .get()
+                    .layered_over(inherited_text_style.get(), &Dropdown::default().style.get()))
+                    .into()
+            },
+            |patch| &mut patch.style,
+        )
+        .field(
+            &[stroke.untyped()],
+            {
+                let stroke = stroke.clone();
+                move || (&stroke.get().color.get()).into()
+            },
+            |patch| &mut patch.stroke_color,
+        )
+        .field(
+            &[stroke.untyped(), tab.untyped()],
+            move || stroke.get().width.get().get_pixels(tab.get().bounds.0),
+            |patch| &mut patch.stroke_width,
+        )
+        .field(
+            &[background.untyped()],
+            move || (&background.get()).into(),
+            |patch| &mut patch.background,
+        )
+        .field(
+            &[selected_id.untyped()],
+            move || selected_id.get(),
+            |patch| &mut patch.selected_id,
+        )
+        .field(
+            &[border_radius.untyped()],
+            move || border_radius.get(),
+            |patch| &mut patch.border_radius,
+        )
+        .field(
+            &[options.untyped()],
+            move || options.get(),
+            |patch| &mut patch.options,
+        )
+        .build(move |patch| {
+            context.enqueue_native_message(pax_message::NativeMessage::DropdownUpdate(patch));
+        });
+        expanded_node.native_message_listener.replace_with(listener);
     }
 
     fn handle_unmount(&self, expanded_node: &Rc<ExpandedNode>, context: &Rc<RuntimeContext>) {
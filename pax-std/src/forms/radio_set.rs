@@ -35,6 +35,7 @@ impl Default for RadioSet {
             outline: Property::new(Stroke {
                 color: Property::new(Color::rgb(209.into(), 213.into(), 219.into())),
                 width: Property::new(Size::Pixels(1.into())),
+                ..Default::default()
             }),
             options: Property::new(vec!["option 1".to_string(), "option 2".to_string()]),
             selected_id: Property::new(0),
@@ -99,7 +100,10 @@ impl InstanceNode for RadioSetInstance {
             .values()
             .cloned()
             .map(|v| v.get_untyped_property().clone())
-            .chain([expanded_node.transform_and_bounds.untyped()])
+            .chain([
+                expanded_node.transform_and_bounds.untyped(),
+                expanded_node.resolved_text_style.untyped(),
+            ])
             .collect();
         expanded_node
             .native_message_listener
@@ -129,7 +133,11 @@ impl InstanceNode for RadioSetInstance {
                             patch_if_needed(
                                 &mut old_state.style,
                                 &mut patch.style,
-                                (&properties.style.get()).into(),
+                                (&properties.style.get().layered_over(
+                                    expanded_node.resolved_text_style.get(),
+                                    &RadioSet::default().style.get(),
+                                ))
+                                    .into(),
                             ),
                             patch_if_needed(
                                 &mut old_state.background,
@@ -1,16 +1,15 @@
-use pax_message::{AnyCreatePatch, NativeInterrupt, SliderPatch};
-use pax_runtime::api::{use_RefCell, Layer, Property};
+use pax_message::{AnyCreatePatch, NativeInterrupt, OrientationMessage, SliderPatch};
+use pax_runtime::api::{Layer, Property};
 use pax_runtime::{
     BaseInstance, ExpandedNode, InstanceFlags, InstanceNode, InstantiationArgs, RuntimeContext,
 };
 
-use_RefCell!();
 use pax_runtime::api::*;
 
 use pax_engine::pax;
 use std::rc::Rc;
 
-use crate::common::patch_if_needed;
+use crate::common::PatchAccumulator;
 
 /// A platform-native Slider control
 #[pax]
@@ -25,6 +24,25 @@ pub struct Slider {
     pub step: Property<f64>,
     pub min: Property<f64>,
     pub max: Property<f64>,
+    /// Axis along which the slider is drawn and dragged, horizontal by default
+    pub orientation: Property<Orientation>,
+}
+
+#[pax]
+#[engine_import_path("pax_engine")]
+pub enum Orientation {
+    #[default]
+    Horizontal,
+    Vertical,
+}
+
+impl Into<OrientationMessage> for &Orientation {
+    fn into(self) -> OrientationMessage {
+        match self {
+            Orientation::Horizontal => OrientationMessage::Horizontal,
+            Orientation::Vertical => OrientationMessage::Vertical,
+        }
+    }
 }
 
 impl Default for Slider {
@@ -37,6 +55,7 @@ impl Default for Slider {
             accent: Property::new(Color::rgb(27.into(), 100.into(), 242.into())),
             border_radius: Property::new(5.0),
             background: Property::new(Color::rgb(229.into(), 231.into(), 235.into())),
+            orientation: Property::new(Orientation::Horizontal),
         }
     }
 }
@@ -76,91 +95,84 @@ impl InstanceNode for SliderInstance {
             occlusion_layer_id: 0,
         }));
 
-        // send update message when relevant properties change
-        let weak_self_ref = Rc::downgrade(&expanded_node);
+        // send update message when relevant properties change, diffing only the
+        // fields whose properties changed
         let context = Rc::clone(context);
-        let last_patch = Rc::new(RefCell::new(SliderPatch {
+        let tab = expanded_node.transform_and_bounds.clone();
+        let (accent, value, step, min, max, border_radius, background, orientation) = expanded_node
+            .with_properties_unwrapped(|properties: &mut Slider| {
+                (
+                    properties.accent.clone(),
+                    properties.value.clone(),
+                    properties.step.clone(),
+                    properties.min.clone(),
+                    properties.max.clone(),
+                    properties.border_radius.clone(),
+                    properties.background.clone(),
+                    properties.orientation.clone(),
+                )
+            });
+        let listener = PatchAccumulator::new(SliderPatch {
             id: id.to_u32(),
             ..Default::default()
-        }));
-
-        let deps: Vec<_> = borrow_mut!(expanded_node.properties_scope)
-            .values()
-            .cloned()
-            .map(|v| v.get_untyped_property().clone())
-            .chain([expanded_node.transform_and_bounds.untyped()])
-            .collect();
-        expanded_node
-            .native_message_listener
-            .replace_with(Property::computed(
-                move || {
-                    let Some(expanded_node) = weak_self_ref.upgrade() else {
-                        unreachable!()
-                    };
-                    let id = expanded_node.id.clone();
-                    let mut old_state = borrow_mut!(last_patch);
-
-                    let mut patch = SliderPatch {
-                        id: id.to_u32(),
-                        ..Default::default()
-                    };
-                    expanded_node.with_properties_unwrapped(|properties: &mut Slider| {
-                        let computed_tab = expanded_node.transform_and_bounds.get();
-                        let (width, height) = computed_tab.bounds;
-                        let updates = [
-                            patch_if_needed(&mut old_state.size_x, &mut patch.size_x, width),
-                            patch_if_needed(&mut old_state.size_y, &mut patch.size_y, height),
-                            patch_if_needed(
-                                &mut old_state.transform,
-                                &mut patch.transform,
-                                computed_tab.transform.coeffs().to_vec(),
-                            ),
-                            patch_if_needed(
-                                &mut old_state.accent,
-                                &mut patch.accent,
-                                (&properties.accent.get()).into(),
-                            ),
-                            patch_if_needed(
-                                &mut old_state.value,
-                                &mut patch.value,
-                                properties.value.get(),
-                            ),
-                            patch_if_needed(
-                                &mut old_state.step,
-                                &mut patch.step,
-                                properties.step.get(),
-                            ),
-                            patch_if_needed(
-                                &mut old_state.min,
-                                &mut patch.min,
-                                properties.min.get(),
-                            ),
-                            patch_if_needed(
-                                &mut old_state.max,
-                                &mut patch.max,
-                                properties.max.get(),
-                            ),
-                            patch_if_needed(
-                                &mut old_state.border_radius,
-                                &mut patch.border_radius,
-                                properties.border_radius.get(),
-                            ),
-                            patch_if_needed(
-                                &mut old_state.background,
-                                &mut patch.background,
-                                (&properties.background.get()).into(),
-                            ),
-                        ];
-                        if updates.into_iter().any(|v| v == true) {
-                            context.enqueue_native_message(
-                                pax_message::NativeMessage::SliderUpdate(patch),
-                            );
-                        }
-                    });
-                    ()
-                },
-                &deps,
-            ));
+        })
+        .field(
+            &[tab.untyped()],
+            {
+                let tab = tab.clone();
+                move || tab.get().bounds.0
+            },
+            |patch| &mut patch.size_x,
+        )
+        .field(
+            &[tab.untyped()],
+            {
+                let tab = tab.clone();
+                move || tab.get().bounds.1
+            },
+            |patch| &mut patch.size_y,
+        )
+        .field(
+            &[tab.untyped()],
+            move || tab.get().transform.coeffs().to_vec(),
+            |patch| &mut patch.transform,
+        )
+        .field(
+            &[accent.untyped()],
+            move || (&accent.get()).into(),
+            |patch| &mut patch.accent,
+        )
+        .field(
+            &[value.untyped()],
+            move || value.get(),
+            |patch| &mut patch.value,
+        )
+        .field(
+            &[step.untyped()],
+            move || step.get(),
+            |patch| &mut patch.step,
+        )
+        .field(&[min.untyped()], move || min.get(), |patch| &mut patch.min)
+        .field(&[max.untyped()], move || max.get(), |patch| &mut patch.max)
+        .field(
+            &[border_radius.untyped()],
+            move || border_radius.get(),
+            |patch| &mut patch.border_radius,
+        )
+        .field(
+            &[background.untyped()],
+            move || (&background.get()).into(),
+            |patch| &mut patch.background,
+        )
+        .field(
+            &[orientation.untyped()],
+            move || (&orientation.get()).into(),
+            |patch| &mut patch.orientation,
+        )
+        .build(move |patch| {
+            context.enqueue_native_message(pax_message::NativeMessage::SliderUpdate(patch));
+        });
+        expanded_node.native_message_listener.replace_with(listener);
     }
 
     fn handle_unmount(&self, expanded_node: &Rc<ExpandedNode>, context: &Rc<RuntimeContext>) {
@@ -188,9 +200,15 @@ impl InstanceNode for SliderInstance {
         expanded_node: &Rc<ExpandedNode>,
         interrupt: &NativeInterrupt,
     ) {
+        // native controls report the value in the `min..=max` range along whichever axis they're
+        // drawn on, so the value maps the same way for both orientations
         if let NativeInterrupt::FormSliderChange(args) = interrupt {
-            expanded_node
-                .with_properties_unwrapped(|props: &mut Slider| props.value.set(args.value));
+            expanded_node.with_properties_unwrapped(|props: &mut Slider| {
+                let (min, max) = (props.min.get(), props.max.get());
+                props
+                    .value
+                    .set(args.value.clamp(min.min(max), max.max(min)))
+            });
         }
     }
 }
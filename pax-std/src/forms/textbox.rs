@@ -34,6 +34,7 @@ impl Default for Textbox {
             stroke: Property::new(Stroke {
                 color: Property::new(Color::rgb(209.into(), 213.into(), 219.into())),
                 width: Property::new(Size::Pixels(1.into())),
+                ..Default::default()
             }),
             border_radius: Property::new(8.0.into()),
             style: Property::new(TextStyle {
@@ -96,7 +97,10 @@ impl InstanceNode for TextboxInstance {
             .values()
             .cloned()
             .map(|v| v.get_untyped_property().clone())
-            .chain([expanded_node.transform_and_bounds.untyped()])
+            .chain([
+                expanded_node.transform_and_bounds.untyped(),
+                expanded_node.resolved_text_style.untyped(),
+            ])
             .collect();
         expanded_node
             .native_message_listener
@@ -131,7 +135,11 @@ impl InstanceNode for TextboxInstance {
                             patch_if_needed(
                                 &mut old_state.style,
                                 &mut patch.style,
-                                (&properties.style.get()).into(),
+                                (&properties.style.get().layered_over(
+                                    expanded_node.resolved_text_style.get(),
+                                    &Textbox::default().style.get(),
+                                ))
+                                    .into(),
                             ),
                             patch_if_needed(
                                 &mut old_state.stroke_color,
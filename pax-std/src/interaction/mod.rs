@@ -0,0 +1,3 @@
+pub mod ripple;
+
+pub use ripple::*;
@@ -0,0 +1,186 @@
+#[allow(unused)]
+use crate::*;
+use pax_engine::api::*;
+use pax_engine::math::Point2;
+use pax_engine::*;
+
+/// Ripples beyond this many at once replace the oldest
+const MAX_RIPPLES: usize = 8;
+
+/// Material Design style touch feedback: each click on the content spreads a circle of `color`
+/// from the point clicked, which grows to 1.4 times the larger side of the `RippleEffect` while
+/// fading out over `duration_frames`.
+///
+/// ```pax
+/// <RippleEffect width=120px height=40px color=rgba(255, 255, 255, 30%)>
+///     <Rectangle fill=BLUE/>
+/// </RippleEffect>
+/// ```
+///
+/// Ripples are clipped to the bounds of the `RippleEffect`, drawn on top of the content, and don't
+/// receive events, so that clicks still reach the content.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[inlined(
+    <Frame _raycastable=false>
+        for ripple in self._ripples {
+            <Ripple
+                x={(ripple.x)px}
+                y={(ripple.y)px}
+                width=0px
+                height=0px
+                started_at={ripple.started_at}
+                max_radius={ripple.max_radius}
+                duration_frames={self.duration_frames}
+                color={self.color}
+                _raycastable=false
+            />
+        }
+    </Frame>
+    for i in 0..self._slot_children_count {
+        slot(i)
+    }
+    <Rectangle fill=TRANSPARENT/>
+    @settings {
+        @mount: on_mount
+        @pre_render: pre_render
+        @click: on_click
+    }
+)]
+#[custom(Default)]
+pub struct RippleEffect {
+    pub color: Property<Color>,
+    pub duration_frames: Property<usize>,
+    pub _slot_children_count: Property<usize>,
+    pub _ripples: Property<Vec<RippleSpawn>>,
+}
+
+impl Default for RippleEffect {
+    fn default() -> Self {
+        Self {
+            color: Property::new(Color::rgba(
+                0.into(),
+                0.into(),
+                0.into(),
+                ColorChannel::Percent(20.into()),
+            )),
+            duration_frames: Property::new(36),
+            _slot_children_count: Property::new(0),
+            _ripples: Property::new(vec![]),
+        }
+    }
+}
+
+/// A ripple in progress, in the coordinates of its `RippleEffect`
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct RippleSpawn {
+    pub x: f64,
+    pub y: f64,
+    pub max_radius: f64,
+    /// `frames_elapsed` when the ripple was spawned
+    pub started_at: u64,
+}
+
+impl RippleEffect {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        let slot_children_count = ctx.slot_children_count.clone();
+        let deps = [slot_children_count.untyped()];
+        self._slot_children_count
+            .replace_with(Property::computed(move || slot_children_count.get(), &deps));
+    }
+
+    pub fn pre_render(&mut self, ctx: &NodeContext) {
+        let now = ctx.frames_elapsed.get();
+        let duration = self.duration_frames.get() as u64;
+        let finished = |ripple: &RippleSpawn| now >= ripple.started_at + duration;
+        if self._ripples.read(|ripples| ripples.iter().any(finished)) {
+            self._ripples
+                .update(|ripples| ripples.retain(|ripple| !finished(ripple)));
+        }
+    }
+
+    pub fn on_click(&mut self, ctx: &NodeContext, event: Event<Click>) {
+        let point = ctx.local_point(Point2::new(event.mouse.x, event.mouse.y));
+        let (width, height) = ctx.bounds_self.get();
+        let spawn = RippleSpawn {
+            x: point.x,
+            y: point.y,
+            max_radius: width.max(height) * 1.4,
+            started_at: ctx.frames_elapsed.get(),
+        };
+        self._ripples.update(|ripples| {
+            if ripples.len() >= MAX_RIPPLES {
+                ripples.remove(0);
+            }
+            ripples.push(spawn);
+        });
+    }
+}
+
+/// One expanding circle of a `RippleEffect`, centered on its origin
+#[pax]
+#[engine_import_path("pax_engine")]
+#[inlined(
+    <Ellipse
+        anchor_x=50%
+        anchor_y=50%
+        width={(self._diameter)px}
+        height={(self._diameter)px}
+        fill={self._fill}
+        _raycastable=false
+    />
+    @settings {
+        @mount: on_mount
+    }
+)]
+pub struct Ripple {
+    pub started_at: Property<u64>,
+    pub max_radius: Property<f64>,
+    pub duration_frames: Property<usize>,
+    pub color: Property<Color>,
+    pub _progress: Property<f64>,
+    pub _diameter: Property<f64>,
+    pub _fill: Property<Color>,
+}
+
+impl Ripple {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        // ripples are remounted whenever the ripples before them finish, so the transition
+        // resumes from how far along the ripple already is
+        let duration = (self.duration_frames.get() as u64).max(1);
+        let elapsed = ctx
+            .frames_elapsed
+            .get()
+            .saturating_sub(self.started_at.get())
+            .min(duration);
+        self._progress.set(elapsed as f64 / duration as f64);
+        if elapsed < duration {
+            self._progress
+                .ease_to(1.0, duration - elapsed, EasingCurve::Linear);
+        }
+
+        let progress = self._progress.clone();
+        let max_radius = self.max_radius.clone();
+        let deps = [progress.untyped(), max_radius.untyped()];
+        self._diameter.replace_with(Property::computed(
+            move || {
+                // fast at first, slowing down as it reaches its full size
+                let eased = 1.0 - (1.0 - progress.get()).powi(2);
+                2.0 * max_radius.get() * eased
+            },
+            &deps,
+        ));
+
+        let progress = self._progress.clone();
+        let color = self.color.clone();
+        let deps = [progress.untyped(), color.untyped()];
+        self._fill.replace_with(Property::computed(
+            move || {
+                let [r, g, b, a] = color.get().to_rgba_0_1();
+                Color::from_rgba_0_1([r, g, b, a * (1.0 - progress.get())])
+            },
+            &deps,
+        ));
+    }
+}
@@ -0,0 +1,968 @@
+#[allow(unused)]
+use crate::*;
+use pax_engine::api::{Axis, Property, Size, Store};
+use pax_engine::*;
+use pax_runtime::api::NodeContext;
+
+/// Grid lays out its children in the cells of a set of columns and rows, with a gap in between
+/// each column and each row.  Children are placed one per cell in row-major order, filling the
+/// columns of a row before moving to the next one; wrap a child in a [`GridItem`] to place it in a
+/// specific cell or to span it over several columns or rows:
+///
+/// ```pax
+/// <Grid columns=[TrackSize::Auto, TrackSize::Fraction(1)] column_gap=8px>
+///     <Text text="Name"/>
+///     <Textbox/>
+///     <GridItem grid_column="1 / 3"><Button label="Save"/></GridItem>
+/// </Grid>
+/// ```
+///
+/// Rows are added as needed to fit all children, sized `TrackSize::Auto` unless given in `rows`.
+/// `Auto` tracks are sized to the children in them that have a size of their own: a width or
+/// height in pixels, or the rendered size of text.
+///
+/// `GridItem`s find their grid through a local store, so a `Grid` can't be nested directly in the
+/// items of another `Grid` of the same template: move the inner one into its own component.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[custom(Default)]
+#[inlined(
+    for (cell_spec, i) in self._cell_specs {
+        <Group
+            transform={Transform2D::translate((cell_spec.x_px)px, (cell_spec.y_px)px)}
+            width={(cell_spec.width_px)px}
+            height={(cell_spec.height_px)px}
+        >
+            slot(i)
+        </Group>
+    }
+    <Rectangle fill=TRANSPARENT/>
+
+    @settings {
+        @mount: on_mount
+        @pre_render: pre_render
+    }
+
+)]
+pub struct Grid {
+    pub columns: Property<Vec<TrackSize>>,
+    pub rows: Property<Vec<TrackSize>>,
+    pub column_gap: Property<Size>,
+    pub row_gap: Property<Size>,
+
+    /// The computed positions of the columns and rows, e.g. for drawing overlays.  Set by the
+    /// grid, any value given to it is replaced
+    pub tracks: Property<GridTracks>,
+
+    /// Placement of each slot child, set by the children that are `GridItem`s
+    pub _items: Property<Vec<Option<GridItemSpec>>>,
+    pub _cell_specs: Property<Vec<StackerCell>>,
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self {
+            columns: Property::new(vec![TrackSize::Fraction(1.0)]),
+            rows: Property::new(vec![]),
+            column_gap: Property::new(Size::Pixels(0.into())),
+            row_gap: Property::new(Size::Pixels(0.into())),
+            tracks: Property::new(GridTracks::default()),
+            _items: Property::new(vec![]),
+            _cell_specs: Property::new(vec![]),
+        }
+    }
+}
+
+pub struct GridContext {
+    items: Property<Vec<Option<GridItemSpec>>>,
+    /// Size of each slot child, see [`NodeContext::measure_slot_children`]
+    measured: Property<Vec<(f64, f64)>>,
+}
+
+impl Store for GridContext {}
+
+impl Grid {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        let measured = Property::new(vec![]);
+        ctx.push_local_store(GridContext {
+            items: self._items.clone(),
+            measured: measured.clone(),
+        });
+
+        let columns = self.columns.clone();
+        let rows = self.rows.clone();
+        let column_gap = self.column_gap.clone();
+        let row_gap = self.row_gap.clone();
+        let items = self._items.clone();
+        let bounds = ctx.bounds_self.clone();
+        let slot_children_count = ctx.slot_children_count.clone();
+        let deps = [
+            columns.untyped(),
+            rows.untyped(),
+            column_gap.untyped(),
+            row_gap.untyped(),
+            items.untyped(),
+            measured.untyped(),
+            bounds.untyped(),
+            slot_children_count.untyped(),
+        ];
+        let layout = Property::computed_with_name(
+            move || {
+                compute_layout(
+                    &columns.get(),
+                    &rows.get(),
+                    column_gap.get(),
+                    row_gap.get(),
+                    &with_measured_sizes(&items.get(), &measured.get()),
+                    slot_children_count.get(),
+                    bounds.get(),
+                )
+            },
+            &deps,
+            "grid layout",
+        );
+
+        let deps = [layout.untyped()];
+        let tracks_layout = layout.clone();
+        self.tracks
+            .replace_with(Property::computed(move || tracks_layout.get().0, &deps));
+        //NOTE: replace with is needed since the for loop already has a connection to the prop
+        self._cell_specs.replace_with(Property::computed_with_name(
+            move || layout.get().1,
+            &deps,
+            "grid _cell_specs",
+        ));
+    }
+
+    pub fn pre_render(&mut self, ctx: &NodeContext) {
+        // rendered sizes aren't reactive to the slot children as a whole, so poll them
+        let Ok(measured) =
+            ctx.peek_local_store(|grid_ctx: &mut GridContext| grid_ctx.measured.clone())
+        else {
+            return;
+        };
+        let sizes = ctx.measure_slot_children((0.0, 0.0));
+        if measured.read(|measured| *measured != sizes) {
+            measured.set(sizes);
+        }
+    }
+}
+
+/// Places its content in a [`Grid`] it's a child of: in the column and row given by
+/// `grid_column` and `grid_row`, each either empty for the next free cell, a line like `"2"`, lines
+/// like `"1 / 3"` to span from the first to the third line (the first two tracks), or a span
+/// like `"span 2"` or `"2 / span 2"`.  Lines are numbered from 1, as in CSS.
+///
+/// `content_width` and `content_height` are the size of the content, used to size the `Auto`
+/// tracks the item is in.  Without them, the item is measured like its children are by the grid.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[inlined(
+    for i in 0..self._slots {
+        slot(i)
+    }
+
+    @settings {
+        @mount: on_mount
+        @pre_render: pre_render
+        @unmount: on_unmount
+    }
+)]
+pub struct GridItem {
+    pub grid_column: Property<String>,
+    pub grid_row: Property<String>,
+    pub content_width: Property<Option<Size>>,
+    pub content_height: Property<Option<Size>>,
+
+    pub _slots: Property<usize>,
+    pub _on_change: Property<bool>,
+    /// Size of the content, the largest of the slot children's
+    pub _measured_width: Property<f64>,
+    pub _measured_height: Property<f64>,
+}
+
+impl GridItem {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        let slot_children = ctx.slot_children_count.clone();
+        let deps = [slot_children.untyped()];
+        self._slots
+            .replace_with(Property::computed(move || slot_children.get(), &deps));
+
+        let Ok(items) = ctx.peek_local_store(|grid_ctx: &mut GridContext| grid_ctx.items.clone())
+        else {
+            log::warn!("GridItem can only be a child of a Grid");
+            return;
+        };
+        let grid_column = self.grid_column.clone();
+        let grid_row = self.grid_row.clone();
+        let content_width = self.content_width.clone();
+        let content_height = self.content_height.clone();
+        let measured_width = self._measured_width.clone();
+        let measured_height = self._measured_height.clone();
+        let id = ctx.slot_index.clone();
+        let deps = [
+            grid_column.untyped(),
+            grid_row.untyped(),
+            content_width.untyped(),
+            content_height.untyped(),
+            measured_width.untyped(),
+            measured_height.untyped(),
+            id.untyped(),
+        ];
+        self._on_change.replace_with(Property::computed(
+            move || {
+                let Some(id) = id.get() else {
+                    return false;
+                };
+                let placement = |value: String| {
+                    GridPlacement::parse(&value).unwrap_or_else(|e| {
+                        log::warn!("invalid grid placement \"{}\": {}", value, e);
+                        GridPlacement::default()
+                    })
+                };
+                let measured = |size: f64| (size > 0.0).then(|| Size::Pixels(size.into()));
+                let spec = GridItemSpec {
+                    column: placement(grid_column.get()),
+                    row: placement(grid_row.get()),
+                    content_width: content_width.get().or(measured(measured_width.get())),
+                    content_height: content_height.get().or(measured(measured_height.get())),
+                };
+                items.update(|items| {
+                    while items.len() < id + 1 {
+                        items.push(None)
+                    }
+                    items[id] = Some(spec);
+                });
+                false
+            },
+            &deps,
+        ));
+    }
+
+    pub fn on_unmount(&mut self, ctx: &NodeContext) {
+        let (Ok(items), Some(id)) = (
+            ctx.peek_local_store(|grid_ctx: &mut GridContext| grid_ctx.items.clone()),
+            ctx.slot_index.get(),
+        ) else {
+            return;
+        };
+        items.update(|items| {
+            if id < items.len() {
+                items[id] = None;
+            }
+        });
+    }
+
+    pub fn pre_render(&mut self, ctx: &NodeContext) {
+        let (width, height) = ctx
+            .measure_slot_children((0.0, 0.0))
+            .into_iter()
+            .fold((0.0, 0.0), |(w, h), (child_w, child_h)| {
+                (f64::max(w, child_w), f64::max(h, child_h))
+            });
+        if self._measured_width.get() != width {
+            self._measured_width.set(width);
+        }
+        if self._measured_height.get() != height {
+            self._measured_height.set(height);
+        }
+        // trigger dirty prop to fire closure
+        self._on_change.get();
+    }
+}
+
+/// Size of a column or row of a [`Grid`]
+#[pax]
+#[engine_import_path("pax_engine")]
+pub enum TrackSize {
+    /// Sized to the largest content of the children in the track, see [`Grid`], or sharing the
+    /// remaining space with the other `Auto` tracks if there are no `Fraction` ones
+    #[default]
+    Auto,
+    Pixels(f64),
+    /// Percentage of the grid's width for columns, or of its height for rows
+    Percent(f64),
+    /// A weighted share of the space left by the other tracks, like `fr` in CSS
+    Fraction(f64),
+}
+
+impl TrackSize {
+    /// Parses a space-separated list like `"auto 1fr 120px 25%"`
+    pub fn parse_list(value: &str) -> Result<Vec<TrackSize>, String> {
+        value
+            .split_whitespace()
+            .map(|track| {
+                let number = |suffix: &str| {
+                    track
+                        .trim_end_matches(suffix)
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|n| n.is_finite() && *n >= 0.0)
+                        .ok_or_else(|| format!("invalid track size \"{}\"", track))
+                };
+                if track.eq_ignore_ascii_case("auto") {
+                    Ok(TrackSize::Auto)
+                } else if track.ends_with("fr") {
+                    Ok(TrackSize::Fraction(number("fr")?))
+                } else if track.ends_with("px") {
+                    Ok(TrackSize::Pixels(number("px")?))
+                } else if track.ends_with('%') {
+                    Ok(TrackSize::Percent(number("%")?))
+                } else {
+                    Err(format!("invalid track size \"{}\"", track))
+                }
+            })
+            .collect()
+    }
+
+    /// The inverse of [`Self::parse_list`]
+    pub fn format_list(tracks: &[TrackSize]) -> String {
+        tracks
+            .iter()
+            .map(|track| match track {
+                TrackSize::Auto => "auto".to_string(),
+                TrackSize::Pixels(px) => format!("{}px", px),
+                TrackSize::Percent(percent) => format!("{}%", percent),
+                TrackSize::Fraction(fr) => format!("{}fr", fr),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// The list as a pax literal, e.g. `[TrackSize::Auto, TrackSize::Fraction(1)]`
+    pub fn to_pax_list(tracks: &[TrackSize]) -> String {
+        let tracks: Vec<_> = tracks
+            .iter()
+            .map(|track| match track {
+                TrackSize::Auto => "TrackSize::Auto".to_string(),
+                TrackSize::Pixels(px) => format!("TrackSize::Pixels({})", px),
+                TrackSize::Percent(percent) => format!("TrackSize::Percent({})", percent),
+                TrackSize::Fraction(fr) => format!("TrackSize::Fraction({})", fr),
+            })
+            .collect();
+        format!("[{}]", tracks.join(", "))
+    }
+}
+
+/// Placement of a [`GridItem`] along one axis
+#[pax]
+#[engine_import_path("pax_engine")]
+#[custom(Default)]
+pub struct GridPlacement {
+    /// Index of the first track, from 0, or `None` to place it in the next free cell
+    pub start: Option<usize>,
+    /// Number of tracks spanned, at least 1
+    pub span: usize,
+}
+
+impl Default for GridPlacement {
+    fn default() -> Self {
+        Self {
+            start: None,
+            span: 1,
+        }
+    }
+}
+
+impl GridPlacement {
+    /// Parses the value of `grid_column`/`grid_row`, see [`GridItem`]
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let line = |line: &str| match line.trim().parse::<usize>() {
+            Ok(line) if line >= 1 => Ok(line),
+            _ => Err(format!(
+                "expected a line number from 1, found \"{}\"",
+                line.trim()
+            )),
+        };
+        let span = |span: &str| {
+            let count = span.trim().strip_prefix("span")?.trim();
+            Some(match count.parse::<usize>() {
+                Ok(count) if count >= 1 => Ok(count),
+                _ => Err(format!(
+                    "expected a span of at least 1, found \"{}\"",
+                    count
+                )),
+            })
+        };
+
+        let value = value.trim();
+        if value.is_empty() || value.eq_ignore_ascii_case("auto") {
+            return Ok(Self::default());
+        }
+        if let Some(span) = span(value) {
+            return Ok(Self {
+                start: None,
+                span: span?,
+            });
+        }
+        let (start, end) = match value.split_once('/') {
+            Some((start, end)) => (start, Some(end)),
+            None => (value, None),
+        };
+        let start = line(start)?;
+        let span = match end {
+            None => 1,
+            Some(end) => match span(end) {
+                Some(span) => span?,
+                None => {
+                    let end = line(end)?;
+                    if end <= start {
+                        return Err(format!(
+                            "end line {} has to be after start line {}",
+                            end, start
+                        ));
+                    }
+                    end - start
+                }
+            },
+        };
+        Ok(Self {
+            start: Some(start - 1),
+            span,
+        })
+    }
+
+    /// The inverse of [`Self::parse`], empty for the default placement
+    pub fn to_placement_string(&self) -> String {
+        match (self.start, self.span) {
+            (None, 1) => String::new(),
+            (None, span) => format!("span {}", span),
+            (Some(start), 1) => format!("{}", start + 1),
+            (Some(start), span) => format!("{} / {}", start + 1, start + 1 + span),
+        }
+    }
+}
+
+/// Placement and content size of a [`GridItem`], as registered with its grid
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct GridItemSpec {
+    pub column: GridPlacement,
+    pub row: GridPlacement,
+    pub content_width: Option<Size>,
+    pub content_height: Option<Size>,
+}
+
+/// The computed columns and rows of a [`Grid`]
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct GridTracks {
+    pub columns: Vec<GridTrack>,
+    pub rows: Vec<GridTrack>,
+}
+
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct GridTrack {
+    /// Offset of the track from the start of the grid
+    pub start_px: f64,
+    pub size_px: f64,
+}
+
+/// An item placed on the grid, in track indices
+struct PlacedItem {
+    column: usize,
+    column_span: usize,
+    row: usize,
+    row_span: usize,
+}
+
+/// `items` with the measured size of each slot child as the content size of those without one
+fn with_measured_sizes(
+    items: &[Option<GridItemSpec>],
+    measured: &[(f64, f64)],
+) -> Vec<Option<GridItemSpec>> {
+    (0..items.len().max(measured.len()))
+        .map(|i| {
+            let spec = items.get(i).cloned().flatten().unwrap_or_default();
+            let (width, height) = measured.get(i).copied().unwrap_or_default();
+            let measured = |size: f64| (size > 0.0).then(|| Size::Pixels(size.into()));
+            Some(GridItemSpec {
+                content_width: spec.content_width.or(measured(width)),
+                content_height: spec.content_height.or(measured(height)),
+                ..spec
+            })
+        })
+        .collect()
+}
+
+fn compute_layout(
+    columns: &[TrackSize],
+    rows: &[TrackSize],
+    column_gap: Size,
+    row_gap: Size,
+    items: &[Option<GridItemSpec>],
+    count: usize,
+    bounds: (f64, f64),
+) -> (GridTracks, Vec<StackerCell>) {
+    let default_spec = GridItemSpec::default();
+    let specs: Vec<&GridItemSpec> = (0..count)
+        .map(|i| {
+            items
+                .get(i)
+                .and_then(Option::as_ref)
+                .unwrap_or(&default_spec)
+        })
+        .collect();
+
+    let mut column_sizes = columns.to_vec();
+    if column_sizes.is_empty() {
+        column_sizes.push(TrackSize::Fraction(1.0));
+    }
+    let placed = place_items(&specs, column_sizes.len());
+    let row_count = placed
+        .iter()
+        .map(|item| item.row + item.row_span)
+        .max()
+        .unwrap_or(0)
+        .max(rows.len());
+    let mut row_sizes = rows.to_vec();
+    row_sizes.resize(row_count, TrackSize::Auto);
+
+    let column_contents: Vec<_> = placed
+        .iter()
+        .zip(&specs)
+        .map(|(item, spec)| {
+            let size = spec.content_width.map(|s| s.evaluate(bounds, Axis::X));
+            (item.column, item.column_span, size)
+        })
+        .collect();
+    let row_contents: Vec<_> = placed
+        .iter()
+        .zip(&specs)
+        .map(|(item, spec)| {
+            let size = spec.content_height.map(|s| s.evaluate(bounds, Axis::Y));
+            (item.row, item.row_span, size)
+        })
+        .collect();
+    let column_gap = column_gap.evaluate(bounds, Axis::X);
+    let row_gap = row_gap.evaluate(bounds, Axis::Y);
+    let column_tracks = resolve_tracks(&column_sizes, bounds.0, column_gap, &column_contents);
+    let row_tracks = resolve_tracks(&row_sizes, bounds.1, row_gap, &row_contents);
+
+    let extent = |tracks: &[GridTrack], start: usize, span: usize| {
+        let first = &tracks[start];
+        let last = &tracks[start + span - 1];
+        (
+            first.start_px,
+            last.start_px + last.size_px - first.start_px,
+        )
+    };
+    let cells = placed
+        .iter()
+        .map(|item| {
+            let (x_px, width_px) = extent(&column_tracks, item.column, item.column_span);
+            let (y_px, height_px) = extent(&row_tracks, item.row, item.row_span);
+            StackerCell {
+                x_px,
+                y_px,
+                width_px,
+                height_px,
+            }
+        })
+        .collect();
+    (
+        GridTracks {
+            columns: column_tracks,
+            rows: row_tracks,
+        },
+        cells,
+    )
+}
+
+/// Places items with a column and a row first, then the others in the first free cells, row by
+/// row.  Items with only a row go in its first free column, or the first row after it with one.
+/// Rows are added as needed; spans are clamped to the columns of the grid.
+fn place_items(specs: &[&GridItemSpec], column_count: usize) -> Vec<PlacedItem> {
+    let mut occupied: Vec<Vec<bool>> = vec![];
+    let is_free = |occupied: &mut Vec<Vec<bool>>, item: &PlacedItem, take: bool| {
+        while occupied.len() < item.row + item.row_span {
+            occupied.push(vec![false; column_count]);
+        }
+        let cells = || {
+            (item.row..item.row + item.row_span)
+                .flat_map(|r| (item.column..item.column + item.column_span).map(move |c| (r, c)))
+        };
+        if cells().any(|(r, c)| occupied[r][c]) {
+            return false;
+        }
+        if take {
+            cells().for_each(|(r, c)| occupied[r][c] = true);
+        }
+        true
+    };
+    let clamp = |spec: &GridItemSpec| {
+        let column_span = spec.column.span.clamp(1, column_count);
+        let column = spec.column.start.map(|c| c.min(column_count - column_span));
+        (column, column_span, spec.row.start, spec.row.span.max(1))
+    };
+
+    let mut placed: Vec<Option<PlacedItem>> = specs.iter().map(|_| None).collect();
+    for (i, spec) in specs.iter().enumerate() {
+        if let (Some(column), column_span, Some(row), row_span) = clamp(spec) {
+            let item = PlacedItem {
+                column,
+                column_span,
+                row,
+                row_span,
+            };
+            is_free(&mut occupied, &item, true);
+            placed[i] = Some(item);
+        }
+    }
+
+    let mut cursor = (0, 0);
+    for (i, spec) in specs.iter().enumerate() {
+        if placed[i].is_some() {
+            continue;
+        }
+        let (column, column_span, row, row_span) = clamp(spec);
+        let item_at = |row, column| PlacedItem {
+            column,
+            column_span,
+            row,
+            row_span,
+        };
+        let columns_from = |from: usize| match column {
+            Some(column) => (column..column + 1)
+                .filter(|&c| c >= from)
+                .collect::<Vec<_>>(),
+            None => (from..=column_count - column_span).collect(),
+        };
+        let item = if let Some(row) = row {
+            // the row is given: take its first free column, moving down while it's full
+            (row..)
+                .find_map(|r| {
+                    columns_from(0)
+                        .into_iter()
+                        .map(|c| item_at(r, c))
+                        .find(|item| is_free(&mut occupied, item, true))
+                })
+                .unwrap()
+        } else {
+            let mut r = cursor.0;
+            let mut from = cursor.1;
+            loop {
+                let free = columns_from(from)
+                    .into_iter()
+                    .map(|c| item_at(r, c))
+                    .find(|item| is_free(&mut occupied, item, true));
+                if let Some(item) = free {
+                    cursor = (item.row, item.column + item.column_span);
+                    break item;
+                }
+                r += 1;
+                from = 0;
+            }
+        };
+        placed[i] = Some(item);
+    }
+    placed.into_iter().flatten().collect()
+}
+
+/// Resolves the sizes of `tracks` along an axis of `available` pixels.  `contents` is the
+/// (start, span, content size) of each item along the axis.
+///
+/// Fixed tracks are resolved first, then `Auto` tracks in two passes: to the content of the items
+/// in just that track, then grown to fit the items spanning several tracks.  `Fraction` tracks
+/// share what's left, or `Auto` tracks do if there are none.
+fn resolve_tracks(
+    tracks: &[TrackSize],
+    available: f64,
+    gap: f64,
+    contents: &[(usize, usize, Option<f64>)],
+) -> Vec<GridTrack> {
+    let mut sizes: Vec<f64> = tracks
+        .iter()
+        .map(|track| match track {
+            TrackSize::Pixels(px) => *px,
+            TrackSize::Percent(percent) => available * percent / 100.0,
+            TrackSize::Auto | TrackSize::Fraction(_) => 0.0,
+        })
+        .collect();
+    let is_auto = |i: usize| matches!(tracks[i], TrackSize::Auto);
+    let is_fraction = |i: usize| matches!(tracks[i], TrackSize::Fraction(_));
+
+    for &(start, span, content) in contents {
+        if let (1, Some(content)) = (span, content) {
+            if is_auto(start) {
+                sizes[start] = sizes[start].max(content);
+            }
+        }
+    }
+    for &(start, span, content) in contents {
+        let (Some(content), true) = (content, span > 1) else {
+            continue;
+        };
+        let spanned = start..start + span;
+        if spanned.clone().any(is_fraction) {
+            continue;
+        }
+        let autos: Vec<usize> = spanned.clone().filter(|&i| is_auto(i)).collect();
+        let current: f64 = spanned.map(|i| sizes[i]).sum::<f64>() + gap * (span - 1) as f64;
+        if !autos.is_empty() && content > current {
+            let extra = (content - current) / autos.len() as f64;
+            for i in autos {
+                sizes[i] += extra;
+            }
+        }
+    }
+
+    let gaps = gap * tracks.len().saturating_sub(1) as f64;
+    let remaining = (available - gaps - sizes.iter().sum::<f64>()).max(0.0);
+    let total_fraction: f64 = tracks
+        .iter()
+        .map(|track| match track {
+            TrackSize::Fraction(fr) => *fr,
+            _ => 0.0,
+        })
+        .sum();
+    if total_fraction > 0.0 {
+        for (i, track) in tracks.iter().enumerate() {
+            if let TrackSize::Fraction(fr) = track {
+                sizes[i] = remaining * fr / total_fraction;
+            }
+        }
+    } else {
+        let auto_count = (0..tracks.len()).filter(|&i| is_auto(i)).count();
+        for i in (0..tracks.len()).filter(|&i| is_auto(i)) {
+            sizes[i] += remaining / auto_count as f64;
+        }
+    }
+
+    let mut start_px = 0.0;
+    sizes
+        .into_iter()
+        .map(|size_px| {
+            let track = GridTrack { start_px, size_px };
+            start_px += size_px + gap;
+            track
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(column: &str, row: &str) -> GridItemSpec {
+        GridItemSpec {
+            column: GridPlacement::parse(column).unwrap(),
+            row: GridPlacement::parse(row).unwrap(),
+            ..Default::default()
+        }
+    }
+
+    /// (column, column span, row, row span) of each item
+    fn place(specs: &[GridItemSpec], column_count: usize) -> Vec<(usize, usize, usize, usize)> {
+        let specs: Vec<_> = specs.iter().collect();
+        place_items(&specs, column_count)
+            .into_iter()
+            .map(|item| (item.column, item.column_span, item.row, item.row_span))
+            .collect()
+    }
+
+    /// (start, size) of each track
+    fn resolve(
+        tracks: &[TrackSize],
+        available: f64,
+        gap: f64,
+        contents: &[(usize, usize, Option<f64>)],
+    ) -> Vec<(f64, f64)> {
+        resolve_tracks(tracks, available, gap, contents)
+            .into_iter()
+            .map(|track| (track.start_px, track.size_px))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_track_sizes() {
+        let parse = |value: &str| TrackSize::parse_list(value).map(|t| TrackSize::format_list(&t));
+        assert_eq!(
+            parse("auto 1fr  120px 25% AUTO 0.5fr").unwrap(),
+            "auto 1fr 120px 25% auto 0.5fr"
+        );
+        assert_eq!(
+            TrackSize::to_pax_list(&TrackSize::parse_list("auto 1fr 12.5px").unwrap()),
+            "[TrackSize::Auto, TrackSize::Fraction(1), TrackSize::Pixels(12.5)]"
+        );
+        assert_eq!(parse("").unwrap(), "");
+        for invalid in ["12em", "-1px", "fr", "1 fr", "NaNpx", "infpx"] {
+            assert!(parse(invalid).is_err(), "\"{}\" should be invalid", invalid);
+        }
+    }
+
+    #[test]
+    fn test_parse_placements() {
+        let placement = |value: &str| {
+            let placement = GridPlacement::parse(value).unwrap();
+            (placement.start, placement.span)
+        };
+        assert_eq!(placement(""), (None, 1));
+        assert_eq!(placement(" auto "), (None, 1));
+        assert_eq!(placement("2"), (Some(1), 1));
+        assert_eq!(placement("1 / 3"), (Some(0), 2));
+        assert_eq!(placement("span 2"), (None, 2));
+        assert_eq!(placement("2 / span 3"), (Some(1), 3));
+        for invalid in ["0", "3 / 2", "2 / 2", "span 0", "span", "x", "1 / x"] {
+            assert!(
+                GridPlacement::parse(invalid).is_err(),
+                "\"{}\" should be invalid",
+                invalid
+            );
+        }
+        for value in ["", "2", "1 / 3", "span 2", "2 / 4"] {
+            let parsed = GridPlacement::parse(value).unwrap();
+            assert_eq!(parsed.to_placement_string(), value);
+        }
+    }
+
+    #[test]
+    fn test_place_items_in_row_major_order() {
+        let items = [spec("", ""), spec("", ""), spec("", "")];
+        assert_eq!(
+            place(&items, 2),
+            vec![(0, 1, 0, 1), (1, 1, 0, 1), (0, 1, 1, 1)]
+        );
+    }
+
+    #[test]
+    fn test_place_items_with_spans() {
+        // a span that doesn't fit in what's left of a row moves to the next one
+        let items = [
+            spec("", ""),
+            spec("", ""),
+            spec("span 2", ""),
+            spec("", "span 2"),
+            spec("", ""),
+        ];
+        assert_eq!(
+            place(&items, 3),
+            vec![
+                (0, 1, 0, 1),
+                (1, 1, 0, 1),
+                (0, 2, 1, 1),
+                (2, 1, 1, 2),
+                (0, 1, 2, 1)
+            ]
+        );
+        // spans are clamped to the columns of the grid
+        assert_eq!(place(&[spec("2 / span 5", "")], 3), vec![(0, 3, 0, 1)]);
+    }
+
+    #[test]
+    fn test_place_items_with_explicit_columns_and_rows() {
+        // items with a column and a row are placed first, the others flow around them
+        let items = [spec("", ""), spec("", ""), spec("1", "1")];
+        assert_eq!(
+            place(&items, 2),
+            vec![(1, 1, 0, 1), (0, 1, 1, 1), (0, 1, 0, 1)]
+        );
+        // an item with only a column goes in the next row with that column free
+        let items = [spec("", ""), spec("1", ""), spec("2", "")];
+        assert_eq!(
+            place(&items, 2),
+            vec![(0, 1, 0, 1), (0, 1, 1, 1), (1, 1, 1, 1)]
+        );
+    }
+
+    #[test]
+    fn test_place_items_in_full_rows() {
+        // an item with only a row moves down to the next row with a free column instead of
+        // overlapping the full one
+        let items = [
+            spec("", ""),
+            spec("", ""),
+            spec("1 / 3", "2"),
+            spec("", "1"),
+        ];
+        assert_eq!(
+            place(&items, 2),
+            vec![(0, 1, 0, 1), (1, 1, 0, 1), (0, 2, 1, 1), (0, 1, 2, 1)]
+        );
+    }
+
+    #[test]
+    fn test_resolve_fixed_and_fraction_tracks() {
+        let tracks = [
+            TrackSize::Pixels(100.0),
+            TrackSize::Percent(25.0),
+            TrackSize::Fraction(1.0),
+            TrackSize::Fraction(3.0),
+        ];
+        // 1000 - 3 gaps of 10 - 100 - 250 leaves 620 for the fractions
+        assert_eq!(
+            resolve(&tracks, 1000.0, 10.0, &[]),
+            vec![(0.0, 100.0), (110.0, 250.0), (370.0, 155.0), (535.0, 465.0)]
+        );
+        // fractions don't go below zero when fixed tracks overflow
+        let tracks = [TrackSize::Pixels(300.0), TrackSize::Fraction(1.0)];
+        assert_eq!(
+            resolve(&tracks, 200.0, 0.0, &[]),
+            vec![(0.0, 300.0), (300.0, 0.0)]
+        );
+    }
+
+    #[test]
+    fn test_resolve_auto_tracks() {
+        // `Auto` tracks take the size of their content, fractions the rest
+        let tracks = [TrackSize::Auto, TrackSize::Fraction(1.0)];
+        let contents = [(0, 1, Some(60.0)), (0, 1, Some(80.0)), (1, 1, Some(500.0))];
+        assert_eq!(
+            resolve(&tracks, 500.0, 20.0, &contents),
+            vec![(0.0, 80.0), (100.0, 400.0)]
+        );
+        // without fractions, `Auto` tracks share what's left
+        let tracks = [TrackSize::Auto, TrackSize::Auto, TrackSize::Pixels(50.0)];
+        assert_eq!(
+            resolve(&tracks, 300.0, 0.0, &[(0, 1, Some(100.0)), (1, 1, None)]),
+            vec![(0.0, 175.0), (175.0, 75.0), (250.0, 50.0)]
+        );
+        // items spanning several `Auto` tracks grow them evenly, gaps included
+        let tracks = [TrackSize::Auto, TrackSize::Auto, TrackSize::Fraction(1.0)];
+        let contents = [(0, 1, Some(40.0)), (0, 2, Some(150.0))];
+        assert_eq!(
+            resolve(&tracks, 400.0, 10.0, &contents),
+            vec![(0.0, 90.0), (100.0, 50.0), (160.0, 240.0)]
+        );
+        // but not if they span a fraction, which grows instead
+        let contents = [(1, 2, Some(1000.0))];
+        assert_eq!(
+            resolve(&tracks, 400.0, 10.0, &contents),
+            vec![(0.0, 0.0), (10.0, 0.0), (20.0, 380.0)]
+        );
+    }
+
+    #[test]
+    fn test_auto_columns_fit_measured_children() {
+        // a plain child measured 60px wide, e.g. auto sized text, sizes its `Auto` column, and a
+        // `GridItem`'s content size takes precedence over its measured size
+        let columns = [TrackSize::Auto, TrackSize::Fraction(1.0)];
+        let items = [
+            None,
+            None,
+            Some(GridItemSpec {
+                content_width: Some(Size::Pixels(90.into())),
+                ..Default::default()
+            }),
+        ];
+        let measured = [(60.0, 20.0), (0.0, 0.0), (0.0, 0.0)];
+        let (tracks, cells) = compute_layout(
+            &columns,
+            &[],
+            Size::Pixels(10.into()),
+            Size::Pixels(0.into()),
+            &with_measured_sizes(&items, &measured),
+            3,
+            (300.0, 100.0),
+        );
+        let sizes = |tracks: &[GridTrack]| tracks.iter().map(|t| t.size_px).collect::<Vec<_>>();
+        assert_eq!(sizes(&tracks.columns), vec![90.0, 200.0]);
+        // the first row fits the measured height, and the rows share what's left
+        assert_eq!(sizes(&tracks.rows), vec![60.0, 40.0]);
+        assert_eq!(
+            (cells[1].x_px, cells[1].width_px, cells[2].y_px),
+            (100.0, 200.0, 60.0)
+        );
+    }
+}
@@ -0,0 +1,374 @@
+#[allow(unused)]
+use crate::*;
+use pax_engine::api::*;
+use pax_engine::math::Point2;
+use pax_engine::*;
+
+/// Space between columns, and between a column's edges and its cards
+const GAP: f64 = 10.0;
+/// Height of the column titles
+const HEADER_HEIGHT: f64 = 36.0;
+const CARD_HEIGHT: f64 = 72.0;
+/// Distance the pointer has to move before a pressed card starts being dragged
+const DRAG_THRESHOLD: f64 = 4.0;
+
+/// A board of cards in columns, where cards can be dragged within and
+/// between columns. `columns` is updated when a card is dropped, and moving
+/// a card to another column fires the `card_move` event, with the details of
+/// the move in `last_move`.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[custom(Default)]
+#[inlined(
+    <Group @mouse_down=self.mouse_down @mouse_move=self.mouse_move @mouse_up=self.mouse_up>
+        for card in self._cards {
+            <Group
+                x={(card.x)px}
+                y={(card.y)px}
+                width={(card.width)px}
+                height={(card.height)px}
+                anchor_x=0%
+                anchor_y=0%
+            >
+                <Text x=12px y=8px width={100% - 20px} height=20px text={card.title} id=card_title/>
+                <Text x=12px y=30px width={100% - 20px} height={100% - 38px} text={card.description} id=card_description/>
+                <Rectangle width=4px fill={card.color} corner_radii={self._accent_corner_radii}/>
+                <Rectangle fill={self.card_background} stroke={self.card_stroke} corner_radii={self._card_corner_radii}/>
+            </Group>
+        }
+        for column in self._columns {
+            <Group x={(column.x)px} width={(column.width)px} anchor_x=0%>
+                <Text x=10px y=0px width={100% - 20px} height=36px text={column.title} id=column_title/>
+                <Rectangle fill={self.column_background} corner_radii={self._card_corner_radii}/>
+            </Group>
+        }
+        <Rectangle fill=TRANSPARENT/>
+    </Group>
+
+    @settings {
+        @mount: on_mount
+        #column_title {
+            selectable: false,
+            style: {
+                font: {Font::Web(
+                    "ff-real-headline-pro",
+                    "https://use.typekit.net/ivu7epf.css",
+                    FontStyle::Normal,
+                    FontWeight::Bold,
+                )},
+                font_size: 14px,
+                fill: rgb(50, 50, 50),
+                align_vertical: TextAlignVertical::Center,
+                align_horizontal: TextAlignHorizontal::Left,
+            }
+        }
+        #card_title {
+            selectable: false,
+            style: {
+                font: {Font::Web(
+                    "ff-real-headline-pro",
+                    "https://use.typekit.net/ivu7epf.css",
+                    FontStyle::Normal,
+                    FontWeight::Normal,
+                )},
+                font_size: 14px,
+                fill: rgb(30, 30, 30),
+                align_vertical: TextAlignVertical::Top,
+                align_horizontal: TextAlignHorizontal::Left,
+            }
+        }
+        #card_description {
+            selectable: false,
+            style: {
+                font: {Font::Web(
+                    "ff-real-headline-pro",
+                    "https://use.typekit.net/ivu7epf.css",
+                    FontStyle::Normal,
+                    FontWeight::Light,
+                )},
+                font_size: 12px,
+                fill: rgb(90, 90, 90),
+                align_vertical: TextAlignVertical::Top,
+                align_horizontal: TextAlignHorizontal::Left,
+            }
+        }
+    }
+)]
+pub struct KanbanBoard {
+    pub columns: Property<Vec<KanbanColumn>>,
+    /// The last card moved to another column, set before firing `card_move`
+    pub last_move: Property<Option<KanbanCardMove>>,
+    pub column_background: Property<Color>,
+    pub card_background: Property<Color>,
+    pub card_stroke: Property<Stroke>,
+    pub card_border_radius: Property<f64>,
+
+    // private
+    pub _columns: Property<Vec<KanbanColumnSpec>>,
+    pub _cards: Property<Vec<KanbanCardSpec>>,
+    pub _drag: Property<Option<KanbanDrag>>,
+    pub _card_corner_radii: Property<RectangleCornerRadii>,
+    pub _accent_corner_radii: Property<RectangleCornerRadii>,
+}
+
+impl Default for KanbanBoard {
+    fn default() -> Self {
+        Self {
+            columns: Default::default(),
+            last_move: Default::default(),
+            column_background: Property::new(Color::rgb(235.into(), 236.into(), 240.into())),
+            card_background: Property::new(Color::WHITE),
+            card_stroke: Property::new(Stroke {
+                color: Property::new(Color::rgb(210.into(), 212.into(), 218.into())),
+                width: Property::new(Size::Pixels(1.into())),
+                ..Default::default()
+            }),
+            card_border_radius: Property::new(6.0),
+            _columns: Default::default(),
+            _cards: Default::default(),
+            _drag: Default::default(),
+            _card_corner_radii: Default::default(),
+            _accent_corner_radii: Default::default(),
+        }
+    }
+}
+
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct KanbanColumn {
+    pub title: String,
+    pub cards: Vec<KanbanCard>,
+}
+
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct KanbanCard {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub color: Color,
+}
+
+/// A card moved from the column at index `from_column` of `columns` to the
+/// one at `to_column`
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct KanbanCardMove {
+    pub card_id: String,
+    pub from_column: usize,
+    pub to_column: usize,
+}
+
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct KanbanColumnSpec {
+    pub x: f64,
+    pub width: f64,
+    pub title: String,
+}
+
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct KanbanCardSpec {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub title: String,
+    pub description: String,
+    pub color: Color,
+}
+
+/// A pressed card, dragged once the pointer moved past `DRAG_THRESHOLD`
+#[pax]
+#[engine_import_path("pax_engine")]
+pub struct KanbanDrag {
+    pub column: usize,
+    pub index: usize,
+    pub start_x: f64,
+    pub start_y: f64,
+    pub x: f64,
+    pub y: f64,
+    pub dragging: bool,
+}
+
+impl KanbanBoard {
+    pub fn on_mount(&mut self, ctx: &NodeContext) {
+        let columns = self.columns.clone();
+        let bounds = ctx.bounds_self.clone();
+        let deps = [columns.untyped(), bounds.untyped()];
+        self._columns.replace_with(Property::computed(
+            move || {
+                let columns = columns.get();
+                let width = column_width(columns.len(), bounds.get().0);
+                columns
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, column)| KanbanColumnSpec {
+                        x: column_x(i, width),
+                        width,
+                        title: column.title,
+                    })
+                    .collect()
+            },
+            &deps,
+        ));
+
+        let columns = self.columns.clone();
+        let drag = self._drag.clone();
+        let bounds = ctx.bounds_self.clone();
+        let deps = [columns.untyped(), drag.untyped(), bounds.untyped()];
+        self._cards.replace_with(Property::computed(
+            move || {
+                let columns = columns.get();
+                let width = column_width(columns.len(), bounds.get().0);
+                let card_width = (width - 2.0 * GAP).max(0.0);
+                let drag = drag.get().filter(|d| d.dragging);
+                let mut cards = vec![];
+                for (i, column) in columns.into_iter().enumerate() {
+                    for (j, card) in column.cards.into_iter().enumerate() {
+                        let (mut x, mut y) = (column_x(i, width) + GAP, card_y(j));
+                        let dragged = drag.as_ref().is_some_and(|d| d.column == i && d.index == j);
+                        if let Some(drag) = drag.as_ref().filter(|_| dragged) {
+                            x += drag.x - drag.start_x;
+                            y += drag.y - drag.start_y;
+                        }
+                        let spec = KanbanCardSpec {
+                            x,
+                            y,
+                            width: card_width,
+                            height: CARD_HEIGHT,
+                            title: card.title,
+                            description: card.description,
+                            color: card.color,
+                        };
+                        // the first card is drawn on top
+                        if dragged {
+                            cards.insert(0, spec);
+                        } else {
+                            cards.push(spec);
+                        }
+                    }
+                }
+                cards
+            },
+            &deps,
+        ));
+
+        let radius = self.card_border_radius.clone();
+        let deps = [radius.untyped()];
+        let radius_cp = radius.clone();
+        self._card_corner_radii.replace_with(Property::computed(
+            move || {
+                let r = radius_cp.get().into();
+                RectangleCornerRadii::radii(r, r, r, r)
+            },
+            &deps,
+        ));
+        self._accent_corner_radii.replace_with(Property::computed(
+            move || {
+                let r = radius.get();
+                RectangleCornerRadii::radii(r.into(), 0.into(), 0.into(), r.into())
+            },
+            &deps,
+        ));
+    }
+
+    pub fn mouse_down(&mut self, ctx: &NodeContext, event: Event<MouseDown>) {
+        let point = ctx.local_point(Point2::new(event.mouse.x, event.mouse.y));
+        let columns = self.columns.get();
+        let width = column_width(columns.len(), ctx.bounds_self.get().0);
+        let Some(column) = column_at(point.x, width, columns.len()) else {
+            return;
+        };
+        let x = point.x - column_x(column, width);
+        let y = point.y - HEADER_HEIGHT;
+        let index = (y / (CARD_HEIGHT + GAP)) as usize;
+        let on_card = x >= GAP
+            && x <= width - GAP
+            && y >= 0.0
+            && y % (CARD_HEIGHT + GAP) <= CARD_HEIGHT
+            && index < columns[column].cards.len();
+        if on_card {
+            self._drag.set(Some(KanbanDrag {
+                column,
+                index,
+                start_x: point.x,
+                start_y: point.y,
+                x: point.x,
+                y: point.y,
+                dragging: false,
+            }));
+        }
+    }
+
+    pub fn mouse_move(&mut self, ctx: &NodeContext, event: Event<MouseMove>) {
+        let Some(mut drag) = self._drag.get() else {
+            return;
+        };
+        let point = ctx.local_point(Point2::new(event.mouse.x, event.mouse.y));
+        drag.x = point.x;
+        drag.y = point.y;
+        drag.dragging |= (drag.x - drag.start_x).hypot(drag.y - drag.start_y) > DRAG_THRESHOLD;
+        self._drag.set(Some(drag));
+    }
+
+    pub fn mouse_up(&mut self, ctx: &NodeContext, event: Event<MouseUp>) {
+        let Some(drag) = self._drag.get() else {
+            return;
+        };
+        self._drag.set(None);
+        if !drag.dragging {
+            return;
+        }
+        let point = ctx.local_point(Point2::new(event.mouse.x, event.mouse.y));
+        let mut columns = self.columns.get();
+        let width = column_width(columns.len(), ctx.bounds_self.get().0);
+        let Some(to_column) = column_at(point.x, width, columns.len()) else {
+            return;
+        };
+        let card = columns[drag.column].cards.remove(drag.index);
+        let card_id = card.id.clone();
+        // drop before the card whose center is below the pointer
+        let slot = ((point.y - HEADER_HEIGHT + CARD_HEIGHT / 2.0) / (CARD_HEIGHT + GAP)).max(0.0);
+        let to_index = (slot as usize).min(columns[to_column].cards.len());
+        columns[to_column].cards.insert(to_index, card);
+        self.columns.set(columns);
+
+        if to_column != drag.column {
+            self.last_move.set(Some(KanbanCardMove {
+                card_id,
+                from_column: drag.column,
+                to_column,
+            }));
+            if let Err(e) = ctx.dispatch_event("card_move") {
+                log::warn!("failed to dispatch card_move: {}", e);
+            }
+        }
+    }
+}
+
+/// Width of each column if `count` columns are spread over `width`
+fn column_width(count: usize, width: f64) -> f64 {
+    if count == 0 {
+        return 0.0;
+    }
+    ((width - GAP * (count - 1) as f64) / count as f64).max(0.0)
+}
+
+fn column_x(index: usize, column_width: f64) -> f64 {
+    index as f64 * (column_width + GAP)
+}
+
+fn card_y(index: usize) -> f64 {
+    HEADER_HEIGHT + index as f64 * (CARD_HEIGHT + GAP)
+}
+
+/// Index of the column at x, if x is within a column
+fn column_at(x: f64, column_width: f64, count: usize) -> Option<usize> {
+    if x < 0.0 || column_width <= 0.0 {
+        return None;
+    }
+    let index = (x / (column_width + GAP)) as usize;
+    (index < count && x - column_x(index, column_width) <= column_width).then_some(index)
+}
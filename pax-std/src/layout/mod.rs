@@ -1,9 +1,13 @@
 pub mod carousel;
+pub mod grid;
+pub mod kanban_board;
 pub mod resizable;
 pub mod stacker;
 pub mod table;
 
 pub use carousel::*;
+pub use grid::*;
+pub use kanban_board::*;
 pub use resizable::*;
 pub use stacker::*;
 pub use table::*;
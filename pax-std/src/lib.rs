@@ -1,11 +1,33 @@
+#[cfg(feature = "charts")]
+pub mod charts;
 pub mod common;
 pub mod core;
+#[cfg(feature = "drawing")]
 pub mod drawing;
+#[cfg(feature = "drawing")]
+pub mod effects;
+#[cfg(feature = "forms")]
 pub mod forms;
+#[cfg(feature = "drawing")]
+pub mod interaction;
+#[cfg(feature = "layout")]
 pub mod layout;
+#[cfg(feature = "forms")]
+pub mod native;
 
+#[cfg(feature = "charts")]
+pub use charts::*;
 pub use common::*;
 pub use core::*;
+#[cfg(feature = "drawing")]
 pub use drawing::*;
+#[cfg(feature = "drawing")]
+pub use effects::*;
+#[cfg(feature = "forms")]
 pub use forms::*;
+#[cfg(feature = "drawing")]
+pub use interaction::*;
+#[cfg(feature = "layout")]
 pub use layout::*;
+#[cfg(feature = "forms")]
+pub use native::*;
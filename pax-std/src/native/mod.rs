@@ -0,0 +1,3 @@
+pub mod print_button;
+
+pub use print_button::*;
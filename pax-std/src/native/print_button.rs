@@ -0,0 +1,43 @@
+#[allow(unused)]
+use crate::*;
+use pax_engine::api::*;
+use pax_engine::*;
+
+/// A button that opens the platform's native print dialog when clicked.
+/// If target_id is set, only the element with that id is printed,
+/// otherwise the whole app is.
+#[pax]
+#[engine_import_path("pax_engine")]
+#[inlined(
+    <Button width=100% height=100% label={self.label} style={self.style} @button_click=on_click/>
+)]
+#[custom(Default)]
+pub struct PrintButton {
+    pub label: Property<String>,
+    pub style: Property<TextStyle>,
+    pub target_id: Property<Option<String>>,
+}
+
+impl Default for PrintButton {
+    fn default() -> Self {
+        Self {
+            label: Property::new(String::from("Print")),
+            style: Property::new(TextStyle {
+                font: Property::new(Font::default()),
+                font_size: Property::new(Size::Pixels(Numeric::F64(20.0))),
+                fill: Property::new(Color::WHITE),
+                underline: Property::new(false),
+                align_multiline: Property::new(TextAlignHorizontal::Center),
+                align_vertical: Property::new(TextAlignVertical::Center),
+                align_horizontal: Property::new(TextAlignHorizontal::Center),
+            }),
+            target_id: Property::new(None),
+        }
+    }
+}
+
+impl PrintButton {
+    pub fn on_click(&mut self, ctx: &NodeContext, _event: Event<ButtonClick>) {
+        ctx.print(self.target_id.get());
+    }
+}
@@ -0,0 +1,152 @@
+// `PaxEngine::new` only exists without designtime, which feature unification with pax-designer
+// enables in workspace builds
+#![cfg(not(feature = "designtime"))]
+
+use std::rc::Rc;
+
+use pax_engine::api::pax_value::{PaxAny, ToFromPaxAny};
+use pax_engine::api::{
+    use_RefCell, CommonProperties, ImplToFromPaxAny, PaxValue, Platform, Property, OS,
+};
+use pax_message::NativeMessage;
+use pax_runtime::{
+    ComponentInstance, InstanceNode, InstantiationArgs, PaxEngine, RepeatInstance,
+    RepeatProperties, RuntimePropertiesStackFrame,
+};
+use pax_std::core::text::{Text, TextInstance};
+
+use_RefCell!();
+
+const ROWS: usize = 200;
+
+/// Properties of the main component, which has none
+struct Rows;
+
+impl ImplToFromPaxAny for Rows {}
+
+/// Args instantiating a node with default common properties and the properties built by
+/// `properties` from the node's stack frame
+fn args(
+    properties: impl Fn(Rc<RuntimePropertiesStackFrame>) -> PaxAny + 'static,
+) -> InstantiationArgs {
+    InstantiationArgs {
+        prototypical_common_properties_factory: Box::new(|_, _| {
+            Some(Rc::new(RefCell::new(CommonProperties::default())))
+        }),
+        prototypical_properties_factory: Box::new(move |stack, _| {
+            Some(Rc::new(RefCell::new(properties(stack))))
+        }),
+        handler_registry: None,
+        children: None,
+        component_template: None,
+        template_node_identifier: None,
+        properties_scope_factory: None,
+    }
+}
+
+/// `for row in source { <Text text={row} /> }`
+fn main_component(source: Property<PaxValue>) -> Rc<ComponentInstance> {
+    let text = TextInstance::instantiate(args(|stack| {
+        let row = stack.resolve_symbol_as_erased_property("row").unwrap();
+        let text = Property::computed(
+            move || match stack.resolve_symbol_as_pax_value("row") {
+                Some(PaxValue::String(row)) => row,
+                _ => String::new(),
+            },
+            &[row],
+        );
+        Text {
+            text,
+            ..Default::default()
+        }
+        .to_pax_any()
+    }));
+    let repeat = RepeatInstance::instantiate(InstantiationArgs {
+        children: Some(RefCell::new(vec![text])),
+        ..args(move |_| {
+            let properties = RepeatProperties::default();
+            let source = source.clone();
+            let dependencies = [source.untyped()];
+            properties
+                .source_expression
+                .replace_with(Property::computed(move || source.get(), &dependencies));
+            properties
+                .iterator_elem_symbol
+                .replace_with(Property::new(Some("row".to_string())));
+            properties.to_pax_any()
+        })
+    });
+    ComponentInstance::instantiate(InstantiationArgs {
+        component_template: Some(RefCell::new(vec![repeat])),
+        ..args(|_| Rows.to_pax_any())
+    })
+}
+
+/// The rows `0..len`, in descending order if `descending`
+fn rows(len: usize, descending: bool) -> PaxValue {
+    let mut rows: Vec<_> = (0..len)
+        .map(|i| PaxValue::String(format!("row {:03}", i)))
+        .collect();
+    if descending {
+        rows.reverse();
+    }
+    PaxValue::Vec(rows)
+}
+
+fn create_engine(recycling: bool) -> (PaxEngine, Property<PaxValue>) {
+    let source = Property::new(rows(ROWS, false));
+    let engine = PaxEngine::new(
+        main_component(source.clone()),
+        (800.0, 600.0),
+        Platform::Web,
+        OS::Linux,
+        Box::new(|| 0),
+    );
+    engine
+        .runtime_context
+        .set_native_element_recycling(recycling);
+    (engine, source)
+}
+
+/// The number of native Text elements created and deleted by `messages`
+fn creates_and_deletes(messages: &[NativeMessage]) -> (usize, usize) {
+    let creates = messages
+        .iter()
+        .filter(|m| matches!(m, NativeMessage::TextCreate(_)))
+        .count();
+    let deletes = messages
+        .iter()
+        .filter(|m| matches!(m, NativeMessage::TextDelete(_)))
+        .count();
+    (creates, deletes)
+}
+
+/// Re-sorts the rows, first in place then while filtering one out, which makes Repeat re-expand
+/// them, then brings back the full list.  Returns the creates and deletes of each tick
+fn resort_rows(recycling: bool) -> Vec<(usize, usize)> {
+    let (mut engine, source) = create_engine(recycling);
+    let mut counts = vec![creates_and_deletes(&engine.tick())];
+    for rows in [rows(ROWS, true), rows(ROWS - 1, false), rows(ROWS, true)] {
+        source.set(rows);
+        counts.push(creates_and_deletes(&engine.tick()));
+    }
+    let texts = engine.root_expanded_node.children.get()[0]
+        .children
+        .get()
+        .len();
+    assert_eq!(texts, ROWS);
+    counts
+}
+
+#[test]
+fn test_resorting_rows_reuses_native_elements() {
+    assert_eq!(resort_rows(true), [(ROWS, 0), (0, 0), (0, 0), (0, 0)]);
+}
+
+#[test]
+fn test_resorting_rows_without_recycling_recreates_native_elements() {
+    assert_eq!(
+        resort_rows(false),
+        [(ROWS, 0), (0, 0), (ROWS - 1, ROWS), (ROWS, ROWS - 1)]
+    );
+}